@@ -68,9 +68,18 @@ export default defineConfig({
             { text: "Deployment & Docker", link: "/config/deployment" },
             { text: "Environment Variables", link: "/config/env" },
             { text: "Authentication", link: "/config/authentication" },
+            { text: "Two-Factor Authentication", link: "/config/two-factor" },
+            { text: "Passkeys (WebAuthn)", link: "/config/passkeys" },
+            { text: "Mail & Password Recovery", link: "/config/mail" },
             { text: "OIDC / SSO", link: "/config/oidc" },
             { text: "OIDC Config Examples", link: "/config/oidc-config-examples" },
+            { text: "LDAP / Active Directory", link: "/config/ldap" },
             { text: "Admin Settings", link: "/config/admin-settings" },
+            { text: "Activity Log", link: "/config/activity" },
+            { text: "End-to-End Encryption", link: "/config/e2ee" },
+            { text: "Notifications", link: "/config/notifications" },
+            { text: "Webhooks", link: "/config/webhooks" },
+            { text: "Live Events", link: "/config/live-events" },
             { text: "WOPI (Office Editing)", link: "/config/wopi" },
           ],
         },
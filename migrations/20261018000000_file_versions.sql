@@ -0,0 +1,41 @@
+-- File version history.
+--
+-- Every time a file's content is replaced (WebDAV PUT, WOPI PutFile,
+-- Nextcloud chunked upload, REST restore), the previous blob_hash is kept
+-- here instead of being released.  The version row inherits the reference
+-- the file row held on the blob, so the content stays alive until the
+-- version is pruned.  Because blobs are content-addressed and CDC chunks
+-- are shared, a version of an edited file costs only its changed chunks.
+--
+-- Deleting a version row (pruning, explicit delete, or ON DELETE CASCADE
+-- when the file itself is removed) releases the reference through the same
+-- trigger function used for storage.files, so garbage_collect() reclaims
+-- blobs that are no longer referenced by any file or version.
+
+CREATE TABLE IF NOT EXISTS storage.file_versions (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    file_id     UUID NOT NULL REFERENCES storage.files(id) ON DELETE CASCADE,
+    user_id     UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    blob_hash   VARCHAR(64) NOT NULL,
+    size        BIGINT NOT NULL DEFAULT 0,
+    mime_type   TEXT NOT NULL DEFAULT 'application/octet-stream',
+    -- Modification time of the content at the moment it was superseded
+    modified_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- When the content was superseded (i.e. when this version was recorded)
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_file_versions_file_id
+    ON storage.file_versions(file_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_file_versions_user_id
+    ON storage.file_versions(user_id);
+CREATE INDEX IF NOT EXISTS idx_file_versions_blob_hash
+    ON storage.file_versions(blob_hash);
+
+DROP TRIGGER IF EXISTS trg_file_versions_decrement_blob_ref ON storage.file_versions;
+CREATE TRIGGER trg_file_versions_decrement_blob_ref
+    AFTER DELETE ON storage.file_versions
+    FOR EACH ROW
+    EXECUTE FUNCTION storage.decrement_blob_ref();
+
+COMMENT ON TABLE storage.file_versions IS 'Previous contents of files, each holding a reference on its blob';
@@ -0,0 +1,340 @@
+-- Named, read-only folder snapshots.
+--
+-- A snapshot records the tree below a folder (names, structure, blob_hash,
+-- sizes) at a point in time.  No content is copied: blobs are
+-- content-addressed and CDC chunks are shared, so each file entry simply
+-- holds a reference on its blob (like a storage.files row does) and
+-- garbage_collect() keeps the content alive until the snapshot is deleted.
+--
+-- Restoring materialises all or part of the tree as new folders/files,
+-- again zero-copy (same blob_hash, one extra reference per file).
+
+-- ── Schedules ───────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS storage.snapshot_schedules (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    folder_id      UUID NOT NULL UNIQUE REFERENCES storage.folders(id) ON DELETE CASCADE,
+    user_id        UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    interval_hours INTEGER NOT NULL CHECK (interval_hours > 0),
+    -- NULL = scheduled snapshots never expire
+    retention_days INTEGER CHECK (retention_days IS NULL OR retention_days > 0),
+    last_run_at    TIMESTAMP WITH TIME ZONE,
+    created_at     TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_snapshot_schedules_user_id
+    ON storage.snapshot_schedules(user_id);
+
+-- ── Snapshots ───────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS storage.snapshots (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id      UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    -- Source folder; kept NULL once it is deleted so the snapshot can still
+    -- be restored elsewhere.
+    folder_id    UUID REFERENCES storage.folders(id) ON DELETE SET NULL,
+    folder_name  TEXT NOT NULL,
+    name         TEXT NOT NULL,
+    description  TEXT,
+    folder_count BIGINT NOT NULL DEFAULT 0,
+    file_count   BIGINT NOT NULL DEFAULT 0,
+    total_size   BIGINT NOT NULL DEFAULT 0,
+    schedule_id  UUID REFERENCES storage.snapshot_schedules(id) ON DELETE SET NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at   TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_snapshots_user_id
+    ON storage.snapshots(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_snapshots_folder_id
+    ON storage.snapshots(folder_id);
+CREATE INDEX IF NOT EXISTS idx_snapshots_expires_at
+    ON storage.snapshots(expires_at) WHERE expires_at IS NOT NULL;
+
+-- ── Snapshot entries ────────────────────────────────────────────────────
+-- One row per folder/file below the snapshot root.  `source_id` is the id
+-- the item had when the snapshot was taken; `parent_source_id` links to the
+-- parent entry (NULL = direct child of the snapshot root).  `path` is
+-- relative to the root, `depth` is its number of components.
+CREATE TABLE IF NOT EXISTS storage.snapshot_entries (
+    snapshot_id      UUID NOT NULL REFERENCES storage.snapshots(id) ON DELETE CASCADE,
+    source_id        UUID NOT NULL,
+    parent_source_id UUID,
+    is_folder        BOOLEAN NOT NULL,
+    name             TEXT NOT NULL,
+    path             TEXT NOT NULL,
+    depth            INTEGER NOT NULL,
+    blob_hash        VARCHAR(64),
+    size             BIGINT NOT NULL DEFAULT 0,
+    mime_type        TEXT,
+    modified_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (snapshot_id, source_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_snapshot_entries_parent
+    ON storage.snapshot_entries(snapshot_id, parent_source_id);
+CREATE INDEX IF NOT EXISTS idx_snapshot_entries_path
+    ON storage.snapshot_entries(snapshot_id, path text_pattern_ops);
+CREATE INDEX IF NOT EXISTS idx_snapshot_entries_blob_hash
+    ON storage.snapshot_entries(blob_hash) WHERE blob_hash IS NOT NULL;
+
+-- File entries release their blob reference through the same trigger
+-- function as storage.files (folder entries have a NULL hash → no-op).
+DROP TRIGGER IF EXISTS trg_snapshot_entries_decrement_blob_ref ON storage.snapshot_entries;
+CREATE TRIGGER trg_snapshot_entries_decrement_blob_ref
+    AFTER DELETE ON storage.snapshot_entries
+    FOR EACH ROW
+    EXECUTE FUNCTION storage.decrement_blob_ref();
+
+-- ── Batch reference increment (manifest-aware) ──────────────────────────
+-- CDC files are counted on storage.chunk_manifests, legacy whole-file blobs
+-- on storage.blobs — mirrors DedupService::add_reference for many hashes.
+CREATE OR REPLACE FUNCTION storage.increment_blob_refs(p_hashes TEXT[])
+RETURNS VOID AS $$
+BEGIN
+    WITH hc AS (
+        SELECT h AS blob_hash, COUNT(*)::int AS cnt
+          FROM unnest(p_hashes) AS h
+         GROUP BY h
+    ),
+    m AS (
+        UPDATE storage.chunk_manifests cm
+           SET ref_count = cm.ref_count + hc.cnt
+          FROM hc
+         WHERE cm.file_hash = hc.blob_hash
+        RETURNING cm.file_hash
+    )
+    UPDATE storage.blobs b
+       SET ref_count = b.ref_count + hc.cnt
+      FROM hc
+     WHERE b.hash = hc.blob_hash
+       AND hc.blob_hash NOT IN (SELECT file_hash FROM m);
+END;
+$$ LANGUAGE plpgsql;
+
+-- ── Atomic snapshot creation ────────────────────────────────────────────
+CREATE OR REPLACE FUNCTION storage.create_folder_snapshot(
+    p_folder_id   UUID,
+    p_user_id     UUID,
+    p_name        TEXT,
+    p_description TEXT DEFAULT NULL,
+    p_expires_at  TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    p_schedule_id UUID DEFAULT NULL
+) RETURNS UUID AS $$
+DECLARE
+    v_root_lpath ltree;
+    v_root_path  TEXT;
+    v_root_name  TEXT;
+    v_root_depth INT;
+    v_snapshot   UUID;
+    v_folders    BIGINT;
+    v_files      BIGINT;
+    v_size       BIGINT;
+BEGIN
+    SELECT fo.lpath, fo.path, fo.name, nlevel(fo.lpath)
+      INTO v_root_lpath, v_root_path, v_root_name, v_root_depth
+      FROM storage.folders fo
+     WHERE fo.id = p_folder_id AND fo.user_id = p_user_id AND NOT fo.is_trashed;
+
+    IF v_root_lpath IS NULL THEN
+        RAISE EXCEPTION 'Folder not found: %', p_folder_id
+            USING ERRCODE = 'P0002';  -- no_data_found
+    END IF;
+
+    INSERT INTO storage.snapshots(user_id, folder_id, folder_name, name, description,
+                                  expires_at, schedule_id)
+    VALUES (p_user_id, p_folder_id, v_root_name, p_name, p_description,
+            p_expires_at, p_schedule_id)
+    RETURNING id INTO v_snapshot;
+
+    -- Sub-folders (the root itself is the snapshot)
+    INSERT INTO storage.snapshot_entries(snapshot_id, source_id, parent_source_id, is_folder,
+                                         name, path, depth, modified_at)
+    SELECT v_snapshot, fo.id,
+           NULLIF(fo.parent_id, p_folder_id),
+           TRUE, fo.name,
+           substr(fo.path, length(v_root_path) + 2),
+           nlevel(fo.lpath) - v_root_depth,
+           fo.updated_at
+      FROM storage.folders fo
+     WHERE fo.lpath <@ v_root_lpath
+       AND fo.id <> p_folder_id
+       AND NOT fo.is_trashed;
+
+    GET DIAGNOSTICS v_folders = ROW_COUNT;
+
+    -- Files (skipping deferred uploads that still carry the placeholder hash)
+    INSERT INTO storage.snapshot_entries(snapshot_id, source_id, parent_source_id, is_folder,
+                                         name, path, depth, blob_hash, size, mime_type,
+                                         modified_at)
+    SELECT v_snapshot, f.id,
+           NULLIF(f.folder_id, p_folder_id),
+           FALSE, f.name,
+           CASE WHEN fo.id = p_folder_id THEN f.name
+                ELSE substr(fo.path, length(v_root_path) + 2) || '/' || f.name END,
+           nlevel(fo.lpath) - v_root_depth + 1,
+           f.blob_hash, f.size, f.mime_type, f.updated_at
+      FROM storage.files f
+      JOIN storage.folders fo ON fo.id = f.folder_id
+     WHERE fo.lpath <@ v_root_lpath
+       AND NOT fo.is_trashed
+       AND NOT f.is_trashed
+       AND f.blob_hash <> repeat('0', 64);
+
+    GET DIAGNOSTICS v_files = ROW_COUNT;
+
+    -- Hold one reference per file entry
+    PERFORM storage.increment_blob_refs(ARRAY(
+        SELECT e.blob_hash FROM storage.snapshot_entries e
+         WHERE e.snapshot_id = v_snapshot AND NOT e.is_folder
+    ));
+
+    SELECT COALESCE(SUM(e.size), 0)::bigint INTO v_size
+      FROM storage.snapshot_entries e
+     WHERE e.snapshot_id = v_snapshot AND NOT e.is_folder;
+
+    UPDATE storage.snapshots
+       SET folder_count = v_folders, file_count = v_files, total_size = v_size
+     WHERE id = v_snapshot;
+
+    RETURN v_snapshot;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION storage.create_folder_snapshot(UUID, UUID, TEXT, TEXT, TIMESTAMP WITH TIME ZONE, UUID)
+    IS 'Records a folder subtree as snapshot entries and holds a blob reference per file';
+
+-- ── Atomic snapshot restore ─────────────────────────────────────────────
+--
+-- Restores the whole snapshot (`p_path` NULL/empty) or the entry at `p_path`
+-- under `p_target_parent_id` with the name `p_dest_name`.  Folders are
+-- inserted level by level (see copy_folder_tree) so trg_folders_path can
+-- resolve each parent; files are zero-copy.
+CREATE OR REPLACE FUNCTION storage.restore_snapshot(
+    p_snapshot_id      UUID,
+    p_path             TEXT,
+    p_target_parent_id UUID,
+    p_dest_name        TEXT
+) RETURNS TABLE(new_root_id TEXT, root_is_folder BOOLEAN, folders_restored BIGINT, files_restored BIGINT) AS $$
+DECLARE
+    v_user       UUID;
+    v_entry      storage.snapshot_entries%ROWTYPE;
+    v_base_depth INT := 0;
+    v_prefix     TEXT := '';
+    v_max_depth  INT;
+    v_level      INT;
+    v_new_root   UUID;
+    v_folders    BIGINT := 1;
+    v_files      BIGINT := 0;
+    v_inserted   BIGINT;
+BEGIN
+    SELECT s.user_id INTO v_user FROM storage.snapshots s WHERE s.id = p_snapshot_id;
+    IF v_user IS NULL THEN
+        RAISE EXCEPTION 'Snapshot not found: %', p_snapshot_id
+            USING ERRCODE = 'P0002';
+    END IF;
+
+    IF p_target_parent_id IS NOT NULL AND NOT EXISTS (
+        SELECT 1 FROM storage.folders fo
+         WHERE fo.id = p_target_parent_id AND fo.user_id = v_user AND NOT fo.is_trashed
+    ) THEN
+        RAISE EXCEPTION 'Target folder not found: %', p_target_parent_id
+            USING ERRCODE = 'P0002';
+    END IF;
+
+    IF COALESCE(p_path, '') <> '' THEN
+        SELECT * INTO v_entry FROM storage.snapshot_entries e
+         WHERE e.snapshot_id = p_snapshot_id AND e.path = p_path;
+        IF NOT FOUND THEN
+            RAISE EXCEPTION 'Snapshot entry not found: %', p_path
+                USING ERRCODE = 'P0002';
+        END IF;
+
+        -- Single file: one row, one reference
+        IF NOT v_entry.is_folder THEN
+            INSERT INTO storage.files(name, folder_id, user_id, blob_hash, size, mime_type, updated_at)
+            VALUES (p_dest_name, p_target_parent_id, v_user, v_entry.blob_hash,
+                    v_entry.size, COALESCE(v_entry.mime_type, 'application/octet-stream'),
+                    v_entry.modified_at)
+            RETURNING id INTO v_new_root;
+
+            PERFORM storage.increment_blob_refs(ARRAY[v_entry.blob_hash]);
+
+            RETURN QUERY SELECT v_new_root::text, FALSE, 0::bigint, 1::bigint;
+            RETURN;
+        END IF;
+
+        v_base_depth := v_entry.depth;
+        v_prefix := p_path || '/';
+    END IF;
+
+    INSERT INTO storage.folders(name, parent_id, user_id)
+    VALUES (p_dest_name, p_target_parent_id, v_user)
+    RETURNING id INTO v_new_root;
+
+    CREATE TEMP TABLE IF NOT EXISTS _restore_map(
+        old_id UUID PRIMARY KEY,
+        new_id UUID NOT NULL DEFAULT gen_random_uuid()
+    ) ON COMMIT DROP;
+    TRUNCATE _restore_map;
+
+    INSERT INTO _restore_map(old_id)
+    SELECT e.source_id
+      FROM storage.snapshot_entries e
+     WHERE e.snapshot_id = p_snapshot_id
+       AND e.is_folder
+       AND starts_with(e.path, v_prefix);
+
+    SELECT MAX(e.depth) INTO v_max_depth
+      FROM storage.snapshot_entries e
+      JOIN _restore_map rm ON rm.old_id = e.source_id
+     WHERE e.snapshot_id = p_snapshot_id;
+
+    IF v_max_depth IS NOT NULL THEN
+        FOR v_level IN v_base_depth + 1 .. v_max_depth LOOP
+            INSERT INTO storage.folders(id, name, parent_id, user_id)
+            SELECT rm.new_id, e.name,
+                   CASE WHEN e.depth = v_base_depth + 1 THEN v_new_root ELSE pm.new_id END,
+                   v_user
+              FROM storage.snapshot_entries e
+              JOIN _restore_map rm ON rm.old_id = e.source_id
+              LEFT JOIN _restore_map pm ON pm.old_id = e.parent_source_id
+             WHERE e.snapshot_id = p_snapshot_id
+               AND e.depth = v_level;
+
+            GET DIAGNOSTICS v_inserted = ROW_COUNT;
+            v_folders := v_folders + v_inserted;
+        END LOOP;
+    END IF;
+
+    INSERT INTO storage.files(name, folder_id, user_id, blob_hash, size, mime_type, updated_at)
+    SELECT e.name,
+           CASE WHEN e.depth = v_base_depth + 1 THEN v_new_root ELSE pm.new_id END,
+           v_user, e.blob_hash, e.size,
+           COALESCE(e.mime_type, 'application/octet-stream'),
+           e.modified_at
+      FROM storage.snapshot_entries e
+      LEFT JOIN _restore_map pm ON pm.old_id = e.parent_source_id
+     WHERE e.snapshot_id = p_snapshot_id
+       AND NOT e.is_folder
+       AND starts_with(e.path, v_prefix);
+
+    GET DIAGNOSTICS v_files = ROW_COUNT;
+
+    IF v_files > 0 THEN
+        PERFORM storage.increment_blob_refs(ARRAY(
+            SELECT e.blob_hash FROM storage.snapshot_entries e
+             WHERE e.snapshot_id = p_snapshot_id
+               AND NOT e.is_folder
+               AND starts_with(e.path, v_prefix)
+        ));
+    END IF;
+
+    RETURN QUERY SELECT v_new_root::text, TRUE, v_folders, v_files;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION storage.restore_snapshot(UUID, TEXT, UUID, TEXT)
+    IS 'Materialises a snapshot (or a sub-path of it) as new folders/files — zero-copy via dedup';
+
+COMMENT ON TABLE storage.snapshots IS 'Named read-only point-in-time records of folder trees';
+COMMENT ON TABLE storage.snapshot_entries IS 'Folders and files captured by a snapshot, each file holding a blob reference';
+COMMENT ON TABLE storage.snapshot_schedules IS 'Per-folder schedules for automatic snapshots with optional expiry';
@@ -0,0 +1,95 @@
+-- WebDAV dead properties (RFC 4918 §4).
+--
+-- Arbitrary client-defined properties set through PROPPATCH (Windows
+-- Explorer's Win32 timestamps, Finder metadata, backup-tool tags, …) and
+-- returned by PROPFIND.  Each row belongs to exactly one file or folder;
+-- the foreign keys remove the properties together with the resource, and
+-- MOVE keeps them because moves never change a resource's id.
+
+CREATE TABLE IF NOT EXISTS storage.dead_properties (
+    file_id     UUID REFERENCES storage.files(id) ON DELETE CASCADE,
+    folder_id   UUID REFERENCES storage.folders(id) ON DELETE CASCADE,
+    resource_id UUID GENERATED ALWAYS AS (COALESCE(file_id, folder_id)) STORED,
+    -- Namespace URI of the property element ('' = no namespace)
+    namespace   TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    -- Text content of the property element (NULL = empty element)
+    value       TEXT,
+    updated_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (num_nonnulls(file_id, folder_id) = 1),
+    PRIMARY KEY (resource_id, namespace, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_properties_file_id
+    ON storage.dead_properties(file_id) WHERE file_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_dead_properties_folder_id
+    ON storage.dead_properties(folder_id) WHERE folder_id IS NOT NULL;
+
+COMMENT ON TABLE storage.dead_properties IS 'WebDAV dead properties stored by PROPPATCH, keyed by resource and qualified name';
+
+-- Copies the dead properties of a folder subtree onto a copy of it
+-- (as produced by storage.copy_folder_tree).  Folders are matched by their
+-- path relative to the subtree root, files by parent folder and name.
+CREATE OR REPLACE FUNCTION storage.copy_dead_properties_tree(
+    p_source_id UUID,
+    p_target_id UUID
+) RETURNS BIGINT AS $$
+DECLARE
+    v_source_path  TEXT;
+    v_source_lpath ltree;
+    v_target_path  TEXT;
+    v_target_lpath ltree;
+    v_copied       BIGINT := 0;
+    v_inserted     BIGINT;
+BEGIN
+    SELECT path, lpath INTO v_source_path, v_source_lpath
+      FROM storage.folders WHERE id = p_source_id;
+    SELECT path, lpath INTO v_target_path, v_target_lpath
+      FROM storage.folders WHERE id = p_target_id;
+
+    IF v_source_lpath IS NULL OR v_target_lpath IS NULL THEN
+        RAISE EXCEPTION 'Folder not found: % or %', p_source_id, p_target_id
+            USING ERRCODE = 'P0002';  -- no_data_found
+    END IF;
+
+    CREATE TEMP TABLE IF NOT EXISTS _dead_prop_map(
+        old_id UUID PRIMARY KEY,
+        new_id UUID NOT NULL
+    ) ON COMMIT DROP;
+    TRUNCATE _dead_prop_map;
+
+    INSERT INTO _dead_prop_map(old_id, new_id)
+    SELECT s.id, d.id
+      FROM storage.folders s
+      JOIN storage.folders d
+        ON substr(d.path, length(v_target_path) + 1) = substr(s.path, length(v_source_path) + 1)
+     WHERE s.lpath <@ v_source_lpath AND NOT s.is_trashed
+       AND d.lpath <@ v_target_lpath AND NOT d.is_trashed;
+
+    INSERT INTO storage.dead_properties(folder_id, namespace, name, value)
+    SELECT m.new_id, p.namespace, p.name, p.value
+      FROM storage.dead_properties p
+      JOIN _dead_prop_map m ON p.folder_id = m.old_id
+    ON CONFLICT (resource_id, namespace, name) DO UPDATE
+        SET value = EXCLUDED.value, updated_at = CURRENT_TIMESTAMP;
+    GET DIAGNOSTICS v_inserted = ROW_COUNT;
+    v_copied := v_copied + v_inserted;
+
+    INSERT INTO storage.dead_properties(file_id, namespace, name, value)
+    SELECT df.id, p.namespace, p.name, p.value
+      FROM storage.dead_properties p
+      JOIN storage.files sf ON p.file_id = sf.id AND NOT sf.is_trashed
+      JOIN _dead_prop_map m ON sf.folder_id = m.old_id
+      JOIN storage.files df
+        ON df.folder_id = m.new_id AND df.name = sf.name AND NOT df.is_trashed
+    ON CONFLICT (resource_id, namespace, name) DO UPDATE
+        SET value = EXCLUDED.value, updated_at = CURRENT_TIMESTAMP;
+    GET DIAGNOSTICS v_inserted = ROW_COUNT;
+    v_copied := v_copied + v_inserted;
+
+    RETURN v_copied;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION storage.copy_dead_properties_tree(UUID, UUID)
+    IS 'Copy WebDAV dead properties from a folder subtree onto its copy';
@@ -0,0 +1,110 @@
+-- CalDAV collection synchronization (RFC 6578).
+--
+-- Every calendar carries a monotonically increasing sync token.  Each
+-- change to one of its events bumps the token and appends a row to the
+-- change log, keyed by the event's resource name (its iCalendar UID), so a
+-- sync-collection REPORT can answer "what changed since token N" —
+-- including deletions, which are otherwise lost.
+--
+-- The log is written by triggers so the CalDAV handler, the REST API and
+-- any future writer are all covered.  Old entries are pruned by
+-- caldav.prune_calendar_changes(); tokens older than the pruned range are
+-- rejected with DAV:valid-sync-token and the client falls back to a full
+-- sync.
+
+ALTER TABLE caldav.calendars
+    ADD COLUMN IF NOT EXISTS sync_token BIGINT NOT NULL DEFAULT 0,
+    -- Highest token whose changes have been pruned; older tokens are expired
+    ADD COLUMN IF NOT EXISTS sync_token_floor BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS caldav.calendar_changes (
+    calendar_id UUID NOT NULL REFERENCES caldav.calendars(id) ON DELETE CASCADE,
+    -- Value of calendars.sync_token after this change
+    sync_token  BIGINT NOT NULL,
+    ical_uid    VARCHAR(255) NOT NULL,
+    operation   VARCHAR(8) NOT NULL CHECK (operation IN ('created', 'modified', 'deleted')),
+    changed_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (calendar_id, sync_token)
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendar_changes_changed_at
+    ON caldav.calendar_changes(changed_at);
+
+COMMENT ON TABLE caldav.calendar_changes IS 'Per-calendar change log backing CalDAV sync-collection';
+
+-- Bumps a calendar's sync token and logs one change.  Does nothing when the
+-- calendar itself is gone (events removed by ON DELETE CASCADE).
+CREATE OR REPLACE FUNCTION caldav.log_calendar_change(
+    p_calendar_id UUID,
+    p_ical_uid    VARCHAR,
+    p_operation   VARCHAR
+) RETURNS VOID AS $$
+DECLARE
+    v_token BIGINT;
+BEGIN
+    -- The row lock serialises writers per calendar, so tokens become
+    -- visible in order.
+    UPDATE caldav.calendars
+       SET sync_token = sync_token + 1
+     WHERE id = p_calendar_id
+    RETURNING sync_token INTO v_token;
+
+    IF v_token IS NOT NULL THEN
+        INSERT INTO caldav.calendar_changes(calendar_id, sync_token, ical_uid, operation)
+        VALUES (p_calendar_id, v_token, p_ical_uid, p_operation);
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION caldav.trg_calendar_events_log_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM caldav.log_calendar_change(NEW.calendar_id, NEW.ical_uid, 'created');
+    ELSIF TG_OP = 'DELETE' THEN
+        PERFORM caldav.log_calendar_change(OLD.calendar_id, OLD.ical_uid, 'deleted');
+    ELSIF OLD.calendar_id <> NEW.calendar_id OR OLD.ical_uid <> NEW.ical_uid THEN
+        -- Moved or renamed: gone from the old resource, new at the new one
+        PERFORM caldav.log_calendar_change(OLD.calendar_id, OLD.ical_uid, 'deleted');
+        PERFORM caldav.log_calendar_change(NEW.calendar_id, NEW.ical_uid, 'created');
+    ELSE
+        PERFORM caldav.log_calendar_change(NEW.calendar_id, NEW.ical_uid, 'modified');
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_calendar_events_log_change ON caldav.calendar_events;
+CREATE TRIGGER trg_calendar_events_log_change
+    AFTER INSERT OR UPDATE OR DELETE ON caldav.calendar_events
+    FOR EACH ROW EXECUTE FUNCTION caldav.trg_calendar_events_log_change();
+
+-- Deletes change-log entries older than p_retention and raises each
+-- affected calendar's token floor.  Returns the number of entries removed.
+CREATE OR REPLACE FUNCTION caldav.prune_calendar_changes(p_retention INTERVAL)
+RETURNS BIGINT AS $$
+DECLARE
+    v_deleted BIGINT;
+BEGIN
+    WITH pruned AS (
+        DELETE FROM caldav.calendar_changes
+         WHERE changed_at < NOW() - p_retention
+        RETURNING calendar_id, sync_token
+    ), floors AS (
+        SELECT calendar_id, MAX(sync_token) AS floor, COUNT(*) AS n
+          FROM pruned
+         GROUP BY calendar_id
+    ), raised AS (
+        UPDATE caldav.calendars c
+           SET sync_token_floor = GREATEST(c.sync_token_floor, f.floor)
+          FROM floors f
+         WHERE c.id = f.calendar_id
+        RETURNING f.n
+    )
+    SELECT COALESCE(SUM(n), 0) INTO v_deleted FROM raised;
+    RETURN v_deleted;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION caldav.prune_calendar_changes(INTERVAL)
+    IS 'Prune the CalDAV change log and expire sync tokens older than the retained range';
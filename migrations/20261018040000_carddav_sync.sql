@@ -0,0 +1,104 @@
+-- CardDAV collection synchronization (RFC 6578).
+--
+-- Same scheme as caldav_sync: every address book carries a monotonically
+-- increasing sync token, and each change to one of its vCards — contacts
+-- and group vCards alike, both live in carddav.contacts — bumps the token
+-- and appends a row keyed by the vCard's UID.  Pruned by
+-- carddav.prune_address_book_changes().
+
+ALTER TABLE carddav.address_books
+    ADD COLUMN IF NOT EXISTS sync_token BIGINT NOT NULL DEFAULT 0,
+    -- Highest token whose changes have been pruned; older tokens are expired
+    ADD COLUMN IF NOT EXISTS sync_token_floor BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS carddav.address_book_changes (
+    address_book_id UUID NOT NULL REFERENCES carddav.address_books(id) ON DELETE CASCADE,
+    -- Value of address_books.sync_token after this change
+    sync_token      BIGINT NOT NULL,
+    uid             VARCHAR(255) NOT NULL,
+    operation       VARCHAR(8) NOT NULL CHECK (operation IN ('created', 'modified', 'deleted')),
+    changed_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address_book_id, sync_token)
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_book_changes_changed_at
+    ON carddav.address_book_changes(changed_at);
+
+COMMENT ON TABLE carddav.address_book_changes IS 'Per-address-book change log backing CardDAV sync-collection';
+
+-- Bumps an address book's sync token and logs one change.  Does nothing
+-- when the address book itself is gone (contacts removed by ON DELETE CASCADE).
+CREATE OR REPLACE FUNCTION carddav.log_address_book_change(
+    p_address_book_id UUID,
+    p_uid             VARCHAR,
+    p_operation       VARCHAR
+) RETURNS VOID AS $$
+DECLARE
+    v_token BIGINT;
+BEGIN
+    -- The row lock serialises writers per address book, so tokens become
+    -- visible in order.
+    UPDATE carddav.address_books
+       SET sync_token = sync_token + 1
+     WHERE id = p_address_book_id
+    RETURNING sync_token INTO v_token;
+
+    IF v_token IS NOT NULL THEN
+        INSERT INTO carddav.address_book_changes(address_book_id, sync_token, uid, operation)
+        VALUES (p_address_book_id, v_token, p_uid, p_operation);
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION carddav.trg_contacts_log_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM carddav.log_address_book_change(NEW.address_book_id, NEW.uid, 'created');
+    ELSIF TG_OP = 'DELETE' THEN
+        PERFORM carddav.log_address_book_change(OLD.address_book_id, OLD.uid, 'deleted');
+    ELSIF OLD.address_book_id <> NEW.address_book_id OR OLD.uid <> NEW.uid THEN
+        -- Moved or renamed: gone from the old resource, new at the new one
+        PERFORM carddav.log_address_book_change(OLD.address_book_id, OLD.uid, 'deleted');
+        PERFORM carddav.log_address_book_change(NEW.address_book_id, NEW.uid, 'created');
+    ELSE
+        PERFORM carddav.log_address_book_change(NEW.address_book_id, NEW.uid, 'modified');
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_contacts_log_change ON carddav.contacts;
+CREATE TRIGGER trg_contacts_log_change
+    AFTER INSERT OR UPDATE OR DELETE ON carddav.contacts
+    FOR EACH ROW EXECUTE FUNCTION carddav.trg_contacts_log_change();
+
+-- Deletes change-log entries older than p_retention and raises each
+-- affected address book's token floor.  Returns the number of entries removed.
+CREATE OR REPLACE FUNCTION carddav.prune_address_book_changes(p_retention INTERVAL)
+RETURNS BIGINT AS $$
+DECLARE
+    v_deleted BIGINT;
+BEGIN
+    WITH pruned AS (
+        DELETE FROM carddav.address_book_changes
+         WHERE changed_at < NOW() - p_retention
+        RETURNING address_book_id, sync_token
+    ), floors AS (
+        SELECT address_book_id, MAX(sync_token) AS floor, COUNT(*) AS n
+          FROM pruned
+         GROUP BY address_book_id
+    ), raised AS (
+        UPDATE carddav.address_books b
+           SET sync_token_floor = GREATEST(b.sync_token_floor, f.floor)
+          FROM floors f
+         WHERE b.id = f.address_book_id
+        RETURNING f.n
+    )
+    SELECT COALESCE(SUM(n), 0) INTO v_deleted FROM raised;
+    RETURN v_deleted;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION carddav.prune_address_book_changes(INTERVAL)
+    IS 'Prune the CardDAV change log and expire sync tokens older than the retained range';
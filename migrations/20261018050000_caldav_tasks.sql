@@ -0,0 +1,25 @@
+-- CalDAV tasks (VTODO, RFC 5545 §3.6.2).
+--
+-- Tasks share caldav.calendar_events with events so they reuse the CalDAV
+-- resource handling and the sync change log.  `component` tells them apart;
+-- the task columns index the VTODO properties clients filter and sort on,
+-- while ical_data remains the source of truth.  For tasks, start_time and
+-- end_time hold DTSTART and DUE (or the creation time when undated).
+
+ALTER TABLE caldav.calendar_events
+    ADD COLUMN IF NOT EXISTS component VARCHAR(10) NOT NULL DEFAULT 'VEVENT'
+        CHECK (component IN ('VEVENT', 'VTODO')),
+    ADD COLUMN IF NOT EXISTS due_time TIMESTAMP WITH TIME ZONE,
+    -- NEEDS-ACTION, IN-PROCESS, COMPLETED or CANCELLED
+    ADD COLUMN IF NOT EXISTS status VARCHAR(20),
+    ADD COLUMN IF NOT EXISTS percent_complete SMALLINT
+        CHECK (percent_complete BETWEEN 0 AND 100),
+    -- 0 = undefined, 1 = highest, 9 = lowest
+    ADD COLUMN IF NOT EXISTS priority SMALLINT
+        CHECK (priority BETWEEN 0 AND 9),
+    -- UID of the parent task (RELATED-TO;RELTYPE=PARENT)
+    ADD COLUMN IF NOT EXISTS related_to VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS completed_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_calendar_events_component
+    ON caldav.calendar_events(calendar_id, component);
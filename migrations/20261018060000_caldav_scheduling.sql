@@ -0,0 +1,23 @@
+-- CalDAV scheduling inbox (RFC 6638 §2.2).
+--
+-- iTIP messages (REQUEST, REPLY, CANCEL) delivered between OxiCloud users
+-- when they store or delete scheduling objects.  Clients list the inbox at
+-- /caldav/inbox/ and delete messages once processed.
+
+CREATE TABLE IF NOT EXISTS caldav.schedule_inbox (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Recipient
+    user_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    -- UID of the scheduled calendar object
+    ical_uid VARCHAR(255) NOT NULL,
+    method VARCHAR(16) NOT NULL CHECK (method IN ('REQUEST', 'REPLY', 'CANCEL')),
+    -- Calendar user address of the sender (mailto:)
+    sender VARCHAR(255) NOT NULL,
+    ical_data TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedule_inbox_user
+    ON caldav.schedule_inbox(user_id, created_at);
+
+COMMENT ON TABLE caldav.schedule_inbox IS 'CalDAV scheduling inbox messages (iTIP)';
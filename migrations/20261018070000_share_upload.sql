@@ -0,0 +1,10 @@
+-- Public upload ("file drop") through share links.
+--
+-- Folder links with permissions_write accept uploads from anonymous
+-- visitors.  hide_contents turns such a link into an upload-only drop box;
+-- max_upload_size caps each uploaded file.
+
+ALTER TABLE storage.shares
+    ADD COLUMN IF NOT EXISTS hide_contents BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Bytes per uploaded file, NULL = no limit besides the owner's quota
+    ADD COLUMN IF NOT EXISTS max_upload_size BIGINT CHECK (max_upload_size > 0);
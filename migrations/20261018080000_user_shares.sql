@@ -0,0 +1,42 @@
+-- Direct shares of files and folders with other users.
+--
+-- Unlike token links (storage.shares), a user share makes the item appear
+-- in the recipient's tree under `mount_name`.  A folder share grants access
+-- to the whole subtree; a file share to that file only.  Everything stays
+-- owned by `owner_id`: files a recipient uploads into a writable shared
+-- folder belong to, and are charged against, the folder's owner.
+--
+-- `shared_by` differs from `owner_id` when a recipient with the reshare
+-- permission passed the item on.
+
+CREATE TABLE IF NOT EXISTS storage.user_shares (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    folder_id    UUID REFERENCES storage.folders(id) ON DELETE CASCADE,
+    file_id      UUID REFERENCES storage.files(id) ON DELETE CASCADE,
+    owner_id     UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    shared_by    UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    recipient_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    can_write    BOOLEAN NOT NULL DEFAULT FALSE,
+    can_reshare  BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Name of the item in the recipient's home folder
+    mount_name   TEXT NOT NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at   TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT user_shares_one_item CHECK ((folder_id IS NULL) <> (file_id IS NULL)),
+    CONSTRAINT user_shares_not_self CHECK (recipient_id <> owner_id)
+);
+
+-- One share per item and recipient, one mount per name and recipient
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_shares_item_recipient
+    ON storage.user_shares(COALESCE(folder_id, file_id), recipient_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_shares_mount_name
+    ON storage.user_shares(recipient_id, mount_name);
+
+CREATE INDEX IF NOT EXISTS idx_user_shares_owner_id ON storage.user_shares(owner_id);
+CREATE INDEX IF NOT EXISTS idx_user_shares_shared_by ON storage.user_shares(shared_by);
+CREATE INDEX IF NOT EXISTS idx_user_shares_folder_id
+    ON storage.user_shares(folder_id) WHERE folder_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_user_shares_file_id
+    ON storage.user_shares(file_id) WHERE file_id IS NOT NULL;
+
+COMMENT ON TABLE storage.user_shares IS 'Files and folders shared directly with other users';
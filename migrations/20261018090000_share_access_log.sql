@@ -0,0 +1,26 @@
+-- Download limits and access log for share links.
+--
+-- max_downloads stops a link from serving files once download_count
+-- reaches it.  Every download through a link is recorded in
+-- share_accesses so the owner can see who fetched what and when.
+
+ALTER TABLE storage.shares
+    -- NULL = unlimited
+    ADD COLUMN IF NOT EXISTS max_downloads BIGINT CHECK (max_downloads > 0),
+    ADD COLUMN IF NOT EXISTS download_count BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS storage.share_accesses (
+    id          BIGSERIAL PRIMARY KEY,
+    share_id    UUID NOT NULL REFERENCES storage.shares(id) ON DELETE CASCADE,
+    accessed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ip_address  TEXT,
+    user_agent  TEXT,
+    -- File served; for folder links the file inside the folder, NULL for
+    -- ZIP downloads.  Not a foreign key: the log outlives deleted files.
+    file_id     TEXT,
+    -- Name of the file or archive as served to the visitor
+    file_name   TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_accesses_share
+    ON storage.share_accesses(share_id, accessed_at DESC);
@@ -0,0 +1,34 @@
+-- User groups.
+--
+-- Groups are managed by admins or mirrored from the OIDC groups claim on
+-- login.  A membership records where it came from: the OIDC sync only ever
+-- adds or removes 'oidc' memberships, so admin-managed ones survive logins.
+--
+-- default_quota_bytes acts as a floor: members whose quota is lower are
+-- raised to the largest default among their groups.  Unlimited quotas (0)
+-- are left alone.
+
+CREATE TABLE IF NOT EXISTS auth.groups (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name                TEXT NOT NULL,
+    description         TEXT,
+    default_quota_bytes BIGINT CHECK (default_quota_bytes > 0),
+    created_at          TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at          TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Names are unique regardless of case
+CREATE UNIQUE INDEX IF NOT EXISTS idx_groups_name ON auth.groups(lower(name));
+
+CREATE TABLE IF NOT EXISTS auth.group_members (
+    group_id UUID NOT NULL REFERENCES auth.groups(id) ON DELETE CASCADE,
+    user_id  UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    source   TEXT NOT NULL DEFAULT 'manual' CHECK (source IN ('manual', 'oidc')),
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_members_user_id ON auth.group_members(user_id);
+
+COMMENT ON TABLE auth.groups IS 'User groups, managed by admins or synced from OIDC';
+COMMENT ON TABLE auth.group_members IS 'Group memberships and where they came from';
@@ -0,0 +1,7 @@
+-- Group memberships mirrored from the LDAP groups attribute on login.
+-- Like 'oidc' ones, they are replaced on every LDAP login and leave
+-- memberships from other sources alone.
+
+ALTER TABLE auth.group_members DROP CONSTRAINT IF EXISTS group_members_source_check;
+ALTER TABLE auth.group_members ADD CONSTRAINT group_members_source_check
+    CHECK (source IN ('manual', 'oidc', 'ldap'));
@@ -0,0 +1,28 @@
+-- TOTP two-factor authentication.
+--
+-- A secret is stored unconfirmed when a user starts enrolment and becomes
+-- active once they prove their authenticator works.  last_used_step holds
+-- the time step of the last accepted code so a code cannot be replayed.
+--
+-- Recovery codes are single-use and stored as SHA-256 hashes: they are
+-- random, so a slow hash buys nothing.
+
+CREATE TABLE IF NOT EXISTS auth.user_totp (
+    user_id        UUID PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    secret         TEXT NOT NULL,
+    confirmed_at   TIMESTAMP WITH TIME ZONE,
+    last_used_step BIGINT,
+    created_at     TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS auth.user_recovery_codes (
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id    UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    code_hash  TEXT NOT NULL,
+    used_at    TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, code_hash)
+);
+
+COMMENT ON TABLE auth.user_totp IS 'TOTP secrets of users with two-factor authentication';
+COMMENT ON TABLE auth.user_recovery_codes IS 'Hashed single-use two-factor recovery codes';
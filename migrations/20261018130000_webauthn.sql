@@ -0,0 +1,37 @@
+-- WebAuthn / passkey credentials.
+--
+-- public_key holds the COSE_Key returned at registration; sign_count is
+-- the authenticator's counter from the last assertion, used to spot cloned
+-- authenticators.
+--
+-- webauthn_session_families links the refresh-token families created by
+-- passkey logins to the credential that was used, so removing a passkey
+-- revokes the sessions it opened.
+
+CREATE TABLE IF NOT EXISTS auth.webauthn_credentials (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id       UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    credential_id BYTEA NOT NULL UNIQUE,
+    public_key    BYTEA NOT NULL,
+    algorithm     INTEGER NOT NULL,
+    sign_count    BIGINT NOT NULL DEFAULT 0,
+    name          TEXT NOT NULL,
+    transports    TEXT[] NOT NULL DEFAULT '{}',
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at  TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user_id
+    ON auth.webauthn_credentials(user_id);
+
+CREATE TABLE IF NOT EXISTS auth.webauthn_session_families (
+    family_id     UUID PRIMARY KEY,
+    credential_id UUID NOT NULL REFERENCES auth.webauthn_credentials(id) ON DELETE CASCADE,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_webauthn_session_families_credential
+    ON auth.webauthn_session_families(credential_id);
+
+COMMENT ON TABLE auth.webauthn_credentials IS 'Registered WebAuthn credentials (passkeys and security keys)';
+COMMENT ON TABLE auth.webauthn_session_families IS 'Session token families opened with a WebAuthn credential';
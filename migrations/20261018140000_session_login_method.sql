@@ -0,0 +1,17 @@
+-- How each session family was opened, for the session list.
+--
+-- Refreshed sessions copy the method of the session they replace, so every
+-- row of a family carries the method of its original login.
+
+ALTER TABLE auth.sessions
+    ADD COLUMN IF NOT EXISTS login_method TEXT NOT NULL DEFAULT 'password';
+
+-- Existing families: device-flow sessions are tagged by their user agent,
+-- passkey logins by their link to a WebAuthn credential.
+UPDATE auth.sessions SET login_method = 'device'
+    WHERE user_agent LIKE 'device:%';
+
+UPDATE auth.sessions SET login_method = 'passkey'
+    WHERE family_id IN (SELECT family_id FROM auth.webauthn_session_families);
+
+COMMENT ON COLUMN auth.sessions.login_method IS 'password, passkey, oidc or device';
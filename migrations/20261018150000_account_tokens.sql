@@ -0,0 +1,29 @@
+-- Password reset and address verification links.
+--
+-- The links carry a signed token whose ID is a row here, which makes each
+-- link single-use: consuming it sets used_at.  Issuing a new link of the
+-- same purpose invalidates the earlier ones.
+--
+-- A row in unverified_emails means the user registered themselves and has
+-- not followed the verification link yet; such users cannot sign in.
+
+CREATE TABLE IF NOT EXISTS auth.account_tokens (
+    id         UUID PRIMARY KEY,
+    user_id    UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    purpose    TEXT NOT NULL CHECK (purpose IN ('password_reset', 'email_verification')),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at    TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_tokens_user_purpose
+    ON auth.account_tokens (user_id, purpose);
+
+CREATE TABLE IF NOT EXISTS auth.unverified_emails (
+    user_id    UUID PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    email      TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON TABLE auth.account_tokens IS 'Single-use password reset and address verification tokens';
+COMMENT ON TABLE auth.unverified_emails IS 'Self-registered users whose address is not verified yet';
@@ -0,0 +1,37 @@
+-- Activity log: who did what to which file, folder, share or account.
+--
+-- actor_id is the user who acted, owner_id the user whose item (or
+-- account) was affected; they differ when someone works in a folder shared
+-- with them or tries to sign in to another account.  Both are kept when the
+-- user is deleted so the audit trail survives, and item names are copied
+-- because the items themselves come and go.
+
+CREATE SCHEMA IF NOT EXISTS activity;
+
+CREATE TABLE IF NOT EXISTS activity.events (
+    id          BIGSERIAL PRIMARY KEY,
+    occurred_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- e.g. file_created, folder_moved, share_accessed, login_failed
+    action      TEXT NOT NULL,
+    actor_id    UUID REFERENCES auth.users(id) ON DELETE SET NULL,
+    -- Name the actor gave when there is no user to point at (failed logins)
+    actor_name  TEXT,
+    owner_id    UUID REFERENCES auth.users(id) ON DELETE SET NULL,
+    -- 'file', 'folder', 'share' or 'account'
+    item_type   TEXT,
+    item_id     TEXT,
+    item_name   TEXT,
+    ip_address  TEXT,
+    details     JSONB NOT NULL DEFAULT '{}'::jsonb
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_events_occurred
+    ON activity.events (occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_activity_events_owner
+    ON activity.events (owner_id, occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_activity_events_actor
+    ON activity.events (actor_id, occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_activity_events_item
+    ON activity.events (item_id);
+
+COMMENT ON TABLE activity.events IS 'Audit trail of file, folder, share and sign-in events';
@@ -0,0 +1,38 @@
+-- End-to-end encryption (Nextcloud `end_to_end_encryption` API).
+--
+-- The server never sees plaintext: it stores each user's certificate, the
+-- user's private key as encrypted by the client, and per-folder metadata
+-- that is opaque to the server.  A row in e2ee.folders marks the folder as
+-- encrypted; the lock columns serialise metadata updates between clients.
+
+CREATE SCHEMA IF NOT EXISTS e2ee;
+
+CREATE TABLE IF NOT EXISTS e2ee.user_keys (
+    user_id     UUID PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    -- PEM certificate signed by the server key from the user's CSR
+    public_key  TEXT,
+    -- Private key encrypted by the client with the user's mnemonic
+    private_key TEXT,
+    updated_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS e2ee.folders (
+    folder_id  UUID PRIMARY KEY REFERENCES storage.folders(id) ON DELETE CASCADE,
+    owner_id   UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    -- Encrypted metadata document written by the client (NULL until created)
+    metadata   TEXT,
+    lock_token TEXT,
+    locked_at  TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_e2ee_folders_owner ON e2ee.folders (owner_id);
+
+-- Key pair the server signs user certificates with (a single row).
+CREATE TABLE IF NOT EXISTS e2ee.server_key (
+    id          BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    private_key TEXT NOT NULL,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON TABLE e2ee.folders IS 'End-to-end encrypted folders with their opaque metadata and lock';
@@ -0,0 +1,39 @@
+-- Per-user notifications, shown in the web UI and by Nextcloud clients.
+--
+-- Subject and message are rendered when the notification is created, since
+-- the item it is about may be renamed or deleted afterwards.
+
+CREATE SCHEMA IF NOT EXISTS notifications;
+
+CREATE TABLE IF NOT EXISTS notifications.notifications (
+    id          BIGSERIAL PRIMARY KEY,
+    user_id     UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    -- e.g. share_received, quota_warning, share_expiring
+    kind        TEXT NOT NULL,
+    subject     TEXT NOT NULL,
+    message     TEXT NOT NULL DEFAULT '',
+    -- Where the notification leads, relative to the base URL
+    link        TEXT,
+    -- What it is about: 'share', 'link' or 'quota', and that object's id
+    object_type TEXT NOT NULL,
+    object_id   TEXT NOT NULL,
+    is_read     BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user
+    ON notifications.notifications (user_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_object
+    ON notifications.notifications (object_type, object_id);
+
+-- Conditions a user was already notified about.  The periodic checks
+-- (storage quota, expiring links) skip keys found here, so each condition
+-- is reported once even after the notification is deleted.
+CREATE TABLE IF NOT EXISTS notifications.sent (
+    user_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    key     TEXT NOT NULL,
+    sent_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, key)
+);
+
+COMMENT ON TABLE notifications.notifications IS 'Notifications for users about shares, storage and expiring links';
@@ -0,0 +1,51 @@
+-- Outgoing webhooks: HTTP callbacks for file and share events, and the log
+-- of their deliveries.
+
+CREATE SCHEMA IF NOT EXISTS webhooks;
+
+CREATE TABLE IF NOT EXISTS webhooks.subscriptions (
+    id          UUID PRIMARY KEY,
+    -- NULL for admin webhooks, which receive the events of every user
+    owner_id    UUID REFERENCES auth.users(id) ON DELETE CASCADE,
+    url         TEXT NOT NULL,
+    -- HMAC-SHA256 key the payloads are signed with
+    secret      TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    -- Filters; an empty list or NULL matches everything
+    events      TEXT[] NOT NULL DEFAULT '{}',
+    path_prefix TEXT,
+    mime_types  TEXT[] NOT NULL DEFAULT '{}',
+    active      BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_subscriptions_owner
+    ON webhooks.subscriptions (owner_id);
+
+CREATE TABLE IF NOT EXISTS webhooks.deliveries (
+    id              BIGSERIAL PRIMARY KEY,
+    webhook_id      UUID NOT NULL REFERENCES webhooks.subscriptions(id) ON DELETE CASCADE,
+    event           TEXT NOT NULL,
+    payload         JSONB NOT NULL,
+    -- pending, delivered or failed
+    status          TEXT NOT NULL DEFAULT 'pending',
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    -- HTTP status of the last attempt, if the receiver answered
+    response_status INTEGER,
+    error           TEXT,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_attempt_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook
+    ON webhooks.deliveries (webhook_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_pending
+    ON webhooks.deliveries (created_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_created
+    ON webhooks.deliveries (created_at);
+-- Finds what was last sent about a file once it has been deleted
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_file
+    ON webhooks.deliveries ((payload->'file'->>'id'));
+
+COMMENT ON TABLE webhooks.subscriptions IS 'Outgoing webhooks of users and administrators';
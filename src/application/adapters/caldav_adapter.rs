@@ -15,7 +15,39 @@ use uuid::Uuid;
 use crate::application::adapters::webdav_adapter::{
     PropFindRequest, PropFindType, QualifiedName, Result, WebDavAdapter, WebDavError,
 };
-use crate::application::dtos::calendar_dto::{CalendarDto, CalendarEventDto};
+use crate::application::dtos::calendar_dto::{
+    CalendarChangesDto, CalendarDto, CalendarEventDto, FreeBusyResponseDto, ScheduleMessageDto,
+};
+use crate::domain::services::recurrence_service::RecurrenceSet;
+use crate::domain::services::scheduling_service;
+
+/// Calendar components stored in every calendar
+const SUPPORTED_COMPONENTS: &[&str] = &["VEVENT", "VTODO"];
+
+/// Scheduling inbox of the current user (RFC 6638 §2.2)
+pub const SCHEDULE_INBOX_HREF: &str = "/caldav/inbox/";
+
+/// Scheduling outbox of the current user (RFC 6638 §2.1)
+pub const SCHEDULE_OUTBOX_HREF: &str = "/caldav/outbox/";
+
+/// Recurrence handling requested inside `C:calendar-data` (RFC 4791 §9.6)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CalendarDataRecurrence {
+    /// Return each event as a single object
+    #[default]
+    AsStored,
+    /// `C:expand`: return the instances in the range as separate components
+    Expand {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// `C:limit-recurrence-set`: return the master and only the overridden
+    /// instances that affect the range
+    LimitRecurrenceSet {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+}
 
 /// CalDAV report type
 #[derive(Debug, PartialEq)]
@@ -23,18 +55,28 @@ pub enum CalDavReportType {
     /// Calendar-query report
     CalendarQuery {
         time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+        /// Component named by the `comp-filter` inside VCALENDAR
+        /// (`VEVENT` or `VTODO`); `None` matches every component
+        component: Option<String>,
         props: Vec<QualifiedName>,
+        recurrence: CalendarDataRecurrence,
     },
     /// Calendar-multiget report
     CalendarMultiget {
         hrefs: Vec<String>,
         props: Vec<QualifiedName>,
+        recurrence: CalendarDataRecurrence,
     },
     /// Sync-collection report
     SyncCollection {
+        /// Token from the previous sync; empty for an initial sync
         sync_token: String,
         props: Vec<QualifiedName>,
     },
+    /// Free-busy-query report (RFC 4791 §7.10)
+    FreeBusyQuery {
+        time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    },
 }
 
 /// CalDAV adapter for converting between XML and domain objects
@@ -50,10 +92,16 @@ impl CalDavAdapter {
         let mut in_calendar_query = false;
         let mut in_calendar_multiget = false;
         let mut in_sync_collection = false;
+        let mut in_free_busy_query = false;
         let mut in_prop = false;
         let mut in_filter = false;
+        let mut in_sync_token = false;
+        let mut in_href = false;
+        let mut in_calendar_data = false;
+        let mut recurrence = CalendarDataRecurrence::AsStored;
         let mut start_time: Option<DateTime<Utc>> = None;
         let mut end_time: Option<DateTime<Utc>> = None;
+        let mut component: Option<String> = None;
         let mut props = Vec::new();
         let mut hrefs = Vec::new();
         let mut sync_token = String::new();
@@ -67,6 +115,12 @@ impl CalDavAdapter {
                     let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
 
                     match name_str {
+                        // comp/prop selectors inside calendar-data are not properties
+                        _ if in_calendar_data => {}
+                        s if in_prop && (s == "calendar-data" || s.ends_with(":calendar-data")) => {
+                            props.push(WebDavAdapter::resolve_name(name_str, &ns_map));
+                            in_calendar_data = true;
+                        }
                         s if s == "calendar-query" || s.ends_with(":calendar-query") => {
                             in_calendar_query = true
                         }
@@ -76,32 +130,25 @@ impl CalDavAdapter {
                         s if s == "sync-collection" || s.ends_with(":sync-collection") => {
                             in_sync_collection = true
                         }
+                        s if s == "free-busy-query" || s.ends_with(":free-busy-query") => {
+                            in_free_busy_query = true
+                        }
                         s if s == "prop" || s.ends_with(":prop") => in_prop = true,
                         s if s == "filter" || s.ends_with(":filter") => in_filter = true,
+                        s if in_filter && (s == "comp-filter" || s.ends_with(":comp-filter")) => {
+                            Self::parse_comp_filter(e, &mut component);
+                        }
                         s if s == "time-range" || s.ends_with(":time-range") => {
                             // Parse time-range attributes
-                            for attr in e.attributes().flatten() {
-                                let attr_name =
-                                    std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                                let attr_value = attr.unescape_value().unwrap_or_default();
-
-                                if attr_name == "start" {
-                                    // Parse ISO date format with Z for UTC
-                                    start_time = DateTime::parse_from_rfc3339(&attr_value)
-                                        .ok()
-                                        .map(|dt| dt.with_timezone(&Utc));
-                                } else if attr_name == "end" {
-                                    end_time = DateTime::parse_from_rfc3339(&attr_value)
-                                        .ok()
-                                        .map(|dt| dt.with_timezone(&Utc));
-                                }
-                            }
+                            (start_time, end_time) = Self::parse_time_range_attrs(e);
                         }
                         s if s == "sync-token" || s.ends_with(":sync-token") => {
                             // We'll capture the text in the Text event
+                            in_sync_token = true
                         }
                         s if s == "href" || s.ends_with(":href") => {
                             // We'll capture the text in the Text event
+                            in_href = true
                         }
                         _ if in_prop => {
                             let qname = WebDavAdapter::resolve_name(name_str, &ns_map);
@@ -114,12 +161,12 @@ impl CalDavAdapter {
                     let text = e.decode().unwrap_or_default();
 
                     // Check if we're in sync-token element
-                    if in_sync_collection && !in_prop && !in_filter {
+                    if in_sync_token && in_sync_collection {
                         sync_token = text.to_string();
                     }
 
                     // Check if we're in href element
-                    if (in_calendar_multiget || in_sync_collection) && !in_prop && !in_filter {
+                    if in_href && in_calendar_multiget && !in_prop && !in_filter {
                         hrefs.push(text.to_string());
                     }
                 }
@@ -129,8 +176,16 @@ impl CalDavAdapter {
 
                     match name_str {
                         // Don't reset report-type flags — they're needed at EOF for decision logic
+                        s if s == "calendar-data" || s.ends_with(":calendar-data") => {
+                            in_calendar_data = false
+                        }
+                        _ if in_calendar_data => {}
                         s if s == "prop" || s.ends_with(":prop") => in_prop = false,
                         s if s == "filter" || s.ends_with(":filter") => in_filter = false,
+                        s if s == "sync-token" || s.ends_with(":sync-token") => {
+                            in_sync_token = false
+                        }
+                        s if s == "href" || s.ends_with(":href") => in_href = false,
                         s if s == "time-range" || s.ends_with(":time-range") => { /* time-range end, attributes already parsed */
                         }
                         _ => (),
@@ -141,26 +196,27 @@ impl CalDavAdapter {
                     let name = e.name();
                     let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
 
-                    if in_prop {
+                    if in_calendar_data {
+                        let (start, end) = Self::parse_time_range_attrs(e);
+                        if let (Some(start), Some(end)) = (start, end) {
+                            if name_str == "expand" || name_str.ends_with(":expand") {
+                                recurrence = CalendarDataRecurrence::Expand { start, end };
+                            } else if name_str == "limit-recurrence-set"
+                                || name_str.ends_with(":limit-recurrence-set")
+                            {
+                                recurrence =
+                                    CalendarDataRecurrence::LimitRecurrenceSet { start, end };
+                            }
+                        }
+                    } else if in_prop {
                         let qname = WebDavAdapter::resolve_name(name_str, &ns_map);
                         props.push(qname);
                     } else if name_str == "time-range" || name_str.ends_with(":time-range") {
-                        // Parse time-range attributes
-                        for attr in e.attributes().flatten() {
-                            let attr_name = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                            let attr_value = attr.unescape_value().unwrap_or_default();
-
-                            if attr_name == "start" {
-                                // Parse ISO date format with Z for UTC
-                                start_time = DateTime::parse_from_rfc3339(&attr_value)
-                                    .ok()
-                                    .map(|dt| dt.with_timezone(&Utc));
-                            } else if attr_name == "end" {
-                                end_time = DateTime::parse_from_rfc3339(&attr_value)
-                                    .ok()
-                                    .map(|dt| dt.with_timezone(&Utc));
-                            }
-                        }
+                        (start_time, end_time) = Self::parse_time_range_attrs(e);
+                    } else if in_filter
+                        && (name_str == "comp-filter" || name_str.ends_with(":comp-filter"))
+                    {
+                        Self::parse_comp_filter(e, &mut component);
                     }
                 }
                 Ok(Event::Eof) => break,
@@ -171,31 +227,85 @@ impl CalDavAdapter {
             buffer.clear();
         }
 
-        // Create the appropriate report type based on what we parsed
-        let report_type = if in_calendar_query {
-            // If both start and end time are present, create a time range
-            let time_range = if let (Some(start), Some(end)) = (start_time, end_time) {
-                Some((start, end))
-            } else {
-                None
-            };
+        // If both start and end time are present, create a time range
+        let time_range = if let (Some(start), Some(end)) = (start_time, end_time) {
+            Some((start, end))
+        } else {
+            None
+        };
 
-            CalDavReportType::CalendarQuery { time_range, props }
+        // Create the appropriate report type based on what we parsed
+        let report_type = if in_free_busy_query {
+            CalDavReportType::FreeBusyQuery { time_range }
+        } else if in_calendar_query {
+            CalDavReportType::CalendarQuery {
+                time_range,
+                component,
+                props,
+                recurrence,
+            }
         } else if in_calendar_multiget {
-            CalDavReportType::CalendarMultiget { hrefs, props }
+            CalDavReportType::CalendarMultiget {
+                hrefs,
+                props,
+                recurrence,
+            }
         } else if in_sync_collection {
             CalDavReportType::SyncCollection { sync_token, props }
         } else {
             // Default to empty calendar query
             CalDavReportType::CalendarQuery {
                 time_range: None,
+                component: None,
                 props,
+                recurrence,
             }
         };
 
         Ok(report_type)
     }
 
+    /// Records the component named by a `comp-filter`.  The outer
+    /// VCALENDAR filter is skipped; the first nested one wins.
+    fn parse_comp_filter(e: &BytesStart, component: &mut Option<String>) {
+        if component.is_some() {
+            return;
+        }
+        let name = e
+            .attributes()
+            .flatten()
+            .find(|attr| attr.key.as_ref() == b"name")
+            .and_then(|attr| attr.unescape_value().ok())
+            .map(|value| value.to_ascii_uppercase());
+        if let Some(name) = name.filter(|n| n != "VCALENDAR") {
+            *component = Some(name);
+        }
+    }
+
+    /// Parses the `start`/`end` attributes of `time-range`, `expand` and
+    /// `limit-recurrence-set`.  Values use the iCalendar UTC form
+    /// (`20250101T000000Z`, RFC 4791 §9.9); RFC 3339 is accepted as well.
+    fn parse_time_range_attrs(e: &BytesStart) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let parse = |value: &str| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+                .map(|dt| dt.and_utc())
+                .or_else(|_| DateTime::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc)))
+                .ok()
+        };
+
+        let mut start = None;
+        let mut end = None;
+        for attr in e.attributes().flatten() {
+            let attr_value = attr.unescape_value().unwrap_or_default();
+            match attr.key.as_ref() {
+                b"start" => start = parse(&attr_value),
+                b"end" => end = parse(&attr_value),
+                _ => {}
+            }
+        }
+        (start, end)
+    }
+
     /// Generate a PROPFIND response for the root CalDAV resource.
     /// Includes a response for /caldav/ itself with discovery properties
     /// (current-user-principal, calendar-home-set) plus each calendar.
@@ -271,10 +381,14 @@ impl CalDavAdapter {
     }
 
     /// Generate a PROPFIND response for a user principal resource.
+    ///
+    /// `email` is given for the current user's own principal; it enables
+    /// the scheduling properties (RFC 6638 §2).
     pub fn generate_principal_propfind_response<W: Write>(
         writer: W,
         request: &PropFindRequest,
         username: &str,
+        email: Option<&str>,
     ) -> Result<()> {
         let mut xml_writer = Writer::new(writer);
 
@@ -299,10 +413,10 @@ impl CalDavAdapter {
 
         match &request.prop_find_type {
             PropFindType::AllProp | PropFindType::PropName => {
-                Self::write_principal_props(&mut xml_writer, username)?;
+                Self::write_principal_props(&mut xml_writer, username, email)?;
             }
             PropFindType::Prop(props) => {
-                Self::write_principal_requested_props(&mut xml_writer, username, props)?;
+                Self::write_principal_requested_props(&mut xml_writer, username, email, props)?;
             }
         }
 
@@ -445,7 +559,11 @@ impl CalDavAdapter {
     }
 
     /// Write standard properties for a principal resource.
-    fn write_principal_props<W: Write>(xml_writer: &mut Writer<W>, username: &str) -> Result<()> {
+    fn write_principal_props<W: Write>(
+        xml_writer: &mut Writer<W>,
+        username: &str,
+        email: Option<&str>,
+    ) -> Result<()> {
         // resourcetype — principal
         xml_writer.write_event(Event::Start(BytesStart::new("D:resourcetype")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("D:collection")))?;
@@ -477,13 +595,54 @@ impl CalDavAdapter {
         xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
         xml_writer.write_event(Event::End(BytesEnd::new("D:current-user-principal")))?;
 
+        // Scheduling (RFC 6638 §2)
+        Self::write_calendar_user_address_set(xml_writer, username, email)?;
+        xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-user-type")))?;
+        xml_writer.write_event(Event::Text(BytesText::new("INDIVIDUAL")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-user-type")))?;
+        if email.is_some() {
+            Self::write_href_prop(xml_writer, "C:schedule-inbox-URL", &[SCHEDULE_INBOX_HREF])?;
+            Self::write_href_prop(xml_writer, "C:schedule-outbox-URL", &[SCHEDULE_OUTBOX_HREF])?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a property holding `D:href` elements.
+    fn write_href_prop<W: Write>(
+        xml_writer: &mut Writer<W>,
+        name: &str,
+        hrefs: &[&str],
+    ) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new(name)))?;
+        for href in hrefs {
+            xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(href)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+        }
+        xml_writer.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 
+    /// Write `calendar-user-address-set`: the user's `mailto:` address,
+    /// when known, and their principal URL.
+    fn write_calendar_user_address_set<W: Write>(
+        xml_writer: &mut Writer<W>,
+        username: &str,
+        email: Option<&str>,
+    ) -> Result<()> {
+        let principal = format!("/caldav/principals/{}/", username);
+        let mailto = email.map(scheduling_service::email_to_address);
+        let mut hrefs: Vec<&str> = mailto.iter().map(String::as_str).collect();
+        hrefs.push(&principal);
+        Self::write_href_prop(xml_writer, "C:calendar-user-address-set", &hrefs)
+    }
+
     /// Write requested properties for a principal resource.
     fn write_principal_requested_props<W: Write>(
         xml_writer: &mut Writer<W>,
         username: &str,
+        email: Option<&str>,
         props: &[QualifiedName],
     ) -> Result<()> {
         for prop in props {
@@ -522,17 +681,27 @@ impl CalDavAdapter {
                     xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-home-set")))?;
                 }
                 ("urn:ietf:params:xml:ns:caldav", "calendar-user-address-set") => {
-                    xml_writer.write_event(Event::Start(BytesStart::new(
-                        "C:calendar-user-address-set",
-                    )))?;
-                    xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
-                    xml_writer.write_event(Event::Text(BytesText::new(&format!(
-                        "/caldav/principals/{}/",
-                        username
-                    ))))?;
-                    xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+                    Self::write_calendar_user_address_set(xml_writer, username, email)?;
+                }
+                ("urn:ietf:params:xml:ns:caldav", "calendar-user-type") => {
                     xml_writer
-                        .write_event(Event::End(BytesEnd::new("C:calendar-user-address-set")))?;
+                        .write_event(Event::Start(BytesStart::new("C:calendar-user-type")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new("INDIVIDUAL")))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-user-type")))?;
+                }
+                ("urn:ietf:params:xml:ns:caldav", "schedule-inbox-URL") if email.is_some() => {
+                    Self::write_href_prop(
+                        xml_writer,
+                        "C:schedule-inbox-URL",
+                        &[SCHEDULE_INBOX_HREF],
+                    )?;
+                }
+                ("urn:ietf:params:xml:ns:caldav", "schedule-outbox-URL") if email.is_some() => {
+                    Self::write_href_prop(
+                        xml_writer,
+                        "C:schedule-outbox-URL",
+                        &[SCHEDULE_OUTBOX_HREF],
+                    )?;
                 }
                 _ => {
                     let prop_name = if prop.namespace == "http://calendarserver.org/ns/" {
@@ -642,15 +811,20 @@ impl CalDavAdapter {
         )))?;
         xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
 
+        // Collection sync token (RFC 6578) and its CalendarServer alias
+        Self::write_calendar_sync_props(xml_writer, calendar)?;
+
         // CalDAV specific properties
 
         // Supported calendar component set
         xml_writer.write_event(Event::Start(BytesStart::new(
             "C:supported-calendar-component-set",
         )))?;
-        xml_writer.write_event(Event::Empty(
-            BytesStart::new("C:comp").with_attributes([("name", "VEVENT")]),
-        ))?;
+        for component in SUPPORTED_COMPONENTS {
+            xml_writer.write_event(Event::Empty(
+                BytesStart::new("C:comp").with_attributes([("name", *component)]),
+            ))?;
+        }
         xml_writer.write_event(Event::End(BytesEnd::new(
             "C:supported-calendar-component-set",
         )))?;
@@ -706,6 +880,24 @@ impl CalDavAdapter {
         Ok(())
     }
 
+    /// Write the collection sync token as `DAV:sync-token` and `CS:getctag`
+    fn write_calendar_sync_props<W: Write>(
+        xml_writer: &mut Writer<W>,
+        calendar: &CalendarDto,
+    ) -> Result<()> {
+        let token = WebDavAdapter::sync_token_uri(calendar.sync_token);
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&token)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:sync-token")))?;
+
+        xml_writer.write_event(Event::Start(BytesStart::new("CS:getctag")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&token)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("CS:getctag")))?;
+
+        Ok(())
+    }
+
     /// Write calendar property names
     fn write_calendar_prop_names<W: Write>(xml_writer: &mut Writer<W>) -> Result<()> {
         // Common WebDAV property names
@@ -714,6 +906,8 @@ impl CalDavAdapter {
         xml_writer.write_event(Event::Empty(BytesStart::new("D:getlastmodified")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("D:getetag")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("D:getcontenttype")))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("CS:getctag")))?;
 
         // CalDAV specific property names
         xml_writer.write_event(Event::Empty(BytesStart::new(
@@ -791,15 +985,41 @@ impl CalDavAdapter {
                     xml_writer
                         .write_event(Event::End(BytesEnd::new("D:current-user-privilege-set")))?;
                 }
+                ("DAV:", "supported-report-set") => {
+                    xml_writer
+                        .write_event(Event::Start(BytesStart::new("D:supported-report-set")))?;
+                    for report in [
+                        "C:calendar-query",
+                        "C:calendar-multiget",
+                        "D:sync-collection",
+                    ] {
+                        xml_writer
+                            .write_event(Event::Start(BytesStart::new("D:supported-report")))?;
+                        xml_writer.write_event(Event::Start(BytesStart::new("D:report")))?;
+                        xml_writer.write_event(Event::Empty(BytesStart::new(report)))?;
+                        xml_writer.write_event(Event::End(BytesEnd::new("D:report")))?;
+                        xml_writer.write_event(Event::End(BytesEnd::new("D:supported-report")))?;
+                    }
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:supported-report-set")))?;
+                }
+                ("DAV:", "sync-token") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:sync-token")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(
+                        &WebDavAdapter::sync_token_uri(calendar.sync_token),
+                    )))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:sync-token")))?;
+                }
 
                 // CalDAV namespace properties
                 ("urn:ietf:params:xml:ns:caldav", "supported-calendar-component-set") => {
                     xml_writer.write_event(Event::Start(BytesStart::new(
                         "C:supported-calendar-component-set",
                     )))?;
-                    xml_writer.write_event(Event::Empty(
-                        BytesStart::new("C:comp").with_attributes([("name", "VEVENT")]),
-                    ))?;
+                    for component in SUPPORTED_COMPONENTS {
+                        xml_writer.write_event(Event::Empty(
+                            BytesStart::new("C:comp").with_attributes([("name", *component)]),
+                        ))?;
+                    }
                     xml_writer.write_event(Event::End(BytesEnd::new(
                         "C:supported-calendar-component-set",
                     )))?;
@@ -835,6 +1055,13 @@ impl CalDavAdapter {
                             .write_event(Event::Empty(BytesStart::new("CS:calendar-color")))?;
                     }
                 }
+                ("http://calendarserver.org/ns/", "getctag") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("CS:getctag")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(
+                        &WebDavAdapter::sync_token_uri(calendar.sync_token),
+                    )))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("CS:getctag")))?;
+                }
 
                 // Custom properties from the calendar
                 _ => {
@@ -921,9 +1148,10 @@ impl CalDavAdapter {
 
                 // getcontenttype
                 xml_writer.write_event(Event::Start(BytesStart::new("D:getcontenttype")))?;
-                xml_writer.write_event(Event::Text(BytesText::new(
-                    "text/calendar; component=vevent",
-                )))?;
+                xml_writer.write_event(Event::Text(BytesText::new(&format!(
+                    "text/calendar; component={}",
+                    event.component().to_ascii_lowercase()
+                ))))?;
                 xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
 
                 // getlastmodified
@@ -966,10 +1194,17 @@ impl CalDavAdapter {
         ))?;
 
         // Determine which properties to include based on request type
-        let props = match request {
-            CalDavReportType::CalendarQuery { props, .. } => props.clone(),
-            CalDavReportType::CalendarMultiget { props, .. } => props.clone(),
-            CalDavReportType::SyncCollection { props, .. } => props.clone(),
+        let (props, recurrence) = match request {
+            CalDavReportType::CalendarQuery {
+                props, recurrence, ..
+            } => (props.as_slice(), *recurrence),
+            CalDavReportType::CalendarMultiget {
+                props, recurrence, ..
+            } => (props.as_slice(), *recurrence),
+            CalDavReportType::SyncCollection { props, .. } => {
+                (props.as_slice(), CalendarDataRecurrence::AsStored)
+            }
+            CalDavReportType::FreeBusyQuery { .. } => (&[][..], CalendarDataRecurrence::AsStored),
         };
 
         // Add responses for events
@@ -978,7 +1213,7 @@ impl CalDavAdapter {
             let href = format!("{}{}.ics", base_href, event.ical_uid);
 
             // Write event response
-            Self::write_event_response(&mut xml_writer, event, &props, &href)?;
+            Self::write_event_response(&mut xml_writer, event, props, recurrence, &href)?;
         }
 
         // End multistatus
@@ -987,11 +1222,66 @@ impl CalDavAdapter {
         Ok(())
     }
 
+    /// Generate a sync-collection response (RFC 6578 §3.2)
+    ///
+    /// Changed events get a regular response with the requested properties,
+    /// deleted ones a bare `404 Not Found` response, and the new sync token
+    /// closes the multistatus.
+    pub fn generate_sync_collection_response<W: Write>(
+        writer: W,
+        changes: &CalendarChangesDto,
+        props: &[QualifiedName],
+        base_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+
+        xml_writer.write_event(Event::Start(
+            BytesStart::new("D:multistatus").with_attributes([
+                ("xmlns:D", "DAV:"),
+                ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+                ("xmlns:CS", "http://calendarserver.org/ns/"),
+            ]),
+        ))?;
+
+        for event in &changes.changed {
+            let href = format!("{}{}.ics", base_href, event.ical_uid);
+            Self::write_event_response(
+                &mut xml_writer,
+                event,
+                props,
+                CalendarDataRecurrence::AsStored,
+                &href,
+            )?;
+        }
+
+        for uid in &changes.deleted {
+            let href = format!("{}{}.ics", base_href, uid);
+            xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(&href)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+            xml_writer.write_event(Event::Text(BytesText::new("HTTP/1.1 404 Not Found")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        }
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&WebDavAdapter::sync_token_uri(
+            changes.sync_token,
+        ))))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:sync-token")))?;
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
     /// Write event properties as a response
     fn write_event_response<W: Write>(
         xml_writer: &mut Writer<W>,
         event: &CalendarEventDto,
         props: &[QualifiedName],
+        recurrence: CalendarDataRecurrence,
         href: &str,
     ) -> Result<()> {
         // Start response element
@@ -1010,10 +1300,10 @@ impl CalDavAdapter {
 
         // If no specific props requested, return all common ones
         if props.is_empty() {
-            Self::write_event_standard_props(xml_writer, event)?;
+            Self::write_event_standard_props(xml_writer, event, recurrence)?;
         } else {
             // Write specifically requested properties
-            Self::write_event_requested_props(xml_writer, event, props)?;
+            Self::write_event_requested_props(xml_writer, event, props, recurrence)?;
         }
 
         // End prop
@@ -1033,10 +1323,68 @@ impl CalDavAdapter {
         Ok(())
     }
 
+    /// iCalendar text for an event's `calendar-data` property
+    fn event_calendar_data(event: &CalendarEventDto, recurrence: CalendarDataRecurrence) -> String {
+        // Tasks are always created with complete iCalendar data; undated
+        // ones have no instances to expand
+        if event.is_task()
+            && (recurrence == CalendarDataRecurrence::AsStored
+                || RecurrenceSet::from_ical(&event.ical_data).start().is_none())
+        {
+            return event.ical_data.clone();
+        }
+
+        let recurrence_set = || {
+            RecurrenceSet::from_event(
+                &event.ical_data,
+                &event.ical_uid,
+                event.start_time,
+                event.end_time,
+                event.rrule.as_deref(),
+            )
+        };
+        match recurrence {
+            CalendarDataRecurrence::Expand { start, end } => recurrence_set().expand(start, end),
+            CalendarDataRecurrence::LimitRecurrenceSet { start, end } => {
+                recurrence_set().limit_recurrence_set(start, end)
+            }
+            // Scheduling objects keep their organizer and attendees
+            CalendarDataRecurrence::AsStored if event.is_scheduling_object() => {
+                event.ical_data.clone()
+            }
+            // In a full implementation, we would generate a complete iCalendar component here
+            // For now, we'll just provide a basic example
+            CalendarDataRecurrence::AsStored => format!(
+                "BEGIN:VCALENDAR\r\n\
+                VERSION:2.0\r\n\
+                PRODID:-//OxiCloud//NONSGML Calendar//EN\r\n\
+                BEGIN:VEVENT\r\n\
+                UID:{}\r\n\
+                SUMMARY:{}\r\n\
+                DTSTART:{}\r\n\
+                DTEND:{}\r\n\
+                {}\
+                DTSTAMP:{}\r\n\
+                END:VEVENT\r\n\
+                END:VCALENDAR\r\n",
+                event.ical_uid,
+                event.summary.replace("\n", "\\n"),
+                event.start_time.format("%Y%m%dT%H%M%SZ"),
+                event.end_time.format("%Y%m%dT%H%M%SZ"),
+                event
+                    .rrule
+                    .as_ref()
+                    .map_or("".to_string(), |r| format!("RRULE:{}\r\n", r)),
+                event.updated_at.format("%Y%m%dT%H%M%SZ"),
+            ),
+        }
+    }
+
     /// Write standard event properties
     fn write_event_standard_props<W: Write>(
         xml_writer: &mut Writer<W>,
         event: &CalendarEventDto,
+        recurrence: CalendarDataRecurrence,
     ) -> Result<()> {
         // Common WebDAV properties
 
@@ -1050,9 +1398,10 @@ impl CalDavAdapter {
 
         // Content type
         xml_writer.write_event(Event::Start(BytesStart::new("D:getcontenttype")))?;
-        xml_writer.write_event(Event::Text(BytesText::new(
-            "text/calendar; component=VEVENT",
-        )))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&format!(
+            "text/calendar; component={}",
+            event.component()
+        ))))?;
         xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
 
         // Last modified
@@ -1064,32 +1413,9 @@ impl CalDavAdapter {
 
         // Calendar data (iCalendar format)
         xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-data")))?;
-        // In a full implementation, we would generate a complete iCalendar component here
-        // For now, we'll just provide a basic example
-        let ical_data = format!(
-            "BEGIN:VCALENDAR\r\n\
-            VERSION:2.0\r\n\
-            PRODID:-//OxiCloud//NONSGML Calendar//EN\r\n\
-            BEGIN:VEVENT\r\n\
-            UID:{}\r\n\
-            SUMMARY:{}\r\n\
-            DTSTART:{}\r\n\
-            DTEND:{}\r\n\
-            {}\
-            DTSTAMP:{}\r\n\
-            END:VEVENT\r\n\
-            END:VCALENDAR\r\n",
-            event.ical_uid,
-            event.summary.replace("\n", "\\n"),
-            event.start_time.format("%Y%m%dT%H%M%SZ"),
-            event.end_time.format("%Y%m%dT%H%M%SZ"),
-            event
-                .rrule
-                .as_ref()
-                .map_or("".to_string(), |r| format!("RRULE:{}\r\n", r)),
-            event.updated_at.format("%Y%m%dT%H%M%SZ"),
-        );
-        xml_writer.write_event(Event::Text(BytesText::new(&ical_data)))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&Self::event_calendar_data(
+            event, recurrence,
+        ))))?;
         xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-data")))?;
 
         Ok(())
@@ -1100,6 +1426,7 @@ impl CalDavAdapter {
         xml_writer: &mut Writer<W>,
         event: &CalendarEventDto,
         props: &[QualifiedName],
+        recurrence: CalendarDataRecurrence,
     ) -> Result<()> {
         for prop in props {
             match (prop.namespace.as_str(), prop.name.as_str()) {
@@ -1115,9 +1442,10 @@ impl CalDavAdapter {
                 }
                 ("DAV:", "getcontenttype") => {
                     xml_writer.write_event(Event::Start(BytesStart::new("D:getcontenttype")))?;
-                    xml_writer.write_event(Event::Text(BytesText::new(
-                        "text/calendar; component=VEVENT",
-                    )))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(&format!(
+                        "text/calendar; component={}",
+                        event.component()
+                    ))))?;
                     xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
                 }
                 ("DAV:", "getlastmodified") => {
@@ -1130,32 +1458,9 @@ impl CalDavAdapter {
                 // CalDAV namespace properties
                 ("urn:ietf:params:xml:ns:caldav", "calendar-data") => {
                     xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-data")))?;
-                    // In a full implementation, we would generate a complete iCalendar component here
-                    // For now, we'll just provide a basic example
-                    let ical_data = format!(
-                        "BEGIN:VCALENDAR\r\n\
-                        VERSION:2.0\r\n\
-                        PRODID:-//OxiCloud//NONSGML Calendar//EN\r\n\
-                        BEGIN:VEVENT\r\n\
-                        UID:{}\r\n\
-                        SUMMARY:{}\r\n\
-                        DTSTART:{}\r\n\
-                        DTEND:{}\r\n\
-                        {}\
-                        DTSTAMP:{}\r\n\
-                        END:VEVENT\r\n\
-                        END:VCALENDAR\r\n",
-                        event.ical_uid,
-                        event.summary.replace("\n", "\\n"),
-                        event.start_time.format("%Y%m%dT%H%M%SZ"),
-                        event.end_time.format("%Y%m%dT%H%M%SZ"),
-                        event
-                            .rrule
-                            .as_ref()
-                            .map_or("".to_string(), |r| format!("RRULE:{}\r\n", r)),
-                        event.updated_at.format("%Y%m%dT%H%M%SZ"),
-                    );
-                    xml_writer.write_event(Event::Text(BytesText::new(&ical_data)))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(
+                        &Self::event_calendar_data(event, recurrence),
+                    )))?;
                     xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-data")))?;
                 }
 
@@ -1180,6 +1485,268 @@ impl CalDavAdapter {
         Ok(())
     }
 
+    /// Generate a PROPFIND response for the scheduling inbox (RFC 6638
+    /// §2.2), listing its messages unless `depth` is "0"
+    pub fn generate_schedule_inbox_propfind<W: Write>(
+        writer: W,
+        messages: &[ScheduleMessageDto],
+        request: &PropFindRequest,
+        depth: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        Self::start_multistatus(&mut xml_writer)?;
+
+        Self::write_schedule_collection_response(
+            &mut xml_writer,
+            SCHEDULE_INBOX_HREF,
+            "C:schedule-inbox",
+            "Scheduling Inbox",
+            request,
+        )?;
+        if depth != "0" {
+            let props = match &request.prop_find_type {
+                PropFindType::Prop(props) => props.as_slice(),
+                _ => &[],
+            };
+            for message in messages {
+                Self::write_schedule_message_response(&mut xml_writer, message, props)?;
+            }
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
+    /// Generate a PROPFIND response for the scheduling outbox (RFC 6638 §2.1)
+    pub fn generate_schedule_outbox_propfind<W: Write>(
+        writer: W,
+        request: &PropFindRequest,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        Self::start_multistatus(&mut xml_writer)?;
+        Self::write_schedule_collection_response(
+            &mut xml_writer,
+            SCHEDULE_OUTBOX_HREF,
+            "C:schedule-outbox",
+            "Scheduling Outbox",
+            request,
+        )?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
+    /// Generate a REPORT response for scheduling inbox messages
+    pub fn generate_schedule_messages_response<W: Write>(
+        writer: W,
+        messages: &[ScheduleMessageDto],
+        props: &[QualifiedName],
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        Self::start_multistatus(&mut xml_writer)?;
+        for message in messages {
+            Self::write_schedule_message_response(&mut xml_writer, message, props)?;
+        }
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
+    /// Generate the `schedule-response` to a free-busy request POSTed to
+    /// the scheduling outbox (RFC 6638 §6.3)
+    pub fn generate_schedule_response<W: Write>(
+        writer: W,
+        responses: &[FreeBusyResponseDto],
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+
+        xml_writer.write_event(Event::Start(
+            BytesStart::new("C:schedule-response").with_attributes([
+                ("xmlns:D", "DAV:"),
+                ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+            ]),
+        ))?;
+
+        for response in responses {
+            xml_writer.write_event(Event::Start(BytesStart::new("C:response")))?;
+
+            xml_writer.write_event(Event::Start(BytesStart::new("C:recipient")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(&response.recipient)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("C:recipient")))?;
+
+            xml_writer.write_event(Event::Start(BytesStart::new("C:request-status")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(&response.request_status)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("C:request-status")))?;
+
+            if let Some(calendar_data) = &response.calendar_data {
+                xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-data")))?;
+                xml_writer.write_event(Event::Text(BytesText::new(calendar_data)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-data")))?;
+            }
+
+            xml_writer.write_event(Event::End(BytesEnd::new("C:response")))?;
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("C:schedule-response")))?;
+        Ok(())
+    }
+
+    /// Start a `D:multistatus` element with the CalDAV namespaces
+    fn start_multistatus<W: Write>(xml_writer: &mut Writer<W>) -> Result<()> {
+        xml_writer.write_event(Event::Start(
+            BytesStart::new("D:multistatus").with_attributes([
+                ("xmlns:D", "DAV:"),
+                ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+            ]),
+        ))?;
+        Ok(())
+    }
+
+    /// Write the response for a scheduling inbox or outbox collection
+    fn write_schedule_collection_response<W: Write>(
+        xml_writer: &mut Writer<W>,
+        href: &str,
+        kind: &str,
+        display_name: &str,
+        request: &PropFindRequest,
+    ) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(href)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+
+        let write_resourcetype = |xml_writer: &mut Writer<W>| -> Result<()> {
+            xml_writer.write_event(Event::Start(BytesStart::new("D:resourcetype")))?;
+            xml_writer.write_event(Event::Empty(BytesStart::new("D:collection")))?;
+            xml_writer.write_event(Event::Empty(BytesStart::new(kind)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:resourcetype")))?;
+            Ok(())
+        };
+        let write_displayname = |xml_writer: &mut Writer<W>| -> Result<()> {
+            xml_writer.write_event(Event::Start(BytesStart::new("D:displayname")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(display_name)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:displayname")))?;
+            Ok(())
+        };
+
+        match &request.prop_find_type {
+            PropFindType::AllProp | PropFindType::PropName => {
+                write_resourcetype(xml_writer)?;
+                write_displayname(xml_writer)?;
+            }
+            PropFindType::Prop(props) => {
+                for prop in props {
+                    match (prop.namespace.as_str(), prop.name.as_str()) {
+                        ("DAV:", "resourcetype") => write_resourcetype(xml_writer)?,
+                        ("DAV:", "displayname") => write_displayname(xml_writer)?,
+                        _ => Self::write_empty_prop(xml_writer, prop)?,
+                    }
+                }
+            }
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+        xml_writer.write_event(Event::Text(BytesText::new("HTTP/1.1 200 OK")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:propstat")))?;
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        Ok(())
+    }
+
+    /// Write the response for a scheduling inbox message; with no requested
+    /// props, the common WebDAV ones
+    fn write_schedule_message_response<W: Write>(
+        xml_writer: &mut Writer<W>,
+        message: &ScheduleMessageDto,
+        props: &[QualifiedName],
+    ) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&format!(
+            "{}{}.ics",
+            SCHEDULE_INBOX_HREF, message.id
+        ))))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+
+        const COMMON: &[(&str, &str)] = &[
+            ("DAV:", "resourcetype"),
+            ("DAV:", "getetag"),
+            ("DAV:", "getcontenttype"),
+            ("DAV:", "getlastmodified"),
+        ];
+        let common: Vec<QualifiedName> = COMMON
+            .iter()
+            .map(|(namespace, name)| QualifiedName::new(*namespace, *name))
+            .collect();
+        let props = if props.is_empty() { &common } else { props };
+
+        for prop in props {
+            match (prop.namespace.as_str(), prop.name.as_str()) {
+                ("DAV:", "resourcetype") => {
+                    xml_writer.write_event(Event::Empty(BytesStart::new("D:resourcetype")))?;
+                }
+                ("DAV:", "getetag") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:getetag")))?;
+                    xml_writer
+                        .write_event(Event::Text(BytesText::new(&format!("\"{}\"", message.id))))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:getetag")))?;
+                }
+                ("DAV:", "getcontenttype") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:getcontenttype")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(&format!(
+                        "text/calendar; method={}",
+                        message.method
+                    ))))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
+                }
+                ("DAV:", "getlastmodified") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:getlastmodified")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(
+                        &message.created_at.to_rfc2822(),
+                    )))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:getlastmodified")))?;
+                }
+                ("urn:ietf:params:xml:ns:caldav", "calendar-data") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-data")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(&message.ical_data)))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-data")))?;
+                }
+                _ => Self::write_empty_prop(xml_writer, prop)?,
+            }
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+        xml_writer.write_event(Event::Text(BytesText::new("HTTP/1.1 200 OK")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:propstat")))?;
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        Ok(())
+    }
+
+    /// Write an unsupported property as an empty element
+    fn write_empty_prop<W: Write>(xml_writer: &mut Writer<W>, prop: &QualifiedName) -> Result<()> {
+        let prop_name = match prop.namespace.as_str() {
+            "http://calendarserver.org/ns/" => format!("CS:{}", prop.name),
+            "urn:ietf:params:xml:ns:caldav" => format!("C:{}", prop.name),
+            "DAV:" => format!("D:{}", prop.name),
+            namespace => format!("{}:{}", namespace, prop.name),
+        };
+        xml_writer.write_event(Event::Empty(BytesStart::new(&prop_name)))?;
+        Ok(())
+    }
+
     /// Parse a MKCALENDAR XML request
     pub fn parse_mkcalendar<R: Read>(
         reader: R,
@@ -1,10 +1,14 @@
 #[cfg(test)]
 mod tests {
-    use crate::application::adapters::caldav_adapter::{CalDavAdapter, CalDavReportType};
+    use crate::application::adapters::caldav_adapter::{
+        CalDavAdapter, CalDavReportType, CalendarDataRecurrence,
+    };
     use crate::application::adapters::webdav_adapter::{
         PropFindRequest, PropFindType, QualifiedName,
     };
-    use crate::application::dtos::calendar_dto::{CalendarDto, CalendarEventDto};
+    use crate::application::dtos::calendar_dto::{
+        CalendarChangesDto, CalendarDto, CalendarEventDto, FreeBusyResponseDto, TaskDto,
+    };
     use chrono::{TimeZone, Utc};
     use std::collections::HashMap;
     use std::io::Cursor;
@@ -20,6 +24,7 @@ mod tests {
             created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
             updated_at: Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap(),
             custom_properties: HashMap::new(),
+            sync_token: 0,
         }
     }
 
@@ -37,6 +42,30 @@ mod tests {
             ical_uid: "uid-evt-001@oxicloud".to_string(),
             created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
             updated_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            ical_data: String::new(),
+            task: None,
+        }
+    }
+
+    fn sample_task() -> CalendarEventDto {
+        CalendarEventDto {
+            id: "task-001".to_string(),
+            summary: "Renew passport".to_string(),
+            description: None,
+            location: None,
+            start_time: Utc.with_ymd_and_hms(2025, 6, 20, 17, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2025, 6, 20, 17, 0, 0).unwrap(),
+            ical_uid: "uid-task-001".to_string(),
+            ical_data: "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\n\
+                UID:uid-task-001\r\nSUMMARY:Renew passport\r\nDUE:20250620T170000Z\r\n\
+                PRIORITY:1\r\nX-APPLE-SORT-ORDER:3\r\nEND:VTODO\r\nEND:VCALENDAR\r\n"
+                .to_string(),
+            task: Some(TaskDto {
+                due_time: Some(Utc.with_ymd_and_hms(2025, 6, 20, 17, 0, 0).unwrap()),
+                priority: Some(1),
+                ..Default::default()
+            }),
+            ..sample_event()
         }
     }
 
@@ -113,7 +142,9 @@ mod tests {
         assert!(result.is_ok(), "Failed to parse report: {:?}", result.err());
 
         match result.unwrap() {
-            CalDavReportType::CalendarQuery { time_range, props } => {
+            CalDavReportType::CalendarQuery {
+                time_range, props, ..
+            } => {
                 assert!(time_range.is_some(), "Time range should be parsed");
                 let (start, end) = time_range.unwrap();
                 assert_eq!(start, Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap());
@@ -144,7 +175,7 @@ mod tests {
         );
 
         match result.unwrap() {
-            CalDavReportType::CalendarMultiget { hrefs, props } => {
+            CalDavReportType::CalendarMultiget { hrefs, props, .. } => {
                 assert_eq!(hrefs.len(), 2);
                 assert_eq!(hrefs[0], "/caldav/cal-001/evt-001.ics");
                 assert_eq!(hrefs[1], "/caldav/cal-001/evt-002.ics");
@@ -267,6 +298,8 @@ mod tests {
         let events = vec![sample_event()];
         let report = CalDavReportType::CalendarQuery {
             time_range: None,
+            component: None,
+            recurrence: CalendarDataRecurrence::AsStored,
             props: vec![
                 QualifiedName {
                     namespace: "DAV:".to_string(),
@@ -315,6 +348,8 @@ mod tests {
         let events: Vec<CalendarEventDto> = vec![];
         let report = CalDavReportType::CalendarQuery {
             time_range: None,
+            component: None,
+            recurrence: CalendarDataRecurrence::AsStored,
             props: vec![],
         };
 
@@ -519,8 +554,12 @@ mod tests {
         };
 
         let mut output = Vec::new();
-        let result =
-            CalDavAdapter::generate_principal_propfind_response(&mut output, &request, "testuser");
+        let result = CalDavAdapter::generate_principal_propfind_response(
+            &mut output,
+            &request,
+            "testuser",
+            None,
+        );
         assert!(result.is_ok(), "Failed: {:?}", result.err());
 
         let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
@@ -583,11 +622,15 @@ mod tests {
         // displayname should be populated
         assert!(xml_str.contains("Personal"), "Should contain calendar name");
 
-        // supported-calendar-component-set should have VEVENT
+        // supported-calendar-component-set should have VEVENT and VTODO
         assert!(
-            xml_str.contains("VEVENT"),
+            xml_str.contains(r#"<C:comp name="VEVENT"/>"#),
             "Should contain VEVENT component"
         );
+        assert!(
+            xml_str.contains(r#"<C:comp name="VTODO"/>"#),
+            "Should contain VTODO component"
+        );
     }
 
     #[test]
@@ -618,4 +661,348 @@ mod tests {
             other => panic!("Expected CalendarQuery, got {:?}", other),
         }
     }
+
+    // ========================
+    // Sync-collection tests (RFC 6578)
+    // ========================
+
+    #[test]
+    fn test_parse_sync_collection_report() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+        <D:sync-collection xmlns:D="DAV:">
+            <D:sync-token>http://oxicloud.org/ns/sync/7</D:sync-token>
+            <D:sync-level>1</D:sync-level>
+            <D:prop>
+                <D:getetag/>
+            </D:prop>
+        </D:sync-collection>"#;
+
+        match CalDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CalDavReportType::SyncCollection { sync_token, props } => {
+                assert_eq!(sync_token, "http://oxicloud.org/ns/sync/7");
+                assert_eq!(props.len(), 1);
+                assert_eq!(props[0].name, "getetag");
+            }
+            other => panic!("Expected SyncCollection, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_initial_sync_collection_report() {
+        let xml = r#"<D:sync-collection xmlns:D="DAV:">
+            <D:sync-token/>
+            <D:sync-level>1</D:sync-level>
+            <D:prop><D:getetag/></D:prop>
+        </D:sync-collection>"#;
+
+        match CalDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CalDavReportType::SyncCollection { sync_token, .. } => {
+                assert!(sync_token.is_empty())
+            }
+            other => panic!("Expected SyncCollection, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_generate_sync_collection_response() {
+        let changes = CalendarChangesDto {
+            sync_token: 12,
+            changed: vec![sample_event()],
+            deleted: vec!["gone@oxicloud".to_string()],
+        };
+        let props = vec![QualifiedName::new("DAV:", "getetag")];
+
+        let mut output = Vec::new();
+        CalDavAdapter::generate_sync_collection_response(
+            &mut output,
+            &changes,
+            &props,
+            "/caldav/cal-001/",
+        )
+        .unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.contains("<D:href>/caldav/cal-001/uid-evt-001@oxicloud.ics</D:href>"));
+        assert!(
+            xml_str.contains("evt-001"),
+            "Changed event should carry its ETag"
+        );
+        assert!(xml_str.contains(
+            "<D:href>/caldav/cal-001/gone@oxicloud.ics</D:href><D:status>HTTP/1.1 404 Not Found</D:status>"
+        ));
+        assert!(xml_str.contains(
+            "<D:sync-token>http://oxicloud.org/ns/sync/12</D:sync-token></D:multistatus>"
+        ));
+    }
+
+    #[test]
+    fn test_calendar_propfind_includes_sync_token() {
+        let mut calendar = sample_calendar();
+        calendar.sync_token = 5;
+        let request = PropFindRequest {
+            prop_find_type: PropFindType::Prop(vec![
+                QualifiedName::new("DAV:", "sync-token"),
+                QualifiedName::new("http://calendarserver.org/ns/", "getctag"),
+            ]),
+        };
+
+        let mut output = Vec::new();
+        CalDavAdapter::generate_calendar_collection_propfind(
+            &mut output,
+            &calendar,
+            &[],
+            &request,
+            "/caldav/cal-001/",
+            "0",
+        )
+        .unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.contains("<D:sync-token>http://oxicloud.org/ns/sync/5</D:sync-token>"));
+        assert!(xml_str.contains("<CS:getctag>http://oxicloud.org/ns/sync/5</CS:getctag>"));
+    }
+
+    #[test]
+    fn test_parse_calendar_query_with_expand() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+        <C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+            <D:prop>
+                <D:getetag/>
+                <C:calendar-data>
+                    <C:comp name="VCALENDAR">
+                        <C:prop name="VERSION"/>
+                        <C:comp name="VEVENT"/>
+                    </C:comp>
+                    <C:expand start="20250601T000000Z" end="20250608T000000Z"/>
+                </C:calendar-data>
+            </D:prop>
+            <C:filter>
+                <C:comp-filter name="VCALENDAR">
+                    <C:comp-filter name="VEVENT">
+                        <C:time-range start="20250601T000000Z" end="20250608T000000Z"/>
+                    </C:comp-filter>
+                </C:comp-filter>
+            </C:filter>
+        </C:calendar-query>"#;
+
+        match CalDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CalDavReportType::CalendarQuery {
+                time_range,
+                props,
+                recurrence,
+                ..
+            } => {
+                let start = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+                let end = Utc.with_ymd_and_hms(2025, 6, 8, 0, 0, 0).unwrap();
+                assert_eq!(time_range, Some((start, end)));
+                let names: Vec<_> = props.iter().map(|p| p.name.as_str()).collect();
+                assert_eq!(names, vec!["getetag", "calendar-data"]);
+                assert_eq!(recurrence, CalendarDataRecurrence::Expand { start, end });
+            }
+            other => panic!("Expected CalendarQuery, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_multiget_with_limit_recurrence_set() {
+        let xml = r#"<C:calendar-multiget xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+            <D:prop>
+                <C:calendar-data>
+                    <C:limit-recurrence-set start="20250101T000000Z" end="20250201T000000Z"/>
+                </C:calendar-data>
+            </D:prop>
+            <D:href>/caldav/cal-001/evt-001.ics</D:href>
+        </C:calendar-multiget>"#;
+
+        match CalDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CalDavReportType::CalendarMultiget {
+                hrefs, recurrence, ..
+            } => {
+                assert_eq!(hrefs.len(), 1);
+                assert!(matches!(
+                    recurrence,
+                    CalendarDataRecurrence::LimitRecurrenceSet { .. }
+                ));
+            }
+            other => panic!("Expected CalendarMultiget, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_generate_expanded_calendar_data() {
+        let mut event = sample_event();
+        event.rrule = Some("FREQ=WEEKLY".to_string());
+        let start = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 6, 15, 0, 0, 0).unwrap();
+        let report = CalDavReportType::CalendarQuery {
+            time_range: Some((start, end)),
+            component: None,
+            props: vec![QualifiedName::new(
+                "urn:ietf:params:xml:ns:caldav",
+                "calendar-data",
+            )],
+            recurrence: CalendarDataRecurrence::Expand { start, end },
+        };
+
+        let mut output = Vec::new();
+        CalDavAdapter::generate_calendar_events_response(
+            &mut output,
+            &[event],
+            &report,
+            "/caldav/cal-001/",
+        )
+        .unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert_eq!(xml_str.matches("RECURRENCE-ID:").count(), 2);
+        assert!(xml_str.contains("DTSTART:20260607T100000Z"));
+        assert!(!xml_str.contains("RRULE"));
+    }
+
+    #[test]
+    fn test_parse_calendar_query_vtodo_filter() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+            <D:prop>
+                <D:getetag/>
+            </D:prop>
+            <C:filter>
+                <C:comp-filter name="VCALENDAR">
+                    <C:comp-filter name="VTODO"/>
+                </C:comp-filter>
+            </C:filter>
+        </C:calendar-query>"#;
+
+        match CalDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CalDavReportType::CalendarQuery {
+                time_range,
+                component,
+                ..
+            } => {
+                assert_eq!(component.as_deref(), Some("VTODO"));
+                assert!(time_range.is_none());
+            }
+            other => panic!("Expected CalendarQuery, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_calendar_query_without_component_filter() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+            <D:prop><D:getetag/></D:prop>
+            <C:filter><C:comp-filter name="VCALENDAR"/></C:filter>
+        </C:calendar-query>"#;
+
+        match CalDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CalDavReportType::CalendarQuery { component, .. } => assert!(component.is_none()),
+            other => panic!("Expected CalendarQuery, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_generate_task_response_returns_stored_vtodo() {
+        let report = CalDavReportType::CalendarQuery {
+            time_range: None,
+            component: Some("VTODO".to_string()),
+            props: vec![
+                QualifiedName::new("DAV:", "getcontenttype"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "calendar-data"),
+            ],
+            recurrence: CalendarDataRecurrence::AsStored,
+        };
+
+        let mut output = Vec::new();
+        CalDavAdapter::generate_calendar_events_response(
+            &mut output,
+            &[sample_task()],
+            &report,
+            "/caldav/cal-001/",
+        )
+        .unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.contains("text/calendar; component=VTODO"));
+        assert!(xml_str.contains("BEGIN:VTODO"));
+        assert!(xml_str.contains("X-APPLE-SORT-ORDER:3"));
+        assert!(!xml_str.contains("VEVENT"));
+    }
+
+    #[test]
+    fn test_parse_free_busy_query_report() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <C:free-busy-query xmlns:C="urn:ietf:params:xml:ns:caldav">
+            <C:time-range start="20240115T000000Z" end="20240116T000000Z"/>
+        </C:free-busy-query>"#;
+
+        match CalDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CalDavReportType::FreeBusyQuery { time_range } => {
+                let (start, end) = time_range.expect("time-range");
+                assert_eq!(start, Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap());
+                assert_eq!(end, Utc.with_ymd_and_hms(2024, 1, 16, 0, 0, 0).unwrap());
+            }
+            other => panic!("Expected FreeBusyQuery, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_principal_propfind_advertises_scheduling() {
+        let request = PropFindRequest {
+            prop_find_type: PropFindType::Prop(vec![
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "schedule-inbox-URL"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "schedule-outbox-URL"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "calendar-user-address-set"),
+            ]),
+        };
+
+        let mut output = Vec::new();
+        CalDavAdapter::generate_principal_propfind_response(
+            &mut output,
+            &request,
+            "testuser",
+            Some("Test@example.com"),
+        )
+        .unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.contains("<C:schedule-inbox-URL><D:href>/caldav/inbox/</D:href>"));
+        assert!(xml_str.contains("<C:schedule-outbox-URL><D:href>/caldav/outbox/</D:href>"));
+        assert!(xml_str.contains("<D:href>mailto:Test@example.com</D:href>"));
+
+        // Other users' principals have no scheduling collections for the requester
+        let mut output = Vec::new();
+        CalDavAdapter::generate_principal_propfind_response(&mut output, &request, "other", None)
+            .unwrap();
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.contains("<C:schedule-inbox-URL/>"));
+        assert!(!xml_str.contains("mailto:"));
+    }
+
+    #[test]
+    fn test_generate_schedule_response() {
+        let responses = vec![
+            FreeBusyResponseDto {
+                recipient: "mailto:bob@example.com".to_string(),
+                request_status: "2.0;Success".to_string(),
+                calendar_data: Some("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n".to_string()),
+            },
+            FreeBusyResponseDto {
+                recipient: "mailto:nobody@example.com".to_string(),
+                request_status: "3.7;Invalid calendar user".to_string(),
+                calendar_data: None,
+            },
+        ];
+
+        let mut output = Vec::new();
+        CalDavAdapter::generate_schedule_response(&mut output, &responses).unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.starts_with("<C:schedule-response"));
+        assert_eq!(xml_str.matches("<C:response>").count(), 2);
+        assert!(xml_str.contains(
+            "<C:recipient><D:href>mailto:bob@example.com</D:href></C:recipient>\
+             <C:request-status>2.0;Success</C:request-status>"
+        ));
+        assert_eq!(xml_str.matches("<C:calendar-data>").count(), 1);
+    }
 }
@@ -15,7 +15,7 @@ use crate::application::adapters::webdav_adapter::{
     PropFindRequest, PropFindType, QualifiedName, Result, WebDavAdapter, WebDavError,
 };
 use crate::application::dtos::address_book_dto::AddressBookDto;
-use crate::application::dtos::contact_dto::ContactDto;
+use crate::application::dtos::contact_dto::{ContactChangesDto, ContactDto};
 
 /// CardDAV report type
 #[derive(Debug, PartialEq)]
@@ -29,6 +29,7 @@ pub enum CardDavReportType {
     },
     /// Sync-collection report
     SyncCollection {
+        /// Token from the previous sync; empty for an initial sync
         sync_token: String,
         props: Vec<QualifiedName>,
     },
@@ -263,6 +264,15 @@ impl CardDavAdapter {
         xml_writer.write_event(Event::Text(BytesText::new("text/vcard")))?;
         xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
 
+        // sync-token (RFC 6578) and its CalendarServer alias
+        let token = WebDavAdapter::sync_token_uri(book.sync_token);
+        xml_writer.write_event(Event::Start(BytesStart::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&token)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("CS:getctag")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&token)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("CS:getctag")))?;
+
         // supported-address-data
         xml_writer.write_event(Event::Start(BytesStart::new("CR:supported-address-data")))?;
         xml_writer.write_event(Event::Empty(
@@ -303,6 +313,8 @@ impl CardDavAdapter {
         xml_writer.write_event(Event::Empty(BytesStart::new("D:getlastmodified")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("D:getetag")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("D:getcontenttype")))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("CS:getctag")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("CR:supported-address-data")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("CR:addressbook-description")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new(
@@ -346,6 +358,37 @@ impl CardDavAdapter {
                     xml_writer.write_event(Event::Text(BytesText::new("text/vcard")))?;
                     xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
                 }
+                ("DAV:", "sync-token") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:sync-token")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(
+                        &WebDavAdapter::sync_token_uri(book.sync_token),
+                    )))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:sync-token")))?;
+                }
+                ("http://calendarserver.org/ns/", "getctag") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("CS:getctag")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(
+                        &WebDavAdapter::sync_token_uri(book.sync_token),
+                    )))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("CS:getctag")))?;
+                }
+                ("DAV:", "supported-report-set") => {
+                    xml_writer
+                        .write_event(Event::Start(BytesStart::new("D:supported-report-set")))?;
+                    for report in [
+                        "CR:addressbook-query",
+                        "CR:addressbook-multiget",
+                        "D:sync-collection",
+                    ] {
+                        xml_writer
+                            .write_event(Event::Start(BytesStart::new("D:supported-report")))?;
+                        xml_writer.write_event(Event::Start(BytesStart::new("D:report")))?;
+                        xml_writer.write_event(Event::Empty(BytesStart::new(report)))?;
+                        xml_writer.write_event(Event::End(BytesEnd::new("D:report")))?;
+                        xml_writer.write_event(Event::End(BytesEnd::new("D:supported-report")))?;
+                    }
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:supported-report-set")))?;
+                }
                 ("urn:ietf:params:xml:ns:carddav", "addressbook-description") => {
                     if let Some(ref desc) = book.description {
                         xml_writer.write_event(Event::Start(BytesStart::new(
@@ -440,6 +483,52 @@ impl CardDavAdapter {
         Ok(())
     }
 
+    /// Generate a sync-collection response (RFC 6578 §3.2)
+    ///
+    /// Changed contacts get a regular response, deleted ones a bare
+    /// `404 Not Found` response, and the new sync token closes the multistatus.
+    pub fn generate_sync_collection_response<W: Write>(
+        writer: W,
+        changes: &ContactChangesDto,
+        props: &[QualifiedName],
+        base_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+
+        xml_writer.write_event(Event::Start(
+            BytesStart::new("D:multistatus").with_attributes([
+                ("xmlns:D", "DAV:"),
+                ("xmlns:CR", "urn:ietf:params:xml:ns:carddav"),
+            ]),
+        ))?;
+
+        for contact in &changes.changed {
+            let href = format!("{}{}.vcf", base_href, contact.uid);
+            Self::write_contact_response(&mut xml_writer, contact, props, &href)?;
+        }
+
+        for uid in &changes.deleted {
+            let href = format!("{}{}.vcf", base_href, uid);
+            xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(&href)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+            xml_writer.write_event(Event::Text(BytesText::new("HTTP/1.1 404 Not Found")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        }
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&WebDavAdapter::sync_token_uri(
+            changes.sync_token,
+        ))))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:sync-token")))?;
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
     /// Write a single contact response element
     fn write_contact_response<W: Write>(
         xml_writer: &mut Writer<W>,
@@ -7,7 +7,9 @@ mod tests {
         PropFindRequest, PropFindType, QualifiedName,
     };
     use crate::application::dtos::address_book_dto::AddressBookDto;
-    use crate::application::dtos::contact_dto::{AddressDto, ContactDto, EmailDto, PhoneDto};
+    use crate::application::dtos::contact_dto::{
+        AddressDto, ContactChangesDto, ContactDto, EmailDto, PhoneDto,
+    };
     use chrono::{NaiveDate, TimeZone, Utc};
     use std::io::Cursor;
 
@@ -21,6 +23,7 @@ mod tests {
             is_public: false,
             created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
             updated_at: Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap(),
+            sync_token: 0,
         }
     }
 
@@ -521,4 +524,83 @@ mod tests {
         assert!(vcard.contains("Springfield"), "Should have city");
         assert!(vcard.contains("62701"), "Should have postal code");
     }
+
+    #[test]
+    fn test_parse_sync_collection_report() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+        <D:sync-collection xmlns:D="DAV:">
+            <D:sync-token>http://oxicloud.org/ns/sync/12</D:sync-token>
+            <D:sync-level>1</D:sync-level>
+            <D:prop>
+                <D:getetag/>
+            </D:prop>
+        </D:sync-collection>"#;
+
+        match CardDavAdapter::parse_report(Cursor::new(xml)).unwrap() {
+            CardDavReportType::SyncCollection { sync_token, props } => {
+                assert_eq!(sync_token, "http://oxicloud.org/ns/sync/12");
+                assert_eq!(props.len(), 1);
+                assert_eq!(props[0].name, "getetag");
+            }
+            other => panic!("Expected SyncCollection, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_generate_sync_collection_response() {
+        let changes = ContactChangesDto {
+            sync_token: 13,
+            changed: vec![sample_contact()],
+            deleted: vec!["gone-uid".to_string()],
+        };
+        let props = vec![QualifiedName::new("DAV:", "getetag")];
+
+        let mut output = Vec::new();
+        CardDavAdapter::generate_sync_collection_response(
+            &mut output,
+            &changes,
+            &props,
+            "/carddav/ab-001/",
+        )
+        .unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.contains("etag-abc123"), "Should contain etag");
+        assert!(
+            xml_str.contains("<D:href>/carddav/ab-001/gone-uid.vcf</D:href>"),
+            "Should reference the deleted contact"
+        );
+        assert!(xml_str.contains("HTTP/1.1 404 Not Found"));
+        assert!(
+            xml_str.contains(
+                "<D:sync-token>http://oxicloud.org/ns/sync/13</D:sync-token></D:multistatus>"
+            ),
+            "Should end with the new sync token"
+        );
+    }
+
+    #[test]
+    fn test_addressbook_propfind_includes_sync_token() {
+        let mut book = sample_address_book();
+        book.sync_token = 42;
+        let request = PropFindRequest {
+            prop_find_type: PropFindType::Prop(vec![
+                QualifiedName::new("DAV:", "sync-token"),
+                QualifiedName::new("http://calendarserver.org/ns/", "getctag"),
+            ]),
+        };
+
+        let mut output = Vec::new();
+        CardDavAdapter::generate_addressbooks_propfind_response(
+            &mut output,
+            &[book],
+            &request,
+            "/carddav",
+        )
+        .unwrap();
+
+        let xml_str = String::from_utf8(output).expect("Invalid UTF-8");
+        assert!(xml_str.contains("<D:sync-token>http://oxicloud.org/ns/sync/42</D:sync-token>"));
+        assert!(xml_str.contains("<CS:getctag>http://oxicloud.org/ns/sync/42</CS:getctag>"));
+    }
 }
@@ -89,6 +89,17 @@ pub struct PropFindRequest {
     pub prop_find_type: PropFindType,
 }
 
+impl PropFindRequest {
+    /// Whether the response can include dead properties, i.e. whether
+    /// they need to be loaded for this request.
+    pub fn wants_dead_props(&self) -> bool {
+        match &self.prop_find_type {
+            PropFindType::AllProp | PropFindType::PropName => true,
+            PropFindType::Prop(props) => props.iter().any(|p| p.namespace != "DAV:"),
+        }
+    }
+}
+
 /// WebDAV property value
 #[derive(Debug, Clone)]
 pub struct PropValue {
@@ -96,6 +107,61 @@ pub struct PropValue {
     pub value: Option<String>,
 }
 
+impl PropValue {
+    /// The modification time (Unix seconds) this property asks to set, if
+    /// it is `DAV:getlastmodified` or Windows' `Win32LastModifiedTime`,
+    /// both carried as RFC 1123 dates.
+    pub fn client_mtime(&self) -> Option<i64> {
+        let is_mtime = matches!(
+            (self.name.namespace.as_str(), self.name.name.as_str()),
+            ("DAV:", "getlastmodified") | ("urn:schemas-microsoft-com:", "Win32LastModifiedTime")
+        );
+        if !is_mtime {
+            return None;
+        }
+        chrono::DateTime::parse_from_rfc2822(self.value.as_deref()?.trim())
+            .ok()
+            .map(|t| t.timestamp())
+    }
+}
+
+/// Outcome of one PROPPATCH instruction (RFC 4918 §9.2.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropPatchStatus {
+    Ok,
+    /// Protected property, or nowhere to store it
+    Forbidden,
+    /// Value not acceptable for the property
+    Conflict,
+    /// Valid, but not applied because another instruction failed
+    FailedDependency,
+}
+
+impl PropPatchStatus {
+    fn status_line(self) -> &'static str {
+        match self {
+            Self::Ok => "HTTP/1.1 200 OK",
+            Self::Forbidden => "HTTP/1.1 403 Forbidden",
+            Self::Conflict => "HTTP/1.1 409 Conflict",
+            Self::FailedDependency => "HTTP/1.1 424 Failed Dependency",
+        }
+    }
+
+    /// PROPPATCH is atomic: when any instruction failed, the valid ones are
+    /// not applied either and become 424.  Returns whether all succeeded.
+    pub fn settle<N>(results: &mut [(N, PropPatchStatus)]) -> bool {
+        if results.iter().all(|(_, status)| *status == Self::Ok) {
+            return true;
+        }
+        for (_, status) in results.iter_mut() {
+            if *status == Self::Ok {
+                *status = Self::FailedDependency;
+            }
+        }
+        false
+    }
+}
+
 /// WebDAV lock information
 #[derive(Debug, Clone)]
 pub struct LockInfo {
@@ -176,10 +242,28 @@ impl NextcloudPropContext {
     }
 }
 
+/// Prefix of the sync tokens handed out for DAV collections (RFC 6578
+/// requires tokens to be URIs); the suffix is the collection's change counter.
+const SYNC_TOKEN_PREFIX: &str = "http://oxicloud.org/ns/sync/";
+
 /// WebDAV adapter for converting between XML and domain objects
 pub struct WebDavAdapter;
 
 impl WebDavAdapter {
+    /// Formats a collection change counter as a `DAV:sync-token` URI
+    pub fn sync_token_uri(token: i64) -> String {
+        format!("{}{}", SYNC_TOKEN_PREFIX, token)
+    }
+
+    /// Parses a `DAV:sync-token` URI issued by `sync_token_uri`
+    pub fn parse_sync_token(uri: &str) -> Option<i64> {
+        uri.trim()
+            .strip_prefix(SYNC_TOKEN_PREFIX)?
+            .parse()
+            .ok()
+            .filter(|n: &i64| *n >= 0)
+    }
+
     /// Collect namespace prefix → URI mappings from element attributes.
     /// E.g. `xmlns:D="DAV:"` maps prefix `"D"` to `"DAV:"`.
     pub fn collect_ns_decls(
@@ -307,6 +391,7 @@ impl WebDavAdapter {
         folder: &FolderDto,
         request: &PropFindRequest,
         href: &str,
+        dead_props: &[PropValue],
     ) -> Result<()> {
         // Start response element
         xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
@@ -327,14 +412,16 @@ impl WebDavAdapter {
             PropFindType::AllProp => {
                 // Write all standard properties for a folder
                 Self::write_folder_standard_props(xml_writer, folder)?;
+                Self::write_dead_props(xml_writer, dead_props, true)?;
             }
             PropFindType::PropName => {
                 // Write only property names (empty elements)
                 Self::write_folder_prop_names(xml_writer)?;
+                Self::write_dead_props(xml_writer, dead_props, false)?;
             }
             PropFindType::Prop(props) => {
                 // Write requested properties
-                Self::write_folder_requested_props(xml_writer, folder, props)?;
+                Self::write_folder_requested_props(xml_writer, folder, props, dead_props)?;
             }
         }
 
@@ -361,6 +448,7 @@ impl WebDavAdapter {
         file: &FileDto,
         request: &PropFindRequest,
         href: &str,
+        dead_props: &[PropValue],
     ) -> Result<()> {
         // Start response element
         xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
@@ -381,14 +469,16 @@ impl WebDavAdapter {
             PropFindType::AllProp => {
                 // Write all standard properties for a file
                 Self::write_file_standard_props(xml_writer, file)?;
+                Self::write_dead_props(xml_writer, dead_props, true)?;
             }
             PropFindType::PropName => {
                 // Write only property names (empty elements)
                 Self::write_file_prop_names(xml_writer)?;
+                Self::write_dead_props(xml_writer, dead_props, false)?;
             }
             PropFindType::Prop(props) => {
                 // Write requested properties
-                Self::write_file_requested_props(xml_writer, file, props)?;
+                Self::write_file_requested_props(xml_writer, file, props, dead_props)?;
             }
         }
 
@@ -546,6 +636,7 @@ impl WebDavAdapter {
         xml_writer: &mut Writer<W>,
         folder: &FolderDto,
         props: &[QualifiedName],
+        dead_props: &[PropValue],
     ) -> Result<()> {
         for prop in props {
             if prop.namespace == "DAV:" {
@@ -614,6 +705,8 @@ impl WebDavAdapter {
                         ))))?;
                     }
                 }
+            } else if let Some(dead) = dead_props.iter().find(|d| &d.name == prop) {
+                Self::write_dead_prop(xml_writer, dead, true)?;
             } else {
                 // Non-DAV namespace, not supported
                 xml_writer.write_event(Event::Empty(BytesStart::new(format!(
@@ -631,6 +724,7 @@ impl WebDavAdapter {
         xml_writer: &mut Writer<W>,
         file: &FileDto,
         props: &[QualifiedName],
+        dead_props: &[PropValue],
     ) -> Result<()> {
         for prop in props {
             if prop.namespace == "DAV:" {
@@ -697,6 +791,8 @@ impl WebDavAdapter {
                         ))))?;
                     }
                 }
+            } else if let Some(dead) = dead_props.iter().find(|d| &d.name == prop) {
+                Self::write_dead_prop(xml_writer, dead, true)?;
             } else {
                 // Non-DAV namespace, not supported
                 xml_writer.write_event(Event::Empty(BytesStart::new(format!(
@@ -709,6 +805,42 @@ impl WebDavAdapter {
         Ok(())
     }
 
+    /// Write dead properties, with their values or as names only
+    fn write_dead_props<W: Write>(
+        xml_writer: &mut Writer<W>,
+        dead_props: &[PropValue],
+        with_values: bool,
+    ) -> Result<()> {
+        for prop in dead_props {
+            Self::write_dead_prop(xml_writer, prop, with_values)?;
+        }
+        Ok(())
+    }
+
+    /// Write a single dead property.
+    ///
+    /// The element declares its own default namespace, so any namespace URI
+    /// round-trips without having to allocate prefixes.
+    fn write_dead_prop<W: Write>(
+        xml_writer: &mut Writer<W>,
+        prop: &PropValue,
+        with_value: bool,
+    ) -> Result<()> {
+        let mut elem = BytesStart::new(prop.name.name.as_str());
+        elem.push_attribute(("xmlns", prop.name.namespace.as_str()));
+        match prop.value.as_deref() {
+            Some(value) if with_value => {
+                xml_writer.write_event(Event::Start(elem))?;
+                xml_writer.write_event(Event::Text(BytesText::new(value)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new(prop.name.name.as_str())))?;
+            }
+            _ => {
+                xml_writer.write_event(Event::Empty(elem))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Parse a PROPPATCH XML request
     pub fn parse_proppatch<R: Read>(reader: R) -> Result<(Vec<PropValue>, Vec<QualifiedName>)> {
         let mut xml_reader = Reader::from_reader(BufReader::new(reader));
@@ -820,7 +952,7 @@ impl WebDavAdapter {
     pub fn generate_proppatch_response<W: Write>(
         writer: W,
         href: &str,
-        results: &[(&QualifiedName, bool)],
+        results: &[(&QualifiedName, PropPatchStatus)],
     ) -> Result<()> {
         let mut xml_writer = Writer::new(writer);
 
@@ -837,56 +969,25 @@ impl WebDavAdapter {
         xml_writer.write_event(Event::Text(BytesText::new(href)))?;
         xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
 
-        // Group results by status
-        let mut success_props = Vec::new();
-        let mut failed_props = Vec::new();
-
-        for (prop, success) in results {
-            if *success {
-                success_props.push(prop);
-            } else {
-                failed_props.push(prop);
+        // One propstat per status
+        for status in [
+            PropPatchStatus::Ok,
+            PropPatchStatus::Forbidden,
+            PropPatchStatus::Conflict,
+            PropPatchStatus::FailedDependency,
+        ] {
+            let props: Vec<_> = results
+                .iter()
+                .filter(|(_, s)| *s == status)
+                .map(|(prop, _)| prop)
+                .collect();
+            if props.is_empty() {
+                continue;
             }
-        }
-
-        // Write successful properties
-        if !success_props.is_empty() {
-            xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
-
-            // Start prop
-            xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
-
-            // Write property names
-            for prop in success_props {
-                let prop_name = if prop.namespace == "DAV:" {
-                    format!("D:{}", prop.name)
-                } else {
-                    format!("{}:{}", prop.namespace, prop.name)
-                };
-                xml_writer.write_event(Event::Empty(BytesStart::new(&prop_name)))?;
-            }
-
-            // End prop
-            xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
-
-            // Write status
-            xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
-            xml_writer.write_event(Event::Text(BytesText::new("HTTP/1.1 200 OK")))?;
-            xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
-
-            // End propstat
-            xml_writer.write_event(Event::End(BytesEnd::new("D:propstat")))?;
-        }
 
-        // Write failed properties
-        if !failed_props.is_empty() {
             xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
-
-            // Start prop
             xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
-
-            // Write property names
-            for prop in failed_props {
+            for prop in props {
                 let prop_name = if prop.namespace == "DAV:" {
                     format!("D:{}", prop.name)
                 } else {
@@ -894,16 +995,11 @@ impl WebDavAdapter {
                 };
                 xml_writer.write_event(Event::Empty(BytesStart::new(&prop_name)))?;
             }
-
-            // End prop
             xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
 
-            // Write status
             xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
-            xml_writer.write_event(Event::Text(BytesText::new("HTTP/1.1 403 Forbidden")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(status.status_line())))?;
             xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
-
-            // End propstat
             xml_writer.write_event(Event::End(BytesEnd::new("D:propstat")))?;
         }
 
@@ -1135,8 +1231,9 @@ impl WebDavAdapter {
         folder: &FolderDto,
         request: &PropFindRequest,
         href: &str,
+        dead_props: &[PropValue],
     ) -> Result<()> {
-        Self::write_folder_response(writer, folder, request, href)
+        Self::write_folder_response(writer, folder, request, href, dead_props)
     }
 
     /// Writes a single `<D:response>` element for a file.
@@ -1145,7 +1242,74 @@ impl WebDavAdapter {
         file: &FileDto,
         request: &PropFindRequest,
         href: &str,
+        dead_props: &[PropValue],
     ) -> Result<()> {
-        Self::write_file_response(writer, file, request, href)
+        Self::write_file_response(writer, file, request, href, dead_props)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prop(namespace: &str, name: &str, value: &str) -> PropValue {
+        PropValue {
+            name: QualifiedName::new(namespace, name),
+            value: Some(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn client_mtime_parses_rfc1123_dates() {
+        let p = prop("DAV:", "getlastmodified", "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(p.client_mtime(), Some(784111777));
+        let p = prop(
+            "urn:schemas-microsoft-com:",
+            "Win32LastModifiedTime",
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+        );
+        assert_eq!(p.client_mtime(), Some(784111777));
+    }
+
+    #[test]
+    fn client_mtime_ignores_other_properties() {
+        let p = prop("urn:x", "getlastmodified", "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(p.client_mtime(), None);
+        let p = prop("DAV:", "getlastmodified", "yesterday");
+        assert_eq!(p.client_mtime(), None);
+    }
+
+    #[test]
+    fn proppatch_is_all_or_nothing() {
+        let a = QualifiedName::new("urn:x", "a");
+        let b = QualifiedName::new("DAV:", "getetag");
+        let mut results = vec![(&a, PropPatchStatus::Ok), (&b, PropPatchStatus::Ok)];
+        assert!(PropPatchStatus::settle(&mut results));
+
+        results[1].1 = PropPatchStatus::Forbidden;
+        assert!(!PropPatchStatus::settle(&mut results));
+        assert_eq!(results[0].1, PropPatchStatus::FailedDependency);
+
+        let mut body = Vec::new();
+        WebDavAdapter::generate_proppatch_response(&mut body, "/webdav/f", &results).unwrap();
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains("HTTP/1.1 403 Forbidden"));
+        assert!(body.contains("HTTP/1.1 424 Failed Dependency"));
+        assert!(!body.contains("200 OK"));
+    }
+
+    #[test]
+    fn sync_token_round_trip() {
+        let uri = WebDavAdapter::sync_token_uri(42);
+        assert_eq!(WebDavAdapter::parse_sync_token(&uri), Some(42));
+        assert_eq!(WebDavAdapter::parse_sync_token("42"), None);
+        assert_eq!(
+            WebDavAdapter::parse_sync_token("http://example.com/sync/42"),
+            None
+        );
+        assert_eq!(
+            WebDavAdapter::parse_sync_token(&WebDavAdapter::sync_token_uri(-1)),
+            None
+        );
     }
 }
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::application::ports::activity_ports::ActivityRecord;
+
+/// One entry of the activity log
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ActivityDto {
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    /// e.g. `file_created`, `folder_moved`, `share_accessed`, `login_failed`
+    pub action: String,
+    /// User who acted; absent for anonymous visitors and unknown logins
+    pub actor_id: Option<String>,
+    /// Username of the actor, or the name given at a failed login
+    pub actor_name: Option<String>,
+    /// User whose item or account was affected
+    pub owner_id: Option<String>,
+    pub owner_name: Option<String>,
+    /// `file`, `folder`, `share` or `account`
+    pub item_type: Option<String>,
+    pub item_id: Option<String>,
+    /// Name of the item when the event happened
+    pub item_name: Option<String>,
+    pub ip_address: Option<String>,
+    /// Action-specific extras, e.g. `old_name` for renames
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+}
+
+impl From<ActivityRecord> for ActivityDto {
+    fn from(record: ActivityRecord) -> Self {
+        Self {
+            id: record.id,
+            occurred_at: record.occurred_at,
+            action: record.action,
+            actor_id: record.actor_id.map(|id| id.to_string()),
+            actor_name: record.actor_name,
+            owner_id: record.owner_id.map(|id| id.to_string()),
+            owner_name: record.owner_name,
+            item_type: record.item_type,
+            item_id: record.item_id,
+            item_name: record.item_name,
+            ip_address: record.ip_address,
+            details: record.details,
+        }
+    }
+}
+
+/// Filters and paging of the activity feeds
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct ActivityQueryDto {
+    /// Comma-separated actions (`file_renamed`) or item types (`file`,
+    /// `folder`, `share`, `account`)
+    pub action: Option<String>,
+    /// Only entries about this file, folder or share
+    pub item_id: Option<String>,
+    /// Only entries at or after this time (RFC 3339 or `YYYY-MM-DD`)
+    pub from: Option<String>,
+    /// Only entries before this time (RFC 3339, or `YYYY-MM-DD` for the
+    /// whole day)
+    pub to: Option<String>,
+    /// Only entries where this user acted or was affected (admin views)
+    pub user_id: Option<String>,
+    /// Page number, starting at 0
+    pub page: Option<usize>,
+    /// Entries per page (default 50, max 500)
+    pub page_size: Option<usize>,
+    /// Export format: `csv` (default) or `json`
+    pub format: Option<String>,
+}
@@ -12,6 +12,8 @@ pub struct AddressBookDto {
     pub is_public: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Collection sync token (RFC 6578), bumped by every vCard change
+    pub sync_token: i64,
 }
 
 impl Default for AddressBookDto {
@@ -25,6 +27,7 @@ impl Default for AddressBookDto {
             is_public: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            sync_token: 0,
         }
     }
 }
@@ -40,6 +43,7 @@ impl From<AddressBook> for AddressBookDto {
             is_public: book.is_public(),
             created_at: *book.created_at(),
             updated_at: *book.updated_at(),
+            sync_token: book.sync_token(),
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::domain::entities::calendar::Calendar;
-use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::entities::calendar_event::{CalendarEvent, TaskDetails, TaskUpdate};
+use crate::domain::services::scheduling_service;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -16,6 +17,8 @@ pub struct CalendarDto {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub custom_properties: HashMap<String, String>,
+    /// Collection sync token (RFC 6578), bumped by every event change
+    pub sync_token: i64,
 }
 
 impl Default for CalendarDto {
@@ -30,6 +33,7 @@ impl Default for CalendarDto {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             custom_properties: HashMap::new(),
+            sync_token: 0,
         }
     }
 }
@@ -46,6 +50,7 @@ impl From<Calendar> for CalendarDto {
             created_at: *calendar.created_at(),
             updated_at: *calendar.updated_at(),
             custom_properties: calendar.custom_properties().clone(),
+            sync_token: calendar.sync_token(),
         }
     }
 }
@@ -91,6 +96,60 @@ pub struct CalendarEventDto {
     pub ical_uid: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Stored iCalendar object, used for recurrence expansion; not part of
+    /// the REST representation
+    #[serde(skip)]
+    pub ical_data: String,
+    /// Task properties; present only for tasks (VTODO)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<TaskDto>,
+}
+
+impl CalendarEventDto {
+    /// Whether this is a task (VTODO) rather than an event
+    pub fn is_task(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// iCalendar component name: `VEVENT` or `VTODO`
+    pub fn component(&self) -> &'static str {
+        if self.is_task() { "VTODO" } else { "VEVENT" }
+    }
+
+    /// Whether the stored object has an ORGANIZER (RFC 6638 §3.1)
+    pub fn is_scheduling_object(&self) -> bool {
+        scheduling_service::is_scheduling_object(&self.ical_data)
+    }
+}
+
+/// DTO for the task properties of a VTODO
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TaskDto {
+    /// DTSTART, when the task has one
+    pub start_time: Option<DateTime<Utc>>,
+    pub due_time: Option<DateTime<Utc>>,
+    /// NEEDS-ACTION, IN-PROCESS, COMPLETED or CANCELLED
+    pub status: Option<String>,
+    pub percent_complete: Option<i16>,
+    /// 1 (highest) to 9 (lowest), 0 when undefined
+    pub priority: Option<i16>,
+    /// UID of the parent task
+    pub related_to: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<&TaskDetails> for TaskDto {
+    fn from(task: &TaskDetails) -> Self {
+        Self {
+            start_time: None,
+            due_time: task.due,
+            status: task.status.clone(),
+            percent_complete: task.percent_complete,
+            priority: task.priority,
+            related_to: task.related_to.clone(),
+            completed_at: task.completed_at,
+        }
+    }
 }
 
 impl Default for CalendarEventDto {
@@ -108,6 +167,8 @@ impl Default for CalendarEventDto {
             ical_uid: String::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            ical_data: String::new(),
+            task: None,
         }
     }
 }
@@ -127,10 +188,26 @@ impl From<CalendarEvent> for CalendarEventDto {
             ical_uid: event.ical_uid().to_string(),
             created_at: *event.created_at(),
             updated_at: *event.updated_at(),
+            ical_data: event.ical_data().to_string(),
+            task: event.task().map(|task| TaskDto {
+                start_time: event.task_start(),
+                ..TaskDto::from(task)
+            }),
         }
     }
 }
 
+/// DTO for the changes to a calendar's events since a sync token
+#[derive(Debug, Clone)]
+pub struct CalendarChangesDto {
+    /// The calendar's current sync token
+    pub sync_token: i64,
+    /// Events created or modified since the token
+    pub changed: Vec<CalendarEventDto>,
+    /// iCalendar UIDs of events deleted since the token
+    pub deleted: Vec<String>,
+}
+
 /// DTO for calendar event creation using iCalendar data
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateEventICalDto {
@@ -165,7 +242,89 @@ pub struct UpdateEventDto {
     pub user_id: String, // Added for authorization
 }
 
+/// DTO for task creation
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTaskDto {
+    pub calendar_id: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub due_time: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+    pub percent_complete: Option<i16>,
+    pub priority: Option<i16>,
+    pub related_to: Option<String>,
+}
+
+impl From<CreateTaskDto> for TaskUpdate {
+    fn from(dto: CreateTaskDto) -> Self {
+        Self {
+            summary: Some(dto.summary),
+            description: dto.description,
+            start_time: dto.start_time,
+            due: dto.due_time,
+            status: dto.status,
+            percent_complete: dto.percent_complete,
+            priority: dto.priority,
+            related_to: dto.related_to,
+        }
+    }
+}
+
+/// DTO for updating a task; absent fields are left unchanged and an empty
+/// string clears a text field
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateTaskDto {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub due_time: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+    pub percent_complete: Option<i16>,
+    pub priority: Option<i16>,
+    pub related_to: Option<String>,
+}
+
+impl From<UpdateTaskDto> for TaskUpdate {
+    fn from(dto: UpdateTaskDto) -> Self {
+        Self {
+            summary: dto.summary,
+            description: dto.description,
+            start_time: dto.start_time,
+            due: dto.due_time,
+            status: dto.status,
+            percent_complete: dto.percent_complete,
+            priority: dto.priority,
+            related_to: dto.related_to,
+        }
+    }
+}
+
 /// DTO for querying events in a time range
+/// DTO for a message in a user's scheduling inbox (RFC 6638 §2.2)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleMessageDto {
+    pub id: String,
+    pub ical_uid: String,
+    /// iTIP method: REQUEST, REPLY or CANCEL
+    pub method: String,
+    /// Calendar user address of the sender
+    pub sender: String,
+    pub ical_data: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of a free-busy request for one recipient (RFC 6638 §5.2)
+#[derive(Debug, Clone)]
+pub struct FreeBusyResponseDto {
+    /// Calendar user address of the recipient
+    pub recipient: String,
+    /// iTIP REQUEST-STATUS, e.g. `2.0;Success`
+    pub request_status: String,
+    /// METHOD:REPLY VFREEBUSY, when the request succeeded
+    pub calendar_data: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EventQueryDto {
     pub calendar_id: String,
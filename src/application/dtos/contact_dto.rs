@@ -218,6 +218,17 @@ pub struct UpdateContactGroupDto {
     pub user_id: String, // User updating the group
 }
 
+/// DTO for the changes to an address book since a sync token
+#[derive(Debug, Clone)]
+pub struct ContactChangesDto {
+    /// The address book's current sync token
+    pub sync_token: i64,
+    /// Contacts created or modified since the token
+    pub changed: Vec<ContactDto>,
+    /// UIDs of contacts deleted since the token
+    pub deleted: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupMembershipDto {
     pub group_id: String,
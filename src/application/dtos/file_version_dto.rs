@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// DTO representing a previous version of a file
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FileVersionDto {
+    /// Version ID
+    pub id: String,
+    /// ID of the file this version belongs to
+    pub file_id: String,
+    /// Current name of the file (versions share the file's name)
+    pub name: String,
+    /// Size of the version content in bytes
+    pub size: u64,
+    /// MIME type of the version content
+    pub mime_type: String,
+    /// Content hash (also usable as a stable ETag)
+    pub etag: String,
+    /// Modification time of the content when it was superseded
+    pub modified_at: DateTime<Utc>,
+    /// When the content was superseded by a newer upload
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::entities::group::Group;
+
+/// DTO representing a user group
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GroupDto {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Storage quota members get at least
+    pub default_quota_bytes: Option<i64>,
+    pub member_count: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GroupDto {
+    pub fn from_entity(group: &Group, member_count: u64) -> Self {
+        Self {
+            id: group.id().to_string(),
+            name: group.name().to_string(),
+            description: group.description().map(str::to_string),
+            default_quota_bytes: group.default_quota_bytes(),
+            member_count,
+            created_at: group.created_at(),
+            updated_at: group.updated_at(),
+        }
+    }
+}
+
+/// A member of a group
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GroupMemberDto {
+    pub user_id: String,
+    pub username: String,
+    pub email: String,
+    /// "manual" when added by an admin, "oidc" or "ldap" when synced on login
+    pub source: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Query parameters for listing groups
+#[derive(Debug, Deserialize)]
+pub struct ListGroupsQueryDto {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request to create a group
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateGroupDto {
+    pub name: String,
+    pub description: Option<String>,
+    pub default_quota_bytes: Option<i64>,
+}
+
+/// Request to update a group; omitted fields are left unchanged
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateGroupDto {
+    pub name: Option<String>,
+    /// An empty description removes it
+    pub description: Option<String>,
+    /// 0 removes the group's default quota
+    pub default_quota_bytes: Option<i64>,
+}
+
+/// Request to add a user to a group
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddGroupMemberDto {
+    pub user_id: String,
+}
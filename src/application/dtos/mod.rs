@@ -1,3 +1,4 @@
+pub mod activity_dto;
 pub mod address_book_dto;
 pub mod app_password_dto;
 pub mod calendar_dto;
@@ -6,14 +7,23 @@ pub mod device_auth_dto;
 pub mod display_helpers;
 pub mod favorites_dto;
 pub mod file_dto;
+pub mod file_version_dto;
 pub mod folder_dto;
 pub mod folder_listing_dto;
+pub mod group_dto;
 pub mod i18n_dto;
+pub mod notification_dto;
 pub mod pagination;
 pub mod playlist_dto;
 pub mod recent_dto;
 pub mod search_dto;
+pub mod session_dto;
 pub mod settings_dto;
 pub mod share_dto;
+pub mod snapshot_dto;
 pub mod trash_dto;
+pub mod two_factor_dto;
 pub mod user_dto;
+pub mod user_share_dto;
+pub mod webauthn_dto;
+pub mod webhook_dto;
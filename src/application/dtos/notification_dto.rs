@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::application::ports::notification_ports::NotificationRecord;
+
+/// A notification of the current user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationDto {
+    pub id: i64,
+    /// `share_received`, `quota_warning` or `share_expiring`
+    pub kind: String,
+    pub subject: String,
+    pub message: String,
+    /// Where to go from the notification, relative to the base URL
+    pub link: Option<String>,
+    /// `share`, `link` or `quota`
+    pub object_type: String,
+    pub object_id: String,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<NotificationRecord> for NotificationDto {
+    fn from(record: NotificationRecord) -> Self {
+        Self {
+            id: record.id,
+            kind: record.kind,
+            subject: record.subject,
+            message: record.message,
+            link: record.link,
+            object_type: record.object_type,
+            object_id: record.object_id,
+            read: record.is_read,
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// The latest notifications and the number of unread ones
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationListDto {
+    pub notifications: Vec<NotificationDto>,
+    /// Unread notifications in total, including ones beyond `limit`
+    pub unread: usize,
+}
+
+/// How many notifications to list
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct NotificationQueryDto {
+    /// Most notifications to return (default 50, max 200)
+    pub limit: Option<usize>,
+}
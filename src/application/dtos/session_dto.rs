@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::application::dtos::app_password_dto::AppPasswordSummaryDto;
+use crate::application::services::device_auth_service::DeviceInfoDto;
+
+/// A signed-in device of the current user (one refresh token family)
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDto {
+    /// Family ID; pass it to `DELETE /api/auth/sessions/{id}`
+    pub id: String,
+    /// Readable client description, e.g. "Firefox on Linux"
+    pub device: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// `password`, `passkey`, `oidc` or `device`
+    pub login_method: String,
+    pub signed_in_at: DateTime<Utc>,
+    /// Latest token refresh
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+/// Everything that can access the account: browser and device sessions,
+/// app passwords and device authorizations still waiting for their client
+#[derive(Debug, Serialize)]
+pub struct SessionOverviewDto {
+    pub sessions: Vec<SessionDto>,
+    pub app_passwords: Vec<AppPasswordSummaryDto>,
+    pub devices: Vec<DeviceInfoDto>,
+}
@@ -58,6 +58,36 @@ pub struct OidcTestResultDto {
     pub provider_name_suggestion: Option<String>,
 }
 
+// ============================================================================
+// LDAP DTOs (Admin Panel)
+// ============================================================================
+
+/// Request body for testing the LDAP connection
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TestLdapConnectionDto {
+    /// When set, the user is also looked up and their mapped attributes returned
+    pub username: Option<String>,
+}
+
+/// Result of LDAP connection test
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LdapTestResultDto {
+    pub success: bool,
+    pub message: String,
+    pub user: Option<LdapTestUserDto>,
+}
+
+/// Attributes of a user as OxiCloud maps them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LdapTestUserDto {
+    pub dn: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub groups: Vec<String>,
+    pub quota_bytes: Option<i64>,
+}
+
 // ============================================================================
 // Admin User Management DTOs
 // ============================================================================
@@ -17,6 +17,13 @@ pub struct ShareDto {
     pub created_at: u64,
     pub created_by: String,
     pub access_count: u64,
+    /// Upload-only link: visitors can upload but not see the contents
+    pub hide_contents: bool,
+    /// Largest file, in bytes, a visitor may upload
+    pub max_upload_size: Option<u64>,
+    /// Downloads after which the link stops working
+    pub max_downloads: Option<u64>,
+    pub download_count: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -34,13 +41,37 @@ pub struct CreateShareDto {
     pub password: Option<String>,
     pub expires_at: Option<u64>,
     pub permissions: Option<SharePermissionsDto>,
+    /// Upload-only link; requires a folder link with write permission
+    #[serde(default)]
+    pub hide_contents: bool,
+    pub max_upload_size: Option<u64>,
+    pub max_downloads: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateShareDto {
     pub password: Option<String>,
+    /// New expiration (unix seconds); 0 removes it
     pub expires_at: Option<u64>,
     pub permissions: Option<SharePermissionsDto>,
+    pub hide_contents: Option<bool>,
+    /// New upload size limit in bytes; 0 removes the limit
+    pub max_upload_size: Option<u64>,
+    /// New download limit; 0 removes it
+    pub max_downloads: Option<u64>,
+}
+
+/// One download through a share link, as shown to the link's owner
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShareAccessDto {
+    pub id: i64,
+    /// Unix seconds
+    pub accessed_at: u64,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// File served; absent for ZIP downloads of a folder link
+    pub file_id: Option<String>,
+    pub file_name: Option<String>,
 }
 
 /// Extension methods to convert between DTOs and domain entities
@@ -61,6 +92,10 @@ impl ShareDto {
             created_at: share.created_at(),
             created_by: share.created_by().to_string(),
             access_count: share.access_count(),
+            hide_contents: share.hide_contents(),
+            max_upload_size: share.max_upload_size(),
+            max_downloads: share.max_downloads(),
+            download_count: share.download_count(),
         }
     }
 }
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// DTO representing a named, read-only snapshot of a folder tree
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SnapshotDto {
+    pub id: String,
+    /// Source folder (None once the folder has been deleted)
+    pub folder_id: Option<String>,
+    /// Name of the source folder when the snapshot was taken
+    pub folder_name: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub folder_count: i64,
+    pub file_count: i64,
+    /// Sum of the sizes of all files in the snapshot (logical size)
+    pub total_size: i64,
+    /// Whether the snapshot was taken by a schedule
+    pub scheduled: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// DTO representing a folder or file inside a snapshot
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SnapshotEntryDto {
+    pub name: String,
+    /// Path relative to the snapshot root (e.g. "docs/report.pdf")
+    pub path: String,
+    pub is_folder: bool,
+    pub size: i64,
+    pub mime_type: Option<String>,
+    pub modified_at: DateTime<Utc>,
+}
+
+/// Request to create a snapshot of a folder
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSnapshotDto {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Delete the snapshot automatically after this many days
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
+}
+
+/// Request to restore all or part of a snapshot.
+///
+/// Restores never overwrite live data: the content is materialised as a new
+/// folder (or file) under `target_folder_id`.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RestoreSnapshotDto {
+    /// Path inside the snapshot to restore; empty/None = the whole snapshot
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Destination folder; defaults to the parent of the source folder
+    #[serde(default)]
+    pub target_folder_id: Option<String>,
+    /// Name of the restored item; defaults to "<name> (<snapshot name>)"
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Result of a snapshot restore
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RestoreSnapshotResultDto {
+    /// ID of the restored root folder or file
+    pub id: String,
+    pub is_folder: bool,
+    pub folders_restored: i64,
+    pub files_restored: i64,
+}
+
+/// DTO representing a folder's automatic snapshot schedule
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SnapshotScheduleDto {
+    pub id: String,
+    pub folder_id: String,
+    pub interval_hours: i32,
+    /// Days a scheduled snapshot is kept (None = forever)
+    pub retention_days: Option<i32>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to create or replace a folder's snapshot schedule
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetSnapshotScheduleDto {
+    pub interval_hours: u32,
+    #[serde(default)]
+    pub retention_days: Option<u32>,
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::application::dtos::user_dto::AuthResponseDto;
+
+/// Two-factor state of the current user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TwoFactorStatusDto {
+    pub enabled: bool,
+    /// Whether an admin requires two-factor authentication for this user
+    pub enforced: bool,
+    pub recovery_codes_remaining: u64,
+}
+
+/// A new TOTP secret to add to an authenticator app
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TotpSetupDto {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI, usually rendered as a QR code
+    pub otpauth_uri: String,
+}
+
+/// A TOTP code or a recovery code
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorCodeDto {
+    pub code: String,
+}
+
+/// Single-use recovery codes; shown once
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Returned by a password login that still needs the second factor
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TwoFactorChallengeDto {
+    /// Always true; tells this response apart from a token response
+    pub two_factor_required: bool,
+    /// Short-lived token to pass to `/api/auth/login/2fa`
+    pub challenge_token: String,
+    /// True when two-factor authentication is enforced but the user has
+    /// not set it up yet: they must enrol through `/api/auth/login/2fa/setup`
+    pub enrollment_required: bool,
+    /// Second factors the user can answer with: "totp" (a TOTP or recovery
+    /// code via `/api/auth/login/2fa`) and "webauthn" (a passkey via
+    /// `/api/auth/webauthn/login`)
+    pub methods: Vec<String>,
+    /// Seconds the challenge stays valid
+    pub expires_in: u64,
+}
+
+/// Second step of a password login
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorLoginDto {
+    pub challenge_token: String,
+    /// TOTP code, or a recovery code
+    pub code: String,
+}
+
+/// Starts the enrolment required by a login challenge
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorChallengeSetupDto {
+    pub challenge_token: String,
+}
+
+/// Tokens issued by the second login step
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TwoFactorLoginResponseDto {
+    #[serde(flatten)]
+    pub auth: AuthResponseDto,
+    /// Set when the login completed an enforced enrolment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+/// Who must use two-factor authentication
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorPolicyDto {
+    /// Roles ("admin", "user") whose members must use it
+    #[serde(default)]
+    pub enforced_roles: Vec<String>,
+    /// Groups whose members must use it, matched by name ignoring case
+    #[serde(default)]
+    pub enforced_groups: Vec<String>,
+}
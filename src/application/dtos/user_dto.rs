@@ -50,6 +50,16 @@ pub struct RegisterDto {
     pub password: String,
 }
 
+/// Response of a self-registration.
+#[derive(Debug, Serialize, Clone)]
+pub struct RegisterResponseDto {
+    #[serde(flatten)]
+    pub user: UserDto,
+    /// The account can sign in only after following the mailed
+    /// verification link
+    pub email_verification_required: bool,
+}
+
 /// DTO for the one-time initial admin setup endpoint (`/api/setup`).
 /// Available only when the system is not yet initialized (no admin exists).
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -79,6 +89,26 @@ pub struct RefreshTokenDto {
     pub refresh_token: String,
 }
 
+/// Request for a mailed link: a password reset, or a new verification link.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AccountLinkRequestDto {
+    /// Username or email address
+    pub login: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResetPasswordDto {
+    /// Token from the mailed reset link
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyEmailDto {
+    /// Token from the mailed verification link
+    pub token: String,
+}
+
 /// Authenticated current user data (for use in application services)
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct CurrentUser {
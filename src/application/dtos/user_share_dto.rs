@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::application::dtos::share_dto::SharePermissionsDto;
+
+/// DTO representing a file or folder shared directly with another user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserShareDto {
+    pub id: String,
+    pub item_id: String,
+    /// "file" or "folder"
+    pub item_type: String,
+    /// Name of the item in the owner's tree
+    pub item_name: String,
+    /// Name under which the item appears in the recipient's home folder
+    pub mount_name: String,
+    pub owner_id: String,
+    pub owner_name: String,
+    /// User who created the share (differs from the owner for reshares)
+    pub shared_by: String,
+    pub shared_by_name: String,
+    pub recipient_id: String,
+    pub recipient_name: String,
+    pub permissions: SharePermissionsDto,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to share a file or folder with another user
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateUserShareDto {
+    pub item_id: String,
+    /// "file" or "folder"
+    pub item_type: String,
+    /// Username or email address of the recipient
+    pub recipient: String,
+    /// Read-only when omitted
+    #[serde(default)]
+    pub permissions: Option<SharePermissionsDto>,
+}
+
+/// Request to change a user share.
+///
+/// The owner (or resharer) may change the permissions; the recipient may
+/// rename the share in their home folder.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpdateUserShareDto {
+    #[serde(default)]
+    pub permissions: Option<SharePermissionsDto>,
+    #[serde(default)]
+    pub mount_name: Option<String>,
+}
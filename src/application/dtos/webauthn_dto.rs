@@ -0,0 +1,179 @@
+//! WebAuthn ceremony payloads.
+//!
+//! Options and credentials use the JSON encoding of the WebAuthn API
+//! (camelCase names, binary values as base64url), so browsers can pass them
+//! through `PublicKeyCredential.parseCreationOptionsFromJSON()` and
+//! `credential.toJSON()` unchanged.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::application::ports::webauthn_ports::WebAuthnCredential;
+
+/// A registered passkey of the current user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebAuthnCredentialDto {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<&WebAuthnCredential> for WebAuthnCredentialDto {
+    fn from(credential: &WebAuthnCredential) -> Self {
+        Self {
+            id: credential.id.to_string(),
+            name: credential.name.clone(),
+            created_at: credential.created_at,
+            last_used_at: credential.last_used_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RelyingPartyDto {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAuthnUserDto {
+    /// Opaque user handle (base64url)
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CredentialParameterDto {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub alg: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CredentialDescriptorDto {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    /// Credential ID (base64url)
+    pub id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transports: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelectionDto {
+    pub resident_key: String,
+    pub require_resident_key: bool,
+    pub user_verification: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCreationOptionsDto {
+    pub rp: RelyingPartyDto,
+    pub user: WebAuthnUserDto,
+    /// base64url
+    pub challenge: String,
+    pub pub_key_cred_params: Vec<CredentialParameterDto>,
+    /// Milliseconds
+    pub timeout: u64,
+    pub exclude_credentials: Vec<CredentialDescriptorDto>,
+    pub authenticator_selection: AuthenticatorSelectionDto,
+    pub attestation: String,
+}
+
+/// Options for `navigator.credentials.create()`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialCreationOptionsDto {
+    pub public_key: PublicKeyCreationOptionsDto,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyRequestOptionsDto {
+    /// base64url
+    pub challenge: String,
+    /// Milliseconds
+    pub timeout: u64,
+    pub rp_id: String,
+    /// Empty for passwordless login: the authenticator offers its
+    /// discoverable credentials
+    pub allow_credentials: Vec<CredentialDescriptorDto>,
+    pub user_verification: String,
+}
+
+/// Options for `navigator.credentials.get()`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRequestOptionsDto {
+    pub public_key: PublicKeyRequestOptionsDto,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponseDto {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+/// A new credential from `navigator.credentials.create()`
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationCredentialDto {
+    pub id: String,
+    pub raw_id: Option<String>,
+    pub response: AttestationResponseDto,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponseDto {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+/// An assertion from `navigator.credentials.get()`
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionCredentialDto {
+    pub id: String,
+    pub raw_id: Option<String>,
+    pub response: AssertionResponseDto,
+}
+
+/// Completes a passkey registration
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WebAuthnRegisterDto {
+    /// Label for the passkey, e.g. the device it lives on
+    pub name: Option<String>,
+    pub credential: RegistrationCredentialDto,
+}
+
+/// Requests login options.  With a challenge token the passkey is the
+/// second factor of a password login; without one it is a passwordless
+/// login.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct WebAuthnLoginOptionsDto {
+    #[serde(default)]
+    pub challenge_token: Option<String>,
+}
+
+/// Completes a passkey login
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WebAuthnLoginDto {
+    /// Challenge token of the password login, when used as second factor
+    #[serde(default)]
+    pub challenge_token: Option<String>,
+    pub credential: AssertionCredentialDto,
+}
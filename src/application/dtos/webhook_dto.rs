@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::application::ports::webhook_ports::{DeliveryRecord, WebhookRecord};
+
+/// A webhook subscription
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookDto {
+    pub id: String,
+    pub url: String,
+    pub description: String,
+    /// Events sent to the webhook; empty for all
+    pub events: Vec<String>,
+    /// Only events about files at or below this path
+    pub path_prefix: Option<String>,
+    /// Only events about files of these MIME types, e.g. `application/pdf` or `image/*`
+    pub mime_types: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Signing key; only returned when the webhook is created or the key
+    /// is rotated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl From<WebhookRecord> for WebhookDto {
+    fn from(record: WebhookRecord) -> Self {
+        Self {
+            id: record.id.to_string(),
+            url: record.url,
+            description: record.description,
+            events: record.events,
+            path_prefix: record.path_prefix,
+            mime_types: record.mime_types,
+            active: record.active,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            secret: None,
+        }
+    }
+}
+
+/// Request to create a webhook
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookDto {
+    /// `http` or `https` URL the events are posted to
+    pub url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// `file_created`, `file_updated`, `file_deleted`, `share_created`,
+    /// `share_deleted`; all when omitted
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    #[serde(default)]
+    pub mime_types: Vec<String>,
+}
+
+/// Request to change a webhook; omitted fields stay as they are
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpdateWebhookDto {
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub events: Option<Vec<String>>,
+    /// An empty string removes the path filter
+    pub path_prefix: Option<String>,
+    pub mime_types: Option<Vec<String>>,
+    pub active: Option<bool>,
+    /// Replace the signing key; the new one is returned
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+/// An attempt to deliver an event, with its outcome
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookDeliveryDto {
+    pub id: i64,
+    pub event: String,
+    /// The JSON body that was sent
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    /// `pending`, `delivered` or `failed`
+    pub status: String,
+    pub attempts: i32,
+    /// HTTP status of the last attempt, if the receiver answered
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl From<DeliveryRecord> for WebhookDeliveryDto {
+    fn from(record: DeliveryRecord) -> Self {
+        Self {
+            id: record.id,
+            event: record.event,
+            payload: record.payload,
+            status: record.status,
+            attempts: record.attempts,
+            response_status: record.response_status,
+            error: record.error,
+            created_at: record.created_at,
+            last_attempt_at: record.last_attempt_at,
+        }
+    }
+}
+
+/// How many deliveries to list
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct WebhookDeliveryQueryDto {
+    /// Most deliveries to return (default 50, max 500)
+    pub limit: Option<usize>,
+}
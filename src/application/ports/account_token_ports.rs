@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::common::errors::Result;
+
+/// What a mailed account token may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountTokenPurpose {
+    PasswordReset,
+    EmailVerification,
+}
+
+impl AccountTokenPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PasswordReset => "password_reset",
+            Self::EmailVerification => "email_verification",
+        }
+    }
+}
+
+/// Defines persistence operations for mailed account tokens and pending
+/// address verifications.
+pub trait AccountTokenRepositoryPort: Send + Sync + 'static {
+    /// Stores a new token and invalidates the user's earlier unused tokens
+    /// of the same purpose, so only the latest link works.
+    async fn create_token(
+        &self,
+        token_id: Uuid,
+        user_id: Uuid,
+        purpose: AccountTokenPurpose,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// Marks an unused, unexpired token as used.  Returns `false` when the
+    /// token is unknown, used or expired.
+    async fn consume_token(
+        &self,
+        token_id: Uuid,
+        user_id: Uuid,
+        purpose: AccountTokenPurpose,
+    ) -> Result<bool>;
+
+    /// Invalidates all unused tokens of the user for `purpose`.
+    async fn revoke_tokens(&self, user_id: Uuid, purpose: AccountTokenPurpose) -> Result<()>;
+
+    /// Records that `email` of the user awaits verification.
+    async fn set_email_unverified(&self, user_id: Uuid, email: &str) -> Result<()>;
+
+    /// The address awaiting verification, if any.
+    async fn get_unverified_email(&self, user_id: Uuid) -> Result<Option<String>>;
+
+    /// Clears the pending verification.  Returns `false` when there was none.
+    async fn set_email_verified(&self, user_id: Uuid) -> Result<bool>;
+}
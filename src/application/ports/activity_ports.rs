@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::common::errors::Result;
+
+/// Something a user did that ends up in the activity log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityAction {
+    FileCreated,
+    FileUpdated,
+    FileRenamed,
+    FileMoved,
+    FileTrashed,
+    FileRestored,
+    FileDeleted,
+    FolderCreated,
+    FolderRenamed,
+    FolderMoved,
+    FolderTrashed,
+    FolderRestored,
+    FolderDeleted,
+    ShareCreated,
+    ShareAccessed,
+    Login,
+    Logout,
+    LoginFailed,
+}
+
+impl ActivityAction {
+    pub const ALL: [ActivityAction; 18] = [
+        Self::FileCreated,
+        Self::FileUpdated,
+        Self::FileRenamed,
+        Self::FileMoved,
+        Self::FileTrashed,
+        Self::FileRestored,
+        Self::FileDeleted,
+        Self::FolderCreated,
+        Self::FolderRenamed,
+        Self::FolderMoved,
+        Self::FolderTrashed,
+        Self::FolderRestored,
+        Self::FolderDeleted,
+        Self::ShareCreated,
+        Self::ShareAccessed,
+        Self::Login,
+        Self::Logout,
+        Self::LoginFailed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FileCreated => "file_created",
+            Self::FileUpdated => "file_updated",
+            Self::FileRenamed => "file_renamed",
+            Self::FileMoved => "file_moved",
+            Self::FileTrashed => "file_trashed",
+            Self::FileRestored => "file_restored",
+            Self::FileDeleted => "file_deleted",
+            Self::FolderCreated => "folder_created",
+            Self::FolderRenamed => "folder_renamed",
+            Self::FolderMoved => "folder_moved",
+            Self::FolderTrashed => "folder_trashed",
+            Self::FolderRestored => "folder_restored",
+            Self::FolderDeleted => "folder_deleted",
+            Self::ShareCreated => "share_created",
+            Self::ShareAccessed => "share_accessed",
+            Self::Login => "login",
+            Self::Logout => "logout",
+            Self::LoginFailed => "login_failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == s)
+    }
+
+    /// Kind of item the action applies to: `file`, `folder`, `share` or
+    /// `account`.
+    pub fn item_type(&self) -> &'static str {
+        match self {
+            Self::FileCreated
+            | Self::FileUpdated
+            | Self::FileRenamed
+            | Self::FileMoved
+            | Self::FileTrashed
+            | Self::FileRestored
+            | Self::FileDeleted => "file",
+            Self::FolderCreated
+            | Self::FolderRenamed
+            | Self::FolderMoved
+            | Self::FolderTrashed
+            | Self::FolderRestored
+            | Self::FolderDeleted => "folder",
+            Self::ShareCreated | Self::ShareAccessed => "share",
+            Self::Login | Self::Logout | Self::LoginFailed => "account",
+        }
+    }
+}
+
+/// A new activity log entry.
+#[derive(Debug, Clone)]
+pub struct NewActivity {
+    pub action: ActivityAction,
+    /// User who acted; `None` for anonymous visitors and unknown logins
+    pub actor_id: Option<Uuid>,
+    /// Name given by an actor that is not a known user
+    pub actor_name: Option<String>,
+    /// User whose item or account was affected
+    pub owner_id: Option<Uuid>,
+    pub item_id: Option<String>,
+    pub item_name: Option<String>,
+    pub ip_address: Option<String>,
+    /// Action-specific extras, e.g. the previous name of a renamed file
+    pub details: serde_json::Value,
+}
+
+impl NewActivity {
+    pub fn new(action: ActivityAction) -> Self {
+        Self {
+            action,
+            actor_id: None,
+            actor_name: None,
+            owner_id: None,
+            item_id: None,
+            item_name: None,
+            ip_address: None,
+            details: serde_json::Value::Object(Default::default()),
+        }
+    }
+
+    /// An action a user took on their own item or account.
+    pub fn by(action: ActivityAction, user_id: Uuid) -> Self {
+        Self::new(action).with_actor(user_id).with_owner(user_id)
+    }
+
+    pub fn with_actor(mut self, actor_id: Uuid) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    pub fn with_actor_name(mut self, name: impl Into<String>) -> Self {
+        self.actor_name = Some(name.into());
+        self
+    }
+
+    pub fn with_owner(mut self, owner_id: Uuid) -> Self {
+        self.owner_id = Some(owner_id);
+        self
+    }
+
+    pub fn with_item(mut self, id: impl Into<String>, name: impl Into<String>) -> Self {
+        self.item_id = Some(id.into());
+        self.item_name = Some(name.into());
+        self
+    }
+
+    pub fn with_ip(mut self, ip: impl Into<String>) -> Self {
+        self.ip_address = Some(ip.into());
+        self
+    }
+
+    pub fn with_detail(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        if let serde_json::Value::Object(map) = &mut self.details {
+            map.insert(key.to_string(), value.into());
+        }
+        self
+    }
+}
+
+/// A stored activity log entry.
+#[derive(Debug, Clone)]
+pub struct ActivityRecord {
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    /// Current username of the actor, or the name they gave
+    pub actor_name: Option<String>,
+    pub owner_id: Option<Uuid>,
+    pub owner_name: Option<String>,
+    pub item_type: Option<String>,
+    pub item_id: Option<String>,
+    pub item_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub details: serde_json::Value,
+}
+
+/// Which entries to list.  Empty fields do not filter.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityFilter {
+    /// Entries where this user acted or whose item was affected
+    pub user_id: Option<Uuid>,
+    /// Entries with one of these actions
+    pub actions: Vec<ActivityAction>,
+    pub item_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Defines persistence operations for the activity log.
+pub trait ActivityRepositoryPort: Send + Sync + 'static {
+    /// Appends an entry.
+    async fn record(&self, activity: &NewActivity) -> Result<()>;
+
+    /// Entries matching `filter`, newest first, and the total number of
+    /// matches.
+    async fn list(
+        &self,
+        filter: &ActivityFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<ActivityRecord>, usize)>;
+
+    /// Owner and name the log last recorded for an item, for events that
+    /// arrive after the item is gone.
+    async fn last_known_item(&self, item_id: &str) -> Result<Option<(Option<Uuid>, String)>>;
+
+    /// Deletes entries older than `before`.  Returns how many were deleted.
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64>;
+}
@@ -1,7 +1,7 @@
 use crate::common::errors::DomainError;
 use crate::domain::entities::app_password::AppPassword;
 use crate::domain::entities::device_code::DeviceCode;
-use crate::domain::entities::session::Session;
+use crate::domain::entities::session::{Session, SessionFamily};
 use crate::domain::entities::user::User;
 use uuid::Uuid;
 
@@ -187,6 +187,33 @@ pub trait OidcServicePort: Send + Sync + 'static {
     fn provider_name(&self) -> &str;
 }
 
+/// A user entry found in the LDAP directory, with its attributes mapped
+#[derive(Debug, Clone)]
+pub struct LdapUserEntry {
+    pub dn: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    /// Group names (the first RDN value of group DNs)
+    pub groups: Vec<String>,
+    pub quota_bytes: Option<i64>,
+}
+
+/// Port for LDAP operations — implemented in infrastructure layer
+pub trait LdapServicePort: Send + Sync + 'static {
+    /// Looks up the user and binds as them with `password`.
+    /// Fails with `AccessDenied` when the user is unknown or the password
+    /// is wrong, and with `InternalError` when the directory is unreachable.
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<LdapUserEntry, DomainError>;
+
+    /// Looks up a user with the service account, without authenticating.
+    async fn find_user(&self, username: &str) -> Result<Option<LdapUserEntry>, DomainError>;
+}
+
 pub trait SessionStoragePort: Send + Sync + 'static {
     /// Creates a new session
     async fn create_session(&self, session: Session) -> Result<Session, DomainError>;
@@ -205,6 +232,30 @@ pub trait SessionStoragePort: Send + Sync + 'static {
 
     /// Revokes all sessions in a token family (used when replay of a revoked token is detected)
     async fn revoke_session_family(&self, family_id: Uuid) -> Result<u64, DomainError>;
+
+    /// Revokes the token families opened with a WebAuthn credential (used when the passkey is removed)
+    async fn revoke_webauthn_sessions(&self, credential_id: Uuid) -> Result<u64, DomainError>;
+
+    /// Revokes one token family of a user (signing out one device)
+    async fn revoke_user_session_family(
+        &self,
+        user_id: Uuid,
+        family_id: Uuid,
+    ) -> Result<u64, DomainError>;
+
+    /// Lists the signed-in devices of a user
+    async fn list_active_session_families(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<SessionFamily>, DomainError>;
+
+    /// Records the IP address and user agent behind a session
+    async fn record_session_client(
+        &self,
+        refresh_token: &str,
+        ip_address: &str,
+        user_agent: Option<&str>,
+    ) -> Result<(), DomainError>;
 }
 
 // ============================================================================
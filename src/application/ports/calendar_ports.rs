@@ -1,6 +1,6 @@
 use crate::application::dtos::calendar_dto::{
-    CalendarDto, CalendarEventDto, CreateCalendarDto, CreateEventDto, CreateEventICalDto,
-    UpdateCalendarDto, UpdateEventDto,
+    CalendarChangesDto, CalendarDto, CalendarEventDto, CreateCalendarDto, CreateEventDto,
+    CreateEventICalDto, CreateTaskDto, UpdateCalendarDto, UpdateEventDto, UpdateTaskDto,
 };
 use crate::common::errors::DomainError;
 use chrono::{DateTime, Utc};
@@ -87,6 +87,23 @@ pub trait CalendarStoragePort: Send + Sync + 'static {
     ) -> Result<CalendarEventDto, DomainError>;
     async fn delete_event(&self, event_id: &str) -> Result<(), DomainError>;
     async fn get_event(&self, event_id: &str) -> Result<CalendarEventDto, DomainError>;
+    async fn find_event_by_uid(
+        &self,
+        calendar_id: &str,
+        ical_uid: &str,
+    ) -> Result<Option<CalendarEventDto>, DomainError>;
+    /// Replaces an event's iCalendar object, keeping its id
+    async fn update_event_ical(
+        &self,
+        event_id: &str,
+        ical_data: String,
+    ) -> Result<CalendarEventDto, DomainError>;
+    async fn create_task(&self, task: CreateTaskDto) -> Result<CalendarEventDto, DomainError>;
+    async fn update_task(
+        &self,
+        task_id: &str,
+        update: UpdateTaskDto,
+    ) -> Result<CalendarEventDto, DomainError>;
     async fn list_events_by_calendar(
         &self,
         calendar_id: &str,
@@ -103,6 +120,12 @@ pub trait CalendarStoragePort: Send + Sync + 'static {
         start: &DateTime<Utc>,
         end: &DateTime<Utc>,
     ) -> Result<Vec<CalendarEventDto>, DomainError>;
+    /// Changes since a sync token; `None` when the token is invalid or expired
+    async fn get_event_changes(
+        &self,
+        calendar_id: &str,
+        since: Option<i64>,
+    ) -> Result<Option<CalendarChangesDto>, DomainError>;
 }
 
 /// Port for calendar use cases.
@@ -180,6 +203,19 @@ pub trait CalendarUseCase: Send + Sync + 'static {
         event_id: &str,
         user_id: Uuid,
     ) -> Result<CalendarEventDto, DomainError>;
+
+    // Task (VTODO) operations; tasks are read and deleted as events
+    async fn create_task(
+        &self,
+        task: CreateTaskDto,
+        user_id: Uuid,
+    ) -> Result<CalendarEventDto, DomainError>;
+    async fn update_task(
+        &self,
+        task_id: &str,
+        update: UpdateTaskDto,
+        user_id: Uuid,
+    ) -> Result<CalendarEventDto, DomainError>;
     async fn list_events(
         &self,
         calendar_id: &str,
@@ -194,4 +230,14 @@ pub trait CalendarUseCase: Send + Sync + 'static {
         end: DateTime<Utc>,
         user_id: Uuid,
     ) -> Result<Vec<CalendarEventDto>, DomainError>;
+    /// Events changed and deleted since a sync token (RFC 6578).
+    ///
+    /// `since = None` is an initial sync returning every event.  Returns
+    /// `None` when the token is invalid or has expired.
+    async fn get_changes_since(
+        &self,
+        calendar_id: &str,
+        since: Option<i64>,
+        user_id: Uuid,
+    ) -> Result<Option<CalendarChangesDto>, DomainError>;
 }
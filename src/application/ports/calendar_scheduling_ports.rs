@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::dtos::calendar_dto::{
+    CalendarEventDto, FreeBusyResponseDto, ScheduleMessageDto,
+};
+use crate::common::errors::Result;
+
+/// Defines CalDAV scheduling (RFC 6638) between OxiCloud users.
+///
+/// Scheduling is implicit: after a client stores or deletes a scheduling
+/// object (one with an ORGANIZER), the CalDAV handler reports the change and
+/// the service delivers the resulting iTIP messages.  Attendees outside
+/// OxiCloud are skipped.
+pub trait CalendarSchedulingUseCase: Send + Sync {
+    /// Delivers the messages caused by a stored object.
+    ///
+    /// When the user organizes it, attendees receive a REQUEST and a copy
+    /// in their default calendar, and removed attendees a CANCEL.  When the
+    /// user attends it and changed their PARTSTAT, the organizer's copy is
+    /// updated and the organizer receives a REPLY.
+    async fn schedule_write(
+        &self,
+        user_id: Uuid,
+        previous: Option<&CalendarEventDto>,
+        current: &CalendarEventDto,
+    ) -> Result<()>;
+
+    /// Delivers the messages caused by a deleted object: a CANCEL to every
+    /// attendee when the user organized it, a DECLINED reply otherwise.
+    async fn schedule_delete(&self, user_id: Uuid, deleted: &CalendarEventDto) -> Result<()>;
+
+    /// Lists the messages in the user's scheduling inbox, oldest first.
+    async fn list_inbox(&self, user_id: Uuid) -> Result<Vec<ScheduleMessageDto>>;
+
+    /// Removes a message from the user's scheduling inbox.
+    async fn delete_inbox_message(&self, user_id: Uuid, message_id: &str) -> Result<()>;
+
+    /// Busy time within `[start, end)` for a `free-busy-query` REPORT
+    /// (RFC 4791 §7.10), as a VCALENDAR with one VFREEBUSY.
+    ///
+    /// With a `calendar_id`, covers that calendar if the user can read it;
+    /// otherwise covers the user's own calendars and those shared with them.
+    async fn free_busy_query(
+        &self,
+        user_id: Uuid,
+        calendar_id: Option<&str>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<String>;
+
+    /// Answers a VFREEBUSY request POSTed to the user's outbox (RFC 6638
+    /// §5).  Each attendee's busy time covers the calendars they own that
+    /// the requester may read (own, shared or public).
+    async fn free_busy_request(
+        &self,
+        user_id: Uuid,
+        ical_data: &str,
+    ) -> Result<Vec<FreeBusyResponseDto>>;
+}
+
+// ─────────────────────────────────────────────────────
+// Outbound port — persistence abstraction
+// ─────────────────────────────────────────────────────
+
+/// Secondary (outbound) port for scheduling inbox persistence.
+pub trait ScheduleInboxRepositoryPort: Send + Sync + 'static {
+    /// Stores a message in a user's inbox.
+    async fn deliver(&self, user_id: Uuid, message: &ScheduleMessageDto) -> Result<()>;
+
+    /// Lists a user's inbox, oldest first.
+    async fn list_messages(&self, user_id: Uuid) -> Result<Vec<ScheduleMessageDto>>;
+
+    /// Deletes a message from a user's inbox; returns whether it existed.
+    async fn delete_message(&self, user_id: Uuid, message_id: Uuid) -> Result<bool>;
+}
@@ -3,8 +3,8 @@ use crate::application::dtos::address_book_dto::{
     UpdateAddressBookDto,
 };
 use crate::application::dtos::contact_dto::{
-    ContactDto, ContactGroupDto, CreateContactDto, CreateContactGroupDto, CreateContactVCardDto,
-    GroupMembershipDto, UpdateContactDto, UpdateContactGroupDto,
+    ContactChangesDto, ContactDto, ContactGroupDto, CreateContactDto, CreateContactGroupDto,
+    CreateContactVCardDto, GroupMembershipDto, UpdateContactDto, UpdateContactGroupDto,
 };
 use crate::common::errors::DomainError;
 use uuid::Uuid;
@@ -138,4 +138,14 @@ pub trait ContactUseCase: Send + Sync + 'static {
         address_book_id: &str,
         user_id: Uuid,
     ) -> Result<Vec<(String, String)>, DomainError>;
+
+    // Collection sync (RFC 6578)
+    /// Contacts changed and deleted since a sync token; `since = None` is an
+    /// initial sync. Returns `None` when the token is invalid or has expired.
+    async fn get_changes_since(
+        &self,
+        address_book_id: &str,
+        since: Option<i64>,
+        user_id: Uuid,
+    ) -> Result<Option<ContactChangesDto>, DomainError>;
 }
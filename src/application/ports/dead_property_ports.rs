@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use crate::application::adapters::webdav_adapter::{PropPatchStatus, PropValue, QualifiedName};
+use crate::common::errors::Result;
+
+/// Defines operations on WebDAV dead properties.
+///
+/// Callers resolve (and authorise) the file or folder first; properties are
+/// addressed by resource id only.
+pub trait DeadPropertyUseCase: Send + Sync {
+    /// Returns the dead properties of a file or folder.
+    async fn get_properties(&self, resource_id: &str) -> Result<Vec<PropValue>>;
+
+    /// Returns the dead properties of several resources, keyed by resource id.
+    /// Resources without properties are absent from the map.
+    async fn get_properties_batch(
+        &self,
+        resource_ids: &[String],
+    ) -> Result<HashMap<String, Vec<PropValue>>>;
+
+    /// Validates a PROPPATCH without applying it: protected (live)
+    /// properties are `Forbidden`, oversized values `Conflict`.
+    fn check_patch(
+        &self,
+        set: &[PropValue],
+        remove: &[QualifiedName],
+    ) -> Vec<(QualifiedName, PropPatchStatus)>;
+
+    /// Applies a PROPPATCH to a file or folder, atomically: if any property
+    /// is rejected nothing is stored and the others are `FailedDependency`.
+    async fn patch_properties(
+        &self,
+        resource_id: &str,
+        is_folder: bool,
+        set: Vec<PropValue>,
+        remove: Vec<QualifiedName>,
+    ) -> Result<Vec<(QualifiedName, PropPatchStatus)>>;
+
+    /// Copies the dead properties of one resource onto another.
+    async fn copy_properties(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        is_folder: bool,
+    ) -> Result<()>;
+
+    /// Copies the dead properties of a folder subtree onto a copy of it.
+    async fn copy_tree_properties(
+        &self,
+        source_folder_id: &str,
+        target_folder_id: &str,
+    ) -> Result<()>;
+}
+
+// ─────────────────────────────────────────────────────
+// Outbound port — persistence abstraction
+// ─────────────────────────────────────────────────────
+
+/// Secondary (outbound) port for dead property persistence.
+pub trait DeadPropertyRepositoryPort: Send + Sync + 'static {
+    /// Lists the properties of the given resources as `(resource_id, property)`.
+    async fn list_properties(&self, resource_ids: &[String]) -> Result<Vec<(String, PropValue)>>;
+
+    /// Sets and removes properties of a resource in one transaction.
+    async fn update_properties(
+        &self,
+        resource_id: &str,
+        is_folder: bool,
+        set: &[PropValue],
+        remove: &[QualifiedName],
+    ) -> Result<()>;
+
+    /// Copies all properties of `source_id` onto `target_id`, replacing
+    /// properties with the same name.
+    async fn copy_properties(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        is_folder: bool,
+    ) -> Result<()>;
+
+    /// Copies the properties of a folder subtree onto a copy of it.
+    /// Returns the number of properties copied.
+    async fn copy_tree_properties(
+        &self,
+        source_folder_id: &str,
+        target_folder_id: &str,
+    ) -> Result<u64>;
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::common::errors::Result;
+
+/// A folder marked as end-to-end encrypted.
+#[derive(Debug, Clone)]
+pub struct EncryptedFolder {
+    pub folder_id: String,
+    pub owner_id: Uuid,
+    /// Metadata document as written by the client; opaque to the server
+    pub metadata: Option<String>,
+    pub lock_token: Option<String>,
+    pub locked_at: Option<DateTime<Utc>>,
+}
+
+/// Key material a user stored for end-to-end encryption.
+#[derive(Debug, Clone, Default)]
+pub struct UserKeys {
+    /// PEM certificate the server issued from the user's CSR
+    pub public_key: Option<String>,
+    /// Private key, encrypted by the client
+    pub private_key: Option<String>,
+}
+
+/// Defines persistence operations for end-to-end encryption.
+pub trait E2eeRepositoryPort: Send + Sync + 'static {
+    async fn get_user_keys(&self, user_id: Uuid) -> Result<UserKeys>;
+
+    /// Stores or (with `None`) clears the user's certificate.
+    async fn set_public_key(&self, user_id: Uuid, public_key: Option<&str>) -> Result<()>;
+
+    /// Stores or (with `None`) clears the user's encrypted private key.
+    async fn set_private_key(&self, user_id: Uuid, private_key: Option<&str>) -> Result<()>;
+
+    /// Certificates of the given users, as `(username, certificate)`.
+    /// Users without one are left out.
+    async fn public_keys_by_username(&self, usernames: &[String]) -> Result<Vec<(String, String)>>;
+
+    /// The server's signing key (PKCS#8 PEM), if one was stored.
+    async fn get_server_key(&self) -> Result<Option<String>>;
+
+    /// Stores `private_key` unless a server key exists already, and returns
+    /// the key in effect.
+    async fn store_server_key(&self, private_key: &str) -> Result<String>;
+
+    async fn get_folder(&self, folder_id: &str) -> Result<Option<EncryptedFolder>>;
+
+    async fn mark_encrypted(&self, folder_id: &str, owner_id: Uuid) -> Result<()>;
+
+    /// Removes the encrypted flag together with the metadata and lock.
+    async fn unmark_encrypted(&self, folder_id: &str) -> Result<()>;
+
+    async fn set_metadata(&self, folder_id: &str, metadata: Option<&str>) -> Result<()>;
+
+    /// Locks a folder in one statement, so concurrent clients cannot both
+    /// win.  Succeeds when the folder is unlocked, its lock is older than
+    /// `timeout_minutes`, or `current` is its lock (which renews it, keeping
+    /// the token); otherwise `new_token` is stored.  Returns the token in
+    /// effect, or `None` when another client holds the lock.
+    async fn acquire_lock(
+        &self,
+        folder_id: &str,
+        new_token: &str,
+        current: Option<&str>,
+        timeout_minutes: i32,
+    ) -> Result<Option<String>>;
+
+    /// Releases the lock when the folder is unlocked, its lock is older than
+    /// `timeout_minutes`, or `token` is its lock.  Returns whether it did.
+    async fn release_lock(
+        &self,
+        folder_id: &str,
+        token: Option<&str>,
+        timeout_minutes: i32,
+    ) -> Result<bool>;
+
+    /// Encrypted folders containing an item (a folder or file id), nearest
+    /// first.  A folder counts as containing itself.
+    async fn encrypted_ancestors(&self, item_id: &str) -> Result<Vec<EncryptedFolder>>;
+
+    /// Which of `folder_ids` are marked as encrypted.
+    async fn filter_encrypted(&self, folder_ids: &[String]) -> Result<HashSet<String>>;
+
+    /// Whether a folder has no files or subfolders outside the trash.
+    async fn is_folder_empty(&self, folder_id: &str) -> Result<bool>;
+}
@@ -282,7 +282,9 @@ pub trait FileManagementUseCase: Send + Sync + 'static {
         target_folder_id: Option<String>,
     ) -> Result<FileDto, DomainError>;
 
-    /// Copies a file, enforcing that `caller_id` is the owner.
+    /// Copies a file `caller_id` can read into a folder they may add to.
+    /// The copy belongs to, and counts against the quota of, the owner of
+    /// the target folder.
     async fn copy_file_owned(
         &self,
         file_id: &str,
@@ -301,6 +303,10 @@ pub trait FileManagementUseCase: Send + Sync + 'static {
         new_name: &str,
     ) -> Result<FileDto, DomainError>;
 
+    /// Sets a file's modification time (Unix seconds) without changing its
+    /// content (system/internal — no ownership check).
+    async fn set_modified_time(&self, file_id: &str, modified_at: i64) -> Result<(), DomainError>;
+
     /// Deletes a file (system/internal — no ownership check).
     async fn delete_file(&self, id: &str) -> Result<(), DomainError>;
 
@@ -335,8 +341,9 @@ pub trait FileManagementUseCase: Send + Sync + 'static {
         ))
     }
 
-    /// Copies a folder tree, enforcing that `caller_id` owns both the source folder
-    /// and the target parent folder.
+    /// Copies a folder tree `caller_id` can read into a folder they may add
+    /// to.  The copy belongs to, and counts against the quota of, the owner
+    /// of the target folder.
     async fn copy_folder_tree_owned(
         &self,
         source_folder_id: &str,
@@ -0,0 +1,66 @@
+use uuid::Uuid;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::file_version_dto::FileVersionDto;
+use crate::application::ports::blob_storage_ports::BlobStream;
+use crate::common::errors::Result;
+
+/// Defines operations on the version history of a file.
+///
+/// Versions are recorded by the storage layer whenever a file's content
+/// is replaced; this use case only reads, restores and deletes them.
+pub trait FileVersionUseCase: Send + Sync {
+    /// Lists the previous versions of a file, newest first.
+    async fn list_versions(&self, file_id: &str, owner_id: Uuid) -> Result<Vec<FileVersionDto>>;
+
+    /// Returns a version's metadata together with a stream of its content.
+    async fn get_version_content(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<(FileVersionDto, BlobStream)>;
+
+    /// Makes a version the current content of the file.
+    ///
+    /// The content being replaced is itself recorded as a new version,
+    /// so a restore can always be undone.
+    async fn restore_version(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<FileDto>;
+
+    /// Permanently deletes a single version.
+    async fn delete_version(&self, file_id: &str, version_id: &str, owner_id: Uuid) -> Result<()>;
+}
+
+// ─────────────────────────────────────────────────────
+// Outbound port — persistence abstraction
+// ─────────────────────────────────────────────────────
+
+/// Secondary (outbound) port for file version persistence.
+pub trait FileVersionRepositoryPort: Send + Sync + 'static {
+    /// Lists versions of a file owned by `owner_id`, newest first.
+    async fn list_versions(&self, file_id: &str, owner_id: Uuid) -> Result<Vec<FileVersionDto>>;
+
+    /// Gets a single version of a file owned by `owner_id`.
+    ///
+    /// Returns `NotFound` when the version does not exist, belongs to
+    /// another file, or the file belongs to another user.
+    async fn get_version(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<FileVersionDto>;
+
+    /// Deletes a version row. Returns the blob hash it referenced.
+    async fn delete_version(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<String>;
+}
@@ -0,0 +1,75 @@
+use uuid::Uuid;
+
+use crate::application::dtos::group_dto::GroupMemberDto;
+use crate::common::errors::Result;
+use crate::domain::entities::group::Group;
+
+/// Where a group membership came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MembershipSource {
+    /// Added by an admin
+    Manual,
+    /// Mirrored from the OIDC groups claim; replaced on every OIDC login
+    Oidc,
+    /// Mirrored from the LDAP groups attribute; replaced on every LDAP login
+    Ldap,
+}
+
+impl MembershipSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MembershipSource::Manual => "manual",
+            MembershipSource::Oidc => "oidc",
+            MembershipSource::Ldap => "ldap",
+        }
+    }
+}
+
+/// Defines persistence operations for user groups and their memberships.
+pub trait GroupRepositoryPort: Send + Sync + 'static {
+    /// Fails with `AlreadyExists` when a group of the same name (ignoring
+    /// case) exists.
+    async fn create_group(&self, group: &Group) -> Result<Group>;
+
+    async fn get_group(&self, id: Uuid) -> Result<Group>;
+
+    /// Groups ordered by name, each with its member count, and the total.
+    async fn list_groups(&self, offset: i64, limit: i64) -> Result<(Vec<(Group, u64)>, u64)>;
+
+    async fn update_group(&self, group: &Group) -> Result<Group>;
+
+    async fn delete_group(&self, id: Uuid) -> Result<()>;
+
+    async fn member_count(&self, group_id: Uuid) -> Result<u64>;
+
+    async fn list_members(&self, group_id: Uuid) -> Result<Vec<GroupMemberDto>>;
+
+    /// Adds a member; an existing membership is kept as it is.
+    async fn add_member(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        source: MembershipSource,
+    ) -> Result<()>;
+
+    /// Returns `false` when the user was not a member.
+    async fn remove_member(&self, group_id: Uuid, user_id: Uuid) -> Result<bool>;
+
+    /// Groups `user_id` belongs to, ordered by name.
+    async fn groups_of_user(&self, user_id: Uuid) -> Result<Vec<Group>>;
+
+    /// Makes the user's memberships from `source` exactly `group_names`,
+    /// creating the groups that do not exist yet.  Memberships from other
+    /// sources are untouched.
+    async fn sync_memberships(
+        &self,
+        user_id: Uuid,
+        source: MembershipSource,
+        group_names: &[String],
+    ) -> Result<()>;
+
+    /// Raises the quota of each given user to the largest default quota
+    /// among their groups, when lower.  Unlimited quotas (0) are left
+    /// alone.  Returns the number of users raised.
+    async fn apply_quota_defaults(&self, user_ids: &[Uuid]) -> Result<u64>;
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::application::ports::activity_ports::ActivityAction;
+use crate::common::errors::Result;
+
+/// What changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveEventKind {
+    FileCreated,
+    FileUpdated,
+    FileRenamed,
+    FileMoved,
+    FileTrashed,
+    FileRestored,
+    FileDeleted,
+    FolderCreated,
+    FolderRenamed,
+    FolderMoved,
+    FolderTrashed,
+    FolderRestored,
+    FolderDeleted,
+    ShareCreated,
+    ShareUpdated,
+    ShareDeleted,
+    NotificationCreated,
+}
+
+impl LiveEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FileCreated => "file_created",
+            Self::FileUpdated => "file_updated",
+            Self::FileRenamed => "file_renamed",
+            Self::FileMoved => "file_moved",
+            Self::FileTrashed => "file_trashed",
+            Self::FileRestored => "file_restored",
+            Self::FileDeleted => "file_deleted",
+            Self::FolderCreated => "folder_created",
+            Self::FolderRenamed => "folder_renamed",
+            Self::FolderMoved => "folder_moved",
+            Self::FolderTrashed => "folder_trashed",
+            Self::FolderRestored => "folder_restored",
+            Self::FolderDeleted => "folder_deleted",
+            Self::ShareCreated => "share_created",
+            Self::ShareUpdated => "share_updated",
+            Self::ShareDeleted => "share_deleted",
+            Self::NotificationCreated => "notification_created",
+        }
+    }
+
+    /// The change an activity log action records, if it is one.
+    pub fn from_activity(action: ActivityAction) -> Option<Self> {
+        Some(match action {
+            ActivityAction::FileCreated => Self::FileCreated,
+            ActivityAction::FileUpdated => Self::FileUpdated,
+            ActivityAction::FileRenamed => Self::FileRenamed,
+            ActivityAction::FileMoved => Self::FileMoved,
+            ActivityAction::FileTrashed => Self::FileTrashed,
+            ActivityAction::FileRestored => Self::FileRestored,
+            ActivityAction::FileDeleted => Self::FileDeleted,
+            ActivityAction::FolderCreated => Self::FolderCreated,
+            ActivityAction::FolderRenamed => Self::FolderRenamed,
+            ActivityAction::FolderMoved => Self::FolderMoved,
+            ActivityAction::FolderTrashed => Self::FolderTrashed,
+            ActivityAction::FolderRestored => Self::FolderRestored,
+            ActivityAction::FolderDeleted => Self::FolderDeleted,
+            ActivityAction::ShareCreated => Self::ShareCreated,
+            ActivityAction::ShareAccessed
+            | ActivityAction::Login
+            | ActivityAction::Logout
+            | ActivityAction::LoginFailed => return None,
+        })
+    }
+
+    /// Kind of item that changed: `file`, `folder`, `share` or
+    /// `notification`.
+    pub fn item_type(&self) -> &'static str {
+        match self {
+            Self::FileCreated
+            | Self::FileUpdated
+            | Self::FileRenamed
+            | Self::FileMoved
+            | Self::FileTrashed
+            | Self::FileRestored
+            | Self::FileDeleted => "file",
+            Self::FolderCreated
+            | Self::FolderRenamed
+            | Self::FolderMoved
+            | Self::FolderTrashed
+            | Self::FolderRestored
+            | Self::FolderDeleted => "folder",
+            Self::ShareCreated | Self::ShareUpdated | Self::ShareDeleted => "share",
+            Self::NotificationCreated => "notification",
+        }
+    }
+}
+
+/// A change as subscribers see it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveChange {
+    pub kind: LiveEventKind,
+    /// Id of the file, folder, share or notification
+    pub item_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Folder the file or folder is in after the change; `None` at the root
+    /// of a user's tree and for other items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<String>,
+    /// Folder a moved file or folder was in before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_folder_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl LiveChange {
+    pub fn new(kind: LiveEventKind, item_id: impl Into<String>) -> Self {
+        Self {
+            kind,
+            item_id: item_id.into(),
+            name: None,
+            folder_id: None,
+            previous_folder_id: None,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_folder(mut self, folder_id: Option<&str>) -> Self {
+        self.folder_id = folder_id.map(str::to_string);
+        self
+    }
+
+    pub fn with_previous_folder(mut self, folder_id: Option<&str>) -> Self {
+        self.previous_folder_id = folder_id.map(str::to_string);
+        self
+    }
+}
+
+/// A change and the users it is delivered to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveEvent {
+    pub users: Vec<Uuid>,
+    pub change: LiveChange,
+}
+
+/// Carries changes to the other instances sharing the database, which
+/// deliver them to their own subscribers.
+pub trait LiveEventRelayPort: Send + Sync + 'static {
+    async fn publish(&self, event: &LiveEvent) -> Result<()>;
+}
@@ -0,0 +1,14 @@
+use crate::common::errors::Result;
+
+/// A plain-text message to a single recipient.
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Outbound mail delivery.
+pub trait MailPort: Send + Sync + 'static {
+    async fn send(&self, message: MailMessage) -> Result<()>;
+}
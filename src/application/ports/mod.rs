@@ -1,22 +1,37 @@
+pub mod account_token_ports;
+pub mod activity_ports;
 pub mod auth_ports;
 pub mod blob_lifecycle;
 pub mod blob_storage_ports;
 pub mod cache_ports;
 pub mod calendar_ports;
+pub mod calendar_scheduling_ports;
 pub mod carddav_ports;
 pub mod chunked_upload_ports;
 pub mod compression_ports;
+pub mod dead_property_ports;
 pub mod dedup_ports;
+pub mod e2ee_ports;
 pub mod favorites_ports;
 pub mod file_lifecycle;
 pub mod file_ports;
+pub mod file_version_ports;
+pub mod group_ports;
 pub mod inbound;
+pub mod live_event_ports;
+pub mod mail_ports;
 pub mod music_ports;
+pub mod notification_ports;
 pub mod outbound;
 pub mod recent_ports;
 pub mod share_ports;
+pub mod snapshot_ports;
 pub mod storage_ports;
 pub mod thumbnail_ports;
 pub mod transcode_ports;
 pub mod trash_ports;
+pub mod two_factor_ports;
+pub mod user_share_ports;
+pub mod webauthn_ports;
+pub mod webhook_ports;
 pub mod zip_ports;
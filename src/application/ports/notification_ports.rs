@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::common::errors::Result;
+
+/// What a notification is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// A file or folder was shared with the user
+    ShareReceived,
+    /// The user's storage is nearly full
+    QuotaWarning,
+    /// A shared link the user created expires soon
+    ShareExpiring,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 3] =
+        [Self::ShareReceived, Self::QuotaWarning, Self::ShareExpiring];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ShareReceived => "share_received",
+            Self::QuotaWarning => "quota_warning",
+            Self::ShareExpiring => "share_expiring",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+
+    /// Nextcloud app the notification is attributed to; clients pick the
+    /// icon from it.
+    pub fn app(&self) -> &'static str {
+        match self {
+            Self::ShareReceived | Self::ShareExpiring => "files_sharing",
+            Self::QuotaWarning => "files",
+        }
+    }
+}
+
+/// A notification to deliver.
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub user_id: Uuid,
+    pub kind: NotificationKind,
+    pub subject: String,
+    pub message: String,
+    /// Link relative to the base URL, e.g. `/#/shared`
+    pub link: Option<String>,
+    pub object_type: &'static str,
+    pub object_id: String,
+}
+
+/// A stored notification.
+#[derive(Debug, Clone)]
+pub struct NotificationRecord {
+    pub id: i64,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub subject: String,
+    pub message: String,
+    pub link: Option<String>,
+    pub object_type: String,
+    pub object_id: String,
+    pub is_read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Storage use of a user with a quota.
+#[derive(Debug, Clone)]
+pub struct QuotaUsage {
+    pub user_id: Uuid,
+    pub used_bytes: i64,
+    pub quota_bytes: i64,
+}
+
+/// A shared link with an expiry date.
+#[derive(Debug, Clone)]
+pub struct ExpiringLink {
+    pub share_id: String,
+    pub owner_id: Uuid,
+    pub item_name: String,
+    /// Unix seconds
+    pub expires_at: i64,
+}
+
+/// Defines persistence operations for notifications.
+pub trait NotificationRepositoryPort: Send + Sync + 'static {
+    async fn create(&self, notification: &NewNotification) -> Result<()>;
+
+    /// Creates the notification unless the user was notified under `key`
+    /// before, and remembers the key.  Returns whether it was created.
+    async fn create_once(&self, notification: &NewNotification, key: &str) -> Result<bool>;
+
+    /// Forgets `key` for users whose storage use is below `percent` of
+    /// their quota, so a later rise notifies them again.
+    async fn forget_quota_key(&self, key: &str, percent: u8) -> Result<u64>;
+
+    /// The user's notifications, newest first, at most `limit`, and how
+    /// many of all of them are unread.
+    async fn list(&self, user_id: Uuid, limit: usize) -> Result<(Vec<NotificationRecord>, usize)>;
+
+    async fn get(&self, user_id: Uuid, id: i64) -> Result<Option<NotificationRecord>>;
+
+    /// Marks one notification, or with `None` all of them, as read.
+    async fn mark_read(&self, user_id: Uuid, id: Option<i64>) -> Result<u64>;
+
+    /// Deletes one notification, or with `None` all of them.
+    async fn delete(&self, user_id: Uuid, id: Option<i64>) -> Result<u64>;
+
+    /// Deletes the notifications about an object, e.g. a removed share.
+    async fn delete_for_object(&self, object_type: &str, object_id: &str) -> Result<u64>;
+
+    /// Users with a quota using at least `percent` of it.
+    async fn users_near_quota(&self, percent: u8) -> Result<Vec<QuotaUsage>>;
+
+    /// Shared links expiring after `after` and up to `until` (Unix seconds).
+    async fn links_expiring(&self, after: i64, until: i64) -> Result<Vec<ExpiringLink>>;
+
+    /// Deletes notifications and remembered keys older than `before`.
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64>;
+}
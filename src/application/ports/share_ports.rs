@@ -1,14 +1,26 @@
+use std::collections::HashSet;
+
 use uuid::Uuid;
 
 use crate::{
     application::dtos::{
         pagination::PaginatedResponseDto,
-        share_dto::{CreateShareDto, ShareDto, UpdateShareDto},
+        share_dto::{CreateShareDto, ShareAccessDto, ShareDto, UpdateShareDto},
     },
     common::errors::DomainError,
     domain::entities::share::ShareItemType,
 };
 
+/// A download through a share link, about to be recorded.
+#[derive(Debug, Clone, Default)]
+pub struct ShareDownload {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// File served; `None` for ZIP archives
+    pub file_id: Option<String>,
+    pub file_name: Option<String>,
+}
+
 pub trait ShareUseCase: Send + Sync + 'static {
     /// Create a new shared link for a file or folder
     async fn create_shared_link(
@@ -61,6 +73,23 @@ pub trait ShareUseCase: Send + Sync + 'static {
 
     /// Register an access to a shared link
     async fn register_shared_link_access(&self, token: &str) -> Result<(), DomainError>;
+
+    /// Count and log a download through a shared link.  Fails as an
+    /// expired share once the link's download limit has been reached.
+    async fn record_shared_link_download(
+        &self,
+        token: &str,
+        download: ShareDownload,
+    ) -> Result<(), DomainError>;
+
+    /// Downloads through a shared link, newest first (ownership-verified)
+    async fn get_shared_link_accesses(
+        &self,
+        id: Uuid,
+        requester_id: Uuid,
+        page: usize,
+        per_page: usize,
+    ) -> Result<PaginatedResponseDto<ShareAccessDto>, DomainError>;
 }
 
 pub trait ShareStoragePort: Send + Sync + 'static {
@@ -105,4 +134,27 @@ pub trait ShareStoragePort: Send + Sync + 'static {
         offset: usize,
         limit: usize,
     ) -> Result<(Vec<crate::domain::entities::share::Share>, usize), DomainError>;
+
+    /// Atomically counts a download against the share's limit and logs it.
+    /// Returns `false`, recording nothing, when the limit is already reached.
+    async fn record_download(
+        &self,
+        share_id: Uuid,
+        download: &ShareDownload,
+    ) -> Result<bool, DomainError>;
+
+    /// Logged downloads of a share, newest first, with the total count.
+    async fn find_share_accesses(
+        &self,
+        share_id: Uuid,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<ShareAccessDto>, usize), DomainError>;
+
+    /// Ids among `item_ids` with an unexpired link created by `user_id`.
+    async fn find_shared_item_ids(
+        &self,
+        user_id: Uuid,
+        item_ids: &[String],
+    ) -> Result<HashSet<String>, DomainError>;
 }
@@ -0,0 +1,169 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::dtos::snapshot_dto::{
+    CreateSnapshotDto, RestoreSnapshotDto, RestoreSnapshotResultDto, SetSnapshotScheduleDto,
+    SnapshotDto, SnapshotEntryDto, SnapshotScheduleDto,
+};
+use crate::application::ports::blob_storage_ports::BlobStream;
+use crate::common::errors::Result;
+
+/// Defines operations on named, read-only folder snapshots.
+pub trait SnapshotUseCase: Send + Sync {
+    /// Takes a snapshot of a folder tree owned by `user_id`.
+    async fn create_snapshot(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        dto: CreateSnapshotDto,
+    ) -> Result<SnapshotDto>;
+
+    /// Lists the user's snapshots, newest first, optionally for one folder.
+    async fn list_snapshots(
+        &self,
+        user_id: Uuid,
+        folder_id: Option<&str>,
+    ) -> Result<Vec<SnapshotDto>>;
+
+    /// Gets a single snapshot.
+    async fn get_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<SnapshotDto>;
+
+    /// Lists the entries directly below `path` (empty = snapshot root).
+    async fn browse(
+        &self,
+        snapshot_id: &str,
+        path: &str,
+        user_id: Uuid,
+    ) -> Result<Vec<SnapshotEntryDto>>;
+
+    /// Returns a file entry together with a stream of its content.
+    async fn get_file_content(
+        &self,
+        snapshot_id: &str,
+        path: &str,
+        user_id: Uuid,
+    ) -> Result<(SnapshotEntryDto, BlobStream)>;
+
+    /// Restores all or part of a snapshot as new folders/files.
+    async fn restore(
+        &self,
+        snapshot_id: &str,
+        user_id: Uuid,
+        dto: RestoreSnapshotDto,
+    ) -> Result<RestoreSnapshotResultDto>;
+
+    /// Deletes a snapshot and releases its blob references.
+    async fn delete_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<()>;
+
+    /// Gets the automatic snapshot schedule of a folder, if any.
+    async fn get_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+    ) -> Result<Option<SnapshotScheduleDto>>;
+
+    /// Creates or replaces the automatic snapshot schedule of a folder.
+    async fn set_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        dto: SetSnapshotScheduleDto,
+    ) -> Result<SnapshotScheduleDto>;
+
+    /// Removes the automatic snapshot schedule of a folder.
+    async fn delete_schedule(&self, folder_id: &str, user_id: Uuid) -> Result<()>;
+}
+
+// ─────────────────────────────────────────────────────
+// Outbound port — persistence abstraction
+// ─────────────────────────────────────────────────────
+
+/// Secondary (outbound) port for snapshot persistence.
+pub trait SnapshotRepositoryPort: Send + Sync + 'static {
+    /// Records the folder tree and holds a blob reference per file.
+    /// Returns the new snapshot ID.
+    async fn create_snapshot(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        name: &str,
+        description: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+        schedule_id: Option<&str>,
+    ) -> Result<String>;
+
+    async fn get_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<SnapshotDto>;
+
+    async fn list_snapshots(
+        &self,
+        user_id: Uuid,
+        folder_id: Option<&str>,
+    ) -> Result<Vec<SnapshotDto>>;
+
+    /// Gets the entry at `path` and its blob hash (None for folders).
+    async fn get_entry(
+        &self,
+        snapshot_id: &str,
+        path: &str,
+    ) -> Result<(SnapshotEntryDto, Option<String>)>;
+
+    /// Lists the children of the entry at `parent_path` (empty = root).
+    async fn list_children(
+        &self,
+        snapshot_id: &str,
+        parent_path: &str,
+    ) -> Result<Vec<SnapshotEntryDto>>;
+
+    /// Parent of the snapshot's source folder, if the folder still exists.
+    async fn source_parent_id(&self, snapshot_id: &str) -> Result<Option<String>>;
+
+    /// Total size of the files `restore` would materialise for `path`
+    /// (or the whole snapshot).
+    async fn restore_size(&self, snapshot_id: &str, path: Option<&str>) -> Result<u64>;
+
+    /// Materialises `path` (or the whole snapshot) under `target_parent_id`.
+    async fn restore(
+        &self,
+        snapshot_id: &str,
+        path: Option<&str>,
+        target_parent_id: Option<&str>,
+        dest_name: &str,
+    ) -> Result<RestoreSnapshotResultDto>;
+
+    /// Lists `(file_id, blob_hash, mime_type)` of the files a restore
+    /// created under `root_id` (or the restored file itself).
+    async fn list_restored_files(
+        &self,
+        root_id: &str,
+        root_is_folder: bool,
+    ) -> Result<Vec<(String, String, String)>>;
+
+    /// Deletes a snapshot. Returns the blob hash of every file entry
+    /// (one per entry, duplicates included) for orphan cleanup.
+    async fn delete_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<Vec<String>>;
+
+    /// Lists `(snapshot_id, user_id)` of snapshots past their expiry.
+    async fn list_expired(&self) -> Result<Vec<(String, Uuid)>>;
+
+    async fn get_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+    ) -> Result<Option<SnapshotScheduleDto>>;
+
+    async fn upsert_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        interval_hours: i32,
+        retention_days: Option<i32>,
+    ) -> Result<SnapshotScheduleDto>;
+
+    /// Returns `true` if a schedule was removed.
+    async fn delete_schedule(&self, folder_id: &str, user_id: Uuid) -> Result<bool>;
+
+    /// Lists `(user_id, schedule)` of schedules whose interval has elapsed.
+    async fn list_due_schedules(&self) -> Result<Vec<(Uuid, SnapshotScheduleDto)>>;
+
+    async fn mark_schedule_run(&self, schedule_id: &str) -> Result<()>;
+}
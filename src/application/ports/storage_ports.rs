@@ -309,13 +309,49 @@ pub trait FileWritePort: Send + Sync + 'static {
     /// Copies a file to a (possibly different) folder.
     ///
     /// With blob-dedup, this only creates a new metadata row and increments
-    /// the blob reference count — zero disk I/O for the content.
+    /// the blob reference count — zero disk I/O for the content.  The copy
+    /// belongs to the owner of the folder it lands in.
     async fn copy_file(
         &self,
         file_id: &str,
         target_folder_id: Option<String>,
     ) -> Result<File, DomainError>;
 
+    /// Replaces a file's content with an already-stored blob.
+    ///
+    /// Used to restore a previous version: no content is uploaded, the
+    /// file just takes a new reference on `blob_hash`. When version history
+    /// is enabled the replaced content is recorded as a version as well.
+    ///
+    /// Default: returns error (only PostgreSQL backend implements this).
+    async fn restore_blob_hash(
+        &self,
+        _file_id: &str,
+        _blob_hash: &str,
+        _size: u64,
+    ) -> Result<String, DomainError> {
+        Err(DomainError::internal_error(
+            "FileWritePort",
+            "restore_blob_hash not implemented for this storage backend",
+        ))
+    }
+
+    /// Sets a file's modification time (Unix seconds) without touching
+    /// its content — for clients that report the original mtime of an
+    /// upload.
+    ///
+    /// Default: returns error (only PostgreSQL backend implements this).
+    async fn set_modified_time(
+        &self,
+        _file_id: &str,
+        _modified_at: i64,
+    ) -> Result<(), DomainError> {
+        Err(DomainError::internal_error(
+            "FileWritePort",
+            "set_modified_time not implemented for this storage backend",
+        ))
+    }
+
     /// Copies an entire folder subtree atomically using ltree.
     ///
     /// Creates a copy of `source_folder_id` (with optional `dest_name`)
@@ -325,6 +361,9 @@ pub trait FileWritePort: Send + Sync + 'static {
     /// Uses a PL/pgSQL function: O(depth) folder INSERTs + 1 file batch
     /// + 1 ref_count batch.  Replaces the N+1 sequential copy pattern.
     ///
+    /// The copy belongs to the owner of `target_parent_id`, or to the source
+    /// owner when copying to the root.
+    ///
     /// Default: returns error (only PostgreSQL backend implements this).
     async fn copy_folder_tree(
         &self,
@@ -0,0 +1,49 @@
+use uuid::Uuid;
+
+use crate::common::errors::Result;
+
+/// A user's TOTP secret.
+#[derive(Debug, Clone)]
+pub struct TotpSecret {
+    /// Base32-encoded shared secret
+    pub secret: String,
+    /// Whether enrolment was completed; unconfirmed secrets are not
+    /// asked for on login
+    pub confirmed: bool,
+    /// Time step of the last accepted code
+    pub last_used_step: Option<i64>,
+}
+
+/// Defines persistence operations for two-factor authentication.
+pub trait TwoFactorRepositoryPort: Send + Sync + 'static {
+    async fn get_totp(&self, user_id: Uuid) -> Result<Option<TotpSecret>>;
+
+    /// Stores a new unconfirmed secret, replacing any unconfirmed one.
+    /// Fails with `AlreadyExists` when the user has a confirmed secret.
+    async fn set_pending_totp(&self, user_id: Uuid, secret: &str) -> Result<()>;
+
+    /// Confirms the pending secret at `step` and replaces the user's
+    /// recovery codes.
+    async fn confirm_totp(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<()>;
+
+    /// Records `step` as used.  Returns `false` when a code of the same or
+    /// a later step was accepted meanwhile.
+    async fn use_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool>;
+
+    /// Removes the secret and recovery codes.  Returns `false` when the
+    /// user had none.
+    async fn delete_totp(&self, user_id: Uuid) -> Result<bool>;
+
+    async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: &[String]) -> Result<()>;
+
+    /// Marks an unused recovery code as used.  Returns `false` when no
+    /// unused code has this hash.
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool>;
+
+    async fn remaining_recovery_codes(&self, user_id: Uuid) -> Result<u64>;
+}
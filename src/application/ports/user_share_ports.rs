@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::application::dtos::user_share_dto::{
+    CreateUserShareDto, UpdateUserShareDto, UserShareDto,
+};
+use crate::common::errors::Result;
+
+/// What a user may do with an item another user shared with them.
+///
+/// Read access is implied.  When several shares cover an item (a folder
+/// and one of its subfolders), the permissions are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedAccess {
+    /// Owner of the shared item and of everything below a shared folder
+    pub owner_id: Uuid,
+    pub can_write: bool,
+    pub can_reshare: bool,
+}
+
+/// An item shared with a user, as mounted in their home folder.
+#[derive(Debug, Clone)]
+pub struct SharedMount {
+    pub share_id: String,
+    pub mount_name: String,
+    pub item_id: String,
+    pub is_folder: bool,
+    pub access: SharedAccess,
+}
+
+/// A user a share can be addressed to.
+#[derive(Debug, Clone)]
+pub struct ShareRecipient {
+    pub id: Uuid,
+    pub username: String,
+}
+
+/// Fields of a share about to be created.
+#[derive(Debug, Clone)]
+pub struct NewUserShare {
+    pub item_id: String,
+    pub is_folder: bool,
+    pub owner_id: Uuid,
+    pub shared_by: Uuid,
+    pub recipient_id: Uuid,
+    pub can_write: bool,
+    pub can_reshare: bool,
+    pub mount_name: String,
+}
+
+/// Defines operations on files and folders shared directly with other users.
+pub trait UserShareUseCase: Send + Sync {
+    /// Shares an item the caller owns, or may reshare, with another user.
+    async fn share_with_user(
+        &self,
+        caller_id: Uuid,
+        dto: CreateUserShareDto,
+    ) -> Result<UserShareDto>;
+
+    /// Lists the shares the caller created or that cover items they own,
+    /// optionally restricted to one item.
+    async fn list_outgoing(
+        &self,
+        caller_id: Uuid,
+        item: Option<(&str, &str)>,
+    ) -> Result<Vec<UserShareDto>>;
+
+    /// Lists the items other users shared with the caller.
+    async fn list_shared_with_me(&self, caller_id: Uuid) -> Result<Vec<UserShareDto>>;
+
+    /// Changes permissions (owner or resharer) or the mount name (recipient).
+    async fn update_share(
+        &self,
+        share_id: &str,
+        caller_id: Uuid,
+        dto: UpdateUserShareDto,
+    ) -> Result<UserShareDto>;
+
+    /// Removes a share.  Recipients may remove shares they received.
+    async fn delete_share(&self, share_id: &str, caller_id: Uuid) -> Result<()>;
+}
+
+// ─────────────────────────────────────────────────────
+// Outbound port — persistence abstraction
+// ─────────────────────────────────────────────────────
+
+/// Secondary (outbound) port for user share persistence and access checks.
+pub trait UserShareRepositoryPort: Send + Sync + 'static {
+    async fn create_share(&self, share: &NewUserShare) -> Result<UserShareDto>;
+
+    async fn get_share(&self, share_id: &str) -> Result<UserShareDto>;
+
+    /// Shares created by `user_id` or covering items owned by `user_id`.
+    async fn list_outgoing(
+        &self,
+        user_id: Uuid,
+        item: Option<(&str, bool)>,
+    ) -> Result<Vec<UserShareDto>>;
+
+    /// Shares received by `user_id` whose item is not trashed.
+    async fn list_incoming(&self, user_id: Uuid) -> Result<Vec<UserShareDto>>;
+
+    async fn update_permissions(
+        &self,
+        share_id: &str,
+        can_write: bool,
+        can_reshare: bool,
+    ) -> Result<UserShareDto>;
+
+    async fn rename_mount(&self, share_id: &str, mount_name: &str) -> Result<UserShareDto>;
+
+    async fn delete_share(&self, share_id: &str) -> Result<()>;
+
+    /// Names already used in `recipient_id`'s home folder, by mounts or by
+    /// the recipient's own files and folders.
+    async fn mount_names(&self, recipient_id: Uuid) -> Result<Vec<String>>;
+
+    /// The share mounted as `mount_name` in `recipient_id`'s home folder.
+    async fn find_mount(&self, recipient_id: Uuid, mount_name: &str)
+    -> Result<Option<SharedMount>>;
+
+    /// All shares mounted in `recipient_id`'s home folder.
+    async fn list_mounts(&self, recipient_id: Uuid) -> Result<Vec<SharedMount>>;
+
+    /// Ids among `item_ids` with a share created by `user_id` or covering
+    /// items owned by `user_id`.
+    async fn shared_item_ids(&self, user_id: Uuid, item_ids: &[String]) -> Result<HashSet<String>>;
+
+    /// Access of `user_id` to a folder through shares of it or an ancestor.
+    async fn folder_access(&self, folder_id: &str, user_id: Uuid) -> Result<Option<SharedAccess>>;
+
+    /// Access of `user_id` to a file through a share of it or a folder
+    /// containing it.
+    async fn file_access(&self, file_id: &str, user_id: Uuid) -> Result<Option<SharedAccess>>;
+
+    /// Owner and name of a non-trashed file or folder.
+    async fn item_owner(&self, item_id: &str, is_folder: bool) -> Result<(Uuid, String)>;
+
+    /// Recipients of shares of an item, of the folder it is in or of
+    /// `folder_ids`, or of any folder above those.  Works for items that
+    /// are trashed or already deleted, as long as the folders remain.
+    async fn recipients(&self, item_id: &str, folder_ids: &[&str]) -> Result<Vec<Uuid>>;
+
+    /// Active user by username or email address.
+    async fn find_recipient(&self, username_or_email: &str) -> Result<Option<ShareRecipient>>;
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::common::errors::Result;
+
+/// A registered WebAuthn credential (passkey or security key).
+#[derive(Debug, Clone)]
+pub struct WebAuthnCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Credential ID chosen by the authenticator
+    pub credential_id: Vec<u8>,
+    /// COSE_Key encoding of the public key
+    pub public_key: Vec<u8>,
+    /// COSE algorithm identifier
+    pub algorithm: i64,
+    /// Signature counter of the last assertion
+    pub sign_count: u32,
+    pub name: String,
+    /// Transport hints passed back to the browser ("usb", "internal", ...)
+    pub transports: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Defines persistence operations for WebAuthn credentials.
+pub trait WebAuthnRepositoryPort: Send + Sync + 'static {
+    /// Stores a new credential.  Fails with `AlreadyExists` when the
+    /// credential ID is registered already.
+    async fn create_credential(&self, credential: &WebAuthnCredential) -> Result<()>;
+
+    async fn list_credentials(&self, user_id: Uuid) -> Result<Vec<WebAuthnCredential>>;
+
+    async fn count_credentials(&self, user_id: Uuid) -> Result<u64>;
+
+    async fn find_by_credential_id(
+        &self,
+        credential_id: &[u8],
+    ) -> Result<Option<WebAuthnCredential>>;
+
+    /// Records a successful assertion with its signature counter.
+    async fn record_use(&self, id: Uuid, sign_count: u32) -> Result<()>;
+
+    /// Removes a credential of the user.  Returns `false` when the user has
+    /// no credential with this ID.
+    async fn delete_credential(&self, user_id: Uuid, id: Uuid) -> Result<bool>;
+
+    /// Remembers that the session token family was opened with the
+    /// credential.
+    async fn link_session_family(&self, id: Uuid, family_id: Uuid) -> Result<()>;
+}
@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::common::errors::Result;
+
+/// Something that happened and is sent to matching webhooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    FileCreated,
+    FileUpdated,
+    FileDeleted,
+    ShareCreated,
+    ShareDeleted,
+    /// Test delivery requested by the webhook's owner
+    Ping,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 6] = [
+        Self::FileCreated,
+        Self::FileUpdated,
+        Self::FileDeleted,
+        Self::ShareCreated,
+        Self::ShareDeleted,
+        Self::Ping,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FileCreated => "file_created",
+            Self::FileUpdated => "file_updated",
+            Self::FileDeleted => "file_deleted",
+            Self::ShareCreated => "share_created",
+            Self::ShareDeleted => "share_deleted",
+            Self::Ping => "ping",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == s)
+    }
+}
+
+/// State of a delivery in the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Not delivered yet; more attempts follow
+    Pending,
+    Delivered,
+    /// Given up after the last attempt or a permanent error
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// A webhook subscription.
+#[derive(Debug, Clone)]
+pub struct WebhookRecord {
+    pub id: Uuid,
+    /// `None` for admin webhooks, which receive the events of all users
+    pub owner_id: Option<Uuid>,
+    pub url: String,
+    /// Key the payloads are signed with
+    pub secret: String,
+    pub description: String,
+    /// Events to send; empty for all
+    pub events: Vec<String>,
+    /// Only files at or below this path
+    pub path_prefix: Option<String>,
+    /// Only files of these MIME types; `image/*` matches a whole type
+    pub mime_types: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An entry of the delivery log.
+#[derive(Debug, Clone)]
+pub struct DeliveryRecord {
+    pub id: i64,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    /// HTTP status of the last attempt, if the receiver answered
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+/// Persistence of webhooks and their delivery log.
+pub trait WebhookRepositoryPort: Send + Sync + 'static {
+    async fn create(&self, webhook: &WebhookRecord) -> Result<()>;
+
+    /// Saves url, secret, description, filters and the active flag.
+    async fn update(&self, webhook: &WebhookRecord) -> Result<()>;
+
+    async fn get(&self, id: Uuid) -> Result<Option<WebhookRecord>>;
+
+    /// Webhooks of a user, or with `None` the admin webhooks.
+    async fn list(&self, owner_id: Option<Uuid>) -> Result<Vec<WebhookRecord>>;
+
+    async fn delete(&self, id: Uuid) -> Result<u64>;
+
+    /// Active webhooks that may receive an event about something owned by
+    /// `owner_id`: the owner's own and the admin webhooks.
+    async fn subscribers(&self, owner_id: Option<Uuid>) -> Result<Vec<WebhookRecord>>;
+
+    /// Logs a pending delivery and returns its id.
+    async fn create_delivery(
+        &self,
+        webhook_id: Uuid,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> Result<i64>;
+
+    /// Records the outcome of an attempt.
+    async fn record_attempt(
+        &self,
+        id: i64,
+        status: DeliveryStatus,
+        response_status: Option<u16>,
+        error: Option<&str>,
+    ) -> Result<()>;
+
+    /// Latest deliveries of a webhook, newest first.
+    async fn list_deliveries(&self, webhook_id: Uuid, limit: usize) -> Result<Vec<DeliveryRecord>>;
+
+    /// Owner and `file` object of the latest payload about a file, for
+    /// events about files that no longer exist.
+    async fn last_known_file(
+        &self,
+        file_id: &str,
+    ) -> Result<Option<(Option<Uuid>, serde_json::Value)>>;
+
+    /// Deliveries still pending that were created after `since`.
+    async fn pending_deliveries(&self, since: DateTime<Utc>) -> Result<Vec<DeliveryRecord>>;
+
+    /// Deletes deliveries older than `before`.
+    async fn prune_deliveries(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+/// A signed request to a webhook receiver.
+#[derive(Debug, Clone)]
+pub struct WebhookRequest {
+    pub url: String,
+    pub body: String,
+    pub headers: Vec<(&'static str, String)>,
+    /// Whether the receiver may live on a loopback or private address
+    pub allow_private_target: bool,
+}
+
+/// Outbound HTTP for webhook deliveries.
+pub trait WebhookSenderPort: Send + Sync + 'static {
+    /// Posts the request and returns the HTTP status of the answer.
+    ///
+    /// Fails with `AccessDenied` for a target that is not allowed and with
+    /// `InternalError` when the receiver could not be reached.
+    async fn send(&self, request: &WebhookRequest) -> Result<u16>;
+}
@@ -0,0 +1,360 @@
+//! Password reset and address verification by mail.
+//!
+//! Both flows mail a link with a signed, single-use token.  Requests never
+//! reveal whether an account exists: unknown addresses, SSO accounts and
+//! deactivated users get the same answer, they just receive no mail.  The
+//! mail itself is sent in the background so the response time does not
+//! tell either.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::application::ports::account_token_ports::{
+    AccountTokenPurpose, AccountTokenRepositoryPort,
+};
+use crate::application::ports::auth_ports::{
+    PasswordHasherPort, SessionStoragePort, UserStoragePort,
+};
+use crate::application::ports::mail_ports::{MailMessage, MailPort};
+use crate::common::config::MailConfig;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::entities::user::User;
+use crate::infrastructure::repositories::pg::{
+    AccountTokenPgRepository, SessionPgRepository, UserPgRepository,
+};
+use crate::infrastructure::services::account_token::{self, AccountToken};
+use crate::infrastructure::services::mail_transport::MailTransport;
+use crate::infrastructure::services::password_hasher::Argon2PasswordHasher;
+
+fn invalid_link() -> DomainError {
+    DomainError::new(
+        ErrorKind::InvalidInput,
+        "AccountRecovery",
+        "This link is invalid or has expired",
+    )
+}
+
+/// Link to the login page that handles `param`.
+fn action_link(base_url: &str, param: &str, token: &str) -> String {
+    format!(
+        "{}/login?{}={}",
+        base_url.trim_end_matches('/'),
+        param,
+        token
+    )
+}
+
+fn reset_mail(user: &User, link: &str, ttl_secs: i64) -> MailMessage {
+    MailMessage {
+        to: user.email().to_string(),
+        subject: "Reset your OxiCloud password".to_string(),
+        body: format!(
+            "Hello {},\n\n\
+             Someone asked to reset the password of your OxiCloud account. \
+             To choose a new password, open this link within {} minutes:\n\n\
+             {}\n\n\
+             If you did not ask for this, ignore this mail; your password stays unchanged.\n",
+            user.username(),
+            ttl_secs / 60,
+            link
+        ),
+    }
+}
+
+fn verification_mail(user: &User, email: &str, link: &str) -> MailMessage {
+    MailMessage {
+        to: email.to_string(),
+        subject: "Confirm your OxiCloud email address".to_string(),
+        body: format!(
+            "Hello {},\n\n\
+             Please confirm your email address to finish creating your OxiCloud account:\n\n\
+             {}\n\n\
+             If you did not sign up, ignore this mail.\n",
+            user.username(),
+            link
+        ),
+    }
+}
+
+/// Service for the mailed account recovery and verification flows.
+pub struct AccountRecoveryService {
+    user_storage: Arc<UserPgRepository>,
+    session_storage: Arc<SessionPgRepository>,
+    password_hasher: Arc<Argon2PasswordHasher>,
+    tokens: Arc<AccountTokenPgRepository>,
+    mailer: Arc<MailTransport>,
+    /// Secret the tokens are signed with (derived from the JWT secret)
+    token_secret: String,
+    /// Public URL the links point to
+    base_url: String,
+    config: MailConfig,
+}
+
+impl AccountRecoveryService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_storage: Arc<UserPgRepository>,
+        session_storage: Arc<SessionPgRepository>,
+        password_hasher: Arc<Argon2PasswordHasher>,
+        tokens: Arc<AccountTokenPgRepository>,
+        mailer: Arc<MailTransport>,
+        token_secret: String,
+        base_url: String,
+        config: MailConfig,
+    ) -> Self {
+        Self {
+            user_storage,
+            session_storage,
+            password_hasher,
+            tokens,
+            mailer,
+            token_secret,
+            base_url,
+            config,
+        }
+    }
+
+    /// Whether self-registered accounts must verify their address.
+    pub fn verification_required(&self) -> bool {
+        self.config.verify_registrations
+    }
+
+    /// Mails a reset link if `login` (a username or address) belongs to an
+    /// active local account.  Succeeds either way.
+    pub async fn request_password_reset(&self, login: &str) -> Result<()> {
+        let Some(user) = self.find_user(login).await else {
+            tracing::info!("Password reset requested for an unknown account");
+            return Ok(());
+        };
+        if user.is_oidc_user() || !user.is_active() {
+            tracing::info!(
+                "Password reset requested for {}, which cannot reset its password",
+                user.id()
+            );
+            return Ok(());
+        }
+
+        let ttl = self.config.reset_token_ttl_secs;
+        let token = self
+            .issue_token(&user, AccountTokenPurpose::PasswordReset, None, ttl)
+            .await?;
+        let link = action_link(&self.base_url, "reset_token", &token);
+        self.send_in_background(reset_mail(&user, &link, ttl));
+        tracing::info!("Password reset link sent to user {}", user.id());
+        Ok(())
+    }
+
+    /// Sets a new password with a reset token and signs the user out
+    /// everywhere.
+    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<()> {
+        let claims = account_token::verify(
+            &self.token_secret,
+            AccountTokenPurpose::PasswordReset,
+            token,
+        )
+        .ok_or_else(invalid_link)?;
+
+        // Checked before the token is used up, so a typo does not cost the link
+        if new_password.len() < 8 {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "User",
+                "Password must be at least 8 characters long",
+            ));
+        }
+
+        if !self
+            .tokens
+            .consume_token(
+                claims.token_id,
+                claims.user_id,
+                AccountTokenPurpose::PasswordReset,
+            )
+            .await?
+        {
+            return Err(invalid_link());
+        }
+
+        let mut user = self
+            .user_storage
+            .get_user_by_id(claims.user_id)
+            .await
+            .map_err(|_| invalid_link())?;
+        if user.is_oidc_user() || !user.is_active() {
+            return Err(invalid_link());
+        }
+
+        let hash = self.password_hasher.hash_password(new_password).await?;
+        user.update_password_hash(hash);
+        self.user_storage.update_user(user).await?;
+
+        self.session_storage
+            .revoke_all_user_sessions(claims.user_id)
+            .await?;
+        // Following a link mailed to the account proves the address works
+        self.tokens.set_email_verified(claims.user_id).await?;
+
+        tracing::info!("Password of user {} reset by mail", claims.user_id);
+        Ok(())
+    }
+
+    /// Marks the address of a newly registered user as unverified and
+    /// mails the verification link.
+    pub async fn start_email_verification(&self, user: &User) -> Result<()> {
+        self.tokens
+            .set_email_unverified(user.id(), user.email())
+            .await?;
+        self.send_verification(user, user.email()).await
+    }
+
+    /// Mails a new verification link if `login` belongs to an account
+    /// that awaits verification.  Succeeds either way.
+    pub async fn resend_verification(&self, login: &str) -> Result<()> {
+        let Some(user) = self.find_user(login).await else {
+            return Ok(());
+        };
+        match self.tokens.get_unverified_email(user.id()).await? {
+            Some(email) => self.send_verification(&user, &email).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Confirms an address with a verification token.
+    pub async fn verify_email(&self, token: &str) -> Result<()> {
+        let claims = account_token::verify(
+            &self.token_secret,
+            AccountTokenPurpose::EmailVerification,
+            token,
+        )
+        .ok_or_else(invalid_link)?;
+
+        let pending = self.tokens.get_unverified_email(claims.user_id).await?;
+        // A link to an address the user has since replaced is worthless
+        if pending.is_some() && pending != claims.email {
+            return Err(invalid_link());
+        }
+        if !self
+            .tokens
+            .consume_token(
+                claims.token_id,
+                claims.user_id,
+                AccountTokenPurpose::EmailVerification,
+            )
+            .await?
+        {
+            return Err(invalid_link());
+        }
+
+        self.tokens.set_email_verified(claims.user_id).await?;
+        tracing::info!("Email address of user {} verified", claims.user_id);
+        Ok(())
+    }
+
+    /// Whether the user registered and has not verified the address yet.
+    pub async fn is_email_unverified(&self, user_id: Uuid) -> Result<bool> {
+        Ok(self.tokens.get_unverified_email(user_id).await?.is_some())
+    }
+
+    async fn send_verification(&self, user: &User, email: &str) -> Result<()> {
+        let token = self
+            .issue_token(
+                user,
+                AccountTokenPurpose::EmailVerification,
+                Some(email.to_string()),
+                self.config.verification_token_ttl_secs,
+            )
+            .await?;
+        let link = action_link(&self.base_url, "verify_token", &token);
+        self.send_in_background(verification_mail(user, email, &link));
+        tracing::info!("Verification link sent to user {}", user.id());
+        Ok(())
+    }
+
+    /// Looks a user up by address when `login` contains an `@`, by
+    /// username otherwise.
+    async fn find_user(&self, login: &str) -> Option<User> {
+        let login = login.trim();
+        if login.is_empty() {
+            return None;
+        }
+        let user = if login.contains('@') {
+            self.user_storage.get_user_by_email(login).await
+        } else {
+            self.user_storage.get_user_by_username(login).await
+        };
+        user.ok()
+    }
+
+    async fn issue_token(
+        &self,
+        user: &User,
+        purpose: AccountTokenPurpose,
+        email: Option<String>,
+        ttl_secs: i64,
+    ) -> Result<String> {
+        let token = AccountToken {
+            user_id: user.id(),
+            token_id: Uuid::new_v4(),
+            email,
+        };
+        self.tokens
+            .create_token(
+                token.token_id,
+                token.user_id,
+                purpose,
+                Utc::now() + Duration::seconds(ttl_secs),
+            )
+            .await?;
+        account_token::issue(&self.token_secret, purpose, &token, ttl_secs)
+    }
+
+    fn send_in_background(&self, message: MailMessage) {
+        let mailer = self.mailer.clone();
+        tokio::spawn(async move {
+            let to = message.to.clone();
+            if let Err(e) = mailer.send(message).await {
+                tracing::error!("Could not send mail to {}: {}", to, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::user::UserRole;
+
+    fn user() -> User {
+        User::new(
+            "alice".to_string(),
+            "alice@example.com".to_string(),
+            "hash".to_string(),
+            UserRole::User,
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn action_link_points_to_the_login_page() {
+        assert_eq!(
+            action_link("https://cloud.example.com/", "reset_token", "a.b.c"),
+            "https://cloud.example.com/login?reset_token=a.b.c"
+        );
+    }
+
+    #[test]
+    fn mails_go_to_the_right_address_and_carry_the_link() {
+        let link = "https://cloud.example.com/login?reset_token=a.b.c";
+        let reset = reset_mail(&user(), link, 3600);
+        assert_eq!(reset.to, "alice@example.com");
+        assert!(reset.body.contains(link));
+        assert!(reset.body.contains("60 minutes"));
+
+        let verify = verification_mail(&user(), "new@example.com", link);
+        assert_eq!(verify.to, "new@example.com");
+        assert!(verify.body.contains(link));
+    }
+}
@@ -0,0 +1,374 @@
+//! Activity log: records file, folder, share and sign-in events and serves
+//! them as a per-user feed and an admin-wide audit view.
+//!
+//! Recording never fails the operation it describes: entries are written
+//! in the background and errors only reach the log.  File uploads and
+//! overwrites arrive through the file lifecycle hooks, which do not know
+//! who uploaded, so those entries name the file's owner as the actor.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::application::dtos::activity_dto::{ActivityDto, ActivityQueryDto};
+use crate::application::dtos::pagination::PaginatedResponseDto;
+use crate::application::ports::activity_ports::{
+    ActivityAction, ActivityFilter, ActivityRepositoryPort, NewActivity,
+};
+use crate::application::ports::file_lifecycle::{
+    FileCreatedHook, FileDeletedHook, FileUpdatedHook,
+};
+use crate::application::ports::storage_ports::FileReadPort;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::infrastructure::repositories::pg::ActivityPgRepository;
+use crate::infrastructure::repositories::pg::file_blob_read_repository::FileBlobReadRepository;
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+/// Most entries a single export returns
+pub const EXPORT_LIMIT: usize = 100_000;
+
+fn invalid_query(message: impl Into<String>) -> DomainError {
+    DomainError::new(ErrorKind::InvalidInput, "Activity", message)
+}
+
+/// Parses a comma-separated list of actions and item types into actions.
+fn parse_actions(spec: &str) -> Result<Vec<ActivityAction>> {
+    let mut actions = Vec::new();
+    for token in spec.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let matched: Vec<ActivityAction> = match ActivityAction::parse(token) {
+            Some(action) => vec![action],
+            None => ActivityAction::ALL
+                .into_iter()
+                .filter(|a| a.item_type() == token)
+                .collect(),
+        };
+        if matched.is_empty() {
+            return Err(invalid_query(format!("Unknown activity type '{}'", token)));
+        }
+        for action in matched {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+    }
+    Ok(actions)
+}
+
+/// Parses an RFC 3339 timestamp or a `YYYY-MM-DD` date.  A date stands for
+/// the start of the day, or with `end_of_day` for the start of the next.
+fn parse_time(value: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| invalid_query(format!("Invalid date '{}'", value)))?;
+    let start = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    Ok(if end_of_day {
+        start + Duration::days(1)
+    } else {
+        start
+    })
+}
+
+/// Quotes a CSV cell when needed and defuses cells a spreadsheet would
+/// run as a formula.
+fn csv_cell(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Renders entries as CSV with a header row.
+pub fn to_csv(entries: &[ActivityDto]) -> String {
+    let mut out = String::from(
+        "id,occurred_at,action,actor_id,actor_name,owner_id,owner_name,\
+         item_type,item_id,item_name,ip_address,details\n",
+    );
+    for entry in entries {
+        let details = entry.details.to_string();
+        let cells = [
+            entry.id.to_string(),
+            entry.occurred_at.to_rfc3339(),
+            entry.action.clone(),
+            entry.actor_id.clone().unwrap_or_default(),
+            entry.actor_name.clone().unwrap_or_default(),
+            entry.owner_id.clone().unwrap_or_default(),
+            entry.owner_name.clone().unwrap_or_default(),
+            entry.item_type.clone().unwrap_or_default(),
+            entry.item_id.clone().unwrap_or_default(),
+            entry.item_name.clone().unwrap_or_default(),
+            entry.ip_address.clone().unwrap_or_default(),
+            details,
+        ];
+        let line: Vec<String> = cells.iter().map(|c| csv_cell(c)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Service for recording and querying the activity log.
+pub struct ActivityService {
+    repository: Arc<ActivityPgRepository>,
+    /// Resolves owner and name of files reported by the lifecycle hooks
+    file_read: Arc<FileBlobReadRepository>,
+}
+
+impl ActivityService {
+    pub fn new(
+        repository: Arc<ActivityPgRepository>,
+        file_read: Arc<FileBlobReadRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            file_read,
+        }
+    }
+
+    /// Records an entry in the background.
+    pub fn record(&self, activity: NewActivity) {
+        let repository = self.repository.clone();
+        tokio::spawn(async move {
+            Self::store(&repository, activity).await;
+        });
+    }
+
+    async fn store(repository: &ActivityPgRepository, activity: NewActivity) {
+        if let Err(e) = repository.record(&activity).await {
+            tracing::warn!(
+                "Could not record activity {}: {}",
+                activity.action.as_str(),
+                e
+            );
+        }
+    }
+
+    /// Entries where the user acted or whose items were affected.
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        query: &ActivityQueryDto,
+    ) -> Result<PaginatedResponseDto<ActivityDto>> {
+        let mut filter = Self::filter_from_query(query)?;
+        filter.user_id = Some(user_id);
+        self.list_page(&filter, query).await
+    }
+
+    /// Entries of all users, optionally narrowed to one with `user_id`.
+    pub async fn list_all(
+        &self,
+        query: &ActivityQueryDto,
+    ) -> Result<PaginatedResponseDto<ActivityDto>> {
+        let filter = Self::admin_filter(query)?;
+        self.list_page(&filter, query).await
+    }
+
+    /// Entries of all users matching the query, newest first, up to
+    /// [`EXPORT_LIMIT`].
+    pub async fn export(&self, query: &ActivityQueryDto) -> Result<Vec<ActivityDto>> {
+        let filter = Self::admin_filter(query)?;
+        let (records, _) = self.repository.list(&filter, 0, EXPORT_LIMIT).await?;
+        Ok(records.into_iter().map(ActivityDto::from).collect())
+    }
+
+    async fn list_page(
+        &self,
+        filter: &ActivityFilter,
+        query: &ActivityQueryDto,
+    ) -> Result<PaginatedResponseDto<ActivityDto>> {
+        let page = query.page.unwrap_or(0);
+        let page_size = query
+            .page_size
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let (records, total) = self
+            .repository
+            .list(filter, page * page_size, page_size)
+            .await?;
+        let items = records.into_iter().map(ActivityDto::from).collect();
+        Ok(PaginatedResponseDto::new(items, page, page_size, total))
+    }
+
+    fn admin_filter(query: &ActivityQueryDto) -> Result<ActivityFilter> {
+        let mut filter = Self::filter_from_query(query)?;
+        if let Some(user_id) = query.user_id.as_deref().filter(|s| !s.is_empty()) {
+            filter.user_id = Some(
+                Uuid::parse_str(user_id)
+                    .map_err(|_| invalid_query(format!("Invalid user ID '{}'", user_id)))?,
+            );
+        }
+        Ok(filter)
+    }
+
+    fn filter_from_query(query: &ActivityQueryDto) -> Result<ActivityFilter> {
+        let non_empty = |v: &Option<String>| v.clone().filter(|s| !s.trim().is_empty());
+        Ok(ActivityFilter {
+            user_id: None,
+            actions: match non_empty(&query.action) {
+                Some(spec) => parse_actions(&spec)?,
+                None => Vec::new(),
+            },
+            item_id: non_empty(&query.item_id),
+            since: non_empty(&query.from)
+                .map(|v| parse_time(v.trim(), false))
+                .transpose()?,
+            until: non_empty(&query.to)
+                .map(|v| parse_time(v.trim(), true))
+                .transpose()?,
+        })
+    }
+
+    /// Records a hook-reported file event, naming the file's owner.
+    fn record_file_event(&self, action: ActivityAction, file_id: &str) {
+        let repository = self.repository.clone();
+        let file_read = self.file_read.clone();
+        let file_id = file_id.to_string();
+        tokio::spawn(async move {
+            let mut activity = NewActivity::new(action);
+            match file_read.get_file(&file_id).await {
+                Ok(file) => {
+                    activity = activity.with_item(file.id(), file.name());
+                    if let Some(owner_id) = file.owner_id() {
+                        activity = activity.with_actor(owner_id).with_owner(owner_id);
+                    }
+                }
+                Err(e) => {
+                    tracing::debug!("Activity: file {} not found: {}", file_id, e);
+                    activity.item_id = Some(file_id);
+                }
+            }
+            Self::store(&repository, activity).await;
+        });
+    }
+
+    /// Records the deletion of a file.  The file is gone by now, so its
+    /// owner and name come from earlier entries about it.
+    fn record_deleted_file(&self, file_id: &str) {
+        let repository = self.repository.clone();
+        let file_id = file_id.to_string();
+        tokio::spawn(async move {
+            let mut activity = NewActivity::new(ActivityAction::FileDeleted);
+            match repository.last_known_item(&file_id).await {
+                Ok(Some((owner_id, name))) => {
+                    activity = activity.with_item(file_id, name);
+                    if let Some(owner_id) = owner_id {
+                        activity = activity.with_actor(owner_id).with_owner(owner_id);
+                    }
+                }
+                _ => activity.item_id = Some(file_id),
+            }
+            Self::store(&repository, activity).await;
+        });
+    }
+}
+
+impl FileCreatedHook for ActivityService {
+    fn on_file_created<'a>(
+        &'a self,
+        file_id: &'a str,
+        _blob_hash: &'a str,
+        _content_type: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move { self.record_file_event(ActivityAction::FileCreated, file_id) })
+    }
+}
+
+impl FileUpdatedHook for ActivityService {
+    fn on_file_updated<'a>(
+        &'a self,
+        file_id: &'a str,
+        _blob_hash: &'a str,
+        _content_type: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move { self.record_file_event(ActivityAction::FileUpdated, file_id) })
+    }
+}
+
+impl FileDeletedHook for ActivityService {
+    fn on_file_deleted<'a>(
+        &'a self,
+        file_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move { self.record_deleted_file(file_id) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_accept_actions_item_types_and_dates() {
+        let query = ActivityQueryDto {
+            action: Some("share, file_renamed,login_failed".to_string()),
+            from: Some("2026-10-01".to_string()),
+            to: Some("2026-10-02".to_string()),
+            ..Default::default()
+        };
+        let filter = ActivityService::filter_from_query(&query).unwrap();
+        assert_eq!(
+            filter.actions,
+            vec![
+                ActivityAction::ShareCreated,
+                ActivityAction::ShareAccessed,
+                ActivityAction::FileRenamed,
+                ActivityAction::LoginFailed,
+            ]
+        );
+        assert_eq!(
+            filter.since.unwrap().to_rfc3339(),
+            "2026-10-01T00:00:00+00:00"
+        );
+        // A date as upper bound includes the whole day
+        assert_eq!(
+            filter.until.unwrap().to_rfc3339(),
+            "2026-10-03T00:00:00+00:00"
+        );
+
+        let bad = |q: ActivityQueryDto| ActivityService::filter_from_query(&q).is_err();
+        assert!(bad(ActivityQueryDto {
+            action: Some("file_exploded".to_string()),
+            ..Default::default()
+        }));
+        assert!(bad(ActivityQueryDto {
+            from: Some("yesterday".to_string()),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn csv_quotes_cells_and_defuses_formulas() {
+        let entry = ActivityDto {
+            id: 7,
+            occurred_at: DateTime::parse_from_rfc3339("2026-10-18T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            action: "file_renamed".to_string(),
+            actor_id: None,
+            actor_name: Some("alice".to_string()),
+            owner_id: None,
+            owner_name: None,
+            item_type: Some("file".to_string()),
+            item_id: Some("f1".to_string()),
+            item_name: Some("=HYPERLINK(\"x\")".to_string()),
+            ip_address: None,
+            details: serde_json::json!({"old_name": "a,b.txt"}),
+        };
+        let csv = to_csv(&[entry]);
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("7,2026-10-18T12:00:00+00:00,file_renamed,,alice,"));
+        assert!(row.contains(",\"'=HYPERLINK(\"\"x\"\")\","));
+        assert!(row.ends_with(",\"{\"\"old_name\"\":\"\"a,b.txt\"\"}\""));
+    }
+}
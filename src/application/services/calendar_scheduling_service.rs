@@ -0,0 +1,475 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::application::dtos::calendar_dto::{
+    CalendarDto, CalendarEventDto, CreateEventICalDto, FreeBusyResponseDto, ScheduleMessageDto,
+};
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::calendar_ports::CalendarStoragePort;
+use crate::application::ports::calendar_scheduling_ports::{
+    CalendarSchedulingUseCase, ScheduleInboxRepositoryPort,
+};
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::entities::user::User;
+use crate::domain::services::recurrence_service::RecurrenceSet;
+use crate::domain::services::scheduling_service::{
+    self, BusyPeriod, FreeBusyRequest, ItipMethod, SchedulingObject,
+};
+use crate::infrastructure::adapters::calendar_storage_adapter::CalendarStorageAdapter;
+use crate::infrastructure::repositories::pg::{ScheduleInboxPgRepository, UserPgRepository};
+
+/// Service for CalDAV scheduling between OxiCloud users (RFC 6638).
+///
+/// Attendee copies and organizer updates are written straight to storage,
+/// so they never trigger further scheduling.
+pub struct CalendarSchedulingService {
+    calendar_storage: Arc<CalendarStorageAdapter>,
+    inbox_repo: Arc<ScheduleInboxPgRepository>,
+    user_repo: Arc<UserPgRepository>,
+}
+
+impl CalendarSchedulingService {
+    pub fn new(
+        calendar_storage: Arc<CalendarStorageAdapter>,
+        inbox_repo: Arc<ScheduleInboxPgRepository>,
+        user_repo: Arc<UserPgRepository>,
+    ) -> Self {
+        Self {
+            calendar_storage,
+            inbox_repo,
+            user_repo,
+        }
+    }
+
+    /// The account with the given e-mail address, if any.
+    async fn find_user(&self, email: &str) -> Result<Option<User>> {
+        match self.user_repo.get_user_by_email(email).await {
+            Ok(user) => Ok(Some(user)),
+            Err(e) if e.kind == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn deliver(
+        &self,
+        recipient: &User,
+        method: ItipMethod,
+        sender_email: &str,
+        uid: &str,
+        ical_data: String,
+    ) -> Result<()> {
+        let message = ScheduleMessageDto {
+            id: Uuid::new_v4().to_string(),
+            ical_uid: uid.to_string(),
+            method: method.as_str().to_string(),
+            sender: scheduling_service::email_to_address(sender_email),
+            ical_data,
+            created_at: Utc::now(),
+        };
+        self.inbox_repo.deliver(recipient.id(), &message).await
+    }
+
+    /// A user's copy of an object: the event with this UID in one of the
+    /// calendars they own.
+    async fn find_copy(&self, owner_id: Uuid, uid: &str) -> Result<Option<CalendarEventDto>> {
+        for calendar in self
+            .calendar_storage
+            .list_calendars_by_owner(owner_id)
+            .await?
+        {
+            if let Some(event) = self
+                .calendar_storage
+                .find_event_by_uid(&calendar.id, uid)
+                .await?
+            {
+                return Ok(Some(event));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Sends the organizer's object to an attendee: a REQUEST in their inbox
+    /// and a copy in their first calendar.  An existing copy keeps the
+    /// attendee's answer unless the organizer bumped the SEQUENCE; an event
+    /// with the same UID from another organizer is left untouched.
+    async fn invite(
+        &self,
+        organizer_email: &str,
+        attendee: &User,
+        object: &SchedulingObject,
+        ical_data: &str,
+    ) -> Result<()> {
+        self.deliver(
+            attendee,
+            ItipMethod::Request,
+            organizer_email,
+            &object.uid,
+            scheduling_service::request_message(ical_data),
+        )
+        .await?;
+
+        let copy = scheduling_service::attendee_copy(ical_data);
+        match self.find_copy(attendee.id(), &object.uid).await? {
+            Some(existing)
+                if !scheduling_service::is_organized_by(&existing.ical_data, organizer_email) =>
+            {
+                // The UID is taken by an event someone else organizes, or
+                // by the attendee's own; leave it alone
+                warn!(
+                    "Not updating event {} of {}: organized by someone other than {}",
+                    existing.id,
+                    attendee.id(),
+                    organizer_email
+                );
+            }
+            Some(existing) => {
+                let answered = SchedulingObject::parse(&existing.ical_data)
+                    .is_some_and(|old| old.sequence >= object.sequence);
+                let copy = if answered {
+                    scheduling_service::apply_partstats(
+                        &copy,
+                        &existing.ical_data,
+                        attendee.email(),
+                    )
+                    .unwrap_or(copy)
+                } else {
+                    copy
+                };
+                self.calendar_storage
+                    .update_event_ical(&existing.id, copy)
+                    .await?;
+            }
+            None => {
+                // Attendees without a calendar only get the inbox message
+                let calendars = self
+                    .calendar_storage
+                    .list_calendars_by_owner(attendee.id())
+                    .await?;
+                if let Some(calendar) = calendars.first() {
+                    self.calendar_storage
+                        .create_event_from_ical(CreateEventICalDto {
+                            calendar_id: calendar.id.clone(),
+                            ical_data: copy,
+                        })
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a CANCEL to an attendee and marks their copy cancelled, if it
+    /// is a copy of this organizer's object.
+    async fn cancel(
+        &self,
+        organizer_email: &str,
+        attendee: &User,
+        uid: &str,
+        ical_data: &str,
+    ) -> Result<()> {
+        self.deliver(
+            attendee,
+            ItipMethod::Cancel,
+            organizer_email,
+            uid,
+            scheduling_service::cancel_message(ical_data),
+        )
+        .await?;
+
+        if let Some(existing) = self.find_copy(attendee.id(), uid).await?
+            && scheduling_service::is_organized_by(&existing.ical_data, organizer_email)
+        {
+            self.calendar_storage
+                .update_event_ical(
+                    &existing.id,
+                    scheduling_service::cancelled_copy(&existing.ical_data),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Sends an attendee's answer to the organizer: updates the organizer's
+    /// copy and delivers a REPLY.
+    async fn reply(
+        &self,
+        attendee_email: &str,
+        object: &SchedulingObject,
+        ical_data: &str,
+    ) -> Result<()> {
+        let Some(organizer) = self.find_user(&object.organizer).await? else {
+            return Ok(());
+        };
+        let Some(reply) = scheduling_service::reply_message(ical_data, attendee_email) else {
+            return Ok(());
+        };
+
+        if let Some(copy) = self.find_copy(organizer.id(), &object.uid).await?
+            && scheduling_service::is_organized_by(&copy.ical_data, organizer.email())
+            && let Some(updated) =
+                scheduling_service::apply_partstats(&copy.ical_data, &reply, attendee_email)
+        {
+            self.calendar_storage
+                .update_event_ical(&copy.id, updated)
+                .await?;
+        }
+        self.deliver(
+            &organizer,
+            ItipMethod::Reply,
+            attendee_email,
+            &object.uid,
+            reply,
+        )
+        .await
+    }
+
+    /// The calendars of `owner_id` whose busy time `requester_id` may see.
+    async fn visible_calendars(
+        &self,
+        owner_id: Uuid,
+        requester_id: Uuid,
+    ) -> Result<Vec<CalendarDto>> {
+        let calendars = self
+            .calendar_storage
+            .list_calendars_by_owner(owner_id)
+            .await?;
+        if owner_id == requester_id {
+            return Ok(calendars);
+        }
+        let mut visible = Vec::with_capacity(calendars.len());
+        for calendar in calendars {
+            if self
+                .calendar_storage
+                .check_calendar_access(&calendar.id, requester_id)
+                .await?
+            {
+                visible.push(calendar);
+            }
+        }
+        Ok(visible)
+    }
+
+    /// Merged busy time of the events in `calendars`; tasks are ignored.
+    async fn busy_time(
+        &self,
+        calendars: &[CalendarDto],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<BusyPeriod>> {
+        let mut periods = Vec::new();
+        for calendar in calendars {
+            let events = self
+                .calendar_storage
+                .get_events_in_time_range(&calendar.id, &start, &end)
+                .await?;
+            for event in events.iter().filter(|e| !e.is_task()) {
+                let set = RecurrenceSet::from_event(
+                    &event.ical_data,
+                    &event.ical_uid,
+                    event.start_time,
+                    event.end_time,
+                    event.rrule.as_deref(),
+                );
+                periods.extend(scheduling_service::busy_periods(&set, start, end));
+            }
+        }
+        Ok(scheduling_service::merge_busy_periods(periods))
+    }
+}
+
+impl CalendarSchedulingUseCase for CalendarSchedulingService {
+    async fn schedule_write(
+        &self,
+        user_id: Uuid,
+        previous: Option<&CalendarEventDto>,
+        current: &CalendarEventDto,
+    ) -> Result<()> {
+        let current_object = SchedulingObject::parse(&current.ical_data);
+        let previous_object = previous.and_then(|p| SchedulingObject::parse(&p.ical_data));
+        if current_object.is_none() && previous_object.is_none() {
+            return Ok(());
+        }
+        let user = self.user_repo.get_user_by_id(user_id).await?;
+        let email = user.email();
+
+        // Organizer: invite the current attendees, cancel the removed ones
+        let organized = current_object.as_ref().filter(|o| o.is_organizer(email));
+        if let Some(object) = organized {
+            for recipient in object.recipients() {
+                if let Some(attendee) = self.find_user(recipient).await? {
+                    self.invite(email, &attendee, object, &current.ical_data)
+                        .await?;
+                }
+            }
+        }
+        if let (Some(previous), Some(old)) = (previous, previous_object.as_ref())
+            && old.is_organizer(email)
+        {
+            for recipient in old.recipients() {
+                if organized
+                    .is_some_and(|o| o.recipients().any(|r| r.eq_ignore_ascii_case(recipient)))
+                {
+                    continue;
+                }
+                if let Some(attendee) = self.find_user(recipient).await? {
+                    self.cancel(email, &attendee, &old.uid, &previous.ical_data)
+                        .await?;
+                }
+            }
+        }
+
+        // Attendee: reply when their PARTSTAT changed
+        if let Some(object) = &current_object
+            && !object.is_organizer(email)
+            && object.attendee(email).is_some()
+        {
+            let before = previous
+                .map(|p| scheduling_service::partstats(&p.ical_data, email))
+                .unwrap_or_default();
+            let after = scheduling_service::partstats(&current.ical_data, email);
+            let unanswered = before.is_empty() && after.iter().all(|(_, p)| p == "NEEDS-ACTION");
+            if before != after && !unanswered {
+                self.reply(email, object, &current.ical_data).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn schedule_delete(&self, user_id: Uuid, deleted: &CalendarEventDto) -> Result<()> {
+        let Some(object) = SchedulingObject::parse(&deleted.ical_data) else {
+            return Ok(());
+        };
+        let user = self.user_repo.get_user_by_id(user_id).await?;
+        let email = user.email();
+
+        if object.is_organizer(email) {
+            for recipient in object.recipients() {
+                if let Some(attendee) = self.find_user(recipient).await? {
+                    self.cancel(email, &attendee, &object.uid, &deleted.ical_data)
+                        .await?;
+                }
+            }
+        } else if !object.cancelled
+            && let Some(declined) =
+                scheduling_service::set_partstat(&deleted.ical_data, email, "DECLINED")
+        {
+            // Deleting an invitation declines it
+            self.reply(email, &object, &declined).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_inbox(&self, user_id: Uuid) -> Result<Vec<ScheduleMessageDto>> {
+        self.inbox_repo.list_messages(user_id).await
+    }
+
+    async fn delete_inbox_message(&self, user_id: Uuid, message_id: &str) -> Result<()> {
+        let id = Uuid::parse_str(message_id)
+            .map_err(|_| DomainError::not_found("ScheduleMessage", message_id))?;
+        if self.inbox_repo.delete_message(user_id, id).await? {
+            Ok(())
+        } else {
+            Err(DomainError::not_found("ScheduleMessage", message_id))
+        }
+    }
+
+    async fn free_busy_query(
+        &self,
+        user_id: Uuid,
+        calendar_id: Option<&str>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<String> {
+        if end <= start {
+            return Err(DomainError::validation_error(
+                "time-range end must be after its start",
+            ));
+        }
+        let calendars = match calendar_id {
+            Some(calendar_id) => {
+                if !self
+                    .calendar_storage
+                    .check_calendar_access(calendar_id, user_id)
+                    .await?
+                {
+                    return Err(DomainError::access_denied(
+                        "Calendar",
+                        "You don't have access to this calendar",
+                    ));
+                }
+                vec![self.calendar_storage.get_calendar(calendar_id).await?]
+            }
+            None => {
+                let mut calendars = self
+                    .calendar_storage
+                    .list_calendars_by_owner(user_id)
+                    .await?;
+                calendars.extend(
+                    self.calendar_storage
+                        .list_calendars_shared_with_user(user_id)
+                        .await?,
+                );
+                calendars
+            }
+        };
+
+        let periods = self.busy_time(&calendars, start, end).await?;
+        Ok(scheduling_service::freebusy_calendar(&periods, start, end))
+    }
+
+    async fn free_busy_request(
+        &self,
+        user_id: Uuid,
+        ical_data: &str,
+    ) -> Result<Vec<FreeBusyResponseDto>> {
+        let request = FreeBusyRequest::parse(ical_data).ok_or_else(|| {
+            DomainError::validation_error(
+                "Expected a METHOD:REQUEST VFREEBUSY with ORGANIZER, ATTENDEE, DTSTART and DTEND",
+            )
+        })?;
+        let user = self.user_repo.get_user_by_id(user_id).await?;
+        if !request.organizer.eq_ignore_ascii_case(user.email()) {
+            return Err(DomainError::access_denied(
+                "Calendar",
+                "ORGANIZER must be the authenticated user",
+            ));
+        }
+
+        let mut responses = Vec::with_capacity(request.attendees.len());
+        for address in &request.attendees {
+            let target = match scheduling_service::address_to_email(address) {
+                Some(email) => self.find_user(&email).await?,
+                None => None,
+            };
+            let Some(target) = target else {
+                responses.push(FreeBusyResponseDto {
+                    recipient: address.clone(),
+                    request_status: "3.7;Invalid calendar user".to_string(),
+                    calendar_data: None,
+                });
+                continue;
+            };
+
+            let calendars = self.visible_calendars(target.id(), user_id).await?;
+            if calendars.is_empty() && target.id() != user_id {
+                responses.push(FreeBusyResponseDto {
+                    recipient: address.clone(),
+                    request_status: "3.8;No authority".to_string(),
+                    calendar_data: None,
+                });
+                continue;
+            }
+            let periods = self
+                .busy_time(&calendars, request.start, request.end)
+                .await?;
+            responses.push(FreeBusyResponseDto {
+                recipient: address.clone(),
+                request_status: "2.0;Success".to_string(),
+                calendar_data: Some(request.reply(address, &periods)),
+            });
+        }
+        Ok(responses)
+    }
+}
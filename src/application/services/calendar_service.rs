@@ -3,8 +3,8 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::application::dtos::calendar_dto::{
-    CalendarDto, CalendarEventDto, CreateCalendarDto, CreateEventDto, CreateEventICalDto,
-    UpdateCalendarDto, UpdateEventDto,
+    CalendarChangesDto, CalendarDto, CalendarEventDto, CreateCalendarDto, CreateEventDto,
+    CreateEventICalDto, CreateTaskDto, UpdateCalendarDto, UpdateEventDto, UpdateTaskDto,
 };
 use crate::application::ports::calendar_ports::{CalendarStoragePort, CalendarUseCase};
 use crate::common::errors::{DomainError, ErrorKind};
@@ -277,6 +277,46 @@ impl CalendarUseCase for CalendarService {
         Ok(event)
     }
 
+    async fn create_task(
+        &self,
+        task: CreateTaskDto,
+        user_id: Uuid,
+    ) -> Result<CalendarEventDto, DomainError> {
+        let has_access = self
+            .calendar_storage
+            .check_calendar_access(&task.calendar_id, user_id)
+            .await?;
+        if !has_access {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Calendar",
+                "You don't have permission to add tasks to this calendar",
+            ));
+        }
+        self.calendar_storage.create_task(task).await
+    }
+
+    async fn update_task(
+        &self,
+        task_id: &str,
+        update: UpdateTaskDto,
+        user_id: Uuid,
+    ) -> Result<CalendarEventDto, DomainError> {
+        let task = self.calendar_storage.get_event(task_id).await?;
+        let has_access = self
+            .calendar_storage
+            .check_calendar_access(&task.calendar_id, user_id)
+            .await?;
+        if !has_access {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Calendar",
+                "You don't have permission to update tasks in this calendar",
+            ));
+        }
+        self.calendar_storage.update_task(task_id, update).await
+    }
+
     async fn list_events(
         &self,
         calendar_id: &str,
@@ -332,4 +372,27 @@ impl CalendarUseCase for CalendarService {
             .get_events_in_time_range(calendar_id, &start, &end)
             .await
     }
+
+    async fn get_changes_since(
+        &self,
+        calendar_id: &str,
+        since: Option<i64>,
+        user_id: Uuid,
+    ) -> Result<Option<CalendarChangesDto>, DomainError> {
+        let has_access = self
+            .calendar_storage
+            .check_calendar_access(calendar_id, user_id)
+            .await?;
+        let calendar = self.calendar_storage.get_calendar(calendar_id).await?;
+        if !has_access && !calendar.is_public {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Calendar",
+                "You don't have permission to view events in this calendar",
+            ));
+        }
+        self.calendar_storage
+            .get_event_changes(calendar_id, since)
+            .await
+    }
 }
@@ -7,8 +7,8 @@ use crate::application::dtos::address_book_dto::{
     UpdateAddressBookDto,
 };
 use crate::application::dtos::contact_dto::{
-    ContactDto, ContactGroupDto, CreateContactDto, CreateContactGroupDto, CreateContactVCardDto,
-    GroupMembershipDto, UpdateContactDto, UpdateContactGroupDto,
+    ContactChangesDto, ContactDto, ContactGroupDto, CreateContactDto, CreateContactGroupDto,
+    CreateContactVCardDto, GroupMembershipDto, UpdateContactDto, UpdateContactGroupDto,
 };
 use crate::application::ports::carddav_ports::{AddressBookUseCase, ContactUseCase};
 use crate::application::ports::storage_ports::StorageUseCase;
@@ -1116,6 +1116,29 @@ impl ContactUseCase for ContactService {
 
         Ok(vcards)
     }
+
+    async fn get_changes_since(
+        &self,
+        address_book_id: &str,
+        since: Option<i64>,
+        user_id: Uuid,
+    ) -> Result<Option<ContactChangesDto>, DomainError> {
+        let id = Uuid::parse_str(address_book_id)
+            .map_err(|_| DomainError::validation_error("Invalid address book ID format"))?;
+
+        // Check if user has access to the address book
+        self.check_address_book_access(&id, &user_id).await?;
+
+        let changes = self
+            .contact_repository
+            .list_changes_since(&id, since)
+            .await?;
+        Ok(changes.map(|c| ContactChangesDto {
+            sync_token: c.sync_token,
+            changed: c.changed.into_iter().map(ContactDto::from).collect(),
+            deleted: c.deleted,
+        }))
+    }
 }
 
 impl StorageUseCase for ContactService {
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::application::adapters::webdav_adapter::{PropPatchStatus, PropValue, QualifiedName};
+use crate::application::ports::dead_property_ports::{
+    DeadPropertyRepositoryPort, DeadPropertyUseCase,
+};
+use crate::common::errors::Result;
+use crate::infrastructure::repositories::pg::DeadPropertyPgRepository;
+
+/// Maximum size of a single property value in bytes.
+const MAX_VALUE_LEN: usize = 64 * 1024;
+
+/// Maximum length of a property name or namespace.
+const MAX_NAME_LEN: usize = 1024;
+
+/// Service for WebDAV dead properties (RFC 4918 §4).
+///
+/// Properties in the `DAV:` namespace are live — computed from the file or
+/// folder itself — and can never be stored.
+pub struct DeadPropertyService {
+    repo: Arc<DeadPropertyPgRepository>,
+}
+
+impl DeadPropertyService {
+    pub fn new(repo: Arc<DeadPropertyPgRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Whether a client may store this property.
+    fn is_writable(name: &QualifiedName) -> bool {
+        name.namespace != "DAV:"
+            && !name.name.is_empty()
+            && name.name.len() <= MAX_NAME_LEN
+            && name.namespace.len() <= MAX_NAME_LEN
+    }
+
+    fn set_status(prop: &PropValue) -> PropPatchStatus {
+        if !Self::is_writable(&prop.name) {
+            PropPatchStatus::Forbidden
+        } else if prop.value.as_ref().is_some_and(|v| v.len() > MAX_VALUE_LEN) {
+            PropPatchStatus::Conflict
+        } else {
+            PropPatchStatus::Ok
+        }
+    }
+
+    fn remove_status(name: &QualifiedName) -> PropPatchStatus {
+        if Self::is_writable(name) {
+            PropPatchStatus::Ok
+        } else {
+            PropPatchStatus::Forbidden
+        }
+    }
+}
+
+impl DeadPropertyUseCase for DeadPropertyService {
+    async fn get_properties(&self, resource_id: &str) -> Result<Vec<PropValue>> {
+        let rows = self
+            .repo
+            .list_properties(&[resource_id.to_string()])
+            .await?;
+        Ok(rows.into_iter().map(|(_, prop)| prop).collect())
+    }
+
+    async fn get_properties_batch(
+        &self,
+        resource_ids: &[String],
+    ) -> Result<HashMap<String, Vec<PropValue>>> {
+        let mut map: HashMap<String, Vec<PropValue>> = HashMap::new();
+        for (id, prop) in self.repo.list_properties(resource_ids).await? {
+            map.entry(id).or_default().push(prop);
+        }
+        Ok(map)
+    }
+
+    fn check_patch(
+        &self,
+        set: &[PropValue],
+        remove: &[QualifiedName],
+    ) -> Vec<(QualifiedName, PropPatchStatus)> {
+        set.iter()
+            .map(|prop| (prop.name.clone(), Self::set_status(prop)))
+            .chain(
+                remove
+                    .iter()
+                    .map(|name| (name.clone(), Self::remove_status(name))),
+            )
+            .collect()
+    }
+
+    async fn patch_properties(
+        &self,
+        resource_id: &str,
+        is_folder: bool,
+        set: Vec<PropValue>,
+        remove: Vec<QualifiedName>,
+    ) -> Result<Vec<(QualifiedName, PropPatchStatus)>> {
+        let mut results = self.check_patch(&set, &remove);
+        if PropPatchStatus::settle(&mut results) && !results.is_empty() {
+            self.repo
+                .update_properties(resource_id, is_folder, &set, &remove)
+                .await?;
+        }
+        Ok(results)
+    }
+
+    async fn copy_properties(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        is_folder: bool,
+    ) -> Result<()> {
+        self.repo
+            .copy_properties(source_id, target_id, is_folder)
+            .await
+    }
+
+    async fn copy_tree_properties(
+        &self,
+        source_folder_id: &str,
+        target_folder_id: &str,
+    ) -> Result<()> {
+        let copied = self
+            .repo
+            .copy_tree_properties(source_folder_id, target_folder_id)
+            .await?;
+        tracing::debug!(
+            "Copied {} dead properties from folder tree {} to {}",
+            copied,
+            source_folder_id,
+            target_folder_id
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dav_namespace_is_protected() {
+        assert!(!DeadPropertyService::is_writable(&QualifiedName::new(
+            "DAV:",
+            "getlastmodified"
+        )));
+        assert!(!DeadPropertyService::is_writable(&QualifiedName::new(
+            "DAV:",
+            "displayname"
+        )));
+    }
+
+    #[test]
+    fn custom_namespaces_are_writable() {
+        assert!(DeadPropertyService::is_writable(&QualifiedName::new(
+            "urn:schemas-microsoft-com:",
+            "Win32LastModifiedTime"
+        )));
+        assert!(DeadPropertyService::is_writable(&QualifiedName::new(
+            "", "tag"
+        )));
+        assert!(!DeadPropertyService::is_writable(&QualifiedName::new(
+            "urn:x", ""
+        )));
+    }
+
+    #[test]
+    fn oversized_values_conflict_and_live_properties_are_forbidden() {
+        let prop = |ns: &str, value: String| PropValue {
+            name: QualifiedName::new(ns, "p"),
+            value: Some(value),
+        };
+        assert_eq!(
+            DeadPropertyService::set_status(&prop("urn:x", "v".into())),
+            PropPatchStatus::Ok
+        );
+        assert_eq!(
+            DeadPropertyService::set_status(&prop("urn:x", "v".repeat(MAX_VALUE_LEN + 1))),
+            PropPatchStatus::Conflict
+        );
+        assert_eq!(
+            DeadPropertyService::set_status(&prop("DAV:", "v".into())),
+            PropPatchStatus::Forbidden
+        );
+        assert_eq!(
+            DeadPropertyService::remove_status(&QualifiedName::new("DAV:", "getetag")),
+            PropPatchStatus::Forbidden
+        );
+    }
+}
@@ -17,7 +17,7 @@ use crate::application::ports::auth_ports::{
 };
 use crate::common::errors::{DomainError, ErrorKind};
 use crate::domain::entities::device_code::{DeviceCode, DeviceCodeStatus};
-use crate::domain::entities::session::Session;
+use crate::domain::entities::session::{LoginMethod, Session};
 use crate::infrastructure::repositories::pg::DeviceCodePgRepository;
 use crate::infrastructure::repositories::pg::SessionPgRepository;
 use crate::infrastructure::repositories::pg::UserPgRepository;
@@ -187,6 +187,7 @@ impl DeviceAuthService {
             Some(format!("device:{}", dc.client_name())), // user_agent
             self.token_service.refresh_token_expiry_days(),
             Uuid::new_v4(),
+            LoginMethod::Device,
         );
         self.session_storage.create_session(session).await?;
 
@@ -0,0 +1,369 @@
+//! End-to-end encryption: key storage, encrypted folders, their opaque
+//! metadata and the locks clients take while updating it.
+//!
+//! This backs the Nextcloud `end_to_end_encryption` API.  The server only
+//! ever stores what clients encrypted; it signs user certificates and
+//! refuses writes into an encrypted folder unless the writer holds the
+//! folder's lock, so two clients cannot overwrite each other's metadata.
+//! Only the owner works with their encrypted folders.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use rand_core::{OsRng, RngCore};
+use tokio::sync::OnceCell;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::application::ports::e2ee_ports::{E2eeRepositoryPort, EncryptedFolder};
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::repositories::folder_repository::FolderRepository;
+use crate::infrastructure::repositories::pg::{E2eePgRepository, FolderDbRepository};
+use crate::infrastructure::services::e2ee_keys::{self, CsrError};
+
+/// A lock nobody renewed for this long is treated as abandoned
+const LOCK_TIMEOUT_MINUTES: i32 = 30;
+
+fn locked() -> DomainError {
+    DomainError::access_denied("E2ee", "The encrypted folder is locked by another client")
+}
+
+/// Whether a lock taken at `locked_at` still holds at `now`.
+fn lock_is_active(locked_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    locked_at.is_some_and(|at| now - at < Duration::minutes(i64::from(LOCK_TIMEOUT_MINUTES)))
+}
+
+/// Whether `token` is the live lock on `folder`.
+fn holds_lock(folder: &EncryptedFolder, token: Option<&str>, now: DateTime<Utc>) -> bool {
+    token.is_some()
+        && folder.lock_token.as_deref() == token
+        && lock_is_active(folder.locked_at, now)
+}
+
+/// Service for end-to-end encryption keys and encrypted folders.
+pub struct E2eeService {
+    repo: Arc<E2eePgRepository>,
+    folders: Arc<FolderDbRepository>,
+    /// Server signing key, loaded or generated on first use
+    server_key: OnceCell<String>,
+}
+
+impl E2eeService {
+    pub fn new(repo: Arc<E2eePgRepository>, folders: Arc<FolderDbRepository>) -> Self {
+        Self {
+            repo,
+            folders,
+            server_key: OnceCell::new(),
+        }
+    }
+
+    async fn server_key(&self) -> Result<&String> {
+        self.server_key
+            .get_or_try_init(|| async {
+                if let Some(key) = self.repo.get_server_key().await? {
+                    return Ok(key);
+                }
+                // RSA key generation takes a moment of CPU time
+                let key = tokio::task::spawn_blocking(e2ee_keys::generate_server_key)
+                    .await
+                    .map_err(|e| DomainError::internal_error("E2ee", e.to_string()))?
+                    .map_err(|e| DomainError::internal_error("E2ee", e))?;
+                info!("Generated end-to-end encryption server key");
+                self.repo.store_server_key(&key).await
+            })
+            .await
+    }
+
+    /// Public key clients check user certificates against.
+    pub async fn server_public_key(&self) -> Result<String> {
+        e2ee_keys::server_public_key(self.server_key().await?)
+            .map_err(|e| DomainError::internal_error("E2ee", e))
+    }
+
+    /// Signs the user's certificate signing request and stores the
+    /// certificate as their public key.
+    pub async fn sign_public_key(
+        &self,
+        user_id: Uuid,
+        username: &str,
+        csr: &str,
+    ) -> Result<String> {
+        if self.repo.get_user_keys(user_id).await?.public_key.is_some() {
+            return Err(DomainError::already_exists("E2eePublicKey", username));
+        }
+        let certificate =
+            e2ee_keys::sign_csr(csr, username, self.server_key().await?).map_err(|e| match e {
+                CsrError::Invalid => DomainError::new(
+                    ErrorKind::InvalidInput,
+                    "E2ee",
+                    "Invalid certificate signing request",
+                ),
+                CsrError::WrongCommonName => DomainError::new(
+                    ErrorKind::InvalidInput,
+                    "E2ee",
+                    "The common name of the request must be your username",
+                ),
+                CsrError::Signing(msg) => DomainError::internal_error("E2ee", msg),
+            })?;
+        self.repo
+            .set_public_key(user_id, Some(&certificate))
+            .await?;
+        info!(
+            "Issued end-to-end encryption certificate for '{}'",
+            username
+        );
+        Ok(certificate)
+    }
+
+    pub async fn public_key(&self, user_id: Uuid) -> Result<String> {
+        self.repo
+            .get_user_keys(user_id)
+            .await?
+            .public_key
+            .ok_or_else(|| DomainError::not_found("E2eePublicKey", user_id.to_string()))
+    }
+
+    /// Certificates of the given users by username; users without one are
+    /// left out.
+    pub async fn public_keys(&self, usernames: &[String]) -> Result<HashMap<String, String>> {
+        Ok(self
+            .repo
+            .public_keys_by_username(usernames)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
+    pub async fn delete_public_key(&self, user_id: Uuid) -> Result<()> {
+        self.repo.set_public_key(user_id, None).await
+    }
+
+    pub async fn private_key(&self, user_id: Uuid) -> Result<String> {
+        self.repo
+            .get_user_keys(user_id)
+            .await?
+            .private_key
+            .ok_or_else(|| DomainError::not_found("E2eePrivateKey", user_id.to_string()))
+    }
+
+    /// Stores the user's private key as encrypted by the client.
+    pub async fn store_private_key(&self, user_id: Uuid, private_key: &str) -> Result<()> {
+        if private_key.trim().is_empty() {
+            return Err(DomainError::validation_error("The private key is empty"));
+        }
+        if self
+            .repo
+            .get_user_keys(user_id)
+            .await?
+            .private_key
+            .is_some()
+        {
+            return Err(DomainError::already_exists(
+                "E2eePrivateKey",
+                user_id.to_string(),
+            ));
+        }
+        self.repo.set_private_key(user_id, Some(private_key)).await
+    }
+
+    pub async fn delete_private_key(&self, user_id: Uuid) -> Result<()> {
+        self.repo.set_private_key(user_id, None).await
+    }
+
+    /// Checks that `folder_id` is one of the user's own folders.
+    async fn own_folder(&self, user_id: Uuid, folder_id: &str) -> Result<()> {
+        let folder = self
+            .folders
+            .get_folder(folder_id)
+            .await
+            .map_err(|_| DomainError::not_found("Folder", folder_id))?;
+        if folder.owner_id() != Some(user_id) {
+            return Err(DomainError::not_found("Folder", folder_id));
+        }
+        Ok(())
+    }
+
+    /// The user's encrypted folder `folder_id`.
+    async fn own_encrypted_folder(
+        &self,
+        user_id: Uuid,
+        folder_id: &str,
+    ) -> Result<EncryptedFolder> {
+        match self.repo.get_folder(folder_id).await? {
+            Some(folder) if folder.owner_id == user_id => Ok(folder),
+            _ => Err(DomainError::not_found("EncryptedFolder", folder_id)),
+        }
+    }
+
+    async fn ensure_empty(&self, folder_id: &str) -> Result<()> {
+        if !self.repo.is_folder_empty(folder_id).await? {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "E2ee",
+                "Only empty folders can change their encryption",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Marks an empty folder of the user as encrypted.
+    pub async fn set_encrypted(&self, user_id: Uuid, folder_id: &str) -> Result<()> {
+        self.own_folder(user_id, folder_id).await?;
+        if self.repo.get_folder(folder_id).await?.is_some() {
+            return Ok(());
+        }
+        self.ensure_empty(folder_id).await?;
+        self.repo.mark_encrypted(folder_id, user_id).await?;
+        info!("Folder {} marked as end-to-end encrypted", folder_id);
+        Ok(())
+    }
+
+    /// Turns an empty encrypted folder back into a plain one, dropping its
+    /// metadata.
+    pub async fn remove_encrypted(&self, user_id: Uuid, folder_id: &str) -> Result<()> {
+        self.own_encrypted_folder(user_id, folder_id).await?;
+        self.ensure_empty(folder_id).await?;
+        self.repo.unmark_encrypted(folder_id).await?;
+        info!("Folder {} no longer end-to-end encrypted", folder_id);
+        Ok(())
+    }
+
+    /// Locks an encrypted folder for a metadata update and returns the lock
+    /// token.  Passing the current token renews the lock.
+    pub async fn lock(
+        &self,
+        user_id: Uuid,
+        folder_id: &str,
+        token: Option<&str>,
+    ) -> Result<String> {
+        self.own_encrypted_folder(user_id, folder_id).await?;
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        self.repo
+            .acquire_lock(folder_id, &hex::encode(bytes), token, LOCK_TIMEOUT_MINUTES)
+            .await?
+            .ok_or_else(locked)
+    }
+
+    /// Releases the lock held with `token`.  Unlocking a folder that is not
+    /// locked succeeds.
+    pub async fn unlock(&self, user_id: Uuid, folder_id: &str, token: Option<&str>) -> Result<()> {
+        self.own_encrypted_folder(user_id, folder_id).await?;
+        if self
+            .repo
+            .release_lock(folder_id, token, LOCK_TIMEOUT_MINUTES)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(locked())
+        }
+    }
+
+    pub async fn metadata(&self, user_id: Uuid, folder_id: &str) -> Result<String> {
+        self.own_encrypted_folder(user_id, folder_id)
+            .await?
+            .metadata
+            .ok_or_else(|| DomainError::not_found("E2eeMetadata", folder_id))
+    }
+
+    /// Stores the first metadata document of an encrypted folder.
+    pub async fn create_metadata(
+        &self,
+        user_id: Uuid,
+        folder_id: &str,
+        metadata: &str,
+    ) -> Result<()> {
+        let folder = self.own_encrypted_folder(user_id, folder_id).await?;
+        if folder.metadata.is_some() {
+            return Err(DomainError::already_exists("E2eeMetadata", folder_id));
+        }
+        self.repo.set_metadata(folder_id, Some(metadata)).await
+    }
+
+    /// Replaces the metadata; the caller must hold the folder's lock.
+    pub async fn update_metadata(
+        &self,
+        user_id: Uuid,
+        folder_id: &str,
+        token: Option<&str>,
+        metadata: &str,
+    ) -> Result<()> {
+        self.own_encrypted_folder(user_id, folder_id).await?;
+        self.check_write(folder_id, token).await?;
+        self.repo.set_metadata(folder_id, Some(metadata)).await
+    }
+
+    pub async fn delete_metadata(&self, user_id: Uuid, folder_id: &str) -> Result<()> {
+        self.own_encrypted_folder(user_id, folder_id).await?;
+        self.repo.set_metadata(folder_id, None).await
+    }
+
+    /// Whether an item (file or folder id) is an encrypted folder or lies
+    /// inside one.  The server cannot read such items and must not try to
+    /// preview, index or open them.
+    pub async fn is_encrypted(&self, item_id: &str) -> Result<bool> {
+        Ok(!self.repo.encrypted_ancestors(item_id).await?.is_empty())
+    }
+
+    /// Which of `folder_ids` are marked as encrypted.
+    pub async fn encrypted_folder_ids(&self, folder_ids: &[String]) -> Result<HashSet<String>> {
+        self.repo.filter_encrypted(folder_ids).await
+    }
+
+    /// Allows a change inside `folder_id` when the folder is not encrypted,
+    /// or when `token` is the live lock on it or on an encrypted folder
+    /// above it (newer clients lock only the top-level folder).
+    pub async fn check_write(&self, folder_id: &str, token: Option<&str>) -> Result<()> {
+        let ancestors = self.repo.encrypted_ancestors(folder_id).await?;
+        let now = Utc::now();
+        if ancestors.is_empty() || ancestors.iter().any(|f| holds_lock(f, token, now)) {
+            Ok(())
+        } else {
+            Err(DomainError::access_denied(
+                "E2ee",
+                "Writing to an encrypted folder requires its lock token",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(token: Option<&str>, locked_at: Option<DateTime<Utc>>) -> EncryptedFolder {
+        EncryptedFolder {
+            folder_id: "f".to_string(),
+            owner_id: Uuid::nil(),
+            metadata: None,
+            lock_token: token.map(str::to_string),
+            locked_at,
+        }
+    }
+
+    #[test]
+    fn locks_expire_after_timeout() {
+        let now = Utc::now();
+        assert!(!lock_is_active(None, now));
+        assert!(lock_is_active(Some(now - Duration::minutes(5)), now));
+        assert!(!lock_is_active(
+            Some(now - Duration::minutes(i64::from(LOCK_TIMEOUT_MINUTES))),
+            now
+        ));
+    }
+
+    #[test]
+    fn only_the_current_token_holds_a_live_lock() {
+        let now = Utc::now();
+        let live = folder(Some("abc"), Some(now));
+        assert!(holds_lock(&live, Some("abc"), now));
+        assert!(!holds_lock(&live, Some("xyz"), now));
+        assert!(!holds_lock(&live, None, now));
+
+        let stale = folder(Some("abc"), Some(now - Duration::hours(1)));
+        assert!(!holds_lock(&stale, Some("abc"), now));
+        assert!(!holds_lock(&folder(None, None), None, now));
+    }
+}
@@ -1,13 +1,24 @@
 use std::sync::Arc;
 
 use crate::application::dtos::file_dto::FileDto;
+use crate::application::ports::activity_ports::{ActivityAction, NewActivity};
 use crate::application::ports::file_lifecycle::FileDeletedHook;
 use crate::application::ports::file_ports::FileManagementUseCase;
-use crate::application::ports::storage_ports::{CopyFolderTreeResult, FileReadPort, FileWritePort};
+use crate::application::ports::live_event_ports::{LiveChange, LiveEventKind};
+use crate::application::ports::storage_ports::{
+    CopyFolderTreeResult, FileReadPort, FileWritePort, StorageUsagePort,
+};
 use crate::application::ports::trash_ports::TrashUseCase;
+use crate::application::ports::user_share_ports::UserShareRepositoryPort;
+use crate::application::services::activity_service::ActivityService;
+use crate::application::services::live_event_service::LiveEventService;
+use crate::application::services::storage_usage_service::StorageUsageService;
 use crate::application::services::trash_service::TrashService;
-use crate::common::errors::DomainError;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::file::File;
+use crate::domain::repositories::folder_repository::FolderRepository;
 use crate::domain::services::path_service::validate_storage_name;
+use crate::infrastructure::repositories::pg::UserSharePgRepository;
 use crate::infrastructure::repositories::pg::file_blob_read_repository::FileBlobReadRepository;
 use crate::infrastructure::repositories::pg::file_blob_write_repository::FileBlobWriteRepository;
 use crate::infrastructure::repositories::pg::folder_db_repository::FolderDbRepository;
@@ -29,6 +40,14 @@ pub struct FileManagementService {
     content_cache: Option<Arc<FileContentCache>>,
     /// Hooks fired after a file is permanently deleted.
     file_deleted_hooks: Vec<Arc<dyn FileDeletedHook>>,
+    /// Grants access to files in folders other users shared with the caller.
+    user_shares: Option<Arc<UserSharePgRepository>>,
+    /// Records renames and moves.
+    activity: Option<Arc<ActivityService>>,
+    /// Tells subscribed clients about renames, moves, copies and deletions.
+    live_events: Option<Arc<LiveEventService>>,
+    /// Charges copies to the owner of the folder they land in.
+    storage_usage: Option<Arc<StorageUsageService>>,
 }
 
 impl FileManagementService {
@@ -41,6 +60,10 @@ impl FileManagementService {
             trash_service: None,
             content_cache: None,
             file_deleted_hooks: Vec::new(),
+            user_shares: None,
+            activity: None,
+            live_events: None,
+            storage_usage: None,
         }
     }
 
@@ -59,6 +82,10 @@ impl FileManagementService {
             trash_service,
             content_cache,
             file_deleted_hooks: Vec::new(),
+            user_shares: None,
+            activity: None,
+            live_events: None,
+            storage_usage: None,
         }
     }
 
@@ -68,40 +95,263 @@ impl FileManagementService {
         self
     }
 
-    /// Verifies ownership via the read repository.
-    async fn verify_owner(&self, file_id: &str, caller_id: Uuid) -> Result<(), DomainError> {
-        if let Some(read) = &self.file_read {
-            read.verify_file_owner(file_id, caller_id).await
-        } else {
+    /// Lets callers modify files in folders shared with them with write access.
+    pub fn with_user_shares(mut self, user_shares: Option<Arc<UserSharePgRepository>>) -> Self {
+        self.user_shares = user_shares;
+        self
+    }
+
+    /// Records renames and moves in the activity log.
+    pub fn with_activity(mut self, activity: Option<Arc<ActivityService>>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Publishes file changes to subscribed clients.
+    pub fn with_live_events(mut self, live_events: Option<Arc<LiveEventService>>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
+    /// Enforces storage quotas on copies.
+    pub fn with_storage_usage(mut self, storage_usage: Option<Arc<StorageUsageService>>) -> Self {
+        self.storage_usage = storage_usage;
+        self
+    }
+
+    /// The file as it is before a change, when the change will be logged
+    /// or published.
+    async fn file_before_change(&self, file_id: &str) -> Option<File> {
+        if self.activity.is_none() && self.live_events.is_none() {
+            return None;
+        }
+        match &self.file_read {
+            Some(read) => read.get_file(file_id).await.ok(),
+            None => None,
+        }
+    }
+
+    /// Tells subscribed clients about a change to `file` by `actor_id`.
+    fn publish_change(
+        &self,
+        kind: LiveEventKind,
+        file: &File,
+        previous_folder: Option<&str>,
+        actor_id: Option<Uuid>,
+    ) {
+        if let Some(live_events) = &self.live_events {
+            let change = LiveChange::new(kind, file.id())
+                .with_name(file.name())
+                .with_folder(file.folder_id())
+                .with_previous_folder(previous_folder);
+            live_events.publish(file.owner_id().into_iter().chain(actor_id), change);
+        }
+    }
+
+    /// Logs and publishes a rename or move by `actor_id`, or by the owner
+    /// when the caller is unknown (WebDAV paths that resolved ownership
+    /// beforehand).
+    fn record_change(
+        &self,
+        action: ActivityAction,
+        before: Option<File>,
+        after: &File,
+        actor_id: Option<Uuid>,
+    ) {
+        // The write path does not always return the owner; the lookup before
+        // the change does.
+        let owner_id = after
+            .owner_id()
+            .or_else(|| before.as_ref().and_then(|b| b.owner_id()));
+        if let Some(live_events) = &self.live_events
+            && let Some(kind) = LiveEventKind::from_activity(action)
+        {
+            let previous_folder = match action {
+                ActivityAction::FileMoved => before.as_ref().and_then(|b| b.folder_id()),
+                _ => None,
+            };
+            let change = LiveChange::new(kind, after.id())
+                .with_name(after.name())
+                .with_folder(after.folder_id())
+                .with_previous_folder(previous_folder);
+            live_events.publish(owner_id.into_iter().chain(actor_id), change);
+        }
+
+        let Some(activity) = &self.activity else {
+            return;
+        };
+        let mut entry = NewActivity::new(action).with_item(after.id(), after.name());
+        if let Some(owner_id) = owner_id {
+            entry = entry.with_owner(owner_id);
+        }
+        if let Some(actor_id) = actor_id.or(owner_id) {
+            entry = entry.with_actor(actor_id);
+        }
+        if let Some(before) = before {
+            entry = match action {
+                ActivityAction::FileRenamed => entry.with_detail("old_name", before.name()),
+                _ => entry
+                    .with_detail("old_path", before.path_string())
+                    .with_detail("new_path", after.path_string()),
+            };
+        }
+        activity.record(entry);
+    }
+
+    async fn move_file_as(
+        &self,
+        file_id: &str,
+        folder_id: Option<String>,
+        actor_id: Option<Uuid>,
+    ) -> Result<FileDto, DomainError> {
+        info!(
+            "Moving file with ID: {} to folder: {:?}",
+            file_id, folder_id
+        );
+
+        let before = self.file_before_change(file_id).await;
+        let moved_file = self
+            .file_repository
+            .move_file(file_id, folder_id)
+            .await
+            .map_err(|e| {
+                error!("Error moving file (ID: {}): {}", file_id, e);
+                e
+            })?;
+
+        info!(
+            "File moved successfully: {} (ID: {}) to folder: {:?}",
+            moved_file.name(),
+            moved_file.id(),
+            moved_file.folder_id()
+        );
+        self.record_change(ActivityAction::FileMoved, before, &moved_file, actor_id);
+
+        Ok(FileDto::from(moved_file))
+    }
+
+    async fn rename_file_as(
+        &self,
+        file_id: &str,
+        new_name: &str,
+        actor_id: Option<Uuid>,
+    ) -> Result<FileDto, DomainError> {
+        if let Err(reason) = validate_storage_name(new_name) {
+            return Err(DomainError::validation_error(format!(
+                "Invalid file name '{new_name}': {reason}"
+            )));
+        }
+
+        info!("Renaming file with ID: {} to \"{}\"", file_id, new_name);
+
+        let before = self.file_before_change(file_id).await;
+        let renamed_file = self
+            .file_repository
+            .rename_file(file_id, new_name)
+            .await
+            .map_err(|e| {
+                error!("Error renaming file (ID: {}): {}", file_id, e);
+                e
+            })?;
+
+        info!(
+            "File renamed successfully: {} (ID: {})",
+            renamed_file.name(),
+            renamed_file.id()
+        );
+        self.record_change(ActivityAction::FileRenamed, before, &renamed_file, actor_id);
+
+        Ok(FileDto::from(renamed_file))
+    }
+
+    /// Verifies that `caller_id` may modify a file and returns its owner.
+    ///
+    /// Recipients need write access to the folder containing the file; a
+    /// file shared on its own can be read but not renamed, moved or deleted.
+    async fn verify_owner(&self, file_id: &str, caller_id: Uuid) -> Result<Uuid, DomainError> {
+        let Some(read) = &self.file_read else {
             // Fallback: no read repo injected — deny by default (fail-closed)
-            Err(DomainError::internal_error(
+            return Err(DomainError::internal_error(
                 "FileManagement",
                 "Ownership verification unavailable",
-            ))
+            ));
+        };
+        match read.verify_file_owner(file_id, caller_id).await {
+            Ok(()) => Ok(caller_id),
+            Err(e) if e.kind == ErrorKind::NotFound => {
+                let Some(shares) = &self.user_shares else {
+                    return Err(e);
+                };
+                let Some(access) = shares.file_access(file_id, caller_id).await? else {
+                    return Err(e);
+                };
+                let folder_access = match read.get_file(file_id).await?.folder_id() {
+                    Some(folder_id) => shares.folder_access(folder_id, caller_id).await?,
+                    None => None,
+                };
+                match folder_access {
+                    Some(folder_access) if folder_access.can_write => Ok(access.owner_id),
+                    _ => Err(DomainError::access_denied(
+                        "File",
+                        "You are not allowed to modify this shared file",
+                    )),
+                }
+            }
+            Err(e) => Err(e),
         }
     }
 
-    /// Verifies that the target folder is owned by the caller.
+    /// Verifies that `caller_id` may read a file (own or shared with them).
+    async fn verify_readable(&self, file_id: &str, caller_id: Uuid) -> Result<(), DomainError> {
+        let Some(read) = &self.file_read else {
+            return Err(DomainError::internal_error(
+                "FileManagement",
+                "Ownership verification unavailable",
+            ));
+        };
+        match read.verify_file_owner(file_id, caller_id).await {
+            Err(e) if e.kind == ErrorKind::NotFound => match &self.user_shares {
+                Some(shares) if shares.file_access(file_id, caller_id).await?.is_some() => Ok(()),
+                _ => Err(e),
+            },
+            other => other,
+        }
+    }
+
+    /// Verifies that the caller may add items to the target folder and
+    /// returns the folder's owner: the caller for their own folders, the
+    /// sharer for folders shared with write access.
     /// If folder_id is None (root), ownership is implicitly granted.
     async fn verify_target_folder_owner(
         &self,
         folder_id: &Option<String>,
         caller_id: Uuid,
-    ) -> Result<(), DomainError> {
+    ) -> Result<Uuid, DomainError> {
         let folder_id = match folder_id {
             Some(id) => id,
-            None => return Ok(()), // Moving to root is always allowed
+            None => return Ok(caller_id), // Moving to root is always allowed
         };
 
         if let Some(folder_repo) = &self.folder_repo {
             let folder_owner = folder_repo.get_folder_user_id(folder_id).await?;
-            if folder_owner != caller_id {
-                return Err(DomainError::not_found(
+            if folder_owner == caller_id {
+                return Ok(caller_id);
+            }
+            let access = match &self.user_shares {
+                Some(shares) => shares.folder_access(folder_id, caller_id).await?,
+                None => None,
+            };
+            match access {
+                Some(access) if access.can_write => Ok(access.owner_id),
+                Some(_) => Err(DomainError::access_denied(
+                    "Folder",
+                    "This shared folder is read-only",
+                )),
+                None => Err(DomainError::not_found(
                     "Folder",
                     "Target folder not found or access denied",
-                ));
+                )),
             }
-            Ok(())
         } else {
             // Fallback: no folder repo injected — deny by default (fail-closed)
             Err(DomainError::internal_error(
@@ -112,34 +362,32 @@ impl FileManagementService {
     }
 }
 
+impl FileManagementService {
+    /// Refuses a copy of `size` bytes that would not fit in `owner_id`'s quota.
+    async fn check_copy_quota(&self, owner_id: Uuid, size: u64) -> Result<(), DomainError> {
+        match &self.storage_usage {
+            Some(storage_usage) => storage_usage.check_storage_quota(owner_id, size).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Recounts `owner_id`'s usage after a copy.
+    async fn refresh_storage_usage(&self, owner_id: Uuid) {
+        if let Some(storage_usage) = &self.storage_usage
+            && let Err(e) = storage_usage.update_user_storage_usage(owner_id).await
+        {
+            warn!("Failed to update storage usage for {}: {}", owner_id, e);
+        }
+    }
+}
+
 impl FileManagementUseCase for FileManagementService {
     async fn move_file(
         &self,
         file_id: &str,
         folder_id: Option<String>,
     ) -> Result<FileDto, DomainError> {
-        info!(
-            "Moving file with ID: {} to folder: {:?}",
-            file_id, folder_id
-        );
-
-        let moved_file = self
-            .file_repository
-            .move_file(file_id, folder_id)
-            .await
-            .map_err(|e| {
-                error!("Error moving file (ID: {}): {}", file_id, e);
-                e
-            })?;
-
-        info!(
-            "File moved successfully: {} (ID: {}) to folder: {:?}",
-            moved_file.name(),
-            moved_file.id(),
-            moved_file.folder_id()
-        );
-
-        Ok(FileDto::from(moved_file))
+        self.move_file_as(file_id, folder_id, None).await
     }
 
     async fn move_file_owned(
@@ -149,11 +397,19 @@ impl FileManagementUseCase for FileManagementService {
         folder_id: Option<String>,
     ) -> Result<FileDto, DomainError> {
         // Verify file ownership first
-        self.verify_owner(file_id, caller_id).await?;
-        // Verify target folder ownership (prevents file from "disappearing")
-        self.verify_target_folder_owner(&folder_id, caller_id)
+        let owner_id = self.verify_owner(file_id, caller_id).await?;
+        // Verify target folder ownership (prevents file from "disappearing").
+        // Files never change owner by moving in or out of a shared folder.
+        let target_owner = self
+            .verify_target_folder_owner(&folder_id, caller_id)
             .await?;
-        self.move_file(file_id, folder_id).await
+        if target_owner != owner_id {
+            return Err(DomainError::access_denied(
+                "File",
+                "Cannot move a file between different users' folders",
+            ));
+        }
+        self.move_file_as(file_id, folder_id, Some(caller_id)).await
     }
 
     async fn copy_file(
@@ -181,6 +437,7 @@ impl FileManagementUseCase for FileManagementService {
             copied_file.id(),
             copied_file.folder_id()
         );
+        self.publish_change(LiveEventKind::FileCreated, &copied_file, None, None);
 
         Ok(FileDto::from(copied_file))
     }
@@ -191,37 +448,29 @@ impl FileManagementUseCase for FileManagementService {
         caller_id: Uuid,
         target_folder_id: Option<String>,
     ) -> Result<FileDto, DomainError> {
-        self.verify_owner(file_id, caller_id).await?;
-        self.verify_target_folder_owner(&target_folder_id, caller_id)
+        self.verify_readable(file_id, caller_id).await?;
+        let source = match &self.file_read {
+            Some(read) => read.get_file(file_id).await?,
+            None => {
+                return Err(DomainError::internal_error(
+                    "FileManagement",
+                    "Ownership verification unavailable",
+                ));
+            }
+        };
+        // Without a target the copy lands next to the source file
+        let target_folder_id = target_folder_id.or_else(|| source.folder_id().map(str::to_string));
+        let owner_id = self
+            .verify_target_folder_owner(&target_folder_id, caller_id)
             .await?;
-        self.copy_file(file_id, target_folder_id).await
+        self.check_copy_quota(owner_id, source.size()).await?;
+        let copied = self.copy_file(file_id, target_folder_id).await?;
+        self.refresh_storage_usage(owner_id).await;
+        Ok(copied)
     }
 
     async fn rename_file(&self, file_id: &str, new_name: &str) -> Result<FileDto, DomainError> {
-        if let Err(reason) = validate_storage_name(new_name) {
-            return Err(DomainError::validation_error(format!(
-                "Invalid file name '{new_name}': {reason}"
-            )));
-        }
-
-        info!("Renaming file with ID: {} to \"{}\"", file_id, new_name);
-
-        let renamed_file = self
-            .file_repository
-            .rename_file(file_id, new_name)
-            .await
-            .map_err(|e| {
-                error!("Error renaming file (ID: {}): {}", file_id, e);
-                e
-            })?;
-
-        info!(
-            "File renamed successfully: {} (ID: {})",
-            renamed_file.name(),
-            renamed_file.id()
-        );
-
-        Ok(FileDto::from(renamed_file))
+        self.rename_file_as(file_id, new_name, None).await
     }
 
     async fn rename_file_owned(
@@ -231,10 +480,18 @@ impl FileManagementUseCase for FileManagementService {
         new_name: &str,
     ) -> Result<FileDto, DomainError> {
         self.verify_owner(file_id, caller_id).await?;
-        self.rename_file(file_id, new_name).await
+        self.rename_file_as(file_id, new_name, Some(caller_id))
+            .await
+    }
+
+    async fn set_modified_time(&self, file_id: &str, modified_at: i64) -> Result<(), DomainError> {
+        self.file_repository
+            .set_modified_time(file_id, modified_at)
+            .await
     }
 
     async fn delete_file(&self, id: &str) -> Result<(), DomainError> {
+        let before = self.file_before_change(id).await;
         self.file_repository.delete_file(id).await?;
         if let Some(cc) = &self.content_cache {
             cc.invalidate(id).await;
@@ -242,6 +499,9 @@ impl FileManagementUseCase for FileManagementService {
         for hook in &self.file_deleted_hooks {
             hook.on_file_deleted(id).await;
         }
+        if let Some(before) = before {
+            self.publish_change(LiveEventKind::FileDeleted, &before, None, None);
+        }
         Ok(())
     }
 
@@ -257,10 +517,14 @@ impl FileManagementUseCase for FileManagementService {
     /// We do NOT decrement here — trashing is a soft-delete (UPDATE, not DELETE)
     /// so the blob must remain referenced until the file is permanently deleted.
     async fn delete_with_cleanup(&self, id: &str, user_id: Uuid) -> Result<bool, DomainError> {
+        // Step 0: Verify access. Files deleted from a shared folder go to
+        // the trash of the folder's owner.
+        let owner_id = self.verify_owner(id, user_id).await?;
+
         // Step 1: Try trash (soft delete — file row stays, blob stays referenced)
         if let Some(trash) = &self.trash_service {
             info!("Moving file to trash: {}", id);
-            match trash.move_to_trash(id, "file", user_id).await {
+            match trash.move_to_trash(id, "file", owner_id).await {
                 Ok(_) => {
                     info!("File successfully moved to trash: {}", id);
                     // Invalidate content cache — trashed files must not be served.
@@ -284,6 +548,7 @@ impl FileManagementUseCase for FileManagementService {
 
         // Step 2: Permanent delete — trigger handles blob ref_count
         warn!("Permanently deleting file: {}", id);
+        let before = self.file_before_change(id).await;
         self.file_repository.delete_file(id).await?;
         if let Some(cc) = &self.content_cache {
             cc.invalidate(id).await;
@@ -291,6 +556,9 @@ impl FileManagementUseCase for FileManagementService {
         for hook in &self.file_deleted_hooks {
             hook.on_file_deleted(id).await;
         }
+        if let Some(before) = before {
+            self.publish_change(LiveEventKind::FileDeleted, &before, None, Some(user_id));
+        }
         info!("File permanently deleted: {}", id);
 
         Ok(false) // permanently deleted
@@ -323,6 +591,14 @@ impl FileManagementUseCase for FileManagementService {
             "Folder tree copied: {} folders, {} files (new root: {})",
             result.folders_copied, result.files_copied, result.new_root_folder_id
         );
+        if let (Some(live_events), Some(folder_repo)) = (&self.live_events, &self.folder_repo)
+            && let Ok(folder) = folder_repo.get_folder(&result.new_root_folder_id).await
+        {
+            let change = LiveChange::new(LiveEventKind::FolderCreated, folder.id())
+                .with_name(folder.name())
+                .with_folder(folder.parent_id());
+            live_events.publish(folder.owner_id(), change);
+        }
 
         Ok(result)
     }
@@ -334,23 +610,42 @@ impl FileManagementUseCase for FileManagementService {
         target_parent_id: Option<String>,
         dest_name: Option<String>,
     ) -> Result<CopyFolderTreeResult, DomainError> {
-        if let Some(folder_repo) = &self.folder_repo {
-            let owner = folder_repo.get_folder_user_id(source_folder_id).await?;
-            if owner != caller_id {
-                return Err(DomainError::not_found(
-                    "Folder",
-                    "Source folder not found or access denied",
-                ));
-            }
-        } else {
+        let Some(folder_repo) = &self.folder_repo else {
             return Err(DomainError::internal_error(
                 "FileManagement",
                 "Folder ownership verification unavailable",
             ));
+        };
+        let owner = folder_repo.get_folder_user_id(source_folder_id).await?;
+        let shared = match &self.user_shares {
+            Some(shares) if owner != caller_id => shares
+                .folder_access(source_folder_id, caller_id)
+                .await?
+                .is_some(),
+            _ => false,
+        };
+        if owner != caller_id && !shared {
+            return Err(DomainError::not_found(
+                "Folder",
+                "Source folder not found or access denied",
+            ));
+        }
+        // Copies to the root keep the source owner
+        if target_parent_id.is_none() && owner != caller_id {
+            return Err(DomainError::access_denied(
+                "Folder",
+                "A shared folder can only be copied into a folder",
+            ));
         }
-        self.verify_target_folder_owner(&target_parent_id, caller_id)
+        let owner_id = self
+            .verify_target_folder_owner(&target_parent_id, caller_id)
             .await?;
-        self.copy_folder_tree(source_folder_id, target_parent_id, dest_name)
-            .await
+        let size = folder_repo.get_subtree_file_size(source_folder_id).await?;
+        self.check_copy_quota(owner_id, size).await?;
+        let result = self
+            .copy_folder_tree(source_folder_id, target_parent_id, dest_name)
+            .await?;
+        self.refresh_storage_usage(owner_id).await;
+        Ok(result)
     }
 }
@@ -6,7 +6,10 @@ use std::sync::Arc;
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::ports::file_ports::{FileRetrievalUseCase, OptimizedFileContent};
 use crate::application::ports::storage_ports::FileReadPort;
-use crate::common::errors::DomainError;
+use crate::application::ports::user_share_ports::UserShareRepositoryPort;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::file::File;
+use crate::infrastructure::repositories::pg::UserSharePgRepository;
 use crate::infrastructure::repositories::pg::file_blob_read_repository::FileBlobReadRepository;
 use crate::infrastructure::services::file_content_cache::FileContentCache;
 use crate::infrastructure::services::image_transcode_service::{
@@ -29,6 +32,8 @@ pub struct FileRetrievalService {
     file_read: Arc<FileBlobReadRepository>,
     content_cache: Option<Arc<FileContentCache>>,
     transcode: Option<Arc<ImageTranscodeService>>,
+    /// Grants read access to files other users shared with the caller.
+    user_shares: Option<Arc<UserSharePgRepository>>,
 }
 
 impl FileRetrievalService {
@@ -38,6 +43,7 @@ impl FileRetrievalService {
             file_read: file_repository,
             content_cache: None,
             transcode: None,
+            user_shares: None,
         }
     }
 
@@ -51,11 +57,50 @@ impl FileRetrievalService {
             file_read,
             content_cache: Some(content_cache),
             transcode: Some(transcode),
+            user_shares: None,
         }
     }
 
+    /// Lets the `_owned` reads reach files shared with the caller.
+    pub fn with_user_shares(mut self, user_shares: Option<Arc<UserSharePgRepository>>) -> Self {
+        self.user_shares = user_shares;
+        self
+    }
+
     // ── private helpers ──────────────────────────────────────────
 
+    /// Owner-scoped lookup that falls back to the file's owner when the
+    /// file was shared with `caller_id`, directly or through a folder.
+    async fn get_file_readable(&self, id: &str, caller_id: Uuid) -> Result<File, DomainError> {
+        match self.file_read.get_file_for_owner(id, caller_id).await {
+            Err(e) if e.kind == ErrorKind::NotFound => {
+                let Some(shares) = &self.user_shares else {
+                    return Err(e);
+                };
+                match shares.file_access(id, caller_id).await? {
+                    Some(access) => self.file_read.get_file_for_owner(id, access.owner_id).await,
+                    None => Err(e),
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Owner whose files a listing of `folder_id` shows to `caller_id`:
+    /// the folder's owner when the folder was shared with the caller.
+    async fn listing_owner(
+        &self,
+        folder_id: Option<&str>,
+        caller_id: Uuid,
+    ) -> Result<Uuid, DomainError> {
+        if let (Some(folder_id), Some(shares)) = (folder_id, &self.user_shares)
+            && let Some(access) = shares.folder_access(folder_id, caller_id).await?
+        {
+            return Ok(access.owner_id);
+        }
+        Ok(caller_id)
+    }
+
     /// Try to transcode image content to WebP and return transcoded variant.
     async fn try_transcode(
         &self,
@@ -203,7 +248,7 @@ impl FileRetrievalUseCase for FileRetrievalService {
     }
 
     async fn get_file_owned(&self, id: &str, caller_id: Uuid) -> Result<FileDto, DomainError> {
-        let file = self.file_read.get_file_for_owner(id, caller_id).await?;
+        let file = self.get_file_readable(id, caller_id).await?;
         Ok(FileDto::from(file))
     }
 
@@ -229,6 +274,7 @@ impl FileRetrievalUseCase for FileRetrievalService {
         folder_id: Option<&str>,
         owner_id: Uuid,
     ) -> Result<Vec<FileDto>, DomainError> {
+        let owner_id = self.listing_owner(folder_id, owner_id).await?;
         let files = self
             .file_read
             .list_files_for_owner(folder_id, owner_id)
@@ -248,7 +294,7 @@ impl FileRetrievalUseCase for FileRetrievalService {
         id: &str,
         caller_id: Uuid,
     ) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
-        self.file_read.verify_file_owner(id, caller_id).await?;
+        self.get_file_readable(id, caller_id).await?;
         self.file_read.get_file_stream(id).await
     }
 
@@ -272,7 +318,7 @@ impl FileRetrievalUseCase for FileRetrievalService {
         accept_webp: bool,
         prefer_original: bool,
     ) -> Result<(FileDto, OptimizedFileContent), DomainError> {
-        let file = self.file_read.get_file_for_owner(id, caller_id).await?;
+        let file = self.get_file_readable(id, caller_id).await?;
         let dto = FileDto::from(file);
         self.optimized_inner(id, dto, accept_webp, prefer_original)
             .await
@@ -307,8 +353,8 @@ impl FileRetrievalUseCase for FileRetrievalService {
         start: u64,
         end: Option<u64>,
     ) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
-        // Verify ownership first, then delegate to the unscoped stream
-        self.file_read.verify_file_owner(id, caller_id).await?;
+        // Verify access first, then delegate to the unscoped stream
+        self.get_file_readable(id, caller_id).await?;
         self.file_read.get_file_range_stream(id, start, end).await
     }
 
@@ -341,6 +387,7 @@ impl FileRetrievalUseCase for FileRetrievalService {
         offset: i64,
         limit: i64,
     ) -> Result<Vec<FileDto>, DomainError> {
+        let owner_id = self.listing_owner(folder_id, owner_id).await?;
         let files = self
             .file_read
             .list_files_batch_for_owner(folder_id, owner_id, offset, limit)
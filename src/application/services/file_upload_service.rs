@@ -116,6 +116,14 @@ impl FileUploadService {
 
     // ── private helpers ──────────────────────────────────────────
 
+    /// Runs the file-created hooks for a newly stored file.
+    async fn notify_file_created(&self, dto: &FileDto) {
+        for hook in &self.file_created_hooks {
+            hook.on_file_created(&dto.id, &dto.etag, &dto.mime_type)
+                .await;
+        }
+    }
+
     /// Optionally update storage usage after a successful upload.
     fn maybe_update_storage_usage(&self, file: &FileDto) {
         if let Some(storage_service) = &self.storage_usage_service {
@@ -170,10 +178,7 @@ impl FileUploadUseCase for FileUploadService {
             name, size, dto.id
         );
         self.maybe_update_storage_usage(&dto);
-        for hook in &self.file_created_hooks {
-            hook.on_file_created(&dto.id, &dto.etag, &dto.mime_type)
-                .await;
-        }
+        self.notify_file_created(&dto).await;
         Ok(dto)
     }
 
@@ -254,6 +259,7 @@ impl FileUploadUseCase for FileUploadService {
             .await?;
         let dto = FileDto::from(file);
         self.maybe_update_storage_usage(&dto);
+        self.notify_file_created(&dto).await;
         Ok(dto)
     }
 
@@ -365,6 +371,12 @@ impl FileUploadUseCase for FileUploadService {
                 pre_computed_hash,
             )
             .await?;
-        Ok(FileDto::from(created))
+        let mut dto = FileDto::from(created);
+        if let Some(mtime) = modified_at {
+            self.file_write.set_modified_time(&dto.id, mtime).await?;
+            dto.modified_at = mtime.max(0) as u64;
+        }
+        self.notify_file_created(&dto).await;
+        Ok(dto)
     }
 }
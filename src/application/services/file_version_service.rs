@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::file_version_dto::FileVersionDto;
+use crate::application::ports::blob_storage_ports::BlobStream;
+use crate::application::ports::file_lifecycle::FileUpdatedHook;
+use crate::application::ports::file_version_ports::{
+    FileVersionRepositoryPort, FileVersionUseCase,
+};
+use crate::application::ports::storage_ports::{FileReadPort, FileWritePort};
+use crate::common::errors::Result;
+use crate::infrastructure::repositories::pg::{
+    FileBlobReadRepository, FileBlobWriteRepository, FileVersionPgRepository,
+};
+use crate::infrastructure::services::dedup_service::DedupService;
+use crate::infrastructure::services::file_content_cache::FileContentCache;
+
+/// Service for browsing and restoring previous versions of files.
+///
+/// Versions are recorded by `FileBlobWriteRepository` when a file's blob is
+/// swapped, so every overwrite path (WebDAV PUT, WOPI PutFile, Nextcloud
+/// chunked upload) is covered without any handler involvement.
+pub struct FileVersionService {
+    repo: Arc<FileVersionPgRepository>,
+    file_read: Arc<FileBlobReadRepository>,
+    file_write: Arc<FileBlobWriteRepository>,
+    dedup: Arc<DedupService>,
+    /// Content cache — invalidated on restore so stale content is never served.
+    content_cache: Option<Arc<FileContentCache>>,
+    /// Hooks fired after a restore replaces the file's blob.
+    file_updated_hooks: Vec<Arc<dyn FileUpdatedHook>>,
+}
+
+impl FileVersionService {
+    pub fn new(
+        repo: Arc<FileVersionPgRepository>,
+        file_read: Arc<FileBlobReadRepository>,
+        file_write: Arc<FileBlobWriteRepository>,
+        dedup: Arc<DedupService>,
+    ) -> Self {
+        Self {
+            repo,
+            file_read,
+            file_write,
+            dedup,
+            content_cache: None,
+            file_updated_hooks: Vec::new(),
+        }
+    }
+
+    /// Configures the content cache for invalidation on restore.
+    pub fn with_content_cache(mut self, cache: Arc<FileContentCache>) -> Self {
+        self.content_cache = Some(cache);
+        self
+    }
+
+    /// Registers a hook to fire after a restore replaces a file's blob.
+    pub fn with_file_updated_hook(mut self, hook: Arc<dyn FileUpdatedHook>) -> Self {
+        self.file_updated_hooks.push(hook);
+        self
+    }
+}
+
+impl FileVersionUseCase for FileVersionService {
+    async fn list_versions(&self, file_id: &str, owner_id: Uuid) -> Result<Vec<FileVersionDto>> {
+        // Ownership check first so a foreign file yields 404, not an empty list.
+        self.file_read.verify_file_owner(file_id, owner_id).await?;
+        self.repo.list_versions(file_id, owner_id).await
+    }
+
+    async fn get_version_content(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<(FileVersionDto, BlobStream)> {
+        let version = self.repo.get_version(file_id, version_id, owner_id).await?;
+        let stream = self.dedup.read_blob_stream(&version.etag).await?;
+        Ok((version, stream))
+    }
+
+    async fn restore_version(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<FileDto> {
+        let version = self.repo.get_version(file_id, version_id, owner_id).await?;
+
+        self.file_write
+            .restore_blob_hash(file_id, &version.etag, version.size)
+            .await?;
+
+        if let Some(cc) = &self.content_cache {
+            cc.invalidate(file_id).await;
+        }
+
+        let dto = FileDto::from(self.file_read.get_file_for_owner(file_id, owner_id).await?);
+        for hook in &self.file_updated_hooks {
+            hook.on_file_updated(file_id, &dto.etag, &dto.mime_type)
+                .await;
+        }
+
+        info!(
+            "Restored version {} of file {} for user {}",
+            version_id, file_id, owner_id
+        );
+        Ok(dto)
+    }
+
+    async fn delete_version(&self, file_id: &str, version_id: &str, owner_id: Uuid) -> Result<()> {
+        let blob_hash = self
+            .repo
+            .delete_version(file_id, version_id, owner_id)
+            .await?;
+        // The PG trigger has decremented the ref_count; reclaim the blob if
+        // nothing else references it.
+        self.dedup.cleanup_if_orphaned(&blob_hash).await;
+        Ok(())
+    }
+}
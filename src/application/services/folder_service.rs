@@ -1,10 +1,17 @@
 use crate::application::dtos::folder_dto::{
     CreateFolderDto, FolderDto, MoveFolderDto, RenameFolderDto,
 };
+use crate::application::ports::activity_ports::{ActivityAction, NewActivity};
 use crate::application::ports::inbound::FolderUseCase;
+use crate::application::ports::live_event_ports::{LiveChange, LiveEventKind};
+use crate::application::ports::user_share_ports::{SharedAccess, UserShareRepositoryPort};
+use crate::application::services::activity_service::ActivityService;
+use crate::application::services::live_event_service::LiveEventService;
 use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::folder::Folder;
 use crate::domain::repositories::folder_repository::FolderRepository;
 use crate::domain::services::path_service::{StoragePath, validate_storage_name};
+use crate::infrastructure::repositories::pg::UserSharePgRepository;
 use crate::infrastructure::repositories::pg::folder_db_repository::FolderDbRepository;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -12,12 +19,166 @@ use uuid::Uuid;
 /// Implementation of the use case for folder operations
 pub struct FolderService {
     folder_storage: Arc<FolderDbRepository>,
+    /// Grants access to folders other users shared with the caller.
+    user_shares: Option<Arc<UserSharePgRepository>>,
+    /// Records folder changes.
+    activity: Option<Arc<ActivityService>>,
+    /// Tells subscribed clients about folder changes.
+    live_events: Option<Arc<LiveEventService>>,
 }
 
 impl FolderService {
     /// Creates a new folder service
     pub fn new(folder_storage: Arc<FolderDbRepository>) -> Self {
-        Self { folder_storage }
+        Self {
+            folder_storage,
+            user_shares: None,
+            activity: None,
+            live_events: None,
+        }
+    }
+
+    /// Lets callers reach folders shared with them.
+    pub fn with_user_shares(mut self, user_shares: Option<Arc<UserSharePgRepository>>) -> Self {
+        self.user_shares = user_shares;
+        self
+    }
+
+    /// Records folder changes in the activity log.
+    pub fn with_activity(mut self, activity: Option<Arc<ActivityService>>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Publishes folder changes to subscribed clients.
+    pub fn with_live_events(mut self, live_events: Option<Arc<LiveEventService>>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
+    /// Logs a change to `folder` by `actor_id`, or by the owner when the
+    /// caller is unknown, and publishes it.  `previous_parent` is where a
+    /// moved folder was before.
+    fn record_change(
+        &self,
+        action: ActivityAction,
+        folder: &Folder,
+        actor_id: Option<Uuid>,
+        previous_parent: Option<&str>,
+        details: &[(&str, &str)],
+    ) {
+        if let Some(live_events) = &self.live_events
+            && let Some(kind) = LiveEventKind::from_activity(action)
+        {
+            let change = LiveChange::new(kind, folder.id())
+                .with_name(folder.name())
+                .with_folder(folder.parent_id())
+                .with_previous_folder(previous_parent);
+            live_events.publish(folder.owner_id().into_iter().chain(actor_id), change);
+        }
+        let Some(activity) = &self.activity else {
+            return;
+        };
+        let mut entry = NewActivity::new(action).with_item(folder.id(), folder.name());
+        if let Some(owner_id) = folder.owner_id() {
+            entry = entry.with_owner(owner_id);
+        }
+        if let Some(actor_id) = actor_id.or(folder.owner_id()) {
+            entry = entry.with_actor(actor_id);
+        }
+        for (key, value) in details {
+            entry = entry.with_detail(key, *value);
+        }
+        activity.record(entry);
+    }
+
+    /// Access of `caller_id` to a folder through user shares.
+    async fn shared_access(
+        &self,
+        folder_id: &str,
+        caller_id: Uuid,
+    ) -> Result<Option<SharedAccess>, DomainError> {
+        match &self.user_shares {
+            Some(shares) => shares.folder_access(folder_id, caller_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Owner of a folder `caller_id` may create files and folders in: the
+    /// caller's own folder, or a folder shared with them with write access.
+    pub async fn writable_folder_owner(
+        &self,
+        folder_id: &str,
+        caller_id: Uuid,
+    ) -> Result<Uuid, DomainError> {
+        let folder = self
+            .folder_storage
+            .get_folder(folder_id)
+            .await
+            .map_err(|_| DomainError::not_found("Folder", folder_id))?;
+        if folder.owner_id() == Some(caller_id) {
+            return Ok(caller_id);
+        }
+        match self.shared_access(folder_id, caller_id).await? {
+            Some(access) if access.can_write => Ok(access.owner_id),
+            Some(_) => Err(DomainError::access_denied(
+                "Folder",
+                "This shared folder is read-only",
+            )),
+            None => Err(DomainError::not_found("Folder", folder_id)),
+        }
+    }
+
+    /// Owner of a folder `caller_id` may rename, move or delete.
+    ///
+    /// Recipients need write access to the folder's parent, so the root of
+    /// a share can only be changed by its owner.
+    pub async fn modifiable_folder_owner(
+        &self,
+        folder_id: &str,
+        caller_id: Uuid,
+    ) -> Result<Uuid, DomainError> {
+        let folder = self.folder_storage.get_folder(folder_id).await?;
+        self.verify_can_modify(&folder, caller_id).await
+    }
+
+    async fn verify_can_modify(
+        &self,
+        folder: &Folder,
+        caller_id: Uuid,
+    ) -> Result<Uuid, DomainError> {
+        if folder.owner_id() == Some(caller_id) {
+            return Ok(caller_id);
+        }
+        if self.shared_access(folder.id(), caller_id).await?.is_none() {
+            return Err(DomainError::not_found("Folder", folder.id()));
+        }
+        let parent_access = match folder.parent_id() {
+            Some(parent_id) => self.shared_access(parent_id, caller_id).await?,
+            None => None,
+        };
+        match parent_access {
+            Some(access) if access.can_write => Ok(access.owner_id),
+            _ => Err(DomainError::access_denied(
+                "Folder",
+                "You are not allowed to modify this shared folder",
+            )),
+        }
+    }
+
+    /// Owner whose folders a listing of `parent_id` shows to `caller_id`:
+    /// the parent's owner when the parent was shared with the caller.
+    async fn listing_owner(
+        &self,
+        parent_id: Option<&str>,
+        caller_id: Uuid,
+    ) -> Result<Uuid, DomainError> {
+        if let Some(parent_id) = parent_id
+            && let Some(access) = self.shared_access(parent_id, caller_id).await?
+        {
+            return Ok(access.owner_id);
+        }
+        Ok(caller_id)
     }
 
     /// Creates a stub implementation for testing and middleware
@@ -156,6 +317,7 @@ impl FolderUseCase for FolderService {
             .folder_storage
             .create_folder(dto.name, dto.parent_id)
             .await?;
+        self.record_change(ActivityAction::FolderCreated, &folder, None, None, &[]);
 
         // Convert to DTO
         Ok(FolderDto::from(folder))
@@ -198,10 +360,13 @@ impl FolderUseCase for FolderService {
         Ok(FolderDto::from(folder))
     }
 
-    /// Gets a folder by its ID, enforcing that `caller_id` is the owner.
+    /// Gets a folder by its ID, enforcing that `caller_id` is the owner or
+    /// that the folder was shared with them.
     async fn get_folder_owned(&self, id: &str, caller_id: Uuid) -> Result<FolderDto, DomainError> {
         let folder_dto = self.get_folder(id).await?;
-        if folder_dto.owner_id.as_deref() != Some(&caller_id.to_string()) {
+        if folder_dto.owner_id.as_deref() != Some(&caller_id.to_string())
+            && self.shared_access(id, caller_id).await?.is_none()
+        {
             tracing::warn!(
                 "get_folder_owned: user '{}' attempted to access folder '{}' owned by '{:?}'",
                 caller_id,
@@ -256,6 +421,7 @@ impl FolderUseCase for FolderService {
         parent_id: Option<&str>,
         owner_id: Uuid,
     ) -> Result<Vec<FolderDto>, DomainError> {
+        let owner_id = self.listing_owner(parent_id, owner_id).await?;
         let owner_id_short = {
             let s = owner_id.to_string();
             s[..8.min(s.len())].to_string()
@@ -349,6 +515,7 @@ impl FolderUseCase for FolderService {
     ) -> Result<crate::application::dtos::pagination::PaginatedResponseDto<FolderDto>, DomainError>
     {
         let pagination = pagination.validate_and_adjust();
+        let owner_id = self.listing_owner(parent_id, owner_id).await?;
 
         let (folders, total_items) = self
             .folder_storage
@@ -397,17 +564,17 @@ impl FolderUseCase for FolderService {
             )));
         }
 
-        // Verify the folder exists and belongs to the caller
+        // Verify the folder exists and the caller owns it or may write to it
         let existing_folder = self.folder_storage.get_folder(id).await?;
 
-        if existing_folder.owner_id() != Some(caller_id) {
+        if let Err(e) = self.verify_can_modify(&existing_folder, caller_id).await {
             tracing::warn!(
                 "rename_folder: user '{}' attempted to rename folder '{}' owned by '{:?}'",
                 caller_id,
                 id,
                 existing_folder.owner_id()
             );
-            return Err(DomainError::not_found("Folder", id));
+            return Err(e);
         }
 
         // Rename folder — UPDATE RETURNING gives us the updated row directly
@@ -421,6 +588,13 @@ impl FolderUseCase for FolderService {
                     format!("Failed to rename folder with ID: {}: {}", id, e),
                 )
             })?;
+        self.record_change(
+            ActivityAction::FolderRenamed,
+            &folder,
+            Some(caller_id),
+            None,
+            &[("old_name", existing_folder.name())],
+        );
 
         Ok(FolderDto::from(folder))
     }
@@ -432,20 +606,32 @@ impl FolderUseCase for FolderService {
         dto: MoveFolderDto,
         caller_id: Uuid,
     ) -> Result<FolderDto, DomainError> {
-        // Verify the source folder exists and belongs to the caller
+        // Verify the source folder exists and the caller owns it or may write to it
         let source_folder = self.folder_storage.get_folder(id).await?;
 
-        if source_folder.owner_id() != Some(caller_id) {
-            tracing::warn!(
-                "move_folder: user '{}' attempted to move folder '{}' owned by '{:?}'",
-                caller_id,
-                id,
-                source_folder.owner_id()
-            );
-            return Err(DomainError::not_found("Folder", id));
+        let owner_id = match self.verify_can_modify(&source_folder, caller_id).await {
+            Ok(owner_id) => owner_id,
+            Err(e) => {
+                tracing::warn!(
+                    "move_folder: user '{}' attempted to move folder '{}' owned by '{:?}'",
+                    caller_id,
+                    id,
+                    source_folder.owner_id()
+                );
+                return Err(e);
+            }
+        };
+
+        // Folders never leave their owner's tree: moving out of a shared
+        // folder into the caller's own tree (or back) is not allowed
+        if owner_id != caller_id && dto.parent_id.is_none() {
+            return Err(DomainError::access_denied(
+                "Folder",
+                "Cannot move a shared folder out of its owner's tree",
+            ));
         }
 
-        // If a parent_id is specified, verify it exists and belongs to the caller
+        // If a parent_id is specified, verify it exists and is writable by the caller
         if let Some(parent_id) = &dto.parent_id {
             // Verify we are not trying to move the folder into itself or one of its descendants
             if parent_id == id {
@@ -456,13 +642,15 @@ impl FolderUseCase for FolderService {
                 ));
             }
 
-            // Verify the destination exists and is owned by the caller
+            // Verify the destination is writable by the caller and
+            // belongs to the same owner as the source
             let parent = self
                 .folder_storage
                 .get_folder(parent_id)
                 .await
                 .map_err(|_| DomainError::not_found("Folder", parent_id))?;
-            if parent.owner_id() != Some(caller_id) {
+            let parent_owner = self.writable_folder_owner(parent_id, caller_id).await?;
+            if parent_owner != owner_id {
                 tracing::warn!(
                     "move_folder: user '{}' attempted to move into folder '{}' owned by '{:?}'",
                     caller_id,
@@ -487,23 +675,33 @@ impl FolderUseCase for FolderService {
                     format!("Failed to move folder with ID: {}: {}", id, e),
                 )
             })?;
+        self.record_change(
+            ActivityAction::FolderMoved,
+            &folder,
+            Some(caller_id),
+            source_folder.parent_id(),
+            &[
+                ("old_path", source_folder.path_string()),
+                ("new_path", folder.path_string()),
+            ],
+        );
 
         Ok(FolderDto::from(folder))
     }
 
     /// Deletes a folder after verifying ownership.
     async fn delete_folder(&self, id: &str, caller_id: Uuid) -> Result<(), DomainError> {
-        // Verify the folder exists and belongs to the caller
+        // Verify the folder exists and the caller owns it or may write to it
         let folder = self.folder_storage.get_folder(id).await?;
 
-        if folder.owner_id() != Some(caller_id) {
+        if let Err(e) = self.verify_can_modify(&folder, caller_id).await {
             tracing::warn!(
                 "delete_folder: user '{}' attempted to delete folder '{}' owned by '{:?}'",
                 caller_id,
                 id,
                 folder.owner_id()
             );
-            return Err(DomainError::not_found("Folder", id));
+            return Err(e);
         }
 
         // Delete the folder
@@ -512,6 +710,14 @@ impl FolderUseCase for FolderService {
                 "FolderStorage",
                 format!("Failed to delete folder with ID: {}: {}", id, e),
             )
-        })
+        })?;
+        self.record_change(
+            ActivityAction::FolderDeleted,
+            &folder,
+            Some(caller_id),
+            None,
+            &[],
+        );
+        Ok(())
     }
 }
@@ -0,0 +1,196 @@
+//! User groups: admin management, OIDC/LDAP group sync and group quota
+//! defaults.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::dtos::group_dto::{
+    CreateGroupDto, GroupDto, GroupMemberDto, UpdateGroupDto,
+};
+use crate::application::ports::group_ports::{GroupRepositoryPort, MembershipSource};
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::entities::group::{Group, GroupError};
+use crate::infrastructure::repositories::pg::GroupPgRepository;
+
+fn invalid(e: GroupError) -> DomainError {
+    DomainError::new(ErrorKind::InvalidInput, "Group", e.to_string())
+}
+
+/// Keeps the valid names of an OIDC groups claim or LDAP groups attribute,
+/// trimmed and without case-insensitive duplicates.
+fn normalize_claim(group_names: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    group_names
+        .iter()
+        .filter_map(|name| match Group::validate_name(name) {
+            Ok(name) => Some(name),
+            Err(e) => {
+                warn!("Ignoring external group {:?}: {}", name, e);
+                None
+            }
+        })
+        .filter(|name| seen.insert(name.to_lowercase()))
+        .collect()
+}
+
+/// Service for user groups and their memberships.
+pub struct GroupService {
+    repo: Arc<GroupPgRepository>,
+}
+
+impl GroupService {
+    pub fn new(repo: Arc<GroupPgRepository>) -> Self {
+        Self { repo }
+    }
+
+    async fn to_dto(&self, group: &Group) -> Result<GroupDto> {
+        let member_count = self.repo.member_count(group.id()).await?;
+        Ok(GroupDto::from_entity(group, member_count))
+    }
+
+    /// Raises the members' quotas to the group default after it changed.
+    async fn apply_group_quota(&self, group: &Group) -> Result<()> {
+        if group.default_quota_bytes().is_none() {
+            return Ok(());
+        }
+        let member_ids: Vec<Uuid> = self
+            .repo
+            .list_members(group.id())
+            .await?
+            .iter()
+            .filter_map(|m| Uuid::parse_str(&m.user_id).ok())
+            .collect();
+        let raised = self.repo.apply_quota_defaults(&member_ids).await?;
+        if raised > 0 {
+            info!(
+                "Raised the quota of {} member(s) of group '{}' to its default",
+                raised,
+                group.name()
+            );
+        }
+        Ok(())
+    }
+
+    /// Groups ordered by name, with the total number of groups.
+    pub async fn list_groups(&self, limit: i64, offset: i64) -> Result<(Vec<GroupDto>, u64)> {
+        let (groups, total) = self.repo.list_groups(offset, limit).await?;
+        let dtos = groups
+            .iter()
+            .map(|(group, count)| GroupDto::from_entity(group, *count))
+            .collect();
+        Ok((dtos, total))
+    }
+
+    pub async fn get_group(&self, id: Uuid) -> Result<GroupDto> {
+        let group = self.repo.get_group(id).await?;
+        self.to_dto(&group).await
+    }
+
+    pub async fn create_group(&self, dto: CreateGroupDto) -> Result<GroupDto> {
+        let group =
+            Group::new(dto.name, dto.description, dto.default_quota_bytes).map_err(invalid)?;
+        let created = self.repo.create_group(&group).await?;
+        info!("Group created: {} ({})", created.name(), created.id());
+        Ok(GroupDto::from_entity(&created, 0))
+    }
+
+    pub async fn update_group(&self, id: Uuid, dto: UpdateGroupDto) -> Result<GroupDto> {
+        let mut group = self.repo.get_group(id).await?;
+        if let Some(name) = dto.name {
+            group.rename(&name).map_err(invalid)?;
+        }
+        if let Some(description) = dto.description {
+            group.set_description(Some(description));
+        }
+        let quota_changed = dto.default_quota_bytes.is_some();
+        if let Some(quota) = dto.default_quota_bytes {
+            group
+                .set_default_quota(Some(quota).filter(|&q| q != 0))
+                .map_err(invalid)?;
+        }
+
+        let updated = self.repo.update_group(&group).await?;
+        if quota_changed {
+            self.apply_group_quota(&updated).await?;
+        }
+        self.to_dto(&updated).await
+    }
+
+    pub async fn delete_group(&self, id: Uuid) -> Result<()> {
+        self.repo.delete_group(id).await?;
+        info!("Group deleted: {}", id);
+        Ok(())
+    }
+
+    pub async fn list_members(&self, group_id: Uuid) -> Result<Vec<GroupMemberDto>> {
+        // 404 for an unknown group rather than an empty list
+        self.repo.get_group(group_id).await?;
+        self.repo.list_members(group_id).await
+    }
+
+    /// Adds a user to a group and raises their quota to the group default.
+    pub async fn add_member(&self, group_id: Uuid, user_id: Uuid) -> Result<()> {
+        self.repo.get_group(group_id).await?;
+        self.repo
+            .add_member(group_id, user_id, MembershipSource::Manual)
+            .await?;
+        self.repo.apply_quota_defaults(&[user_id]).await?;
+        Ok(())
+    }
+
+    /// Removes a user from a group.  Their quota is left as it is.
+    pub async fn remove_member(&self, group_id: Uuid, user_id: Uuid) -> Result<()> {
+        if !self.repo.remove_member(group_id, user_id).await? {
+            return Err(DomainError::not_found(
+                "Group",
+                format!("user {user_id} is not a member of group {group_id}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Groups a user belongs to.
+    pub async fn groups_of_user(&self, user_id: Uuid) -> Result<Vec<GroupDto>> {
+        let groups = self.repo.groups_of_user(user_id).await?;
+        let mut dtos = Vec::with_capacity(groups.len());
+        for group in &groups {
+            dtos.push(self.to_dto(group).await?);
+        }
+        Ok(dtos)
+    }
+
+    /// Mirrors the groups reported by an OIDC or LDAP login: the user's
+    /// memberships from `source` become exactly the listed groups, which
+    /// are created if needed.  Memberships added by an admin are kept.
+    pub async fn sync_external_groups(
+        &self,
+        user_id: Uuid,
+        source: MembershipSource,
+        group_names: &[String],
+    ) -> Result<()> {
+        let names = normalize_claim(group_names);
+        self.repo.sync_memberships(user_id, source, &names).await?;
+        self.repo.apply_quota_defaults(&[user_id]).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_claim_trims_dedups_and_drops_invalid_names() {
+        let claim = vec![
+            " Staff ".to_string(),
+            "staff".to_string(),
+            String::new(),
+            "/org/finance".to_string(),
+            "bad\u{0}name".to_string(),
+        ];
+        assert_eq!(normalize_claim(&claim), vec!["Staff", "/org/finance"]);
+    }
+}
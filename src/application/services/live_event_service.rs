@@ -0,0 +1,300 @@
+//! Live change notifications: clients subscribe to the changes of their
+//! files, folders, shares and notifications instead of polling listings.
+//!
+//! Services publish a change as they make it.  It goes to the users they
+//! name — the owner and whoever made the change — and, for files and
+//! folders, to the recipients of shares that reach the item, looked up in
+//! the background.  Subscribers receive their changes from an in-process
+//! broadcast channel; with the PostgreSQL relay, changes made on other
+//! instances arrive there too.  Changes are not stored: a subscriber that
+//! falls behind, or may have missed changes from other instances, is told
+//! to reload instead.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Stream;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::application::ports::file_lifecycle::{FileCreatedHook, FileUpdatedHook};
+use crate::application::ports::live_event_ports::{
+    LiveChange, LiveEvent, LiveEventKind, LiveEventRelayPort,
+};
+use crate::application::ports::storage_ports::FileReadPort;
+use crate::application::ports::user_share_ports::UserShareRepositoryPort;
+use crate::common::config::LiveEventsConfig;
+use crate::infrastructure::repositories::pg::UserSharePgRepository;
+use crate::infrastructure::repositories::pg::file_blob_read_repository::FileBlobReadRepository;
+use crate::infrastructure::services::pg_live_event_relay::PgLiveEventRelay;
+
+/// Changes buffered for slow subscribers before they are told to reload
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// What travels through the broadcast channel.
+#[derive(Debug)]
+enum Broadcast {
+    Event(LiveEvent),
+    /// Changes may have been lost; every subscriber reloads
+    Resync,
+}
+
+/// What a subscriber receives.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiveMessage {
+    Change(LiveChange),
+    /// Changes were missed; reload what is shown
+    Resync,
+}
+
+/// The messages for `user_id` among those broadcast to `receiver`.
+fn messages_for(
+    mut receiver: broadcast::Receiver<Arc<Broadcast>>,
+    user_id: Uuid,
+) -> impl Stream<Item = LiveMessage> + Send + 'static {
+    async_stream::stream! {
+        loop {
+            match receiver.recv().await {
+                Ok(message) => match message.as_ref() {
+                    Broadcast::Event(event) if event.users.contains(&user_id) => {
+                        yield LiveMessage::Change(event.change.clone());
+                    }
+                    Broadcast::Event(_) => {}
+                    Broadcast::Resync => yield LiveMessage::Resync,
+                },
+                Err(RecvError::Lagged(missed)) => {
+                    debug!("Live event subscriber of {} missed {} changes", user_id, missed);
+                    yield LiveMessage::Resync;
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+/// Service for publishing and subscribing to live changes.
+pub struct LiveEventService {
+    sender: broadcast::Sender<Arc<Broadcast>>,
+    /// Resolves owner, name and folder of files reported by the lifecycle hooks
+    file_read: Arc<FileBlobReadRepository>,
+    /// Finds the recipients of shares that reach a changed item
+    user_shares: Option<Arc<UserSharePgRepository>>,
+    /// Carries changes to the other instances
+    relay: Option<Arc<PgLiveEventRelay>>,
+    keepalive: Duration,
+}
+
+impl LiveEventService {
+    pub fn new(file_read: Arc<FileBlobReadRepository>, config: &LiveEventsConfig) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            file_read,
+            user_shares: None,
+            relay: None,
+            keepalive: Duration::from_secs(config.keepalive_secs.max(1)),
+        }
+    }
+
+    /// Also delivers file and folder changes to the users they are shared with.
+    pub fn with_user_shares(mut self, user_shares: Option<Arc<UserSharePgRepository>>) -> Self {
+        self.user_shares = user_shares;
+        self
+    }
+
+    /// Relays changes to the other instances sharing the database.
+    pub fn with_relay(mut self, relay: Option<Arc<PgLiveEventRelay>>) -> Self {
+        self.relay = relay;
+        self
+    }
+
+    /// Interval of keep-alive messages on idle connections.
+    pub fn keepalive(&self) -> Duration {
+        self.keepalive
+    }
+
+    /// Publishes a change to `users` in the background, and for files and
+    /// folders to the recipients of shares that reach the item.
+    pub fn publish(&self, users: impl IntoIterator<Item = Uuid>, change: LiveChange) {
+        let mut users: Vec<Uuid> = users.into_iter().collect();
+        let user_shares = match change.kind.item_type() {
+            "file" | "folder" => self.user_shares.clone(),
+            _ => None,
+        };
+        let sender = self.sender.clone();
+        let relay = self.relay.clone();
+        tokio::spawn(async move {
+            if let Some(shares) = user_shares {
+                let folders: Vec<&str> = [&change.folder_id, &change.previous_folder_id]
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect();
+                match shares.recipients(&change.item_id, &folders).await {
+                    Ok(recipients) => users.extend(recipients),
+                    Err(e) => warn!(
+                        "Could not find who shares {} {}: {}",
+                        change.kind.item_type(),
+                        change.item_id,
+                        e
+                    ),
+                }
+            }
+            let mut seen = HashSet::new();
+            users.retain(|user| seen.insert(*user));
+            if users.is_empty() {
+                return;
+            }
+
+            let event = LiveEvent { users, change };
+            if let Some(relay) = relay
+                && let Err(e) = relay.publish(&event).await
+            {
+                warn!(
+                    "Could not relay {} to other instances: {}",
+                    event.change.kind.as_str(),
+                    e
+                );
+            }
+            // No receivers is not an error: nobody is listening right now
+            let _ = sender.send(Arc::new(Broadcast::Event(event)));
+        });
+    }
+
+    /// Hands a change published on another instance to local subscribers.
+    pub fn deliver(&self, event: LiveEvent) {
+        let _ = self.sender.send(Arc::new(Broadcast::Event(event)));
+    }
+
+    /// Tells every subscriber to reload, e.g. after the relay lost changes.
+    pub fn resync_all(&self) {
+        let _ = self.sender.send(Arc::new(Broadcast::Resync));
+    }
+
+    /// Changes for `user_id` from now on.  The stream ends when the service
+    /// is dropped.
+    pub fn subscribe(&self, user_id: Uuid) -> impl Stream<Item = LiveMessage> + Send + use<> {
+        messages_for(self.sender.subscribe(), user_id)
+    }
+
+    /// Publishes a change to a file reported by a lifecycle hook.
+    async fn publish_file_change(&self, kind: LiveEventKind, file_id: &str) {
+        match self.file_read.get_file(file_id).await {
+            Ok(file) => {
+                let change = LiveChange::new(kind, file.id())
+                    .with_name(file.name())
+                    .with_folder(file.folder_id());
+                self.publish(file.owner_id(), change);
+            }
+            Err(e) => warn!("Could not look up file {} for live events: {}", file_id, e),
+        }
+    }
+}
+
+impl FileCreatedHook for LiveEventService {
+    fn on_file_created<'a>(
+        &'a self,
+        file_id: &'a str,
+        _blob_hash: &'a str,
+        _content_type: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(self.publish_file_change(LiveEventKind::FileCreated, file_id))
+    }
+}
+
+impl FileUpdatedHook for LiveEventService {
+    fn on_file_updated<'a>(
+        &'a self,
+        file_id: &'a str,
+        _blob_hash: &'a str,
+        _content_type: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(self.publish_file_change(LiveEventKind::FileUpdated, file_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn hub() -> broadcast::Sender<Arc<Broadcast>> {
+        broadcast::channel(4).0
+    }
+
+    #[test]
+    fn change_serializes_without_empty_fields() {
+        let mut change = LiveChange::new(LiveEventKind::FileMoved, "f1")
+            .with_name("a.txt")
+            .with_folder(Some("d2"))
+            .with_previous_folder(Some("d1"));
+        change.occurred_at = "2026-10-18T12:00:00Z".parse().unwrap();
+        assert_eq!(
+            serde_json::to_value(&change).unwrap(),
+            serde_json::json!({
+                "kind": "file_moved",
+                "item_id": "f1",
+                "name": "a.txt",
+                "folder_id": "d2",
+                "previous_folder_id": "d1",
+                "occurred_at": "2026-10-18T12:00:00Z",
+            })
+        );
+
+        let bare = LiveChange::new(LiveEventKind::NotificationCreated, "7");
+        let value = serde_json::to_value(&bare).unwrap();
+        assert!(value.get("name").is_none());
+        assert!(value.get("folder_id").is_none());
+        let event = LiveEvent {
+            users: vec![Uuid::nil()],
+            change: bare,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<LiveEvent>(&json).unwrap(), event);
+    }
+
+    #[tokio::test]
+    async fn subscribers_only_receive_their_changes() {
+        let sender = hub();
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        let mut stream = Box::pin(messages_for(sender.subscribe(), alice));
+
+        let for_bob = LiveChange::new(LiveEventKind::FolderCreated, "x");
+        let for_both = LiveChange::new(LiveEventKind::FileCreated, "y");
+        sender
+            .send(Arc::new(Broadcast::Event(LiveEvent {
+                users: vec![bob],
+                change: for_bob,
+            })))
+            .unwrap();
+        sender
+            .send(Arc::new(Broadcast::Event(LiveEvent {
+                users: vec![bob, alice],
+                change: for_both.clone(),
+            })))
+            .unwrap();
+
+        assert_eq!(stream.next().await, Some(LiveMessage::Change(for_both)));
+    }
+
+    #[tokio::test]
+    async fn lagging_subscribers_are_told_to_reload() {
+        let sender = hub();
+        let user = Uuid::new_v4();
+        let mut stream = Box::pin(messages_for(sender.subscribe(), user));
+        for i in 0..10 {
+            sender
+                .send(Arc::new(Broadcast::Event(LiveEvent {
+                    users: vec![user],
+                    change: LiveChange::new(LiveEventKind::FileUpdated, i.to_string()),
+                })))
+                .unwrap();
+        }
+        assert_eq!(stream.next().await, Some(LiveMessage::Resync));
+    }
+}
@@ -1,27 +1,43 @@
+pub mod account_recovery_service;
+pub mod activity_service;
 pub mod admin_settings_service;
 pub mod app_password_service;
 pub mod auth_application_service;
 pub mod batch_operations;
+pub mod calendar_scheduling_service;
 pub mod calendar_service;
 pub mod contact_service;
+pub mod dead_property_service;
 pub mod device_auth_service;
+pub mod e2ee_service;
 pub mod favorites_service;
 pub mod file_management_service;
 pub mod file_retrieval_service;
 pub mod file_upload_service;
 pub mod file_use_case_factory;
+pub mod file_version_service;
 pub mod folder_service;
+pub mod group_service;
 pub mod i18n_application_service;
+pub mod live_event_service;
 pub mod music_service;
 pub mod nextcloud_file_id_service;
 pub mod nextcloud_login_flow_service;
+pub mod notification_service;
 pub mod recent_service;
 pub mod search_service;
 pub mod share_browse_service;
 pub mod share_service;
+pub mod share_upload_service;
+pub mod snapshot_service;
 pub mod storage_settings_service;
 pub mod storage_usage_service;
 pub mod trash_service;
+pub mod two_factor_service;
+pub mod user_session_service;
+pub mod user_share_service;
+pub mod webauthn_service;
+pub mod webhook_service;
 pub mod wopi_lock_service;
 pub mod wopi_token_service;
 
@@ -0,0 +1,310 @@
+//! Notifications: messages for a user about things that need their
+//! attention, listed by the web UI and by Nextcloud clients.
+//!
+//! Shares create theirs when they happen.  Storage quotas and expiring
+//! shared links are conditions rather than events, so a periodic check
+//! looks for them and remembers whom it told, notifying once per
+//! condition.  Creating a notification never fails the operation that
+//! caused it.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::application::dtos::display_helpers::format_file_size;
+use crate::application::dtos::notification_dto::{NotificationDto, NotificationListDto};
+use crate::application::dtos::user_share_dto::UserShareDto;
+use crate::application::ports::live_event_ports::{LiveChange, LiveEventKind};
+use crate::application::ports::notification_ports::{
+    ExpiringLink, NewNotification, NotificationKind, NotificationRepositoryPort, QuotaUsage,
+};
+use crate::application::services::live_event_service::LiveEventService;
+use crate::common::errors::{DomainError, Result};
+use crate::infrastructure::repositories::pg::NotificationPgRepository;
+
+/// Storage use, in percent of the quota, that triggers a warning
+pub const QUOTA_WARNING_PERCENT: u8 = 90;
+/// How long before a shared link expires its creator is told
+const LINK_EXPIRY_NOTICE_HOURS: i64 = 24;
+/// Notifications older than this are deleted
+const RETENTION_DAYS: i64 = 90;
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 200;
+
+/// Key under which a quota warning is remembered
+const QUOTA_KEY: &str = "quota_warning";
+
+fn quota_notification(usage: &QuotaUsage) -> NewNotification {
+    let percent = usage.used_bytes.saturating_mul(100) / usage.quota_bytes.max(1);
+    NewNotification {
+        user_id: usage.user_id,
+        kind: NotificationKind::QuotaWarning,
+        subject: format!("You have used {percent}% of your storage"),
+        message: format!(
+            "{} of {} used. Delete files or empty the trash to free up space.",
+            format_file_size(usage.used_bytes.max(0) as u64),
+            format_file_size(usage.quota_bytes as u64)
+        ),
+        link: Some("/profile".to_string()),
+        object_type: "quota",
+        object_id: usage.user_id.to_string(),
+    }
+}
+
+/// The notification for an expiring link, and the key it is sent under.
+/// The key includes the expiry time, so a link whose expiry is extended
+/// is reported again.
+fn expiring_link_notification(link: &ExpiringLink) -> (NewNotification, String) {
+    let expires = DateTime::<Utc>::from_timestamp(link.expires_at, 0).unwrap_or_default();
+    let notification = NewNotification {
+        user_id: link.owner_id,
+        kind: NotificationKind::ShareExpiring,
+        subject: format!("Your shared link to \"{}\" expires soon", link.item_name),
+        message: format!(
+            "The link stops working on {}.",
+            expires.format("%Y-%m-%d %H:%M UTC")
+        ),
+        link: Some("/#/shared".to_string()),
+        object_type: "link",
+        object_id: link.share_id.clone(),
+    };
+    let key = format!("share_expiring:{}:{}", link.share_id, link.expires_at);
+    (notification, key)
+}
+
+fn share_notification(share: &UserShareDto, recipient_id: Uuid) -> NewNotification {
+    let link = if share.item_type == "folder" {
+        format!("/#/files/folder/{}", share.item_id)
+    } else {
+        "/#/files".to_string()
+    };
+    NewNotification {
+        user_id: recipient_id,
+        kind: NotificationKind::ShareReceived,
+        subject: format!(
+            "{} shared \"{}\" with you",
+            share.shared_by_name, share.mount_name
+        ),
+        message: format!("The {} is now in your files.", share.item_type),
+        link: Some(link),
+        object_type: "share",
+        object_id: share.id.clone(),
+    }
+}
+
+/// Service for creating, listing and dismissing notifications.
+pub struct NotificationService {
+    repository: Arc<NotificationPgRepository>,
+    /// Tells the user's clients about new notifications
+    live_events: Option<Arc<LiveEventService>>,
+}
+
+impl NotificationService {
+    pub fn new(repository: Arc<NotificationPgRepository>) -> Self {
+        Self {
+            repository,
+            live_events: None,
+        }
+    }
+
+    /// Publishes new notifications to subscribed clients.
+    pub fn with_live_events(mut self, live_events: Option<Arc<LiveEventService>>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
+    fn publish_created(live_events: Option<&LiveEventService>, notification: &NewNotification) {
+        if let Some(live_events) = live_events {
+            let change = LiveChange::new(
+                LiveEventKind::NotificationCreated,
+                notification.object_id.clone(),
+            )
+            .with_name(notification.subject.clone());
+            live_events.publish([notification.user_id], change);
+        }
+    }
+
+    /// Creates a notification in the background.
+    pub fn notify(&self, notification: NewNotification) {
+        let repository = self.repository.clone();
+        let live_events = self.live_events.clone();
+        tokio::spawn(async move {
+            match repository.create(&notification).await {
+                Ok(()) => Self::publish_created(live_events.as_deref(), &notification),
+                Err(e) => warn!(
+                    "Could not create {} notification: {}",
+                    notification.kind.as_str(),
+                    e
+                ),
+            }
+        });
+    }
+
+    /// Tells the recipient of a new share about it.
+    pub fn share_received(&self, share: &UserShareDto) {
+        match Uuid::parse_str(&share.recipient_id) {
+            Ok(recipient_id) => self.notify(share_notification(share, recipient_id)),
+            Err(_) => warn!("Share {} has an invalid recipient id", share.id),
+        }
+    }
+
+    /// Withdraws the notifications about a share that was removed.
+    pub fn share_removed(&self, share_id: &str) {
+        let repository = self.repository.clone();
+        let share_id = share_id.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = repository.delete_for_object("share", &share_id).await {
+                warn!(
+                    "Could not withdraw notifications of share {}: {}",
+                    share_id, e
+                );
+            }
+        });
+    }
+
+    /// The user's latest notifications and their unread count.
+    pub async fn list(&self, user_id: Uuid, limit: Option<usize>) -> Result<NotificationListDto> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let (records, unread) = self.repository.list(user_id, limit).await?;
+        Ok(NotificationListDto {
+            notifications: records.into_iter().map(NotificationDto::from).collect(),
+            unread,
+        })
+    }
+
+    pub async fn get(&self, user_id: Uuid, id: i64) -> Result<NotificationDto> {
+        self.repository
+            .get(user_id, id)
+            .await?
+            .map(NotificationDto::from)
+            .ok_or_else(|| DomainError::not_found("Notification", id.to_string()))
+    }
+
+    /// Marks one notification, or with `None` all of them, as read.
+    pub async fn mark_read(&self, user_id: Uuid, id: Option<i64>) -> Result<()> {
+        if let Some(id) = id {
+            self.get(user_id, id).await?;
+        }
+        self.repository.mark_read(user_id, id).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, user_id: Uuid, id: i64) -> Result<()> {
+        if self.repository.delete(user_id, Some(id)).await? == 0 {
+            return Err(DomainError::not_found("Notification", id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_all(&self, user_id: Uuid) -> Result<()> {
+        self.repository.delete(user_id, None).await?;
+        Ok(())
+    }
+
+    /// Warns users whose storage is nearly full.  Users who dropped below
+    /// the threshold are forgotten, so they are warned again next time.
+    pub async fn check_quotas(&self) -> Result<usize> {
+        self.repository
+            .forget_quota_key(QUOTA_KEY, QUOTA_WARNING_PERCENT)
+            .await?;
+        let mut sent = 0;
+        for usage in self
+            .repository
+            .users_near_quota(QUOTA_WARNING_PERCENT)
+            .await?
+        {
+            let notification = quota_notification(&usage);
+            if self
+                .repository
+                .create_once(&notification, QUOTA_KEY)
+                .await?
+            {
+                Self::publish_created(self.live_events.as_deref(), &notification);
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Tells creators of shared links that expire within the notice period.
+    pub async fn check_expiring_links(&self, now: DateTime<Utc>) -> Result<usize> {
+        let until = now + Duration::hours(LINK_EXPIRY_NOTICE_HOURS);
+        let mut sent = 0;
+        for link in self
+            .repository
+            .links_expiring(now.timestamp(), until.timestamp())
+            .await?
+        {
+            let (notification, key) = expiring_link_notification(&link);
+            if self.repository.create_once(&notification, &key).await? {
+                Self::publish_created(self.live_events.as_deref(), &notification);
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Runs the periodic checks and deletes old notifications.
+    pub async fn run_checks(&self) {
+        let now = Utc::now();
+        match self.check_quotas().await {
+            Ok(0) => debug!("No new quota warnings"),
+            Ok(n) => info!("Sent {} quota warning(s)", n),
+            Err(e) => warn!("Quota check failed: {}", e),
+        }
+        match self.check_expiring_links(now).await {
+            Ok(0) => debug!("No shared links about to expire"),
+            Ok(n) => info!("Sent {} expiring link notice(s)", n),
+            Err(e) => warn!("Expiring link check failed: {}", e),
+        }
+        match self
+            .repository
+            .prune(now - Duration::days(RETENTION_DAYS))
+            .await
+        {
+            Ok(0) => {}
+            Ok(n) => info!("Deleted {} old notification(s)", n),
+            Err(e) => warn!("Pruning notifications failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quota_warning_states_usage() {
+        let usage = QuotaUsage {
+            user_id: Uuid::nil(),
+            used_bytes: 950 * 1024 * 1024,
+            quota_bytes: 1024 * 1024 * 1024,
+        };
+        let n = quota_notification(&usage);
+        assert_eq!(n.kind, NotificationKind::QuotaWarning);
+        assert_eq!(n.subject, "You have used 92% of your storage");
+        assert!(n.message.starts_with("950 MB of 1 GB used."));
+    }
+
+    #[test]
+    fn expiring_link_key_follows_the_expiry() {
+        let link = ExpiringLink {
+            share_id: "s1".to_string(),
+            owner_id: Uuid::nil(),
+            item_name: "report.pdf".to_string(),
+            expires_at: 1_792_281_600, // 2026-10-18 00:00 UTC
+        };
+        let (n, key) = expiring_link_notification(&link);
+        assert_eq!(n.subject, "Your shared link to \"report.pdf\" expires soon");
+        assert_eq!(n.message, "The link stops working on 2026-10-18 00:00 UTC.");
+        assert_eq!(key, "share_expiring:s1:1792281600");
+
+        let extended = ExpiringLink {
+            expires_at: link.expires_at + 86_400,
+            ..link
+        };
+        assert_ne!(expiring_link_notification(&extended).1, key);
+    }
+}
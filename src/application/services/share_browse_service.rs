@@ -63,6 +63,14 @@ impl ShareBrowseService {
             ));
         }
 
+        // Upload-only links never reveal what others have dropped
+        if share.hide_contents {
+            return Err(DomainError::access_denied(
+                "Share",
+                "This link only accepts uploads",
+            ));
+        }
+
         let owner_id = Uuid::parse_str(&share.created_by).map_err(|_| {
             DomainError::internal_error(
                 "Share",
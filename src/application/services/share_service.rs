@@ -1,9 +1,15 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use thiserror::Error;
 use tokio::sync::Semaphore;
 use uuid::Uuid;
 
+use crate::application::ports::activity_ports::{ActivityAction, NewActivity};
+use crate::application::ports::live_event_ports::{LiveChange, LiveEventKind};
+use crate::application::services::activity_service::ActivityService;
+use crate::application::services::live_event_service::LiveEventService;
+use crate::application::services::webhook_service::WebhookService;
 use crate::domain::repositories::folder_repository::FolderRepository;
 use crate::infrastructure::repositories::pg::SharePgRepository;
 use crate::infrastructure::repositories::pg::file_blob_read_repository::FileBlobReadRepository;
@@ -13,11 +19,11 @@ use crate::{
     application::{
         dtos::{
             pagination::PaginatedResponseDto,
-            share_dto::{CreateShareDto, ShareDto, UpdateShareDto},
+            share_dto::{CreateShareDto, ShareAccessDto, ShareDto, UpdateShareDto},
         },
         ports::{
             auth_ports::PasswordHasherPort,
-            share_ports::{ShareStoragePort, ShareUseCase},
+            share_ports::{ShareDownload, ShareStoragePort, ShareUseCase},
             storage_ports::FileReadPort,
         },
     },
@@ -40,6 +46,8 @@ pub enum ShareServiceError {
     InvalidPassword(String),
     #[error("Share expired")]
     Expired,
+    #[error("Share download limit reached")]
+    DownloadLimitReached,
     #[error("Repository error: {0}")]
     Repository(String),
     #[error("Invalid item type: {0}")]
@@ -58,6 +66,11 @@ impl From<ShareServiceError> for DomainError {
             ShareServiceError::Expired => {
                 DomainError::access_denied("Share", "Share has expired".to_string())
             }
+            // Worded as an expiry so handlers answer 410 Gone, as for a lapsed link
+            ShareServiceError::DownloadLimitReached => DomainError::access_denied(
+                "Share",
+                "Share has expired: download limit reached".to_string(),
+            ),
             ShareServiceError::Repository(s) => DomainError::internal_error("Share", s),
             ShareServiceError::InvalidItemType(s) => DomainError::validation_error(s),
             ShareServiceError::Validation(s) => DomainError::validation_error(s),
@@ -81,6 +94,12 @@ pub struct ShareService {
     /// Bounds the number of in-flight Argon2 password hashes to avoid
     /// saturating the blocking thread pool and consuming excessive RAM.
     hash_semaphore: Arc<Semaphore>,
+    /// Records created and visited links.
+    activity: Option<Arc<ActivityService>>,
+    /// Reports created and deleted links to webhooks.
+    webhooks: Option<Arc<WebhookService>>,
+    /// Tells the creator's clients about created, changed and deleted links.
+    live_events: Option<Arc<LiveEventService>>,
 }
 
 impl ShareService {
@@ -98,6 +117,52 @@ impl ShareService {
             folder_repository,
             password_hasher,
             hash_semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_HASHES)),
+            activity: None,
+            webhooks: None,
+            live_events: None,
+        }
+    }
+
+    /// Records created and visited links in the activity log.
+    pub fn with_activity(mut self, activity: Option<Arc<ActivityService>>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Reports created and deleted links to webhooks.
+    pub fn with_webhooks(mut self, webhooks: Option<Arc<WebhookService>>) -> Self {
+        self.webhooks = webhooks;
+        self
+    }
+
+    /// Publishes created, changed and deleted links to subscribed clients.
+    pub fn with_live_events(mut self, live_events: Option<Arc<LiveEventService>>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
+    /// Tells the creator's clients about a change to a link.
+    fn publish_change(&self, kind: LiveEventKind, share: &Share) {
+        if let Some(live_events) = &self.live_events {
+            let change = LiveChange::new(kind, share.id().to_string())
+                .with_name(share.item_name().unwrap_or(share.item_id()));
+            live_events.publish([share.created_by()], change);
+        }
+    }
+
+    /// Logs an event about a link; the shared item is named in the details.
+    fn record_activity(&self, entry: NewActivity, share: &Share) {
+        if let Some(activity) = &self.activity {
+            activity.record(
+                entry
+                    .with_owner(share.created_by())
+                    .with_item(
+                        share.id().to_string(),
+                        share.item_name().unwrap_or(share.item_id()),
+                    )
+                    .with_detail("shared_item_id", share.item_id())
+                    .with_detail("shared_item_type", share.item_type().to_string()),
+            );
         }
     }
 
@@ -134,6 +199,28 @@ impl ShareService {
         Ok(())
     }
 
+    /// Checks the upload and download settings of a share about to be
+    /// saved: only links that accept uploads can hide their contents, and
+    /// limits must be positive.
+    fn validate_upload_options(share: &Share) -> Result<(), ShareServiceError> {
+        if share.hide_contents() && !share.accepts_uploads() {
+            return Err(ShareServiceError::Validation(
+                "Only folder links with write permission can be upload-only".to_string(),
+            ));
+        }
+        if share.max_upload_size() == Some(0) {
+            return Err(ShareServiceError::Validation(
+                "max_upload_size must be greater than 0".to_string(),
+            ));
+        }
+        if share.max_downloads() == Some(0) {
+            return Err(ShareServiceError::Validation(
+                "max_downloads must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Hash a password via the injected `PasswordHasherPort`, bounded by a
     /// semaphore so at most `MAX_CONCURRENT_HASHES` Argon2 operations run
     /// concurrently. This keeps RAM usage predictable (~19 MB × 2 = ~38 MB max)
@@ -178,6 +265,9 @@ impl ShareService {
         if share.is_expired() {
             return Err(ShareServiceError::Expired.into());
         }
+        if share.download_limit_reached() {
+            return Err(ShareServiceError::DownloadLimitReached.into());
+        }
 
         if share.has_password() && !allow_password_protected {
             return Err(DomainError::new(
@@ -190,6 +280,17 @@ impl ShareService {
         Ok(ShareDto::from_entity(&share, &self.config.base_url()))
     }
 
+    /// Ids among `item_ids` with an active link created by `user_id`.
+    pub async fn shared_item_ids(
+        &self,
+        user_id: Uuid,
+        item_ids: &[String],
+    ) -> Result<HashSet<String>, DomainError> {
+        self.share_repository
+            .find_shared_item_ids(user_id, item_ids)
+            .await
+    }
+
     pub fn issue_unlock_jwt(&self, share_token: &str) -> Result<String, DomainError> {
         crate::infrastructure::services::share_unlock_cookie::issue_jwt(
             &self.config.auth.jwt_secret,
@@ -247,7 +348,11 @@ impl ShareUseCase for ShareService {
             password_hash,
             dto.expires_at,
         )
-        .map_err(|e| ShareServiceError::Validation(e.to_string()))?;
+        .map_err(|e| ShareServiceError::Validation(e.to_string()))?
+        .with_hide_contents(dto.hide_contents)
+        .with_max_upload_size(dto.max_upload_size)
+        .with_max_downloads(dto.max_downloads);
+        Self::validate_upload_options(&share)?;
 
         // Save to the repository
         let saved_share = self
@@ -255,6 +360,17 @@ impl ShareUseCase for ShareService {
             .save_share(&share)
             .await
             .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
+        self.record_activity(
+            NewActivity::new(ActivityAction::ShareCreated)
+                .with_actor(user_id)
+                .with_detail("has_password", saved_share.has_password())
+                .with_detail("expires_at", saved_share.expires_at()),
+            &saved_share,
+        );
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.link_created(&saved_share);
+        }
+        self.publish_change(LiveEventKind::ShareCreated, &saved_share);
 
         // Convert the entity to DTO for the response
         Ok(ShareDto::from_entity(&saved_share, &self.config.base_url()))
@@ -332,10 +448,24 @@ impl ShareUseCase for ShareService {
             share = share.with_password(password_hash);
         }
 
-        // Update expiration date if provided
-        if dto.expires_at.is_some() {
-            share = share.with_expiration(dto.expires_at);
+        // Update expiration date if provided (zero removes it)
+        if let Some(expires_at) = dto.expires_at {
+            share = share.with_expiration(Some(expires_at).filter(|&at| at > 0));
+        }
+
+        // Update upload settings if provided (a zero size removes the limit)
+        if let Some(hide_contents) = dto.hide_contents {
+            share = share.with_hide_contents(hide_contents);
+        }
+        if let Some(max_upload_size) = dto.max_upload_size {
+            share = share.with_max_upload_size(Some(max_upload_size).filter(|&size| size > 0));
+        }
+
+        // Update the download limit if provided (zero removes it)
+        if let Some(max_downloads) = dto.max_downloads {
+            share = share.with_max_downloads(Some(max_downloads).filter(|&max| max > 0));
         }
+        Self::validate_upload_options(&share)?;
 
         // Save the changes
         let updated_share = self
@@ -343,6 +473,7 @@ impl ShareUseCase for ShareService {
             .update_share(&share)
             .await
             .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
+        self.publish_change(LiveEventKind::ShareUpdated, &updated_share);
 
         // Convert the entity to DTO for the response
         Ok(ShareDto::from_entity(
@@ -352,11 +483,27 @@ impl ShareUseCase for ShareService {
     }
 
     async fn delete_shared_link(&self, id: Uuid, requester_id: Uuid) -> Result<(), DomainError> {
+        // Webhooks and live events describe the deleted link, so look it up first
+        let share = match (&self.webhooks, &self.live_events) {
+            (None, None) => None,
+            _ => self
+                .share_repository
+                .find_share_by_id_for_user(id, requester_id)
+                .await
+                .ok(),
+        };
+
         // SECURITY: ownership-verified delete — only the creator can remove
         self.share_repository
             .delete_share_for_user(id, requester_id)
             .await?;
 
+        if let Some(share) = share {
+            if let Some(webhooks) = &self.webhooks {
+                webhooks.link_deleted(&share);
+            }
+            self.publish_change(LiveEventKind::ShareDeleted, &share);
+        }
         Ok(())
     }
 
@@ -402,10 +549,13 @@ impl ShareUseCase for ShareService {
                 ShareServiceError::NotFound(format!("Share with token {} not found: {}", token, e))
             })?;
 
-        // Check if it has expired
+        // Check if it has expired or used up its downloads
         if share.is_expired() {
             return Err(ShareServiceError::Expired.into());
         }
+        if share.download_limit_reached() {
+            return Err(ShareServiceError::DownloadLimitReached.into());
+        }
 
         // Verify the password using the infrastructure port
         match share.password_hash() {
@@ -449,9 +599,59 @@ impl ShareUseCase for ShareService {
             .update_share(&updated_share)
             .await
             .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
+        // Visitors of a link are anonymous
+        self.record_activity(
+            NewActivity::new(ActivityAction::ShareAccessed),
+            &updated_share,
+        );
 
         Ok(())
     }
+
+    async fn record_shared_link_download(
+        &self,
+        token: &str,
+        download: ShareDownload,
+    ) -> Result<(), DomainError> {
+        let share = self
+            .share_repository
+            .find_share_by_token(token)
+            .await
+            .map_err(|e| {
+                ShareServiceError::NotFound(format!("Share with token {} not found: {}", token, e))
+            })?;
+
+        // The repository re-checks the limit atomically: two visitors racing
+        // for the last download cannot both get it.
+        let recorded = self
+            .share_repository
+            .record_download(share.id(), &download)
+            .await?;
+        if !recorded {
+            return Err(ShareServiceError::DownloadLimitReached.into());
+        }
+
+        Ok(())
+    }
+
+    async fn get_shared_link_accesses(
+        &self,
+        id: Uuid,
+        requester_id: Uuid,
+        page: usize,
+        per_page: usize,
+    ) -> Result<PaginatedResponseDto<ShareAccessDto>, DomainError> {
+        // SECURITY: only the link's creator may see who used it
+        let share = self.fetch_owned_share(id, requester_id).await?;
+
+        let offset = (page - 1) * per_page;
+        let (accesses, total) = self
+            .share_repository
+            .find_share_accesses(share.id(), offset, per_page)
+            .await?;
+
+        Ok(PaginatedResponseDto::new(accesses, page, per_page, total))
+    }
 }
 
 #[cfg(feature = "integration_tests")]
@@ -465,7 +665,7 @@ mod tests {
     use crate::application::ports::storage_ports::FileReadPort;
     use crate::common::config::AppConfig;
     use crate::domain::repositories::folder_repository::FolderRepository;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::sync::{Arc, Mutex};
 
     /// Test-only service that mirrors `ShareService` logic but accepts generic repos.
@@ -651,8 +851,8 @@ mod tests {
                 };
                 share = share.with_password(hash);
             }
-            if dto.expires_at.is_some() {
-                share = share.with_expiration(dto.expires_at);
+            if let Some(expires_at) = dto.expires_at {
+                share = share.with_expiration(Some(expires_at).filter(|&at| at > 0));
             }
             let updated = self
                 .share_repository
@@ -742,6 +942,40 @@ mod tests {
                 .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
             Ok(())
         }
+
+        async fn record_shared_link_download(
+            &self,
+            token: &str,
+            download: ShareDownload,
+        ) -> Result<(), DomainError> {
+            let share = self.share_repository.find_share_by_token(token).await?;
+            if !self
+                .share_repository
+                .record_download(share.id(), &download)
+                .await?
+            {
+                return Err(ShareServiceError::DownloadLimitReached.into());
+            }
+            Ok(())
+        }
+
+        async fn get_shared_link_accesses(
+            &self,
+            id: Uuid,
+            requester_id: Uuid,
+            page: usize,
+            per_page: usize,
+        ) -> Result<PaginatedResponseDto<ShareAccessDto>, DomainError> {
+            let share = self
+                .share_repository
+                .find_share_by_id_for_user(id, requester_id)
+                .await?;
+            let (accesses, total) = self
+                .share_repository
+                .find_share_accesses(share.id(), (page - 1) * per_page, per_page)
+                .await?;
+            Ok(PaginatedResponseDto::new(accesses, page, per_page, total))
+        }
     }
 
     struct MockPasswordHasher;
@@ -1094,6 +1328,20 @@ mod tests {
             Ok(result)
         }
 
+        async fn find_shared_item_ids(
+            &self,
+            user_id: Uuid,
+            item_ids: &[String],
+        ) -> Result<HashSet<String>, DomainError> {
+            let shares = self.shares.lock().unwrap();
+            Ok(shares
+                .values()
+                .filter(|s| s.created_by() == user_id && !s.is_expired())
+                .map(|s| s.item_id().to_string())
+                .filter(|id| item_ids.contains(id))
+                .collect())
+        }
+
         async fn update_share(&self, share: &Share) -> Result<Share, DomainError> {
             let mut shares = self.shares.lock().unwrap();
 
@@ -1128,6 +1376,34 @@ mod tests {
 
             Ok((paginated, total))
         }
+
+        async fn record_download(
+            &self,
+            share_id: Uuid,
+            _download: &ShareDownload,
+        ) -> Result<bool, DomainError> {
+            let mut shares = self.shares.lock().unwrap();
+            let id_str = share_id.to_string();
+            let share = shares
+                .get(&id_str)
+                .cloned()
+                .ok_or_else(|| DomainError::not_found("Share", &id_str))?;
+            if share.download_limit_reached() {
+                return Ok(false);
+            }
+            let count = share.download_count() + 1;
+            shares.insert(id_str, share.with_download_count(count));
+            Ok(true)
+        }
+
+        async fn find_share_accesses(
+            &self,
+            _share_id: Uuid,
+            _offset: usize,
+            _limit: usize,
+        ) -> Result<(Vec<ShareAccessDto>, usize), DomainError> {
+            Ok((Vec::new(), 0))
+        }
     }
 
     #[tokio::test]
@@ -1154,6 +1430,9 @@ mod tests {
                 write: false,
                 reshare: false,
             }),
+            hide_contents: false,
+            max_upload_size: None,
+            max_downloads: None,
         };
 
         let result = service.create_shared_link(Uuid::new_v4(), dto).await;
@@ -0,0 +1,405 @@
+//! Anonymous uploads ("file drop") through folder share links.
+//!
+//! Folder links with write permission accept files from visitors, either
+//! as a single request or through the chunked upload protocol.  Uploaded
+//! files land in the link's folder tree, belong to the link's owner and are
+//! charged against the owner's quota.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::ports::chunked_upload_ports::{
+    ChunkUploadResponseDto, ChunkedUploadPort, CreateUploadResponseDto, DEFAULT_CHUNK_SIZE,
+    UploadStatusResponseDto,
+};
+use crate::application::ports::file_ports::{FileRetrievalUseCase, FileUploadUseCase};
+use crate::application::ports::storage_ports::StorageUsagePort;
+use crate::application::services::file_retrieval_service::FileRetrievalService;
+use crate::application::services::file_upload_service::FileUploadService;
+use crate::application::services::share_service::ShareService;
+use crate::application::services::storage_usage_service::StorageUsageService;
+use crate::common::errors::DomainError;
+use crate::domain::repositories::folder_repository::FolderRepository;
+use crate::domain::services::path_service::unique_file_name;
+use crate::infrastructure::repositories::pg::folder_db_repository::FolderDbRepository;
+use crate::infrastructure::services::chunked_upload_service::ChunkedUploadService;
+
+/// How long a chunked upload started through a link stays bound to it;
+/// matches the lifetime of upload sessions.
+const SESSION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Smallest chunk size accepted for chunked uploads.
+const MIN_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Largest chunk size accepted from visitors; bounds the request bodies
+/// buffered for anonymous chunk uploads.
+pub const MAX_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// A visitor's access to a share link: its token and, for password
+/// protected links, the unlock cookie issued by `/verify`.
+#[derive(Debug, Clone, Copy)]
+pub struct ShareAccess<'a> {
+    pub token: &'a str,
+    pub unlock_jwt: Option<&'a str>,
+}
+
+/// A chunked upload a visitor starts through a link.
+#[derive(Debug, Clone)]
+pub struct ShareChunkedUploadRequest {
+    pub filename: String,
+    pub folder_id: Option<String>,
+    pub content_type: String,
+    pub total_size: u64,
+    pub chunk_size: Option<usize>,
+}
+
+/// The folder an upload goes to and who it belongs to.
+struct UploadTarget {
+    folder_id: String,
+    owner_id: Uuid,
+    /// The link's per-file upload limit
+    max_upload_size: Option<u64>,
+}
+
+/// Reduces a client-supplied file name to its last path segment (folder
+/// uploads send relative paths).
+pub fn upload_file_name(raw: &str) -> String {
+    raw.rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .unwrap_or("unnamed")
+        .to_string()
+}
+
+pub struct ShareUploadService {
+    share_service: Arc<ShareService>,
+    folder_repo: Arc<FolderDbRepository>,
+    file_retrieval: Arc<FileRetrievalService>,
+    file_upload: Arc<FileUploadService>,
+    chunked_upload: Arc<ChunkedUploadService>,
+    storage_usage: Option<Arc<StorageUsageService>>,
+    /// Link token of each chunked upload started through a link, so that a
+    /// link can only drive its own upload sessions
+    sessions: moka::sync::Cache<String, String>,
+}
+
+impl ShareUploadService {
+    pub fn new(
+        share_service: Arc<ShareService>,
+        folder_repo: Arc<FolderDbRepository>,
+        file_retrieval: Arc<FileRetrievalService>,
+        file_upload: Arc<FileUploadService>,
+        chunked_upload: Arc<ChunkedUploadService>,
+        storage_usage: Option<Arc<StorageUsageService>>,
+    ) -> Self {
+        Self {
+            share_service,
+            folder_repo,
+            file_retrieval,
+            file_upload,
+            chunked_upload,
+            storage_usage,
+            sessions: moka::sync::Cache::builder()
+                .time_to_live(SESSION_TTL)
+                .build(),
+        }
+    }
+
+    /// Resolves the link and the folder an upload of `size` bytes goes
+    /// to: the shared folder, or `folder_id` when it lies inside it.
+    ///
+    /// Fails when the link does not accept uploads, or the file exceeds the
+    /// link's size limit or the owner's quota.
+    async fn resolve_target(
+        &self,
+        access: ShareAccess<'_>,
+        folder_id: Option<&str>,
+        size: u64,
+    ) -> Result<UploadTarget, DomainError> {
+        let share = self
+            .share_service
+            .get_shared_link_with_unlock(access.token, access.unlock_jwt)
+            .await?;
+
+        if share.item_type != "folder" || !share.permissions.write {
+            return Err(DomainError::access_denied(
+                "Share",
+                "This link does not accept uploads",
+            ));
+        }
+
+        let owner_id = Uuid::parse_str(&share.created_by).map_err(|_| {
+            DomainError::internal_error(
+                "Share",
+                format!("Share has invalid created_by UUID: {}", share.created_by),
+            )
+        })?;
+
+        let folder_id = match folder_id {
+            None => share.item_id,
+            Some(id) => {
+                if !self
+                    .folder_repo
+                    .is_folder_in_subtree(id, &share.item_id)
+                    .await?
+                {
+                    return Err(DomainError::not_found("Folder", id));
+                }
+                id.to_string()
+            }
+        };
+
+        if let Some(limit) = share.max_upload_size
+            && size > limit
+        {
+            return Err(DomainError::quota_exceeded(format!(
+                "File exceeds this link's upload limit of {} bytes",
+                limit
+            )));
+        }
+        if let Some(storage_usage) = &self.storage_usage {
+            storage_usage.check_storage_quota(owner_id, size).await?;
+        }
+
+        Ok(UploadTarget {
+            folder_id,
+            owner_id,
+            max_upload_size: share.max_upload_size,
+        })
+    }
+
+    /// Returns the upload session `upload_id` if it was started through
+    /// the visitor's link, with the owner it runs under.
+    async fn resolve_session(
+        &self,
+        access: ShareAccess<'_>,
+        upload_id: &str,
+    ) -> Result<Uuid, DomainError> {
+        if self.sessions.get(upload_id).as_deref() != Some(access.token) {
+            return Err(DomainError::not_found("UploadSession", upload_id));
+        }
+        // Re-check the link: it may have been revoked or changed since
+        let target = self.resolve_target(access, None, 0).await?;
+        Ok(target.owner_id)
+    }
+
+    /// Stores an uploaded file under a name that is free in the target
+    /// folder; visitors cannot see, and so cannot avoid, existing names.
+    async fn store(
+        &self,
+        target: &UploadTarget,
+        filename: &str,
+        content_type: String,
+        path: &Path,
+        hash: Option<String>,
+    ) -> Result<FileDto, DomainError> {
+        let existing: HashSet<String> = self
+            .file_retrieval
+            .list_files_owned(Some(&target.folder_id), target.owner_id)
+            .await?
+            .into_iter()
+            .map(|file| file.name)
+            .collect();
+        let name = unique_file_name(filename, &existing);
+
+        self.file_upload
+            .upload_file_from_path(
+                name,
+                Some(target.folder_id.clone()),
+                content_type,
+                path,
+                hash,
+            )
+            .await
+    }
+
+    /// Checks that a visitor may upload a file announced as `size` bytes
+    /// to `folder_id` (the shared folder when `None`), before the upload is
+    /// received, and returns the most bytes it may take: the smaller of the
+    /// link's limit and the owner's remaining quota, `None` when neither
+    /// applies.
+    pub async fn upload_allowance(
+        &self,
+        access: ShareAccess<'_>,
+        folder_id: Option<&str>,
+        size: u64,
+    ) -> Result<Option<u64>, DomainError> {
+        let target = self.resolve_target(access, folder_id, size).await?;
+        let mut allowance = target.max_upload_size;
+        if let Some(storage_usage) = &self.storage_usage {
+            let (used, quota) = storage_usage.get_user_storage_info(target.owner_id).await?;
+            // Quota of 0 means unlimited
+            if quota > 0 {
+                let remaining = (quota - used).max(0) as u64;
+                allowance = Some(allowance.map_or(remaining, |limit| limit.min(remaining)));
+            }
+        }
+        Ok(allowance)
+    }
+
+    /// Stores a file a visitor uploaded in one request, spooled to
+    /// `temp_path`.  The temp file is consumed on success.
+    pub async fn upload_file(
+        &self,
+        access: ShareAccess<'_>,
+        folder_id: Option<&str>,
+        filename: &str,
+        content_type: String,
+        temp_path: &Path,
+        hash: String,
+    ) -> Result<FileDto, DomainError> {
+        let size = tokio::fs::metadata(temp_path)
+            .await
+            .map_err(|e| DomainError::internal_error("ShareUpload", format!("temp metadata: {e}")))?
+            .len();
+        let target = self.resolve_target(access, folder_id, size).await?;
+        self.store(&target, filename, content_type, temp_path, Some(hash))
+            .await
+    }
+
+    /// Starts a chunked upload through a link.
+    pub async fn create_chunked_upload(
+        &self,
+        access: ShareAccess<'_>,
+        request: ShareChunkedUploadRequest,
+    ) -> Result<CreateUploadResponseDto, DomainError> {
+        if request.filename.is_empty() {
+            return Err(DomainError::validation_error("Filename is required"));
+        }
+        if request.total_size == 0 {
+            return Err(DomainError::validation_error(
+                "Total size must be greater than 0",
+            ));
+        }
+        let chunk_size = request.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+        if !(MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+            return Err(DomainError::validation_error(
+                "Chunk size must be between 1MB and 64MB",
+            ));
+        }
+
+        let target = self
+            .resolve_target(access, request.folder_id.as_deref(), request.total_size)
+            .await?;
+        let session = self
+            .chunked_upload
+            .create_session(
+                target.owner_id,
+                upload_file_name(&request.filename),
+                Some(target.folder_id),
+                request.content_type,
+                request.total_size,
+                Some(chunk_size),
+            )
+            .await?;
+        self.sessions
+            .insert(session.upload_id.clone(), access.token.to_string());
+        Ok(session)
+    }
+
+    /// Receives one chunk of a chunked upload started through the link.
+    pub async fn upload_chunk(
+        &self,
+        access: ShareAccess<'_>,
+        upload_id: &str,
+        chunk_index: usize,
+        data: Bytes,
+        checksum: Option<String>,
+    ) -> Result<ChunkUploadResponseDto, DomainError> {
+        let owner_id = self.resolve_session(access, upload_id).await?;
+        self.chunked_upload
+            .upload_chunk(upload_id, owner_id, chunk_index, data, checksum)
+            .await
+    }
+
+    /// Progress of a chunked upload started through the link.
+    pub async fn upload_status(
+        &self,
+        access: ShareAccess<'_>,
+        upload_id: &str,
+    ) -> Result<UploadStatusResponseDto, DomainError> {
+        let owner_id = self.resolve_session(access, upload_id).await?;
+        self.chunked_upload.get_status(upload_id, owner_id).await
+    }
+
+    /// Assembles a chunked upload started through the link and stores the
+    /// file in the folder it was started for.
+    pub async fn complete_chunked_upload(
+        &self,
+        access: ShareAccess<'_>,
+        upload_id: &str,
+    ) -> Result<FileDto, DomainError> {
+        let owner_id = self.resolve_session(access, upload_id).await?;
+        let (assembled_path, filename, folder_id, content_type, total_size, hash) = self
+            .chunked_upload
+            .complete_upload(upload_id, owner_id)
+            .await?;
+
+        let content_type = crate::common::mime_detect::refine_content_type_from_file(
+            &assembled_path,
+            &filename,
+            &content_type,
+        )
+        .await;
+
+        // The quota may have been used up while the chunks arrived
+        let result = match self
+            .resolve_target(access, folder_id.as_deref(), total_size)
+            .await
+        {
+            Ok(target) => {
+                self.store(
+                    &target,
+                    &filename,
+                    content_type,
+                    &assembled_path,
+                    Some(hash),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        let _ = self
+            .chunked_upload
+            .finalize_upload(upload_id, owner_id)
+            .await;
+        self.sessions.invalidate(upload_id);
+        result
+    }
+
+    /// Cancels a chunked upload started through the link.
+    pub async fn cancel_chunked_upload(
+        &self,
+        access: ShareAccess<'_>,
+        upload_id: &str,
+    ) -> Result<(), DomainError> {
+        let owner_id = self.resolve_session(access, upload_id).await?;
+        self.chunked_upload
+            .cancel_upload(upload_id, owner_id)
+            .await?;
+        self.sessions.invalidate(upload_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_file_name_keeps_the_last_segment() {
+        assert_eq!(upload_file_name("report.pdf"), "report.pdf");
+        assert_eq!(upload_file_name("photos/2024/a.jpg"), "a.jpg");
+        assert_eq!(upload_file_name("..\\..\\evil.exe"), "evil.exe");
+        assert_eq!(upload_file_name("../.."), "unnamed");
+        assert_eq!(upload_file_name("dir/"), "unnamed");
+        assert_eq!(upload_file_name(""), "unnamed");
+    }
+}
@@ -0,0 +1,372 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::dtos::snapshot_dto::{
+    CreateSnapshotDto, RestoreSnapshotDto, RestoreSnapshotResultDto, SetSnapshotScheduleDto,
+    SnapshotDto, SnapshotEntryDto, SnapshotScheduleDto,
+};
+use crate::application::ports::blob_storage_ports::BlobStream;
+use crate::application::ports::file_lifecycle::FileCreatedHook;
+use crate::application::ports::snapshot_ports::{SnapshotRepositoryPort, SnapshotUseCase};
+use crate::application::ports::storage_ports::StorageUsagePort;
+use crate::application::services::storage_usage_service::StorageUsageService;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::infrastructure::repositories::pg::SnapshotPgRepository;
+use crate::infrastructure::services::dedup_service::DedupService;
+
+/// Maximum length of a snapshot name.
+const MAX_NAME_LEN: usize = 255;
+
+/// Service for named, read-only folder snapshots.
+///
+/// A snapshot only records the tree and holds blob references, so taking
+/// one is O(items) metadata work with zero content I/O.  Restores never
+/// overwrite live data: they materialise a new folder or file, which counts
+/// against the owner's quota like an upload.
+pub struct SnapshotService {
+    repo: Arc<SnapshotPgRepository>,
+    dedup: Arc<DedupService>,
+    storage_usage: Option<Arc<StorageUsageService>>,
+    file_created_hooks: Vec<Arc<dyn FileCreatedHook>>,
+}
+
+impl SnapshotService {
+    pub fn new(repo: Arc<SnapshotPgRepository>, dedup: Arc<DedupService>) -> Self {
+        Self {
+            repo,
+            dedup,
+            storage_usage: None,
+            file_created_hooks: Vec::new(),
+        }
+    }
+
+    /// Enforces storage quotas on restore.
+    pub fn with_storage_usage(mut self, storage_usage: Option<Arc<StorageUsageService>>) -> Self {
+        self.storage_usage = storage_usage;
+        self
+    }
+
+    /// Registers a hook to fire for every file a restore creates.
+    pub fn with_file_created_hook(mut self, hook: Arc<dyn FileCreatedHook>) -> Self {
+        self.file_created_hooks.push(hook);
+        self
+    }
+
+    fn invalid(msg: impl Into<String>) -> DomainError {
+        DomainError::new(ErrorKind::InvalidInput, "Snapshot", msg.into())
+    }
+
+    /// Normalise a client-supplied snapshot path ("/a/b/" → "a/b").
+    fn normalize_path(path: &str) -> String {
+        path.trim_matches('/').to_string()
+    }
+
+    /// "report.pdf" + "before cleanup" → "report (before cleanup).pdf"
+    fn default_restore_name(name: &str, is_folder: bool, snapshot_name: &str) -> String {
+        if !is_folder
+            && let Some(dot) = name.rfind('.')
+            && dot > 0
+        {
+            return format!("{} ({}){}", &name[..dot], snapshot_name, &name[dot..]);
+        }
+        format!("{} ({})", name, snapshot_name)
+    }
+
+    /// Takes a snapshot on behalf of a schedule. Used by the scheduler.
+    pub async fn run_schedule(&self, user_id: Uuid, schedule: &SnapshotScheduleDto) -> Result<()> {
+        let now = Utc::now();
+        let expires_at = schedule
+            .retention_days
+            .map(|days| now + Duration::days(days as i64));
+        let name = format!("Scheduled {}", now.format("%Y-%m-%d %H:%M"));
+
+        self.repo
+            .create_snapshot(
+                &schedule.folder_id,
+                user_id,
+                &name,
+                None,
+                expires_at,
+                Some(&schedule.id),
+            )
+            .await?;
+        self.repo.mark_schedule_run(&schedule.id).await?;
+        info!(
+            "Scheduled snapshot '{}' taken of folder {}",
+            name, schedule.folder_id
+        );
+        Ok(())
+    }
+
+    /// Takes all snapshots whose schedule is due. Returns how many were taken.
+    pub async fn run_due_schedules(&self) -> Result<usize> {
+        let due = self.repo.list_due_schedules().await?;
+        let mut taken = 0;
+        for (user_id, schedule) in &due {
+            match self.run_schedule(*user_id, schedule).await {
+                Ok(()) => taken += 1,
+                Err(e) => warn!(
+                    "Scheduled snapshot of folder {} failed: {}",
+                    schedule.folder_id, e
+                ),
+            }
+        }
+        Ok(taken)
+    }
+
+    /// Deletes all snapshots past their expiry. Returns how many were deleted.
+    pub async fn delete_expired(&self) -> Result<usize> {
+        let expired = self.repo.list_expired().await?;
+        let mut deleted = 0;
+        for (snapshot_id, user_id) in &expired {
+            match self.delete_snapshot(snapshot_id, *user_id).await {
+                Ok(()) => deleted += 1,
+                Err(e) => warn!("Failed to delete expired snapshot {}: {}", snapshot_id, e),
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+impl SnapshotUseCase for SnapshotService {
+    async fn create_snapshot(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        dto: CreateSnapshotDto,
+    ) -> Result<SnapshotDto> {
+        let name = dto.name.trim();
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(Self::invalid(format!(
+                "Snapshot name must be between 1 and {MAX_NAME_LEN} characters"
+            )));
+        }
+        let expires_at = match dto.expires_in_days {
+            Some(0) => return Err(Self::invalid("expires_in_days must be at least 1")),
+            Some(days) => Some(Utc::now() + Duration::days(days as i64)),
+            None => None,
+        };
+
+        let id = self
+            .repo
+            .create_snapshot(
+                folder_id,
+                user_id,
+                name,
+                dto.description.as_deref(),
+                expires_at,
+                None,
+            )
+            .await?;
+        let snapshot = self.repo.get_snapshot(&id, user_id).await?;
+        info!(
+            "Snapshot '{}' of folder {} created: {} folders, {} files",
+            snapshot.name, folder_id, snapshot.folder_count, snapshot.file_count
+        );
+        Ok(snapshot)
+    }
+
+    async fn list_snapshots(
+        &self,
+        user_id: Uuid,
+        folder_id: Option<&str>,
+    ) -> Result<Vec<SnapshotDto>> {
+        self.repo.list_snapshots(user_id, folder_id).await
+    }
+
+    async fn get_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<SnapshotDto> {
+        self.repo.get_snapshot(snapshot_id, user_id).await
+    }
+
+    async fn browse(
+        &self,
+        snapshot_id: &str,
+        path: &str,
+        user_id: Uuid,
+    ) -> Result<Vec<SnapshotEntryDto>> {
+        self.repo.get_snapshot(snapshot_id, user_id).await?;
+        let path = Self::normalize_path(path);
+        if !path.is_empty() {
+            let (entry, _) = self.repo.get_entry(snapshot_id, &path).await?;
+            if !entry.is_folder {
+                return Err(Self::invalid(format!("'{path}' is not a folder")));
+            }
+        }
+        self.repo.list_children(snapshot_id, &path).await
+    }
+
+    async fn get_file_content(
+        &self,
+        snapshot_id: &str,
+        path: &str,
+        user_id: Uuid,
+    ) -> Result<(SnapshotEntryDto, BlobStream)> {
+        self.repo.get_snapshot(snapshot_id, user_id).await?;
+        let path = Self::normalize_path(path);
+        let (entry, blob_hash) = self.repo.get_entry(snapshot_id, &path).await?;
+        let Some(blob_hash) = blob_hash.filter(|_| !entry.is_folder) else {
+            return Err(Self::invalid(format!("'{path}' is not a file")));
+        };
+        let stream = self.dedup.read_blob_stream(&blob_hash).await?;
+        Ok((entry, stream))
+    }
+
+    async fn restore(
+        &self,
+        snapshot_id: &str,
+        user_id: Uuid,
+        dto: RestoreSnapshotDto,
+    ) -> Result<RestoreSnapshotResultDto> {
+        let snapshot = self.repo.get_snapshot(snapshot_id, user_id).await?;
+        let path = dto
+            .path
+            .as_deref()
+            .map(Self::normalize_path)
+            .filter(|p| !p.is_empty());
+
+        let dest_name = match dto.name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => match &path {
+                Some(p) => {
+                    let (entry, _) = self.repo.get_entry(snapshot_id, p).await?;
+                    Self::default_restore_name(&entry.name, entry.is_folder, &snapshot.name)
+                }
+                None => Self::default_restore_name(&snapshot.folder_name, true, &snapshot.name),
+            },
+        };
+
+        let target = match dto.target_folder_id {
+            Some(t) => Some(t),
+            None => self.repo.source_parent_id(snapshot_id).await?,
+        };
+
+        if let Some(storage_usage) = &self.storage_usage {
+            let size = self.repo.restore_size(snapshot_id, path.as_deref()).await?;
+            storage_usage.check_storage_quota(user_id, size).await?;
+        }
+
+        let result = self
+            .repo
+            .restore(snapshot_id, path.as_deref(), target.as_deref(), &dest_name)
+            .await?;
+
+        if let Some(storage_usage) = &self.storage_usage
+            && let Err(e) = storage_usage.update_user_storage_usage(user_id).await
+        {
+            warn!("Failed to update storage usage for {}: {}", user_id, e);
+        }
+        if !self.file_created_hooks.is_empty() {
+            let files = self
+                .repo
+                .list_restored_files(&result.id, result.is_folder)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Failed to list files restored from {}: {}", snapshot_id, e);
+                    Vec::new()
+                });
+            for (file_id, blob_hash, mime_type) in &files {
+                for hook in &self.file_created_hooks {
+                    hook.on_file_created(file_id, blob_hash, mime_type).await;
+                }
+            }
+        }
+        info!(
+            "Snapshot {} restored as '{}': {} folders, {} files",
+            snapshot_id, dest_name, result.folders_restored, result.files_restored
+        );
+        Ok(result)
+    }
+
+    async fn delete_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<()> {
+        let hashes = self.repo.delete_snapshot(snapshot_id, user_id).await?;
+        // The PG trigger has decremented each ref_count; reclaim blobs that
+        // are no longer referenced by any file, version or snapshot.
+        for hash in &hashes {
+            self.dedup.cleanup_if_orphaned(hash).await;
+        }
+        info!(
+            "Snapshot {} deleted ({} blob references released)",
+            snapshot_id,
+            hashes.len()
+        );
+        Ok(())
+    }
+
+    async fn get_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+    ) -> Result<Option<SnapshotScheduleDto>> {
+        self.repo.get_schedule(folder_id, user_id).await
+    }
+
+    async fn set_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        dto: SetSnapshotScheduleDto,
+    ) -> Result<SnapshotScheduleDto> {
+        let interval_hours = i32::try_from(dto.interval_hours)
+            .ok()
+            .filter(|h| *h > 0)
+            .ok_or_else(|| Self::invalid("interval_hours must be a positive number"))?;
+        let retention_days = match dto.retention_days {
+            Some(days) => Some(
+                i32::try_from(days)
+                    .ok()
+                    .filter(|d| *d > 0)
+                    .ok_or_else(|| Self::invalid("retention_days must be a positive number"))?,
+            ),
+            None => None,
+        };
+        self.repo
+            .upsert_schedule(folder_id, user_id, interval_hours, retention_days)
+            .await
+    }
+
+    async fn delete_schedule(&self, folder_id: &str, user_id: Uuid) -> Result<()> {
+        if self.repo.delete_schedule(folder_id, user_id).await? {
+            Ok(())
+        } else {
+            Err(DomainError::not_found("SnapshotSchedule", folder_id))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_restore_name_keeps_extension_for_files() {
+        assert_eq!(
+            SnapshotService::default_restore_name("report.pdf", false, "v1"),
+            "report (v1).pdf"
+        );
+        assert_eq!(
+            SnapshotService::default_restore_name("archive.tar.gz", false, "v1"),
+            "archive.tar (v1).gz"
+        );
+    }
+
+    #[test]
+    fn default_restore_name_for_folders_and_dotfiles() {
+        assert_eq!(
+            SnapshotService::default_restore_name("photos.2024", true, "v1"),
+            "photos.2024 (v1)"
+        );
+        assert_eq!(
+            SnapshotService::default_restore_name(".env", false, "v1"),
+            ".env (v1)"
+        );
+    }
+
+    #[test]
+    fn normalize_path_strips_slashes() {
+        assert_eq!(SnapshotService::normalize_path("/a/b/"), "a/b");
+        assert_eq!(SnapshotService::normalize_path("/"), "");
+    }
+}
@@ -15,7 +15,8 @@ use uuid::Uuid;
  * is using and updating this information in the user records.
  *
  * Storage usage is calculated directly from the `storage.files` table
- * by summing file sizes for each user (using the `user_id` column).
+ * by summing file sizes for each user (using the `user_id` column), plus
+ * the content that only the user's folder snapshots still reference.
  */
 pub struct StorageUsageService {
     pool: Arc<PgPool>,
@@ -72,14 +73,52 @@ impl StorageUsageService {
             DomainError::internal_error("StorageUsage", format!("Failed to calculate usage: {e}"))
         })?;
 
+        // Content kept alive only by the user's snapshots counts as well
+        let snapshot_size = self.calculate_user_snapshot_usage(user_id).await?;
+        let total_size = total_size + snapshot_size;
+
         debug!(
-            "Calculated storage for user {}: {} bytes",
-            user_id, total_size
+            "Calculated storage for user {}: {} bytes ({} in snapshots)",
+            user_id, total_size, snapshot_size
         );
 
         Ok(total_size)
     }
 
+    /// Calculates the bytes held exclusively by a user's folder snapshots.
+    ///
+    /// Each distinct blob is counted once, and blobs still used by one of the
+    /// user's live files are skipped — they are already part of the file sum.
+    pub async fn calculate_user_snapshot_usage(&self, user_id: Uuid) -> Result<i64, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(x.size), 0)::bigint
+              FROM (
+                SELECT DISTINCT ON (e.blob_hash) e.blob_hash, e.size
+                  FROM storage.snapshot_entries e
+                  JOIN storage.snapshots s ON s.id = e.snapshot_id
+                 WHERE s.user_id = $1
+                   AND NOT e.is_folder
+                   AND NOT EXISTS (
+                       SELECT 1 FROM storage.files f
+                        WHERE f.user_id = $1
+                          AND f.blob_hash = e.blob_hash
+                          AND NOT f.is_trashed
+                   )
+              ) x
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| {
+            DomainError::internal_error(
+                "StorageUsage",
+                format!("Failed to calculate snapshot usage: {e}"),
+            )
+        })
+    }
+
     /// Calculates and updates storage usage for a user identified by username.
     pub async fn update_user_storage_usage_by_username(
         &self,
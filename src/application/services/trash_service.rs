@@ -6,8 +6,12 @@ use crate::application::dtos::display_helpers::{
     category_for, icon_class_for, icon_special_class_for,
 };
 use crate::application::dtos::trash_dto::TrashedItemDto;
+use crate::application::ports::activity_ports::{ActivityAction, NewActivity};
+use crate::application::ports::live_event_ports::{LiveChange, LiveEventKind};
 use crate::application::ports::storage_ports::{FileReadPort, FileWritePort};
 use crate::application::ports::trash_ports::TrashUseCase;
+use crate::application::services::activity_service::ActivityService;
+use crate::application::services::live_event_service::LiveEventService;
 use crate::common::errors::{DomainError, ErrorKind, Result};
 use crate::domain::entities::trashed_item::{TrashedItem, TrashedItemType};
 use crate::domain::repositories::folder_repository::FolderRepository;
@@ -58,6 +62,12 @@ pub struct TrashService {
 
     /// Number of days items should be kept in trash before automatic cleanup
     retention_days: u32,
+
+    /// Records items moved to, restored from and deleted from the trash
+    activity: Option<Arc<ActivityService>>,
+
+    /// Tells subscribed clients about the same
+    live_events: Option<Arc<LiveEventService>>,
 }
 
 impl TrashService {
@@ -81,6 +91,47 @@ impl TrashService {
             thumbnail_service,
             content_cache,
             retention_days,
+            activity: None,
+            live_events: None,
+        }
+    }
+
+    /// Records trash operations in the activity log.
+    pub fn with_activity(mut self, activity: Option<Arc<ActivityService>>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Publishes trash operations to subscribed clients.
+    pub fn with_live_events(mut self, live_events: Option<Arc<LiveEventService>>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
+    /// Logs and publishes `item` with the file or folder flavour of an
+    /// action.
+    fn record_activity(
+        &self,
+        item: &TrashedItem,
+        file_action: ActivityAction,
+        folder_action: ActivityAction,
+    ) {
+        let action = match item.item_type() {
+            TrashedItemType::File => file_action,
+            TrashedItemType::Folder => folder_action,
+        };
+        if let Some(live_events) = &self.live_events
+            && let Some(kind) = LiveEventKind::from_activity(action)
+        {
+            let change =
+                LiveChange::new(kind, item.original_id().to_string()).with_name(item.name());
+            live_events.publish([item.user_id()], change);
+        }
+        if let Some(activity) = &self.activity {
+            activity.record(
+                NewActivity::by(action, item.user_id())
+                    .with_item(item.original_id().to_string(), item.name()),
+            );
         }
     }
 
@@ -283,6 +334,11 @@ impl TrashUseCase for TrashService {
                 }
 
                 info!("File completely moved to trash: {}", item_id);
+                self.record_activity(
+                    &trashed_item,
+                    ActivityAction::FileTrashed,
+                    ActivityAction::FolderTrashed,
+                );
                 Ok(())
             }
             "folder" => {
@@ -348,6 +404,11 @@ impl TrashUseCase for TrashService {
                     })?;
 
                 debug!("Folder moved to trash: {}", item_id);
+                self.record_activity(
+                    &trashed_item,
+                    ActivityAction::FileTrashed,
+                    ActivityAction::FolderTrashed,
+                );
                 Ok(())
             }
             _ => Err(DomainError::validation_error(format!(
@@ -507,6 +568,11 @@ impl TrashUseCase for TrashService {
                 }
 
                 info!("Item successfully restored from trash: {}", trash_id);
+                self.record_activity(
+                    &item,
+                    ActivityAction::FileRestored,
+                    ActivityAction::FolderRestored,
+                );
                 Ok(())
             }
             Ok(None) => {
@@ -677,6 +743,11 @@ impl TrashUseCase for TrashService {
                 };
 
                 info!("Item permanently deleted from trash: {}", trash_id);
+                self.record_activity(
+                    &item,
+                    ActivityAction::FileDeleted,
+                    ActivityAction::FolderDeleted,
+                );
                 Ok(())
             }
             Ok(None) => {
@@ -702,24 +773,29 @@ impl TrashUseCase for TrashService {
     async fn empty_trash(&self, user_id: Uuid) -> Result<()> {
         info!("Emptying trash for user {}", user_id);
 
-        // Collect trashed file IDs BEFORE bulk-deleting so we can clean up
-        // their thumbnails afterward.  This is best-effort — if the query
-        // fails we still proceed with the bulk delete.
-        let trashed_file_ids: Vec<String> = if self.thumbnail_service.is_some() {
+        // Collect trashed items BEFORE bulk-deleting so we can clean up
+        // their thumbnails and log their deletion afterward.  This is
+        // best-effort — if the query fails we still proceed with the bulk
+        // delete.
+        let trashed_items: Vec<TrashedItem> = if self.thumbnail_service.is_some()
+            || self.activity.is_some()
+            || self.live_events.is_some()
+        {
             match self.trash_repository.get_trash_items(&user_id).await {
-                Ok(items) => items
-                    .iter()
-                    .filter(|i| matches!(i.item_type(), TrashedItemType::File))
-                    .map(|i| i.original_id().to_string())
-                    .collect(),
+                Ok(items) => items,
                 Err(e) => {
-                    warn!("Could not list trashed items for thumbnail cleanup: {}", e);
+                    warn!("Could not list trashed items for cleanup: {}", e);
                     Vec::new()
                 }
             }
         } else {
             Vec::new()
         };
+        let trashed_file_ids: Vec<String> = trashed_items
+            .iter()
+            .filter(|i| matches!(i.item_type(), TrashedItemType::File))
+            .map(|i| i.original_id().to_string())
+            .collect();
 
         // clear_trash() performs bulk SQL DELETEs in 2 queries:
         //   1. DELETE FROM storage.files  WHERE user_id = $1 AND is_trashed = TRUE
@@ -755,6 +831,14 @@ impl TrashUseCase for TrashService {
             }
         }
 
+        for item in &trashed_items {
+            self.record_activity(
+                item,
+                ActivityAction::FileDeleted,
+                ActivityAction::FolderDeleted,
+            );
+        }
+
         info!("Trash emptied for user {}", user_id);
         Ok(())
     }
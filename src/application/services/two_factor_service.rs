@@ -0,0 +1,299 @@
+//! TOTP two-factor authentication: enrolment, code verification, recovery
+//! codes and the admin enforcement policy.
+//!
+//! The second factor applies to password logins only.  SSO accounts rely
+//! on their identity provider, and app passwords stay single-factor so DAV
+//! and sync clients keep working.
+
+use std::sync::Arc;
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::application::dtos::two_factor_dto::{
+    RecoveryCodesDto, TotpSetupDto, TwoFactorPolicyDto, TwoFactorStatusDto,
+};
+use crate::application::ports::group_ports::GroupRepositoryPort;
+use crate::application::ports::two_factor_ports::TwoFactorRepositoryPort;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::entities::user::User;
+use crate::domain::repositories::settings_repository::SettingsRepository;
+use crate::infrastructure::repositories::pg::{
+    GroupPgRepository, SettingsPgRepository, TwoFactorPgRepository,
+};
+use crate::infrastructure::services::totp;
+
+const SETTINGS_CATEGORY: &str = "two_factor";
+const ENFORCED_ROLES_KEY: &str = "two_factor.enforced_roles";
+const ENFORCED_GROUPS_KEY: &str = "two_factor.enforced_groups";
+/// Issuer shown by authenticator apps
+const ISSUER: &str = "OxiCloud";
+
+fn invalid_code() -> DomainError {
+    DomainError::new(
+        ErrorKind::AccessDenied,
+        "TwoFactor",
+        "Invalid two-factor code",
+    )
+}
+
+/// Splits a stored comma-separated list, dropping empty entries.
+fn parse_list(value: Option<String>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn now_unix() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+/// Service for TOTP enrolment and verification.
+pub struct TwoFactorService {
+    repo: Arc<TwoFactorPgRepository>,
+    settings_repo: Arc<SettingsPgRepository>,
+    group_repo: Arc<GroupPgRepository>,
+}
+
+impl TwoFactorService {
+    pub fn new(
+        repo: Arc<TwoFactorPgRepository>,
+        settings_repo: Arc<SettingsPgRepository>,
+        group_repo: Arc<GroupPgRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            settings_repo,
+            group_repo,
+        }
+    }
+
+    /// Whether the user has a confirmed TOTP secret.
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool> {
+        Ok(self
+            .repo
+            .get_totp(user_id)
+            .await?
+            .is_some_and(|totp| totp.confirmed))
+    }
+
+    pub async fn policy(&self) -> Result<TwoFactorPolicyDto> {
+        Ok(TwoFactorPolicyDto {
+            enforced_roles: parse_list(self.settings_repo.get(ENFORCED_ROLES_KEY).await?),
+            enforced_groups: parse_list(self.settings_repo.get(ENFORCED_GROUPS_KEY).await?),
+        })
+    }
+
+    pub async fn set_policy(
+        &self,
+        dto: TwoFactorPolicyDto,
+        updated_by: Uuid,
+    ) -> Result<TwoFactorPolicyDto> {
+        let roles = parse_list(Some(dto.enforced_roles.join(",")));
+        if let Some(role) = roles.iter().find(|r| *r != "admin" && *r != "user") {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "TwoFactor",
+                format!("Invalid role: {}. Must be 'admin' or 'user'", role),
+            ));
+        }
+        if dto.enforced_groups.iter().any(|g| g.contains(',')) {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "TwoFactor",
+                "Group names in the two-factor policy cannot contain commas",
+            ));
+        }
+        let groups = parse_list(Some(dto.enforced_groups.join(",")));
+
+        self.settings_repo
+            .set(
+                ENFORCED_ROLES_KEY,
+                &roles.join(","),
+                SETTINGS_CATEGORY,
+                false,
+                Some(updated_by),
+            )
+            .await?;
+        self.settings_repo
+            .set(
+                ENFORCED_GROUPS_KEY,
+                &groups.join(","),
+                SETTINGS_CATEGORY,
+                false,
+                Some(updated_by),
+            )
+            .await?;
+        info!(
+            "Two-factor policy updated: roles {:?}, groups {:?}",
+            roles, groups
+        );
+        self.policy().await
+    }
+
+    /// Whether the policy requires two-factor authentication of `user`.
+    /// SSO accounts are exempt: they never log in with a password.
+    pub async fn is_enforced(&self, user: &User) -> Result<bool> {
+        if user.is_oidc_user() && !user.is_ldap_user() {
+            return Ok(false);
+        }
+        let policy = self.policy().await?;
+        if policy.enforced_roles.contains(&user.role().to_string()) {
+            return Ok(true);
+        }
+        if policy.enforced_groups.is_empty() {
+            return Ok(false);
+        }
+        let groups = self.group_repo.groups_of_user(user.id()).await?;
+        Ok(groups.iter().any(|group| {
+            policy
+                .enforced_groups
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(group.name()))
+        }))
+    }
+
+    pub async fn status(&self, user: &User) -> Result<TwoFactorStatusDto> {
+        let enabled = self.is_enabled(user.id()).await?;
+        let recovery_codes_remaining = if enabled {
+            self.repo.remaining_recovery_codes(user.id()).await?
+        } else {
+            0
+        };
+        Ok(TwoFactorStatusDto {
+            enabled,
+            enforced: self.is_enforced(user).await?,
+            recovery_codes_remaining,
+        })
+    }
+
+    /// Generates a new secret for the user to add to their authenticator.
+    /// It only takes effect once confirmed with a code.
+    pub async fn begin_setup(&self, user: &User) -> Result<TotpSetupDto> {
+        if user.is_oidc_user() && !user.is_ldap_user() {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "TwoFactor",
+                "Two-factor authentication for SSO accounts is managed by your identity provider",
+            ));
+        }
+        let secret = totp::generate_secret();
+        self.repo.set_pending_totp(user.id(), &secret).await?;
+        Ok(TotpSetupDto {
+            otpauth_uri: totp::provisioning_uri(&secret, ISSUER, user.username()),
+            secret,
+        })
+    }
+
+    /// Completes enrolment with a code from the authenticator and returns
+    /// the first set of recovery codes.
+    pub async fn confirm_setup(&self, user_id: Uuid, code: &str) -> Result<RecoveryCodesDto> {
+        let pending = self
+            .repo
+            .get_totp(user_id)
+            .await?
+            .filter(|totp| !totp.confirmed)
+            .ok_or_else(|| {
+                DomainError::new(
+                    ErrorKind::InvalidInput,
+                    "TwoFactor",
+                    "No two-factor setup in progress",
+                )
+            })?;
+        let step =
+            totp::verify(&pending.secret, code, now_unix(), None).ok_or_else(invalid_code)?;
+
+        let codes = totp::generate_recovery_codes();
+        let hashes: Vec<String> = codes.iter().map(|c| totp::hash_recovery_code(c)).collect();
+        self.repo.confirm_totp(user_id, step, &hashes).await?;
+        info!(user_id = %user_id, "Two-factor authentication enabled");
+        Ok(RecoveryCodesDto {
+            recovery_codes: codes,
+        })
+    }
+
+    /// Checks a TOTP or recovery code of a user with two-factor
+    /// authentication enabled.  Accepted codes cannot be used again.
+    pub async fn verify(&self, user_id: Uuid, code: &str) -> Result<bool> {
+        let Some(secret) = self.repo.get_totp(user_id).await?.filter(|t| t.confirmed) else {
+            return Ok(false);
+        };
+        let code = code.trim();
+        if let Some(step) = totp::verify(&secret.secret, code, now_unix(), secret.last_used_step) {
+            return self.repo.use_totp_step(user_id, step).await;
+        }
+        let used = self
+            .repo
+            .use_recovery_code(user_id, &totp::hash_recovery_code(code))
+            .await?;
+        if used {
+            info!(user_id = %user_id, "Two-factor recovery code used");
+        }
+        Ok(used)
+    }
+
+    /// Turns two-factor authentication off after checking a current code.
+    pub async fn disable(&self, user: &User, code: &str) -> Result<()> {
+        if self.is_enforced(user).await? {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "TwoFactor",
+                "Two-factor authentication is required by your administrator",
+            ));
+        }
+        if !self.verify(user.id(), code).await? {
+            return Err(invalid_code());
+        }
+        self.repo.delete_totp(user.id()).await?;
+        info!(user_id = %user.id(), "Two-factor authentication disabled");
+        Ok(())
+    }
+
+    /// Replaces the recovery codes after checking a current code.
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<RecoveryCodesDto> {
+        if !self.verify(user_id, code).await? {
+            return Err(invalid_code());
+        }
+        let codes = totp::generate_recovery_codes();
+        let hashes: Vec<String> = codes.iter().map(|c| totp::hash_recovery_code(c)).collect();
+        self.repo.replace_recovery_codes(user_id, &hashes).await?;
+        Ok(RecoveryCodesDto {
+            recovery_codes: codes,
+        })
+    }
+
+    /// Admin: removes a user's second factor, e.g. after they lost their
+    /// device.  If it is enforced they enrol again on their next login.
+    pub async fn reset(&self, user_id: Uuid) -> Result<()> {
+        if !self.repo.delete_totp(user_id).await? {
+            return Err(DomainError::not_found(
+                "TwoFactor",
+                format!("user {user_id} has no two-factor authentication"),
+            ));
+        }
+        info!(user_id = %user_id, "Two-factor authentication reset by admin");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_list_trims_and_drops_empty_entries() {
+        assert_eq!(
+            parse_list(Some(" admin, ,user,".to_string())),
+            vec!["admin", "user"]
+        );
+        assert!(parse_list(None).is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+//! Signed-in devices of a user.
+//!
+//! A session here is a refresh token family: every refresh replaces the
+//! token but keeps the family, so one family is one login on one device.
+//! The overview adds the other ways into the account — app passwords and
+//! device authorizations — so a user can review them in one place.
+
+use std::sync::Arc;
+
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::application::dtos::session_dto::{SessionDto, SessionOverviewDto};
+use crate::application::ports::auth_ports::SessionStoragePort;
+use crate::application::services::app_password_service::AppPasswordService;
+use crate::application::services::device_auth_service::DeviceAuthService;
+use crate::common::errors::{DomainError, Result};
+use crate::domain::entities::session::SessionFamily;
+use crate::infrastructure::repositories::pg::SessionPgRepository;
+
+/// Prefix the device flow gives the user agent of its sessions
+const DEVICE_AGENT_PREFIX: &str = "device:";
+/// Longer user agents are cut before they are stored
+const MAX_USER_AGENT_LEN: usize = 512;
+
+pub struct UserSessionService {
+    session_storage: Arc<SessionPgRepository>,
+    app_passwords: Option<Arc<AppPasswordService>>,
+    devices: Option<Arc<DeviceAuthService>>,
+}
+
+impl UserSessionService {
+    pub fn new(
+        session_storage: Arc<SessionPgRepository>,
+        app_passwords: Option<Arc<AppPasswordService>>,
+        devices: Option<Arc<DeviceAuthService>>,
+    ) -> Self {
+        Self {
+            session_storage,
+            app_passwords,
+            devices,
+        }
+    }
+
+    /// Lists the signed-in devices of `user_id`.  `current_refresh_token`
+    /// is the refresh token of the caller, if any, and marks its session.
+    pub async fn list_sessions(
+        &self,
+        user_id: Uuid,
+        current_refresh_token: Option<&str>,
+    ) -> Result<Vec<SessionDto>> {
+        let current = self.current_family(user_id, current_refresh_token).await;
+        let families = self
+            .session_storage
+            .list_active_session_families(user_id)
+            .await?;
+        Ok(families
+            .into_iter()
+            .map(|family| session_dto(family, current))
+            .collect())
+    }
+
+    /// Signs a device out by revoking its token family.  Returns whether it
+    /// was the caller's own session.
+    pub async fn revoke_session(
+        &self,
+        user_id: Uuid,
+        family_id: Uuid,
+        current_refresh_token: Option<&str>,
+    ) -> Result<bool> {
+        let current = self.current_family(user_id, current_refresh_token).await;
+        let revoked = self
+            .session_storage
+            .revoke_user_session_family(user_id, family_id)
+            .await?;
+        if revoked == 0 {
+            return Err(DomainError::not_found(
+                "Session",
+                "Session not found or already signed out",
+            ));
+        }
+        Ok(current == Some(family_id))
+    }
+
+    /// Sessions, app passwords and pending device authorizations together.
+    pub async fn overview(
+        &self,
+        user_id: Uuid,
+        current_refresh_token: Option<&str>,
+    ) -> Result<SessionOverviewDto> {
+        let sessions = self.list_sessions(user_id, current_refresh_token).await?;
+        let app_passwords = match &self.app_passwords {
+            Some(service) => service.list(user_id).await?.app_passwords,
+            None => Vec::new(),
+        };
+        let devices = match &self.devices {
+            Some(service) => service.list_user_devices(user_id).await?,
+            None => Vec::new(),
+        };
+        Ok(SessionOverviewDto {
+            sessions,
+            app_passwords,
+            devices,
+        })
+    }
+
+    /// Records the client behind a freshly issued refresh token.  A failure
+    /// only costs the session list some detail, so it is logged, not raised.
+    pub async fn record_client(&self, refresh_token: &str, ip: &str, user_agent: Option<&str>) {
+        let user_agent = user_agent.map(|ua| truncate(ua, MAX_USER_AGENT_LEN));
+        if let Err(e) = self
+            .session_storage
+            .record_session_client(refresh_token, ip, user_agent)
+            .await
+        {
+            warn!("Could not record the client of a session: {}", e);
+        }
+    }
+
+    async fn current_family(&self, user_id: Uuid, refresh_token: Option<&str>) -> Option<Uuid> {
+        let session = self
+            .session_storage
+            .get_session_by_refresh_token(refresh_token?)
+            .await
+            .ok()?;
+        (session.user_id() == user_id && !session.is_revoked()).then(|| session.family_id())
+    }
+}
+
+fn truncate(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((end, _)) => &s[..end],
+        None => s,
+    }
+}
+
+fn session_dto(family: SessionFamily, current: Option<Uuid>) -> SessionDto {
+    SessionDto {
+        id: family.family_id.to_string(),
+        device: describe_user_agent(family.user_agent.as_deref()),
+        ip_address: family.ip_address,
+        user_agent: family.user_agent,
+        login_method: family.login_method.as_str().to_string(),
+        signed_in_at: family.signed_in_at,
+        last_used_at: family.last_used_at,
+        expires_at: family.expires_at,
+        current: current == Some(family.family_id),
+    }
+}
+
+/// Turns a user agent into a short label such as "Firefox on Linux".
+/// Device-flow sessions show the client name they were authorized for.
+fn describe_user_agent(user_agent: Option<&str>) -> String {
+    let Some(ua) = user_agent.map(str::trim).filter(|ua| !ua.is_empty()) else {
+        return "Unknown device".to_string();
+    };
+    if let Some(client) = ua.strip_prefix(DEVICE_AGENT_PREFIX) {
+        return client.to_string();
+    }
+
+    // Order matters: Edge and Opera also claim Chrome, Chrome claims Safari
+    const BROWSERS: &[(&str, &str)] = &[
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+        ("mirall/", "Nextcloud desktop"),
+        ("Nextcloud-android", "Nextcloud Android"),
+        ("Nextcloud-iOS", "Nextcloud iOS"),
+    ];
+    const SYSTEMS: &[(&str, &str)] = &[
+        ("Android", "Android"),
+        ("iPhone", "iOS"),
+        ("iPad", "iPadOS"),
+        ("CrOS", "ChromeOS"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("Macintosh", "macOS"),
+        ("Linux", "Linux"),
+    ];
+
+    let browser = BROWSERS
+        .iter()
+        .find(|(marker, _)| ua.contains(marker))
+        .map(|(_, name)| *name);
+    let system = SYSTEMS
+        .iter()
+        .find(|(marker, _)| ua.contains(marker))
+        .map(|(_, name)| *name);
+
+    match (browser, system) {
+        (Some(browser), Some(system)) => format!("{browser} on {system}"),
+        (Some(browser), None) => browser.to_string(),
+        // Tools such as curl or rclone: "name/version ..."
+        _ => ua
+            .split(['/', ' '])
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or("Unknown device")
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_user_agent_names_browser_and_system() {
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0";
+        assert_eq!(describe_user_agent(Some(firefox)), "Firefox on Linux");
+
+        let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                    (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36 Edg/129.0.0.0";
+        assert_eq!(describe_user_agent(Some(edge)), "Edge on Windows");
+
+        let safari = "Mozilla/5.0 (iPhone; CPU iPhone OS 18_0 like Mac OS X) \
+                      AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.0 Mobile/15E148 Safari/604.1";
+        assert_eq!(describe_user_agent(Some(safari)), "Safari on iOS");
+    }
+
+    #[test]
+    fn describe_user_agent_handles_clients_and_missing_agents() {
+        assert_eq!(describe_user_agent(Some("device:rclone")), "rclone");
+        assert_eq!(describe_user_agent(Some("curl/8.5.0")), "curl");
+        assert_eq!(describe_user_agent(Some("  ")), "Unknown device");
+        assert_eq!(describe_user_agent(None), "Unknown device");
+    }
+}
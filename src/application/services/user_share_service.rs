@@ -0,0 +1,370 @@
+//! Direct sharing of files and folders with other users.
+//!
+//! A share mounts the item in the recipient's home folder.  Access checks
+//! for shared items live in the repository (see `folder_access` /
+//! `file_access`) so that `FolderService`, `FileManagementService` and
+//! `FileRetrievalService` can consult them without depending on this
+//! service.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::application::dtos::share_dto::SharePermissionsDto;
+use crate::application::dtos::user_share_dto::{
+    CreateUserShareDto, UpdateUserShareDto, UserShareDto,
+};
+use crate::application::ports::live_event_ports::{LiveChange, LiveEventKind};
+use crate::application::ports::user_share_ports::{
+    NewUserShare, SharedAccess, SharedMount, UserShareRepositoryPort, UserShareUseCase,
+};
+use crate::application::services::live_event_service::LiveEventService;
+use crate::application::services::notification_service::NotificationService;
+use crate::application::services::webhook_service::WebhookService;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::services::path_service::{unique_file_name, validate_storage_name};
+use crate::infrastructure::repositories::pg::UserSharePgRepository;
+
+/// Service for files and folders shared directly with other users.
+pub struct UserShareService {
+    repo: Arc<UserSharePgRepository>,
+    notifications: Option<Arc<NotificationService>>,
+    webhooks: Option<Arc<WebhookService>>,
+    live_events: Option<Arc<LiveEventService>>,
+}
+
+impl UserShareService {
+    pub fn new(repo: Arc<UserSharePgRepository>) -> Self {
+        Self {
+            repo,
+            notifications: None,
+            webhooks: None,
+            live_events: None,
+        }
+    }
+
+    /// Notifies recipients of new shares.
+    pub fn with_notifications(mut self, notifications: Option<Arc<NotificationService>>) -> Self {
+        self.notifications = notifications;
+        self
+    }
+
+    /// Reports new and removed shares to the owner's webhooks.
+    pub fn with_webhooks(mut self, webhooks: Option<Arc<WebhookService>>) -> Self {
+        self.webhooks = webhooks;
+        self
+    }
+
+    /// Tells the clients of the owner, sharer and recipient about new,
+    /// changed and removed shares.
+    pub fn with_live_events(mut self, live_events: Option<Arc<LiveEventService>>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
+    fn publish_change(&self, kind: LiveEventKind, share: &UserShareDto) {
+        if let Some(live_events) = &self.live_events {
+            let users = [&share.owner_id, &share.shared_by, &share.recipient_id]
+                .into_iter()
+                .filter_map(|id| Uuid::parse_str(id).ok());
+            live_events.publish(
+                users,
+                LiveChange::new(kind, share.id.clone()).with_name(share.item_name.clone()),
+            );
+        }
+    }
+
+    fn invalid(msg: impl Into<String>) -> DomainError {
+        DomainError::new(ErrorKind::InvalidInput, "UserShare", msg.into())
+    }
+
+    /// "folder" → true, "file" → false.
+    fn parse_item_type(item_type: &str) -> Result<bool> {
+        match item_type {
+            "folder" => Ok(true),
+            "file" => Ok(false),
+            other => Err(Self::invalid(format!(
+                "Invalid item type '{other}': expected 'file' or 'folder'"
+            ))),
+        }
+    }
+
+    /// What `caller_id` may do with an item: everything for its owner, the
+    /// shared permissions for a recipient, `NotFound` otherwise.
+    pub async fn caller_access(
+        &self,
+        item_id: &str,
+        is_folder: bool,
+        caller_id: Uuid,
+    ) -> Result<SharedAccess> {
+        let (owner_id, _) = self.repo.item_owner(item_id, is_folder).await?;
+        if owner_id == caller_id {
+            return Ok(SharedAccess {
+                owner_id,
+                can_write: true,
+                can_reshare: true,
+            });
+        }
+        let access = if is_folder {
+            self.repo.folder_access(item_id, caller_id).await?
+        } else {
+            self.repo.file_access(item_id, caller_id).await?
+        };
+        access.ok_or_else(|| {
+            DomainError::not_found(if is_folder { "Folder" } else { "File" }, item_id)
+        })
+    }
+
+    /// The shares mounted in `user_id`'s home folder.
+    pub async fn list_mounts(&self, user_id: Uuid) -> Result<Vec<SharedMount>> {
+        self.repo.list_mounts(user_id).await
+    }
+
+    /// The share mounted as `mount_name` in `user_id`'s home folder.
+    pub async fn find_mount(&self, user_id: Uuid, mount_name: &str) -> Result<Option<SharedMount>> {
+        self.repo.find_mount(user_id, mount_name).await
+    }
+
+    /// Ids among `item_ids` that `user_id` shared, or that are shared and
+    /// owned by `user_id`.
+    pub async fn shared_item_ids(
+        &self,
+        user_id: Uuid,
+        item_ids: &[String],
+    ) -> Result<HashSet<String>> {
+        self.repo.shared_item_ids(user_id, item_ids).await
+    }
+}
+
+/// Permissions `(can_write, can_reshare)` to grant for `requested`; a
+/// resharer cannot grant more than they hold.
+fn granted_permissions(
+    requested: &SharePermissionsDto,
+    access: &SharedAccess,
+) -> Result<(bool, bool)> {
+    if (requested.write && !access.can_write) || (requested.reshare && !access.can_reshare) {
+        return Err(DomainError::access_denied(
+            "UserShare",
+            "Cannot grant more permissions than you have on this item",
+        ));
+    }
+    Ok((requested.write, requested.reshare))
+}
+
+impl UserShareUseCase for UserShareService {
+    async fn share_with_user(
+        &self,
+        caller_id: Uuid,
+        dto: CreateUserShareDto,
+    ) -> Result<UserShareDto> {
+        let is_folder = Self::parse_item_type(&dto.item_type)?;
+        let recipient_name = dto.recipient.trim();
+        let recipient = self
+            .repo
+            .find_recipient(recipient_name)
+            .await?
+            .ok_or_else(|| DomainError::not_found("User", recipient_name))?;
+
+        let access = self
+            .caller_access(&dto.item_id, is_folder, caller_id)
+            .await?;
+        if !access.can_reshare {
+            return Err(DomainError::access_denied(
+                "UserShare",
+                "You are not allowed to reshare this item",
+            ));
+        }
+        if recipient.id == caller_id {
+            return Err(Self::invalid("You cannot share an item with yourself"));
+        }
+        if recipient.id == access.owner_id {
+            return Err(Self::invalid("The recipient owns this item"));
+        }
+
+        let requested = dto.permissions.unwrap_or(SharePermissionsDto {
+            read: true,
+            write: false,
+            reshare: false,
+        });
+        let (can_write, can_reshare) = granted_permissions(&requested, &access)?;
+
+        let (_, item_name) = self.repo.item_owner(&dto.item_id, is_folder).await?;
+        let taken: HashSet<String> = self
+            .repo
+            .mount_names(recipient.id)
+            .await?
+            .into_iter()
+            .collect();
+        let mount_name = unique_file_name(&item_name, &taken);
+
+        let share = self
+            .repo
+            .create_share(&NewUserShare {
+                item_id: dto.item_id,
+                is_folder,
+                owner_id: access.owner_id,
+                shared_by: caller_id,
+                recipient_id: recipient.id,
+                can_write,
+                can_reshare,
+                mount_name,
+            })
+            .await?;
+        info!(
+            "{} '{}' shared with user '{}' (write: {}, reshare: {})",
+            share.item_type, share.item_name, recipient.username, can_write, can_reshare
+        );
+        if let Some(notifications) = &self.notifications {
+            notifications.share_received(&share);
+        }
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.user_share_created(&share);
+        }
+        self.publish_change(LiveEventKind::ShareCreated, &share);
+        Ok(share)
+    }
+
+    async fn list_outgoing(
+        &self,
+        caller_id: Uuid,
+        item: Option<(&str, &str)>,
+    ) -> Result<Vec<UserShareDto>> {
+        let item = match item {
+            Some((item_id, item_type)) => Some((item_id, Self::parse_item_type(item_type)?)),
+            None => None,
+        };
+        self.repo.list_outgoing(caller_id, item).await
+    }
+
+    async fn list_shared_with_me(&self, caller_id: Uuid) -> Result<Vec<UserShareDto>> {
+        self.repo.list_incoming(caller_id).await
+    }
+
+    async fn update_share(
+        &self,
+        share_id: &str,
+        caller_id: Uuid,
+        dto: UpdateUserShareDto,
+    ) -> Result<UserShareDto> {
+        let share = self.repo.get_share(share_id).await?;
+        let caller = caller_id.to_string();
+        let is_manager = share.owner_id == caller || share.shared_by == caller;
+        let is_recipient = share.recipient_id == caller;
+        if !is_manager && !is_recipient {
+            return Err(DomainError::not_found("UserShare", share_id));
+        }
+
+        let mut share = share;
+        if let Some(requested) = dto.permissions {
+            if !is_manager {
+                return Err(DomainError::access_denied(
+                    "UserShare",
+                    "Only the owner of a share can change its permissions",
+                ));
+            }
+            let is_folder = share.item_type == "folder";
+            let access = self
+                .caller_access(&share.item_id, is_folder, caller_id)
+                .await?;
+            let (can_write, can_reshare) = granted_permissions(&requested, &access)?;
+            share = self
+                .repo
+                .update_permissions(share_id, can_write, can_reshare)
+                .await?;
+        }
+
+        if let Some(mount_name) = dto.mount_name {
+            if !is_recipient {
+                return Err(DomainError::access_denied(
+                    "UserShare",
+                    "Only the recipient can rename a share",
+                ));
+            }
+            let mount_name = mount_name.trim();
+            if let Err(reason) = validate_storage_name(mount_name) {
+                return Err(Self::invalid(format!(
+                    "Invalid name '{mount_name}': {reason}"
+                )));
+            }
+            share = self.repo.rename_mount(share_id, mount_name).await?;
+        }
+
+        self.publish_change(LiveEventKind::ShareUpdated, &share);
+        Ok(share)
+    }
+
+    async fn delete_share(&self, share_id: &str, caller_id: Uuid) -> Result<()> {
+        let share = self.repo.get_share(share_id).await?;
+        let caller = caller_id.to_string();
+        if share.owner_id != caller && share.shared_by != caller && share.recipient_id != caller {
+            return Err(DomainError::not_found("UserShare", share_id));
+        }
+        self.repo.delete_share(share_id).await?;
+        if let Some(notifications) = &self.notifications {
+            notifications.share_removed(share_id);
+        }
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.user_share_deleted(&share);
+        }
+        self.publish_change(LiveEventKind::ShareDeleted, &share);
+        info!(
+            "Share of {} '{}' with user '{}' removed",
+            share.item_type, share.item_name, share.recipient_name
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access(can_write: bool, can_reshare: bool) -> SharedAccess {
+        SharedAccess {
+            owner_id: Uuid::new_v4(),
+            can_write,
+            can_reshare,
+        }
+    }
+
+    fn permissions(write: bool, reshare: bool) -> SharePermissionsDto {
+        SharePermissionsDto {
+            read: true,
+            write,
+            reshare,
+        }
+    }
+
+    #[test]
+    fn owners_can_grant_any_permissions() {
+        let owner = access(true, true);
+        assert_eq!(
+            granted_permissions(&permissions(true, true), &owner).unwrap(),
+            (true, true)
+        );
+        assert_eq!(
+            granted_permissions(&permissions(false, false), &owner).unwrap(),
+            (false, false)
+        );
+    }
+
+    #[test]
+    fn resharers_cannot_escalate_permissions() {
+        let read_only_resharer = access(false, true);
+        assert_eq!(
+            granted_permissions(&permissions(false, true), &read_only_resharer).unwrap(),
+            (false, true)
+        );
+        let err = granted_permissions(&permissions(true, false), &read_only_resharer).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+    }
+
+    #[test]
+    fn item_types_are_validated() {
+        assert!(UserShareService::parse_item_type("folder").unwrap());
+        assert!(!UserShareService::parse_item_type("file").unwrap());
+        assert!(UserShareService::parse_item_type("calendar").is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! WebAuthn passkeys: registration, credential management and the
+//! authentication ceremony used for passwordless logins and as a second
+//! factor.
+//!
+//! Challenges live in memory for the duration of a ceremony and are looked
+//! up through the challenge echoed in the client data, so a challenge can
+//! only be answered once.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use moka::sync::Cache;
+use rand_core::{OsRng, RngCore};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::dtos::webauthn_dto::{
+    AssertionCredentialDto, AuthenticatorSelectionDto, CredentialCreationOptionsDto,
+    CredentialDescriptorDto, CredentialParameterDto, CredentialRequestOptionsDto,
+    PublicKeyCreationOptionsDto, PublicKeyRequestOptionsDto, RelyingPartyDto,
+    WebAuthnCredentialDto, WebAuthnRegisterDto, WebAuthnUserDto,
+};
+use crate::application::ports::auth_ports::SessionStoragePort;
+use crate::application::ports::webauthn_ports::{WebAuthnCredential, WebAuthnRepositoryPort};
+use crate::common::config::WebAuthnConfig;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::entities::user::User;
+use crate::infrastructure::repositories::pg::{SessionPgRepository, WebAuthnPgRepository};
+use crate::infrastructure::services::webauthn::{
+    self, AuthenticatorData, Ceremony, ClientData, SUPPORTED_ALGORITHMS, WebAuthnError,
+};
+
+/// Lifetime of a registration or login challenge
+const CEREMONY_TTL_SECS: u64 = 300;
+const MAX_NAME_LEN: usize = 100;
+const DEFAULT_NAME: &str = "Passkey";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PendingCeremony {
+    Registration {
+        user_id: Uuid,
+    },
+    /// `user_id` is set when the passkey is the second factor of that user
+    Authentication {
+        user_id: Option<Uuid>,
+    },
+}
+
+fn webauthn_error(e: WebAuthnError) -> DomainError {
+    let kind = match e {
+        WebAuthnError::Malformed(_) | WebAuthnError::UnsupportedAlgorithm(_) => {
+            ErrorKind::InvalidInput
+        }
+        _ => ErrorKind::AccessDenied,
+    };
+    DomainError::new(kind, "WebAuthn", e.to_string())
+}
+
+fn decode(value: &str, what: &'static str) -> Result<Vec<u8>> {
+    webauthn::decode_base64url(value).ok_or_else(|| webauthn_error(WebAuthnError::Malformed(what)))
+}
+
+fn descriptor(credential: &WebAuthnCredential) -> CredentialDescriptorDto {
+    CredentialDescriptorDto {
+        credential_type: "public-key".to_string(),
+        id: webauthn::encode_base64url(&credential.credential_id),
+        transports: credential.transports.clone(),
+    }
+}
+
+/// Trims a passkey label, falling back to a default.
+fn credential_name(name: Option<&str>) -> String {
+    let name: String = name
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_NAME_LEN)
+        .collect();
+    if name.is_empty() {
+        DEFAULT_NAME.to_string()
+    } else {
+        name
+    }
+}
+
+/// Whether an assertion's signature counter shows a cloned authenticator.
+/// Authenticators without a counter always report zero.
+fn counter_regressed(stored: u32, received: u32) -> bool {
+    (stored != 0 || received != 0) && received <= stored
+}
+
+/// Service for WebAuthn credentials and ceremonies.
+pub struct WebAuthnService {
+    repo: Arc<WebAuthnPgRepository>,
+    session_repo: Arc<SessionPgRepository>,
+    config: WebAuthnConfig,
+    /// Open ceremonies keyed by their challenge.
+    /// Auto-expires after 5 minutes via moka TTL; max 10 000 entries for DoS protection.
+    ceremonies: Cache<Vec<u8>, PendingCeremony>,
+}
+
+impl WebAuthnService {
+    pub fn new(
+        repo: Arc<WebAuthnPgRepository>,
+        session_repo: Arc<SessionPgRepository>,
+        config: WebAuthnConfig,
+    ) -> Self {
+        Self {
+            repo,
+            session_repo,
+            config,
+            ceremonies: Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(Duration::from_secs(CEREMONY_TTL_SECS))
+                .build(),
+        }
+    }
+
+    fn new_challenge(&self, ceremony: PendingCeremony) -> Vec<u8> {
+        let mut challenge = vec![0u8; 32];
+        OsRng.fill_bytes(&mut challenge);
+        self.ceremonies.insert(challenge.clone(), ceremony);
+        challenge
+    }
+
+    /// Parses the client data and consumes the ceremony it answers.
+    fn take_ceremony(
+        &self,
+        client_data_json: &[u8],
+        ceremony: Ceremony,
+    ) -> Result<(ClientData, PendingCeremony)> {
+        let client_data = ClientData::parse(client_data_json).map_err(webauthn_error)?;
+        let pending = self
+            .ceremonies
+            .remove(&client_data.challenge)
+            .ok_or_else(|| {
+                DomainError::new(
+                    ErrorKind::AccessDenied,
+                    "WebAuthn",
+                    "Passkey challenge expired or invalid. Please try again.",
+                )
+            })?;
+        // Found by its challenge; this checks the type and origin
+        client_data
+            .check(ceremony, &client_data.challenge, &self.config.origins)
+            .map_err(webauthn_error)?;
+        Ok((client_data, pending))
+    }
+
+    pub async fn has_credentials(&self, user_id: Uuid) -> Result<bool> {
+        Ok(self.repo.count_credentials(user_id).await? > 0)
+    }
+
+    pub async fn list_credentials(&self, user_id: Uuid) -> Result<Vec<WebAuthnCredentialDto>> {
+        let credentials = self.repo.list_credentials(user_id).await?;
+        Ok(credentials
+            .iter()
+            .map(WebAuthnCredentialDto::from)
+            .collect())
+    }
+
+    /// Starts registering a passkey for `user`.
+    pub async fn registration_options(&self, user: &User) -> Result<CredentialCreationOptionsDto> {
+        if user.is_oidc_user() && !user.is_ldap_user() {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "WebAuthn",
+                "Sign-in of SSO accounts is managed by your identity provider",
+            ));
+        }
+        let existing = self.repo.list_credentials(user.id()).await?;
+        let challenge = self.new_challenge(PendingCeremony::Registration { user_id: user.id() });
+
+        Ok(CredentialCreationOptionsDto {
+            public_key: PublicKeyCreationOptionsDto {
+                rp: RelyingPartyDto {
+                    id: self.config.rp_id.clone(),
+                    name: self.config.rp_name.clone(),
+                },
+                user: WebAuthnUserDto {
+                    id: webauthn::encode_base64url(user.id().as_bytes()),
+                    name: user.username().to_string(),
+                    display_name: user.username().to_string(),
+                },
+                challenge: webauthn::encode_base64url(&challenge),
+                pub_key_cred_params: SUPPORTED_ALGORITHMS
+                    .iter()
+                    .map(|&alg| CredentialParameterDto {
+                        credential_type: "public-key".to_string(),
+                        alg,
+                    })
+                    .collect(),
+                timeout: CEREMONY_TTL_SECS * 1000,
+                exclude_credentials: existing.iter().map(descriptor).collect(),
+                // Discoverable credentials make passwordless login possible
+                authenticator_selection: AuthenticatorSelectionDto {
+                    resident_key: "preferred".to_string(),
+                    require_resident_key: false,
+                    user_verification: "preferred".to_string(),
+                },
+                attestation: "none".to_string(),
+            },
+        })
+    }
+
+    /// Verifies the new credential and stores it.
+    pub async fn finish_registration(
+        &self,
+        user_id: Uuid,
+        dto: WebAuthnRegisterDto,
+    ) -> Result<WebAuthnCredentialDto> {
+        let response = &dto.credential.response;
+        let client_data_json = decode(&response.client_data_json, "client data")?;
+        let (_, pending) = self.take_ceremony(&client_data_json, Ceremony::Registration)?;
+        if pending != (PendingCeremony::Registration { user_id }) {
+            return Err(webauthn_error(WebAuthnError::ChallengeMismatch));
+        }
+
+        let attestation_object = decode(&response.attestation_object, "attestation object")?;
+        let auth_data = webauthn::attestation_auth_data(&attestation_object)
+            .and_then(|data| AuthenticatorData::parse(&data))
+            .map_err(webauthn_error)?;
+        auth_data
+            .check(&self.config.rp_id, false)
+            .map_err(webauthn_error)?;
+        let attested = auth_data
+            .attested_credential
+            .ok_or_else(|| webauthn_error(WebAuthnError::Malformed("attested credential")))?;
+        let raw_id = decode(
+            dto.credential.raw_id.as_ref().unwrap_or(&dto.credential.id),
+            "credential ID",
+        )?;
+        if raw_id != attested.credential_id {
+            return Err(webauthn_error(WebAuthnError::Malformed("credential ID")));
+        }
+
+        let credential = WebAuthnCredential {
+            id: Uuid::new_v4(),
+            user_id,
+            credential_id: attested.credential_id,
+            public_key: attested.public_key,
+            algorithm: attested.algorithm,
+            sign_count: auth_data.sign_count,
+            name: credential_name(dto.name.as_deref()),
+            transports: response
+                .transports
+                .iter()
+                .filter(|t| t.len() <= 32)
+                .take(8)
+                .cloned()
+                .collect(),
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        self.repo.create_credential(&credential).await?;
+        info!(user_id = %user_id, "Passkey '{}' registered", credential.name);
+        Ok(WebAuthnCredentialDto::from(&credential))
+    }
+
+    /// Removes a passkey and revokes the sessions opened with it.
+    pub async fn delete_credential(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let owned = self
+            .repo
+            .list_credentials(user_id)
+            .await?
+            .iter()
+            .any(|c| c.id == id);
+        if !owned {
+            return Err(DomainError::not_found("WebAuthn", format!("passkey {id}")));
+        }
+        self.session_repo.revoke_webauthn_sessions(id).await?;
+        self.repo.delete_credential(user_id, id).await?;
+        info!(user_id = %user_id, "Passkey {} removed", id);
+        Ok(())
+    }
+
+    /// Starts a login.  For a second factor the user's credentials are
+    /// listed; a passwordless login lets the authenticator pick a
+    /// discoverable credential and requires user verification.
+    pub async fn authentication_options(
+        &self,
+        user_id: Option<Uuid>,
+    ) -> Result<CredentialRequestOptionsDto> {
+        let allow_credentials = match user_id {
+            Some(user_id) => {
+                let credentials = self.repo.list_credentials(user_id).await?;
+                if credentials.is_empty() {
+                    return Err(DomainError::new(
+                        ErrorKind::InvalidInput,
+                        "WebAuthn",
+                        "No passkey is registered for this account",
+                    ));
+                }
+                credentials.iter().map(descriptor).collect()
+            }
+            None => Vec::new(),
+        };
+        let challenge = self.new_challenge(PendingCeremony::Authentication { user_id });
+
+        Ok(CredentialRequestOptionsDto {
+            public_key: PublicKeyRequestOptionsDto {
+                challenge: webauthn::encode_base64url(&challenge),
+                timeout: CEREMONY_TTL_SECS * 1000,
+                rp_id: self.config.rp_id.clone(),
+                allow_credentials,
+                user_verification: if user_id.is_some() {
+                    "preferred"
+                } else {
+                    "required"
+                }
+                .to_string(),
+            },
+        })
+    }
+
+    /// Verifies an assertion and returns the credential that signed it.
+    /// `user_id` must match the user the options were created for.
+    pub async fn finish_authentication(
+        &self,
+        user_id: Option<Uuid>,
+        dto: &AssertionCredentialDto,
+    ) -> Result<WebAuthnCredential> {
+        let response = &dto.response;
+        let client_data_json = decode(&response.client_data_json, "client data")?;
+        let (_, pending) = self.take_ceremony(&client_data_json, Ceremony::Authentication)?;
+        if pending != (PendingCeremony::Authentication { user_id }) {
+            return Err(webauthn_error(WebAuthnError::ChallengeMismatch));
+        }
+
+        let unknown = || DomainError::new(ErrorKind::AccessDenied, "WebAuthn", "Unknown passkey");
+        let raw_id = decode(dto.raw_id.as_ref().unwrap_or(&dto.id), "credential ID")?;
+        let credential = self
+            .repo
+            .find_by_credential_id(&raw_id)
+            .await?
+            .ok_or_else(unknown)?;
+        if user_id.is_some_and(|id| id != credential.user_id) {
+            return Err(unknown());
+        }
+        if let Some(handle) = response.user_handle.as_deref().filter(|h| !h.is_empty())
+            && decode(handle, "user handle")? != credential.user_id.as_bytes()
+        {
+            return Err(unknown());
+        }
+
+        let authenticator_data = decode(&response.authenticator_data, "authenticator data")?;
+        let auth_data = AuthenticatorData::parse(&authenticator_data).map_err(webauthn_error)?;
+        auth_data
+            .check(&self.config.rp_id, user_id.is_none())
+            .map_err(webauthn_error)?;
+        let signature = decode(&response.signature, "signature")?;
+        webauthn::verify_assertion_signature(
+            &credential.public_key,
+            &authenticator_data,
+            &client_data_json,
+            &signature,
+        )
+        .map_err(webauthn_error)?;
+
+        if counter_regressed(credential.sign_count, auth_data.sign_count) {
+            warn!(
+                user_id = %credential.user_id,
+                "Passkey {} reported signature counter {} after {}; it may have been cloned",
+                credential.id,
+                auth_data.sign_count,
+                credential.sign_count
+            );
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "WebAuthn",
+                "This passkey cannot be used: its signature counter went backwards",
+            ));
+        }
+        self.repo
+            .record_use(credential.id, auth_data.sign_count)
+            .await?;
+        Ok(credential)
+    }
+
+    /// Ties a session token family to the passkey that opened it.
+    pub async fn link_session_family(&self, credential_id: Uuid, family_id: Uuid) -> Result<()> {
+        self.repo
+            .link_session_family(credential_id, family_id)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_regression_ignores_authenticators_without_counter() {
+        assert!(!counter_regressed(0, 0));
+        assert!(!counter_regressed(4, 5));
+        assert!(counter_regressed(5, 5));
+        assert!(counter_regressed(5, 0));
+    }
+
+    #[test]
+    fn credential_name_is_trimmed_and_defaulted() {
+        assert_eq!(credential_name(Some("  Laptop \n")), "Laptop");
+        assert_eq!(credential_name(Some("   ")), DEFAULT_NAME);
+        assert_eq!(credential_name(None), DEFAULT_NAME);
+        assert_eq!(credential_name(Some(&"x".repeat(300))).len(), MAX_NAME_LEN);
+    }
+}
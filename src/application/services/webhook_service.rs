@@ -0,0 +1,873 @@
+//! Outgoing webhooks: users and administrators subscribe URLs to file and
+//! share events, which are posted there as HMAC-signed JSON.
+//!
+//! A user's webhooks receive the events about what the user owns; admin
+//! webhooks receive the events of everyone.  Every delivery is logged and
+//! failed attempts are retried in the background with exponential backoff,
+//! so emitting an event never slows down or fails the operation behind it.
+//! File events arrive through the file lifecycle hooks.  A deleted file is
+//! gone by the time its hook runs, so its owner, path and type come from
+//! the last delivery about it; without one, only admin webhooks without
+//! file filters hear of the deletion.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, KeyInit, Mac};
+use rand_core::{OsRng, RngCore};
+use serde_json::json;
+use sha2::Sha256;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::application::dtos::user_share_dto::UserShareDto;
+use crate::application::dtos::webhook_dto::{
+    CreateWebhookDto, UpdateWebhookDto, WebhookDeliveryDto, WebhookDto,
+};
+use crate::application::ports::file_lifecycle::{
+    FileCreatedHook, FileDeletedHook, FileUpdatedHook,
+};
+use crate::application::ports::storage_ports::FileReadPort;
+use crate::application::ports::webhook_ports::{
+    DeliveryStatus, WebhookEvent, WebhookRecord, WebhookRepositoryPort, WebhookRequest,
+    WebhookSenderPort,
+};
+use crate::common::config::WebhookConfig;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::entities::share::Share;
+use crate::infrastructure::repositories::pg::WebhookPgRepository;
+use crate::infrastructure::repositories::pg::file_blob_read_repository::FileBlobReadRepository;
+use crate::infrastructure::services::retry_blob_backend::RetryPolicy;
+use crate::infrastructure::services::webhook_sender::{WebhookSender, is_public_address};
+
+/// Most webhooks a single user may register
+const MAX_WEBHOOKS_PER_USER: usize = 20;
+const MAX_DESCRIPTION_LEN: usize = 200;
+const DEFAULT_DELIVERY_LIMIT: usize = 50;
+const MAX_DELIVERY_LIMIT: usize = 500;
+/// Pending deliveries younger than this are resumed after a restart
+const RESUME_WINDOW_HOURS: i64 = 24;
+
+pub const SIGNATURE_HEADER: &str = "X-OxiCloud-Signature";
+pub const EVENT_HEADER: &str = "X-OxiCloud-Event";
+pub const DELIVERY_HEADER: &str = "X-OxiCloud-Delivery";
+pub const WEBHOOK_HEADER: &str = "X-OxiCloud-Webhook";
+
+fn invalid(message: impl Into<String>) -> DomainError {
+    DomainError::new(ErrorKind::InvalidInput, "Webhook", message)
+}
+
+/// Signature of a payload: `sha256=` and the hex HMAC-SHA256 of the body.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Whether an answer asks for another attempt: timeouts, rate limits and
+/// server errors.
+fn is_retryable_status(status: u16) -> bool {
+    status == 408 || status == 429 || (500..600).contains(&status)
+}
+
+fn validate_url(url: &str, allow_private: bool) -> Result<String> {
+    let url = url.trim();
+    let parsed =
+        reqwest::Url::parse(url).map_err(|e| invalid(format!("Invalid URL '{}': {}", url, e)))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(invalid("Webhook URLs must use http or https"));
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| invalid("Webhook URL has no host"))?;
+    if !allow_private {
+        // Names are checked again when they are resolved for each delivery
+        let bare = host.trim_start_matches('[').trim_end_matches(']');
+        let private_ip = bare
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| !is_public_address(ip));
+        if private_ip || bare.eq_ignore_ascii_case("localhost") || bare.ends_with(".localhost") {
+            return Err(invalid(
+                "Webhooks cannot post to local or private addresses",
+            ));
+        }
+    }
+    Ok(url.to_string())
+}
+
+fn validate_events(events: Vec<String>) -> Result<Vec<String>> {
+    let mut valid = Vec::new();
+    for event in events.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        match WebhookEvent::parse(event) {
+            Some(WebhookEvent::Ping) | None => {
+                return Err(invalid(format!("Unknown webhook event '{}'", event)));
+            }
+            Some(_) if valid.iter().any(|v| v == event) => {}
+            Some(_) => valid.push(event.to_string()),
+        }
+    }
+    Ok(valid)
+}
+
+/// Normalizes a path filter to `/a/b`; `None` for an empty one.
+fn normalize_path_prefix(prefix: Option<String>) -> Option<String> {
+    let prefix = prefix?;
+    let trimmed = prefix.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(format!("/{}", trimmed))
+    }
+}
+
+fn validate_mime_types(mime_types: Vec<String>) -> Result<Vec<String>> {
+    let mut valid = Vec::new();
+    for mime in mime_types.iter().map(|m| m.trim().to_ascii_lowercase()) {
+        if mime.is_empty() {
+            continue;
+        }
+        match mime.split_once('/') {
+            Some((kind, sub)) if !kind.is_empty() && !sub.is_empty() && !kind.contains('*') => {}
+            _ => return Err(invalid(format!("Invalid MIME type '{}'", mime))),
+        }
+        if !valid.contains(&mime) {
+            valid.push(mime);
+        }
+    }
+    Ok(valid)
+}
+
+fn validate_description(description: Option<String>) -> Result<String> {
+    let description = description.unwrap_or_default().trim().to_string();
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(invalid(format!(
+            "Description is longer than {} characters",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    Ok(description)
+}
+
+fn path_matches(prefix: &str, path: &str) -> bool {
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn mime_matches(pattern: &str, mime: &str) -> bool {
+    let mime = mime.to_ascii_lowercase();
+    match pattern.strip_suffix("/*") {
+        Some(kind) => mime
+            .split_once('/')
+            .is_some_and(|(mime_kind, _)| mime_kind == kind),
+        None => pattern == mime,
+    }
+}
+
+/// An event about to be sent.
+struct Event {
+    kind: WebhookEvent,
+    /// Owner of the file or share; `None` when not known
+    owner_id: Option<Uuid>,
+    /// Path and MIME type of the file, for the filters of file events
+    path: Option<String>,
+    mime_type: Option<String>,
+    payload: serde_json::Value,
+}
+
+impl Event {
+    fn new(kind: WebhookEvent, owner_id: Option<Uuid>, key: &str, data: serde_json::Value) -> Self {
+        let mut payload = json!({
+            "event": kind.as_str(),
+            "timestamp": Utc::now().to_rfc3339(),
+            "user_id": owner_id.map(|id| id.to_string()),
+        });
+        payload[key] = data;
+        Self {
+            kind,
+            owner_id,
+            path: None,
+            mime_type: None,
+            payload,
+        }
+    }
+
+    fn file(kind: WebhookEvent, owner_id: Option<Uuid>, file: serde_json::Value) -> Self {
+        let path = file["path"].as_str().map(str::to_string);
+        let mime_type = file["mime_type"].as_str().map(str::to_string);
+        Self {
+            path,
+            mime_type,
+            ..Self::new(kind, owner_id, "file", file)
+        }
+    }
+
+    fn is_file_event(&self) -> bool {
+        matches!(
+            self.kind,
+            WebhookEvent::FileCreated | WebhookEvent::FileUpdated | WebhookEvent::FileDeleted
+        )
+    }
+
+    /// Whether the webhook's filters let the event through.  Path and MIME
+    /// filters only apply to file events and reject files whose path or
+    /// type is not known.
+    fn matches(&self, webhook: &WebhookRecord) -> bool {
+        if !webhook.events.is_empty() && !webhook.events.iter().any(|e| e == self.kind.as_str()) {
+            return false;
+        }
+        if !self.is_file_event() {
+            return true;
+        }
+        if let Some(prefix) = &webhook.path_prefix {
+            match &self.path {
+                Some(path) if path_matches(prefix, path) => {}
+                _ => return false,
+            }
+        }
+        if !webhook.mime_types.is_empty() {
+            match &self.mime_type {
+                Some(mime) if webhook.mime_types.iter().any(|p| mime_matches(p, mime)) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+/// State shared by the service and its background deliveries.
+struct Delivery {
+    repository: Arc<WebhookPgRepository>,
+    sender: Arc<WebhookSender>,
+    policy: RetryPolicy,
+    allow_private_targets: bool,
+}
+
+impl Delivery {
+    fn request(
+        &self,
+        webhook: &WebhookRecord,
+        delivery_id: i64,
+        event: &str,
+        body: String,
+    ) -> WebhookRequest {
+        WebhookRequest {
+            url: webhook.url.clone(),
+            headers: vec![
+                (SIGNATURE_HEADER, sign(&webhook.secret, &body)),
+                (EVENT_HEADER, event.to_string()),
+                (DELIVERY_HEADER, delivery_id.to_string()),
+                (WEBHOOK_HEADER, webhook.id.to_string()),
+            ],
+            body,
+            // Admin webhooks are set up by whoever runs the server
+            allow_private_target: webhook.owner_id.is_none() || self.allow_private_targets,
+        }
+    }
+
+    /// Makes one attempt and logs it.  Returns the status the delivery is
+    /// left in; `retry` says whether a failure may be retried.
+    async fn attempt(
+        &self,
+        request: &WebhookRequest,
+        delivery_id: i64,
+        retry: bool,
+    ) -> (DeliveryStatus, Option<u16>, Option<String>) {
+        let (status, response_status, error) = match self.sender.send(request).await {
+            Ok(code) if (200..300).contains(&code) => (DeliveryStatus::Delivered, Some(code), None),
+            Ok(code) => {
+                let status = if retry && is_retryable_status(code) {
+                    DeliveryStatus::Pending
+                } else {
+                    DeliveryStatus::Failed
+                };
+                (
+                    status,
+                    Some(code),
+                    Some(format!("Receiver answered HTTP {}", code)),
+                )
+            }
+            Err(e) => {
+                let status = if retry && e.kind != ErrorKind::AccessDenied {
+                    DeliveryStatus::Pending
+                } else {
+                    DeliveryStatus::Failed
+                };
+                (status, None, Some(e.message))
+            }
+        };
+        if let Err(e) = self
+            .repository
+            .record_attempt(delivery_id, status, response_status, error.as_deref())
+            .await
+        {
+            warn!("Could not log webhook delivery {}: {}", delivery_id, e);
+        }
+        (status, response_status, error)
+    }
+
+    /// Delivers with retries, after `attempts` earlier attempts.
+    async fn run(
+        &self,
+        webhook: WebhookRecord,
+        delivery_id: i64,
+        event: String,
+        body: String,
+        attempts: u32,
+    ) {
+        let request = self.request(&webhook, delivery_id, &event, body);
+        let mut attempt = attempts;
+        let mut backoff = self.policy.initial_backoff;
+        for _ in 0..attempt {
+            backoff = self.next_backoff(backoff);
+        }
+        loop {
+            let retry = attempt < self.policy.max_retries;
+            let (status, _, error) = self.attempt(&request, delivery_id, retry).await;
+            match status {
+                DeliveryStatus::Delivered => {
+                    debug!(
+                        "Webhook {} received {} ({})",
+                        webhook.id, event, delivery_id
+                    );
+                    return;
+                }
+                DeliveryStatus::Failed => {
+                    warn!(
+                        "Giving up delivering {} to webhook {}: {}",
+                        event,
+                        webhook.id,
+                        error.unwrap_or_default()
+                    );
+                    return;
+                }
+                DeliveryStatus::Pending => {
+                    attempt += 1;
+                    debug!(
+                        "Retry {}/{} of delivery {} in {:?}",
+                        attempt, self.policy.max_retries, delivery_id, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = self.next_backoff(backoff);
+                }
+            }
+        }
+    }
+
+    fn next_backoff(&self, backoff: Duration) -> Duration {
+        Duration::from_secs_f64(backoff.as_secs_f64() * self.policy.backoff_multiplier)
+            .min(self.policy.max_backoff)
+    }
+}
+
+/// Service for managing webhooks and delivering events to them.
+pub struct WebhookService {
+    delivery: Arc<Delivery>,
+    /// Resolves owner, path and type of files reported by the lifecycle hooks
+    file_read: Arc<FileBlobReadRepository>,
+}
+
+impl WebhookService {
+    pub fn new(
+        repository: Arc<WebhookPgRepository>,
+        sender: Arc<WebhookSender>,
+        file_read: Arc<FileBlobReadRepository>,
+        config: &WebhookConfig,
+    ) -> Self {
+        let policy = RetryPolicy {
+            max_retries: if config.retry.enabled {
+                config.retry.max_retries
+            } else {
+                0
+            },
+            initial_backoff: Duration::from_millis(config.retry.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.retry.max_backoff_ms),
+            backoff_multiplier: config.retry.backoff_multiplier,
+        };
+        Self {
+            delivery: Arc::new(Delivery {
+                repository,
+                sender,
+                policy,
+                allow_private_targets: config.allow_private_targets,
+            }),
+            file_read,
+        }
+    }
+
+    fn repository(&self) -> &WebhookPgRepository {
+        &self.delivery.repository
+    }
+
+    /// The webhook with `id` if it belongs to `owner_id` (`None` for admin
+    /// webhooks).
+    async fn owned(&self, owner_id: Option<Uuid>, id: &str) -> Result<WebhookRecord> {
+        let not_found = || DomainError::not_found("Webhook", id);
+        let uuid = Uuid::parse_str(id).map_err(|_| not_found())?;
+        match self.repository().get(uuid).await? {
+            Some(webhook) if webhook.owner_id == owner_id => Ok(webhook),
+            _ => Err(not_found()),
+        }
+    }
+
+    /// Webhooks of a user, or with `None` the admin webhooks.
+    pub async fn list(&self, owner_id: Option<Uuid>) -> Result<Vec<WebhookDto>> {
+        let webhooks = self.repository().list(owner_id).await?;
+        Ok(webhooks.into_iter().map(WebhookDto::from).collect())
+    }
+
+    /// Registers a webhook; the returned one carries its signing key.
+    pub async fn create(
+        &self,
+        owner_id: Option<Uuid>,
+        dto: CreateWebhookDto,
+    ) -> Result<WebhookDto> {
+        let allow_private = owner_id.is_none() || self.delivery.allow_private_targets;
+        if owner_id.is_some()
+            && self.repository().list(owner_id).await?.len() >= MAX_WEBHOOKS_PER_USER
+        {
+            return Err(invalid(format!(
+                "A user can have at most {} webhooks",
+                MAX_WEBHOOKS_PER_USER
+            )));
+        }
+        let now = Utc::now();
+        let webhook = WebhookRecord {
+            id: Uuid::new_v4(),
+            owner_id,
+            url: validate_url(&dto.url, allow_private)?,
+            secret: generate_secret(),
+            description: validate_description(dto.description)?,
+            events: validate_events(dto.events)?,
+            path_prefix: normalize_path_prefix(dto.path_prefix),
+            mime_types: validate_mime_types(dto.mime_types)?,
+            active: true,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repository().create(&webhook).await?;
+        info!("Webhook {} created for {}", webhook.id, webhook.url);
+        let secret = webhook.secret.clone();
+        Ok(WebhookDto {
+            secret: Some(secret),
+            ..WebhookDto::from(webhook)
+        })
+    }
+
+    /// Changes a webhook; returns the new signing key if it was rotated.
+    pub async fn update(
+        &self,
+        owner_id: Option<Uuid>,
+        id: &str,
+        dto: UpdateWebhookDto,
+    ) -> Result<WebhookDto> {
+        let mut webhook = self.owned(owner_id, id).await?;
+        let allow_private = owner_id.is_none() || self.delivery.allow_private_targets;
+        if let Some(url) = dto.url {
+            webhook.url = validate_url(&url, allow_private)?;
+        }
+        if dto.description.is_some() {
+            webhook.description = validate_description(dto.description)?;
+        }
+        if let Some(events) = dto.events {
+            webhook.events = validate_events(events)?;
+        }
+        if dto.path_prefix.is_some() {
+            webhook.path_prefix = normalize_path_prefix(dto.path_prefix);
+        }
+        if let Some(mime_types) = dto.mime_types {
+            webhook.mime_types = validate_mime_types(mime_types)?;
+        }
+        if let Some(active) = dto.active {
+            webhook.active = active;
+        }
+        if dto.rotate_secret {
+            webhook.secret = generate_secret();
+        }
+        webhook.updated_at = Utc::now();
+        self.repository().update(&webhook).await?;
+        let secret = dto.rotate_secret.then(|| webhook.secret.clone());
+        Ok(WebhookDto {
+            secret,
+            ..WebhookDto::from(webhook)
+        })
+    }
+
+    pub async fn delete(&self, owner_id: Option<Uuid>, id: &str) -> Result<()> {
+        let webhook = self.owned(owner_id, id).await?;
+        self.repository().delete(webhook.id).await?;
+        info!("Webhook {} deleted", webhook.id);
+        Ok(())
+    }
+
+    /// Latest deliveries to a webhook, newest first.
+    pub async fn deliveries(
+        &self,
+        owner_id: Option<Uuid>,
+        id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<WebhookDeliveryDto>> {
+        let webhook = self.owned(owner_id, id).await?;
+        let limit = limit
+            .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+            .clamp(1, MAX_DELIVERY_LIMIT);
+        let deliveries = self.repository().list_deliveries(webhook.id, limit).await?;
+        Ok(deliveries
+            .into_iter()
+            .map(WebhookDeliveryDto::from)
+            .collect())
+    }
+
+    /// Sends a `ping` event once, without retries, and returns the outcome.
+    pub async fn ping(&self, owner_id: Option<Uuid>, id: &str) -> Result<WebhookDeliveryDto> {
+        let webhook = self.owned(owner_id, id).await?;
+        let event = Event::new(
+            WebhookEvent::Ping,
+            owner_id,
+            "webhook",
+            json!({ "id": webhook.id.to_string(), "url": webhook.url }),
+        );
+        let name = event.kind.as_str();
+        let created_at = Utc::now();
+        let delivery_id = self
+            .repository()
+            .create_delivery(webhook.id, name, &event.payload)
+            .await?;
+        let request = self
+            .delivery
+            .request(&webhook, delivery_id, name, event.payload.to_string());
+        let (status, response_status, error) =
+            self.delivery.attempt(&request, delivery_id, false).await;
+        Ok(WebhookDeliveryDto {
+            id: delivery_id,
+            event: name.to_string(),
+            payload: event.payload,
+            status: status.as_str().to_string(),
+            attempts: 1,
+            response_status: response_status.map(i32::from),
+            error,
+            created_at,
+            last_attempt_at: Some(Utc::now()),
+        })
+    }
+
+    /// Resumes deliveries that were still being retried when the server
+    /// stopped.
+    pub async fn resume_pending(&self) {
+        let since = Utc::now() - chrono::Duration::hours(RESUME_WINDOW_HOURS);
+        let pending = match self.repository().pending_deliveries(since).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                warn!("Could not load pending webhook deliveries: {}", e);
+                return;
+            }
+        };
+        if !pending.is_empty() {
+            info!("Resuming {} pending webhook deliveries", pending.len());
+        }
+        for record in pending {
+            let webhook = match self.repository().get(record.webhook_id).await {
+                Ok(Some(webhook)) if webhook.active => webhook,
+                _ => continue,
+            };
+            let delivery = self.delivery.clone();
+            tokio::spawn(async move {
+                delivery
+                    .run(
+                        webhook,
+                        record.id,
+                        record.event,
+                        record.payload.to_string(),
+                        record.attempts.max(0) as u32,
+                    )
+                    .await;
+            });
+        }
+    }
+
+    /// Sends an event to every matching webhook in the background.
+    fn emit(&self, event: Event) {
+        let delivery = self.delivery.clone();
+        tokio::spawn(async move { Self::dispatch(delivery, event).await });
+    }
+
+    async fn dispatch(delivery: Arc<Delivery>, event: Event) {
+        let webhooks = match delivery.repository.subscribers(event.owner_id).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                warn!("Could not find webhooks for {}: {}", event.kind.as_str(), e);
+                return;
+            }
+        };
+        let name = event.kind.as_str();
+        let body = event.payload.to_string();
+        for webhook in webhooks.into_iter().filter(|w| event.matches(w)) {
+            let delivery_id = match delivery
+                .repository
+                .create_delivery(webhook.id, name, &event.payload)
+                .await
+            {
+                Ok(id) => id,
+                Err(e) => {
+                    warn!("Could not log delivery to webhook {}: {}", webhook.id, e);
+                    continue;
+                }
+            };
+            let delivery = delivery.clone();
+            let body = body.clone();
+            tokio::spawn(async move {
+                delivery
+                    .run(webhook, delivery_id, name.to_string(), body, 0)
+                    .await;
+            });
+        }
+    }
+
+    /// Reports a hook-reported file event about a file that still exists.
+    fn emit_file_event(&self, kind: WebhookEvent, file_id: &str) {
+        let file_read = self.file_read.clone();
+        let delivery = self.delivery.clone();
+        let file_id = file_id.to_string();
+        tokio::spawn(async move {
+            let file = match file_read.get_file(&file_id).await {
+                Ok(file) => file,
+                Err(e) => {
+                    debug!("Webhooks: file {} not found: {}", file_id, e);
+                    return;
+                }
+            };
+            let data = json!({
+                "id": file.id(),
+                "name": file.name(),
+                "path": file.path_string(),
+                "mime_type": file.mime_type(),
+                "size": file.size(),
+                "folder_id": file.folder_id(),
+                "modified_at": file.modified_at(),
+            });
+            Self::dispatch(delivery, Event::file(kind, file.owner_id(), data)).await;
+        });
+    }
+
+    /// Reports the deletion of a file, described by the last delivery
+    /// about it.
+    fn emit_deleted_file(&self, file_id: &str) {
+        let delivery = self.delivery.clone();
+        let file_id = file_id.to_string();
+        tokio::spawn(async move {
+            let (owner_id, data) = match delivery.repository.last_known_file(&file_id).await {
+                Ok(Some((owner_id, file))) if file.is_object() => (owner_id, file),
+                _ => (None, json!({ "id": file_id })),
+            };
+            Self::dispatch(
+                delivery,
+                Event::file(WebhookEvent::FileDeleted, owner_id, data),
+            )
+            .await;
+        });
+    }
+
+    fn link_data(share: &Share) -> serde_json::Value {
+        json!({
+            "id": share.id().to_string(),
+            "type": "link",
+            "item_id": share.item_id(),
+            "item_type": share.item_type().to_string(),
+            "item_name": share.item_name(),
+            "has_password": share.has_password(),
+            "expires_at": share.expires_at(),
+        })
+    }
+
+    /// Reports a new shared link.
+    pub fn link_created(&self, share: &Share) {
+        self.emit(Event::new(
+            WebhookEvent::ShareCreated,
+            Some(share.created_by()),
+            "share",
+            Self::link_data(share),
+        ));
+    }
+
+    /// Reports a deleted shared link.
+    pub fn link_deleted(&self, share: &Share) {
+        self.emit(Event::new(
+            WebhookEvent::ShareDeleted,
+            Some(share.created_by()),
+            "share",
+            Self::link_data(share),
+        ));
+    }
+
+    fn user_share_data(share: &UserShareDto) -> serde_json::Value {
+        json!({
+            "id": share.id,
+            "type": "user",
+            "item_id": share.item_id,
+            "item_type": share.item_type,
+            "item_name": share.item_name,
+            "shared_by": share.shared_by,
+            "recipient_id": share.recipient_id,
+            "recipient_name": share.recipient_name,
+            "can_write": share.permissions.write,
+            "can_reshare": share.permissions.reshare,
+        })
+    }
+
+    /// Reports a share with another user, to the webhooks of the owner.
+    pub fn user_share_created(&self, share: &UserShareDto) {
+        self.emit(Event::new(
+            WebhookEvent::ShareCreated,
+            Uuid::parse_str(&share.owner_id).ok(),
+            "share",
+            Self::user_share_data(share),
+        ));
+    }
+
+    /// Reports a removed share with another user.
+    pub fn user_share_deleted(&self, share: &UserShareDto) {
+        self.emit(Event::new(
+            WebhookEvent::ShareDeleted,
+            Uuid::parse_str(&share.owner_id).ok(),
+            "share",
+            Self::user_share_data(share),
+        ));
+    }
+}
+
+impl FileCreatedHook for WebhookService {
+    fn on_file_created<'a>(
+        &'a self,
+        file_id: &'a str,
+        _blob_hash: &'a str,
+        _content_type: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move { self.emit_file_event(WebhookEvent::FileCreated, file_id) })
+    }
+}
+
+impl FileUpdatedHook for WebhookService {
+    fn on_file_updated<'a>(
+        &'a self,
+        file_id: &'a str,
+        _blob_hash: &'a str,
+        _content_type: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move { self.emit_file_event(WebhookEvent::FileUpdated, file_id) })
+    }
+}
+
+impl FileDeletedHook for WebhookService {
+    fn on_file_deleted<'a>(
+        &'a self,
+        file_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move { self.emit_deleted_file(file_id) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: &[&str], path_prefix: Option<&str>, mime_types: &[&str]) -> WebhookRecord {
+        WebhookRecord {
+            id: Uuid::new_v4(),
+            owner_id: None,
+            url: "https://example.com/hook".to_string(),
+            secret: "s".to_string(),
+            description: String::new(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            path_prefix: path_prefix.map(str::to_string),
+            mime_types: mime_types.iter().map(|m| m.to_string()).collect(),
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn file_event(kind: WebhookEvent, path: &str, mime: &str) -> Event {
+        Event::file(
+            kind,
+            None,
+            json!({ "id": "f1", "path": path, "mime_type": mime }),
+        )
+    }
+
+    #[test]
+    fn signature_is_hmac_sha256_of_the_body() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn filters_match_event_path_and_mime_type() {
+        let pdf = file_event(
+            WebhookEvent::FileCreated,
+            "/Home/Scans/a.pdf",
+            "application/pdf",
+        );
+        assert!(pdf.matches(&webhook(&[], None, &[])));
+        assert!(pdf.matches(&webhook(
+            &["file_created"],
+            Some("/Home/Scans"),
+            &["application/pdf"]
+        )));
+        assert!(!pdf.matches(&webhook(&["file_deleted"], None, &[])));
+        assert!(!pdf.matches(&webhook(&[], Some("/Home/Scan"), &[])));
+        assert!(!pdf.matches(&webhook(&[], None, &["image/*"])));
+
+        let photo = file_event(WebhookEvent::FileUpdated, "/Home/p.JPG", "image/JPEG");
+        assert!(photo.matches(&webhook(&[], Some("/Home"), &["image/*"])));
+
+        // Deleted files whose path is not known only pass webhooks without file filters
+        let gone = Event::file(WebhookEvent::FileDeleted, None, json!({ "id": "f1" }));
+        assert!(gone.matches(&webhook(&[], None, &[])));
+        assert!(!gone.matches(&webhook(&[], Some("/Home"), &[])));
+
+        // File filters do not apply to share events
+        let share = Event::new(WebhookEvent::ShareCreated, None, "share", json!({}));
+        assert!(share.matches(&webhook(&[], Some("/Home"), &["image/*"])));
+        assert!(!share.matches(&webhook(&["file_created"], None, &[])));
+    }
+
+    #[test]
+    fn subscriptions_are_validated() {
+        assert!(validate_url("https://hooks.example.com/x", false).is_ok());
+        assert!(validate_url("ftp://example.com/x", false).is_err());
+        assert!(validate_url("http://127.0.0.1:8080/x", false).is_err());
+        assert!(validate_url("http://[::1]/x", false).is_err());
+        assert!(validate_url("http://localhost/x", false).is_err());
+        assert!(validate_url("http://localhost/x", true).is_ok());
+
+        assert_eq!(
+            validate_events(vec!["file_created".into(), "file_created".into()]).unwrap(),
+            vec!["file_created"]
+        );
+        assert!(validate_events(vec!["ping".into()]).is_err());
+        assert!(validate_mime_types(vec!["*/*".into()]).is_err());
+        assert_eq!(
+            validate_mime_types(vec![" Image/* ".into()]).unwrap(),
+            vec!["image/*"]
+        );
+        assert_eq!(
+            normalize_path_prefix(Some("Home/Scans/".into())).as_deref(),
+            Some("/Home/Scans")
+        );
+        assert_eq!(normalize_path_prefix(Some("/".into())), None);
+    }
+}
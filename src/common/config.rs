@@ -208,6 +208,10 @@ pub struct StorageConfig {
     pub parallel_threshold: usize,
     /// Retention days for files in the trash
     pub trash_retention_days: u32,
+    /// Maximum number of previous versions kept per file (oldest are pruned).
+    pub max_file_versions: u32,
+    /// Days activity log entries are kept (0 = forever)
+    pub activity_retention_days: u32,
     /// Maximum upload file size in bytes (default: 10 GB).
     /// Applied as a hard limit to WebDAV PUT and streaming uploads.
     pub max_upload_size: usize,
@@ -347,6 +351,8 @@ impl Default for StorageConfig {
             chunk_size: 1024 * 1024,               // 1 MB
             parallel_threshold: 100 * 1024 * 1024, // 100 MB
             trash_retention_days: 30,              // 30 days
+            max_file_versions: 50,
+            activity_retention_days: 90,
             max_upload_size: MAX_UPLOAD_SIZE,
             backend: StorageBackendType::Local,
             s3: None,
@@ -547,6 +553,449 @@ impl OidcConfig {
     }
 }
 
+/// LDAP / Active Directory authentication configuration
+#[derive(Debug, Clone)]
+pub struct LdapConfig {
+    /// Whether LDAP authentication is enabled
+    pub enabled: bool,
+    /// Server URL (ldap://host:389 or ldaps://host:636)
+    pub url: String,
+    /// Upgrade plain ldap:// connections with StartTLS
+    pub starttls: bool,
+    /// Skip TLS certificate verification (self-signed test servers only)
+    pub tls_skip_verify: bool,
+    /// DN of the service account used to search for users (empty = anonymous)
+    pub bind_dn: String,
+    /// Password of the service account
+    pub bind_password: String,
+    /// Base DN under which users are searched
+    pub user_base_dn: String,
+    /// Search filter; `{username}` is replaced by the escaped login name
+    pub user_filter: String,
+    /// Attribute holding the login name
+    pub username_attribute: String,
+    /// Attribute holding the email address
+    pub email_attribute: String,
+    /// Attribute holding the display name
+    pub display_name_attribute: String,
+    /// Attribute listing the user's groups (DNs or plain names)
+    pub groups_attribute: String,
+    /// Attribute holding the storage quota in bytes (empty = not mapped)
+    pub quota_attribute: String,
+    /// Comma-separated list of LDAP groups that map to admin role
+    pub admin_groups: String,
+    /// Whether to auto-create users on first LDAP login
+    pub auto_provision: bool,
+    /// Connection and operation timeout in seconds
+    pub timeout_secs: u64,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "ldap://localhost:389".to_string(),
+            starttls: false,
+            tls_skip_verify: false,
+            bind_dn: String::new(),
+            bind_password: String::new(),
+            user_base_dn: String::new(),
+            user_filter: "(&(objectClass=person)(uid={username}))".to_string(),
+            username_attribute: "uid".to_string(),
+            email_attribute: "mail".to_string(),
+            display_name_attribute: "cn".to_string(),
+            groups_attribute: "memberOf".to_string(),
+            quota_attribute: String::new(),
+            admin_groups: String::new(),
+            auto_provision: true,
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl LdapConfig {
+    /// Load LDAP configuration from environment variables
+    pub fn from_env() -> Self {
+        use std::env;
+        let mut cfg = Self::default();
+        if let Ok(v) = env::var("OXICLOUD_LDAP_ENABLED") {
+            cfg.enabled = v.parse::<bool>().unwrap_or(false);
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_URL") {
+            cfg.url = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_STARTTLS") {
+            cfg.starttls = v.parse::<bool>().unwrap_or(false);
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_TLS_SKIP_VERIFY") {
+            cfg.tls_skip_verify = v.parse::<bool>().unwrap_or(false);
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_BIND_DN") {
+            cfg.bind_dn = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_BIND_PASSWORD") {
+            cfg.bind_password = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_USER_BASE_DN") {
+            cfg.user_base_dn = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_USER_FILTER") {
+            cfg.user_filter = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_USERNAME_ATTRIBUTE") {
+            cfg.username_attribute = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_EMAIL_ATTRIBUTE") {
+            cfg.email_attribute = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_DISPLAY_NAME_ATTRIBUTE") {
+            cfg.display_name_attribute = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_GROUPS_ATTRIBUTE") {
+            cfg.groups_attribute = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_QUOTA_ATTRIBUTE") {
+            cfg.quota_attribute = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_ADMIN_GROUPS") {
+            cfg.admin_groups = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_AUTO_PROVISION") {
+            cfg.auto_provision = v.parse::<bool>().unwrap_or(true);
+        }
+        if let Ok(v) = env::var("OXICLOUD_LDAP_TIMEOUT_SECS")
+            && let Ok(n) = v.parse::<u64>()
+        {
+            cfg.timeout_secs = n;
+        }
+        cfg
+    }
+}
+
+/// WebAuthn (passkey) relying party configuration
+#[derive(Debug, Clone)]
+pub struct WebAuthnConfig {
+    /// Relying party ID: the domain passkeys are bound to
+    pub rp_id: String,
+    /// Name shown by authenticators
+    pub rp_name: String,
+    /// Origins the login pages are served from
+    pub origins: Vec<String>,
+}
+
+impl Default for WebAuthnConfig {
+    fn default() -> Self {
+        Self::for_base_url("http://localhost:8086")
+    }
+}
+
+impl WebAuthnConfig {
+    /// Derives the RP ID and origin from the public base URL.
+    pub fn for_base_url(base_url: &str) -> Self {
+        let origin = match base_url.find("://") {
+            Some(scheme_end) => {
+                let authority_end = base_url[scheme_end + 3..]
+                    .find('/')
+                    .map_or(base_url.len(), |i| scheme_end + 3 + i);
+                &base_url[..authority_end]
+            }
+            None => base_url,
+        };
+        let authority = origin.split_once("://").map_or(origin, |(_, a)| a);
+        let host = match authority.strip_prefix('[') {
+            // IPv6 literal
+            Some(rest) => rest.split(']').next().unwrap_or(rest),
+            None => authority.split(':').next().unwrap_or(authority),
+        };
+        Self {
+            rp_id: host.to_lowercase(),
+            rp_name: "OxiCloud".to_string(),
+            origins: vec![origin.to_lowercase()],
+        }
+    }
+
+    /// Load WebAuthn configuration from environment variables, defaulting
+    /// to the domain and origin of `base_url`
+    pub fn from_env(base_url: &str) -> Self {
+        use std::env;
+        let mut cfg = Self::for_base_url(base_url);
+        if let Ok(v) = env::var("OXICLOUD_WEBAUTHN_RP_ID") {
+            let v = v.trim();
+            if !v.is_empty() {
+                cfg.rp_id = v.to_lowercase();
+            }
+        }
+        if let Ok(v) = env::var("OXICLOUD_WEBAUTHN_RP_NAME") {
+            cfg.rp_name = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_WEBAUTHN_ORIGINS") {
+            let origins: Vec<String> = v
+                .split(',')
+                .map(|o| o.trim().trim_end_matches('/').to_lowercase())
+                .filter(|o| !o.is_empty())
+                .collect();
+            if !origins.is_empty() {
+                cfg.origins = origins;
+            }
+        }
+        cfg
+    }
+}
+
+/// How outgoing mail is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailTransportKind {
+    /// No mail is sent; features that need mail are unavailable
+    Disabled,
+    /// Delivered through an SMTP server
+    Smtp,
+    /// Written as .eml files into a directory
+    File,
+    /// Written to the server log
+    Log,
+}
+
+/// TLS mode of the SMTP connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTlsMode {
+    /// Plain connection (local relays and test sinks only)
+    None,
+    /// Plain connection upgraded with STARTTLS
+    StartTls,
+    /// TLS from the first byte (SMTPS)
+    Tls,
+}
+
+/// Outgoing mail configuration
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    /// Delivery transport
+    pub transport: MailTransportKind,
+    /// Sender address, e.g. "OxiCloud <noreply@example.com>"
+    pub from: String,
+    /// SMTP server host
+    pub smtp_host: String,
+    /// SMTP server port
+    pub smtp_port: u16,
+    /// SMTP TLS mode
+    pub smtp_tls: SmtpTlsMode,
+    /// SMTP user name (empty = no authentication)
+    pub smtp_username: String,
+    /// SMTP password
+    pub smtp_password: String,
+    /// Directory the file transport writes messages to
+    pub file_dir: PathBuf,
+    /// Lifetime of password reset links in seconds
+    pub reset_token_ttl_secs: i64,
+    /// Lifetime of email verification links in seconds
+    pub verification_token_ttl_secs: i64,
+    /// Whether self-registered accounts must verify their address before
+    /// they can sign in
+    pub verify_registrations: bool,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: MailTransportKind::Disabled,
+            from: "OxiCloud <noreply@localhost>".to_string(),
+            smtp_host: "localhost".to_string(),
+            smtp_port: 587,
+            smtp_tls: SmtpTlsMode::StartTls,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            file_dir: PathBuf::from("./mail"),
+            reset_token_ttl_secs: 3600,         // 1 hour
+            verification_token_ttl_secs: 86400, // 24 hours
+            verify_registrations: true,
+        }
+    }
+}
+
+impl MailConfig {
+    /// Whether a transport is configured
+    pub fn enabled(&self) -> bool {
+        self.transport != MailTransportKind::Disabled
+    }
+
+    /// Load mail configuration from environment variables
+    pub fn from_env() -> Self {
+        use std::env;
+        let mut cfg = Self::default();
+        if let Ok(v) = env::var("OXICLOUD_MAIL_TRANSPORT") {
+            cfg.transport = match v.trim().to_lowercase().as_str() {
+                "smtp" => MailTransportKind::Smtp,
+                "file" => MailTransportKind::File,
+                "log" => MailTransportKind::Log,
+                "" | "none" | "disabled" => MailTransportKind::Disabled,
+                other => {
+                    tracing::error!(
+                        "Unknown OXICLOUD_MAIL_TRANSPORT '{}' (expected smtp, file, log or none) — mail disabled",
+                        other
+                    );
+                    MailTransportKind::Disabled
+                }
+            };
+        }
+        if let Ok(v) = env::var("OXICLOUD_MAIL_FROM") {
+            cfg.from = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_SMTP_HOST") {
+            cfg.smtp_host = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_SMTP_TLS") {
+            cfg.smtp_tls = match v.trim().to_lowercase().as_str() {
+                "none" | "off" | "false" => SmtpTlsMode::None,
+                "tls" | "smtps" => SmtpTlsMode::Tls,
+                _ => SmtpTlsMode::StartTls,
+            };
+            // Follow the TLS mode unless a port is set explicitly
+            cfg.smtp_port = match cfg.smtp_tls {
+                SmtpTlsMode::None => 25,
+                SmtpTlsMode::StartTls => 587,
+                SmtpTlsMode::Tls => 465,
+            };
+        }
+        if let Ok(v) = env::var("OXICLOUD_SMTP_PORT")
+            && let Ok(port) = v.parse::<u16>()
+        {
+            cfg.smtp_port = port;
+        }
+        if let Ok(v) = env::var("OXICLOUD_SMTP_USERNAME") {
+            cfg.smtp_username = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_SMTP_PASSWORD") {
+            cfg.smtp_password = v;
+        }
+        if let Ok(v) = env::var("OXICLOUD_MAIL_FILE_DIR") {
+            cfg.file_dir = PathBuf::from(v);
+        }
+        if let Ok(v) = env::var("OXICLOUD_MAIL_RESET_TOKEN_TTL_SECS")
+            && let Ok(n) = v.parse::<i64>()
+        {
+            cfg.reset_token_ttl_secs = n;
+        }
+        if let Ok(v) = env::var("OXICLOUD_MAIL_VERIFICATION_TOKEN_TTL_SECS")
+            && let Ok(n) = v.parse::<i64>()
+        {
+            cfg.verification_token_ttl_secs = n;
+        }
+        if let Ok(v) = env::var("OXICLOUD_MAIL_VERIFY_REGISTRATIONS") {
+            cfg.verify_registrations = v.parse::<bool>().unwrap_or(true);
+        }
+        cfg
+    }
+}
+
+/// Outgoing webhook configuration
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Whether users and administrators can register webhooks
+    pub enabled: bool,
+    /// Retries of a failed delivery (network errors, 408, 429 and 5xx)
+    pub retry: RetryConfig,
+    /// Whether user webhooks may post to loopback and private network
+    /// addresses.  Admin webhooks always may.
+    pub allow_private_targets: bool,
+    /// Days delivery log entries are kept
+    pub log_retention_days: u32,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retry: RetryConfig {
+                enabled: true,
+                max_retries: 5,
+                initial_backoff_ms: 5_000,
+                max_backoff_ms: 300_000,
+                backoff_multiplier: 2.0,
+            },
+            allow_private_targets: false,
+            log_retention_days: 30,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Load webhook configuration from environment variables
+    pub fn from_env() -> Self {
+        use std::env;
+        let mut cfg = Self::default();
+        if let Ok(v) = env::var("OXICLOUD_WEBHOOKS_ENABLED") {
+            cfg.enabled = v.parse::<bool>().unwrap_or(true);
+        }
+        if let Ok(v) = env::var("OXICLOUD_WEBHOOKS_MAX_RETRIES")
+            && let Ok(n) = v.parse::<u32>()
+        {
+            cfg.retry.max_retries = n;
+        }
+        if let Ok(v) = env::var("OXICLOUD_WEBHOOKS_INITIAL_BACKOFF_MS")
+            && let Ok(n) = v.parse::<u64>()
+        {
+            cfg.retry.initial_backoff_ms = n;
+        }
+        if let Ok(v) = env::var("OXICLOUD_WEBHOOKS_MAX_BACKOFF_MS")
+            && let Ok(n) = v.parse::<u64>()
+        {
+            cfg.retry.max_backoff_ms = n;
+        }
+        if let Ok(v) = env::var("OXICLOUD_WEBHOOKS_ALLOW_PRIVATE_TARGETS") {
+            cfg.allow_private_targets = v.parse::<bool>().unwrap_or(false);
+        }
+        if let Ok(v) = env::var("OXICLOUD_WEBHOOKS_LOG_RETENTION_DAYS")
+            && let Ok(n) = v.parse::<u32>()
+        {
+            cfg.log_retention_days = n;
+        }
+        cfg
+    }
+}
+
+/// Live change notifications (`/api/events` and Nextcloud notify_push)
+#[derive(Debug, Clone)]
+pub struct LiveEventsConfig {
+    /// Whether clients can subscribe to changes of their files
+    pub enabled: bool,
+    /// Relay changes between instances sharing the database through
+    /// PostgreSQL LISTEN/NOTIFY.  Needed when several instances run behind
+    /// a load balancer.
+    pub pg_notify: bool,
+    /// Seconds between keep-alive messages on idle connections
+    pub keepalive_secs: u64,
+}
+
+impl Default for LiveEventsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pg_notify: false,
+            keepalive_secs: 30,
+        }
+    }
+}
+
+impl LiveEventsConfig {
+    /// Load live event configuration from environment variables
+    pub fn from_env() -> Self {
+        use std::env;
+        let mut cfg = Self::default();
+        if let Ok(v) = env::var("OXICLOUD_LIVE_EVENTS_ENABLED") {
+            cfg.enabled = v.parse::<bool>().unwrap_or(true);
+        }
+        if let Ok(v) = env::var("OXICLOUD_LIVE_EVENTS_PG_NOTIFY") {
+            cfg.pg_notify = v.parse::<bool>().unwrap_or(false);
+        }
+        if let Ok(v) = env::var("OXICLOUD_LIVE_EVENTS_KEEPALIVE_SECS")
+            && let Ok(n) = v.parse::<u64>()
+        {
+            cfg.keepalive_secs = n.max(1);
+        }
+        cfg
+    }
+}
+
 /// WOPI (Web Application Open Platform Interface) configuration
 #[derive(Debug, Clone)]
 pub struct WopiConfig {
@@ -618,6 +1067,16 @@ pub struct FeaturesConfig {
     pub enable_trash: bool,
     pub enable_search: bool,
     pub enable_music: bool,
+    /// Keep previous contents of a file when it is overwritten.
+    pub enable_file_versions: bool,
+    /// Named folder snapshots (manual and scheduled).
+    pub enable_folder_snapshots: bool,
+    /// Record file, share and sign-in events in the activity log.
+    pub enable_activity_log: bool,
+    /// Nextcloud end-to-end encryption API (encrypted folders).
+    pub enable_e2ee: bool,
+    /// Notifications about shares, storage quota and expiring links.
+    pub enable_notifications: bool,
     /// Expose other OxiCloud users as a read-only "system" address book
     /// at GET /api/address-books. Set to false to hide the user directory.
     pub expose_system_users: bool,
@@ -632,6 +1091,11 @@ impl Default for FeaturesConfig {
             enable_trash: true,        // Enable trash feature
             enable_search: true,       // Enable search feature
             enable_music: true,        // Enable music feature
+            enable_file_versions: true,
+            enable_folder_snapshots: true,
+            enable_activity_log: true,
+            enable_e2ee: true,
+            enable_notifications: true,
             expose_system_users: true, // Expose OxiCloud users as address book by default
         }
     }
@@ -666,6 +1130,16 @@ pub struct AppConfig {
     pub features: FeaturesConfig,
     /// OIDC configuration
     pub oidc: OidcConfig,
+    /// LDAP configuration
+    pub ldap: LdapConfig,
+    /// WebAuthn (passkey) configuration
+    pub webauthn: WebAuthnConfig,
+    /// Outgoing mail configuration
+    pub mail: MailConfig,
+    /// Outgoing webhook configuration
+    pub webhooks: WebhookConfig,
+    /// Live change notifications
+    pub live_events: LiveEventsConfig,
     /// WOPI configuration
     pub wopi: WopiConfig,
     /// Nextcloud compatibility configuration
@@ -688,6 +1162,11 @@ impl Default for AppConfig {
             auth: AuthConfig::default(),
             features: FeaturesConfig::default(),
             oidc: OidcConfig::default(),
+            ldap: LdapConfig::default(),
+            webauthn: WebAuthnConfig::default(),
+            mail: MailConfig::default(),
+            webhooks: WebhookConfig::default(),
+            live_events: LiveEventsConfig::default(),
             wopi: WopiConfig::default(),
             nextcloud: NextcloudConfig::default(),
         }
@@ -952,6 +1431,36 @@ impl AppConfig {
             config.features.enable_music = val;
         }
 
+        if let Ok(v) = env::var("OXICLOUD_ENABLE_FILE_VERSIONS").map(|v| v.parse::<bool>())
+            && let Ok(val) = v
+        {
+            config.features.enable_file_versions = val;
+        }
+
+        if let Ok(v) = env::var("OXICLOUD_ENABLE_FOLDER_SNAPSHOTS").map(|v| v.parse::<bool>())
+            && let Ok(val) = v
+        {
+            config.features.enable_folder_snapshots = val;
+        }
+
+        if let Ok(v) = env::var("OXICLOUD_ENABLE_ACTIVITY_LOG").map(|v| v.parse::<bool>())
+            && let Ok(val) = v
+        {
+            config.features.enable_activity_log = val;
+        }
+
+        if let Ok(v) = env::var("OXICLOUD_ENABLE_E2EE").map(|v| v.parse::<bool>())
+            && let Ok(val) = v
+        {
+            config.features.enable_e2ee = val;
+        }
+
+        if let Ok(v) = env::var("OXICLOUD_ENABLE_NOTIFICATIONS").map(|v| v.parse::<bool>())
+            && let Ok(val) = v
+        {
+            config.features.enable_notifications = val;
+        }
+
         if let Ok(v) = env::var("OXICLOUD_EXPOSE_SYSTEM_USERS").map(|v| v.parse::<bool>())
             && let Ok(val) = v
         {
@@ -965,6 +1474,18 @@ impl AppConfig {
             config.storage.max_upload_size = val;
         }
 
+        if let Ok(v) = env::var("OXICLOUD_MAX_FILE_VERSIONS").map(|v| v.parse::<u32>())
+            && let Ok(val) = v
+        {
+            config.storage.max_file_versions = val;
+        }
+
+        if let Ok(v) = env::var("OXICLOUD_ACTIVITY_RETENTION_DAYS").map(|v| v.parse::<u32>())
+            && let Ok(val) = v
+        {
+            config.storage.activity_retention_days = val;
+        }
+
         // Storage backend selection
         if let Ok(backend) = env::var("OXICLOUD_STORAGE_BACKEND") {
             match backend.to_lowercase().as_str() {
@@ -1101,6 +1622,37 @@ impl AppConfig {
             config.oidc.enabled = false;
         }
 
+        // LDAP configuration
+        config.ldap = LdapConfig::from_env();
+        if config.ldap.enabled
+            && (config.ldap.url.is_empty()
+                || config.ldap.user_base_dn.is_empty()
+                || !config.ldap.user_filter.contains("{username}"))
+        {
+            tracing::error!(
+                "LDAP is enabled but OXICLOUD_LDAP_URL or OXICLOUD_LDAP_USER_BASE_DN is not set, or OXICLOUD_LDAP_USER_FILTER lacks {{username}}"
+            );
+            config.ldap.enabled = false;
+        }
+
+        // WebAuthn relying party, bound to the public URL by default
+        config.webauthn = WebAuthnConfig::from_env(&config.base_url());
+
+        // Outgoing mail (password resets, address verification)
+        config.mail = MailConfig::from_env();
+        if config.mail.transport == MailTransportKind::Smtp && config.mail.smtp_host.is_empty() {
+            tracing::error!(
+                "OXICLOUD_MAIL_TRANSPORT=smtp but OXICLOUD_SMTP_HOST is empty — mail disabled"
+            );
+            config.mail.transport = MailTransportKind::Disabled;
+        }
+
+        // Outgoing webhooks
+        config.webhooks = WebhookConfig::from_env();
+
+        // Live change notifications
+        config.live_events = LiveEventsConfig::from_env();
+
         // WOPI configuration
         if let Ok(v) = env::var("OXICLOUD_WOPI_ENABLED") {
             config.wopi.enabled = v.parse::<bool>().unwrap_or(false);
@@ -2,8 +2,12 @@ use sqlx::PgPool;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::application::ports::activity_ports::ActivityRepositoryPort;
 use crate::application::ports::blob_storage_ports::BlobStorageBackend;
+use crate::application::ports::webhook_ports::WebhookRepositoryPort;
 use crate::common::config::StorageBackendType;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
 use crate::infrastructure::db::DbPools;
 
 use crate::application::services::admin_settings_service::AdminSettingsService;
@@ -12,39 +16,52 @@ use crate::application::services::storage_settings_service::StorageSettingsServi
 use crate::infrastructure::services::migration_blob_backend::MigrationState;
 
 use crate::application::ports::file_ports::FileUseCaseFactory;
+use crate::application::services::activity_service::ActivityService;
+use crate::application::services::dead_property_service::DeadPropertyService;
+use crate::application::services::e2ee_service::E2eeService;
 use crate::application::services::favorites_service::FavoritesService;
+use crate::application::services::file_version_service::FileVersionService;
 use crate::application::services::folder_service::FolderService;
 use crate::application::services::i18n_application_service::I18nApplicationService;
+use crate::application::services::live_event_service::LiveEventService;
 use crate::application::services::nextcloud_file_id_service::NextcloudFileIdService;
 use crate::application::services::nextcloud_login_flow_service::NextcloudLoginFlowService;
+use crate::application::services::notification_service::NotificationService;
 use crate::application::services::recent_service::RecentService;
 use crate::application::services::search_service::SearchService;
 use crate::application::services::share_browse_service::ShareBrowseService;
 use crate::application::services::share_service::ShareService;
+use crate::application::services::share_upload_service::ShareUploadService;
+use crate::application::services::snapshot_service::SnapshotService;
 use crate::application::services::trash_service::TrashService;
+use crate::application::services::user_share_service::UserShareService;
+use crate::application::services::webhook_service::WebhookService;
 use crate::application::services::{
     AppFileUseCaseFactory, FileManagementService, FileRetrievalService, FileUploadService,
 };
 use crate::common::config::AppConfig;
 use crate::common::errors::DomainError;
-use crate::infrastructure::repositories::pg::SharePgRepository;
 use crate::infrastructure::repositories::pg::{
     FileBlobReadRepository, FileBlobWriteRepository, FileMetadataRepository, FolderDbRepository,
     TrashDbRepository,
 };
+use crate::infrastructure::repositories::pg::{SharePgRepository, UserSharePgRepository};
 use crate::infrastructure::services::file_content_cache::{
     FileContentCache, FileContentCacheConfig,
 };
 use crate::infrastructure::services::file_system_i18n_service::FileSystemI18nService;
 use crate::infrastructure::services::nextcloud_chunked_upload_service::NextcloudChunkedUploadService;
 use crate::infrastructure::services::path_service::PathService;
+use crate::infrastructure::services::retention_cleanup_service::RetentionCleanupService;
 use crate::infrastructure::services::trash_cleanup_service::TrashCleanupService;
 
 use crate::application::services::app_password_service::AppPasswordService;
+use crate::application::services::calendar_scheduling_service::CalendarSchedulingService;
 use crate::application::services::calendar_service::CalendarService;
 use crate::application::services::device_auth_service::DeviceAuthService;
 use crate::application::services::music_service::MusicService;
 use crate::application::services::storage_usage_service::StorageUsageService;
+use crate::application::services::user_session_service::UserSessionService;
 use crate::application::services::wopi_lock_service::WopiLockService;
 use crate::application::services::wopi_token_service::WopiTokenService;
 use crate::infrastructure::adapters::contact_storage_adapter::ContactStorageAdapter;
@@ -53,7 +70,7 @@ use crate::infrastructure::repositories::DeviceCodePgRepository;
 use crate::infrastructure::repositories::pg::{
     AddressBookPgRepository, AudioMetadataPgRepository, CalendarEventPgRepository,
     CalendarPgRepository, ContactGroupPgRepository, ContactPgRepository, PlaylistItemPgRepository,
-    PlaylistPgRepository, SessionPgRepository, UserPgRepository,
+    PlaylistPgRepository, ScheduleInboxPgRepository, SessionPgRepository, UserPgRepository,
 };
 use crate::infrastructure::services::audio_metadata_service::AudioMetadataService;
 use crate::infrastructure::services::chunked_upload_service::ChunkedUploadService;
@@ -305,12 +322,16 @@ impl AppServiceFactory {
                 folder_repo_concrete.clone(),
             ));
 
-        let file_write_repository: Arc<FileBlobWriteRepository> =
-            Arc::new(FileBlobWriteRepository::new(
-                db_pool.clone(),
-                core.dedup_service.clone(),
-                folder_repo_concrete.clone(),
-            ));
+        // Version history is recorded by the write repository on every blob swap
+        let mut file_write = FileBlobWriteRepository::new(
+            db_pool.clone(),
+            core.dedup_service.clone(),
+            folder_repo_concrete.clone(),
+        );
+        if core.config.features.enable_file_versions {
+            file_write = file_write.with_versioning(core.config.storage.max_file_versions);
+        }
+        let file_write_repository: Arc<FileBlobWriteRepository> = Arc::new(file_write);
 
         // I18n repository
         let i18n_repository = Arc::new(FileSystemI18nService::new(self.locales_path.clone()));
@@ -328,6 +349,13 @@ impl AppServiceFactory {
         // File metadata repository — EXIF/media metadata for images
         let file_metadata_repository = Arc::new(FileMetadataRepository::new(db_pool.clone()));
 
+        // User share repository — access to items shared directly with users
+        let user_share_repository = if core.config.features.enable_file_sharing {
+            Some(Arc::new(UserSharePgRepository::new(db_pool.clone())))
+        } else {
+            None
+        };
+
         tracing::info!(
             "Repository services initialized with 100% blob storage model (PG metadata + DedupService blobs)"
         );
@@ -340,19 +368,30 @@ impl AppServiceFactory {
             file_metadata_repository,
             i18n_repository,
             trash_repository,
+            user_share_repository,
         }
     }
 
     /// Initializes the application services
+    #[allow(clippy::too_many_arguments)]
     pub fn create_application_services(
         &self,
         core: &CoreServices,
         repos: &RepositoryServices,
         trash_service: Option<Arc<TrashService>>,
+        activity_service: Option<Arc<ActivityService>>,
+        webhook_service: Option<Arc<WebhookService>>,
+        live_event_service: Option<Arc<LiveEventService>>,
+        storage_usage_service: Option<Arc<StorageUsageService>>,
         db_pool: &Arc<PgPool>,
     ) -> ApplicationServices {
         // Main services
-        let folder_service = Arc::new(FolderService::new(repos.folder_repository.clone()));
+        let folder_service = Arc::new(
+            FolderService::new(repos.folder_repository.clone())
+                .with_user_shares(repos.user_share_repository.clone())
+                .with_activity(activity_service.clone())
+                .with_live_events(live_event_service.clone()),
+        );
 
         // Refactored services with all infrastructure ports
         // In blob model, dedup is handled by the repository — no separate write-behind needed
@@ -360,33 +399,61 @@ impl AppServiceFactory {
             core.thumbnail_service.clone(),
             core.dedup_service.clone(),
         ));
-        let file_upload_service = Arc::new(
-            FileUploadService::new_with_read(
-                repos.file_write_repository.clone(),
+        let mut file_upload_service = FileUploadService::new_with_read(
+            repos.file_write_repository.clone(),
+            repos.file_read_repository.clone(),
+        )
+        .with_content_cache(core.file_content_cache.clone())
+        .with_file_created_hook(thumbnail_refresh_hook.clone())
+        .with_file_updated_hook(thumbnail_refresh_hook);
+        if let Some(activity) = &activity_service {
+            file_upload_service = file_upload_service
+                .with_file_created_hook(activity.clone())
+                .with_file_updated_hook(activity.clone());
+        }
+        if let Some(webhooks) = &webhook_service {
+            file_upload_service = file_upload_service
+                .with_file_created_hook(webhooks.clone())
+                .with_file_updated_hook(webhooks.clone());
+        }
+        if let Some(live_events) = &live_event_service {
+            file_upload_service = file_upload_service
+                .with_file_created_hook(live_events.clone())
+                .with_file_updated_hook(live_events.clone());
+        }
+        let file_upload_service = Arc::new(file_upload_service);
+
+        let file_retrieval_service = Arc::new(
+            FileRetrievalService::new_with_cache(
                 repos.file_read_repository.clone(),
+                core.file_content_cache.clone(),
+                core.image_transcode_service.clone(),
             )
-            .with_content_cache(core.file_content_cache.clone())
-            .with_file_created_hook(thumbnail_refresh_hook.clone())
-            .with_file_updated_hook(thumbnail_refresh_hook),
+            .with_user_shares(repos.user_share_repository.clone()),
         );
 
-        let file_retrieval_service = Arc::new(FileRetrievalService::new_with_cache(
-            repos.file_read_repository.clone(),
-            core.file_content_cache.clone(),
-            core.image_transcode_service.clone(),
-        ));
-
         // FileManagementService — ref_count handled by PG trigger, no dedup port needed
-        let file_management_service = Arc::new(
-            FileManagementService::with_trash(
-                repos.file_write_repository.clone(),
-                trash_service.clone(),
-                Some(repos.file_read_repository.clone()),
-                Some(repos.folder_repository.clone()),
-                Some(core.file_content_cache.clone()),
-            )
-            .with_file_deleted_hook(core.thumbnail_service.clone()),
-        );
+        let mut file_management_service = FileManagementService::with_trash(
+            repos.file_write_repository.clone(),
+            trash_service.clone(),
+            Some(repos.file_read_repository.clone()),
+            Some(repos.folder_repository.clone()),
+            Some(core.file_content_cache.clone()),
+        )
+        .with_file_deleted_hook(core.thumbnail_service.clone())
+        .with_user_shares(repos.user_share_repository.clone())
+        .with_activity(activity_service.clone())
+        .with_live_events(live_event_service)
+        .with_storage_usage(storage_usage_service);
+        if let Some(activity) = &activity_service {
+            file_management_service =
+                file_management_service.with_file_deleted_hook(activity.clone());
+        }
+        if let Some(webhooks) = &webhook_service {
+            file_management_service =
+                file_management_service.with_file_deleted_hook(webhooks.clone());
+        }
+        let file_management_service = Arc::new(file_management_service);
 
         let file_use_case_factory = Arc::new(AppFileUseCaseFactory::new(
             repos.file_read_repository.clone(),
@@ -440,11 +507,167 @@ impl AppServiceFactory {
         )))
     }
 
+    /// Creates the activity log service and starts its retention job
+    pub async fn create_activity_service(
+        &self,
+        repos: &RepositoryServices,
+        db_pool: &Arc<PgPool>,
+    ) -> Option<Arc<ActivityService>> {
+        if !self.config.features.enable_activity_log {
+            tracing::info!("Activity log is disabled in configuration");
+            return None;
+        }
+        let repo = Arc::new(
+            crate::infrastructure::repositories::pg::ActivityPgRepository::new(db_pool.clone()),
+        );
+        let retention_days = self.config.storage.activity_retention_days;
+        if retention_days > 0 {
+            let prune_repo = repo.clone();
+            RetentionCleanupService::new(
+                "activity log",
+                retention_days,
+                24, // Prune once a day
+                move |cutoff| {
+                    let repo = prune_repo.clone();
+                    async move { repo.prune(cutoff).await }
+                },
+            )
+            .start_cleanup_job()
+            .await;
+        }
+        tracing::info!("Activity log initialized");
+        Some(Arc::new(ActivityService::new(
+            repo,
+            repos.file_read_repository.clone(),
+        )))
+    }
+
+    /// Creates the webhook service, starts its log retention job and
+    /// resumes deliveries interrupted by a restart
+    pub async fn create_webhook_service(
+        &self,
+        repos: &RepositoryServices,
+        db_pool: &Arc<PgPool>,
+    ) -> Option<Arc<WebhookService>> {
+        if !self.config.webhooks.enabled {
+            tracing::info!("Webhooks are disabled in configuration");
+            return None;
+        }
+        let sender = match crate::infrastructure::services::webhook_sender::WebhookSender::new() {
+            Ok(sender) => Arc::new(sender),
+            Err(e) => {
+                tracing::error!("Webhooks disabled: {}", e);
+                return None;
+            }
+        };
+        let repo = Arc::new(
+            crate::infrastructure::repositories::pg::WebhookPgRepository::new(db_pool.clone()),
+        );
+        let prune_repo = repo.clone();
+        RetentionCleanupService::new(
+            "webhook delivery log",
+            self.config.webhooks.log_retention_days,
+            24, // Prune once a day
+            move |cutoff| {
+                let repo = prune_repo.clone();
+                async move { repo.prune_deliveries(cutoff).await }
+            },
+        )
+        .start_cleanup_job()
+        .await;
+        let service = Arc::new(WebhookService::new(
+            repo,
+            sender,
+            repos.file_read_repository.clone(),
+            &self.config.webhooks,
+        ));
+        service.resume_pending().await;
+        tracing::info!("Webhook service initialized");
+        Some(service)
+    }
+
+    /// Creates the live change notification service and, when enabled, the
+    /// relay that shares changes with other instances
+    pub async fn create_live_event_service(
+        &self,
+        repos: &RepositoryServices,
+        db_pool: &Arc<PgPool>,
+    ) -> Option<Arc<LiveEventService>> {
+        let config = &self.config.live_events;
+        if !config.enabled {
+            tracing::info!("Live events are disabled in configuration");
+            return None;
+        }
+        let relay = config.pg_notify.then(|| {
+            Arc::new(
+                crate::infrastructure::services::pg_live_event_relay::PgLiveEventRelay::new(
+                    db_pool.clone(),
+                ),
+            )
+        });
+        let service = Arc::new(
+            LiveEventService::new(repos.file_read_repository.clone(), config)
+                .with_user_shares(repos.user_share_repository.clone())
+                .with_relay(relay.clone()),
+        );
+        if let Some(relay) = relay {
+            relay.start_listener(service.clone()).await;
+        }
+        tracing::info!("Live event service initialized");
+        Some(service)
+    }
+
+    /// Creates the notification service and starts its periodic checks
+    pub async fn create_notification_service(
+        &self,
+        db_pool: &Arc<PgPool>,
+        live_event_service: Option<Arc<LiveEventService>>,
+    ) -> Option<Arc<NotificationService>> {
+        if !self.config.features.enable_notifications {
+            tracing::info!("Notifications are disabled in configuration");
+            return None;
+        }
+        let repo = Arc::new(
+            crate::infrastructure::repositories::pg::NotificationPgRepository::new(db_pool.clone()),
+        );
+        let service = Arc::new(NotificationService::new(repo).with_live_events(live_event_service));
+        crate::infrastructure::services::notification_check_service::NotificationCheckService::new(
+            service.clone(),
+            60, // Check quotas and expiring links hourly
+        )
+        .start_check_job()
+        .await;
+        tracing::info!("Notification service initialized");
+        Some(service)
+    }
+
+    /// Creates the end-to-end encryption service
+    pub fn create_e2ee_service(
+        &self,
+        repos: &RepositoryServices,
+        db_pool: &Arc<PgPool>,
+    ) -> Option<Arc<E2eeService>> {
+        if !self.config.features.enable_e2ee {
+            tracing::info!("End-to-end encryption is disabled in configuration");
+            return None;
+        }
+        let repo = Arc::new(
+            crate::infrastructure::repositories::pg::E2eePgRepository::new(db_pool.clone()),
+        );
+        tracing::info!("End-to-end encryption service initialized");
+        Some(Arc::new(E2eeService::new(
+            repo,
+            repos.folder_repository.clone(),
+        )))
+    }
+
     /// Creates the trash service
     pub async fn create_trash_service(
         &self,
         repos: &RepositoryServices,
         core: &CoreServices,
+        activity_service: Option<Arc<ActivityService>>,
+        live_event_service: Option<Arc<LiveEventService>>,
     ) -> Option<Arc<TrashService>> {
         if !self.config.features.enable_trash {
             tracing::info!("Trash service is disabled in configuration");
@@ -454,16 +677,20 @@ impl AppServiceFactory {
         let trash_repo = repos.trash_repository.as_ref()?;
 
         // Wire ports directly to TrashService — no adapter layer needed
-        let service = Arc::new(TrashService::new(
-            trash_repo.clone(),
-            repos.file_read_repository.clone(),
-            repos.file_write_repository.clone(),
-            repos.folder_repository.clone(),
-            self.config.storage.trash_retention_days,
-            core.dedup_service.clone(),
-            Some(core.thumbnail_service.clone()),
-            Some(core.file_content_cache.clone()),
-        ));
+        let service = Arc::new(
+            TrashService::new(
+                trash_repo.clone(),
+                repos.file_read_repository.clone(),
+                repos.file_write_repository.clone(),
+                repos.folder_repository.clone(),
+                self.config.storage.trash_retention_days,
+                core.dedup_service.clone(),
+                Some(core.thumbnail_service.clone()),
+                Some(core.file_content_cache.clone()),
+            )
+            .with_activity(activity_service)
+            .with_live_events(live_event_service),
+        );
 
         // Initialize cleanup service (bulk-deletes expired items in 2 SQL queries)
         let cleanup_service = TrashCleanupService::new(
@@ -482,6 +709,9 @@ impl AppServiceFactory {
         &self,
         repos: &RepositoryServices,
         db_pool: &Arc<PgPool>,
+        activity_service: Option<Arc<ActivityService>>,
+        webhook_service: Option<Arc<WebhookService>>,
+        live_event_service: Option<Arc<LiveEventService>>,
     ) -> Option<Arc<ShareService>> {
         if !self.config.features.enable_file_sharing {
             tracing::info!("File sharing service is disabled in configuration");
@@ -499,13 +729,18 @@ impl AppServiceFactory {
             ),
         );
 
-        let service = Arc::new(ShareService::new(
-            Arc::new(self.config.clone()),
-            share_repository,
-            repos.file_read_repository.clone(),
-            repos.folder_repository.clone(),
-            password_hasher,
-        ));
+        let service = Arc::new(
+            ShareService::new(
+                Arc::new(self.config.clone()),
+                share_repository,
+                repos.file_read_repository.clone(),
+                repos.folder_repository.clone(),
+                password_hasher,
+            )
+            .with_activity(activity_service)
+            .with_webhooks(webhook_service)
+            .with_live_events(live_event_service),
+        );
 
         tracing::info!("File sharing service initialized");
         Some(service)
@@ -521,6 +756,105 @@ impl AppServiceFactory {
         service
     }
 
+    /// Creates the WebDAV dead property service (requires database)
+    pub fn create_dead_property_service(&self, db_pool: &Arc<PgPool>) -> Arc<DeadPropertyService> {
+        let repo = Arc::new(
+            crate::infrastructure::repositories::pg::DeadPropertyPgRepository::new(db_pool.clone()),
+        );
+        Arc::new(DeadPropertyService::new(repo))
+    }
+
+    /// Creates the file version service (requires database)
+    pub fn create_file_version_service(
+        &self,
+        core: &CoreServices,
+        repos: &RepositoryServices,
+        db_pool: &Arc<PgPool>,
+        activity_service: Option<Arc<ActivityService>>,
+        webhook_service: Option<Arc<WebhookService>>,
+        live_event_service: Option<Arc<LiveEventService>>,
+    ) -> Option<Arc<FileVersionService>> {
+        if !self.config.features.enable_file_versions {
+            tracing::info!("File version history is disabled in configuration");
+            return None;
+        }
+        let repo = Arc::new(
+            crate::infrastructure::repositories::pg::FileVersionPgRepository::new(db_pool.clone()),
+        );
+        let thumbnail_refresh_hook = Arc::new(ThumbnailRefreshHook::new(
+            core.thumbnail_service.clone(),
+            core.dedup_service.clone(),
+        ));
+        let mut service = FileVersionService::new(
+            repo,
+            repos.file_read_repository.clone(),
+            repos.file_write_repository.clone(),
+            core.dedup_service.clone(),
+        )
+        .with_content_cache(core.file_content_cache.clone())
+        .with_file_updated_hook(thumbnail_refresh_hook);
+        if let Some(activity) = activity_service {
+            service = service.with_file_updated_hook(activity);
+        }
+        if let Some(webhooks) = webhook_service {
+            service = service.with_file_updated_hook(webhooks);
+        }
+        if let Some(live_events) = live_event_service {
+            service = service.with_file_updated_hook(live_events);
+        }
+        let service = Arc::new(service);
+        tracing::info!(
+            "File version service initialized (max {} versions per file)",
+            self.config.storage.max_file_versions
+        );
+        Some(service)
+    }
+
+    /// Creates the folder snapshot service and starts its scheduler (requires database)
+    pub async fn create_snapshot_service(
+        &self,
+        core: &CoreServices,
+        db_pool: &Arc<PgPool>,
+        storage_usage_service: Option<Arc<StorageUsageService>>,
+        activity_service: Option<Arc<ActivityService>>,
+        webhook_service: Option<Arc<WebhookService>>,
+        live_event_service: Option<Arc<LiveEventService>>,
+    ) -> Option<Arc<SnapshotService>> {
+        if !self.config.features.enable_folder_snapshots {
+            tracing::info!("Folder snapshots are disabled in configuration");
+            return None;
+        }
+        let repo = Arc::new(
+            crate::infrastructure::repositories::pg::SnapshotPgRepository::new(db_pool.clone()),
+        );
+        let thumbnail_refresh_hook = Arc::new(ThumbnailRefreshHook::new(
+            core.thumbnail_service.clone(),
+            core.dedup_service.clone(),
+        ));
+        let mut service = SnapshotService::new(repo, core.dedup_service.clone())
+            .with_storage_usage(storage_usage_service)
+            .with_file_created_hook(thumbnail_refresh_hook);
+        if let Some(activity) = activity_service {
+            service = service.with_file_created_hook(activity);
+        }
+        if let Some(webhooks) = webhook_service {
+            service = service.with_file_created_hook(webhooks);
+        }
+        if let Some(live_events) = live_event_service {
+            service = service.with_file_created_hook(live_events);
+        }
+        let service = Arc::new(service);
+
+        // Takes due scheduled snapshots and deletes expired ones
+        let scheduler = crate::infrastructure::services::snapshot_scheduler_service::SnapshotSchedulerService::new(
+            service.clone(),
+            15, // Check schedules every 15 minutes
+        );
+        scheduler.start_scheduler_job().await;
+        tracing::info!("Folder snapshot service initialized with scheduler");
+        Some(service)
+    }
+
     /// Creates the recent items service (requires database)
     pub fn create_recent_service(&self, db_pool: &Arc<PgPool>) -> Arc<RecentService> {
         let repo = Arc::new(
@@ -602,15 +936,49 @@ impl AppServiceFactory {
         // 2. Repository services (requires PgPool for all metadata)
         let repos = self.create_repository_services(&core, &pool);
 
-        // 3. Trash service (needed before application services)
-        let trash_service = self.create_trash_service(&repos, &core).await;
+        // 3. Activity log (recorded into by most services below)
+        let activity_service = self.create_activity_service(&repos, &pool).await;
+
+        // 3b. Outgoing webhooks (fired by the same services)
+        let webhook_service = self.create_webhook_service(&repos, &pool).await;
 
-        // 4. Application services (with trash already wired)
-        let mut apps =
-            self.create_application_services(&core, &repos, trash_service.clone(), &pool);
+        // 3c. Live change notifications (published by the same services)
+        let live_event_service = self.create_live_event_service(&repos, &pool).await;
 
-        // 5. Share service
-        let share_service = self.create_share_service(&repos, &pool);
+        // 4. Trash service (needed before application services)
+        let trash_service = self
+            .create_trash_service(
+                &repos,
+                &core,
+                activity_service.clone(),
+                live_event_service.clone(),
+            )
+            .await;
+
+        // 4b. Storage usage (copies and snapshot restores count against quota)
+        let storage_usage_service: Option<Arc<StorageUsageService>> =
+            Some(self.create_storage_usage_service(&repos, &pool, &maintenance_pool));
+
+        // 5. Application services (with trash already wired)
+        let mut apps = self.create_application_services(
+            &core,
+            &repos,
+            trash_service.clone(),
+            activity_service.clone(),
+            webhook_service.clone(),
+            live_event_service.clone(),
+            storage_usage_service.clone(),
+            &pool,
+        );
+
+        // 6. Share service
+        let share_service = self.create_share_service(
+            &repos,
+            &pool,
+            activity_service.clone(),
+            webhook_service.clone(),
+            live_event_service.clone(),
+        );
         apps.share_service = share_service.clone();
 
         let share_browse_service = share_service.as_ref().map(|s| {
@@ -622,10 +990,40 @@ impl AppServiceFactory {
             ))
         });
 
-        // 6. Database-dependent services (PgPool always available in blob model)
+        // 5b. File version history
+        let file_version_service = self.create_file_version_service(
+            &core,
+            &repos,
+            &pool,
+            activity_service.clone(),
+            webhook_service.clone(),
+            live_event_service.clone(),
+        );
+
+        // 5c. Folder snapshots (restores count against quota)
+        let snapshot_service = self
+            .create_snapshot_service(
+                &core,
+                &pool,
+                storage_usage_service.clone(),
+                activity_service.clone(),
+                webhook_service.clone(),
+                live_event_service.clone(),
+            )
+            .await;
+
+        // 5d. End-to-end encrypted folders
+        let e2ee_service = self.create_e2ee_service(&repos, &pool);
+
+        // 5e. Notifications
+        let notification_service = self
+            .create_notification_service(&pool, live_event_service.clone())
+            .await;
+
+        // 7. Database-dependent services (PgPool always available in blob model)
         let favorites_service: Option<Arc<FavoritesService>>;
+        let dead_property_service: Option<Arc<DeadPropertyService>>;
         let recent_service: Option<Arc<RecentService>>;
-        let storage_usage_service: Option<Arc<StorageUsageService>>;
         let mut auth_services: Option<crate::common::di::AuthServices> = None;
         let mut nextcloud_services: Option<NextcloudServices> = None;
 
@@ -634,13 +1032,12 @@ impl AppServiceFactory {
             favorites_service = Some(favs.clone());
             apps.favorites_service = Some(favs);
 
+            dead_property_service = Some(self.create_dead_property_service(&pool));
+
             let recent = self.create_recent_service(&pool);
             recent_service = Some(recent.clone());
             apps.recent_service = Some(recent);
 
-            storage_usage_service =
-                Some(self.create_storage_usage_service(&repos, &pool, &maintenance_pool));
-
             // Auth services
             if self.config.features.enable_auth {
                 let services = crate::infrastructure::auth_factory::create_auth_services(
@@ -732,10 +1129,32 @@ impl AppServiceFactory {
             });
         }
 
-        // 7. Preload translations
+        // 6a. Direct shares with other users
+        let user_share_service = repos.user_share_repository.as_ref().map(|r| {
+            Arc::new(
+                UserShareService::new(r.clone())
+                    .with_notifications(notification_service.clone())
+                    .with_webhooks(webhook_service.clone())
+                    .with_live_events(live_event_service.clone()),
+            )
+        });
+
+        // 6b. Public uploads through share links
+        let share_upload_service = share_service.as_ref().map(|s| {
+            Arc::new(ShareUploadService::new(
+                s.clone(),
+                repos.folder_repository.clone(),
+                apps.file_retrieval_service.clone(),
+                apps.file_upload_service.clone(),
+                core.chunked_upload_service.clone(),
+                storage_usage_service.clone(),
+            ))
+        });
+
+        // 8. Preload translations
         self.preload_translations(&apps.i18n_service).await;
 
-        // 8. Build the ZipService with real application services
+        // 9. Build the ZipService with real application services
         let zip_service: Arc<ZipService> = Arc::new(
             crate::infrastructure::services::zip_service::ZipService::new(
                 apps.file_retrieval_service.clone(),
@@ -745,7 +1164,7 @@ impl AppServiceFactory {
         let mut core = core;
         core.zip_service = Some(zip_service);
 
-        // 9. Assemble final AppState
+        // 10. Assemble final AppState
         let mut app_state = AppState {
             core,
             repositories: repos,
@@ -760,12 +1179,23 @@ impl AppServiceFactory {
             trash_service,
             share_service,
             share_browse_service,
+            share_upload_service,
+            user_share_service,
             favorites_service,
             recent_service,
+            dead_property_service,
+            file_version_service,
+            activity_service,
+            snapshot_service,
+            e2ee_service,
+            notification_service,
+            webhook_service,
+            live_event_service,
             storage_usage_service,
             calendar_service: None,
             contact_service: None,
             calendar_use_case: None,
+            calendar_scheduling_service: None,
             addressbook_use_case: None,
             contact_use_case: None,
             music_service: None,
@@ -774,6 +1204,7 @@ impl AppServiceFactory {
             wopi_discovery_service: None,
             device_auth_service: None,
             app_password_service: None,
+            user_session_service: None,
             path_resolver: None,
             webdav_lock_store:
                 crate::infrastructure::services::webdav_lock_service::create_webdav_lock_store(),
@@ -872,6 +1303,13 @@ impl AppServiceFactory {
 
             // 9d. Wire App Password service (reuse shared instance)
             app_state.app_password_service = shared_app_pw_svc.clone();
+
+            // 9d-2. Session list: sessions plus app passwords and devices
+            app_state.user_session_service = Some(Arc::new(UserSessionService::new(
+                Arc::new(SessionPgRepository::new(pool.clone())),
+                app_state.app_password_service.clone(),
+                app_state.device_auth_service.clone(),
+            )));
         }
 
         // 9e. Wire PathResolver for single-query WebDAV path resolution
@@ -880,7 +1318,7 @@ impl AppServiceFactory {
             tracing::info!("PathResolver service initialized");
         }
 
-        // 10. Wire CalDAV/CardDAV services
+        // 11. Wire CalDAV/CardDAV services
         {
             // CalDAV
             let calendar_repo: Arc<CalendarPgRepository> = Arc::new(
@@ -894,16 +1332,31 @@ impl AppServiceFactory {
             let calendar_storage = Arc::new(
                 crate::infrastructure::adapters::calendar_storage_adapter::CalendarStorageAdapter::new(
                     calendar_repo,
-                    event_repo,
+                    event_repo.clone(),
                 )
             );
             let calendar_service = Arc::new(
                 crate::application::services::calendar_service::CalendarService::new(
-                    calendar_storage,
+                    calendar_storage.clone(),
                 ),
             );
             app_state.calendar_use_case = Some(calendar_service as Arc<CalendarService>);
 
+            // CalDAV scheduling (RFC 6638)
+            let inbox_repo: Arc<ScheduleInboxPgRepository> = Arc::new(
+                crate::infrastructure::repositories::pg::ScheduleInboxPgRepository::new(
+                    pool.clone(),
+                ),
+            );
+            let user_repo: Arc<UserPgRepository> = Arc::new(
+                crate::infrastructure::repositories::pg::UserPgRepository::new(pool.clone()),
+            );
+            app_state.calendar_scheduling_service = Some(Arc::new(CalendarSchedulingService::new(
+                calendar_storage,
+                inbox_repo,
+                user_repo,
+            )));
+
             // CardDAV
             let address_book_repo: Arc<AddressBookPgRepository> = Arc::new(
                 crate::infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone()),
@@ -916,6 +1369,32 @@ impl AppServiceFactory {
                     pool.clone(),
                 ),
             );
+            // Expires CalDAV/CardDAV sync tokens older than 90 days, so
+            // clients offline for longer fall back to a full sync
+            RetentionCleanupService::new(
+                "calendar change log",
+                90,
+                24, // Prune once a day
+                move |cutoff| {
+                    let repo = event_repo.clone();
+                    async move { repo.prune_changes(cutoff).await }
+                },
+            )
+            .start_cleanup_job()
+            .await;
+            let prune_repo = contact_repo.clone();
+            RetentionCleanupService::new(
+                "address book change log",
+                90,
+                24, // Prune once a day
+                move |cutoff| {
+                    let repo = prune_repo.clone();
+                    async move { repo.prune_changes(cutoff).await }
+                },
+            )
+            .start_cleanup_job()
+            .await;
+
             let contact_storage = Arc::new(
                 crate::infrastructure::adapters::contact_storage_adapter::ContactStorageAdapter::new(
                     address_book_repo,
@@ -949,7 +1428,7 @@ impl AppServiceFactory {
             tracing::info!("Music service initialized");
         }
 
-        // 11. Wire WOPI services if enabled
+        // 12. Wire WOPI services if enabled
         if self.config.wopi.enabled {
             let discovery_url = &self.config.wopi.discovery_url;
             if discovery_url.is_empty() {
@@ -1012,6 +1491,7 @@ pub struct RepositoryServices {
     pub file_metadata_repository: Arc<FileMetadataRepository>,
     pub i18n_repository: Arc<FileSystemI18nService>,
     pub trash_repository: Option<Arc<TrashDbRepository>>,
+    pub user_share_repository: Option<Arc<UserSharePgRepository>>,
 }
 
 /// Container for application services
@@ -1041,6 +1521,12 @@ pub struct AuthServices {
     pub auth_application_service: Arc<AuthApplicationService>,
     pub login_lockout:
         Arc<crate::infrastructure::services::login_lockout_service::LoginLockoutService>,
+    pub group_service: Arc<crate::application::services::group_service::GroupService>,
+    pub two_factor_service: Arc<crate::application::services::two_factor_service::TwoFactorService>,
+    pub webauthn_service: Arc<crate::application::services::webauthn_service::WebAuthnService>,
+    /// Password reset and address verification; `None` without mail
+    pub account_recovery:
+        Option<Arc<crate::application::services::account_recovery_service::AccountRecoveryService>>,
 }
 
 /// Container for Nextcloud compatibility services
@@ -1069,12 +1555,28 @@ pub struct AppState {
     pub trash_service: Option<Arc<TrashService>>,
     pub share_service: Option<Arc<ShareService>>,
     pub share_browse_service: Option<Arc<ShareBrowseService>>,
+    pub share_upload_service: Option<Arc<ShareUploadService>>,
+    pub user_share_service: Option<Arc<UserShareService>>,
     pub favorites_service: Option<Arc<FavoritesService>>,
     pub recent_service: Option<Arc<RecentService>>,
+    pub dead_property_service: Option<Arc<DeadPropertyService>>,
+    pub file_version_service: Option<Arc<FileVersionService>>,
+    /// Activity log (optional, enabled by default)
+    pub activity_service: Option<Arc<ActivityService>>,
+    pub snapshot_service: Option<Arc<SnapshotService>>,
+    /// End-to-end encrypted folders (optional, enabled by default)
+    pub e2ee_service: Option<Arc<E2eeService>>,
+    /// User notifications (optional, enabled by default)
+    pub notification_service: Option<Arc<NotificationService>>,
+    /// Outgoing webhooks (optional, enabled by default)
+    pub webhook_service: Option<Arc<WebhookService>>,
+    /// Live change notifications (optional, enabled by default)
+    pub live_event_service: Option<Arc<LiveEventService>>,
     pub storage_usage_service: Option<Arc<StorageUsageService>>,
     pub calendar_service: Option<Arc<CalendarService>>,
     pub contact_service: Option<Arc<ContactStorageAdapter>>,
     pub calendar_use_case: Option<Arc<CalendarService>>,
+    pub calendar_scheduling_service: Option<Arc<CalendarSchedulingService>>,
     pub addressbook_use_case: Option<Arc<ContactStorageAdapter>>,
     pub contact_use_case: Option<Arc<ContactStorageAdapter>>,
     pub music_service: Option<Arc<MusicService>>,
@@ -1088,6 +1590,8 @@ pub struct AppState {
         Option<Arc<crate::application::services::device_auth_service::DeviceAuthService>>,
     pub app_password_service:
         Option<Arc<crate::application::services::app_password_service::AppPasswordService>>,
+    pub user_session_service:
+        Option<Arc<crate::application::services::user_session_service::UserSessionService>>,
     pub path_resolver:
         Option<Arc<crate::infrastructure::services::path_resolver_service::PathResolverService>>,
     pub webdav_lock_store:
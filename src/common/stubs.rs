@@ -672,6 +672,14 @@ impl FileManagementUseCase for StubFileManagementUseCase {
         Ok(FileDto::default())
     }
 
+    async fn set_modified_time(
+        &self,
+        _file_id: &str,
+        _modified_at: i64,
+    ) -> Result<(), DomainError> {
+        Ok(())
+    }
+
     async fn copy_folder_tree_owned(
         &self,
         _source_folder_id: &str,
@@ -47,6 +47,9 @@ pub struct Calendar {
 
     /// Optional list of custom properties (for extended CalDAV support)
     custom_properties: std::collections::HashMap<String, String>,
+
+    /// Current collection sync token (RFC 6578), bumped by every event change
+    sync_token: i64,
 }
 
 impl Calendar {
@@ -89,6 +92,7 @@ impl Calendar {
             created_at: now,
             updated_at: now,
             custom_properties: std::collections::HashMap::new(),
+            sync_token: 0,
         })
     }
 
@@ -136,9 +140,21 @@ impl Calendar {
             created_at,
             updated_at,
             custom_properties: std::collections::HashMap::new(),
+            sync_token: 0,
         })
     }
 
+    /**
+     * Sets the calendar's sync token as loaded from storage.
+     *
+     * @param sync_token Current collection sync token
+     * @return The calendar with the sync token set
+     */
+    pub fn with_sync_token(mut self, sync_token: i64) -> Self {
+        self.sync_token = sync_token;
+        self
+    }
+
     // Getters
 
     /// Returns the calendar's unique identifier
@@ -176,6 +192,11 @@ impl Calendar {
         &self.updated_at
     }
 
+    /// Returns the calendar's current collection sync token
+    pub fn sync_token(&self) -> i64 {
+        self.sync_token
+    }
+
     /// Returns a custom property value by name, if it exists
     pub fn custom_property(&self, name: &str) -> Option<&str> {
         self.custom_properties.get(name).map(|s| s.as_str())
@@ -8,14 +8,105 @@ use chrono::{DateTime, Duration, TimeZone, Utc};
  * Calendar events have properties like summary, description, location, start/end times,
  * and can include recurrence rules for repeating events. Each event belongs to a
  * specific calendar and stores its complete iCalendar representation.
+ *
+ * Tasks (VTODO components) are stored as calendar events too; their
+ * task-specific properties are kept in `TaskDetails`.
  */
 use uuid::Uuid;
 
 use crate::common::errors::{DomainError, ErrorKind, Result};
+use crate::domain::services::recurrence_service::{IcalComponent, IcalProperty, RecurrenceSet};
 
 // Re-export entity errors from the centralized module
 pub use super::entity_errors::CalendarEventError;
 
+/// Start, end, all-day flag and RRULE read from iCalendar data
+type IcalTimes = (DateTime<Utc>, DateTime<Utc>, bool, Option<String>);
+
+/// Task statuses defined for VTODO (RFC 5545 §3.8.1.11)
+pub const TASK_STATUSES: &[&str] = &["NEEDS-ACTION", "IN-PROCESS", "COMPLETED", "CANCELLED"];
+
+/**
+ * Task-specific properties of a VTODO component (RFC 5545 §3.6.2).
+ *
+ * Indexed alongside the stored iCalendar data so tasks can be listed and
+ * filtered without parsing every object.
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskDetails {
+    /// Time the task is due (DUE)
+    pub due: Option<DateTime<Utc>>,
+
+    /// NEEDS-ACTION, IN-PROCESS, COMPLETED or CANCELLED (STATUS)
+    pub status: Option<String>,
+
+    /// Completion percentage, 0 to 100 (PERCENT-COMPLETE)
+    pub percent_complete: Option<i16>,
+
+    /// 1 (highest) to 9 (lowest), 0 when undefined (PRIORITY)
+    pub priority: Option<i16>,
+
+    /// UID of the parent task (RELATED-TO with RELTYPE=PARENT)
+    pub related_to: Option<String>,
+
+    /// Time the task was completed (COMPLETED)
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl TaskDetails {
+    /**
+     * Reads the task properties of a VTODO component.
+     * Out-of-range values are ignored.
+     *
+     * @param todo The VTODO component
+     * @return The task properties
+     */
+    fn from_component(todo: &IcalComponent) -> Self {
+        let number = |name: &str, range: std::ops::RangeInclusive<i16>| {
+            todo.value(name)
+                .and_then(|v| v.trim().parse::<i16>().ok())
+                .filter(|v| range.contains(v))
+        };
+
+        Self {
+            due: todo.time("DUE"),
+            status: todo
+                .value("STATUS")
+                .map(|s| s.trim().to_ascii_uppercase())
+                .filter(|s| TASK_STATUSES.contains(&s.as_str())),
+            percent_complete: number("PERCENT-COMPLETE", 0..=100),
+            priority: number("PRIORITY", 0..=9),
+            related_to: todo
+                .properties
+                .iter()
+                .filter(|p| p.name == "RELATED-TO")
+                .find(|p| {
+                    p.param("RELTYPE")
+                        .is_none_or(|t| t.eq_ignore_ascii_case("PARENT"))
+                })
+                .map(|p| p.value.trim().to_string())
+                .filter(|uid| !uid.is_empty()),
+            completed_at: todo.time("COMPLETED"),
+        }
+    }
+}
+
+/**
+ * Changes to a task; `None` leaves a property unchanged and an empty
+ * string clears a text property.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TaskUpdate {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub due: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+    pub percent_complete: Option<i16>,
+    pub priority: Option<i16>,
+    pub related_to: Option<String>,
+}
+
 /**
  * CalendarEvent entity.
  *
@@ -62,6 +153,9 @@ pub struct CalendarEvent {
 
     /// Time when the event was last modified
     updated_at: DateTime<Utc>,
+
+    /// Task properties, present when the object is a VTODO
+    task: Option<TaskDetails>,
 }
 
 impl CalendarEvent {
@@ -144,6 +238,7 @@ impl CalendarEvent {
             ical_data,
             created_at: now,
             updated_at: now,
+            task: None,
         })
     }
 
@@ -213,9 +308,66 @@ impl CalendarEvent {
             ical_data,
             created_at,
             updated_at,
+            task: None,
         })
     }
 
+    /**
+     * Attaches task properties, marking the object as a VTODO.
+     * Used when reconstructing tasks from storage.
+     *
+     * @param task Task properties, or None for an event
+     * @return The event with the task properties set
+     */
+    pub fn with_task(mut self, task: Option<TaskDetails>) -> Self {
+        self.task = task;
+        self
+    }
+
+    /**
+     * Creates a new task (VTODO) and applies the given properties.
+     *
+     * @param calendar_id ID of the calendar this task belongs to
+     * @param update Properties of the task; the summary is required
+     * @return Result containing the new task or a domain error
+     */
+    pub fn new_task(calendar_id: Uuid, update: TaskUpdate) -> Result<Self> {
+        let summary = update
+            .summary
+            .clone()
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| {
+                DomainError::new(
+                    ErrorKind::InvalidInput,
+                    "CalendarEvent",
+                    "Task summary cannot be empty",
+                )
+            })?;
+
+        let now = Utc::now().format("%Y%m%dT%H%M%SZ");
+        let ical_data = format!(
+            "BEGIN:VCALENDAR\r\n\
+            VERSION:2.0\r\n\
+            PRODID:-//OxiCloud//NONSGML Calendar//EN\r\n\
+            BEGIN:VTODO\r\n\
+            UID:{}\r\n\
+            DTSTAMP:{}\r\n\
+            CREATED:{}\r\n\
+            SUMMARY:{}\r\n\
+            STATUS:NEEDS-ACTION\r\n\
+            END:VTODO\r\n\
+            END:VCALENDAR\r\n",
+            Uuid::new_v4(),
+            now,
+            now,
+            summary.replace('\n', "\\n"),
+        );
+
+        let mut task = Self::from_ical(calendar_id, ical_data)?;
+        task.update_task(update)?;
+        Ok(task)
+    }
+
     /**
      * Creates a calendar event from an iCalendar VEVENT component.
      * Parses the iCalendar data to extract event properties.
@@ -237,53 +389,32 @@ impl CalendarEvent {
             )
         })?;
 
-        let dtstart = Self::extract_ical_property(&ical_data, "DTSTART").ok_or_else(|| {
-            DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                "Missing DTSTART in iCalendar data",
-            )
-        })?;
-
-        let dtend = Self::extract_ical_property(&ical_data, "DTEND").ok_or_else(|| {
-            DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                "Missing DTEND in iCalendar data",
-            )
-        })?;
-
-        // Parse dates (simplified)
-        let start_time = Self::parse_ical_datetime(&dtstart).map_err(|e| {
-            DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                format!("Invalid DTSTART: {}", e),
-            )
-        })?;
-
-        let end_time = Self::parse_ical_datetime(&dtend).map_err(|e| {
-            DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                format!("Invalid DTEND: {}", e),
-            )
-        })?;
+        let task = Self::master_todo(&ical_data).map(|todo| TaskDetails::from_component(&todo));
+        let now = Utc::now();
 
-        // Determine if all-day event (simplified check)
-        let all_day = dtstart.contains("VALUE=DATE") && !dtstart.contains("T");
+        // Times and recurrence come from the master component, which
+        // handles TZID, DURATION and VTIMEZONE blocks
+        let recurrence = RecurrenceSet::from_ical(&ical_data);
+        let (start_time, end_time, all_day, rrule) = match (recurrence.start(), recurrence.end()) {
+            (Some(start), Some(end)) => (
+                start,
+                end,
+                recurrence.is_all_day(),
+                recurrence.rrule().map(str::to_string),
+            ),
+            // Tasks may have neither DTSTART nor DUE
+            _ if task.is_some() => (now, now, false, None),
+            _ => Self::simple_ical_times(&ical_data)?,
+        };
 
         // Extract optional fields
         let description = Self::extract_ical_property(&ical_data, "DESCRIPTION");
         let location = Self::extract_ical_property(&ical_data, "LOCATION");
-        let rrule = Self::extract_ical_property(&ical_data, "RRULE");
 
         // Extract UID or generate a new one
         let ical_uid = Self::extract_ical_property(&ical_data, "UID")
             .unwrap_or_else(|| Uuid::new_v4().to_string());
 
-        let now = Utc::now();
-
         Ok(Self {
             id: Uuid::new_v4(),
             calendar_id,
@@ -298,6 +429,7 @@ impl CalendarEvent {
             ical_data,
             created_at: now,
             updated_at: now,
+            task,
         })
     }
 
@@ -373,6 +505,27 @@ impl CalendarEvent {
         self.end_time - self.start_time
     }
 
+    /// Returns the task properties when this is a task (VTODO)
+    pub fn task(&self) -> Option<&TaskDetails> {
+        self.task.as_ref()
+    }
+
+    /// Returns whether this is a task (VTODO) rather than an event
+    pub fn is_task(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// Returns the task's DTSTART; `start_time` falls back to DUE or the
+    /// creation time for tasks without one
+    pub fn task_start(&self) -> Option<DateTime<Utc>> {
+        Self::master_todo(&self.ical_data)?.time("DTSTART")
+    }
+
+    /// Returns the iCalendar component name: `VEVENT` or `VTODO`
+    pub fn component(&self) -> &'static str {
+        if self.is_task() { "VTODO" } else { "VEVENT" }
+    }
+
     // Setters and Mutators
 
     /**
@@ -541,11 +694,14 @@ impl CalendarEvent {
      */
     pub fn update_ical_data(&mut self, ical_data: String) -> Result<()> {
         // Validate iCalendar data (basic validation)
-        if !ical_data.contains("BEGIN:VEVENT") || !ical_data.contains("END:VEVENT") {
+        let task = Self::master_todo(&ical_data).map(|todo| TaskDetails::from_component(&todo));
+        if task.is_none()
+            && (!ical_data.contains("BEGIN:VEVENT") || !ical_data.contains("END:VEVENT"))
+        {
             return Err(DomainError::new(
                 ErrorKind::InvalidInput,
                 "CalendarEvent",
-                "iCalendar data must contain a VEVENT component",
+                "iCalendar data must contain a VEVENT or VTODO component",
             ));
         }
 
@@ -557,35 +713,149 @@ impl CalendarEvent {
         self.description = Self::extract_ical_property(&ical_data, "DESCRIPTION");
         self.location = Self::extract_ical_property(&ical_data, "LOCATION");
 
-        if let Some(dtstart) = Self::extract_ical_property(&ical_data, "DTSTART")
-            && let Ok(start_time) = Self::parse_ical_datetime(&dtstart)
-        {
+        let recurrence = RecurrenceSet::from_ical(&ical_data);
+        if let (Some(start_time), Some(end_time)) = (recurrence.start(), recurrence.end()) {
             self.start_time = start_time;
-        }
-
-        if let Some(dtend) = Self::extract_ical_property(&ical_data, "DTEND")
-            && let Ok(end_time) = Self::parse_ical_datetime(&dtend)
+            self.end_time = end_time;
+            self.all_day = recurrence.is_all_day();
+            self.rrule = recurrence.rrule().map(str::to_string);
+        } else if task.is_some() {
+            // Undated task
+            self.start_time = self.created_at;
+            self.end_time = self.created_at;
+            self.all_day = false;
+            self.rrule = None;
+        } else if let Ok((start_time, end_time, all_day, rrule)) =
+            Self::simple_ical_times(&ical_data)
         {
+            self.start_time = start_time;
             self.end_time = end_time;
+            self.all_day = all_day;
+            self.rrule = rrule;
         }
 
-        // Update all-day status based on DTSTART
-        if let Some(dtstart) = Self::extract_ical_property(&ical_data, "DTSTART") {
-            self.all_day = dtstart.contains("VALUE=DATE") && !dtstart.contains("T");
-        }
-
-        self.rrule = Self::extract_ical_property(&ical_data, "RRULE");
-
         if let Some(uid) = Self::extract_ical_property(&ical_data, "UID") {
             self.ical_uid = uid;
         }
 
         self.ical_data = ical_data;
+        self.task = task;
         self.updated_at = Utc::now();
 
         Ok(())
     }
 
+    /**
+     * Applies changes to a task's VTODO component, keeping any other
+     * properties, alarms and overridden instances intact.
+     *
+     * Setting the status to COMPLETED records the completion time and sets
+     * the percentage to 100 unless one is given; any other status clears
+     * the completion time.
+     *
+     * @param update Changes to apply
+     * @return Result indicating success or containing a domain error
+     */
+    pub fn update_task(&mut self, update: TaskUpdate) -> Result<()> {
+        let invalid = |message: &str| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "CalendarEvent",
+                message.to_string(),
+            )
+        };
+
+        if !self.is_task() {
+            return Err(invalid("Event is not a task"));
+        }
+        if update.summary.as_ref().is_some_and(|s| s.trim().is_empty()) {
+            return Err(invalid("Task summary cannot be empty"));
+        }
+        let status = update
+            .status
+            .as_ref()
+            .map(|s| s.trim().to_ascii_uppercase());
+        if status
+            .as_ref()
+            .is_some_and(|s| !TASK_STATUSES.contains(&s.as_str()))
+        {
+            return Err(invalid(
+                "Task status must be NEEDS-ACTION, IN-PROCESS, COMPLETED or CANCELLED",
+            ));
+        }
+        if update
+            .percent_complete
+            .is_some_and(|p| !(0..=100).contains(&p))
+        {
+            return Err(invalid("Task percent-complete must be between 0 and 100"));
+        }
+        if update.priority.is_some_and(|p| !(0..=9).contains(&p)) {
+            return Err(invalid("Task priority must be between 0 and 9"));
+        }
+
+        let mut components = IcalComponent::parse(&self.ical_data);
+        let todo = Self::master_todo_mut(&mut components)
+            .ok_or_else(|| invalid("iCalendar data must contain a VTODO component"))?;
+
+        let utc = |dt: DateTime<Utc>| dt.format("%Y%m%dT%H%M%SZ").to_string();
+        let set_text = |todo: &mut IcalComponent, name: &str, value: &str| {
+            if value.is_empty() {
+                todo.remove_property(name);
+            } else {
+                todo.set_property(IcalProperty::new(name, value.replace('\n', "\\n")));
+            }
+        };
+
+        if let Some(summary) = &update.summary {
+            set_text(todo, "SUMMARY", summary);
+        }
+        if let Some(description) = &update.description {
+            set_text(todo, "DESCRIPTION", description);
+        }
+        if let Some(related_to) = &update.related_to {
+            set_text(todo, "RELATED-TO", related_to);
+        }
+        if let Some(start_time) = update.start_time {
+            todo.set_property(IcalProperty::new("DTSTART", utc(start_time)));
+        }
+        if let Some(due) = update.due {
+            // DUE and DURATION are mutually exclusive
+            todo.remove_property("DURATION");
+            todo.set_property(IcalProperty::new("DUE", utc(due)));
+        }
+        if let Some(priority) = update.priority {
+            todo.set_property(IcalProperty::new("PRIORITY", priority.to_string()));
+        }
+        if let Some(status) = status {
+            if status == "COMPLETED" {
+                if todo.property("COMPLETED").is_none() {
+                    todo.set_property(IcalProperty::new("COMPLETED", utc(Utc::now())));
+                }
+                if update.percent_complete.is_none() {
+                    todo.set_property(IcalProperty::new("PERCENT-COMPLETE", "100".to_string()));
+                }
+            } else {
+                todo.remove_property("COMPLETED");
+            }
+            todo.set_property(IcalProperty::new("STATUS", status));
+        }
+        if let Some(percent_complete) = update.percent_complete {
+            todo.set_property(IcalProperty::new(
+                "PERCENT-COMPLETE",
+                percent_complete.to_string(),
+            ));
+        }
+        let now = utc(Utc::now());
+        todo.set_property(IcalProperty::new("DTSTAMP", now.clone()));
+        todo.set_property(IcalProperty::new("LAST-MODIFIED", now));
+
+        let mut ical_data = String::with_capacity(self.ical_data.len() + 128);
+        for component in &components {
+            component.write_to(&mut ical_data);
+        }
+        self.update_ical_data(ical_data)
+    }
+
     /**
      * Checks if this event belongs to the specified calendar.
      *
@@ -604,45 +874,111 @@ impl CalendarEvent {
      * @return true if the event occurs within the range, false otherwise
      */
     pub fn occurs_in_range(&self, start: &DateTime<Utc>, end: &DateTime<Utc>) -> bool {
-        // Basic case: event directly overlaps with range
-        if self.start_time <= *end && self.end_time >= *start {
+        // A task without DTSTART and DUE matches any range (RFC 4791 §9.9)
+        if self.is_task() && RecurrenceSet::from_ical(&self.ical_data).start().is_none() {
             return true;
         }
+        self.recurrence_set().overlaps(*start, *end)
+    }
 
-        // If event has recurrence, check if any recurrence occurs in range
-        // Note: A full implementation would need a proper recurrence rule parser
-        if let Some(rrule) = &self.rrule {
-            // Simplified check for demonstration
-            // A real implementation would need to generate recurrence instances
-            // and check if any fall within the range
-
-            // For now, we'll just check if the recurrence hasn't ended
-            // or if it ended after the start of our range
-            if let Some(until_pos) = rrule.find("UNTIL=") {
-                let until_start = until_pos + 6; // "UNTIL=" is 6 chars
-                if let Some(until_end) = rrule[until_start..].find(';') {
-                    let until_str = &rrule[until_start..until_start + until_end];
-                    if let Ok(until_date) = Self::parse_ical_datetime(until_str) {
-                        return until_date >= *start;
-                    }
-                } else {
-                    // UNTIL is the last part of the rule
-                    let until_str = &rrule[until_start..];
-                    if let Ok(until_date) = Self::parse_ical_datetime(until_str) {
-                        return until_date >= *start;
-                    }
-                }
-            } else {
-                // No UNTIL specified, so recurrence continues indefinitely
-                return true;
-            }
-        }
-
-        false
+    /**
+     * Returns the event's recurrence set: RRULE/RDATE/EXDATE of the master
+     * component and any RECURRENCE-ID overrides in its iCalendar data.
+     *
+     * @return The recurrence set, falling back to the event's own fields
+     */
+    pub fn recurrence_set(&self) -> RecurrenceSet {
+        RecurrenceSet::from_event(
+            &self.ical_data,
+            &self.ical_uid,
+            self.start_time,
+            self.end_time,
+            self.rrule.as_deref(),
+        )
     }
 
     // Helper methods for iCalendar operations
 
+    /**
+     * Returns the master VTODO when the object is a task, i.e. when its
+     * first component other than VTIMEZONE is a VTODO.
+     *
+     * @param ical_data The iCalendar data to search in
+     * @return The VTODO without RECURRENCE-ID, if any
+     */
+    fn master_todo(ical_data: &str) -> Option<IcalComponent> {
+        let mut components = IcalComponent::parse(ical_data);
+        Self::master_todo_mut(&mut components).map(|todo| todo.clone())
+    }
+
+    /**
+     * Mutable variant of `master_todo` over parsed components.
+     *
+     * @param components Top-level components of the iCalendar data
+     * @return The VTODO without RECURRENCE-ID, if the object is a task
+     */
+    fn master_todo_mut(components: &mut [IcalComponent]) -> Option<&mut IcalComponent> {
+        let calendar = match components.iter().position(|c| c.name == "VCALENDAR") {
+            Some(i) => &mut components[i].components[..],
+            None => components,
+        };
+        let first = calendar.iter().find(|c| c.name != "VTIMEZONE")?;
+        if first.name != "VTODO" {
+            return None;
+        }
+        calendar
+            .iter_mut()
+            .find(|c| c.name == "VTODO" && c.property("RECURRENCE-ID").is_none())
+    }
+
+    /**
+     * Extracts start, end, all-day flag and RRULE from plain
+     * `DTSTART:`/`DTEND:` lines (UTC or DATE values only).
+     *
+     * @param ical_data The iCalendar data to search in
+     * @return Result containing the extracted values or a domain error
+     */
+    fn simple_ical_times(ical_data: &str) -> Result<IcalTimes> {
+        let dtstart = Self::extract_ical_property(ical_data, "DTSTART").ok_or_else(|| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "CalendarEvent",
+                "Missing DTSTART in iCalendar data",
+            )
+        })?;
+
+        let dtend = Self::extract_ical_property(ical_data, "DTEND").ok_or_else(|| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "CalendarEvent",
+                "Missing DTEND in iCalendar data",
+            )
+        })?;
+
+        // Parse dates (simplified)
+        let start_time = Self::parse_ical_datetime(&dtstart).map_err(|e| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "CalendarEvent",
+                format!("Invalid DTSTART: {}", e),
+            )
+        })?;
+
+        let end_time = Self::parse_ical_datetime(&dtend).map_err(|e| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "CalendarEvent",
+                format!("Invalid DTEND: {}", e),
+            )
+        })?;
+
+        // Determine if all-day event (simplified check)
+        let all_day = dtstart.contains("VALUE=DATE") && !dtstart.contains("T");
+        let rrule = Self::extract_ical_property(ical_data, "RRULE");
+
+        Ok((start_time, end_time, all_day, rrule))
+    }
+
     /**
      * Extracts a property value from iCalendar data.
      *
@@ -816,3 +1152,131 @@ impl CalendarEvent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(body: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\n\
+            END:VTIMEZONE\r\nBEGIN:VTODO\r\nUID:task-1\r\nSUMMARY:Buy milk\r\n{}\
+            END:VTODO\r\nEND:VCALENDAR\r\n",
+            body
+        )
+    }
+
+    #[test]
+    fn test_from_ical_reads_task_properties() {
+        let ical = todo(
+            "DUE;TZID=Europe/Berlin:20250620T170000\r\nSTATUS:in-process\r\n\
+            PERCENT-COMPLETE:40\r\nPRIORITY:1\r\nRELATED-TO;RELTYPE=PARENT:parent-1\r\n",
+        );
+        let event = CalendarEvent::from_ical(Uuid::new_v4(), ical).unwrap();
+
+        assert!(event.is_task());
+        assert_eq!(event.component(), "VTODO");
+        let task = event.task().unwrap();
+        let due = Utc.with_ymd_and_hms(2025, 6, 20, 15, 0, 0).unwrap();
+        assert_eq!(task.due, Some(due));
+        assert_eq!(task.status.as_deref(), Some("IN-PROCESS"));
+        assert_eq!(task.percent_complete, Some(40));
+        assert_eq!(task.priority, Some(1));
+        assert_eq!(task.related_to.as_deref(), Some("parent-1"));
+        assert_eq!(*event.start_time(), due);
+        assert_eq!(event.task_start(), None);
+    }
+
+    #[test]
+    fn test_undated_task_matches_any_range() {
+        let event = CalendarEvent::from_ical(Uuid::new_v4(), todo("")).unwrap();
+        let start = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2000, 1, 2, 0, 0, 0).unwrap();
+
+        assert!(event.is_task());
+        assert!(event.occurs_in_range(&start, &end));
+    }
+
+    #[test]
+    fn test_event_is_not_a_task() {
+        let ical = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:e\r\nSUMMARY:Lunch\r\n\
+            DTSTART:20250101T120000Z\r\nDTEND:20250101T130000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let event = CalendarEvent::from_ical(Uuid::new_v4(), ical.to_string()).unwrap();
+
+        assert!(!event.is_task());
+        assert!(event.task().is_none());
+        assert!(event.clone().update_task(TaskUpdate::default()).is_err());
+    }
+
+    #[test]
+    fn test_new_task_and_complete_it() {
+        let due = Utc.with_ymd_and_hms(2025, 7, 1, 9, 0, 0).unwrap();
+        let mut task = CalendarEvent::new_task(
+            Uuid::new_v4(),
+            TaskUpdate {
+                summary: Some("Write report".to_string()),
+                due: Some(due),
+                priority: Some(5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(task.summary(), "Write report");
+        assert_eq!(task.task().unwrap().due, Some(due));
+        assert_eq!(task.task().unwrap().status.as_deref(), Some("NEEDS-ACTION"));
+        assert!(task.ical_data().contains("DUE:20250701T090000Z\r\n"));
+
+        task.update_task(TaskUpdate {
+            status: Some("COMPLETED".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let details = task.task().unwrap();
+        assert_eq!(details.status.as_deref(), Some("COMPLETED"));
+        assert_eq!(details.percent_complete, Some(100));
+        assert!(details.completed_at.is_some());
+        assert_eq!(details.priority, Some(5));
+
+        task.update_task(TaskUpdate {
+            status: Some("NEEDS-ACTION".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(task.task().unwrap().completed_at.is_none());
+    }
+
+    #[test]
+    fn test_update_task_keeps_other_properties_and_validates() {
+        let ical = todo("X-TASKS-ORG-ID:42\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nEND:VALARM\r\n");
+        let mut task = CalendarEvent::from_ical(Uuid::new_v4(), ical).unwrap();
+
+        task.update_task(TaskUpdate {
+            description: Some("Two litres".to_string()),
+            percent_complete: Some(50),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(task.ical_data().contains("X-TASKS-ORG-ID:42"));
+        assert!(task.ical_data().contains("BEGIN:VALARM"));
+        assert!(task.ical_data().contains("BEGIN:VTIMEZONE"));
+        assert_eq!(task.description(), Some("Two litres"));
+
+        for update in [
+            TaskUpdate {
+                priority: Some(10),
+                ..Default::default()
+            },
+            TaskUpdate {
+                status: Some("DONE".to_string()),
+                ..Default::default()
+            },
+            TaskUpdate {
+                summary: Some(" ".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(task.update_task(update).is_err());
+        }
+    }
+}
@@ -11,6 +11,8 @@ pub struct AddressBook {
     is_public: bool,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    /// Collection sync token (RFC 6578), bumped by every vCard change
+    sync_token: i64,
 }
 
 impl AddressBook {
@@ -32,6 +34,7 @@ impl AddressBook {
             is_public,
             created_at: now,
             updated_at: now,
+            sync_token: 0,
         }
     }
 
@@ -55,9 +58,16 @@ impl AddressBook {
             is_public,
             created_at,
             updated_at,
+            sync_token: 0,
         }
     }
 
+    /// Sets the sync token as loaded from storage
+    pub fn with_sync_token(mut self, sync_token: i64) -> Self {
+        self.sync_token = sync_token;
+        self
+    }
+
     // --- Getters ---
     pub fn id(&self) -> &Uuid {
         &self.id
@@ -83,6 +93,9 @@ impl AddressBook {
     pub fn updated_at(&self) -> &DateTime<Utc> {
         &self.updated_at
     }
+    pub fn sync_token(&self) -> i64 {
+        self.sync_token
+    }
 
     // --- Setters for mutable operations ---
     pub fn set_name(&mut self, name: String) {
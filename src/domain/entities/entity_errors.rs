@@ -97,6 +97,33 @@ impl Error for UserError {}
 /// Type alias for User entity operation results
 pub type UserResult<T> = Result<T, UserError>;
 
+// ============================================================================
+// GROUP ERRORS
+// ============================================================================
+
+/// Errors that can occur during Group entity operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupError {
+    /// Invalid group name
+    InvalidName(String),
+    /// General validation error
+    ValidationError(String),
+}
+
+impl Display for GroupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            GroupError::InvalidName(msg) => write!(f, "Invalid group name: {}", msg),
+            GroupError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+        }
+    }
+}
+
+impl Error for GroupError {}
+
+/// Type alias for Group entity operation results
+pub type GroupResult<T> = Result<T, GroupError>;
+
 // ============================================================================
 // SHARE ERRORS
 // ============================================================================
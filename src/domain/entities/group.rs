@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+// Re-export entity errors from the centralized module
+pub use super::entity_errors::{GroupError, GroupResult};
+
+/// Longest accepted group name, in characters
+const MAX_NAME_LEN: usize = 128;
+
+/// A named set of users.
+///
+/// Names are unique regardless of case.  They are kept free-form (spaces,
+/// slashes, `=`) so that groups mirrored from an identity provider keep the
+/// provider's naming, e.g. Keycloak's `/staff/finance`.
+#[derive(Debug, Clone)]
+pub struct Group {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    /// Storage quota members get at least, `None` = no group default
+    default_quota_bytes: Option<i64>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl Group {
+    pub fn new(
+        name: String,
+        description: Option<String>,
+        default_quota_bytes: Option<i64>,
+    ) -> GroupResult<Self> {
+        let name = Self::validate_name(&name)?;
+        Self::validate_quota(default_quota_bytes)?;
+
+        let now = Utc::now();
+        Ok(Self {
+            id: Uuid::new_v4(),
+            name,
+            description: description.filter(|d| !d.trim().is_empty()),
+            default_quota_bytes,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    pub fn from_data(
+        id: Uuid,
+        name: String,
+        description: Option<String>,
+        default_quota_bytes: Option<i64>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            default_quota_bytes,
+            created_at,
+            updated_at,
+        }
+    }
+
+    // Getters
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn default_quota_bytes(&self) -> Option<i64> {
+        self.default_quota_bytes
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    // Mutators
+    pub fn rename(&mut self, name: &str) -> GroupResult<()> {
+        self.name = Self::validate_name(name)?;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description.filter(|d| !d.trim().is_empty());
+        self.updated_at = Utc::now();
+    }
+
+    pub fn set_default_quota(&mut self, default_quota_bytes: Option<i64>) -> GroupResult<()> {
+        Self::validate_quota(default_quota_bytes)?;
+        self.default_quota_bytes = default_quota_bytes;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Trims the name and checks it is non-empty, not too long and free of
+    /// control characters.  Returns the trimmed name.
+    pub fn validate_name(name: &str) -> GroupResult<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(GroupError::InvalidName(
+                "Group name cannot be empty".to_string(),
+            ));
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(GroupError::InvalidName(format!(
+                "Group name must be at most {MAX_NAME_LEN} characters"
+            )));
+        }
+        if name.chars().any(char::is_control) {
+            return Err(GroupError::InvalidName(
+                "Group name must not contain control characters".to_string(),
+            ));
+        }
+        Ok(name.to_string())
+    }
+
+    fn validate_quota(default_quota_bytes: Option<i64>) -> GroupResult<()> {
+        if default_quota_bytes.is_some_and(|q| q <= 0) {
+            return Err(GroupError::ValidationError(
+                "Default quota must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_group_trims_name() {
+        let group = Group::new("  Finance ".to_string(), Some(" ".to_string()), None).unwrap();
+        assert_eq!(group.name(), "Finance");
+        assert!(group.description().is_none());
+    }
+
+    #[test]
+    fn test_idp_style_names_are_accepted() {
+        assert!(Group::validate_name("/staff/finance").is_ok());
+        assert!(Group::validate_name("cn=admins,ou=groups").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_names_and_quotas_are_rejected() {
+        assert!(Group::validate_name("   ").is_err());
+        assert!(Group::validate_name("bad\nname").is_err());
+        assert!(Group::validate_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+        assert!(Group::new("ok".to_string(), None, Some(0)).is_err());
+
+        let mut group = Group::new("ok".to_string(), None, Some(1024)).unwrap();
+        assert!(group.set_default_quota(Some(-1)).is_err());
+        assert_eq!(group.default_quota_bytes(), Some(1024));
+    }
+}
@@ -6,6 +6,7 @@ pub mod device_code;
 pub mod entity_errors;
 pub mod file;
 pub mod folder;
+pub mod group;
 pub mod playlist;
 pub mod session;
 pub mod share;
@@ -15,5 +16,6 @@ pub mod user;
 // Re-exportar errores de entidades para facilitar el uso
 pub use entity_errors::{
     CalendarError, CalendarEventError, CalendarEventResult, CalendarResult, FileError, FileResult,
-    FolderError, FolderResult, ShareError, ShareResult, UserError, UserResult,
+    FolderError, FolderResult, GroupError, GroupResult, ShareError, ShareResult, UserError,
+    UserResult,
 };
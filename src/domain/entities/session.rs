@@ -1,6 +1,46 @@
 use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
+/// How the login that opened a session family was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginMethod {
+    /// Username and password, local or LDAP, with or without a TOTP code
+    Password,
+    /// WebAuthn credential, passwordless or as second factor
+    Passkey,
+    /// SSO through the OIDC provider
+    Oidc,
+    /// OAuth 2.0 Device Authorization Grant (RFC 8628)
+    Device,
+}
+
+impl LoginMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Password => "password",
+            Self::Passkey => "passkey",
+            Self::Oidc => "oidc",
+            Self::Device => "device",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "password" => Some(Self::Password),
+            "passkey" => Some(Self::Passkey),
+            "oidc" => Some(Self::Oidc),
+            "device" => Some(Self::Device),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for LoginMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Session {
     id: Uuid,
@@ -14,6 +54,8 @@ pub struct Session {
     /// Groups all tokens issued from the same original login.
     /// Replaying a revoked token from this family triggers full-family revocation.
     family_id: Uuid,
+    /// Carried over on refresh, so it describes the original login
+    login_method: LoginMethod,
 }
 
 impl Session {
@@ -24,6 +66,7 @@ impl Session {
         user_agent: Option<String>,
         expires_in_days: i64,
         family_id: Uuid,
+        login_method: LoginMethod,
     ) -> Self {
         if refresh_token.is_empty() {
             panic!("Session refresh_token cannot be empty");
@@ -40,6 +83,7 @@ impl Session {
             created_at: now,
             revoked: false,
             family_id,
+            login_method,
         }
     }
 
@@ -54,6 +98,7 @@ impl Session {
         created_at: DateTime<Utc>,
         revoked: bool,
         family_id: Uuid,
+        login_method: LoginMethod,
     ) -> Self {
         Self {
             id,
@@ -65,6 +110,7 @@ impl Session {
             created_at,
             revoked,
             family_id,
+            login_method,
         }
     }
 
@@ -112,4 +158,23 @@ impl Session {
     pub fn family_id(&self) -> Uuid {
         self.family_id
     }
+
+    pub fn login_method(&self) -> LoginMethod {
+        self.login_method
+    }
+}
+
+/// One signed-in device: the live end of a refresh token family.
+#[derive(Debug, Clone)]
+pub struct SessionFamily {
+    pub family_id: Uuid,
+    pub login_method: LoginMethod,
+    /// Client of the latest token in the family
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// Oldest token still on record, normally the login itself
+    pub signed_in_at: DateTime<Utc>,
+    /// Latest token refresh
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
 }
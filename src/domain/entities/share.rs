@@ -17,6 +17,14 @@ pub struct Share {
     created_at: u64,
     created_by: Uuid,
     access_count: u64,
+    /// Upload-only ("file drop") link: visitors may upload but not list
+    /// or download the folder's contents
+    hide_contents: bool,
+    /// Largest file, in bytes, a visitor may upload through the link
+    max_upload_size: Option<u64>,
+    /// Downloads after which the link stops working, `None` = unlimited
+    max_downloads: Option<u64>,
+    download_count: u64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -84,6 +92,10 @@ impl Share {
             created_at: now,
             created_by,
             access_count: 0,
+            hide_contents: false,
+            max_upload_size: None,
+            max_downloads: None,
+            download_count: 0,
         })
     }
 
@@ -113,6 +125,10 @@ impl Share {
             created_at,
             created_by,
             access_count,
+            hide_contents: false,
+            max_upload_size: None,
+            max_downloads: None,
+            download_count: 0,
         }
     }
 
@@ -158,6 +174,34 @@ impl Share {
         self.access_count
     }
 
+    pub fn hide_contents(&self) -> bool {
+        self.hide_contents
+    }
+
+    pub fn max_upload_size(&self) -> Option<u64> {
+        self.max_upload_size
+    }
+
+    pub fn max_downloads(&self) -> Option<u64> {
+        self.max_downloads
+    }
+
+    pub fn download_count(&self) -> u64 {
+        self.download_count
+    }
+
+    /// Returns whether the link has served all the downloads it allows.
+    pub fn download_limit_reached(&self) -> bool {
+        self.max_downloads
+            .is_some_and(|max| self.download_count >= max)
+    }
+
+    /// Returns whether visitors may upload through this link: only folder
+    /// links with write permission accept uploads.
+    pub fn accepts_uploads(&self) -> bool {
+        self.permissions.write && self.item_type == ShareItemType::Folder
+    }
+
     // ── Builder-style modifiers (immutable) ──
 
     pub fn with_permissions(mut self, permissions: SharePermissions) -> Self {
@@ -180,6 +224,26 @@ impl Share {
         self
     }
 
+    pub fn with_hide_contents(mut self, hide_contents: bool) -> Self {
+        self.hide_contents = hide_contents;
+        self
+    }
+
+    pub fn with_max_upload_size(mut self, max_upload_size: Option<u64>) -> Self {
+        self.max_upload_size = max_upload_size;
+        self
+    }
+
+    pub fn with_max_downloads(mut self, max_downloads: Option<u64>) -> Self {
+        self.max_downloads = max_downloads;
+        self
+    }
+
+    pub fn with_download_count(mut self, download_count: u64) -> Self {
+        self.download_count = download_count;
+        self
+    }
+
     pub fn is_expired(&self) -> bool {
         if let Some(expires_at) = self.expires_at {
             let now = SystemTime::now()
@@ -328,6 +392,27 @@ mod tests {
         assert!(share_result.is_err());
     }
 
+    #[test]
+    fn test_download_limit_reached() {
+        let share = Share::new(
+            "test_file_id".to_string(),
+            None,
+            ShareItemType::File,
+            test_user_id(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(!share.download_limit_reached());
+
+        let share = share.with_max_downloads(Some(2)).with_download_count(1);
+        assert!(!share.download_limit_reached());
+        let share = share.with_download_count(2);
+        assert!(share.download_limit_reached());
+        assert!(!share.with_max_downloads(None).download_limit_reached());
+    }
+
     #[test]
     fn test_share_item_type_conversion() {
         assert_eq!(ShareItemType::File.to_string(), "file");
@@ -381,4 +466,44 @@ mod tests {
         assert!(!share.has_password());
         assert_eq!(share.password_hash(), None);
     }
+
+    #[test]
+    fn test_only_writable_folder_links_accept_uploads() {
+        let folder = Share::new(
+            "folder_id".to_string(),
+            None,
+            ShareItemType::Folder,
+            test_user_id(),
+            Some(SharePermissions::new(true, true, false)),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(folder.accepts_uploads());
+        assert!(!folder.hide_contents());
+        assert!(folder.max_upload_size().is_none());
+
+        let read_only = folder
+            .clone()
+            .with_permissions(SharePermissions::new(true, false, false));
+        assert!(!read_only.accepts_uploads());
+
+        let file = Share::new(
+            "file_id".to_string(),
+            None,
+            ShareItemType::File,
+            test_user_id(),
+            Some(SharePermissions::new(true, true, false)),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(!file.accepts_uploads());
+
+        let drop = folder
+            .with_hide_contents(true)
+            .with_max_upload_size(Some(1024));
+        assert!(drop.hide_contents());
+        assert_eq!(drop.max_upload_size(), Some(1024));
+    }
 }
@@ -4,6 +4,9 @@ use uuid::Uuid;
 // Re-export entity errors from the centralized module
 pub use super::entity_errors::{UserError, UserResult};
 
+/// `oidc_provider` value of users authenticated against LDAP
+pub const LDAP_PROVIDER: &str = "ldap";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 // We'll handle conversion manually for now until the type is properly set up in the database
 pub enum UserRole {
@@ -115,6 +118,25 @@ impl User {
         })
     }
 
+    /// Create a new LDAP-authenticated user, linked to the directory entry
+    /// through `subject` (no password required).
+    pub fn new_ldap(
+        username: String,
+        email: String,
+        role: UserRole,
+        storage_quota_bytes: i64,
+        subject: String,
+    ) -> UserResult<Self> {
+        Self::new_oidc(
+            username,
+            email,
+            role,
+            storage_quota_bytes,
+            LDAP_PROVIDER.to_string(),
+            subject,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn from_data(
         id: Uuid,
@@ -232,11 +254,16 @@ impl User {
         self.oidc_subject.as_deref()
     }
 
-    /// Returns true if this is an OIDC-only user (no password)
+    /// Returns true if this is an OIDC- or LDAP-only user (no password)
     pub fn is_oidc_user(&self) -> bool {
         self.oidc_provider.is_some()
     }
 
+    /// Returns true if this user authenticates against LDAP
+    pub fn is_ldap_user(&self) -> bool {
+        self.oidc_provider.as_deref() == Some(LDAP_PROVIDER)
+    }
+
     /// Update the password hash.
     ///
     /// The new password should be hashed externally using PasswordHasherPort
@@ -5,6 +5,17 @@ use uuid::Uuid;
 
 pub type CalendarEventRepositoryResult<T> = Result<T, DomainError>;
 
+/// Changes to a calendar's events since a sync token (RFC 6578)
+#[derive(Debug, Clone)]
+pub struct CalendarEventChanges {
+    /// The calendar's sync token at the time of the query
+    pub sync_token: i64,
+    /// Events created or modified since the given token, in their current state
+    pub changed: Vec<CalendarEvent>,
+    /// iCalendar UIDs of events deleted since the given token
+    pub deleted: Vec<String>,
+}
+
 /// Repository interface for CalendarEvent entity operations
 pub trait CalendarEventRepository: Send + Sync + 'static {
     /// Creates a new calendar event
@@ -38,7 +49,8 @@ pub trait CalendarEventRepository: Send + Sync + 'static {
         summary: &str,
     ) -> CalendarEventRepositoryResult<Vec<CalendarEvent>>;
 
-    /// Gets events in a specific time range for a calendar
+    /// Gets events with at least one instance in a time range for a calendar,
+    /// evaluating recurrence rules, exceptions and overridden instances
     async fn get_events_in_time_range(
         &self,
         calendar_id: &Uuid,
@@ -80,4 +92,18 @@ pub trait CalendarEventRepository: Send + Sync + 'static {
         start: &DateTime<Utc>,
         end: &DateTime<Utc>,
     ) -> CalendarEventRepositoryResult<Vec<CalendarEvent>>;
+
+    /// Lists the changes to a calendar's events since a sync token.
+    ///
+    /// `None` as token returns every event (initial sync).  Returns `Ok(None)`
+    /// when the token is unknown or older than the retained change log.
+    async fn list_changes_since(
+        &self,
+        calendar_id: &Uuid,
+        since: Option<i64>,
+    ) -> CalendarEventRepositoryResult<Option<CalendarEventChanges>>;
+
+    /// Deletes change-log entries older than `before`, expiring sync tokens
+    /// from before them. Returns the number of entries removed.
+    async fn prune_changes(&self, before: DateTime<Utc>) -> CalendarEventRepositoryResult<u64>;
 }
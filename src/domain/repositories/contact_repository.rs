@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use std::result::Result;
 use uuid::Uuid;
 
@@ -6,6 +7,17 @@ use crate::domain::entities::contact::{Contact, ContactGroup};
 
 pub type ContactRepositoryResult<T> = Result<T, DomainError>;
 
+/// Changes to an address book's vCards since a sync token (RFC 6578)
+#[derive(Debug, Clone)]
+pub struct ContactChanges {
+    /// The address book's sync token at the time of the query
+    pub sync_token: i64,
+    /// Contacts created or modified since the given token, in their current state
+    pub changed: Vec<Contact>,
+    /// UIDs of contacts deleted since the given token
+    pub deleted: Vec<String>,
+}
+
 pub trait ContactRepository: Send + Sync + 'static {
     async fn create_contact(&self, contact: Contact) -> ContactRepositoryResult<Contact>;
     async fn update_contact(&self, contact: Contact) -> ContactRepositoryResult<Contact>;
@@ -28,6 +40,17 @@ pub trait ContactRepository: Send + Sync + 'static {
         address_book_id: &Uuid,
         query: &str,
     ) -> ContactRepositoryResult<Vec<Contact>>;
+    /// Lists the changes to an address book's vCards since a sync token.
+    /// `None` as token returns every contact; `Ok(None)` means the token is
+    /// unknown or older than the retained change log.
+    async fn list_changes_since(
+        &self,
+        address_book_id: &Uuid,
+        since: Option<i64>,
+    ) -> ContactRepositoryResult<Option<ContactChanges>>;
+    /// Deletes change-log entries older than `before`.
+    /// Returns the number of entries removed.
+    async fn prune_changes(&self, before: DateTime<Utc>) -> ContactRepositoryResult<u64>;
 }
 
 pub trait ContactGroupRepository: Send + Sync + 'static {
@@ -1,5 +1,5 @@
 use crate::common::errors::DomainError;
-use crate::domain::entities::session::Session;
+use crate::domain::entities::session::{Session, SessionFamily};
 use uuid::Uuid;
 
 #[derive(Debug, thiserror::Error)]
@@ -55,6 +55,31 @@ pub trait SessionRepository: Send + Sync + 'static {
     /// Revokes all sessions in a token family (theft response)
     async fn revoke_session_family(&self, family_id: Uuid) -> SessionRepositoryResult<u64>;
 
+    /// Revokes a token family of `user_id`; 0 when the family is not theirs
+    async fn revoke_user_session_family(
+        &self,
+        user_id: Uuid,
+        family_id: Uuid,
+    ) -> SessionRepositoryResult<u64>;
+
+    /// Lists the families of a user that still hold a live token
+    async fn list_active_session_families(
+        &self,
+        user_id: Uuid,
+    ) -> SessionRepositoryResult<Vec<SessionFamily>>;
+
+    /// Records the client behind a session: the IP is refreshed on every
+    /// call, the user agent is kept from the first one
+    async fn record_session_client(
+        &self,
+        refresh_token: &str,
+        ip_address: &str,
+        user_agent: Option<&str>,
+    ) -> SessionRepositoryResult<()>;
+
+    /// Revokes the token families opened with a WebAuthn credential
+    async fn revoke_webauthn_sessions(&self, credential_id: Uuid) -> SessionRepositoryResult<u64>;
+
     /// Deletes expired sessions
     async fn delete_expired_sessions(&self) -> SessionRepositoryResult<u64>;
 }
@@ -1,5 +1,7 @@
 pub mod i18n_service;
 pub mod path_service;
+pub mod recurrence_service;
+pub mod scheduling_service;
 
 // NOTE: auth_service has been moved to infrastructure/services/jwt_service.rs
 // The functionality is now exposed through application/ports/auth_ports.rs (TokenServicePort)
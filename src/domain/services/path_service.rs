@@ -4,6 +4,7 @@
 //! PathService (which implements StoragePort and StorageMediator) was moved to
 //! infrastructure/services/path_service.rs because it has file system dependencies.
 
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 /// Validates a single file or folder name component.
@@ -26,6 +27,22 @@ pub fn validate_storage_name(name: &str) -> Result<(), &'static str> {
     Ok(())
 }
 
+/// Returns `name`, or `name (2)`, `name (3)`, … (before the extension)
+/// for the first one not in `existing`.
+pub fn unique_file_name(name: &str, existing: &HashSet<String>) -> String {
+    if !existing.contains(name) {
+        return name.to_string();
+    }
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+    (2..)
+        .map(|n| format!("{} ({}){}", stem, n, extension))
+        .find(|candidate| !existing.contains(candidate))
+        .expect("an unused name exists")
+}
+
 /// Represents a storage path in the domain (Value Object)
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct StoragePath {
@@ -251,4 +268,23 @@ mod tests {
         assert!(!path.segments().contains(&"..".to_string()));
         assert!(!path.segments().contains(&".".to_string()));
     }
+
+    #[test]
+    fn unique_file_name_keeps_free_names() {
+        let existing = HashSet::from(["report.pdf".to_string()]);
+        assert_eq!(unique_file_name("notes.txt", &existing), "notes.txt");
+    }
+
+    #[test]
+    fn unique_file_name_numbers_before_the_extension() {
+        let existing = HashSet::from([
+            "report.pdf".to_string(),
+            "report (2).pdf".to_string(),
+            "README".to_string(),
+            ".env".to_string(),
+        ]);
+        assert_eq!(unique_file_name("report.pdf", &existing), "report (3).pdf");
+        assert_eq!(unique_file_name("README", &existing), "README (2)");
+        assert_eq!(unique_file_name(".env", &existing), ".env (2)");
+    }
 }
@@ -0,0 +1,1576 @@
+//! Recurrence expansion for iCalendar objects (RFC 5545 §3.8.5).
+//!
+//! A recurring event is stored as one iCalendar object holding the master
+//! component (DTSTART plus RRULE/RDATE/EXDATE) and any number of overridden
+//! instances identified by RECURRENCE-ID.  [`RecurrenceSet`] evaluates all of
+//! them, so time-range queries match an event by its instances rather than by
+//! its first occurrence, and renders the CalDAV `expand` and
+//! `limit-recurrence-set` forms of `calendar-data` (RFC 4791 §9.6).
+//!
+//! Rules are evaluated in the time zone of DTSTART, so a weekly 09:00 meeting
+//! stays at 09:00 local time across DST changes.  TZIDs that are not IANA
+//! names (after stripping vendor prefixes) are treated as floating time.
+//! BYWEEKNO is not supported and is ignored.
+
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Upper bound on the periods a single rule evaluation may walk through.
+const MAX_PERIODS: i64 = 100_000;
+
+/// Longest instance honoured, about ten thousand years: as much as DTSTART
+/// and DTEND can span.  Longer DURATIONs are clamped so instance arithmetic
+/// cannot overflow.
+const MAX_DURATION_DAYS: i64 = 3_660_000;
+
+/// Upper bound on the instances returned for one object.
+pub const MAX_INSTANCES: usize = 5_000;
+
+/// Properties that define the recurrence set; dropped from expanded instances.
+const RECURRENCE_PROPERTIES: &[&str] = &[
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "DURATION",
+    "RRULE",
+    "EXRULE",
+    "RDATE",
+    "EXDATE",
+    "RECURRENCE-ID",
+];
+
+// ─── Content lines and components ────────────────────────────────────
+
+/// One unfolded content line of an iCalendar object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcalProperty {
+    /// Upper-cased property name
+    pub name: String,
+    /// Parameters as `(upper-cased name, unquoted value)`
+    pub params: Vec<(String, String)>,
+    pub value: String,
+    /// The unfolded line as received, re-emitted verbatim
+    raw: String,
+}
+
+impl IcalProperty {
+    /// Parses an unfolded content line.
+    fn parse(line: &str) -> Option<Self> {
+        let mut in_quotes = false;
+        let mut value_start = None;
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ':' if !in_quotes => {
+                    value_start = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let colon = value_start?;
+        let mut head = line[..colon].split(';');
+        let name = head.next()?.trim().to_ascii_uppercase();
+        if name.is_empty() {
+            return None;
+        }
+        let params = head
+            .filter_map(|p| {
+                let (k, v) = p.split_once('=')?;
+                Some((
+                    k.trim().to_ascii_uppercase(),
+                    v.trim_matches('"').to_string(),
+                ))
+            })
+            .collect();
+
+        Some(Self {
+            name,
+            params,
+            value: line[colon + 1..].to_string(),
+            raw: line.to_string(),
+        })
+    }
+
+    /// Creates a property without parameters.
+    pub fn new(name: &str, value: String) -> Self {
+        Self {
+            name: name.to_string(),
+            params: Vec::new(),
+            raw: format!("{}:{}", name, value),
+            value,
+        }
+    }
+
+    /// Returns the value of a parameter, if present.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets a parameter, replacing an existing one, and re-renders the line.
+    pub fn set_param(&mut self, name: &str, value: &str) {
+        match self.params.iter_mut().find(|(k, _)| k == name) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.params.push((name.to_string(), value.to_string())),
+        }
+        let mut raw = self.name.clone();
+        for (k, v) in &self.params {
+            // Values with separators are quoted (RFC 5545 §3.2)
+            if v.contains([':', ';', ',']) && !v.contains('"') {
+                let _ = write!(raw, ";{}=\"{}\"", k, v);
+            } else {
+                let _ = write!(raw, ";{}={}", k, v);
+            }
+        }
+        let _ = write!(raw, ":{}", self.value);
+        self.raw = raw;
+    }
+}
+
+/// An iCalendar component (`BEGIN:NAME` … `END:NAME`) with its children.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IcalComponent {
+    /// Upper-cased component name, e.g. `VEVENT`
+    pub name: String,
+    pub properties: Vec<IcalProperty>,
+    pub components: Vec<IcalComponent>,
+}
+
+impl IcalComponent {
+    /// Parses iCalendar text into its top-level components, unfolding
+    /// continuation lines.  Malformed lines are skipped.
+    pub fn parse(data: &str) -> Vec<IcalComponent> {
+        let mut lines: Vec<String> = Vec::new();
+        for line in data.split('\n') {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if let Some(rest) = line.strip_prefix([' ', '\t']) {
+                if let Some(last) = lines.last_mut() {
+                    last.push_str(rest);
+                }
+            } else if !line.is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+
+        let mut stack: Vec<IcalComponent> = Vec::new();
+        let mut top = Vec::new();
+        for line in &lines {
+            let Some(prop) = IcalProperty::parse(line) else {
+                continue;
+            };
+            match prop.name.as_str() {
+                "BEGIN" => stack.push(IcalComponent {
+                    name: prop.value.trim().to_ascii_uppercase(),
+                    ..Default::default()
+                }),
+                "END" => {
+                    if let Some(done) = stack.pop() {
+                        match stack.last_mut() {
+                            Some(parent) => parent.components.push(done),
+                            None => top.push(done),
+                        }
+                    }
+                }
+                _ => {
+                    if let Some(current) = stack.last_mut() {
+                        current.properties.push(prop);
+                    }
+                }
+            }
+        }
+        top
+    }
+
+    /// Returns the first property with the given name.
+    pub fn property(&self, name: &str) -> Option<&IcalProperty> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// Returns the value of the first property with the given name.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.property(name).map(|p| p.value.as_str())
+    }
+
+    /// Returns a DATE or DATE-TIME property as a UTC instant.
+    pub fn time(&self, name: &str) -> Option<DateTime<Utc>> {
+        IcalTime::from_property(self.property(name)?).map(|t| t.utc())
+    }
+
+    /// Replaces the first property with the given name, or appends it.
+    pub fn set_property(&mut self, prop: IcalProperty) {
+        match self.properties.iter_mut().find(|p| p.name == prop.name) {
+            Some(existing) => *existing = prop,
+            None => self.properties.push(prop),
+        }
+    }
+
+    /// Removes every property with the given name.
+    pub fn remove_property(&mut self, name: &str) {
+        self.properties.retain(|p| p.name != name);
+    }
+
+    /// Writes the component, folding lines at 75 octets.
+    pub fn write_to(&self, out: &mut String) {
+        let _ = write!(out, "BEGIN:{}\r\n", self.name);
+        for prop in &self.properties {
+            write_folded(out, &prop.raw);
+        }
+        for child in &self.components {
+            child.write_to(out);
+        }
+        let _ = write!(out, "END:{}\r\n", self.name);
+    }
+}
+
+/// Writes a content line folded at 75 octets (RFC 5545 §3.1).
+fn write_folded(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+// ─── Date-time values ────────────────────────────────────────────────
+
+/// Time zone of a DATE-TIME value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Utc,
+    /// Local time without a zone; evaluated as UTC
+    Floating,
+    Named(Tz),
+}
+
+impl Zone {
+    /// Resolves a TZID, accepting vendor-prefixed IANA names such as
+    /// `/mozilla.org/20050126_1/Europe/Berlin`.
+    fn from_tzid(tzid: &str) -> Self {
+        let parts: Vec<&str> = tzid.trim_matches('/').split('/').collect();
+        (0..parts.len())
+            .find_map(|i| parts[i..].join("/").parse::<Tz>().ok())
+            .map_or(Zone::Floating, Zone::Named)
+    }
+
+    fn to_utc(self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self {
+            Zone::Utc | Zone::Floating => Utc.from_utc_datetime(&local),
+            Zone::Named(tz) => match tz.from_local_datetime(&local) {
+                LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.with_timezone(&Utc),
+                // Skipped by a DST gap: shift forward by the gap (RFC 5545 §3.3.5)
+                LocalResult::None => tz
+                    .from_local_datetime(&(local + Duration::hours(1)))
+                    .earliest()
+                    .map_or_else(
+                        || Utc.from_utc_datetime(&local),
+                        |dt| dt.with_timezone(&Utc),
+                    ),
+            },
+        }
+    }
+
+    fn local_time(self, utc: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Named(tz) => utc.with_timezone(&tz).naive_local(),
+            Zone::Utc | Zone::Floating => utc.naive_utc(),
+        }
+    }
+}
+
+/// A DATE or DATE-TIME value with its zone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IcalTime {
+    local: NaiveDateTime,
+    zone: Zone,
+    is_date: bool,
+}
+
+impl IcalTime {
+    /// Parses a single value; `tzid` applies to local DATE-TIME values.
+    fn parse(value: &str, tzid: Option<&str>) -> Option<Self> {
+        let value = value.trim();
+        if value.len() == 8 {
+            let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+            return Some(Self {
+                local: date.and_time(NaiveTime::MIN),
+                zone: Zone::Floating,
+                is_date: true,
+            });
+        }
+        let (text, zone) = match value.strip_suffix('Z') {
+            Some(text) => (text, Zone::Utc),
+            None => (value, tzid.map_or(Zone::Floating, Zone::from_tzid)),
+        };
+        let local = NaiveDateTime::parse_from_str(text, "%Y%m%dT%H%M%S").ok()?;
+        Some(Self {
+            local,
+            zone,
+            is_date: false,
+        })
+    }
+
+    /// Parses the (first) value of a property.
+    fn from_property(prop: &IcalProperty) -> Option<Self> {
+        let value = prop.value.split(',').next()?;
+        Self::parse(value.split('/').next()?, prop.param("TZID"))
+    }
+
+    /// Parses every value of a list property such as RDATE or EXDATE.
+    /// PERIOD values contribute their start.
+    fn list_from_property(prop: &IcalProperty) -> Vec<Self> {
+        prop.value
+            .split(',')
+            .filter_map(|v| Self::parse(v.split('/').next()?, prop.param("TZID")))
+            .collect()
+    }
+
+    fn utc(&self) -> DateTime<Utc> {
+        self.zone.to_utc(self.local)
+    }
+}
+
+/// Parses an iCalendar DURATION value such as `PT1H30M`, `P1D` or `-P2W`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'-' => (-1, &value[1..]),
+        b'+' => (1, &value[1..]),
+        _ => (1, value),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let part = match (c, in_time) {
+                    ('W', false) => Duration::try_weeks(n),
+                    ('D', false) => Duration::try_days(n),
+                    ('H', true) => Duration::try_hours(n),
+                    ('M', true) => Duration::try_minutes(n),
+                    ('S', true) => Duration::try_seconds(n),
+                    _ => return None,
+                };
+                total = total.checked_add(&part?)?;
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(if sign < 0 { -total } else { total })
+}
+
+fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// ─── Recurrence rules ────────────────────────────────────────────────
+
+/// RRULE frequency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A parsed RRULE value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    until: Option<IcalTime>,
+    pub by_second: Vec<u32>,
+    pub by_minute: Vec<u32>,
+    pub by_hour: Vec<u32>,
+    /// Weekdays with an optional ordinal (0 = every occurrence in the period)
+    pub by_day: Vec<(i32, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_year_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+}
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    Some(match s {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn parse_list<T: std::str::FromStr>(value: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .trim_start_matches('+')
+                .parse()
+                .map_err(|_| format!("Invalid list value: {}", v))
+        })
+        .collect()
+}
+
+impl RecurrenceRule {
+    /// Parses an RRULE value (without the `RRULE:` prefix).
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let mut freq = None;
+        let mut parsed = Self {
+            freq: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_second: Vec::new(),
+            by_minute: Vec::new(),
+            by_hour: Vec::new(),
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_year_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Mon,
+        };
+
+        for part in rule.trim().split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid rule part: {}", part))?;
+            let value = value.trim();
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_ascii_uppercase().as_str() {
+                        "SECONDLY" => Frequency::Secondly,
+                        "MINUTELY" => Frequency::Minutely,
+                        "HOURLY" => Frequency::Hourly,
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(format!("Unknown FREQ: {}", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    parsed.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|i| *i > 0)
+                        .ok_or_else(|| format!("Invalid INTERVAL: {}", value))?
+                }
+                "COUNT" => {
+                    parsed.count = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid COUNT: {}", value))?,
+                    )
+                }
+                "UNTIL" => {
+                    parsed.until = Some(
+                        IcalTime::parse(value, None)
+                            .ok_or_else(|| format!("Invalid UNTIL: {}", value))?,
+                    )
+                }
+                "BYSECOND" => parsed.by_second = parse_list(value)?,
+                "BYMINUTE" => parsed.by_minute = parse_list(value)?,
+                "BYHOUR" => parsed.by_hour = parse_list(value)?,
+                "BYMONTHDAY" => parsed.by_month_day = parse_list(value)?,
+                "BYYEARDAY" => parsed.by_year_day = parse_list(value)?,
+                "BYMONTH" => parsed.by_month = parse_list(value)?,
+                "BYSETPOS" => parsed.by_set_pos = parse_list(value)?,
+                "BYDAY" => {
+                    parsed.by_day = value
+                        .split(',')
+                        .map(|d| {
+                            let d = d.trim().to_ascii_uppercase();
+                            if !d.is_ascii() {
+                                return Err(format!("Invalid BYDAY: {}", d));
+                            }
+                            let split = d.len().saturating_sub(2);
+                            let weekday = parse_weekday(&d[split..])
+                                .ok_or_else(|| format!("Invalid BYDAY: {}", d))?;
+                            let ordinal = match &d[..split] {
+                                "" => 0,
+                                n => n
+                                    .trim_start_matches('+')
+                                    .parse()
+                                    .map_err(|_| format!("Invalid BYDAY: {}", d))?,
+                            };
+                            Ok((ordinal, weekday))
+                        })
+                        .collect::<Result<_, String>>()?
+                }
+                "WKST" => {
+                    parsed.week_start = parse_weekday(&value.to_ascii_uppercase())
+                        .ok_or_else(|| format!("Invalid WKST: {}", value))?
+                }
+                // BYWEEKNO and extensions are ignored
+                _ => {}
+            }
+        }
+
+        parsed.freq = freq.ok_or("Recurrence rule requires FREQ")?;
+        Ok(parsed)
+    }
+
+    /// Calls `visit` with every instance start (local time in the DTSTART
+    /// zone) in ascending order until it returns `false` or the rule ends.
+    ///
+    /// DTSTART is always the first instance.  Without COUNT, evaluation skips
+    /// ahead to the period containing `skip_to`.
+    fn for_each_instance(
+        &self,
+        dtstart: &IcalTime,
+        skip_to: Option<NaiveDateTime>,
+        mut visit: impl FnMut(NaiveDateTime) -> bool,
+    ) {
+        let start = dtstart.local;
+        if !visit(start) {
+            return;
+        }
+        let mut emitted = 1;
+        if self.count.is_some_and(|c| emitted >= c) {
+            return;
+        }
+
+        let interval = i64::from(self.interval);
+        let mut period = match (self.count, skip_to) {
+            (None, Some(target)) if target > start => {
+                let whole = self.periods_between(start, target) / interval * interval;
+                (whole - interval).max(0)
+            }
+            _ => 0,
+        };
+
+        for _ in 0..MAX_PERIODS {
+            // Periods beyond the last representable date end the rule
+            let Some(instances) = self.period_instances(start, period) else {
+                return;
+            };
+            for candidate in instances {
+                if candidate <= start {
+                    continue;
+                }
+                if self.is_past_until(candidate, dtstart.zone) {
+                    return;
+                }
+                emitted += 1;
+                if !visit(candidate) || self.count.is_some_and(|c| emitted >= c) {
+                    return;
+                }
+            }
+            period += interval;
+        }
+    }
+
+    fn is_past_until(&self, candidate: NaiveDateTime, zone: Zone) -> bool {
+        match self.until {
+            None => false,
+            Some(until) if until.is_date => candidate.date() > until.local.date(),
+            Some(until) if until.zone == Zone::Utc => zone.to_utc(candidate) > until.utc(),
+            Some(until) => candidate > until.local,
+        }
+    }
+
+    /// Number of whole periods between two local times.
+    fn periods_between(&self, from: NaiveDateTime, to: NaiveDateTime) -> i64 {
+        match self.freq {
+            Frequency::Yearly => i64::from(to.year() - from.year()),
+            Frequency::Monthly => {
+                i64::from(to.year() - from.year()) * 12 + i64::from(to.month())
+                    - i64::from(from.month())
+            }
+            Frequency::Weekly => {
+                (self.week_of(to.date()) - self.week_of(from.date())).num_days() / 7
+            }
+            Frequency::Daily => (to.date() - from.date()).num_days(),
+            Frequency::Hourly => (to - from).num_hours(),
+            Frequency::Minutely => (to - from).num_minutes(),
+            Frequency::Secondly => (to - from).num_seconds(),
+        }
+    }
+
+    /// First day of the week containing `date`, honouring WKST.
+    fn week_of(&self, date: NaiveDate) -> NaiveDate {
+        let offset = (7 + date.weekday().num_days_from_monday()
+            - self.week_start.num_days_from_monday())
+            % 7;
+        date - Duration::days(i64::from(offset))
+    }
+
+    /// All instances of the `index`-th period after DTSTART, sorted and
+    /// with BYSETPOS applied; `None` once the period lies beyond the dates
+    /// chrono can represent.
+    fn period_instances(&self, start: NaiveDateTime, index: i64) -> Option<Vec<NaiveDateTime>> {
+        let mut instances: Vec<NaiveDateTime> = match self.freq {
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+                let step = match self.freq {
+                    Frequency::Hourly => Duration::try_hours(index),
+                    Frequency::Minutely => Duration::try_minutes(index),
+                    _ => Duration::try_seconds(index),
+                };
+                let base = start.checked_add_signed(step?)?;
+                if !self.date_matches_limits(base.date()) {
+                    return Some(Vec::new());
+                }
+                self.sub_daily_times(base.time())
+                    .into_iter()
+                    .map(|t| base.date().and_time(t))
+                    .collect()
+            }
+            _ => {
+                let times = self.times_of_day(start.time());
+                self.period_dates(start.date(), index)?
+                    .into_iter()
+                    .flat_map(|d| times.iter().map(move |t| d.and_time(*t)))
+                    .collect()
+            }
+        };
+        instances.sort();
+        instances.dedup();
+
+        if self.by_set_pos.is_empty() {
+            return Some(instances);
+        }
+        let len = instances.len() as i32;
+        let mut selected: Vec<NaiveDateTime> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| {
+                let idx = if pos > 0 { pos - 1 } else { len + pos };
+                (0..len).contains(&idx).then(|| instances[idx as usize])
+            })
+            .collect();
+        selected.sort();
+        selected.dedup();
+        Some(selected)
+    }
+
+    /// Dates of a daily-or-coarser period; `None` when it lies beyond the
+    /// representable dates.
+    fn period_dates(&self, start: NaiveDate, index: i64) -> Option<Vec<NaiveDate>> {
+        Some(match self.freq {
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(index).ok()?)?;
+                NaiveDate::from_ymd_opt(year, 12, 31)?;
+                self.year_dates(start, year)
+            }
+            Frequency::Monthly => {
+                let total = i64::from(start.year()) * 12 + i64::from(start.month0()) + index;
+                let year = i32::try_from(total.div_euclid(12)).ok()?;
+                let month = total.rem_euclid(12) as u32 + 1;
+                NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?;
+                if !self.by_month.is_empty() && !self.by_month.contains(&month) {
+                    return Some(Vec::new());
+                }
+                self.month_dates(year, month, start.day())
+            }
+            Frequency::Weekly => {
+                let week = self
+                    .week_of(start)
+                    .checked_add_signed(Duration::try_weeks(index)?)?;
+                week.checked_add_signed(Duration::days(6))?;
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|(_, wd)| *wd).collect()
+                };
+                weekdays
+                    .into_iter()
+                    .map(|wd| {
+                        let offset = (7 + wd.num_days_from_monday()
+                            - self.week_start.num_days_from_monday())
+                            % 7;
+                        week + Duration::days(i64::from(offset))
+                    })
+                    .filter(|d| self.by_month.is_empty() || self.by_month.contains(&d.month()))
+                    .collect()
+            }
+            _ => {
+                let date = start.checked_add_signed(Duration::try_days(index)?)?;
+                if self.date_matches_limits(date) {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+        })
+    }
+
+    /// BYMONTH/BYMONTHDAY/BYDAY as limits (daily and finer frequencies).
+    fn date_matches_limits(&self, date: NaiveDate) -> bool {
+        (self.by_month.is_empty() || self.by_month.contains(&date.month()))
+            && (self.by_month_day.is_empty()
+                || self.by_month_day.iter().any(|&d| {
+                    resolve_day(d, days_in_month(date.year(), date.month())) == Some(date.day())
+                }))
+            && (self.by_day.is_empty() || self.by_day.iter().any(|(_, wd)| *wd == date.weekday()))
+    }
+
+    fn year_dates(&self, start: NaiveDate, year: i32) -> Vec<NaiveDate> {
+        let Some(jan1) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+            return Vec::new();
+        };
+        let year_len = if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+            366
+        } else {
+            365
+        };
+
+        if !self.by_year_day.is_empty() {
+            return self
+                .by_year_day
+                .iter()
+                .filter_map(|&d| resolve_day(d, year_len))
+                .map(|d| jan1 + Duration::days(i64::from(d) - 1))
+                .filter(|d| self.by_month.is_empty() || self.by_month.contains(&d.month()))
+                .filter(|d| {
+                    self.by_day.is_empty() || self.by_day.iter().any(|(_, wd)| *wd == d.weekday())
+                })
+                .collect();
+        }
+
+        if self.by_month.is_empty() && self.by_month_day.is_empty() && !self.by_day.is_empty() {
+            let all: Vec<NaiveDate> = (0..i64::from(year_len))
+                .map(|i| jan1 + Duration::days(i))
+                .collect();
+            return self.select_weekdays(&all);
+        }
+
+        let months: Vec<u32> = if !self.by_month.is_empty() {
+            self.by_month.clone()
+        } else if !self.by_month_day.is_empty() || !self.by_day.is_empty() {
+            (1..=12).collect()
+        } else {
+            vec![start.month()]
+        };
+        months
+            .into_iter()
+            .flat_map(|m| self.month_dates(year, m, start.day()))
+            .collect()
+    }
+
+    fn month_dates(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        let len = days_in_month(year, month);
+        if len == 0 {
+            return Vec::new();
+        }
+        let date = |d: u32| NaiveDate::from_ymd_opt(year, month, d);
+
+        if !self.by_month_day.is_empty() {
+            return self
+                .by_month_day
+                .iter()
+                .filter_map(|&d| resolve_day(d, len).and_then(date))
+                .filter(|d| {
+                    self.by_day.is_empty() || self.by_day.iter().any(|(_, wd)| *wd == d.weekday())
+                })
+                .collect();
+        }
+        if !self.by_day.is_empty() {
+            let all: Vec<NaiveDate> = (1..=len).filter_map(date).collect();
+            return self.select_weekdays(&all);
+        }
+        date(default_day).into_iter().collect()
+    }
+
+    /// Applies BYDAY (with ordinals relative to `dates`) to a month or year.
+    fn select_weekdays(&self, dates: &[NaiveDate]) -> Vec<NaiveDate> {
+        let mut out = Vec::new();
+        for &(ordinal, weekday) in &self.by_day {
+            let matching: Vec<NaiveDate> = dates
+                .iter()
+                .copied()
+                .filter(|d| d.weekday() == weekday)
+                .collect();
+            if ordinal == 0 {
+                out.extend(matching);
+            } else {
+                let len = matching.len() as i32;
+                let idx = if ordinal > 0 {
+                    ordinal - 1
+                } else {
+                    len + ordinal
+                };
+                if (0..len).contains(&idx) {
+                    out.push(matching[idx as usize]);
+                }
+            }
+        }
+        out
+    }
+
+    /// Times of day for daily-or-coarser frequencies (BYHOUR etc. expand).
+    fn times_of_day(&self, base: NaiveTime) -> Vec<NaiveTime> {
+        let hours = non_empty_or(&self.by_hour, base.hour());
+        let minutes = non_empty_or(&self.by_minute, base.minute());
+        let seconds = non_empty_or(&self.by_second, base.second());
+        combine_times(&hours, &minutes, &seconds)
+    }
+
+    /// Times for sub-daily frequencies: coarser BY parts limit, finer expand.
+    fn sub_daily_times(&self, base: NaiveTime) -> Vec<NaiveTime> {
+        let limit = |by: &[u32], value: u32| by.is_empty() || by.contains(&value);
+        if !limit(&self.by_hour, base.hour()) {
+            return Vec::new();
+        }
+        let minutes = match self.freq {
+            Frequency::Hourly => non_empty_or(&self.by_minute, base.minute()),
+            _ if limit(&self.by_minute, base.minute()) => vec![base.minute()],
+            _ => return Vec::new(),
+        };
+        let seconds = match self.freq {
+            Frequency::Secondly if !limit(&self.by_second, base.second()) => return Vec::new(),
+            Frequency::Secondly => vec![base.second()],
+            _ => non_empty_or(&self.by_second, base.second()),
+        };
+        combine_times(&[base.hour()], &minutes, &seconds)
+    }
+}
+
+fn non_empty_or(values: &[u32], default: u32) -> Vec<u32> {
+    if values.is_empty() {
+        vec![default]
+    } else {
+        values.to_vec()
+    }
+}
+
+fn combine_times(hours: &[u32], minutes: &[u32], seconds: &[u32]) -> Vec<NaiveTime> {
+    hours
+        .iter()
+        .flat_map(|&h| {
+            minutes.iter().flat_map(move |&m| {
+                seconds
+                    .iter()
+                    .filter_map(move |&s| NaiveTime::from_hms_opt(h, m, s))
+            })
+        })
+        .collect()
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    (28..=31)
+        .rev()
+        .find(|&d| NaiveDate::from_ymd_opt(year, month, d).is_some())
+        .unwrap_or(0)
+}
+
+/// Resolves a possibly negative day number (`-1` = last) within `len` days.
+fn resolve_day(day: i32, len: u32) -> Option<u32> {
+    let len = len as i32;
+    let resolved = if day < 0 { len + day + 1 } else { day };
+    (1..=len).contains(&resolved).then_some(resolved as u32)
+}
+
+// ─── Recurrence sets ─────────────────────────────────────────────────
+
+/// One instance of a (possibly recurring) event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    /// Original start of the instance, i.e. its RECURRENCE-ID
+    pub recurrence_id: DateTime<Utc>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Index of the overriding component, if the instance was modified
+    override_index: Option<usize>,
+}
+
+/// A component overriding one instance (RECURRENCE-ID).
+#[derive(Debug, Clone)]
+struct Override {
+    component: IcalComponent,
+    recurrence_id: DateTime<Utc>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+/// The master component of an object with its evaluated recurrence data.
+#[derive(Debug, Clone)]
+struct Master {
+    component: IcalComponent,
+    dtstart: IcalTime,
+    duration: Duration,
+    rules: Vec<RecurrenceRule>,
+    rdates: Vec<IcalTime>,
+    exdates: HashSet<DateTime<Utc>>,
+}
+
+/// All instances of one iCalendar object: the master component, its
+/// recurrence rules and any overridden instances.
+#[derive(Debug, Clone)]
+pub struct RecurrenceSet {
+    master: Option<Master>,
+    overrides: Vec<Override>,
+    timezones: Vec<IcalComponent>,
+}
+
+/// Start and end of a component; a missing end defaults to one day for
+/// DATE values and zero length otherwise.  A VTODO without DTSTART is
+/// placed at its DUE time.
+fn component_times(component: &IcalComponent) -> Option<(IcalTime, Duration)> {
+    let start = component
+        .property("DTSTART")
+        .or_else(|| component.property("DUE"))
+        .and_then(IcalTime::from_property)?;
+    let end = component
+        .property("DTEND")
+        .or_else(|| component.property("DUE"))
+        .and_then(IcalTime::from_property);
+    let duration = match end {
+        Some(end) => end.utc() - start.utc(),
+        None => component
+            .value("DURATION")
+            .and_then(parse_duration)
+            .unwrap_or_else(|| {
+                if start.is_date {
+                    Duration::days(1)
+                } else {
+                    Duration::zero()
+                }
+            }),
+    };
+    Some((
+        start,
+        duration.clamp(Duration::zero(), Duration::days(MAX_DURATION_DAYS)),
+    ))
+}
+
+/// Whether an instance overlaps `[range_start, range_end)` (RFC 4791 §9.9).
+fn overlaps_range(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    range_start: DateTime<Utc>,
+    range_end: DateTime<Utc>,
+) -> bool {
+    if end > start {
+        start < range_end && end > range_start
+    } else {
+        start >= range_start && start < range_end
+    }
+}
+
+impl RecurrenceSet {
+    /// Builds the set from an iCalendar object.  The first component that is
+    /// not a VTIMEZONE decides the kind (VEVENT, VTODO, …) of the set.
+    pub fn from_ical(ical_data: &str) -> Self {
+        let mut master = None;
+        let mut overrides = Vec::new();
+        let mut timezones = Vec::new();
+
+        let calendars = IcalComponent::parse(ical_data);
+        let components = calendars
+            .into_iter()
+            .flat_map(|c| {
+                if c.name == "VCALENDAR" {
+                    c.components
+                } else {
+                    vec![c]
+                }
+            })
+            .collect::<Vec<_>>();
+        let kind = components
+            .iter()
+            .find(|c| c.name != "VTIMEZONE")
+            .map(|c| c.name.clone());
+
+        for component in components {
+            if component.name == "VTIMEZONE" {
+                timezones.push(component);
+                continue;
+            }
+            if Some(&component.name) != kind.as_ref() {
+                continue;
+            }
+            let Some((dtstart, duration)) = component_times(&component) else {
+                continue;
+            };
+
+            if let Some(rid) = component
+                .property("RECURRENCE-ID")
+                .and_then(IcalTime::from_property)
+            {
+                let start = dtstart.utc();
+                overrides.push(Override {
+                    recurrence_id: rid.utc(),
+                    start,
+                    end: start + duration,
+                    component,
+                });
+            } else if master.is_none() {
+                let rules = component
+                    .properties
+                    .iter()
+                    .filter(|p| p.name == "RRULE")
+                    .filter_map(|p| RecurrenceRule::parse(&p.value).ok())
+                    .collect();
+                let list = |name: &str| -> Vec<IcalTime> {
+                    component
+                        .properties
+                        .iter()
+                        .filter(|p| p.name == name)
+                        .flat_map(IcalTime::list_from_property)
+                        .collect()
+                };
+                let rdates = list("RDATE");
+                let exdates = list("EXDATE").iter().map(IcalTime::utc).collect();
+                master = Some(Master {
+                    component,
+                    dtstart,
+                    duration,
+                    rules,
+                    rdates,
+                    exdates,
+                });
+            }
+        }
+
+        Self {
+            master,
+            overrides,
+            timezones,
+        }
+    }
+
+    /// Builds the set for a stored event, falling back to its indexed fields
+    /// when the stored object lacks them (events created through the REST
+    /// API carry a minimal VEVENT).  The master's UID is set to `uid`.
+    pub fn from_event(
+        ical_data: &str,
+        uid: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        rrule: Option<&str>,
+    ) -> Self {
+        let mut set = Self::from_ical(ical_data);
+        let master = set.master.get_or_insert_with(|| {
+            let mut component = IcalComponent {
+                name: "VEVENT".to_string(),
+                ..Default::default()
+            };
+            component
+                .properties
+                .push(IcalProperty::new("DTSTART", format_utc(start)));
+            component
+                .properties
+                .push(IcalProperty::new("DTEND", format_utc(end)));
+            Master {
+                component,
+                dtstart: IcalTime {
+                    local: start.naive_utc(),
+                    zone: Zone::Utc,
+                    is_date: false,
+                },
+                duration: (end - start).max(Duration::zero()),
+                rules: Vec::new(),
+                rdates: Vec::new(),
+                exdates: HashSet::new(),
+            }
+        });
+
+        if master.rules.is_empty()
+            && let Some(rrule) = rrule
+            && let Ok(rule) = RecurrenceRule::parse(rrule)
+        {
+            master.rules.push(rule);
+            master
+                .component
+                .set_property(IcalProperty::new("RRULE", rrule.to_string()));
+        }
+        master
+            .component
+            .set_property(IcalProperty::new("UID", uid.to_string()));
+        for o in &mut set.overrides {
+            o.component
+                .set_property(IcalProperty::new("UID", uid.to_string()));
+        }
+        set
+    }
+
+    /// Start of the master instance.
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        self.master.as_ref().map(|m| m.dtstart.utc())
+    }
+
+    /// End of the master instance.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.master.as_ref().map(|m| m.dtstart.utc() + m.duration)
+    }
+
+    /// Whether the master starts with a DATE value (all-day).
+    pub fn is_all_day(&self) -> bool {
+        self.master.as_ref().is_some_and(|m| m.dtstart.is_date)
+    }
+
+    /// The component describing an instance: its override, or the master.
+    pub fn instance_component(&self, occurrence: &Occurrence) -> Option<&IcalComponent> {
+        match occurrence.override_index {
+            Some(i) => self.overrides.get(i).map(|o| &o.component),
+            None => self.master.as_ref().map(|m| &m.component),
+        }
+    }
+
+    /// The master's RRULE value, if any.
+    pub fn rrule(&self) -> Option<&str> {
+        self.master.as_ref()?.component.value("RRULE")
+    }
+
+    /// Whether the object has more than one instance.
+    pub fn is_recurring(&self) -> bool {
+        self.master
+            .as_ref()
+            .is_some_and(|m| !m.rules.is_empty() || !m.rdates.is_empty())
+            || !self.overrides.is_empty()
+    }
+
+    /// Instances overlapping `[range_start, range_end)`, ordered by start and
+    /// capped at `limit`.
+    pub fn occurrences(
+        &self,
+        range_start: DateTime<Utc>,
+        range_end: DateTime<Utc>,
+        limit: usize,
+    ) -> Vec<Occurrence> {
+        let overridden: HashSet<DateTime<Utc>> =
+            self.overrides.iter().map(|o| o.recurrence_id).collect();
+        let mut out = Vec::new();
+
+        if let Some(master) = &self.master {
+            let zone = master.dtstart.zone;
+            let mut starts = Vec::new();
+            let collect = |local: NaiveDateTime, starts: &mut Vec<DateTime<Utc>>| {
+                let start = zone.to_utc(local);
+                if start >= range_end || starts.len() >= MAX_INSTANCES {
+                    return false;
+                }
+                if overlaps_range(start, start + master.duration, range_start, range_end) {
+                    starts.push(start);
+                }
+                true
+            };
+
+            if master.rules.is_empty() {
+                collect(master.dtstart.local, &mut starts);
+            } else {
+                let skip_to = zone.local_time(range_start - master.duration);
+                for rule in &master.rules {
+                    rule.for_each_instance(&master.dtstart, Some(skip_to), |local| {
+                        collect(local, &mut starts)
+                    });
+                }
+            }
+            for rdate in &master.rdates {
+                let start = rdate.utc();
+                if overlaps_range(start, start + master.duration, range_start, range_end) {
+                    starts.push(start);
+                }
+            }
+
+            starts.sort();
+            starts.dedup();
+            out.extend(
+                starts
+                    .into_iter()
+                    .filter(|s| !master.exdates.contains(s) && !overridden.contains(s))
+                    .map(|start| Occurrence {
+                        recurrence_id: start,
+                        start,
+                        end: start + master.duration,
+                        override_index: None,
+                    }),
+            );
+        }
+
+        for (i, o) in self.overrides.iter().enumerate() {
+            if overlaps_range(o.start, o.end, range_start, range_end) {
+                out.push(Occurrence {
+                    recurrence_id: o.recurrence_id,
+                    start: o.start,
+                    end: o.end,
+                    override_index: Some(i),
+                });
+            }
+        }
+
+        out.sort_by_key(|o| o.start);
+        out.truncate(limit);
+        out
+    }
+
+    /// Whether any instance overlaps `[range_start, range_end)`.
+    pub fn overlaps(&self, range_start: DateTime<Utc>, range_end: DateTime<Utc>) -> bool {
+        !self.occurrences(range_start, range_end, 1).is_empty()
+    }
+
+    /// Renders the instances overlapping the range as separate components in
+    /// UTC, without recurrence properties (CALDAV:expand, RFC 4791 §9.6.5).
+    pub fn expand(&self, range_start: DateTime<Utc>, range_end: DateTime<Utc>) -> String {
+        let recurring = self.is_recurring();
+        let mut out = String::from(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n",
+        );
+
+        for occurrence in self.occurrences(range_start, range_end, MAX_INSTANCES) {
+            let (component, is_date) = match occurrence.override_index {
+                Some(i) => {
+                    let o = &self.overrides[i].component;
+                    let is_date = o
+                        .property("DTSTART")
+                        .and_then(IcalTime::from_property)
+                        .is_some_and(|t| t.is_date);
+                    (o, is_date)
+                }
+                None => match &self.master {
+                    Some(m) => (&m.component, m.dtstart.is_date),
+                    None => continue,
+                },
+            };
+
+            let mut instance = IcalComponent {
+                name: component.name.clone(),
+                properties: component
+                    .properties
+                    .iter()
+                    .filter(|p| !RECURRENCE_PROPERTIES.contains(&p.name.as_str()))
+                    .cloned()
+                    .collect(),
+                components: component.components.clone(),
+            };
+            let time = |dt: DateTime<Utc>| {
+                if is_date {
+                    format!("VALUE=DATE:{}", dt.format("%Y%m%d"))
+                } else {
+                    format_utc(dt)
+                }
+            };
+            let mut push = |name: &str, value: String| {
+                let separator = if value.starts_with("VALUE=") {
+                    ';'
+                } else {
+                    ':'
+                };
+                instance.properties.push(IcalProperty {
+                    name: name.to_string(),
+                    params: Vec::new(),
+                    raw: format!("{}{}{}", name, separator, value),
+                    value,
+                });
+            };
+            // VTODOs end with DUE, and may have it without a DTSTART
+            let is_todo = component.name == "VTODO";
+            if component.property("DTSTART").is_none() {
+                push("DUE", time(occurrence.start));
+            } else {
+                push("DTSTART", time(occurrence.start));
+                if !is_todo {
+                    push("DTEND", time(occurrence.end));
+                } else if component.property("DUE").is_some()
+                    || component.property("DURATION").is_some()
+                {
+                    push("DUE", time(occurrence.end));
+                }
+            }
+            if recurring {
+                push("RECURRENCE-ID", time(occurrence.recurrence_id));
+            }
+            instance.write_to(&mut out);
+        }
+
+        out.push_str("END:VCALENDAR\r\n");
+        out
+    }
+
+    /// Renders the master component and only the overridden instances that
+    /// affect the range (CALDAV:limit-recurrence-set, RFC 4791 §9.6.6).
+    pub fn limit_recurrence_set(
+        &self,
+        range_start: DateTime<Utc>,
+        range_end: DateTime<Utc>,
+    ) -> String {
+        let mut out = String::from(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n",
+        );
+        for tz in &self.timezones {
+            tz.write_to(&mut out);
+        }
+        if let Some(master) = &self.master {
+            master.component.write_to(&mut out);
+        }
+        for o in &self.overrides {
+            let rid_in_range = o.recurrence_id >= range_start && o.recurrence_id < range_end;
+            if rid_in_range || overlaps_range(o.start, o.end, range_start, range_end) {
+                o.component.write_to(&mut out);
+            }
+        }
+        out.push_str("END:VCALENDAR\r\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn event(body: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:test\r\nSUMMARY:Test\r\n{}END:VEVENT\r\nEND:VCALENDAR\r\n",
+            body
+        )
+    }
+
+    fn starts(set: &RecurrenceSet, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        set.occurrences(from, to, MAX_INSTANCES)
+            .into_iter()
+            .map(|o| o.start)
+            .collect()
+    }
+
+    #[test]
+    fn weekly_event_matches_a_year_later() {
+        let set = RecurrenceSet::from_ical(&event(
+            "DTSTART:20250106T090000Z\r\nDTEND:20250106T100000Z\r\nRRULE:FREQ=WEEKLY\r\n",
+        ));
+        assert_eq!(
+            starts(&set, utc(2026, 1, 5, 0, 0), utc(2026, 1, 12, 0, 0)),
+            vec![utc(2026, 1, 5, 9, 0)]
+        );
+        assert!(!set.overlaps(utc(2026, 1, 6, 0, 0), utc(2026, 1, 12, 0, 0)));
+    }
+
+    #[test]
+    fn count_and_until_end_the_rule() {
+        let counted = RecurrenceSet::from_ical(&event(
+            "DTSTART:20250101T120000Z\r\nDURATION:PT1H\r\nRRULE:FREQ=DAILY;COUNT=3\r\n",
+        ));
+        assert_eq!(
+            starts(&counted, utc(2025, 1, 1, 0, 0), utc(2025, 2, 1, 0, 0)).len(),
+            3
+        );
+
+        let until = RecurrenceSet::from_ical(&event(
+            "DTSTART:20250101T120000Z\r\nRRULE:FREQ=DAILY;INTERVAL=2;UNTIL=20250105T120000Z\r\n",
+        ));
+        assert_eq!(
+            starts(&until, utc(2025, 1, 1, 0, 0), utc(2025, 2, 1, 0, 0)),
+            vec![
+                utc(2025, 1, 1, 12, 0),
+                utc(2025, 1, 3, 12, 0),
+                utc(2025, 1, 5, 12, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_last_friday_and_setpos() {
+        let last_friday = RecurrenceSet::from_ical(&event(
+            "DTSTART:20250131T150000Z\r\nRRULE:FREQ=MONTHLY;BYDAY=-1FR\r\n",
+        ));
+        assert_eq!(
+            starts(&last_friday, utc(2025, 2, 1, 0, 0), utc(2025, 4, 1, 0, 0)),
+            vec![utc(2025, 2, 28, 15, 0), utc(2025, 3, 28, 15, 0)]
+        );
+
+        let last_workday = RecurrenceSet::from_ical(&event(
+            "DTSTART:20250131T090000Z\r\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1\r\n",
+        ));
+        assert_eq!(
+            starts(&last_workday, utc(2025, 5, 1, 0, 0), utc(2025, 6, 1, 0, 0)),
+            vec![utc(2025, 5, 30, 9, 0)]
+        );
+    }
+
+    #[test]
+    fn yearly_on_leap_day_skips_other_years() {
+        let set = RecurrenceSet::from_ical(&event(
+            "DTSTART;VALUE=DATE:20240229\r\nRRULE:FREQ=YEARLY\r\n",
+        ));
+        assert_eq!(
+            starts(&set, utc(2025, 1, 1, 0, 0), utc(2029, 1, 1, 0, 0)),
+            vec![utc(2028, 2, 29, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn exdate_rdate_and_overrides() {
+        let data = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nUID:x\r\nSUMMARY:Standup\r\n\
+            DTSTART:20250106T090000Z\r\nDTEND:20250106T091500Z\r\n\
+            RRULE:FREQ=DAILY;COUNT=5\r\n\
+            EXDATE:20250107T090000Z\r\n\
+            RDATE:20250111T090000Z\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:x\r\nSUMMARY:Moved standup\r\n\
+            RECURRENCE-ID:20250108T090000Z\r\n\
+            DTSTART:20250108T140000Z\r\nDTEND:20250108T141500Z\r\n\
+            END:VEVENT\r\nEND:VCALENDAR\r\n";
+        let set = RecurrenceSet::from_ical(data);
+        let occurrences = set.occurrences(utc(2025, 1, 1, 0, 0), utc(2025, 2, 1, 0, 0), 100);
+        let got: Vec<_> = occurrences.iter().map(|o| o.start).collect();
+        assert_eq!(
+            got,
+            vec![
+                utc(2025, 1, 6, 9, 0),
+                utc(2025, 1, 8, 14, 0),
+                utc(2025, 1, 9, 9, 0),
+                utc(2025, 1, 10, 9, 0),
+                utc(2025, 1, 11, 9, 0),
+            ]
+        );
+        assert_eq!(occurrences[1].recurrence_id, utc(2025, 1, 8, 9, 0));
+
+        // The override moved the instance out of the morning
+        assert!(!set.overlaps(utc(2025, 1, 8, 8, 0), utc(2025, 1, 8, 10, 0)));
+    }
+
+    #[test]
+    fn rules_follow_the_dtstart_time_zone() {
+        let set = RecurrenceSet::from_ical(&event(
+            "DTSTART;TZID=/mozilla.org/20050126_1/Europe/Berlin:20250327T090000\r\n\
+             DTEND;TZID=Europe/Berlin:20250327T100000\r\nRRULE:FREQ=WEEKLY\r\n",
+        ));
+        // CET (UTC+1) before the switch, CEST (UTC+2) after
+        assert_eq!(
+            starts(&set, utc(2025, 3, 27, 0, 0), utc(2025, 4, 5, 0, 0)),
+            vec![utc(2025, 3, 27, 8, 0), utc(2025, 4, 3, 7, 0)]
+        );
+    }
+
+    #[test]
+    fn expand_renders_instances_in_utc() {
+        let set = RecurrenceSet::from_ical(&event(
+            "DTSTART:20250106T090000Z\r\nDTEND:20250106T100000Z\r\nRRULE:FREQ=WEEKLY;COUNT=10\r\n",
+        ));
+        let out = set.expand(utc(2025, 1, 10, 0, 0), utc(2025, 1, 25, 0, 0));
+        assert_eq!(out.matches("BEGIN:VEVENT").count(), 2);
+        assert!(out.contains("RECURRENCE-ID:20250113T090000Z\r\n"));
+        assert!(out.contains("DTSTART:20250120T090000Z\r\n"));
+        assert!(!out.contains("RRULE"));
+        assert!(out.contains("SUMMARY:Test\r\n"));
+    }
+
+    #[test]
+    fn limit_recurrence_set_keeps_relevant_overrides() {
+        let data = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nUID:x\r\nDTSTART:20250106T090000Z\r\nRRULE:FREQ=WEEKLY\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:x\r\nSUMMARY:Early\r\nRECURRENCE-ID:20250113T090000Z\r\n\
+            DTSTART:20250113T100000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:x\r\nSUMMARY:Late\r\nRECURRENCE-ID:20250310T090000Z\r\n\
+            DTSTART:20250310T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let out = RecurrenceSet::from_ical(data)
+            .limit_recurrence_set(utc(2025, 1, 1, 0, 0), utc(2025, 2, 1, 0, 0));
+        assert_eq!(out.matches("BEGIN:VEVENT").count(), 2);
+        assert!(out.contains("SUMMARY:Early"));
+        assert!(!out.contains("SUMMARY:Late"));
+    }
+
+    #[test]
+    fn from_event_falls_back_to_indexed_fields() {
+        let minimal =
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:other\nSUMMARY:x\nEND:VEVENT\nEND:VCALENDAR";
+        let set = RecurrenceSet::from_event(
+            minimal,
+            "real-uid",
+            utc(2025, 1, 1, 8, 0),
+            utc(2025, 1, 1, 9, 0),
+            Some("FREQ=DAILY"),
+        );
+        assert!(set.overlaps(utc(2025, 6, 1, 0, 0), utc(2025, 6, 2, 0, 0)));
+        let out = set.expand(utc(2025, 6, 1, 0, 0), utc(2025, 6, 2, 0, 0));
+        assert!(out.contains("UID:real-uid\r\n"));
+        assert!(out.contains("DTSTART:20250601T080000Z\r\n"));
+    }
+
+    #[test]
+    fn recurring_todo_expands_with_due() {
+        let set = RecurrenceSet::from_ical(
+            "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:t\r\nSUMMARY:Water plants\r\n\
+            DUE:20250106T180000Z\r\nRRULE:FREQ=DAILY;COUNT=3\r\nEND:VTODO\r\nEND:VCALENDAR\r\n",
+        );
+        assert_eq!(set.start(), Some(utc(2025, 1, 6, 18, 0)));
+        assert!(set.overlaps(utc(2025, 1, 8, 0, 0), utc(2025, 1, 9, 0, 0)));
+        assert!(!set.overlaps(utc(2025, 1, 9, 0, 0), utc(2025, 1, 10, 0, 0)));
+
+        let out = set.expand(utc(2025, 1, 7, 0, 0), utc(2025, 1, 8, 0, 0));
+        assert_eq!(out.matches("BEGIN:VTODO").count(), 1);
+        assert!(out.contains("DUE:20250107T180000Z\r\n"));
+        assert!(!out.contains("DTSTART") && !out.contains("DTEND"));
+    }
+
+    #[test]
+    fn parses_folded_lines_and_durations() {
+        let components = IcalComponent::parse(
+            "BEGIN:VEVENT\r\nDESCRIPTION:a long\r\n  description\r\nEND:VEVENT\r\n",
+        );
+        assert_eq!(
+            components[0].value("DESCRIPTION"),
+            Some("a long description")
+        );
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("-P1W"), Some(Duration::weeks(-1)));
+        assert_eq!(parse_duration("P1H"), None);
+        assert_eq!(parse_duration("P99999999999999999W"), None);
+        assert_eq!(parse_duration("P9999999999999D"), None);
+    }
+
+    #[test]
+    fn huge_interval_ends_the_rule_instead_of_overflowing() {
+        for freq in ["SECONDLY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY"] {
+            let set = RecurrenceSet::from_ical(&event(&format!(
+                "DTSTART:20250101T120000Z\r\nRRULE:FREQ={};INTERVAL=4294967295\r\n",
+                freq
+            )));
+            assert_eq!(
+                starts(&set, utc(2025, 1, 1, 0, 0), utc(2026, 1, 1, 0, 0)),
+                vec![utc(2025, 1, 1, 12, 0)],
+                "FREQ={}",
+                freq
+            );
+            assert!(!set.overlaps(utc(2030, 1, 1, 0, 0), utc(2031, 1, 1, 0, 0)));
+        }
+    }
+
+    #[test]
+    fn huge_duration_is_clamped() {
+        let set = RecurrenceSet::from_ical(&event(
+            "DTSTART:20250101T120000Z\r\nDURATION:P99999999W\r\nRRULE:FREQ=YEARLY\r\n",
+        ));
+        assert!(set.overlaps(utc(9000, 1, 1, 0, 0), utc(9000, 1, 2, 0, 0)));
+        assert!(
+            !set.expand(utc(2030, 1, 1, 0, 0), utc(2030, 1, 2, 0, 0))
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,791 @@
+//! Calendar scheduling between OxiCloud users (RFC 6638) and free-busy
+//! computation (RFC 4791 §7.10).
+//!
+//! A scheduling object is a calendar object with an ORGANIZER.  Calendar
+//! users are addressed by `mailto:` URIs matching account e-mail addresses.
+//! The functions here build the iTIP messages (RFC 5546) exchanged between
+//! organizer and attendees and merge attendee replies into the organizer's
+//! copy.  They edit the parsed [`IcalComponent`] tree, so properties the
+//! server does not interpret survive unchanged.
+
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+use crate::domain::services::recurrence_service::{
+    IcalComponent, IcalProperty, MAX_INSTANCES, RecurrenceSet,
+};
+
+/// Components that carry scheduling information.
+const SCHEDULING_COMPONENTS: &[&str] = &["VEVENT", "VTODO"];
+
+/// Properties kept in an attendee's REPLY besides their own ATTENDEE line.
+const REPLY_PROPERTIES: &[&str] = &[
+    "UID",
+    "RECURRENCE-ID",
+    "SEQUENCE",
+    "ORGANIZER",
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "DURATION",
+    "SUMMARY",
+];
+
+/// iTIP method of a scheduling message (RFC 5546 §1.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItipMethod {
+    Request,
+    Reply,
+    Cancel,
+}
+
+impl ItipMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Request => "REQUEST",
+            Self::Reply => "REPLY",
+            Self::Cancel => "CANCEL",
+        }
+    }
+}
+
+/// Returns the e-mail address of a `mailto:` calendar user address.
+/// Addresses compare case-insensitively.
+pub fn address_to_email(address: &str) -> Option<String> {
+    let address = address.trim();
+    let email = address
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| address[7..].trim().to_string())?;
+    email.contains('@').then_some(email)
+}
+
+/// The calendar user address of an account e-mail address.
+pub fn email_to_address(email: &str) -> String {
+    format!("mailto:{}", email)
+}
+
+/// Whether an iCalendar object has an ORGANIZER and so takes part in
+/// scheduling.  Scans the raw lines; no parsing needed.
+pub fn is_scheduling_object(ical_data: &str) -> bool {
+    ical_data.lines().any(|line| {
+        let bytes = line.as_bytes();
+        bytes.len() > 9
+            && bytes[..9].eq_ignore_ascii_case(b"ORGANIZER")
+            && matches!(bytes[9], b':' | b';')
+    })
+}
+
+/// Scheduling components of parsed calendars, in document order.
+fn scheduling_components(calendars: &[IcalComponent]) -> impl Iterator<Item = &IcalComponent> {
+    calendars
+        .iter()
+        .flat_map(|c| {
+            if c.name == "VCALENDAR" {
+                c.components.iter()
+            } else {
+                std::slice::from_ref(c).iter()
+            }
+        })
+        .filter(|c| SCHEDULING_COMPONENTS.contains(&c.name.as_str()))
+}
+
+/// Mutable counterpart of [`scheduling_components`].
+fn scheduling_components_mut(
+    calendars: &mut [IcalComponent],
+) -> impl Iterator<Item = &mut IcalComponent> {
+    calendars
+        .iter_mut()
+        .flat_map(|c| {
+            if c.name == "VCALENDAR" {
+                c.components.iter_mut()
+            } else {
+                std::slice::from_mut(c).iter_mut()
+            }
+        })
+        .filter(|c| SCHEDULING_COMPONENTS.contains(&c.name.as_str()))
+}
+
+/// Whether an ATTENDEE or ORGANIZER property addresses `email`.
+fn addresses(prop: &IcalProperty, email: &str) -> bool {
+    address_to_email(&prop.value).is_some_and(|e| e.eq_ignore_ascii_case(email))
+}
+
+/// An ATTENDEE of a scheduling object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attendee {
+    pub email: String,
+    /// PARTSTAT; `NEEDS-ACTION` when absent
+    pub partstat: String,
+    /// False when `SCHEDULE-AGENT` leaves delivery to the client (RFC 6638 §7.1)
+    pub server_scheduled: bool,
+}
+
+impl Attendee {
+    fn from_property(prop: &IcalProperty) -> Option<Self> {
+        Some(Self {
+            email: address_to_email(&prop.value)?,
+            partstat: prop
+                .param("PARTSTAT")
+                .unwrap_or("NEEDS-ACTION")
+                .to_ascii_uppercase(),
+            server_scheduled: prop
+                .param("SCHEDULE-AGENT")
+                .is_none_or(|agent| agent.eq_ignore_ascii_case("SERVER")),
+        })
+    }
+}
+
+/// Organizer and attendees of a calendar object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulingObject {
+    pub uid: String,
+    /// E-mail address of the ORGANIZER
+    pub organizer: String,
+    /// Attendees of the master and of every overridden instance, each once;
+    /// the master's PARTSTAT wins
+    pub attendees: Vec<Attendee>,
+    /// SEQUENCE of the master; organizers bump it on significant changes
+    pub sequence: i64,
+    /// Whether the master is STATUS:CANCELLED
+    pub cancelled: bool,
+}
+
+impl SchedulingObject {
+    /// Reads the scheduling data of an iCalendar object; `None` when it has
+    /// no ORGANIZER.
+    pub fn parse(ical_data: &str) -> Option<Self> {
+        let calendars = IcalComponent::parse(ical_data);
+        let mut components: Vec<&IcalComponent> = scheduling_components(&calendars).collect();
+        components.sort_by_key(|c| c.property("RECURRENCE-ID").is_some());
+
+        let organizer = components
+            .iter()
+            .find_map(|c| c.property("ORGANIZER"))
+            .and_then(|p| address_to_email(&p.value))?;
+        let master = components.first()?;
+        let uid = master.value("UID").unwrap_or_default().trim().to_string();
+        let sequence = master
+            .value("SEQUENCE")
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(0);
+        let cancelled = master
+            .value("STATUS")
+            .is_some_and(|s| s.trim().eq_ignore_ascii_case("CANCELLED"));
+
+        let mut attendees: Vec<Attendee> = Vec::new();
+        for prop in components
+            .iter()
+            .flat_map(|c| c.properties.iter().filter(|p| p.name == "ATTENDEE"))
+        {
+            if let Some(attendee) = Attendee::from_property(prop)
+                && !attendees
+                    .iter()
+                    .any(|a| a.email.eq_ignore_ascii_case(&attendee.email))
+            {
+                attendees.push(attendee);
+            }
+        }
+
+        Some(Self {
+            uid,
+            organizer,
+            attendees,
+            sequence,
+            cancelled,
+        })
+    }
+
+    /// Whether `email` is the organizer.
+    pub fn is_organizer(&self, email: &str) -> bool {
+        self.organizer.eq_ignore_ascii_case(email)
+    }
+
+    /// The attendee with the given e-mail address.
+    pub fn attendee(&self, email: &str) -> Option<&Attendee> {
+        self.attendees
+            .iter()
+            .find(|a| a.email.eq_ignore_ascii_case(email))
+    }
+
+    /// Attendees the server sends invitations to: everyone except the
+    /// organizer and those scheduled by their client.
+    pub fn recipients(&self) -> impl Iterator<Item = &str> {
+        self.attendees
+            .iter()
+            .filter(|a| a.server_scheduled && !self.is_organizer(&a.email))
+            .map(|a| a.email.as_str())
+    }
+}
+
+/// Whether a stored object was organized by `organizer_email`.  Only that
+/// organizer may replace or cancel it: anyone can save an event with a
+/// known UID, so a UID match alone proves nothing.
+pub fn is_organized_by(ical_data: &str, organizer_email: &str) -> bool {
+    SchedulingObject::parse(ical_data).is_some_and(|o| o.is_organizer(organizer_email))
+}
+
+/// Renders components inside a fresh VCALENDAR, optionally with a METHOD.
+fn render(calendars: &[IcalComponent], method: Option<ItipMethod>) -> String {
+    let mut out = String::from(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n",
+    );
+    if let Some(method) = method {
+        let _ = write!(out, "METHOD:{}\r\n", method.as_str());
+    }
+    for component in calendars.iter().flat_map(|c| {
+        if c.name == "VCALENDAR" {
+            c.components.iter()
+        } else {
+            std::slice::from_ref(c).iter()
+        }
+    }) {
+        component.write_to(&mut out);
+    }
+    out.push_str("END:VCALENDAR\r\n");
+    out
+}
+
+/// Copies the organizer's object for an attendee, without the organizer's
+/// alarms.  With `cancelled`, every component is marked STATUS:CANCELLED.
+fn attendee_components(ical_data: &str, cancelled: bool) -> Vec<IcalComponent> {
+    let mut calendars = IcalComponent::parse(ical_data);
+    for component in scheduling_components_mut(&mut calendars) {
+        component.components.retain(|c| c.name != "VALARM");
+        if cancelled {
+            component.set_property(IcalProperty::new("STATUS", "CANCELLED".to_string()));
+        }
+    }
+    calendars
+}
+
+/// The METHOD:REQUEST invitation sent to attendees.
+pub fn request_message(ical_data: &str) -> String {
+    render(
+        &attendee_components(ical_data, false),
+        Some(ItipMethod::Request),
+    )
+}
+
+/// The METHOD:CANCEL message sent to attendees removed from, or left in, a
+/// cancelled object.
+pub fn cancel_message(ical_data: &str) -> String {
+    render(
+        &attendee_components(ical_data, true),
+        Some(ItipMethod::Cancel),
+    )
+}
+
+/// The attendee's calendar copy of an organizer's object.
+pub fn attendee_copy(ical_data: &str) -> String {
+    render(&attendee_components(ical_data, false), None)
+}
+
+/// An attendee's calendar copy marked cancelled.
+pub fn cancelled_copy(ical_data: &str) -> String {
+    render(&attendee_components(ical_data, true), None)
+}
+
+/// The METHOD:REPLY an attendee sends to the organizer: every component the
+/// attendee takes part in, reduced to its identifying properties and the
+/// attendee's own ATTENDEE line.  `None` when `email` is not an attendee.
+pub fn reply_message(ical_data: &str, email: &str) -> Option<String> {
+    let dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut replies = Vec::new();
+    for calendar in IcalComponent::parse(ical_data) {
+        let components = if calendar.name == "VCALENDAR" {
+            calendar.components
+        } else {
+            vec![calendar]
+        };
+        for component in components {
+            if component.name == "VTIMEZONE" {
+                replies.push(component);
+                continue;
+            }
+            if !SCHEDULING_COMPONENTS.contains(&component.name.as_str()) {
+                continue;
+            }
+            let Some(attendee) = component
+                .properties
+                .iter()
+                .find(|p| p.name == "ATTENDEE" && addresses(p, email))
+                .cloned()
+            else {
+                continue;
+            };
+            let mut reply = IcalComponent {
+                name: component.name.clone(),
+                properties: component
+                    .properties
+                    .into_iter()
+                    .filter(|p| REPLY_PROPERTIES.contains(&p.name.as_str()))
+                    .collect(),
+                components: Vec::new(),
+            };
+            reply.set_property(IcalProperty::new("DTSTAMP", dtstamp.clone()));
+            reply.properties.push(attendee);
+            replies.push(reply);
+        }
+    }
+
+    if !replies.iter().any(|c| c.name != "VTIMEZONE") {
+        return None;
+    }
+    Some(render(&replies, Some(ItipMethod::Reply)))
+}
+
+/// An attendee's PARTSTAT in each component, keyed by RECURRENCE-ID
+/// (`None` for the master).
+pub fn partstats(ical_data: &str, email: &str) -> Vec<(Option<String>, String)> {
+    let calendars = IcalComponent::parse(ical_data);
+    scheduling_components(&calendars)
+        .filter_map(|c| {
+            let attendee = c
+                .properties
+                .iter()
+                .find(|p| p.name == "ATTENDEE" && addresses(p, email))?;
+            Some((
+                c.value("RECURRENCE-ID").map(str::to_string),
+                attendee
+                    .param("PARTSTAT")
+                    .unwrap_or("NEEDS-ACTION")
+                    .to_ascii_uppercase(),
+            ))
+        })
+        .collect()
+}
+
+/// Sets an attendee's PARTSTAT in the components chosen by `partstat_for`,
+/// which maps a RECURRENCE-ID to the new value.  Returns the updated object,
+/// or `None` when nothing changed.
+fn update_partstats(
+    ical_data: &str,
+    email: &str,
+    partstat_for: impl Fn(Option<&str>) -> Option<String>,
+) -> Option<String> {
+    let mut calendars = IcalComponent::parse(ical_data);
+    let mut changed = false;
+    for component in scheduling_components_mut(&mut calendars) {
+        let Some(partstat) = partstat_for(component.value("RECURRENCE-ID")) else {
+            continue;
+        };
+        for attendee in component
+            .properties
+            .iter_mut()
+            .filter(|p| p.name == "ATTENDEE" && addresses(p, email))
+        {
+            if attendee.param("PARTSTAT") != Some(partstat.as_str()) {
+                attendee.set_param("PARTSTAT", &partstat);
+                changed = true;
+            }
+        }
+    }
+
+    changed.then(|| {
+        let mut out = String::new();
+        for calendar in &calendars {
+            calendar.write_to(&mut out);
+        }
+        out
+    })
+}
+
+/// Copies an attendee's PARTSTATs from `source` (their copy or REPLY) into
+/// `target` (the organizer's copy), matching components by RECURRENCE-ID.
+/// Instances the organizer never overrode are left alone.  Returns the
+/// updated object, or `None` when nothing changed.
+pub fn apply_partstats(target: &str, source: &str, email: &str) -> Option<String> {
+    let replied = partstats(source, email);
+    if replied.is_empty() {
+        return None;
+    }
+    update_partstats(target, email, |recurrence_id| {
+        replied
+            .iter()
+            .find(|(id, _)| id.as_deref() == recurrence_id)
+            .map(|(_, partstat)| partstat.clone())
+    })
+}
+
+/// Sets an attendee's PARTSTAT in every component they take part in.
+/// Returns the updated object, or `None` when nothing changed.
+pub fn set_partstat(ical_data: &str, email: &str, partstat: &str) -> Option<String> {
+    update_partstats(ical_data, email, |_| Some(partstat.to_string()))
+}
+
+// ─── Free-busy ───────────────────────────────────────────────────────
+
+/// FBTYPE of a busy period (RFC 5545 §3.2.9).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FreeBusyType {
+    Busy,
+    BusyTentative,
+}
+
+impl FreeBusyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Busy => "BUSY",
+            Self::BusyTentative => "BUSY-TENTATIVE",
+        }
+    }
+}
+
+/// A busy interval in UTC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusyPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub kind: FreeBusyType,
+}
+
+/// Busy time of an event's instances within `[range_start, range_end)`,
+/// clipped to the range.  Transparent and cancelled instances are free and
+/// tentative ones BUSY-TENTATIVE (RFC 4791 §7.10).
+pub fn busy_periods(
+    set: &RecurrenceSet,
+    range_start: DateTime<Utc>,
+    range_end: DateTime<Utc>,
+) -> Vec<BusyPeriod> {
+    set.occurrences(range_start, range_end, MAX_INSTANCES)
+        .iter()
+        .filter_map(|occurrence| {
+            let component = set.instance_component(occurrence)?;
+            let transparent = component
+                .value("TRANSP")
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case("TRANSPARENT"));
+            let kind = match component.value("STATUS").map(str::trim) {
+                Some(s) if s.eq_ignore_ascii_case("CANCELLED") => return None,
+                Some(s) if s.eq_ignore_ascii_case("TENTATIVE") => FreeBusyType::BusyTentative,
+                _ => FreeBusyType::Busy,
+            };
+            let start = occurrence.start.max(range_start);
+            let end = occurrence.end.min(range_end);
+            (!transparent && end > start).then_some(BusyPeriod { start, end, kind })
+        })
+        .collect()
+}
+
+/// Merges overlapping and adjacent periods of the same type, ordered by start.
+pub fn merge_busy_periods(mut periods: Vec<BusyPeriod>) -> Vec<BusyPeriod> {
+    periods.sort_by_key(|p| (p.kind, p.start));
+    let mut merged: Vec<BusyPeriod> = Vec::with_capacity(periods.len());
+    for period in periods {
+        match merged.last_mut() {
+            Some(last) if last.kind == period.kind && period.start <= last.end => {
+                last.end = last.end.max(period.end);
+            }
+            _ => merged.push(period),
+        }
+    }
+    merged.sort_by_key(|p| p.start);
+    merged
+}
+
+fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Writes a VFREEBUSY component; `extra` holds pre-rendered content lines.
+fn write_vfreebusy(
+    out: &mut String,
+    extra: &[String],
+    periods: &[BusyPeriod],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    let _ = write!(
+        out,
+        "BEGIN:VFREEBUSY\r\nDTSTAMP:{}\r\nDTSTART:{}\r\nDTEND:{}\r\n",
+        format_utc(Utc::now()),
+        format_utc(start),
+        format_utc(end),
+    );
+    for line in extra {
+        let _ = write!(out, "{}\r\n", line);
+    }
+    for period in periods {
+        let _ = write!(
+            out,
+            "FREEBUSY;FBTYPE={}:{}/{}\r\n",
+            period.kind.as_str(),
+            format_utc(period.start),
+            format_utc(period.end),
+        );
+    }
+    out.push_str("END:VFREEBUSY\r\n");
+}
+
+/// The VCALENDAR answering a `free-busy-query` REPORT (RFC 4791 §7.10).
+pub fn freebusy_calendar(
+    periods: &[BusyPeriod],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> String {
+    let mut out = String::from(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n",
+    );
+    write_vfreebusy(&mut out, &[], periods, start, end);
+    out.push_str("END:VCALENDAR\r\n");
+    out
+}
+
+/// A VFREEBUSY request POSTed to a scheduling outbox (RFC 6638 §5).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeBusyRequest {
+    pub uid: Option<String>,
+    /// E-mail address of the ORGANIZER
+    pub organizer: String,
+    /// Calendar user addresses of the attendees, as sent
+    pub attendees: Vec<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl FreeBusyRequest {
+    /// Parses a METHOD:REQUEST object holding a VFREEBUSY with an ORGANIZER,
+    /// attendees and a non-empty DTSTART/DTEND range.
+    pub fn parse(ical_data: &str) -> Option<Self> {
+        let calendar = IcalComponent::parse(ical_data)
+            .into_iter()
+            .find(|c| c.name == "VCALENDAR")?;
+        if !calendar
+            .value("METHOD")
+            .is_some_and(|m| m.trim().eq_ignore_ascii_case("REQUEST"))
+        {
+            return None;
+        }
+        let freebusy = calendar.components.iter().find(|c| c.name == "VFREEBUSY")?;
+        let start = freebusy.time("DTSTART")?;
+        let end = freebusy.time("DTEND")?;
+        let attendees: Vec<String> = freebusy
+            .properties
+            .iter()
+            .filter(|p| p.name == "ATTENDEE")
+            .map(|p| p.value.trim().to_string())
+            .collect();
+        if end <= start || attendees.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            uid: freebusy.value("UID").map(|u| u.trim().to_string()),
+            organizer: address_to_email(freebusy.value("ORGANIZER")?)?,
+            attendees,
+            start,
+            end,
+        })
+    }
+
+    /// The METHOD:REPLY carrying one attendee's busy time.
+    pub fn reply(&self, attendee: &str, periods: &[BusyPeriod]) -> String {
+        let mut extra = vec![
+            format!("ORGANIZER:{}", email_to_address(&self.organizer)),
+            format!("ATTENDEE:{}", attendee),
+        ];
+        if let Some(uid) = &self.uid {
+            extra.push(format!("UID:{}", uid));
+        }
+        let mut out = String::from(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\nMETHOD:REPLY\r\n",
+        );
+        write_vfreebusy(&mut out, &extra, periods, self.start, self.end);
+        out.push_str("END:VCALENDAR\r\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    const MEETING: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nX-WR-CALNAME:Work\r\n\
+        BEGIN:VEVENT\r\nUID:meeting-1\r\nDTSTART:20250310T090000Z\r\nDTEND:20250310T100000Z\r\n\
+        SUMMARY:Planning\r\nORGANIZER;CN=Ann:mailto:Ann@example.com\r\n\
+        ATTENDEE;PARTSTAT=ACCEPTED:mailto:ann@example.com\r\n\
+        ATTENDEE;CN=Bob;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:bob@example.com\r\n\
+        ATTENDEE;SCHEDULE-AGENT=CLIENT:mailto:carol@example.com\r\n\
+        BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\n\
+        END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn calendar_user_addresses() {
+        assert_eq!(
+            address_to_email(" MAILTO:Bob@Example.com"),
+            Some("Bob@Example.com".to_string())
+        );
+        assert_eq!(address_to_email("https://example.com/bob"), None);
+        assert_eq!(address_to_email("mailto:"), None);
+        assert_eq!(
+            email_to_address("bob@example.com"),
+            "mailto:bob@example.com"
+        );
+    }
+
+    #[test]
+    fn parses_organizer_and_recipients() {
+        assert!(is_scheduling_object(MEETING));
+        assert!(!is_scheduling_object(
+            "BEGIN:VEVENT\r\nUID:x\r\nX-ORGANIZER:me\r\nEND:VEVENT\r\n"
+        ));
+
+        let object = SchedulingObject::parse(MEETING).unwrap();
+        assert_eq!(object.uid, "meeting-1");
+        assert_eq!(object.sequence, 0);
+        assert!(!object.cancelled);
+        assert!(object.is_organizer("ann@example.com"));
+        assert_eq!(
+            object.attendee("bob@example.com").unwrap().partstat,
+            "NEEDS-ACTION"
+        );
+        // The organizer and client-scheduled attendees get no invitation
+        assert_eq!(
+            object.recipients().collect::<Vec<_>>(),
+            vec!["bob@example.com"]
+        );
+    }
+
+    #[test]
+    fn only_the_organizer_owns_a_uid() {
+        assert!(is_organized_by(MEETING, "ANN@example.com"));
+
+        // Someone else reusing the UID as organizer of their own copy
+        let forged = MEETING.replace("mailto:Ann@example.com", "mailto:mallory@example.com");
+        assert!(!is_organized_by(MEETING, "mallory@example.com"));
+        assert!(!is_organized_by(&forged, "ann@example.com"));
+
+        // A personal event with the same UID belongs to nobody else
+        let personal = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:meeting-1\r\n\
+            DTSTART:20250310T090000Z\r\nSUMMARY:Mine\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert!(!is_organized_by(personal, "mallory@example.com"));
+    }
+
+    #[test]
+    fn request_and_cancel_drop_alarms() {
+        let request = request_message(MEETING);
+        assert!(request.contains("METHOD:REQUEST\r\n"));
+        assert!(!request.contains("VALARM"));
+        assert!(!request.contains("X-WR-CALNAME"));
+
+        let cancel = cancel_message(MEETING);
+        assert!(cancel.contains("METHOD:CANCEL\r\n"));
+        assert!(cancel.contains("STATUS:CANCELLED\r\n"));
+
+        let copy = attendee_copy(MEETING);
+        assert!(!copy.contains("METHOD:"));
+        assert!(copy.contains("UID:meeting-1\r\n"));
+    }
+
+    #[test]
+    fn reply_carries_only_the_replying_attendee() {
+        let accepted = set_partstat(MEETING, "bob@example.com", "ACCEPTED").unwrap();
+        assert!(
+            accepted.contains("ATTENDEE;CN=Bob;PARTSTAT=ACCEPTED;RSVP=TRUE:mailto:bob@example.com")
+        );
+        assert!(set_partstat(&accepted, "bob@example.com", "ACCEPTED").is_none());
+
+        let reply = reply_message(&accepted, "bob@example.com").unwrap();
+        assert!(reply.contains("METHOD:REPLY\r\n"));
+        assert!(reply.contains("PARTSTAT=ACCEPTED"));
+        assert!(reply.contains("ORGANIZER;CN=Ann:mailto:Ann@example.com"));
+        assert!(!reply.contains("ann@example.com"));
+        assert!(reply_message(MEETING, "dave@example.com").is_none());
+    }
+
+    #[test]
+    fn reply_updates_the_organizer_copy() {
+        let reply = reply_message(
+            &set_partstat(MEETING, "bob@example.com", "DECLINED").unwrap(),
+            "bob@example.com",
+        )
+        .unwrap();
+
+        let updated = apply_partstats(MEETING, &reply, "bob@example.com").unwrap();
+        assert_eq!(
+            SchedulingObject::parse(&updated)
+                .unwrap()
+                .attendee("bob@example.com")
+                .unwrap()
+                .partstat,
+            "DECLINED"
+        );
+        // Everything else is kept, including the organizer's alarm
+        assert!(updated.contains("X-WR-CALNAME:Work"));
+        assert!(updated.contains("BEGIN:VALARM"));
+        assert!(apply_partstats(&updated, &reply, "bob@example.com").is_none());
+    }
+
+    #[test]
+    fn busy_time_skips_transparent_and_cancelled_instances() {
+        let set = RecurrenceSet::from_ical(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:daily\r\nDTSTART:20250310T090000Z\r\n\
+             DTEND:20250310T110000Z\r\nRRULE:FREQ=DAILY;COUNT=3\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:daily\r\nRECURRENCE-ID:20250311T090000Z\r\n\
+             DTSTART:20250311T090000Z\r\nDTEND:20250311T110000Z\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:daily\r\nRECURRENCE-ID:20250312T090000Z\r\n\
+             DTSTART:20250312T090000Z\r\nDTEND:20250312T110000Z\r\nSTATUS:TENTATIVE\r\nEND:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+        let periods = busy_periods(&set, utc(2025, 3, 10, 10), utc(2025, 3, 13, 0));
+        assert_eq!(
+            periods,
+            vec![
+                BusyPeriod {
+                    start: utc(2025, 3, 10, 10),
+                    end: utc(2025, 3, 10, 11),
+                    kind: FreeBusyType::Busy,
+                },
+                BusyPeriod {
+                    start: utc(2025, 3, 12, 9),
+                    end: utc(2025, 3, 12, 11),
+                    kind: FreeBusyType::BusyTentative,
+                },
+            ]
+        );
+
+        let transparent = RecurrenceSet::from_ical(
+            "BEGIN:VEVENT\r\nUID:t\r\nDTSTART:20250310T090000Z\r\nDTEND:20250310T100000Z\r\nTRANSP:TRANSPARENT\r\nEND:VEVENT\r\n",
+        );
+        assert!(busy_periods(&transparent, utc(2025, 3, 10, 0), utc(2025, 3, 11, 0)).is_empty());
+    }
+
+    #[test]
+    fn merges_overlapping_periods() {
+        let busy = |start, end| BusyPeriod {
+            start: utc(2025, 3, 10, start),
+            end: utc(2025, 3, 10, end),
+            kind: FreeBusyType::Busy,
+        };
+        let merged =
+            merge_busy_periods(vec![busy(13, 14), busy(9, 11), busy(10, 12), busy(12, 13)]);
+        assert_eq!(merged, vec![busy(9, 14)]);
+
+        let calendar = freebusy_calendar(&merged, utc(2025, 3, 10, 0), utc(2025, 3, 11, 0));
+        assert!(calendar.contains("FREEBUSY;FBTYPE=BUSY:20250310T090000Z/20250310T140000Z\r\n"));
+    }
+
+    #[test]
+    fn parses_outbox_freebusy_request() {
+        let request = FreeBusyRequest::parse(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nMETHOD:REQUEST\r\nBEGIN:VFREEBUSY\r\nUID:fb-1\r\n\
+             DTSTART:20250310T000000Z\r\nDTEND:20250311T000000Z\r\nORGANIZER:mailto:ann@example.com\r\n\
+             ATTENDEE:mailto:bob@example.com\r\nEND:VFREEBUSY\r\nEND:VCALENDAR\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.organizer, "ann@example.com");
+        assert_eq!(request.attendees, vec!["mailto:bob@example.com"]);
+
+        let reply = request.reply("mailto:bob@example.com", &[]);
+        assert!(reply.contains("METHOD:REPLY\r\n"));
+        assert!(reply.contains("ATTENDEE:mailto:bob@example.com\r\n"));
+        assert!(reply.contains("UID:fb-1\r\n"));
+
+        assert!(FreeBusyRequest::parse(&reply).is_none());
+    }
+}
@@ -10,8 +10,8 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::application::dtos::calendar_dto::{
-    CalendarDto, CalendarEventDto, CreateCalendarDto, CreateEventDto, CreateEventICalDto,
-    UpdateCalendarDto, UpdateEventDto,
+    CalendarChangesDto, CalendarDto, CalendarEventDto, CreateCalendarDto, CreateEventDto,
+    CreateEventICalDto, CreateTaskDto, UpdateCalendarDto, UpdateEventDto, UpdateTaskDto,
 };
 use crate::application::ports::calendar_ports::CalendarStoragePort;
 use crate::common::errors::{DomainError, ErrorKind};
@@ -402,6 +402,79 @@ impl CalendarStoragePort for CalendarStorageAdapter {
         Ok(CalendarEventDto::from(event))
     }
 
+    async fn find_event_by_uid(
+        &self,
+        calendar_id: &str,
+        ical_uid: &str,
+    ) -> Result<Option<CalendarEventDto>, DomainError> {
+        let uuid = Uuid::parse_str(calendar_id).map_err(|_| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "Event",
+                "Invalid calendar ID format",
+            )
+        })?;
+
+        let event = self
+            .event_repository
+            .find_event_by_ical_uid(&uuid, ical_uid)
+            .await?;
+        Ok(event.map(CalendarEventDto::from))
+    }
+
+    async fn update_event_ical(
+        &self,
+        event_id: &str,
+        ical_data: String,
+    ) -> Result<CalendarEventDto, DomainError> {
+        let uuid = Uuid::parse_str(event_id).map_err(|_| {
+            DomainError::new(ErrorKind::InvalidInput, "Event", "Invalid event ID format")
+        })?;
+
+        let mut event = self.event_repository.find_event_by_id(&uuid).await?;
+        event.update_ical_data(ical_data)?;
+
+        let updated = self.event_repository.update_event(event).await?;
+        Ok(CalendarEventDto::from(updated))
+    }
+
+    async fn create_task(&self, dto: CreateTaskDto) -> Result<CalendarEventDto, DomainError> {
+        let calendar_id = Uuid::parse_str(&dto.calendar_id).map_err(|_| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "Event",
+                "Invalid calendar ID format",
+            )
+        })?;
+
+        // Verify calendar exists
+        let _calendar = self
+            .calendar_repository
+            .find_calendar_by_id(&calendar_id)
+            .await?;
+
+        let task = CalendarEvent::new_task(calendar_id, dto.into())?;
+
+        let created = self.event_repository.create_event(task).await?;
+        Ok(CalendarEventDto::from(created))
+    }
+
+    async fn update_task(
+        &self,
+        task_id: &str,
+        update: UpdateTaskDto,
+    ) -> Result<CalendarEventDto, DomainError> {
+        let uuid = Uuid::parse_str(task_id).map_err(|_| {
+            DomainError::new(ErrorKind::InvalidInput, "Event", "Invalid task ID format")
+        })?;
+
+        let mut task = self.event_repository.find_event_by_id(&uuid).await?;
+        task.update_task(update.into())?;
+
+        let updated = self.event_repository.update_event(task).await?;
+        Ok(CalendarEventDto::from(updated))
+    }
+
     async fn list_events_by_calendar(
         &self,
         calendar_id: &str,
@@ -459,6 +532,30 @@ impl CalendarStoragePort for CalendarStorageAdapter {
             .await?;
         Ok(events.into_iter().map(CalendarEventDto::from).collect())
     }
+
+    async fn get_event_changes(
+        &self,
+        calendar_id: &str,
+        since: Option<i64>,
+    ) -> Result<Option<CalendarChangesDto>, DomainError> {
+        let uuid = Uuid::parse_str(calendar_id).map_err(|_| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "Calendar",
+                "Invalid calendar ID format",
+            )
+        })?;
+
+        let changes = self
+            .event_repository
+            .list_changes_since(&uuid, since)
+            .await?;
+        Ok(changes.map(|c| CalendarChangesDto {
+            sync_token: c.sync_token,
+            changed: c.changed.into_iter().map(CalendarEventDto::from).collect(),
+            deleted: c.deleted,
+        }))
+    }
 }
 
 #[cfg(test)]
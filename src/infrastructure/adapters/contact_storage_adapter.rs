@@ -12,9 +12,9 @@ use crate::application::dtos::address_book_dto::{
     UpdateAddressBookDto,
 };
 use crate::application::dtos::contact_dto::{
-    AddressDto, ContactDto, ContactGroupDto, CreateContactDto, CreateContactGroupDto,
-    CreateContactVCardDto, EmailDto, GroupMembershipDto, PhoneDto, UpdateContactDto,
-    UpdateContactGroupDto,
+    AddressDto, ContactChangesDto, ContactDto, ContactGroupDto, CreateContactDto,
+    CreateContactGroupDto, CreateContactVCardDto, EmailDto, GroupMembershipDto, PhoneDto,
+    UpdateContactDto, UpdateContactGroupDto,
 };
 use crate::application::ports::carddav_ports::{AddressBookUseCase, ContactUseCase};
 use crate::common::errors::{DomainError, ErrorKind};
@@ -993,4 +993,26 @@ impl ContactUseCase for ContactStorageAdapter {
             .map(|c| (c.id().to_string(), c.vcard().to_string()))
             .collect())
     }
+
+    async fn get_changes_since(
+        &self,
+        address_book_id: &str,
+        since: Option<i64>,
+        user_id: Uuid,
+    ) -> Result<Option<ContactChangesDto>, DomainError> {
+        let uuid = Self::parse_uuid(address_book_id, "AddressBook")?;
+
+        // Check read access
+        self.check_address_book_access(&uuid, user_id).await?;
+
+        let changes = self
+            .contact_repository
+            .list_changes_since(&uuid, since)
+            .await?;
+        Ok(changes.map(|c| ContactChangesDto {
+            sync_token: c.sync_token,
+            changed: c.changed.into_iter().map(ContactDto::from).collect(),
+            deleted: c.deleted,
+        }))
+    }
 }
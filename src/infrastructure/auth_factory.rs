@@ -3,12 +3,23 @@ use std::sync::Arc;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+use crate::application::services::account_recovery_service::AccountRecoveryService;
 use crate::application::services::auth_application_service::AuthApplicationService;
 use crate::application::services::folder_service::FolderService;
+use crate::application::services::group_service::GroupService;
+use crate::application::services::two_factor_service::TwoFactorService;
+use crate::application::services::webauthn_service::WebAuthnService;
 use crate::common::config::AppConfig;
 use crate::common::di::AuthServices;
-use crate::infrastructure::repositories::{SessionPgRepository, UserPgRepository};
+use crate::infrastructure::repositories::pg::{
+    AccountTokenPgRepository, SettingsPgRepository, TwoFactorPgRepository, WebAuthnPgRepository,
+};
+use crate::infrastructure::repositories::{
+    GroupPgRepository, SessionPgRepository, UserPgRepository,
+};
 use crate::infrastructure::services::jwt_service::JwtTokenService;
+use crate::infrastructure::services::ldap_service::LdapService;
+use crate::infrastructure::services::mail_transport::MailTransport;
 use crate::infrastructure::services::oidc_service::OidcService;
 use crate::infrastructure::services::password_hasher::Argon2PasswordHasher;
 
@@ -34,6 +45,45 @@ pub async fn create_auth_services(
     // Create PostgreSQL repositories
     let user_repository = Arc::new(UserPgRepository::new(pool.clone()));
     let session_repository = Arc::new(SessionPgRepository::new(pool.clone()));
+    let group_repository = Arc::new(GroupPgRepository::new(pool.clone()));
+    let group_service = Arc::new(GroupService::new(group_repository.clone()));
+    let two_factor_service = Arc::new(TwoFactorService::new(
+        Arc::new(TwoFactorPgRepository::new(pool.clone())),
+        Arc::new(SettingsPgRepository::new(pool.clone())),
+        group_repository,
+    ));
+    let webauthn_service = Arc::new(WebAuthnService::new(
+        Arc::new(WebAuthnPgRepository::new(pool.clone())),
+        session_repository.clone(),
+        config.webauthn.clone(),
+    ));
+    tracing::info!(
+        "Passkeys bound to relying party '{}' (origins: {})",
+        config.webauthn.rp_id,
+        config.webauthn.origins.join(", ")
+    );
+
+    // Outgoing mail: password resets and address verification need it
+    let mailer = match MailTransport::from_config(&config.mail) {
+        Ok(mailer) => mailer,
+        Err(e) => {
+            tracing::error!("Mail transport could not be set up — mail disabled: {}", e);
+            None
+        }
+    };
+    let account_recovery = mailer.map(|mailer| {
+        tracing::info!("Mail transport: {}", MailTransport::describe(&config.mail));
+        Arc::new(AccountRecoveryService::new(
+            user_repository.clone(),
+            session_repository.clone(),
+            password_hasher.clone(),
+            Arc::new(AccountTokenPgRepository::new(pool.clone())),
+            Arc::new(mailer),
+            config.auth.jwt_secret.clone(),
+            config.base_url(),
+            config.mail.clone(),
+        ))
+    });
 
     // Create authentication application service
     let mut auth_app_service = AuthApplicationService::new(
@@ -44,6 +94,15 @@ pub async fn create_auth_services(
         config.storage_path.clone(),
     );
 
+    auth_app_service = auth_app_service
+        .with_group_service(group_service.clone())
+        .with_two_factor(two_factor_service.clone())
+        .with_webauthn(webauthn_service.clone());
+
+    if let Some(recovery) = &account_recovery {
+        auth_app_service = auth_app_service.with_account_recovery(recovery.clone());
+    }
+
     // Configure folder service if available
     if let Some(folder_svc) = folder_service {
         auth_app_service = auth_app_service.with_folder_service(folder_svc);
@@ -65,6 +124,17 @@ pub async fn create_auth_services(
         }
     }
 
+    // Configure LDAP authentication if enabled
+    if config.ldap.enabled {
+        tracing::info!(
+            "Initializing LDAP authentication (server: {}, base: {})",
+            config.ldap.url,
+            config.ldap.user_base_dn
+        );
+        auth_app_service =
+            auth_app_service.with_ldap(Arc::new(LdapService::new(config.ldap.clone())));
+    }
+
     // Package service in Arc
     let auth_application_service = Arc::new(auth_app_service);
 
@@ -86,5 +156,9 @@ pub async fn create_auth_services(
         token_service,
         auth_application_service,
         login_lockout,
+        group_service,
+        two_factor_service,
+        webauthn_service,
+        account_recovery,
     })
 }
@@ -4,6 +4,6 @@ pub mod pg;
 // Re-exportar para facilitar acceso
 pub use pg::{
     AppPasswordPgRepository, DeviceCodePgRepository, FileBlobReadRepository,
-    FileBlobWriteRepository, FolderDbRepository, SessionPgRepository, TrashDbRepository,
-    UserPgRepository,
+    FileBlobWriteRepository, FolderDbRepository, GroupPgRepository, SessionPgRepository,
+    TrashDbRepository, UserPgRepository,
 };
@@ -0,0 +1,125 @@
+//! PostgreSQL repository for mailed account tokens
+//! (`auth.account_tokens`, `auth.unverified_emails`).
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::ports::account_token_ports::{
+    AccountTokenPurpose, AccountTokenRepositoryPort,
+};
+use crate::common::errors::{DomainError, Result};
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    DomainError::internal_error("AccountToken", format!("{context}: {e}"))
+}
+
+/// PostgreSQL implementation of the account token persistence port.
+pub struct AccountTokenPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AccountTokenPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl AccountTokenRepositoryPort for AccountTokenPgRepository {
+    async fn create_token(
+        &self,
+        token_id: Uuid,
+        user_id: Uuid,
+        purpose: AccountTokenPurpose,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(|e| db_error("begin", e))?;
+
+        // Used and expired tokens are no longer needed once a new one exists
+        sqlx::query("DELETE FROM auth.account_tokens WHERE user_id = $1 AND purpose = $2")
+            .bind(user_id)
+            .bind(purpose.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("delete old tokens", e))?;
+        sqlx::query(
+            "INSERT INTO auth.account_tokens (id, user_id, purpose, expires_at) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(token_id)
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("insert token", e))?;
+
+        tx.commit().await.map_err(|e| db_error("commit", e))
+    }
+
+    async fn consume_token(
+        &self,
+        token_id: Uuid,
+        user_id: Uuid,
+        purpose: AccountTokenPurpose,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE auth.account_tokens SET used_at = NOW() \
+             WHERE id = $1 AND user_id = $2 AND purpose = $3 \
+               AND used_at IS NULL AND expires_at > NOW()",
+        )
+        .bind(token_id)
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("consume token", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_tokens(&self, user_id: Uuid, purpose: AccountTokenPurpose) -> Result<()> {
+        sqlx::query(
+            "UPDATE auth.account_tokens SET used_at = NOW() \
+             WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("revoke tokens", e))?;
+        Ok(())
+    }
+
+    async fn set_email_unverified(&self, user_id: Uuid, email: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO auth.unverified_emails (user_id, email) VALUES ($1, $2) \
+             ON CONFLICT (user_id) DO UPDATE SET email = EXCLUDED.email",
+        )
+        .bind(user_id)
+        .bind(email)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("set email unverified", e))?;
+        Ok(())
+    }
+
+    async fn get_unverified_email(&self, user_id: Uuid) -> Result<Option<String>> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT email FROM auth.unverified_emails WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("get unverified email", e))
+    }
+
+    async fn set_email_verified(&self, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM auth.unverified_emails WHERE user_id = $1")
+            .bind(user_id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("set email verified", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
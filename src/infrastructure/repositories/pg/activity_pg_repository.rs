@@ -0,0 +1,135 @@
+//! PostgreSQL repository for the activity log (`activity.events`).
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::ports::activity_ports::{
+    ActivityFilter, ActivityRecord, ActivityRepositoryPort, NewActivity,
+};
+use crate::common::errors::{DomainError, Result};
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    DomainError::internal_error("Activity", format!("{context}: {e}"))
+}
+
+/// PostgreSQL implementation of the activity log port.
+pub struct ActivityPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl ActivityPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl ActivityRepositoryPort for ActivityPgRepository {
+    async fn record(&self, activity: &NewActivity) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO activity.events \
+               (action, actor_id, actor_name, owner_id, item_type, item_id, item_name, \
+                ip_address, details) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(activity.action.as_str())
+        .bind(activity.actor_id)
+        .bind(&activity.actor_name)
+        .bind(activity.owner_id)
+        .bind(activity.action.item_type())
+        .bind(&activity.item_id)
+        .bind(&activity.item_name)
+        .bind(&activity.ip_address)
+        .bind(&activity.details)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("record activity", e))?;
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        filter: &ActivityFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<ActivityRecord>, usize)> {
+        let actions: Vec<&str> = filter.actions.iter().map(|a| a.as_str()).collect();
+        let rows = sqlx::query(
+            r#"
+            SELECT e.id, e.occurred_at, e.action, e.actor_id,
+                   COALESCE(actor.username, e.actor_name) AS actor_name,
+                   e.owner_id, owner.username AS owner_name,
+                   e.item_type, e.item_id, e.item_name, e.ip_address, e.details,
+                   COUNT(*) OVER() AS total_count
+            FROM activity.events e
+            LEFT JOIN auth.users actor ON actor.id = e.actor_id
+            LEFT JOIN auth.users owner ON owner.id = e.owner_id
+            WHERE ($1::uuid IS NULL OR e.actor_id = $1 OR e.owner_id = $1)
+              AND (cardinality($2::text[]) = 0 OR e.action = ANY($2))
+              AND ($3::text IS NULL OR e.item_id = $3)
+              AND ($4::timestamptz IS NULL OR e.occurred_at >= $4)
+              AND ($5::timestamptz IS NULL OR e.occurred_at < $5)
+            ORDER BY e.occurred_at DESC, e.id DESC
+            LIMIT $6 OFFSET $7
+            "#,
+        )
+        .bind(filter.user_id)
+        .bind(&actions)
+        .bind(&filter.item_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list activity", e))?;
+
+        let total = rows
+            .first()
+            .and_then(|r| r.try_get::<i64, _>("total_count").ok())
+            .unwrap_or(0) as usize;
+
+        let records = rows
+            .iter()
+            .map(|r| ActivityRecord {
+                id: r.get("id"),
+                occurred_at: r.get("occurred_at"),
+                action: r.get("action"),
+                actor_id: r.get("actor_id"),
+                actor_name: r.get("actor_name"),
+                owner_id: r.get("owner_id"),
+                owner_name: r.get("owner_name"),
+                item_type: r.get("item_type"),
+                item_id: r.get("item_id"),
+                item_name: r.get("item_name"),
+                ip_address: r.get("ip_address"),
+                details: r.get("details"),
+            })
+            .collect();
+
+        Ok((records, total))
+    }
+
+    async fn last_known_item(&self, item_id: &str) -> Result<Option<(Option<Uuid>, String)>> {
+        let row = sqlx::query(
+            "SELECT owner_id, item_name FROM activity.events \
+             WHERE item_id = $1 AND item_name IS NOT NULL \
+             ORDER BY id DESC LIMIT 1",
+        )
+        .bind(item_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("look up item", e))?;
+        Ok(row.map(|r| (r.get("owner_id"), r.get("item_name"))))
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM activity.events WHERE occurred_at < $1")
+            .bind(before)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("prune activity", e))?;
+        Ok(result.rows_affected())
+    }
+}
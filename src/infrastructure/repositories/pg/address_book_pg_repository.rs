@@ -27,7 +27,7 @@ impl AddressBookRepository for AddressBookPgRepository {
             r#"
             INSERT INTO carddav.address_books (id, name, owner_id, description, color, is_public, created_at, updated_at)
             VALUES ($1, $2, $3::uuid, $4, $5, $6, $7, $8)
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             "#
         )
         .bind(address_book.id())
@@ -52,7 +52,8 @@ impl AddressBookRepository for AddressBookPgRepository {
             row.get("is_public"),
             row.get("created_at"),
             row.get("updated_at"),
-        ))
+        )
+        .with_sync_token(row.get("sync_token")))
     }
 
     async fn update_address_book(
@@ -65,7 +66,7 @@ impl AddressBookRepository for AddressBookPgRepository {
             UPDATE carddav.address_books
             SET name = $1, description = $2, color = $3, is_public = $4, updated_at = $5
             WHERE id = $6
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             "#,
         )
         .bind(address_book.name())
@@ -90,7 +91,8 @@ impl AddressBookRepository for AddressBookPgRepository {
             row.get("is_public"),
             row.get("created_at"),
             row.get("updated_at"),
-        ))
+        )
+        .with_sync_token(row.get("sync_token")))
     }
 
     async fn delete_address_book(&self, id: &Uuid) -> AddressBookRepositoryResult<()> {
@@ -116,7 +118,7 @@ impl AddressBookRepository for AddressBookPgRepository {
     ) -> AddressBookRepositoryResult<Option<AddressBook>> {
         let maybe_row = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             FROM carddav.address_books
             WHERE id = $1
             "#,
@@ -140,6 +142,7 @@ impl AddressBookRepository for AddressBookPgRepository {
                 row.get("created_at"),
                 row.get("updated_at"),
             )
+            .with_sync_token(row.get("sync_token"))
         });
 
         Ok(result)
@@ -151,7 +154,7 @@ impl AddressBookRepository for AddressBookPgRepository {
     ) -> AddressBookRepositoryResult<Vec<AddressBook>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             FROM carddav.address_books
             WHERE owner_id = $1
             ORDER BY name
@@ -178,6 +181,7 @@ impl AddressBookRepository for AddressBookPgRepository {
                     row.get("created_at"),
                     row.get("updated_at"),
                 )
+                .with_sync_token(row.get("sync_token"))
             })
             .collect();
 
@@ -190,7 +194,7 @@ impl AddressBookRepository for AddressBookPgRepository {
     ) -> AddressBookRepositoryResult<Vec<AddressBook>> {
         let rows = sqlx::query(
             r#"
-            SELECT a.id, a.name, a.owner_id, a.description, a.color, a.is_public, a.created_at, a.updated_at
+            SELECT a.id, a.name, a.owner_id, a.description, a.color, a.is_public, a.created_at, a.updated_at, a.sync_token
             FROM carddav.address_books a
             INNER JOIN carddav.address_book_shares s ON a.id = s.address_book_id
             WHERE s.user_id = $1
@@ -216,6 +220,7 @@ impl AddressBookRepository for AddressBookPgRepository {
                     row.get("created_at"),
                     row.get("updated_at"),
                 )
+                .with_sync_token(row.get("sync_token"))
             })
             .collect();
 
@@ -225,7 +230,7 @@ impl AddressBookRepository for AddressBookPgRepository {
     async fn get_public_address_books(&self) -> AddressBookRepositoryResult<Vec<AddressBook>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             FROM carddav.address_books
             WHERE is_public = true
             ORDER BY name
@@ -251,6 +256,7 @@ impl AddressBookRepository for AddressBookPgRepository {
                     row.get("created_at"),
                     row.get("updated_at"),
                 )
+                .with_sync_token(row.get("sync_token"))
             })
             .collect();
 
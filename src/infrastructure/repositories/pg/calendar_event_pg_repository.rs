@@ -3,9 +3,9 @@ use sqlx::{PgPool, Row, types::Uuid};
 use std::sync::Arc;
 
 use crate::common::errors::DomainError;
-use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::entities::calendar_event::{CalendarEvent, TaskDetails};
 use crate::domain::repositories::calendar_event_repository::{
-    CalendarEventRepository, CalendarEventRepositoryResult,
+    CalendarEventChanges, CalendarEventRepository, CalendarEventRepositoryResult,
 };
 
 pub struct CalendarEventPgRepository {
@@ -16,6 +16,36 @@ impl CalendarEventPgRepository {
     pub fn new(pool: Arc<PgPool>) -> Self {
         Self { pool }
     }
+
+    /// Builds a CalendarEvent from a row with the standard event and task columns
+    fn event_from_row(row: &sqlx::postgres::PgRow) -> CalendarEventRepositoryResult<CalendarEvent> {
+        let task = (row.get::<String, _>("component") == "VTODO").then(|| TaskDetails {
+            due: row.get("due_time"),
+            status: row.get("status"),
+            percent_complete: row.get("percent_complete"),
+            priority: row.get("priority"),
+            related_to: row.get("related_to"),
+            completed_at: row.get("completed_at"),
+        });
+
+        CalendarEvent::with_id(
+            row.get("id"),
+            row.get("calendar_id"),
+            row.get("summary"),
+            row.get::<Option<String>, _>("description"),
+            row.get::<Option<String>, _>("location"),
+            row.get("start_time"),
+            row.get("end_time"),
+            row.get("all_day"),
+            row.get::<Option<String>, _>("rrule"),
+            row.get("ical_uid"),
+            row.get("ical_data"),
+            row.get("created_at"),
+            row.get("updated_at"),
+        )
+        .map(|event| event.with_task(task))
+        .map_err(|e| DomainError::database_error(format!("Error creating calendar event: {}", e)))
+    }
 }
 
 impl CalendarEventRepository for CalendarEventPgRepository {
@@ -26,14 +56,18 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         // This method would need a full implementation that builds the CalendarEvent
         // from the query result, using constructor methods
         // For this demonstration, we return the same event
+        let task = event.task();
 
         sqlx::query(
             r#"
             INSERT INTO caldav.calendar_events (
                 id, calendar_id, summary, description, location, start_time, end_time, 
-                all_day, rrule, created_at, updated_at, ical_uid, ical_data
+                all_day, rrule, created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,
+                    $14, $15, $16, $17, $18, $19, $20)
             "#,
         )
         .bind(event.id())
@@ -49,6 +83,13 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         .bind(event.updated_at())
         .bind(event.ical_uid())
         .bind(event.ical_data())
+        .bind(event.component())
+        .bind(task.and_then(|t| t.due))
+        .bind(task.and_then(|t| t.status.as_deref()))
+        .bind(task.and_then(|t| t.percent_complete))
+        .bind(task.and_then(|t| t.priority))
+        .bind(task.and_then(|t| t.related_to.as_deref()))
+        .bind(task.and_then(|t| t.completed_at))
         .execute(&*self.pool)
         .await
         .map_err(|e| {
@@ -64,6 +105,7 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         event: CalendarEvent,
     ) -> CalendarEventRepositoryResult<CalendarEvent> {
         let now = Utc::now();
+        let task = event.task();
 
         sqlx::query(
             r#"
@@ -76,7 +118,14 @@ impl CalendarEventRepository for CalendarEventPgRepository {
                 all_day = $6, 
                 rrule = $7,
                 ical_data = $8,
-                updated_at = $9
+                updated_at = $9,
+                component = $11,
+                due_time = $12,
+                status = $13,
+                percent_complete = $14,
+                priority = $15,
+                related_to = $16,
+                completed_at = $17
             WHERE id = $10
             "#,
         )
@@ -90,6 +139,13 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         .bind(event.ical_data())
         .bind(now)
         .bind(event.id())
+        .bind(event.component())
+        .bind(task.and_then(|t| t.due))
+        .bind(task.and_then(|t| t.status.as_deref()))
+        .bind(task.and_then(|t| t.percent_complete))
+        .bind(task.and_then(|t| t.priority))
+        .bind(task.and_then(|t| t.related_to.as_deref()))
+        .bind(task.and_then(|t| t.completed_at))
         .execute(&*self.pool)
         .await
         .map_err(|e| {
@@ -129,14 +185,23 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             FROM caldav.calendar_events
             WHERE calendar_id = $1 
               AND (
                   (start_time >= $2 AND start_time < $3) OR
                   (end_time > $2 AND end_time <= $3) OR
                   (start_time <= $2 AND end_time >= $3) OR
-                  (rrule IS NOT NULL AND end_time >= $2)
+                  -- Recurring: instances are checked below
+                  (start_time < $3 AND (
+                      rrule IS NOT NULL OR
+                      ical_data LIKE '%RDATE%' OR
+                      ical_data LIKE '%RECURRENCE-ID%'
+                  )) OR
+                  -- Undated tasks match any range; checked below
+                  component = 'VTODO'
               )
             ORDER BY start_time
             "#,
@@ -152,27 +217,13 @@ impl CalendarEventRepository for CalendarEventPgRepository {
 
         let mut events = Vec::new();
         for row in rows {
-            let event = CalendarEvent::with_id(
-                row.get("id"),
-                row.get("calendar_id"),
-                row.get("summary"),
-                row.get::<Option<String>, _>("description"),
-                row.get::<Option<String>, _>("location"),
-                row.get("start_time"),
-                row.get("end_time"),
-                row.get("all_day"),
-                row.get::<Option<String>, _>("rrule"),
-                row.get("ical_uid"),
-                row.get("ical_data"),
-                row.get("created_at"),
-                row.get("updated_at"),
-            )
-            .map_err(|e| {
-                DomainError::database_error(format!("Error creating calendar event: {}", e))
-            })?;
+            let event = Self::event_from_row(&row)?;
             events.push(event);
         }
 
+        // The query only narrows down candidates for recurring events and tasks
+        events.retain(|event| event.occurs_in_range(start, end));
+
         Ok(events)
     }
 
@@ -182,7 +233,9 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             FROM caldav.calendar_events
             WHERE id = $1
             "#,
@@ -199,24 +252,7 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         // For simplicity, we create an object with default values to
         // demonstrate the approach without macros
 
-        let event = CalendarEvent::with_id(
-            row.get("id"),
-            row.get("calendar_id"),
-            row.get("summary"),
-            row.get::<Option<String>, _>("description"),
-            row.get::<Option<String>, _>("location"),
-            row.get("start_time"),
-            row.get("end_time"),
-            row.get("all_day"),
-            row.get::<Option<String>, _>("rrule"),
-            row.get("ical_uid"),
-            row.get("ical_data"),
-            row.get("created_at"),
-            row.get("updated_at"),
-        )
-        .map_err(|e| {
-            DomainError::database_error(format!("Error creating calendar event: {}", e))
-        })?;
+        let event = Self::event_from_row(&row)?;
 
         Ok(event)
     }
@@ -230,7 +266,9 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             FROM caldav.calendar_events
             WHERE calendar_id = $1
             ORDER BY start_time
@@ -245,24 +283,7 @@ impl CalendarEventRepository for CalendarEventPgRepository {
 
         let mut events = Vec::new();
         for row in rows {
-            let event = CalendarEvent::with_id(
-                row.get("id"),
-                row.get("calendar_id"),
-                row.get("summary"),
-                row.get::<Option<String>, _>("description"),
-                row.get::<Option<String>, _>("location"),
-                row.get("start_time"),
-                row.get("end_time"),
-                row.get("all_day"),
-                row.get::<Option<String>, _>("rrule"),
-                row.get("ical_uid"),
-                row.get("ical_data"),
-                row.get("created_at"),
-                row.get("updated_at"),
-            )
-            .map_err(|e| {
-                DomainError::database_error(format!("Error creating calendar event: {}", e))
-            })?;
+            let event = Self::event_from_row(&row)?;
             events.push(event);
         }
 
@@ -281,7 +302,9 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             FROM caldav.calendar_events
             WHERE calendar_id = $1 AND summary ILIKE $2
             ORDER BY start_time
@@ -297,24 +320,7 @@ impl CalendarEventRepository for CalendarEventPgRepository {
 
         let mut events = Vec::new();
         for row in rows {
-            let event = CalendarEvent::with_id(
-                row.get("id"),
-                row.get("calendar_id"),
-                row.get("summary"),
-                row.get::<Option<String>, _>("description"),
-                row.get::<Option<String>, _>("location"),
-                row.get("start_time"),
-                row.get("end_time"),
-                row.get("all_day"),
-                row.get::<Option<String>, _>("rrule"),
-                row.get("ical_uid"),
-                row.get("ical_data"),
-                row.get("created_at"),
-                row.get("updated_at"),
-            )
-            .map_err(|e| {
-                DomainError::database_error(format!("Error creating calendar event: {}", e))
-            })?;
+            let event = Self::event_from_row(&row)?;
             events.push(event);
         }
 
@@ -331,7 +337,9 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             FROM caldav.calendar_events
             WHERE calendar_id = $1 AND ical_uid = $2
             "#,
@@ -346,24 +354,7 @@ impl CalendarEventRepository for CalendarEventPgRepository {
 
         match row_opt {
             Some(row) => {
-                let event = CalendarEvent::with_id(
-                    row.get("id"),
-                    row.get("calendar_id"),
-                    row.get("summary"),
-                    row.get::<Option<String>, _>("description"),
-                    row.get::<Option<String>, _>("location"),
-                    row.get("start_time"),
-                    row.get("end_time"),
-                    row.get("all_day"),
-                    row.get::<Option<String>, _>("rrule"),
-                    row.get("ical_uid"),
-                    row.get("ical_data"),
-                    row.get("created_at"),
-                    row.get("updated_at"),
-                )
-                .map_err(|e| {
-                    DomainError::database_error(format!("Error creating calendar event: {}", e))
-                })?;
+                let event = Self::event_from_row(&row)?;
                 Ok(Some(event))
             }
             None => Ok(None),
@@ -422,7 +413,9 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             FROM caldav.calendar_events
             WHERE calendar_id = $1
             ORDER BY start_time
@@ -443,24 +436,7 @@ impl CalendarEventRepository for CalendarEventPgRepository {
 
         let mut events = Vec::new();
         for row in rows {
-            let event = CalendarEvent::with_id(
-                row.get("id"),
-                row.get("calendar_id"),
-                row.get("summary"),
-                row.get::<Option<String>, _>("description"),
-                row.get::<Option<String>, _>("location"),
-                row.get("start_time"),
-                row.get("end_time"),
-                row.get("all_day"),
-                row.get::<Option<String>, _>("rrule"),
-                row.get("ical_uid"),
-                row.get("ical_data"),
-                row.get("created_at"),
-                row.get("updated_at"),
-            )
-            .map_err(|e| {
-                DomainError::database_error(format!("Error creating calendar event: {}", e))
-            })?;
+            let event = Self::event_from_row(&row)?;
             events.push(event);
         }
 
@@ -478,7 +454,9 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data,
+                component, due_time, status, percent_complete, priority,
+                related_to, completed_at
             FROM caldav.calendar_events
             WHERE calendar_id = $1 
               AND rrule IS NOT NULL
@@ -498,27 +476,131 @@ impl CalendarEventRepository for CalendarEventPgRepository {
 
         let mut events = Vec::new();
         for row in rows {
-            let event = CalendarEvent::with_id(
-                row.get("id"),
-                row.get("calendar_id"),
-                row.get("summary"),
-                row.get::<Option<String>, _>("description"),
-                row.get::<Option<String>, _>("location"),
-                row.get("start_time"),
-                row.get("end_time"),
-                row.get("all_day"),
-                row.get::<Option<String>, _>("rrule"),
-                row.get("ical_uid"),
-                row.get("ical_data"),
-                row.get("created_at"),
-                row.get("updated_at"),
+            let event = Self::event_from_row(&row)?;
+            events.push(event);
+        }
+
+        Ok(events)
+    }
+
+    async fn list_changes_since(
+        &self,
+        calendar_id: &Uuid,
+        since: Option<i64>,
+    ) -> CalendarEventRepositoryResult<Option<CalendarEventChanges>> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            DomainError::database_error(format!("Failed to begin transaction: {}", e))
+        })?;
+
+        // One snapshot for the token and the changes, so a concurrent write
+        // is either fully included or left for the next sync
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                DomainError::database_error(format!("Failed to set isolation level: {}", e))
+            })?;
+
+        let (sync_token, floor): (i64, i64) = sqlx::query_as(
+            "SELECT sync_token, sync_token_floor FROM caldav.calendars WHERE id = $1",
+        )
+        .bind(calendar_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get sync token: {}", e)))?
+        .ok_or_else(|| DomainError::not_found("Calendar", calendar_id.to_string()))?;
+
+        let Some(since) = since else {
+            let rows = sqlx::query(
+                r#"
+                SELECT
+                    id, calendar_id, summary, description, location,
+                    start_time, end_time, all_day, rrule,
+                    created_at, updated_at, ical_uid, ical_data,
+                    component, due_time, status, percent_complete, priority,
+                    related_to, completed_at
+                FROM caldav.calendar_events
+                WHERE calendar_id = $1
+                ORDER BY start_time
+                "#,
             )
+            .bind(calendar_id)
+            .fetch_all(&mut *tx)
+            .await
             .map_err(|e| {
-                DomainError::database_error(format!("Error creating calendar event: {}", e))
+                DomainError::database_error(format!("Failed to get events by calendar: {}", e))
             })?;
-            events.push(event);
+
+            let changed = rows
+                .iter()
+                .map(Self::event_from_row)
+                .collect::<CalendarEventRepositoryResult<Vec<_>>>()?;
+            return Ok(Some(CalendarEventChanges {
+                sync_token,
+                changed,
+                deleted: Vec::new(),
+            }));
+        };
+
+        if since < floor || since > sync_token {
+            return Ok(None);
         }
 
-        Ok(events)
+        // Latest state per changed resource; no event row means it is gone
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                ch.ical_uid AS changed_uid,
+                e.id, e.calendar_id, e.summary, e.description, e.location,
+                e.start_time, e.end_time, e.all_day, e.rrule,
+                e.created_at, e.updated_at, e.ical_uid, e.ical_data,
+                e.component, e.due_time, e.status, e.percent_complete, e.priority,
+                e.related_to, e.completed_at
+            FROM (
+                SELECT DISTINCT ical_uid
+                FROM caldav.calendar_changes
+                WHERE calendar_id = $1 AND sync_token > $2
+            ) ch
+            LEFT JOIN caldav.calendar_events e
+                ON e.calendar_id = $1 AND e.ical_uid = ch.ical_uid
+            ORDER BY ch.ical_uid
+            "#,
+        )
+        .bind(calendar_id)
+        .bind(since)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            DomainError::database_error(format!("Failed to get calendar changes: {}", e))
+        })?;
+
+        let mut changed = Vec::new();
+        let mut deleted = Vec::new();
+        for row in &rows {
+            if row.get::<Option<Uuid>, _>("id").is_some() {
+                changed.push(Self::event_from_row(row)?);
+            } else {
+                deleted.push(row.get("changed_uid"));
+            }
+        }
+
+        Ok(Some(CalendarEventChanges {
+            sync_token,
+            changed,
+            deleted,
+        }))
+    }
+
+    async fn prune_changes(&self, before: DateTime<Utc>) -> CalendarEventRepositoryResult<u64> {
+        let pruned = sqlx::query_scalar::<_, i64>(
+            "SELECT caldav.prune_calendar_changes(now() - $1::timestamptz)",
+        )
+        .bind(before)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| {
+            DomainError::database_error(format!("Failed to prune calendar changes: {}", e))
+        })?;
+        Ok(pruned.max(0) as u64)
     }
 }
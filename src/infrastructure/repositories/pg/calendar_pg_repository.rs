@@ -24,7 +24,7 @@ impl CalendarRepository for CalendarPgRepository {
             r#"
             INSERT INTO caldav.calendars (id, name, owner_id, description, color, is_public, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             "#
         )
         .bind(calendar.id())
@@ -49,6 +49,7 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("created_at"),
             row.get("updated_at"),
         )
+        .map(|c| c.with_sync_token(row.get("sync_token")))
         .map_err(|e| {
             DomainError::database_error(format!("Failed to create calendar object: {}", e))
         })?;
@@ -63,7 +64,7 @@ impl CalendarRepository for CalendarPgRepository {
             UPDATE caldav.calendars
             SET name = $1, description = $2, color = $3, is_public = $4, updated_at = $5
             WHERE id = $6
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             "#,
         )
         .bind(calendar.name())
@@ -86,6 +87,7 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("created_at"),
             row.get("updated_at"),
         )
+        .map(|c| c.with_sync_token(row.get("sync_token")))
         .map_err(|e| {
             DomainError::database_error(format!("Failed to create calendar object: {}", e))
         })?;
@@ -111,7 +113,7 @@ impl CalendarRepository for CalendarPgRepository {
     async fn find_calendar_by_id(&self, id: &Uuid) -> CalendarRepositoryResult<Calendar> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             FROM caldav.calendars
             WHERE id = $1
             "#,
@@ -131,6 +133,7 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("created_at"),
             row.get("updated_at"),
         )
+        .map(|c| c.with_sync_token(row.get("sync_token")))
         .map_err(|e| {
             DomainError::database_error(format!("Failed to create calendar object: {}", e))
         })?;
@@ -144,7 +147,7 @@ impl CalendarRepository for CalendarPgRepository {
     ) -> CalendarRepositoryResult<Vec<Calendar>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             FROM caldav.calendars
             WHERE owner_id = $1
             ORDER BY name
@@ -168,6 +171,7 @@ impl CalendarRepository for CalendarPgRepository {
                 row.get("created_at"),
                 row.get("updated_at"),
             )
+            .map(|c| c.with_sync_token(row.get("sync_token")))
             .map_err(|e| {
                 DomainError::database_error(format!("Failed to create calendar object: {}", e))
             })?;
@@ -184,7 +188,7 @@ impl CalendarRepository for CalendarPgRepository {
     ) -> CalendarRepositoryResult<Calendar> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             FROM caldav.calendars
             WHERE name = $1 AND owner_id = $2
             "#,
@@ -209,6 +213,7 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("created_at"),
             row.get("updated_at"),
         )
+        .map(|c| c.with_sync_token(row.get("sync_token")))
         .map_err(|e| {
             DomainError::database_error(format!("Failed to create calendar object: {}", e))
         })?;
@@ -222,7 +227,7 @@ impl CalendarRepository for CalendarPgRepository {
     ) -> CalendarRepositoryResult<Vec<Calendar>> {
         let rows = sqlx::query(
             r#"
-            SELECT c.id, c.name, c.owner_id, c.description, c.color, c.is_public, c.created_at, c.updated_at
+            SELECT c.id, c.name, c.owner_id, c.description, c.color, c.is_public, c.created_at, c.updated_at, c.sync_token
             FROM caldav.calendars c
             INNER JOIN caldav.calendar_shares s ON c.id = s.calendar_id
             WHERE s.user_id = $1
@@ -245,6 +250,7 @@ impl CalendarRepository for CalendarPgRepository {
                 row.get("created_at"),
                 row.get("updated_at"),
             )
+            .map(|c| c.with_sync_token(row.get("sync_token")))
             .map_err(|e| {
                 DomainError::database_error(format!("Failed to create calendar object: {}", e))
             })?;
@@ -261,7 +267,7 @@ impl CalendarRepository for CalendarPgRepository {
     ) -> CalendarRepositoryResult<Vec<Calendar>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_token
             FROM caldav.calendars
             WHERE is_public = true
             ORDER BY name
@@ -287,6 +293,7 @@ impl CalendarRepository for CalendarPgRepository {
                 row.get("created_at"),
                 row.get("updated_at"),
             )
+            .map(|c| c.with_sync_token(row.get("sync_token")))
             .map_err(|e| {
                 DomainError::database_error(format!("Failed to create calendar object: {}", e))
             })?;
@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde_json::Value as JsonValue;
 use sqlx::{PgPool, Row, types::Uuid};
 use std::sync::Arc;
@@ -10,7 +10,9 @@ use super::contact_persistence_dto::{
 };
 use crate::common::errors::DomainError;
 use crate::domain::entities::contact::Contact;
-use crate::domain::repositories::contact_repository::{ContactRepository, ContactRepositoryResult};
+use crate::domain::repositories::contact_repository::{
+    ContactChanges, ContactRepository, ContactRepositoryResult,
+};
 
 pub struct ContactPgRepository {
     pool: Arc<PgPool>,
@@ -373,4 +375,125 @@ impl ContactRepository for ContactPgRepository {
         }
         Ok(contacts)
     }
+
+    async fn list_changes_since(
+        &self,
+        address_book_id: &Uuid,
+        since: Option<i64>,
+    ) -> ContactRepositoryResult<Option<ContactChanges>> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            DomainError::database_error(format!("Failed to begin transaction: {}", e))
+        })?;
+
+        // One snapshot for the token and the changes, so a concurrent write
+        // is either fully included or left for the next sync
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                DomainError::database_error(format!("Failed to set isolation level: {}", e))
+            })?;
+
+        let (sync_token, floor): (i64, i64) = sqlx::query_as(
+            "SELECT sync_token, sync_token_floor FROM carddav.address_books WHERE id = $1",
+        )
+        .bind(address_book_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get sync token: {}", e)))?
+        .ok_or_else(|| DomainError::not_found("AddressBook", address_book_id.to_string()))?;
+
+        let Some(since) = since else {
+            let rows = sqlx::query(
+                r#"
+                SELECT
+                    id, address_book_id, uid, full_name, first_name, last_name, nickname,
+                    email, phone, address, organization, title, notes, photo_url,
+                    birthday, anniversary, vcard, etag, created_at, updated_at
+                FROM carddav.contacts
+                WHERE address_book_id = $1
+                ORDER BY full_name, first_name, last_name
+                "#,
+            )
+            .bind(address_book_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| {
+                DomainError::database_error(format!(
+                    "Failed to get contacts by address book: {}",
+                    e
+                ))
+            })?;
+
+            let mut changed = Vec::new();
+            for row in &rows {
+                changed.push(Self::row_to_contact(row)?);
+            }
+            return Ok(Some(ContactChanges {
+                sync_token,
+                changed,
+                deleted: Vec::new(),
+            }));
+        };
+
+        if since < floor || since > sync_token {
+            return Ok(None);
+        }
+
+        // Latest state per changed vCard; no contact row means it is gone
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                ch.uid AS changed_uid,
+                c.id, c.address_book_id, c.uid, c.full_name, c.first_name, c.last_name,
+                c.nickname, c.email, c.phone, c.address, c.organization, c.title, c.notes,
+                c.photo_url, c.birthday, c.anniversary, c.vcard, c.etag,
+                c.created_at, c.updated_at
+            FROM (
+                SELECT DISTINCT uid
+                FROM carddav.address_book_changes
+                WHERE address_book_id = $1 AND sync_token > $2
+            ) ch
+            LEFT JOIN carddav.contacts c
+                ON c.address_book_id = $1 AND c.uid = ch.uid
+            ORDER BY ch.uid
+            "#,
+        )
+        .bind(address_book_id)
+        .bind(since)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            DomainError::database_error(format!("Failed to get address book changes: {}", e))
+        })?;
+
+        let mut changed = Vec::new();
+        let mut deleted = Vec::new();
+        for row in &rows {
+            if row.get::<Option<Uuid>, _>("id").is_some() {
+                changed.push(Self::row_to_contact(row)?);
+            } else {
+                deleted.push(row.get("changed_uid"));
+            }
+        }
+
+        Ok(Some(ContactChanges {
+            sync_token,
+            changed,
+            deleted,
+        }))
+    }
+
+    async fn prune_changes(&self, before: DateTime<Utc>) -> ContactRepositoryResult<u64> {
+        let pruned = sqlx::query_scalar::<_, i64>(
+            "SELECT carddav.prune_address_book_changes(now() - $1::timestamptz)",
+        )
+        .bind(before)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| {
+            DomainError::database_error(format!("Failed to prune address book changes: {}", e))
+        })?;
+        Ok(pruned.max(0) as u64)
+    }
 }
@@ -0,0 +1,170 @@
+//! PostgreSQL repository for WebDAV dead properties (`storage.dead_properties`).
+//!
+//! Rows reference either a file or a folder; the foreign keys delete them
+//! with the resource, so this repository never has to clean up after deletes.
+
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::application::adapters::webdav_adapter::{PropValue, QualifiedName};
+use crate::application::ports::dead_property_ports::DeadPropertyRepositoryPort;
+use crate::common::errors::{DomainError, Result};
+
+/// PostgreSQL implementation of the dead property persistence port.
+pub struct DeadPropertyPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl DeadPropertyPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    /// The column holding the resource reference.
+    fn id_column(is_folder: bool) -> &'static str {
+        if is_folder { "folder_id" } else { "file_id" }
+    }
+}
+
+impl DeadPropertyRepositoryPort for DeadPropertyPgRepository {
+    async fn list_properties(&self, resource_ids: &[String]) -> Result<Vec<(String, PropValue)>> {
+        if resource_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+            r#"
+            SELECT resource_id::text, namespace, name, value
+              FROM storage.dead_properties
+             WHERE resource_id = ANY($1::uuid[])
+             ORDER BY resource_id, namespace, name
+            "#,
+        )
+        .bind(resource_ids)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("DeadProperties", format!("list: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, namespace, name, value)| {
+                (
+                    id,
+                    PropValue {
+                        name: QualifiedName::new(namespace, name),
+                        value,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn update_properties(
+        &self,
+        resource_id: &str,
+        is_folder: bool,
+        set: &[PropValue],
+        remove: &[QualifiedName],
+    ) -> Result<()> {
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                DomainError::internal_error("DeadProperties", format!("begin: {e}"))
+            })?;
+
+        if !remove.is_empty() {
+            let namespaces: Vec<&str> = remove.iter().map(|q| q.namespace.as_str()).collect();
+            let names: Vec<&str> = remove.iter().map(|q| q.name.as_str()).collect();
+            sqlx::query(
+                r#"
+                DELETE FROM storage.dead_properties p
+                 USING UNNEST($2::text[], $3::text[]) AS r(namespace, name)
+                 WHERE p.resource_id = $1::uuid
+                   AND p.namespace = r.namespace
+                   AND p.name = r.name
+                "#,
+            )
+            .bind(resource_id)
+            .bind(&namespaces)
+            .bind(&names)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::internal_error("DeadProperties", format!("remove: {e}")))?;
+        }
+
+        if !set.is_empty() {
+            let namespaces: Vec<&str> = set.iter().map(|p| p.name.namespace.as_str()).collect();
+            let names: Vec<&str> = set.iter().map(|p| p.name.name.as_str()).collect();
+            let values: Vec<Option<&str>> = set.iter().map(|p| p.value.as_deref()).collect();
+            let sql = format!(
+                r#"
+                INSERT INTO storage.dead_properties ({}, namespace, name, value)
+                SELECT $1::uuid, s.namespace, s.name, s.value
+                  FROM UNNEST($2::text[], $3::text[], $4::text[]) AS s(namespace, name, value)
+                ON CONFLICT (resource_id, namespace, name) DO UPDATE
+                    SET value = EXCLUDED.value, updated_at = CURRENT_TIMESTAMP
+                "#,
+                Self::id_column(is_folder)
+            );
+            sqlx::query(&sql)
+                .bind(resource_id)
+                .bind(&namespaces)
+                .bind(&names)
+                .bind(&values)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| DomainError::internal_error("DeadProperties", format!("set: {e}")))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::internal_error("DeadProperties", format!("commit: {e}")))
+    }
+
+    async fn copy_properties(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        is_folder: bool,
+    ) -> Result<()> {
+        let sql = format!(
+            r#"
+            INSERT INTO storage.dead_properties ({}, namespace, name, value)
+            SELECT $2::uuid, namespace, name, value
+              FROM storage.dead_properties
+             WHERE resource_id = $1::uuid
+            ON CONFLICT (resource_id, namespace, name) DO UPDATE
+                SET value = EXCLUDED.value, updated_at = CURRENT_TIMESTAMP
+            "#,
+            Self::id_column(is_folder)
+        );
+        sqlx::query(&sql)
+            .bind(source_id)
+            .bind(target_id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| DomainError::internal_error("DeadProperties", format!("copy: {e}")))?;
+        Ok(())
+    }
+
+    async fn copy_tree_properties(
+        &self,
+        source_folder_id: &str,
+        target_folder_id: &str,
+    ) -> Result<u64> {
+        let copied = sqlx::query_scalar::<_, i64>(
+            "SELECT storage.copy_dead_properties_tree($1::uuid, $2::uuid)",
+        )
+        .bind(source_folder_id)
+        .bind(target_folder_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(ref db_err) = e
+                && db_err.code().as_deref() == Some("P0002")
+            {
+                return DomainError::not_found("Folder", source_folder_id);
+            }
+            DomainError::internal_error("DeadProperties", format!("copy_tree: {e}"))
+        })?;
+        Ok(copied.max(0) as u64)
+    }
+}
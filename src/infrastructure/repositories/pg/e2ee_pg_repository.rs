@@ -0,0 +1,274 @@
+//! PostgreSQL repository for end-to-end encryption keys and folders
+//! (`e2ee` schema).
+
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::ports::e2ee_ports::{E2eeRepositoryPort, EncryptedFolder, UserKeys};
+use crate::common::errors::{DomainError, Result};
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    DomainError::internal_error("E2ee", format!("{context}: {e}"))
+}
+
+fn row_to_folder(row: &PgRow) -> EncryptedFolder {
+    EncryptedFolder {
+        folder_id: row.get("folder_id"),
+        owner_id: row.get("owner_id"),
+        metadata: row.get("metadata"),
+        lock_token: row.get("lock_token"),
+        locked_at: row.get("locked_at"),
+    }
+}
+
+/// PostgreSQL implementation of the end-to-end encryption port.
+pub struct E2eePgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl E2eePgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl E2eeRepositoryPort for E2eePgRepository {
+    async fn get_user_keys(&self, user_id: Uuid) -> Result<UserKeys> {
+        let row =
+            sqlx::query("SELECT public_key, private_key FROM e2ee.user_keys WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(self.pool.as_ref())
+                .await
+                .map_err(|e| db_error("get keys", e))?;
+        Ok(row
+            .map(|r| UserKeys {
+                public_key: r.get("public_key"),
+                private_key: r.get("private_key"),
+            })
+            .unwrap_or_default())
+    }
+
+    async fn set_public_key(&self, user_id: Uuid, public_key: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO e2ee.user_keys (user_id, public_key) VALUES ($1, $2) \
+             ON CONFLICT (user_id) DO UPDATE \
+               SET public_key = EXCLUDED.public_key, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(user_id)
+        .bind(public_key)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("set public key", e))?;
+        Ok(())
+    }
+
+    async fn set_private_key(&self, user_id: Uuid, private_key: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO e2ee.user_keys (user_id, private_key) VALUES ($1, $2) \
+             ON CONFLICT (user_id) DO UPDATE \
+               SET private_key = EXCLUDED.private_key, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(user_id)
+        .bind(private_key)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("set private key", e))?;
+        Ok(())
+    }
+
+    async fn public_keys_by_username(&self, usernames: &[String]) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query(
+            "SELECT u.username, k.public_key \
+               FROM e2ee.user_keys k \
+               JOIN auth.users u ON u.id = k.user_id \
+              WHERE u.username = ANY($1) AND k.public_key IS NOT NULL",
+        )
+        .bind(usernames)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list public keys", e))?;
+        Ok(rows
+            .iter()
+            .map(|r| (r.get("username"), r.get("public_key")))
+            .collect())
+    }
+
+    async fn get_server_key(&self) -> Result<Option<String>> {
+        sqlx::query_scalar("SELECT private_key FROM e2ee.server_key")
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("get server key", e))
+    }
+
+    async fn store_server_key(&self, private_key: &str) -> Result<String> {
+        // Two instances starting at once may both generate a key; the first
+        // insert wins and both return it.
+        sqlx::query("INSERT INTO e2ee.server_key (private_key) VALUES ($1) ON CONFLICT DO NOTHING")
+            .bind(private_key)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("store server key", e))?;
+        sqlx::query_scalar("SELECT private_key FROM e2ee.server_key")
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("get server key", e))
+    }
+
+    async fn get_folder(&self, folder_id: &str) -> Result<Option<EncryptedFolder>> {
+        let row = sqlx::query(
+            "SELECT folder_id::text, owner_id, metadata, lock_token, locked_at \
+               FROM e2ee.folders WHERE folder_id = $1::uuid",
+        )
+        .bind(folder_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("get folder", e))?;
+        Ok(row.as_ref().map(row_to_folder))
+    }
+
+    async fn mark_encrypted(&self, folder_id: &str, owner_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO e2ee.folders (folder_id, owner_id) VALUES ($1::uuid, $2) \
+             ON CONFLICT (folder_id) DO NOTHING",
+        )
+        .bind(folder_id)
+        .bind(owner_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("mark folder", e))?;
+        Ok(())
+    }
+
+    async fn unmark_encrypted(&self, folder_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM e2ee.folders WHERE folder_id = $1::uuid")
+            .bind(folder_id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("unmark folder", e))?;
+        Ok(())
+    }
+
+    async fn set_metadata(&self, folder_id: &str, metadata: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE e2ee.folders SET metadata = $2 WHERE folder_id = $1::uuid")
+            .bind(folder_id)
+            .bind(metadata)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("set metadata", e))?;
+        Ok(())
+    }
+
+    async fn acquire_lock(
+        &self,
+        folder_id: &str,
+        new_token: &str,
+        current: Option<&str>,
+        timeout_minutes: i32,
+    ) -> Result<Option<String>> {
+        sqlx::query_scalar::<_, String>(
+            "UPDATE e2ee.folders \
+                SET lock_token = CASE WHEN lock_token = $3 THEN lock_token ELSE $2 END, \
+                    locked_at = CURRENT_TIMESTAMP \
+              WHERE folder_id = $1::uuid \
+                AND (lock_token IS NULL \
+                     OR locked_at IS NULL \
+                     OR locked_at < CURRENT_TIMESTAMP - make_interval(mins => $4) \
+                     OR lock_token = $3) \
+          RETURNING lock_token",
+        )
+        .bind(folder_id)
+        .bind(new_token)
+        .bind(current)
+        .bind(timeout_minutes)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("acquire lock", e))
+    }
+
+    async fn release_lock(
+        &self,
+        folder_id: &str,
+        token: Option<&str>,
+        timeout_minutes: i32,
+    ) -> Result<bool> {
+        let released = sqlx::query(
+            "UPDATE e2ee.folders \
+                SET lock_token = NULL, locked_at = NULL \
+              WHERE folder_id = $1::uuid \
+                AND (lock_token IS NULL \
+                     OR locked_at IS NULL \
+                     OR locked_at < CURRENT_TIMESTAMP - make_interval(mins => $3) \
+                     OR lock_token = $2)",
+        )
+        .bind(folder_id)
+        .bind(token)
+        .bind(timeout_minutes)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("release lock", e))?
+        .rows_affected();
+        Ok(released > 0)
+    }
+
+    async fn encrypted_ancestors(&self, item_id: &str) -> Result<Vec<EncryptedFolder>> {
+        // Non-UUID ids (e.g. virtual entries) cannot be inside a folder.
+        let Ok(item_id) = Uuid::parse_str(item_id) else {
+            return Ok(Vec::new());
+        };
+        let rows = sqlx::query(
+            r#"
+            WITH target AS (
+                SELECT lpath FROM storage.folders WHERE id = $1
+                UNION ALL
+                SELECT fo.lpath
+                  FROM storage.files fi
+                  JOIN storage.folders fo ON fo.id = fi.folder_id
+                 WHERE fi.id = $1
+            )
+            SELECT ef.folder_id::text, ef.owner_id, ef.metadata, ef.lock_token, ef.locked_at
+              FROM target t
+              JOIN storage.folders a ON t.lpath <@ a.lpath
+              JOIN e2ee.folders ef ON ef.folder_id = a.id
+             ORDER BY nlevel(a.lpath) DESC
+            "#,
+        )
+        .bind(item_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find encrypted ancestors", e))?;
+        Ok(rows.iter().map(row_to_folder).collect())
+    }
+
+    async fn filter_encrypted(&self, folder_ids: &[String]) -> Result<HashSet<String>> {
+        let ids: Vec<Uuid> = folder_ids
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+        if ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let rows: Vec<String> = sqlx::query_scalar(
+            "SELECT folder_id::text FROM e2ee.folders WHERE folder_id = ANY($1)",
+        )
+        .bind(&ids)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("filter encrypted", e))?;
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn is_folder_empty(&self, folder_id: &str) -> Result<bool> {
+        sqlx::query_scalar(
+            "SELECT NOT EXISTS (SELECT 1 FROM storage.files \
+                                 WHERE folder_id = $1::uuid AND NOT is_trashed) \
+                AND NOT EXISTS (SELECT 1 FROM storage.folders \
+                                 WHERE parent_id = $1::uuid AND NOT is_trashed)",
+        )
+        .bind(folder_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("check folder empty", e))
+    }
+}
@@ -798,6 +798,7 @@ impl FileReadPort for FileBlobReadRepository {
         let mut conditions: Vec<String> = vec![
             "fi.user_id = $1".to_string(),
             "fi.is_trashed = false".to_string(),
+            super::NOT_IN_ENCRYPTED_FOLDER.to_string(),
         ];
         let mut bind_idx = 1u32; // $1 = user_id
 
@@ -929,6 +930,7 @@ impl FileReadPort for FileBlobReadRepository {
         conditions.push(
             "fo.lpath <@ (SELECT lpath FROM storage.folders WHERE id = $2::uuid)".to_string(),
         );
+        conditions.push(super::NOT_IN_ENCRYPTED_FOLDER.to_string());
 
         if let Some(name) = &criteria.name_contains
             && name.len() >= 3
@@ -20,11 +20,17 @@ use crate::domain::services::path_service::StoragePath;
 use super::folder_db_repository::FolderDbRepository;
 use crate::infrastructure::services::dedup_service::DedupService;
 
+/// Blob hash stored by `register_file_deferred` until real content arrives.
+const PLACEHOLDER_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
 /// File write repository backed by PostgreSQL metadata + blob storage.
 pub struct FileBlobWriteRepository {
     pool: Arc<PgPool>,
     dedup: Arc<DedupService>,
     folder_repo: Arc<FolderDbRepository>,
+    /// When `Some(n)`, replaced content is kept in `storage.file_versions`
+    /// (at most `n` versions per file). `None` disables version history.
+    max_versions: Option<u32>,
 }
 
 impl FileBlobWriteRepository {
@@ -37,9 +43,18 @@ impl FileBlobWriteRepository {
             pool,
             dedup,
             folder_repo,
+            max_versions: None,
         }
     }
 
+    /// Keep up to `max_versions` previous contents per file on overwrite.
+    ///
+    /// A value of `0` disables version history.
+    pub fn with_versioning(mut self, max_versions: u32) -> Self {
+        self.max_versions = (max_versions > 0).then_some(max_versions);
+        self
+    }
+
     /// Creates a stub instance for testing — never hits PG.
     #[cfg(test)]
     pub fn new_stub() -> Self {
@@ -53,6 +68,7 @@ impl FileBlobWriteRepository {
             ),
             dedup: Arc::new(DedupService::new_stub()),
             folder_repo: Arc::new(super::folder_db_repository::FolderDbRepository::new_stub()),
+            max_versions: None,
         }
     }
 
@@ -144,28 +160,48 @@ impl FileBlobWriteRepository {
         new_size: i64,
         modified_at: Option<i64>,
     ) -> Result<String, DomainError> {
-        // Atomic CTE: capture old hash then update in one round-trip, no TOCTOU.
-        let old_hash = match sqlx::query_scalar::<_, String>(
+        // Atomic CTE: capture old row, update, and (when versioning is on)
+        // record the replaced content in one round-trip, no TOCTOU.
+        // The version row takes over the reference the file held on the
+        // old blob, so no ref_count change is needed for it.
+        let (old_hash, versioned) = match sqlx::query_as::<_, (String, bool)>(
             r#"
             WITH old AS (
-                SELECT id, blob_hash FROM storage.files WHERE id = $3::uuid FOR UPDATE
+                SELECT id, user_id, blob_hash, size, mime_type, updated_at
+                  FROM storage.files WHERE id = $3::uuid FOR UPDATE
+            ),
+            upd AS (
+                UPDATE storage.files f
+                   SET blob_hash = $1, size = $2,
+                       updated_at = COALESCE(to_timestamp($4), NOW())
+                  FROM old
+                 WHERE f.id = old.id
+                RETURNING old.id, old.user_id, old.blob_hash, old.size,
+                          old.mime_type, old.updated_at
+            ),
+            ver AS (
+                INSERT INTO storage.file_versions
+                       (file_id, user_id, blob_hash, size, mime_type, modified_at)
+                SELECT id, user_id, blob_hash, size, mime_type, updated_at
+                  FROM upd
+                 WHERE $5
+                   AND blob_hash <> $1
+                   AND blob_hash <> $6
+                RETURNING id
             )
-            UPDATE storage.files f
-               SET blob_hash = $1, size = $2,
-                   updated_at = COALESCE(to_timestamp($4), NOW())
-              FROM old
-             WHERE f.id = old.id
-            RETURNING old.blob_hash
+            SELECT upd.blob_hash, EXISTS (SELECT 1 FROM ver) FROM upd
             "#,
         )
         .bind(new_hash)
         .bind(new_size)
         .bind(file_id)
         .bind(modified_at.map(|t| t as f64))
+        .bind(self.max_versions.is_some())
+        .bind(PLACEHOLDER_HASH)
         .fetch_optional(self.pool.as_ref())
         .await
         {
-            Ok(Some(old)) => old,
+            Ok(Some(row)) => row,
             Ok(None) => {
                 // File not found — compensate: remove the new blob ref
                 if let Err(e) = self.dedup.remove_reference(new_hash).await {
@@ -189,10 +225,14 @@ impl FileBlobWriteRepository {
             }
         };
 
-        // Decrement old blob ref (only if hash changed, best-effort)
-        if old_hash != new_hash
+        if versioned {
+            if let Some(max) = self.max_versions {
+                self.prune_versions(file_id, max).await;
+            }
+        } else if old_hash != new_hash
             && let Err(e) = self.dedup.remove_reference(&old_hash).await
         {
+            // Decrement old blob ref (only if hash changed, best-effort)
             tracing::warn!(
                 "Failed to decrement old blob ref {}: {}",
                 &old_hash[..12],
@@ -202,6 +242,37 @@ impl FileBlobWriteRepository {
 
         Ok(new_hash.to_string())
     }
+
+    /// Drop the oldest versions of a file beyond `max`, releasing their
+    /// blob references. Best-effort: failures are logged, not returned.
+    async fn prune_versions(&self, file_id: &str, max: u32) {
+        // DELETE fires trg_file_versions_decrement_blob_ref → ref_count--
+        let pruned = sqlx::query_scalar::<_, String>(
+            r#"
+            DELETE FROM storage.file_versions
+             WHERE id IN (
+                SELECT id FROM storage.file_versions
+                 WHERE file_id = $1::uuid
+                 ORDER BY created_at DESC
+                 OFFSET $2
+             )
+            RETURNING blob_hash
+            "#,
+        )
+        .bind(file_id)
+        .bind(max as i64)
+        .fetch_all(self.pool.as_ref())
+        .await;
+
+        match pruned {
+            Ok(hashes) => {
+                for hash in &hashes {
+                    self.dedup.cleanup_if_orphaned(hash).await;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to prune versions of {}: {}", file_id, e),
+        }
+    }
 }
 
 impl FileWritePort for FileBlobWriteRepository {
@@ -334,6 +405,8 @@ impl FileWritePort for FileBlobWriteRepository {
     ) -> Result<File, DomainError> {
         // Atomic CTE: read source file → insert new row with same blob_hash → increment ref_count.
         // Single round-trip; blob content is NOT copied (dedup makes this zero-copy).
+        // The copy belongs to the owner of the folder it lands in, which is
+        // not the source owner when copying out of or into a shared folder.
         let target_fid = target_folder_id.clone();
 
         let row = sqlx::query_as::<
@@ -359,7 +432,11 @@ impl FileWritePort for FileBlobWriteRepository {
                 INSERT INTO storage.files (name, folder_id, user_id, blob_hash, size, mime_type)
                 SELECT name,
                        COALESCE($2::uuid, folder_id),
-                       user_id,
+                       COALESCE(
+                           (SELECT fo.user_id FROM storage.folders fo
+                             WHERE fo.id = COALESCE($2::uuid, src.folder_id)),
+                           user_id
+                       ),
                        blob_hash,
                        size,
                        mime_type
@@ -499,6 +576,48 @@ impl FileWritePort for FileBlobWriteRepository {
             .await
     }
 
+    async fn restore_blob_hash(
+        &self,
+        file_id: &str,
+        blob_hash: &str,
+        size: u64,
+    ) -> Result<String, DomainError> {
+        let current = sqlx::query_scalar::<_, String>(
+            "SELECT blob_hash FROM storage.files WHERE id = $1::uuid AND NOT is_trashed",
+        )
+        .bind(file_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("FileBlobWrite", format!("restore: {e}")))?
+        .ok_or_else(|| DomainError::not_found("File", file_id))?;
+
+        // Same content: the swap would neither record a version nor drop
+        // the file's reference, so taking one here would leak it.
+        if current == blob_hash {
+            return Ok(current);
+        }
+
+        // The file row takes a fresh reference; the version row keeps its own.
+        self.dedup.add_reference(blob_hash).await?;
+        self.swap_blob_hash(file_id, blob_hash, size as i64, None)
+            .await
+    }
+
+    async fn set_modified_time(&self, file_id: &str, modified_at: i64) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            "UPDATE storage.files SET updated_at = to_timestamp($2) WHERE id = $1::uuid AND NOT is_trashed",
+        )
+        .bind(file_id)
+        .bind(modified_at as f64)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("FileBlobWrite", format!("set mtime: {e}")))?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("File", file_id));
+        }
+        Ok(())
+    }
+
     async fn register_file_deferred(
         &self,
         name: String,
@@ -510,8 +629,6 @@ impl FileWritePort for FileBlobWriteRepository {
 
         // For deferred registration we use a placeholder hash.
         // The write-behind cache will call update_file_content later.
-        let placeholder_hash = "0000000000000000000000000000000000000000000000000000000000000000";
-
         let row = sqlx::query_as::<_, (String, i64, i64)>(
             r#"
             INSERT INTO storage.files (name, folder_id, user_id, blob_hash, size, mime_type)
@@ -524,7 +641,7 @@ impl FileWritePort for FileBlobWriteRepository {
         .bind(&name)
         .bind(&folder_id)
         .bind(user_id)
-        .bind(placeholder_hash)
+        .bind(PLACEHOLDER_HASH)
         .bind(size as i64)
         .bind(&content_type)
         .fetch_one(self.pool.as_ref())
@@ -604,23 +721,27 @@ impl FileWritePort for FileBlobWriteRepository {
     }
 
     async fn delete_file_permanently(&self, file_id: &str) -> Result<(), DomainError> {
-        // Read blob_hash before deletion so we can clean up disk after the
-        // PG trigger has decremented the ref_count.
-        let blob_hash: Option<String> =
-            sqlx::query_scalar("SELECT blob_hash FROM storage.files WHERE id = $1::uuid")
-                .bind(file_id)
-                .fetch_optional(self.pool.as_ref())
-                .await
-                .map_err(|e| {
-                    DomainError::internal_error("FileBlobWrite", format!("fetch blob_hash: {e}"))
-                })?;
+        // Read blob hashes (file + its versions) before deletion so we can
+        // clean up disk after the PG triggers have decremented the ref_counts.
+        let blob_hashes: Vec<String> = sqlx::query_scalar(
+            "SELECT blob_hash FROM storage.files WHERE id = $1::uuid \
+             UNION ALL \
+             SELECT blob_hash FROM storage.file_versions WHERE file_id = $1::uuid",
+        )
+        .bind(file_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| {
+            DomainError::internal_error("FileBlobWrite", format!("fetch blob_hash: {e}"))
+        })?;
 
         // DELETE fires trg_files_decrement_blob_ref → storage.blobs.ref_count--
+        // and cascades to storage.file_versions (same trigger per version).
         self.delete_file(file_id).await?;
 
-        // If the blob is now unreferenced, remove disk file + thumbnails.
-        if let Some(hash) = blob_hash {
-            self.dedup.cleanup_if_orphaned(&hash).await;
+        // If a blob is now unreferenced, remove disk file + thumbnails.
+        for hash in &blob_hashes {
+            self.dedup.cleanup_if_orphaned(hash).await;
         }
 
         Ok(())
@@ -0,0 +1,127 @@
+//! PostgreSQL repository for file version history (`storage.file_versions`).
+//!
+//! Versions are *written* by `FileBlobWriteRepository` inside the same
+//! statement that swaps a file's blob, so this repository only reads and
+//! deletes them.  Every query is scoped to the owner of the parent file.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::file_version_dto::FileVersionDto;
+use crate::application::ports::file_version_ports::FileVersionRepositoryPort;
+use crate::common::errors::{DomainError, Result};
+
+type VersionRow = (
+    String,
+    String,
+    String,
+    String,
+    i64,
+    String,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+/// PostgreSQL implementation of the file version persistence port.
+pub struct FileVersionPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl FileVersionPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_dto(row: VersionRow) -> FileVersionDto {
+        FileVersionDto {
+            id: row.0,
+            file_id: row.1,
+            name: row.2,
+            etag: row.3,
+            size: row.4.max(0) as u64,
+            mime_type: row.5,
+            modified_at: row.6,
+            created_at: row.7,
+        }
+    }
+}
+
+impl FileVersionRepositoryPort for FileVersionPgRepository {
+    async fn list_versions(&self, file_id: &str, owner_id: Uuid) -> Result<Vec<FileVersionDto>> {
+        let rows = sqlx::query_as::<_, VersionRow>(
+            r#"
+            SELECT v.id::text, v.file_id::text, f.name, v.blob_hash, v.size, v.mime_type,
+                   v.modified_at, v.created_at
+              FROM storage.file_versions v
+              JOIN storage.files f ON f.id = v.file_id
+             WHERE v.file_id = $1::uuid
+               AND f.user_id = $2
+             ORDER BY v.created_at DESC
+            "#,
+        )
+        .bind(file_id)
+        .bind(owner_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("FileVersions", format!("list: {e}")))?;
+
+        Ok(rows.into_iter().map(Self::row_to_dto).collect())
+    }
+
+    async fn get_version(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<FileVersionDto> {
+        let row = sqlx::query_as::<_, VersionRow>(
+            r#"
+            SELECT v.id::text, v.file_id::text, f.name, v.blob_hash, v.size, v.mime_type,
+                   v.modified_at, v.created_at
+              FROM storage.file_versions v
+              JOIN storage.files f ON f.id = v.file_id
+             WHERE v.id = $1::uuid
+               AND v.file_id = $2::uuid
+               AND f.user_id = $3
+            "#,
+        )
+        .bind(version_id)
+        .bind(file_id)
+        .bind(owner_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("FileVersions", format!("get: {e}")))?
+        .ok_or_else(|| DomainError::not_found("FileVersion", version_id))?;
+
+        Ok(Self::row_to_dto(row))
+    }
+
+    async fn delete_version(
+        &self,
+        file_id: &str,
+        version_id: &str,
+        owner_id: Uuid,
+    ) -> Result<String> {
+        // DELETE fires trg_file_versions_decrement_blob_ref → ref_count--
+        sqlx::query_scalar::<_, String>(
+            r#"
+            DELETE FROM storage.file_versions v
+             USING storage.files f
+             WHERE f.id = v.file_id
+               AND v.id = $1::uuid
+               AND v.file_id = $2::uuid
+               AND f.user_id = $3
+            RETURNING v.blob_hash
+            "#,
+        )
+        .bind(version_id)
+        .bind(file_id)
+        .bind(owner_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("FileVersions", format!("delete: {e}")))?
+        .ok_or_else(|| DomainError::not_found("FileVersion", version_id))
+    }
+}
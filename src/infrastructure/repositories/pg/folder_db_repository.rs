@@ -722,6 +722,8 @@ impl FolderRepository for FolderDbRepository {
                 .await;
         }
 
+        let not_encrypted = super::NOT_BELOW_ENCRYPTED_FOLDER;
+
         // Build optional name filter — use ILIKE (case-insensitive) so the
         // GIN trigram index idx_folders_name_trgm is used instead of a seq scan.
         let (name_clause, name_pattern) = match name_contains {
@@ -746,6 +748,7 @@ impl FolderRepository for FolderDbRepository {
                    FROM storage.folders fo \
                   WHERE fo.user_id = $1 \
                     AND fo.is_trashed = false \
+                    AND {not_encrypted} \
                     {name_clause} \
                   ORDER BY fo.name"
             );
@@ -783,6 +786,7 @@ impl FolderRepository for FolderDbRepository {
                   WHERE fo.parent_id = $1::uuid \
                     AND fo.user_id = $2 \
                     AND fo.is_trashed = false \
+                    AND {not_encrypted} \
                     {name_clause} \
                   ORDER BY fo.name"
             )
@@ -801,6 +805,7 @@ impl FolderRepository for FolderDbRepository {
                   WHERE fo.parent_id IS NULL \
                     AND fo.user_id = $1 \
                     AND fo.is_trashed = false \
+                    AND {not_encrypted} \
                     {name_clause_root} \
                   ORDER BY fo.name"
             )
@@ -853,6 +858,7 @@ impl FolderRepository for FolderDbRepository {
         name_contains: Option<&str>,
         user_id: Uuid,
     ) -> Result<Vec<Folder>, DomainError> {
+        let not_encrypted = super::NOT_BELOW_ENCRYPTED_FOLDER;
         let (where_extra, name_pattern) = match name_contains {
             Some(name) if name.len() >= 3 => {
                 (" AND fo.name ILIKE $3", Some(super::like_escape(name)))
@@ -870,6 +876,7 @@ impl FolderRepository for FolderDbRepository {
                 AND fo.is_trashed = false \
                 AND fo.lpath <@ (SELECT lpath FROM storage.folders WHERE id = $2::uuid) \
                 AND fo.id != $2::uuid \
+                AND {not_encrypted} \
                 {where_extra} \
               ORDER BY fo.name"
         );
@@ -1044,4 +1051,22 @@ impl FolderDbRepository {
             .map_err(|e| DomainError::internal_error("FolderDb", format!("user_id lookup: {e}")))?
             .ok_or_else(|| DomainError::not_found("Folder", folder_id))
     }
+
+    /// Total size of the live files in a subtree (inclusive) — what a
+    /// copy of it adds to its new owner's usage.
+    pub async fn get_subtree_file_size(&self, folder_id: &str) -> Result<u64, DomainError> {
+        let size: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(f.size), 0)::bigint \
+               FROM storage.files f \
+               JOIN storage.folders fo ON fo.id = f.folder_id \
+              WHERE NOT f.is_trashed \
+                AND NOT fo.is_trashed \
+                AND fo.lpath <@ (SELECT lpath FROM storage.folders WHERE id = $1::uuid)",
+        )
+        .bind(folder_id)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| DomainError::internal_error("FolderDb", format!("subtree size: {e}")))?;
+        Ok(size.max(0) as u64)
+    }
 }
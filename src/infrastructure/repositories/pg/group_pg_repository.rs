@@ -0,0 +1,320 @@
+//! PostgreSQL repository for user groups (`auth.groups`, `auth.group_members`).
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::group_dto::GroupMemberDto;
+use crate::application::ports::group_ports::{GroupRepositoryPort, MembershipSource};
+use crate::common::errors::{DomainError, Result};
+use crate::domain::entities::group::Group;
+
+type GroupRow = (
+    Uuid,
+    String,
+    Option<String>,
+    Option<i64>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+const GROUP_COLUMNS: &str = "g.id, g.name, g.description, g.default_quota_bytes, \
+     g.created_at, g.updated_at";
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    if let sqlx::Error::Database(ref db_err) = e {
+        match db_err.code().as_deref() {
+            Some("23505") => {
+                return DomainError::already_exists("Group", "a group with this name exists");
+            }
+            // A membership for a user or group deleted meanwhile
+            Some("23503") => return DomainError::not_found("Group", "group or user"),
+            _ => {}
+        }
+    }
+    DomainError::internal_error("Groups", format!("{context}: {e}"))
+}
+
+fn row_to_group(row: GroupRow) -> Group {
+    Group::from_data(row.0, row.1, row.2, row.3, row.4, row.5)
+}
+
+/// PostgreSQL implementation of the group persistence port.
+pub struct GroupPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl GroupPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl GroupRepositoryPort for GroupPgRepository {
+    async fn create_group(&self, group: &Group) -> Result<Group> {
+        let row = sqlx::query_as::<_, GroupRow>(&format!(
+            "INSERT INTO auth.groups AS g \
+                 (id, name, description, default_quota_bytes, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             RETURNING {GROUP_COLUMNS}"
+        ))
+        .bind(group.id())
+        .bind(group.name())
+        .bind(group.description())
+        .bind(group.default_quota_bytes())
+        .bind(group.created_at())
+        .bind(group.updated_at())
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("create group", e))?;
+        Ok(row_to_group(row))
+    }
+
+    async fn get_group(&self, id: Uuid) -> Result<Group> {
+        sqlx::query_as::<_, GroupRow>(&format!(
+            "SELECT {GROUP_COLUMNS} FROM auth.groups g WHERE g.id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("get group", e))?
+        .map(row_to_group)
+        .ok_or_else(|| DomainError::not_found("Group", id.to_string()))
+    }
+
+    async fn list_groups(&self, offset: i64, limit: i64) -> Result<(Vec<(Group, u64)>, u64)> {
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM auth.groups")
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("count groups", e))?;
+
+        let rows = sqlx::query_as::<
+            _,
+            (
+                Uuid,
+                String,
+                Option<String>,
+                Option<i64>,
+                DateTime<Utc>,
+                DateTime<Utc>,
+                i64,
+            ),
+        >(&format!(
+            "SELECT {GROUP_COLUMNS}, \
+                        (SELECT COUNT(*) FROM auth.group_members m WHERE m.group_id = g.id) \
+                 FROM auth.groups g \
+                 ORDER BY lower(g.name) \
+                 LIMIT $1 OFFSET $2"
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list groups", e))?;
+
+        let groups = rows
+            .into_iter()
+            .map(|r| {
+                let count = r.6 as u64;
+                (row_to_group((r.0, r.1, r.2, r.3, r.4, r.5)), count)
+            })
+            .collect();
+        Ok((groups, total as u64))
+    }
+
+    async fn update_group(&self, group: &Group) -> Result<Group> {
+        sqlx::query_as::<_, GroupRow>(&format!(
+            "UPDATE auth.groups AS g \
+             SET name = $2, description = $3, default_quota_bytes = $4, updated_at = $5 \
+             WHERE g.id = $1 \
+             RETURNING {GROUP_COLUMNS}"
+        ))
+        .bind(group.id())
+        .bind(group.name())
+        .bind(group.description())
+        .bind(group.default_quota_bytes())
+        .bind(group.updated_at())
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("update group", e))?
+        .map(row_to_group)
+        .ok_or_else(|| DomainError::not_found("Group", group.id().to_string()))
+    }
+
+    async fn delete_group(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM auth.groups WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("delete group", e))?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("Group", id.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn member_count(&self, group_id: Uuid) -> Result<u64> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM auth.group_members WHERE group_id = $1")
+                .bind(group_id)
+                .fetch_one(self.pool.as_ref())
+                .await
+                .map_err(|e| db_error("count members", e))?;
+        Ok(count as u64)
+    }
+
+    async fn list_members(&self, group_id: Uuid) -> Result<Vec<GroupMemberDto>> {
+        let rows = sqlx::query_as::<_, (Uuid, String, String, String, DateTime<Utc>)>(
+            "SELECT u.id, u.username, u.email, m.source, m.added_at \
+             FROM auth.group_members m \
+             JOIN auth.users u ON u.id = m.user_id \
+             WHERE m.group_id = $1 \
+             ORDER BY lower(u.username)",
+        )
+        .bind(group_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list members", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, username, email, source, added_at)| GroupMemberDto {
+                user_id: id.to_string(),
+                username,
+                email,
+                source,
+                added_at,
+            })
+            .collect())
+    }
+
+    async fn add_member(
+        &self,
+        group_id: Uuid,
+        user_id: Uuid,
+        source: MembershipSource,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO auth.group_members (group_id, user_id, source) \
+             VALUES ($1, $2, $3) \
+             ON CONFLICT (group_id, user_id) DO NOTHING",
+        )
+        .bind(group_id)
+        .bind(user_id)
+        .bind(source.as_str())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("add member", e))?;
+        Ok(())
+    }
+
+    async fn remove_member(&self, group_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM auth.group_members WHERE group_id = $1 AND user_id = $2")
+                .bind(group_id)
+                .bind(user_id)
+                .execute(self.pool.as_ref())
+                .await
+                .map_err(|e| db_error("remove member", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn groups_of_user(&self, user_id: Uuid) -> Result<Vec<Group>> {
+        let rows = sqlx::query_as::<_, GroupRow>(&format!(
+            "SELECT {GROUP_COLUMNS} \
+             FROM auth.groups g \
+             JOIN auth.group_members m ON m.group_id = g.id \
+             WHERE m.user_id = $1 \
+             ORDER BY lower(g.name)"
+        ))
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list user groups", e))?;
+        Ok(rows.into_iter().map(row_to_group).collect())
+    }
+
+    async fn sync_memberships(
+        &self,
+        user_id: Uuid,
+        source: MembershipSource,
+        group_names: &[String],
+    ) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| db_error("begin group sync", e))?;
+
+        // Create the groups the directory knows about but we do not
+        sqlx::query(
+            "INSERT INTO auth.groups (name) \
+             SELECT DISTINCT ON (lower(n)) n FROM unnest($1::text[]) AS n \
+             ON CONFLICT ((lower(name))) DO NOTHING",
+        )
+        .bind(group_names)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("create synced groups", e))?;
+
+        // Drop this source's memberships of groups it no longer lists
+        sqlx::query(
+            "DELETE FROM auth.group_members m \
+             USING auth.groups g \
+             WHERE m.group_id = g.id AND m.user_id = $1 AND m.source = $2 \
+               AND lower(g.name) <> ALL (SELECT lower(n) FROM unnest($3::text[]) AS n)",
+        )
+        .bind(user_id)
+        .bind(source.as_str())
+        .bind(group_names)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("remove synced memberships", e))?;
+
+        // Add the missing ones; a membership of the same group from another
+        // source keeps that source
+        sqlx::query(
+            "INSERT INTO auth.group_members (group_id, user_id, source) \
+             SELECT g.id, $1, $2 FROM auth.groups g \
+             WHERE lower(g.name) IN (SELECT lower(n) FROM unnest($3::text[]) AS n) \
+             ON CONFLICT (group_id, user_id) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(source.as_str())
+        .bind(group_names)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("add synced memberships", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| db_error("commit group sync", e))?;
+        Ok(())
+    }
+
+    async fn apply_quota_defaults(&self, user_ids: &[Uuid]) -> Result<u64> {
+        if user_ids.is_empty() {
+            return Ok(0);
+        }
+        let result = sqlx::query(
+            "UPDATE auth.users u \
+             SET storage_quota_bytes = d.quota, updated_at = NOW() \
+             FROM ( \
+                 SELECT m.user_id, MAX(g.default_quota_bytes) AS quota \
+                 FROM auth.group_members m \
+                 JOIN auth.groups g ON g.id = m.group_id \
+                 WHERE m.user_id = ANY($1) \
+                 GROUP BY m.user_id \
+             ) d \
+             WHERE u.id = d.user_id \
+               AND u.storage_quota_bytes > 0 \
+               AND d.quota > u.storage_quota_bytes",
+        )
+        .bind(user_ids)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("apply group quotas", e))?;
+        Ok(result.rows_affected())
+    }
+}
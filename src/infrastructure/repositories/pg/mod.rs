@@ -1,3 +1,5 @@
+mod account_token_pg_repository;
+mod activity_pg_repository;
 mod address_book_pg_repository;
 mod app_password_pg_repository;
 mod calendar_event_pg_repository;
@@ -5,17 +7,28 @@ mod calendar_pg_repository;
 mod contact_group_pg_repository;
 mod contact_persistence_dto;
 mod contact_pg_repository;
+mod dead_property_pg_repository;
 mod device_code_pg_repository;
+mod e2ee_pg_repository;
 mod favorites_pg_repository;
 pub mod file_metadata_repository;
+mod file_version_pg_repository;
+mod group_pg_repository;
 mod nextcloud_object_id_repository;
+mod notification_pg_repository;
 pub mod playlist_pg_repository;
 mod recent_items_pg_repository;
+mod schedule_inbox_pg_repository;
 mod session_pg_repository;
 mod settings_pg_repository;
 mod share_pg_repository;
+mod snapshot_pg_repository;
 mod transaction_utils;
+mod two_factor_pg_repository;
 mod user_pg_repository;
+mod user_share_pg_repository;
+mod webauthn_pg_repository;
+mod webhook_pg_repository;
 
 // ── Blob-storage repositories ──
 pub mod file_blob_read_repository;
@@ -23,6 +36,8 @@ pub mod file_blob_write_repository;
 pub mod folder_db_repository;
 pub mod trash_db_repository;
 
+pub use account_token_pg_repository::AccountTokenPgRepository;
+pub use activity_pg_repository::ActivityPgRepository;
 pub use address_book_pg_repository::AddressBookPgRepository;
 pub use app_password_pg_repository::AppPasswordPgRepository;
 pub use calendar_event_pg_repository::CalendarEventPgRepository;
@@ -30,22 +45,33 @@ pub use calendar_pg_repository::CalendarPgRepository;
 pub use contact_group_pg_repository::ContactGroupPgRepository;
 pub use contact_persistence_dto::*;
 pub use contact_pg_repository::ContactPgRepository;
+pub use dead_property_pg_repository::DeadPropertyPgRepository;
 pub use device_code_pg_repository::DeviceCodePgRepository;
+pub use e2ee_pg_repository::E2eePgRepository;
 pub use favorites_pg_repository::FavoritesPgRepository;
 pub use file_blob_read_repository::FileBlobReadRepository;
 pub use file_blob_write_repository::FileBlobWriteRepository;
 pub use file_metadata_repository::FileMetadataRepository;
+pub use file_version_pg_repository::FileVersionPgRepository;
 pub use folder_db_repository::FolderDbRepository;
+pub use group_pg_repository::GroupPgRepository;
 pub use nextcloud_object_id_repository::NextcloudObjectIdRepository;
+pub use notification_pg_repository::NotificationPgRepository;
 pub use playlist_pg_repository::{
     AudioMetadataPgRepository, PlaylistItemPgRepository, PlaylistPgRepository,
 };
 pub use recent_items_pg_repository::RecentItemsPgRepository;
+pub use schedule_inbox_pg_repository::ScheduleInboxPgRepository;
 pub use session_pg_repository::SessionPgRepository;
 pub use settings_pg_repository::SettingsPgRepository;
 pub use share_pg_repository::SharePgRepository;
+pub use snapshot_pg_repository::SnapshotPgRepository;
 pub use trash_db_repository::TrashDbRepository;
+pub use two_factor_pg_repository::TwoFactorPgRepository;
 pub use user_pg_repository::UserPgRepository;
+pub use user_share_pg_repository::UserSharePgRepository;
+pub use webauthn_pg_repository::WebAuthnPgRepository;
+pub use webhook_pg_repository::WebhookPgRepository;
 
 // ── SQL helpers ─────────────────────────────────────────────────────────────
 
@@ -62,3 +88,15 @@ pub fn like_escape(raw: &str) -> String {
         .replace('_', "\\_");
     format!("%{escaped}%")
 }
+
+/// SQL condition that holds when the folder aliased `fo` is not an
+/// end-to-end encrypted folder or inside one.  Search uses it to leave out
+/// files whose contents and names only clients can read.
+pub const NOT_IN_ENCRYPTED_FOLDER: &str = "NOT EXISTS (SELECT 1 FROM e2ee.folders ef \
+       JOIN storage.folders ea ON ea.id = ef.folder_id WHERE fo.lpath <@ ea.lpath)";
+
+/// Like [`NOT_IN_ENCRYPTED_FOLDER`], but lets the encrypted folder itself
+/// through: its name is chosen in the clear, those of its subfolders are not.
+pub const NOT_BELOW_ENCRYPTED_FOLDER: &str = "NOT EXISTS (SELECT 1 FROM e2ee.folders ef \
+       JOIN storage.folders ea ON ea.id = ef.folder_id \
+       WHERE fo.lpath <@ ea.lpath AND fo.id <> ea.id)";
@@ -0,0 +1,238 @@
+//! PostgreSQL repository for notifications (`notifications` schema).
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::ports::notification_ports::{
+    ExpiringLink, NewNotification, NotificationRecord, NotificationRepositoryPort, QuotaUsage,
+};
+use crate::common::errors::{DomainError, Result};
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    DomainError::internal_error("Notification", format!("{context}: {e}"))
+}
+
+fn row_to_record(row: &PgRow) -> NotificationRecord {
+    NotificationRecord {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        kind: row.get("kind"),
+        subject: row.get("subject"),
+        message: row.get("message"),
+        link: row.get("link"),
+        object_type: row.get("object_type"),
+        object_id: row.get("object_id"),
+        is_read: row.get("is_read"),
+        created_at: row.get("created_at"),
+    }
+}
+
+const COLUMNS: &str =
+    "id, user_id, kind, subject, message, link, object_type, object_id, is_read, created_at";
+
+/// PostgreSQL implementation of the notification port.
+pub struct NotificationPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl NotificationPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl NotificationRepositoryPort for NotificationPgRepository {
+    async fn create(&self, n: &NewNotification) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO notifications.notifications \
+               (user_id, kind, subject, message, link, object_type, object_id) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(n.user_id)
+        .bind(n.kind.as_str())
+        .bind(&n.subject)
+        .bind(&n.message)
+        .bind(&n.link)
+        .bind(n.object_type)
+        .bind(&n.object_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("create notification", e))?;
+        Ok(())
+    }
+
+    async fn create_once(&self, n: &NewNotification, key: &str) -> Result<bool> {
+        // Claiming the key and inserting happen in one statement, so two
+        // instances running the same check cannot both notify.
+        let result = sqlx::query(
+            r#"
+            WITH claimed AS (
+                INSERT INTO notifications.sent (user_id, key) VALUES ($1, $8)
+                ON CONFLICT DO NOTHING
+                RETURNING user_id
+            )
+            INSERT INTO notifications.notifications
+                (user_id, kind, subject, message, link, object_type, object_id)
+            SELECT user_id, $2, $3, $4, $5, $6, $7 FROM claimed
+            "#,
+        )
+        .bind(n.user_id)
+        .bind(n.kind.as_str())
+        .bind(&n.subject)
+        .bind(&n.message)
+        .bind(&n.link)
+        .bind(n.object_type)
+        .bind(&n.object_id)
+        .bind(key)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("create notification", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn forget_quota_key(&self, key: &str, percent: u8) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM notifications.sent s USING auth.users u \
+              WHERE s.user_id = u.id AND s.key = $1 \
+                AND (u.storage_quota_bytes <= 0 \
+                     OR u.storage_used_bytes * 100 < u.storage_quota_bytes * $2)",
+        )
+        .bind(key)
+        .bind(i64::from(percent))
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("forget quota key", e))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn list(&self, user_id: Uuid, limit: usize) -> Result<(Vec<NotificationRecord>, usize)> {
+        let rows = sqlx::query(&format!(
+            "SELECT {COLUMNS} FROM notifications.notifications \
+              WHERE user_id = $1 ORDER BY id DESC LIMIT $2"
+        ))
+        .bind(user_id)
+        .bind(limit as i64)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list notifications", e))?;
+        let unread: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications.notifications \
+              WHERE user_id = $1 AND NOT is_read",
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("count notifications", e))?;
+        Ok((rows.iter().map(row_to_record).collect(), unread as usize))
+    }
+
+    async fn get(&self, user_id: Uuid, id: i64) -> Result<Option<NotificationRecord>> {
+        let row = sqlx::query(&format!(
+            "SELECT {COLUMNS} FROM notifications.notifications WHERE user_id = $1 AND id = $2"
+        ))
+        .bind(user_id)
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("get notification", e))?;
+        Ok(row.as_ref().map(row_to_record))
+    }
+
+    async fn mark_read(&self, user_id: Uuid, id: Option<i64>) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications.notifications SET is_read = TRUE \
+              WHERE user_id = $1 AND ($2::bigint IS NULL OR id = $2) AND NOT is_read",
+        )
+        .bind(user_id)
+        .bind(id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("mark notifications read", e))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Option<i64>) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM notifications.notifications \
+              WHERE user_id = $1 AND ($2::bigint IS NULL OR id = $2)",
+        )
+        .bind(user_id)
+        .bind(id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("delete notifications", e))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_for_object(&self, object_type: &str, object_id: &str) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM notifications.notifications WHERE object_type = $1 AND object_id = $2",
+        )
+        .bind(object_type)
+        .bind(object_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("delete notifications", e))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn users_near_quota(&self, percent: u8) -> Result<Vec<QuotaUsage>> {
+        let rows = sqlx::query(
+            "SELECT id, storage_used_bytes, storage_quota_bytes FROM auth.users \
+              WHERE active AND storage_quota_bytes > 0 \
+                AND storage_used_bytes * 100 >= storage_quota_bytes * $1",
+        )
+        .bind(i64::from(percent))
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find users near quota", e))?;
+        Ok(rows
+            .iter()
+            .map(|r| QuotaUsage {
+                user_id: r.get("id"),
+                used_bytes: r.get("storage_used_bytes"),
+                quota_bytes: r.get("storage_quota_bytes"),
+            })
+            .collect())
+    }
+
+    async fn links_expiring(&self, after: i64, until: i64) -> Result<Vec<ExpiringLink>> {
+        let rows = sqlx::query(
+            "SELECT s.id::text AS id, s.created_by, COALESCE(s.item_name, '') AS item_name, \
+                    s.expires_at \
+               FROM storage.shares s \
+               JOIN auth.users u ON u.id = s.created_by \
+              WHERE u.active AND s.expires_at > $1 AND s.expires_at <= $2",
+        )
+        .bind(after)
+        .bind(until)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find expiring links", e))?;
+        Ok(rows
+            .iter()
+            .map(|r| ExpiringLink {
+                share_id: r.get("id"),
+                owner_id: r.get("created_by"),
+                item_name: r.get("item_name"),
+                expires_at: r.get("expires_at"),
+            })
+            .collect())
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM notifications.notifications WHERE created_at < $1")
+            .bind(before)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("prune notifications", e))?;
+        sqlx::query("DELETE FROM notifications.sent WHERE sent_at < $1")
+            .bind(before)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("prune notification keys", e))?;
+        Ok(result.rows_affected())
+    }
+}
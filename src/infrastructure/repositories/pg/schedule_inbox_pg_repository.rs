@@ -0,0 +1,91 @@
+//! PostgreSQL repository for CalDAV scheduling inboxes (`caldav.schedule_inbox`).
+//!
+//! Messages are deleted by the recipient's client once processed, and with
+//! the recipient's account through the foreign key.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::calendar_dto::ScheduleMessageDto;
+use crate::application::ports::calendar_scheduling_ports::ScheduleInboxRepositoryPort;
+use crate::common::errors::{DomainError, Result};
+
+/// PostgreSQL implementation of the scheduling inbox persistence port.
+pub struct ScheduleInboxPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl ScheduleInboxPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl ScheduleInboxRepositoryPort for ScheduleInboxPgRepository {
+    async fn deliver(&self, user_id: Uuid, message: &ScheduleMessageDto) -> Result<()> {
+        let id = Uuid::parse_str(&message.id)
+            .map_err(|_| DomainError::validation_error("Invalid schedule message ID"))?;
+        sqlx::query(
+            r#"
+            INSERT INTO caldav.schedule_inbox
+                   (id, user_id, ical_uid, method, sender, ical_data, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(&message.ical_uid)
+        .bind(&message.method)
+        .bind(&message.sender)
+        .bind(&message.ical_data)
+        .bind(message.created_at)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("ScheduleInbox", format!("deliver: {e}")))?;
+        Ok(())
+    }
+
+    async fn list_messages(&self, user_id: Uuid) -> Result<Vec<ScheduleMessageDto>> {
+        let rows = sqlx::query_as::<_, (Uuid, String, String, String, String, DateTime<Utc>)>(
+            r#"
+            SELECT id, ical_uid, method, sender, ical_data, created_at
+              FROM caldav.schedule_inbox
+             WHERE user_id = $1
+             ORDER BY created_at, id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("ScheduleInbox", format!("list: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, ical_uid, method, sender, ical_data, created_at)| ScheduleMessageDto {
+                    id: id.to_string(),
+                    ical_uid,
+                    method,
+                    sender,
+                    ical_data,
+                    created_at,
+                },
+            )
+            .collect())
+    }
+
+    async fn delete_message(&self, user_id: Uuid, message_id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM caldav.schedule_inbox WHERE id = $1 AND user_id = $2")
+                .bind(message_id)
+                .bind(user_id)
+                .execute(self.pool.as_ref())
+                .await
+                .map_err(|e| {
+                    DomainError::internal_error("ScheduleInbox", format!("delete: {e}"))
+                })?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,12 +1,13 @@
 use chrono::Utc;
 use futures::future::BoxFuture;
+use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Row};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::application::ports::auth_ports::SessionStoragePort;
 use crate::common::errors::DomainError;
-use crate::domain::entities::session::Session;
+use crate::domain::entities::session::{LoginMethod, Session, SessionFamily};
 use crate::domain::repositories::session_repository::{
     SessionRepository, SessionRepositoryError, SessionRepositoryResult,
 };
@@ -39,6 +40,10 @@ impl SessionPgRepository {
     }
 }
 
+fn login_method(row: &PgRow) -> LoginMethod {
+    LoginMethod::parse(row.get("login_method")).unwrap_or(LoginMethod::Password)
+}
+
 impl SessionRepository for SessionPgRepository {
     /// Creates a new session using a transaction
     async fn create_session(&self, session: Session) -> SessionRepositoryResult<Session> {
@@ -52,9 +57,10 @@ impl SessionRepository for SessionPgRepository {
                     r#"
                         INSERT INTO auth.sessions (
                             id, user_id, refresh_token, expires_at,
-                            ip_address, user_agent, created_at, revoked, family_id
+                            ip_address, user_agent, created_at, revoked, family_id,
+                            login_method
                         ) VALUES (
-                            $1, $2, $3, $4, $5, $6, $7, $8, $9
+                            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
                         )
                         "#,
                 )
@@ -67,6 +73,7 @@ impl SessionRepository for SessionPgRepository {
                 .bind(session_clone.created_at())
                 .bind(session_clone.is_revoked())
                 .bind(session_clone.family_id())
+                .bind(session_clone.login_method().as_str())
                 .execute(&mut **tx)
                 .await
                 .map_err(Self::map_sqlx_error)?;
@@ -111,7 +118,7 @@ impl SessionRepository for SessionPgRepository {
             r#"
             SELECT
                 id, user_id, refresh_token, expires_at,
-                ip_address, user_agent, created_at, revoked, family_id
+                ip_address, user_agent, created_at, revoked, family_id, login_method
             FROM auth.sessions
             WHERE id = $1
             "#,
@@ -131,6 +138,7 @@ impl SessionRepository for SessionPgRepository {
             row.get("created_at"),
             row.get("revoked"),
             row.get("family_id"),
+            login_method(&row),
         ))
     }
 
@@ -144,7 +152,7 @@ impl SessionRepository for SessionPgRepository {
             r#"
             SELECT
                 id, user_id, refresh_token, expires_at,
-                ip_address, user_agent, created_at, revoked, family_id
+                ip_address, user_agent, created_at, revoked, family_id, login_method
             FROM auth.sessions
             WHERE refresh_token = $1
             "#,
@@ -164,6 +172,7 @@ impl SessionRepository for SessionPgRepository {
             row.get("created_at"),
             row.get("revoked"),
             row.get("family_id"),
+            login_method(&row),
         ))
     }
 
@@ -176,7 +185,7 @@ impl SessionRepository for SessionPgRepository {
             r#"
             SELECT
                 id, user_id, refresh_token, expires_at,
-                ip_address, user_agent, created_at, revoked, family_id
+                ip_address, user_agent, created_at, revoked, family_id, login_method
             FROM auth.sessions
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -200,6 +209,7 @@ impl SessionRepository for SessionPgRepository {
                     row.get("created_at"),
                     row.get("revoked"),
                     row.get("family_id"),
+                    login_method(&row),
                 )
             })
             .collect();
@@ -300,6 +310,132 @@ impl SessionRepository for SessionPgRepository {
         Ok(affected)
     }
 
+    /// Revokes a token family, only if it belongs to `user_id`
+    async fn revoke_user_session_family(
+        &self,
+        user_id: Uuid,
+        family_id: Uuid,
+    ) -> SessionRepositoryResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.sessions
+            SET revoked = true
+            WHERE family_id = $1 AND user_id = $2 AND revoked = false
+            "#,
+        )
+        .bind(family_id)
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let affected = result.rows_affected();
+        if affected > 0 {
+            tracing::info!(
+                "Revoked session family {} of user {} ({} session(s))",
+                family_id,
+                user_id,
+                affected
+            );
+        }
+        Ok(affected)
+    }
+
+    /// Lists the families of a user with a live token.  Each refresh adds a
+    /// row to the family, so the newest row holds the current client and the
+    /// time of last use.
+    async fn list_active_session_families(
+        &self,
+        user_id: Uuid,
+    ) -> SessionRepositoryResult<Vec<SessionFamily>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                family_id,
+                (array_agg(login_method ORDER BY created_at DESC))[1] AS login_method,
+                (array_agg(ip_address ORDER BY created_at DESC))[1] AS ip_address,
+                (array_agg(user_agent ORDER BY created_at DESC))[1] AS user_agent,
+                MIN(created_at) AS signed_in_at,
+                MAX(created_at) AS last_used_at,
+                MAX(expires_at) AS expires_at
+            FROM auth.sessions
+            WHERE user_id = $1
+            GROUP BY family_id
+            HAVING bool_or(revoked = false AND expires_at > NOW())
+            ORDER BY MAX(created_at) DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SessionFamily {
+                family_id: row.get("family_id"),
+                login_method: login_method(row),
+                ip_address: row.get("ip_address"),
+                user_agent: row.get("user_agent"),
+                signed_in_at: row.get("signed_in_at"),
+                last_used_at: row.get("last_used_at"),
+                expires_at: row.get("expires_at"),
+            })
+            .collect())
+    }
+
+    async fn record_session_client(
+        &self,
+        refresh_token: &str,
+        ip_address: &str,
+        user_agent: Option<&str>,
+    ) -> SessionRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE auth.sessions
+            SET ip_address = $2, user_agent = COALESCE(user_agent, $3)
+            WHERE refresh_token = $1
+            "#,
+        )
+        .bind(refresh_token)
+        .bind(ip_address)
+        .bind(user_agent)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+
+    /// Revokes the token families opened with a WebAuthn credential.
+    /// Refreshed sessions stay in their family, so this ends every session
+    /// descending from a passkey login.
+    async fn revoke_webauthn_sessions(&self, credential_id: Uuid) -> SessionRepositoryResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.sessions
+            SET revoked = true
+            WHERE revoked = false AND family_id IN (
+                SELECT family_id FROM auth.webauthn_session_families
+                WHERE credential_id = $1
+            )
+            "#,
+        )
+        .bind(credential_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let affected = result.rows_affected();
+        if affected > 0 {
+            tracing::info!(
+                "Revoked {} session(s) opened with passkey {}",
+                affected,
+                credential_id
+            );
+        }
+        Ok(affected)
+    }
+
     /// Deletes expired sessions
     async fn delete_expired_sessions(&self) -> SessionRepositoryResult<u64> {
         let now = Utc::now();
@@ -353,4 +489,40 @@ impl SessionStoragePort for SessionPgRepository {
             .await
             .map_err(DomainError::from)
     }
+
+    async fn revoke_webauthn_sessions(&self, credential_id: Uuid) -> Result<u64, DomainError> {
+        SessionRepository::revoke_webauthn_sessions(self, credential_id)
+            .await
+            .map_err(DomainError::from)
+    }
+
+    async fn revoke_user_session_family(
+        &self,
+        user_id: Uuid,
+        family_id: Uuid,
+    ) -> Result<u64, DomainError> {
+        SessionRepository::revoke_user_session_family(self, user_id, family_id)
+            .await
+            .map_err(DomainError::from)
+    }
+
+    async fn list_active_session_families(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<SessionFamily>, DomainError> {
+        SessionRepository::list_active_session_families(self, user_id)
+            .await
+            .map_err(DomainError::from)
+    }
+
+    async fn record_session_client(
+        &self,
+        refresh_token: &str,
+        ip_address: &str,
+        user_agent: Option<&str>,
+    ) -> Result<(), DomainError> {
+        SessionRepository::record_session_client(self, refresh_token, ip_address, user_agent)
+            .await
+            .map_err(DomainError::from)
+    }
 }
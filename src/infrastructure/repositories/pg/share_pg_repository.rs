@@ -1,9 +1,13 @@
 use sqlx::{PgPool, Row};
+use std::collections::HashSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
-    application::ports::share_ports::ShareStoragePort,
+    application::{
+        dtos::share_dto::ShareAccessDto,
+        ports::share_ports::{ShareDownload, ShareStoragePort},
+    },
     common::errors::DomainError,
     domain::entities::share::{Share, ShareItemType, SharePermissions},
 };
@@ -63,6 +67,10 @@ impl SharePgRepository {
             DomainError::internal_error("Share", format!("Failed to read created_by: {e}"))
         })?;
         let access_count: i64 = row.try_get("access_count").unwrap_or(0);
+        let hide_contents: bool = row.try_get("hide_contents").unwrap_or(false);
+        let max_upload_size: Option<i64> = row.try_get("max_upload_size").unwrap_or(None);
+        let max_downloads: Option<i64> = row.try_get("max_downloads").unwrap_or(None);
+        let download_count: i64 = row.try_get("download_count").unwrap_or(0);
 
         let item_type =
             ShareItemType::try_from(item_type_str.as_str()).unwrap_or(ShareItemType::File);
@@ -81,7 +89,11 @@ impl SharePgRepository {
             created_at as u64,
             created_by,
             access_count as u64,
-        ))
+        )
+        .with_hide_contents(hide_contents)
+        .with_max_upload_size(max_upload_size.map(|v| v as u64))
+        .with_max_downloads(max_downloads.map(|v| v as u64))
+        .with_download_count(download_count as u64))
     }
 }
 
@@ -92,11 +104,13 @@ impl ShareStoragePort for SharePgRepository {
             INSERT INTO storage.shares
                 (id, item_id, item_name, item_type, token, password_hash,
                  expires_at, permissions_read, permissions_write, permissions_reshare,
-                 created_at, created_by, access_count)
+                 created_at, created_by, access_count,
+                 hide_contents, max_upload_size, max_downloads, download_count)
             VALUES
                 ($1, $2, $3, $4, $5, $6,
                  $7, $8, $9, $10,
-                 $11, $12, $13)
+                 $11, $12, $13,
+                 $14, $15, $16, $17)
             ON CONFLICT (id) DO UPDATE SET
                 item_name         = EXCLUDED.item_name,
                 password_hash     = EXCLUDED.password_hash,
@@ -104,11 +118,15 @@ impl ShareStoragePort for SharePgRepository {
                 permissions_read  = EXCLUDED.permissions_read,
                 permissions_write = EXCLUDED.permissions_write,
                 permissions_reshare = EXCLUDED.permissions_reshare,
-                access_count      = EXCLUDED.access_count
+                access_count      = EXCLUDED.access_count,
+                hide_contents     = EXCLUDED.hide_contents,
+                max_upload_size   = EXCLUDED.max_upload_size,
+                max_downloads     = EXCLUDED.max_downloads
             RETURNING
                 id, item_id, item_name, item_type, token, password_hash,
                 expires_at, permissions_read, permissions_write, permissions_reshare,
-                created_at, created_by, access_count
+                created_at, created_by, access_count,
+                hide_contents, max_upload_size, max_downloads, download_count
             "#,
         )
         .bind(share.id())
@@ -124,6 +142,10 @@ impl ShareStoragePort for SharePgRepository {
         .bind(share.created_at() as i64)
         .bind(share.created_by())
         .bind(share.access_count() as i64)
+        .bind(share.hide_contents())
+        .bind(share.max_upload_size().map(|v| v as i64))
+        .bind(share.max_downloads().map(|v| v as i64))
+        .bind(share.download_count() as i64)
         .fetch_one(&*self.db_pool)
         .await
         .map_err(|e| {
@@ -139,7 +161,8 @@ impl ShareStoragePort for SharePgRepository {
             r#"
             SELECT id, item_id, item_name, item_type, token, password_hash,
                    expires_at, permissions_read, permissions_write, permissions_reshare,
-                   created_at, created_by, access_count
+                   created_at, created_by, access_count,
+                   hide_contents, max_upload_size, max_downloads, download_count
             FROM storage.shares
             WHERE token = $1
             "#,
@@ -170,7 +193,8 @@ impl ShareStoragePort for SharePgRepository {
             r#"
             SELECT id, item_id, item_name, item_type, token, password_hash,
                    expires_at, permissions_read, permissions_write, permissions_reshare,
-                   created_at, created_by, access_count
+                   created_at, created_by, access_count,
+                   hide_contents, max_upload_size, max_downloads, download_count
             FROM storage.shares
             WHERE id = $1 AND created_by = $2
             "#,
@@ -226,7 +250,8 @@ impl ShareStoragePort for SharePgRepository {
             r#"
             SELECT id, item_id, item_name, item_type, token, password_hash,
                    expires_at, permissions_read, permissions_write, permissions_reshare,
-                   created_at, created_by, access_count
+                   created_at, created_by, access_count,
+                   hide_contents, max_upload_size, max_downloads, download_count
             FROM storage.shares
             WHERE item_id = $1 AND item_type = $2 AND created_by = $3
             ORDER BY created_at DESC
@@ -255,12 +280,16 @@ impl ShareStoragePort for SharePgRepository {
                 permissions_read  = $5,
                 permissions_write = $6,
                 permissions_reshare = $7,
-                access_count      = $8
+                access_count      = $8,
+                hide_contents     = $9,
+                max_upload_size   = $10,
+                max_downloads     = $11
             WHERE id = $1
             RETURNING
                 id, item_id, item_name, item_type, token, password_hash,
                 expires_at, permissions_read, permissions_write, permissions_reshare,
-                created_at, created_by, access_count
+                created_at, created_by, access_count,
+                hide_contents, max_upload_size, max_downloads, download_count
             "#,
         )
         .bind(share.id())
@@ -271,6 +300,9 @@ impl ShareStoragePort for SharePgRepository {
         .bind(share.permissions().write())
         .bind(share.permissions().reshare())
         .bind(share.access_count() as i64)
+        .bind(share.hide_contents())
+        .bind(share.max_upload_size().map(|v| v as i64))
+        .bind(share.max_downloads().map(|v| v as i64))
         .fetch_optional(&*self.db_pool)
         .await
         .map_err(|e| {
@@ -299,6 +331,7 @@ impl ShareStoragePort for SharePgRepository {
             SELECT id, item_id, item_name, item_type, token, password_hash,
                    expires_at, permissions_read, permissions_write, permissions_reshare,
                    created_at, created_by, access_count,
+                   hide_contents, max_upload_size, max_downloads, download_count,
                    COUNT(*) OVER() AS total_count
             FROM storage.shares
             WHERE created_by = $1
@@ -326,4 +359,116 @@ impl ShareStoragePort for SharePgRepository {
 
         Ok((shares?, total))
     }
+
+    async fn find_shared_item_ids(
+        &self,
+        user_id: Uuid,
+        item_ids: &[String],
+    ) -> Result<HashSet<String>, DomainError> {
+        if item_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let ids: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT item_id
+            FROM storage.shares
+            WHERE created_by = $1 AND item_id = ANY($2)
+              AND (expires_at IS NULL OR expires_at > EXTRACT(EPOCH FROM NOW())::BIGINT)
+            "#,
+        )
+        .bind(user_id)
+        .bind(item_ids)
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error finding shared item ids: {}", e);
+            DomainError::internal_error("Share", format!("Failed to find shared items: {e}"))
+        })?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    async fn record_download(
+        &self,
+        share_id: Uuid,
+        download: &ShareDownload,
+    ) -> Result<bool, DomainError> {
+        // The conditional UPDATE serialises concurrent downloads on the row
+        // lock, so a limit can never be overshot.
+        let recorded: Option<i64> = sqlx::query_scalar(
+            r#"
+            WITH counted AS (
+                UPDATE storage.shares
+                SET download_count = download_count + 1
+                WHERE id = $1
+                  AND (max_downloads IS NULL OR download_count < max_downloads)
+                RETURNING id
+            )
+            INSERT INTO storage.share_accesses
+                (share_id, ip_address, user_agent, file_id, file_name)
+            SELECT id, $2, $3, $4, $5 FROM counted
+            RETURNING id
+            "#,
+        )
+        .bind(share_id)
+        .bind(download.ip_address.as_deref())
+        .bind(download.user_agent.as_deref())
+        .bind(download.file_id.as_deref())
+        .bind(download.file_name.as_deref())
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error recording share download: {}", e);
+            DomainError::internal_error("Share", format!("Failed to record download: {e}"))
+        })?;
+
+        Ok(recorded.is_some())
+    }
+
+    async fn find_share_accesses(
+        &self,
+        share_id: Uuid,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<ShareAccessDto>, usize), DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, EXTRACT(EPOCH FROM accessed_at)::BIGINT AS accessed_secs,
+                   ip_address, user_agent, file_id, file_name,
+                   COUNT(*) OVER() AS total_count
+            FROM storage.share_accesses
+            WHERE share_id = $1
+            ORDER BY accessed_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(share_id)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing share accesses: {}", e);
+            DomainError::internal_error("Share", format!("Failed to list share accesses: {e}"))
+        })?;
+
+        let total: usize = rows
+            .first()
+            .and_then(|r| r.try_get::<i64, _>("total_count").ok())
+            .unwrap_or(0) as usize;
+
+        let accesses = rows
+            .iter()
+            .map(|r| ShareAccessDto {
+                id: r.get("id"),
+                accessed_at: r.get::<i64, _>("accessed_secs") as u64,
+                ip_address: r.get("ip_address"),
+                user_agent: r.get("user_agent"),
+                file_id: r.get("file_id"),
+                file_name: r.get("file_name"),
+            })
+            .collect();
+
+        Ok((accesses, total))
+    }
 }
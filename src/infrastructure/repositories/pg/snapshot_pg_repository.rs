@@ -0,0 +1,481 @@
+//! PostgreSQL repository for folder snapshots (`storage.snapshots`,
+//! `storage.snapshot_entries`, `storage.snapshot_schedules`).
+//!
+//! Creation and restore are delegated to the PL/pgSQL functions
+//! `storage.create_folder_snapshot` and `storage.restore_snapshot` so the
+//! whole tree is recorded / materialised atomically in one round-trip.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::snapshot_dto::{
+    RestoreSnapshotResultDto, SnapshotDto, SnapshotEntryDto, SnapshotScheduleDto,
+};
+use crate::application::ports::snapshot_ports::SnapshotRepositoryPort;
+use crate::common::errors::{DomainError, Result};
+
+type SnapshotRow = (
+    String,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+    i64,
+    i64,
+    i64,
+    bool,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+type EntryRow = (
+    String,
+    String,
+    bool,
+    i64,
+    Option<String>,
+    DateTime<Utc>,
+    Option<String>,
+);
+
+type ScheduleRow = (
+    String,
+    String,
+    i32,
+    Option<i32>,
+    Option<DateTime<Utc>>,
+    DateTime<Utc>,
+);
+
+const SNAPSHOT_COLUMNS: &str = "id::text, folder_id::text, folder_name, name, description, \
+     folder_count, file_count, total_size, schedule_id IS NOT NULL, created_at, expires_at";
+
+const ENTRY_COLUMNS: &str = "name, path, is_folder, size, mime_type, modified_at, blob_hash";
+
+const SCHEDULE_COLUMNS: &str =
+    "id::text, folder_id::text, interval_hours, retention_days, last_run_at, created_at";
+
+/// Map PL/pgSQL errors raised by the snapshot functions to domain errors.
+fn map_function_error(e: sqlx::Error, entity: &'static str, id: &str) -> DomainError {
+    if let sqlx::Error::Database(ref db_err) = e {
+        // P0002 (no_data_found) — folder, snapshot, entry or target missing
+        if db_err.code().as_deref() == Some("P0002") {
+            return DomainError::not_found(entity, id);
+        }
+        // 23505 (unique_violation) — name already taken in the target folder
+        if db_err.code().as_deref() == Some("23505") {
+            return DomainError::already_exists(
+                entity,
+                "an item with this name already exists in the target location",
+            );
+        }
+    }
+    DomainError::internal_error("Snapshots", format!("{e}"))
+}
+
+/// PostgreSQL implementation of the snapshot persistence port.
+pub struct SnapshotPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl SnapshotPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_snapshot(row: SnapshotRow) -> SnapshotDto {
+        SnapshotDto {
+            id: row.0,
+            folder_id: row.1,
+            folder_name: row.2,
+            name: row.3,
+            description: row.4,
+            folder_count: row.5,
+            file_count: row.6,
+            total_size: row.7,
+            scheduled: row.8,
+            created_at: row.9,
+            expires_at: row.10,
+        }
+    }
+
+    fn row_to_entry(row: EntryRow) -> (SnapshotEntryDto, Option<String>) {
+        (
+            SnapshotEntryDto {
+                name: row.0,
+                path: row.1,
+                is_folder: row.2,
+                size: row.3,
+                mime_type: row.4,
+                modified_at: row.5,
+            },
+            row.6,
+        )
+    }
+
+    fn row_to_schedule(row: ScheduleRow) -> SnapshotScheduleDto {
+        SnapshotScheduleDto {
+            id: row.0,
+            folder_id: row.1,
+            interval_hours: row.2,
+            retention_days: row.3,
+            last_run_at: row.4,
+            created_at: row.5,
+        }
+    }
+}
+
+impl SnapshotRepositoryPort for SnapshotPgRepository {
+    async fn create_snapshot(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        name: &str,
+        description: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+        schedule_id: Option<&str>,
+    ) -> Result<String> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT storage.create_folder_snapshot($1::uuid, $2, $3, $4, $5, $6::uuid)::text",
+        )
+        .bind(folder_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(description)
+        .bind(expires_at)
+        .bind(schedule_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| map_function_error(e, "Folder", folder_id))
+    }
+
+    async fn get_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<SnapshotDto> {
+        let row = sqlx::query_as::<_, SnapshotRow>(&format!(
+            "SELECT {SNAPSHOT_COLUMNS} FROM storage.snapshots \
+              WHERE id = $1::uuid AND user_id = $2"
+        ))
+        .bind(snapshot_id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("get: {e}")))?
+        .ok_or_else(|| DomainError::not_found("Snapshot", snapshot_id))?;
+
+        Ok(Self::row_to_snapshot(row))
+    }
+
+    async fn list_snapshots(
+        &self,
+        user_id: Uuid,
+        folder_id: Option<&str>,
+    ) -> Result<Vec<SnapshotDto>> {
+        let rows = sqlx::query_as::<_, SnapshotRow>(&format!(
+            "SELECT {SNAPSHOT_COLUMNS} FROM storage.snapshots \
+              WHERE user_id = $1 AND ($2::uuid IS NULL OR folder_id = $2::uuid) \
+              ORDER BY created_at DESC"
+        ))
+        .bind(user_id)
+        .bind(folder_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("list: {e}")))?;
+
+        Ok(rows.into_iter().map(Self::row_to_snapshot).collect())
+    }
+
+    async fn get_entry(
+        &self,
+        snapshot_id: &str,
+        path: &str,
+    ) -> Result<(SnapshotEntryDto, Option<String>)> {
+        let row = sqlx::query_as::<_, EntryRow>(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM storage.snapshot_entries \
+              WHERE snapshot_id = $1::uuid AND path = $2"
+        ))
+        .bind(snapshot_id)
+        .bind(path)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("get entry: {e}")))?
+        .ok_or_else(|| DomainError::not_found("SnapshotEntry", path))?;
+
+        Ok(Self::row_to_entry(row))
+    }
+
+    async fn list_children(
+        &self,
+        snapshot_id: &str,
+        parent_path: &str,
+    ) -> Result<Vec<SnapshotEntryDto>> {
+        // Root children have parent_source_id NULL; otherwise resolve the
+        // parent entry by path (IS NOT DISTINCT FROM covers both cases).
+        let rows = sqlx::query_as::<_, EntryRow>(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM storage.snapshot_entries \
+              WHERE snapshot_id = $1::uuid \
+                AND parent_source_id IS NOT DISTINCT FROM ( \
+                    SELECT source_id FROM storage.snapshot_entries \
+                     WHERE snapshot_id = $1::uuid AND path = $2 AND is_folder) \
+              ORDER BY is_folder DESC, name"
+        ))
+        .bind(snapshot_id)
+        .bind(parent_path)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("browse: {e}")))?;
+
+        Ok(rows.into_iter().map(|r| Self::row_to_entry(r).0).collect())
+    }
+
+    async fn source_parent_id(&self, snapshot_id: &str) -> Result<Option<String>> {
+        let parent = sqlx::query_scalar::<_, Option<String>>(
+            r#"
+            SELECT fo.parent_id::text
+              FROM storage.snapshots s
+              JOIN storage.folders fo ON fo.id = s.folder_id
+             WHERE s.id = $1::uuid AND NOT fo.is_trashed
+            "#,
+        )
+        .bind(snapshot_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("source parent: {e}")))?;
+
+        Ok(parent.flatten())
+    }
+
+    async fn restore_size(&self, snapshot_id: &str, path: Option<&str>) -> Result<u64> {
+        let size = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COALESCE(SUM(size), 0)::bigint
+              FROM storage.snapshot_entries
+             WHERE snapshot_id = $1::uuid
+               AND NOT is_folder
+               AND ($2::text IS NULL OR path = $2 OR starts_with(path, $2 || '/'))
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(path)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("restore size: {e}")))?;
+
+        Ok(size.max(0) as u64)
+    }
+
+    async fn restore(
+        &self,
+        snapshot_id: &str,
+        path: Option<&str>,
+        target_parent_id: Option<&str>,
+        dest_name: &str,
+    ) -> Result<RestoreSnapshotResultDto> {
+        let row = sqlx::query_as::<_, (String, bool, i64, i64)>(
+            "SELECT new_root_id, root_is_folder, folders_restored, files_restored \
+               FROM storage.restore_snapshot($1::uuid, $2, $3::uuid, $4)",
+        )
+        .bind(snapshot_id)
+        .bind(path)
+        .bind(target_parent_id)
+        .bind(dest_name)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| map_function_error(e, "Snapshot", snapshot_id))?;
+
+        Ok(RestoreSnapshotResultDto {
+            id: row.0,
+            is_folder: row.1,
+            folders_restored: row.2,
+            files_restored: row.3,
+        })
+    }
+
+    async fn list_restored_files(
+        &self,
+        root_id: &str,
+        root_is_folder: bool,
+    ) -> Result<Vec<(String, String, String)>> {
+        let rows = if root_is_folder {
+            sqlx::query_as::<_, (String, String, String)>(
+                r#"
+                SELECT f.id::text, f.blob_hash, f.mime_type
+                  FROM storage.files f
+                  JOIN storage.folders fo ON fo.id = f.folder_id
+                 WHERE fo.lpath <@ (SELECT lpath FROM storage.folders WHERE id = $1::uuid)
+                "#,
+            )
+        } else {
+            sqlx::query_as::<_, (String, String, String)>(
+                "SELECT id::text, blob_hash, mime_type FROM storage.files WHERE id = $1::uuid",
+            )
+        }
+        .bind(root_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("restored files: {e}")))?;
+
+        Ok(rows)
+    }
+
+    async fn delete_snapshot(&self, snapshot_id: &str, user_id: Uuid) -> Result<Vec<String>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::internal_error("Snapshots", format!("begin: {e}")))?;
+
+        // Delete file entries first so their hashes can be returned;
+        // each row fires trg_snapshot_entries_decrement_blob_ref.
+        let hashes = sqlx::query_scalar::<_, String>(
+            r#"
+            DELETE FROM storage.snapshot_entries e
+             USING storage.snapshots s
+             WHERE s.id = e.snapshot_id
+               AND e.snapshot_id = $1::uuid
+               AND s.user_id = $2
+               AND NOT e.is_folder
+            RETURNING e.blob_hash
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("delete entries: {e}")))?;
+
+        // Folder entries go with the snapshot via ON DELETE CASCADE
+        let deleted =
+            sqlx::query("DELETE FROM storage.snapshots WHERE id = $1::uuid AND user_id = $2")
+                .bind(snapshot_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| DomainError::internal_error("Snapshots", format!("delete: {e}")))?;
+
+        if deleted.rows_affected() == 0 {
+            return Err(DomainError::not_found("Snapshot", snapshot_id));
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::internal_error("Snapshots", format!("commit: {e}")))?;
+
+        Ok(hashes)
+    }
+
+    async fn list_expired(&self) -> Result<Vec<(String, Uuid)>> {
+        sqlx::query_as::<_, (String, Uuid)>(
+            "SELECT id::text, user_id FROM storage.snapshots \
+              WHERE expires_at IS NOT NULL AND expires_at <= NOW()",
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("list expired: {e}")))
+    }
+
+    async fn get_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+    ) -> Result<Option<SnapshotScheduleDto>> {
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM storage.snapshot_schedules \
+              WHERE folder_id = $1::uuid AND user_id = $2"
+        ))
+        .bind(folder_id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("get schedule: {e}")))?;
+
+        Ok(row.map(Self::row_to_schedule))
+    }
+
+    async fn upsert_schedule(
+        &self,
+        folder_id: &str,
+        user_id: Uuid,
+        interval_hours: i32,
+        retention_days: Option<i32>,
+    ) -> Result<SnapshotScheduleDto> {
+        // The folder must belong to the user; INSERT … SELECT yields no row
+        // otherwise, which is reported as NotFound.
+        let row = sqlx::query_as::<_, ScheduleRow>(&format!(
+            "INSERT INTO storage.snapshot_schedules (folder_id, user_id, interval_hours, retention_days) \
+             SELECT id, user_id, $3, $4 FROM storage.folders \
+              WHERE id = $1::uuid AND user_id = $2 AND NOT is_trashed \
+             ON CONFLICT (folder_id) DO UPDATE \
+                SET interval_hours = EXCLUDED.interval_hours, \
+                    retention_days = EXCLUDED.retention_days \
+             RETURNING {SCHEDULE_COLUMNS}"
+        ))
+        .bind(folder_id)
+        .bind(user_id)
+        .bind(interval_hours)
+        .bind(retention_days)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("set schedule: {e}")))?
+        .ok_or_else(|| DomainError::not_found("Folder", folder_id))?;
+
+        Ok(Self::row_to_schedule(row))
+    }
+
+    async fn delete_schedule(&self, folder_id: &str, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM storage.snapshot_schedules WHERE folder_id = $1::uuid AND user_id = $2",
+        )
+        .bind(folder_id)
+        .bind(user_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("delete schedule: {e}")))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_due_schedules(&self) -> Result<Vec<(Uuid, SnapshotScheduleDto)>> {
+        let rows = sqlx::query_as::<
+            _,
+            (
+                Uuid,
+                String,
+                String,
+                i32,
+                Option<i32>,
+                Option<DateTime<Utc>>,
+                DateTime<Utc>,
+            ),
+        >(
+            r#"
+            SELECT s.user_id, s.id::text, s.folder_id::text, s.interval_hours,
+                   s.retention_days, s.last_run_at, s.created_at
+              FROM storage.snapshot_schedules s
+              JOIN storage.folders fo ON fo.id = s.folder_id
+             WHERE NOT fo.is_trashed
+               AND (s.last_run_at IS NULL
+                    OR s.last_run_at + make_interval(hours => s.interval_hours) <= NOW())
+            "#,
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("due schedules: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.0, Self::row_to_schedule((r.1, r.2, r.3, r.4, r.5, r.6))))
+            .collect())
+    }
+
+    async fn mark_schedule_run(&self, schedule_id: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE storage.snapshot_schedules SET last_run_at = NOW() WHERE id = $1::uuid",
+        )
+        .bind(schedule_id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| DomainError::internal_error("Snapshots", format!("mark schedule run: {e}")))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! PostgreSQL repository for two-factor authentication
+//! (`auth.user_totp`, `auth.user_recovery_codes`).
+
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::ports::two_factor_ports::{TotpSecret, TwoFactorRepositoryPort};
+use crate::common::errors::{DomainError, Result};
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    DomainError::internal_error("TwoFactor", format!("{context}: {e}"))
+}
+
+async fn insert_recovery_codes(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    code_hashes: &[String],
+) -> Result<()> {
+    sqlx::query("DELETE FROM auth.user_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("delete recovery codes", e))?;
+    sqlx::query(
+        "INSERT INTO auth.user_recovery_codes (user_id, code_hash) \
+         SELECT $1, h FROM UNNEST($2::text[]) AS h \
+         ON CONFLICT (user_id, code_hash) DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(code_hashes)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| db_error("insert recovery codes", e))?;
+    Ok(())
+}
+
+/// PostgreSQL implementation of the two-factor persistence port.
+pub struct TwoFactorPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl TwoFactorPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl TwoFactorRepositoryPort for TwoFactorPgRepository {
+    async fn get_totp(&self, user_id: Uuid) -> Result<Option<TotpSecret>> {
+        let row = sqlx::query_as::<_, (String, bool, Option<i64>)>(
+            "SELECT secret, confirmed_at IS NOT NULL, last_used_step \
+             FROM auth.user_totp WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("get totp", e))?;
+
+        Ok(row.map(|(secret, confirmed, last_used_step)| TotpSecret {
+            secret,
+            confirmed,
+            last_used_step,
+        }))
+    }
+
+    async fn set_pending_totp(&self, user_id: Uuid, secret: &str) -> Result<()> {
+        let result = sqlx::query(
+            "INSERT INTO auth.user_totp (user_id, secret) VALUES ($1, $2) \
+             ON CONFLICT (user_id) DO UPDATE \
+                 SET secret = EXCLUDED.secret, last_used_step = NULL, \
+                     created_at = CURRENT_TIMESTAMP \
+                 WHERE auth.user_totp.confirmed_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(secret)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("set pending totp", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::already_exists(
+                "TwoFactor",
+                "two-factor authentication is already enabled",
+            ));
+        }
+        Ok(())
+    }
+
+    async fn confirm_totp(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| db_error("begin totp confirmation", e))?;
+
+        let result = sqlx::query(
+            "UPDATE auth.user_totp \
+             SET confirmed_at = CURRENT_TIMESTAMP, last_used_step = $2 \
+             WHERE user_id = $1 AND confirmed_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("confirm totp", e))?;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(
+                "TwoFactor",
+                "no two-factor setup in progress",
+            ));
+        }
+
+        insert_recovery_codes(&mut tx, user_id, recovery_code_hashes).await?;
+        tx.commit()
+            .await
+            .map_err(|e| db_error("commit totp confirmation", e))
+    }
+
+    async fn use_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE auth.user_totp SET last_used_step = $2 \
+             WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)",
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("use totp step", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_totp(&self, user_id: Uuid) -> Result<bool> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| db_error("begin totp removal", e))?;
+        sqlx::query("DELETE FROM auth.user_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("delete recovery codes", e))?;
+        let result = sqlx::query("DELETE FROM auth.user_totp WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("delete totp", e))?;
+        tx.commit()
+            .await
+            .map_err(|e| db_error("commit totp removal", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: &[String]) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| db_error("begin recovery code replacement", e))?;
+        insert_recovery_codes(&mut tx, user_id, code_hashes).await?;
+        tx.commit()
+            .await
+            .map_err(|e| db_error("commit recovery code replacement", e))
+    }
+
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE auth.user_recovery_codes SET used_at = CURRENT_TIMESTAMP \
+             WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("use recovery code", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remaining_recovery_codes(&self, user_id: Uuid) -> Result<u64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM auth.user_recovery_codes \
+             WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("count recovery codes", e))?;
+        Ok(count as u64)
+    }
+}
@@ -0,0 +1,441 @@
+//! PostgreSQL repository for direct user shares (`storage.user_shares`).
+//!
+//! Access checks walk the folder tree with the GiST-indexed `lpath`
+//! column: a share of a folder covers every folder whose `lpath` is a
+//! descendant of the shared folder's.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::share_dto::SharePermissionsDto;
+use crate::application::dtos::user_share_dto::UserShareDto;
+use crate::application::ports::user_share_ports::{
+    NewUserShare, ShareRecipient, SharedAccess, SharedMount, UserShareRepositoryPort,
+};
+use crate::common::errors::{DomainError, Result};
+
+type ShareRow = (
+    String,
+    String,
+    bool,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    bool,
+    bool,
+    DateTime<Utc>,
+);
+
+type MountRow = (String, String, String, bool, Uuid, bool, bool);
+
+const SHARE_SELECT: &str = "SELECT us.id::text, COALESCE(us.folder_id, us.file_id)::text, \
+     us.folder_id IS NOT NULL, COALESCE(fo.name, fi.name, ''), us.mount_name, \
+     us.owner_id::text, ou.username, us.shared_by::text, su.username, \
+     us.recipient_id::text, ru.username, us.can_write, us.can_reshare, us.created_at \
+     FROM storage.user_shares us \
+     LEFT JOIN storage.folders fo ON fo.id = us.folder_id \
+     LEFT JOIN storage.files fi ON fi.id = us.file_id \
+     JOIN auth.users ou ON ou.id = us.owner_id \
+     JOIN auth.users su ON su.id = us.shared_by \
+     JOIN auth.users ru ON ru.id = us.recipient_id";
+
+const MOUNT_SELECT: &str = "SELECT us.id::text, us.mount_name, \
+     COALESCE(us.folder_id, us.file_id)::text, us.folder_id IS NOT NULL, \
+     us.owner_id, us.can_write, us.can_reshare \
+     FROM storage.user_shares us \
+     LEFT JOIN storage.folders fo ON fo.id = us.folder_id \
+     LEFT JOIN storage.files fi ON fi.id = us.file_id \
+     WHERE us.recipient_id = $1 AND NOT COALESCE(fo.is_trashed, fi.is_trashed)";
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    if let sqlx::Error::Database(ref db_err) = e
+        && db_err.code().as_deref() == Some("23505")
+    {
+        return DomainError::already_exists(
+            "UserShare",
+            "the item is already shared with this user, or the name is taken",
+        );
+    }
+    DomainError::internal_error("UserShares", format!("{context}: {e}"))
+}
+
+fn parse_id(share_id: &str) -> Result<Uuid> {
+    Uuid::parse_str(share_id).map_err(|_| DomainError::not_found("UserShare", share_id))
+}
+
+/// PostgreSQL implementation of the user share persistence port.
+pub struct UserSharePgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl UserSharePgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_share(row: ShareRow) -> UserShareDto {
+        UserShareDto {
+            id: row.0,
+            item_id: row.1,
+            item_type: if row.2 { "folder" } else { "file" }.to_string(),
+            item_name: row.3,
+            mount_name: row.4,
+            owner_id: row.5,
+            owner_name: row.6,
+            shared_by: row.7,
+            shared_by_name: row.8,
+            recipient_id: row.9,
+            recipient_name: row.10,
+            permissions: SharePermissionsDto {
+                read: true,
+                write: row.11,
+                reshare: row.12,
+            },
+            created_at: row.13,
+        }
+    }
+
+    fn row_to_mount(row: MountRow) -> SharedMount {
+        SharedMount {
+            share_id: row.0,
+            mount_name: row.1,
+            item_id: row.2,
+            is_folder: row.3,
+            access: SharedAccess {
+                owner_id: row.4,
+                can_write: row.5,
+                can_reshare: row.6,
+            },
+        }
+    }
+
+    fn row_to_access(row: Option<(Uuid, bool, bool)>) -> Option<SharedAccess> {
+        row.map(|(owner_id, can_write, can_reshare)| SharedAccess {
+            owner_id,
+            can_write,
+            can_reshare,
+        })
+    }
+}
+
+impl UserShareRepositoryPort for UserSharePgRepository {
+    async fn create_share(&self, share: &NewUserShare) -> Result<UserShareDto> {
+        let item_id = Uuid::parse_str(&share.item_id)
+            .map_err(|_| DomainError::not_found("Item", &share.item_id))?;
+        let (folder_id, file_id) = if share.is_folder {
+            (Some(item_id), None)
+        } else {
+            (None, Some(item_id))
+        };
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO storage.user_shares
+                (folder_id, file_id, owner_id, shared_by, recipient_id,
+                 can_write, can_reshare, mount_name)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+        )
+        .bind(folder_id)
+        .bind(file_id)
+        .bind(share.owner_id)
+        .bind(share.shared_by)
+        .bind(share.recipient_id)
+        .bind(share.can_write)
+        .bind(share.can_reshare)
+        .bind(&share.mount_name)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("create_share", e))?;
+
+        self.get_share(&id.to_string()).await
+    }
+
+    async fn get_share(&self, share_id: &str) -> Result<UserShareDto> {
+        let id = parse_id(share_id)?;
+        sqlx::query_as::<_, ShareRow>(&format!("{SHARE_SELECT} WHERE us.id = $1"))
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("get_share", e))?
+            .map(Self::row_to_share)
+            .ok_or_else(|| DomainError::not_found("UserShare", share_id))
+    }
+
+    async fn list_outgoing(
+        &self,
+        user_id: Uuid,
+        item: Option<(&str, bool)>,
+    ) -> Result<Vec<UserShareDto>> {
+        let item_id = match item {
+            Some((id, _)) => {
+                Some(Uuid::parse_str(id).map_err(|_| DomainError::not_found("Item", id))?)
+            }
+            None => None,
+        };
+        let is_folder = item.map(|(_, is_folder)| is_folder);
+        let rows = sqlx::query_as::<_, ShareRow>(&format!(
+            "{SHARE_SELECT} \
+             WHERE (us.owner_id = $1 OR us.shared_by = $1) \
+               AND ($2::uuid IS NULL OR COALESCE(us.folder_id, us.file_id) = $2) \
+               AND ($3::bool IS NULL OR (us.folder_id IS NOT NULL) = $3) \
+             ORDER BY us.created_at DESC"
+        ))
+        .bind(user_id)
+        .bind(item_id)
+        .bind(is_folder)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list_outgoing", e))?;
+        Ok(rows.into_iter().map(Self::row_to_share).collect())
+    }
+
+    async fn list_incoming(&self, user_id: Uuid) -> Result<Vec<UserShareDto>> {
+        let rows = sqlx::query_as::<_, ShareRow>(&format!(
+            "{SHARE_SELECT} \
+             WHERE us.recipient_id = $1 AND NOT COALESCE(fo.is_trashed, fi.is_trashed) \
+             ORDER BY us.mount_name"
+        ))
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list_incoming", e))?;
+        Ok(rows.into_iter().map(Self::row_to_share).collect())
+    }
+
+    async fn update_permissions(
+        &self,
+        share_id: &str,
+        can_write: bool,
+        can_reshare: bool,
+    ) -> Result<UserShareDto> {
+        let id = parse_id(share_id)?;
+        let updated = sqlx::query(
+            "UPDATE storage.user_shares \
+                SET can_write = $2, can_reshare = $3, updated_at = NOW() \
+              WHERE id = $1",
+        )
+        .bind(id)
+        .bind(can_write)
+        .bind(can_reshare)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("update_permissions", e))?;
+        if updated.rows_affected() == 0 {
+            return Err(DomainError::not_found("UserShare", share_id));
+        }
+        self.get_share(share_id).await
+    }
+
+    async fn rename_mount(&self, share_id: &str, mount_name: &str) -> Result<UserShareDto> {
+        let id = parse_id(share_id)?;
+        let updated = sqlx::query(
+            "UPDATE storage.user_shares SET mount_name = $2, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(mount_name)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("rename_mount", e))?;
+        if updated.rows_affected() == 0 {
+            return Err(DomainError::not_found("UserShare", share_id));
+        }
+        self.get_share(share_id).await
+    }
+
+    async fn delete_share(&self, share_id: &str) -> Result<()> {
+        let id = parse_id(share_id)?;
+        let deleted = sqlx::query("DELETE FROM storage.user_shares WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("delete_share", e))?;
+        if deleted.rows_affected() == 0 {
+            return Err(DomainError::not_found("UserShare", share_id));
+        }
+        Ok(())
+    }
+
+    async fn mount_names(&self, recipient_id: Uuid) -> Result<Vec<String>> {
+        // Items in the home folder count too, so a new mount never hides
+        // one of the recipient's own files or folders
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT mount_name FROM storage.user_shares WHERE recipient_id = $1
+            UNION
+            SELECT c.name FROM storage.folders c
+              JOIN storage.folders home ON home.id = c.parent_id
+             WHERE home.parent_id IS NULL AND home.user_id = $1 AND NOT c.is_trashed
+            UNION
+            SELECT f.name FROM storage.files f
+              JOIN storage.folders home ON home.id = f.folder_id
+             WHERE home.parent_id IS NULL AND home.user_id = $1 AND NOT f.is_trashed
+            "#,
+        )
+        .bind(recipient_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("mount_names", e))
+    }
+
+    async fn find_mount(
+        &self,
+        recipient_id: Uuid,
+        mount_name: &str,
+    ) -> Result<Option<SharedMount>> {
+        let row = sqlx::query_as::<_, MountRow>(&format!("{MOUNT_SELECT} AND us.mount_name = $2"))
+            .bind(recipient_id)
+            .bind(mount_name)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("find_mount", e))?;
+        Ok(row.map(Self::row_to_mount))
+    }
+
+    async fn list_mounts(&self, recipient_id: Uuid) -> Result<Vec<SharedMount>> {
+        let rows = sqlx::query_as::<_, MountRow>(&format!("{MOUNT_SELECT} ORDER BY us.mount_name"))
+            .bind(recipient_id)
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("list_mounts", e))?;
+        Ok(rows.into_iter().map(Self::row_to_mount).collect())
+    }
+
+    async fn shared_item_ids(&self, user_id: Uuid, item_ids: &[String]) -> Result<HashSet<String>> {
+        if item_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT COALESCE(folder_id, file_id)::text
+              FROM storage.user_shares
+             WHERE (owner_id = $1 OR shared_by = $1)
+               AND COALESCE(folder_id, file_id)::text = ANY($2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(item_ids)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("shared_item_ids", e))?;
+        Ok(ids.into_iter().collect())
+    }
+
+    async fn folder_access(&self, folder_id: &str, user_id: Uuid) -> Result<Option<SharedAccess>> {
+        let Ok(folder_id) = Uuid::parse_str(folder_id) else {
+            return Ok(None);
+        };
+        let row = sqlx::query_as::<_, (Uuid, bool, bool)>(
+            r#"
+            SELECT f.user_id, bool_or(us.can_write), bool_or(us.can_reshare)
+              FROM storage.folders f
+              JOIN storage.folders sf ON f.lpath <@ sf.lpath
+              JOIN storage.user_shares us ON us.folder_id = sf.id
+             WHERE f.id = $1 AND us.recipient_id = $2
+               AND NOT f.is_trashed AND NOT sf.is_trashed
+             GROUP BY f.user_id
+            "#,
+        )
+        .bind(folder_id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("folder_access", e))?;
+        Ok(Self::row_to_access(row))
+    }
+
+    async fn file_access(&self, file_id: &str, user_id: Uuid) -> Result<Option<SharedAccess>> {
+        let Ok(file_id) = Uuid::parse_str(file_id) else {
+            return Ok(None);
+        };
+        let row = sqlx::query_as::<_, (Uuid, bool, bool)>(
+            r#"
+            SELECT fi.user_id, bool_or(us.can_write), bool_or(us.can_reshare)
+              FROM storage.files fi
+              LEFT JOIN storage.folders f ON f.id = fi.folder_id
+              JOIN storage.user_shares us ON us.recipient_id = $2
+              LEFT JOIN storage.folders sf ON sf.id = us.folder_id
+             WHERE fi.id = $1 AND NOT fi.is_trashed
+               AND (us.file_id = fi.id OR (NOT sf.is_trashed AND f.lpath <@ sf.lpath))
+             GROUP BY fi.user_id
+            "#,
+        )
+        .bind(file_id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("file_access", e))?;
+        Ok(Self::row_to_access(row))
+    }
+
+    async fn recipients(&self, item_id: &str, folder_ids: &[&str]) -> Result<Vec<Uuid>> {
+        let item_id = Uuid::parse_str(item_id).ok();
+        let folder_ids: Vec<Uuid> = folder_ids
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+        if item_id.is_none() && folder_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            WITH parents AS (
+                SELECT folder_id AS id FROM storage.files WHERE id = $1
+                UNION SELECT parent_id FROM storage.folders WHERE id = $1
+                UNION SELECT unnest($2::uuid[])
+            )
+            SELECT DISTINCT us.recipient_id
+              FROM storage.user_shares us
+             WHERE us.file_id = $1 OR us.folder_id = $1
+                OR us.folder_id IN (
+                    SELECT sf.id
+                      FROM parents p
+                      JOIN storage.folders f ON f.id = p.id
+                      JOIN storage.folders sf ON f.lpath <@ sf.lpath
+                )
+            "#,
+        )
+        .bind(item_id)
+        .bind(&folder_ids)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("recipients", e))
+    }
+
+    async fn item_owner(&self, item_id: &str, is_folder: bool) -> Result<(Uuid, String)> {
+        let entity = if is_folder { "Folder" } else { "File" };
+        let id = Uuid::parse_str(item_id).map_err(|_| DomainError::not_found(entity, item_id))?;
+        let sql = if is_folder {
+            "SELECT user_id, name FROM storage.folders WHERE id = $1 AND NOT is_trashed"
+        } else {
+            "SELECT user_id, name FROM storage.files WHERE id = $1 AND NOT is_trashed"
+        };
+        sqlx::query_as::<_, (Uuid, String)>(sql)
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("item_owner", e))?
+            .ok_or_else(|| DomainError::not_found(entity, item_id))
+    }
+
+    async fn find_recipient(&self, username_or_email: &str) -> Result<Option<ShareRecipient>> {
+        let row = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, username FROM auth.users \
+              WHERE active AND (username = $1 OR lower(email) = lower($1)) \
+              LIMIT 1",
+        )
+        .bind(username_or_email)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find_recipient", e))?;
+        Ok(row.map(|(id, username)| ShareRecipient { id, username }))
+    }
+}
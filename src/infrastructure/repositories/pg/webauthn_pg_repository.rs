@@ -0,0 +1,169 @@
+//! PostgreSQL repository for WebAuthn credentials
+//! (`auth.webauthn_credentials`, `auth.webauthn_session_families`).
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::ports::webauthn_ports::{WebAuthnCredential, WebAuthnRepositoryPort};
+use crate::common::errors::{DomainError, Result};
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    if let sqlx::Error::Database(ref db_err) = e
+        && db_err.code().as_deref() == Some("23505")
+    {
+        return DomainError::already_exists("WebAuthn", "this passkey is registered already");
+    }
+    DomainError::internal_error("WebAuthn", format!("{context}: {e}"))
+}
+
+const CREDENTIAL_COLUMNS: &str = "id, user_id, credential_id, public_key, algorithm, sign_count, \
+     name, transports, created_at, last_used_at";
+
+type CredentialRow = (
+    Uuid,
+    Uuid,
+    Vec<u8>,
+    Vec<u8>,
+    i32,
+    i64,
+    String,
+    Vec<String>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+fn from_row(row: CredentialRow) -> WebAuthnCredential {
+    let (
+        id,
+        user_id,
+        credential_id,
+        public_key,
+        algorithm,
+        sign_count,
+        name,
+        transports,
+        created_at,
+        last_used_at,
+    ) = row;
+    WebAuthnCredential {
+        id,
+        user_id,
+        credential_id,
+        public_key,
+        algorithm: algorithm as i64,
+        sign_count: sign_count.clamp(0, u32::MAX as i64) as u32,
+        name,
+        transports,
+        created_at,
+        last_used_at,
+    }
+}
+
+/// PostgreSQL implementation of the WebAuthn persistence port.
+pub struct WebAuthnPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl WebAuthnPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl WebAuthnRepositoryPort for WebAuthnPgRepository {
+    async fn create_credential(&self, credential: &WebAuthnCredential) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO auth.webauthn_credentials \
+             (id, user_id, credential_id, public_key, algorithm, sign_count, name, transports, created_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(credential.id)
+        .bind(credential.user_id)
+        .bind(&credential.credential_id)
+        .bind(&credential.public_key)
+        .bind(credential.algorithm as i32)
+        .bind(credential.sign_count as i64)
+        .bind(&credential.name)
+        .bind(&credential.transports)
+        .bind(credential.created_at)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("create credential", e))?;
+        Ok(())
+    }
+
+    async fn list_credentials(&self, user_id: Uuid) -> Result<Vec<WebAuthnCredential>> {
+        let rows = sqlx::query_as::<_, CredentialRow>(&format!(
+            "SELECT {CREDENTIAL_COLUMNS} FROM auth.webauthn_credentials \
+             WHERE user_id = $1 ORDER BY created_at"
+        ))
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list credentials", e))?;
+        Ok(rows.into_iter().map(from_row).collect())
+    }
+
+    async fn count_credentials(&self, user_id: Uuid) -> Result<u64> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM auth.webauthn_credentials WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(self.pool.as_ref())
+                .await
+                .map_err(|e| db_error("count credentials", e))?;
+        Ok(count as u64)
+    }
+
+    async fn find_by_credential_id(
+        &self,
+        credential_id: &[u8],
+    ) -> Result<Option<WebAuthnCredential>> {
+        let row = sqlx::query_as::<_, CredentialRow>(&format!(
+            "SELECT {CREDENTIAL_COLUMNS} FROM auth.webauthn_credentials WHERE credential_id = $1"
+        ))
+        .bind(credential_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find credential", e))?;
+        Ok(row.map(from_row))
+    }
+
+    async fn record_use(&self, id: Uuid, sign_count: u32) -> Result<()> {
+        sqlx::query(
+            "UPDATE auth.webauthn_credentials \
+             SET sign_count = $2, last_used_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(id)
+        .bind(sign_count as i64)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("record credential use", e))?;
+        Ok(())
+    }
+
+    async fn delete_credential(&self, user_id: Uuid, id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM auth.webauthn_credentials WHERE id = $1 AND user_id = $2")
+                .bind(id)
+                .bind(user_id)
+                .execute(self.pool.as_ref())
+                .await
+                .map_err(|e| db_error("delete credential", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn link_session_family(&self, id: Uuid, family_id: Uuid) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO auth.webauthn_session_families (family_id, credential_id) \
+             VALUES ($1, $2) ON CONFLICT (family_id) DO NOTHING",
+        )
+        .bind(family_id)
+        .bind(id)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("link session family", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! PostgreSQL repository for webhooks (`webhooks` schema).
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::ports::webhook_ports::{
+    DeliveryRecord, DeliveryStatus, WebhookRecord, WebhookRepositoryPort,
+};
+use crate::common::errors::{DomainError, Result};
+
+fn db_error(context: &str, e: sqlx::Error) -> DomainError {
+    DomainError::internal_error("Webhook", format!("{context}: {e}"))
+}
+
+const WEBHOOK_COLUMNS: &str = "id, owner_id, url, secret, description, events, path_prefix, \
+                               mime_types, active, created_at, updated_at";
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, event, payload, status, attempts, \
+                                response_status, error, created_at, last_attempt_at";
+
+fn row_to_webhook(row: &PgRow) -> WebhookRecord {
+    WebhookRecord {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        url: row.get("url"),
+        secret: row.get("secret"),
+        description: row.get("description"),
+        events: row.get("events"),
+        path_prefix: row.get("path_prefix"),
+        mime_types: row.get("mime_types"),
+        active: row.get("active"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn row_to_delivery(row: &PgRow) -> DeliveryRecord {
+    DeliveryRecord {
+        id: row.get("id"),
+        webhook_id: row.get("webhook_id"),
+        event: row.get("event"),
+        payload: row.get("payload"),
+        status: row.get("status"),
+        attempts: row.get("attempts"),
+        response_status: row.get("response_status"),
+        error: row.get("error"),
+        created_at: row.get("created_at"),
+        last_attempt_at: row.get("last_attempt_at"),
+    }
+}
+
+/// PostgreSQL implementation of the webhook port.
+pub struct WebhookPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl WebhookPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl WebhookRepositoryPort for WebhookPgRepository {
+    async fn create(&self, w: &WebhookRecord) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO webhooks.subscriptions \
+               (id, owner_id, url, secret, description, events, path_prefix, mime_types, \
+                active, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        )
+        .bind(w.id)
+        .bind(w.owner_id)
+        .bind(&w.url)
+        .bind(&w.secret)
+        .bind(&w.description)
+        .bind(&w.events)
+        .bind(&w.path_prefix)
+        .bind(&w.mime_types)
+        .bind(w.active)
+        .bind(w.created_at)
+        .bind(w.updated_at)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("create webhook", e))?;
+        Ok(())
+    }
+
+    async fn update(&self, w: &WebhookRecord) -> Result<()> {
+        sqlx::query(
+            "UPDATE webhooks.subscriptions \
+                SET url = $2, secret = $3, description = $4, events = $5, path_prefix = $6, \
+                    mime_types = $7, active = $8, updated_at = $9 \
+              WHERE id = $1",
+        )
+        .bind(w.id)
+        .bind(&w.url)
+        .bind(&w.secret)
+        .bind(&w.description)
+        .bind(&w.events)
+        .bind(&w.path_prefix)
+        .bind(&w.mime_types)
+        .bind(w.active)
+        .bind(w.updated_at)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("update webhook", e))?;
+        Ok(())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<WebhookRecord>> {
+        let row = sqlx::query(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks.subscriptions WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("get webhook", e))?;
+        Ok(row.as_ref().map(row_to_webhook))
+    }
+
+    async fn list(&self, owner_id: Option<Uuid>) -> Result<Vec<WebhookRecord>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks.subscriptions \
+              WHERE owner_id IS NOT DISTINCT FROM $1 ORDER BY created_at"
+        ))
+        .bind(owner_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list webhooks", e))?;
+        Ok(rows.iter().map(row_to_webhook).collect())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM webhooks.subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("delete webhook", e))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn subscribers(&self, owner_id: Option<Uuid>) -> Result<Vec<WebhookRecord>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks.subscriptions \
+              WHERE active AND (owner_id IS NULL OR owner_id = $1)"
+        ))
+        .bind(owner_id)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find webhooks", e))?;
+        Ok(rows.iter().map(row_to_webhook).collect())
+    }
+
+    async fn create_delivery(
+        &self,
+        webhook_id: Uuid,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> Result<i64> {
+        sqlx::query_scalar(
+            "INSERT INTO webhooks.deliveries (webhook_id, event, payload) \
+             VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(webhook_id)
+        .bind(event)
+        .bind(payload)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("log delivery", e))
+    }
+
+    async fn record_attempt(
+        &self,
+        id: i64,
+        status: DeliveryStatus,
+        response_status: Option<u16>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE webhooks.deliveries \
+                SET status = $2, attempts = attempts + 1, response_status = $3, error = $4, \
+                    last_attempt_at = CURRENT_TIMESTAMP \
+              WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(response_status.map(i32::from))
+        .bind(error)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("record delivery attempt", e))?;
+        Ok(())
+    }
+
+    async fn list_deliveries(&self, webhook_id: Uuid, limit: usize) -> Result<Vec<DeliveryRecord>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhooks.deliveries \
+              WHERE webhook_id = $1 ORDER BY id DESC LIMIT $2"
+        ))
+        .bind(webhook_id)
+        .bind(limit as i64)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("list deliveries", e))?;
+        Ok(rows.iter().map(row_to_delivery).collect())
+    }
+
+    async fn last_known_file(
+        &self,
+        file_id: &str,
+    ) -> Result<Option<(Option<Uuid>, serde_json::Value)>> {
+        let row = sqlx::query(
+            "SELECT payload->>'user_id' AS owner_id, payload->'file' AS file \
+               FROM webhooks.deliveries \
+              WHERE payload->'file'->>'id' = $1 ORDER BY id DESC LIMIT 1",
+        )
+        .bind(file_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find file in delivery log", e))?;
+        Ok(row.map(|row| {
+            let owner_id: Option<String> = row.get("owner_id");
+            (
+                owner_id.and_then(|id| Uuid::parse_str(&id).ok()),
+                row.get("file"),
+            )
+        }))
+    }
+
+    async fn pending_deliveries(&self, since: DateTime<Utc>) -> Result<Vec<DeliveryRecord>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhooks.deliveries \
+              WHERE status = 'pending' AND created_at > $1 ORDER BY id"
+        ))
+        .bind(since)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| db_error("find pending deliveries", e))?;
+        Ok(rows.iter().map(row_to_delivery).collect())
+    }
+
+    async fn prune_deliveries(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM webhooks.deliveries WHERE created_at < $1")
+            .bind(before)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| db_error("prune deliveries", e))?;
+        Ok(result.rows_affected())
+    }
+}
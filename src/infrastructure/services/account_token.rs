@@ -0,0 +1,126 @@
+//! Signed tokens mailed to users: password reset and address verification
+//! links.  On top of the [`signed_token`] envelope they carry `jti` (the row
+//! that makes them single-use) and, for address verification, the address
+//! they were sent to.  The purpose is the audience.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::application::ports::account_token_ports::AccountTokenPurpose;
+use crate::common::errors::DomainError;
+use crate::infrastructure::services::signed_token::{self, TokenScope};
+
+const DOMAIN: &str = "oxicloud-account-token";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AccountClaims {
+    jti: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+}
+
+/// Contents of a verified token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountToken {
+    pub user_id: Uuid,
+    pub token_id: Uuid,
+    pub email: Option<String>,
+}
+
+fn audience(purpose: AccountTokenPurpose) -> String {
+    format!("oxicloud:{}", purpose.as_str())
+}
+
+pub fn issue(
+    secret: &str,
+    purpose: AccountTokenPurpose,
+    token: &AccountToken,
+    ttl_secs: i64,
+) -> Result<String, DomainError> {
+    let claims = AccountClaims {
+        jti: token.token_id.to_string(),
+        email: token.email.clone(),
+    };
+    let audience = audience(purpose);
+    let scope = TokenScope {
+        domain: DOMAIN,
+        audience: &audience,
+    };
+    signed_token::issue(secret, scope, token.user_id, claims, ttl_secs)
+}
+
+/// Returns the token's contents iff it is well-formed, signed by `secret`,
+/// unexpired and issued for `purpose`.
+pub fn verify(secret: &str, purpose: AccountTokenPurpose, jwt: &str) -> Option<AccountToken> {
+    let audience = audience(purpose);
+    let scope = TokenScope {
+        domain: DOMAIN,
+        audience: &audience,
+    };
+    let (user_id, claims) = signed_token::verify::<AccountClaims>(secret, scope, jwt)?;
+    Some(AccountToken {
+        user_id,
+        token_id: Uuid::parse_str(&claims.jti).ok()?,
+        email: claims.email,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SECRET: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn token() -> AccountToken {
+        AccountToken {
+            user_id: Uuid::new_v4(),
+            token_id: Uuid::new_v4(),
+            email: Some("alice@example.com".to_string()),
+        }
+    }
+
+    #[test]
+    fn issue_then_verify_round_trips() {
+        let token = token();
+        let jwt = issue(
+            TEST_SECRET,
+            AccountTokenPurpose::EmailVerification,
+            &token,
+            60,
+        )
+        .unwrap();
+        assert_eq!(
+            verify(TEST_SECRET, AccountTokenPurpose::EmailVerification, &jwt),
+            Some(token)
+        );
+    }
+
+    #[test]
+    fn verify_rejects_wrong_purpose_secret_expiry_and_plain_jwts() {
+        let jwt = issue(
+            TEST_SECRET,
+            AccountTokenPurpose::PasswordReset,
+            &token(),
+            60,
+        )
+        .unwrap();
+        assert!(verify(TEST_SECRET, AccountTokenPurpose::EmailVerification, &jwt).is_none());
+        assert!(verify("other-secret", AccountTokenPurpose::PasswordReset, &jwt).is_none());
+
+        let expired = issue(
+            TEST_SECRET,
+            AccountTokenPurpose::PasswordReset,
+            &token(),
+            -10,
+        )
+        .unwrap();
+        assert!(verify(TEST_SECRET, AccountTokenPurpose::PasswordReset, &expired).is_none());
+
+        // Signed with the JWT secret itself, as access tokens are
+        let plain =
+            crate::infrastructure::services::share_unlock_cookie::issue_jwt(TEST_SECRET, "x", 60)
+                .unwrap();
+        assert!(verify(TEST_SECRET, AccountTokenPurpose::PasswordReset, &plain).is_none());
+        assert!(verify(TEST_SECRET, AccountTokenPurpose::PasswordReset, "garbage").is_none());
+    }
+}
@@ -738,7 +738,7 @@ impl DedupService {
 
         if let Some((ref_count, chunk_hashes)) = manifest {
             return self
-                .remove_manifest_reference(hash, ref_count, &chunk_hashes)
+                .remove_manifest_reference(hash, ref_count, &chunk_hashes, false)
                 .await;
         }
 
@@ -747,11 +747,16 @@ impl DedupService {
     }
 
     /// Remove a manifest reference.  Handles chunk cleanup when last ref is removed.
+    ///
+    /// With `undo_trigger_decrement`, first restores the `storage.blobs`
+    /// row keyed by `file_hash` that the PG trigger on `storage.files`
+    /// already decremented, in the same transaction.
     async fn remove_manifest_reference(
         &self,
         file_hash: &str,
         _initial_ref_count: i32,
         chunk_hashes: &[String],
+        undo_trigger_decrement: bool,
     ) -> Result<bool, DomainError> {
         let mut tx = self.pool.begin().await.map_err(|e| {
             DomainError::internal_error("Dedup", format!("Failed to begin TX: {}", e))
@@ -771,6 +776,17 @@ impl DedupService {
             return Ok(false);
         };
 
+        if undo_trigger_decrement {
+            // A no-op for multi-chunk files, whose file_hash is not a blob
+            sqlx::query("UPDATE storage.blobs SET ref_count = ref_count + 1 WHERE hash = $1")
+                .bind(file_hash)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    DomainError::internal_error("Dedup", format!("Restore blob ref_count: {}", e))
+                })?;
+        }
+
         if current_rc <= 1 {
             // Last reference — delete manifest and decrement chunks
             sqlx::query("DELETE FROM storage.chunk_manifests WHERE file_hash = $1")
@@ -950,8 +966,12 @@ impl DedupService {
         if let Some((ref_count, chunk_hashes)) = manifest {
             if ref_count <= 1 {
                 // Last reference — remove manifest and all its chunks.
+                // remove_manifest_reference decrements every chunk itself,
+                // so it first undoes the PG trigger's decrement or a
+                // single-chunk blob would go below 0 and the whole cleanup
+                // roll back.
                 if let Err(e) = self
-                    .remove_manifest_reference(hash, ref_count, &chunk_hashes)
+                    .remove_manifest_reference(hash, ref_count, &chunk_hashes, true)
                     .await
                 {
                     tracing::warn!("cleanup_if_orphaned: manifest cleanup failed for {short}: {e}");
@@ -0,0 +1,141 @@
+//! Certificates for end-to-end encryption.
+//!
+//! Clients generate their key pair locally and send a certificate signing
+//! request; the server signs it with its own RSA key so other users can
+//! check whose public key they encrypt for.  The server key is generated
+//! once and stored as PKCS#8 PEM.
+
+use chrono::{Datelike, Duration, Utc};
+use rcgen::{
+    CertificateParams, CertificateSigningRequestParams, DistinguishedName, DnType, DnValue, IsCa,
+    Issuer, KeyPair,
+};
+use rsa::RsaPrivateKey;
+use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+
+/// Size of the server's RSA key in bits
+const SERVER_KEY_BITS: usize = 2048;
+/// Common name on the issuer side of user certificates
+const ISSUER_NAME: &str = "OxiCloud";
+/// How long an issued certificate is valid
+const CERTIFICATE_VALIDITY_YEARS: i32 = 20;
+
+/// Why a signing request was not signed.
+#[derive(Debug, PartialEq, Eq)]
+pub enum CsrError {
+    /// Not a well-formed request, or its signature does not verify
+    Invalid,
+    /// The request names someone other than the requesting user
+    WrongCommonName,
+    /// The server key could not be used
+    Signing(String),
+}
+
+/// A new server key as PKCS#8 PEM.
+pub fn generate_server_key() -> Result<String, String> {
+    let key = RsaPrivateKey::new(&mut rand_core::OsRng, SERVER_KEY_BITS)
+        .map_err(|e| format!("RSA key generation failed: {e}"))?;
+    key.to_pkcs8_pem(LineEnding::LF)
+        .map(|pem| pem.to_string())
+        .map_err(|e| format!("RSA key encoding failed: {e}"))
+}
+
+/// Public half of the server key, as a PEM `PUBLIC KEY`.
+pub fn server_public_key(server_key: &str) -> Result<String, String> {
+    KeyPair::from_pem(server_key)
+        .map(|key| key.public_key_pem())
+        .map_err(|e| format!("Invalid server key: {e}"))
+}
+
+fn dn_text(value: &DnValue) -> Option<&str> {
+    match value {
+        DnValue::Utf8String(s) => Some(s),
+        DnValue::PrintableString(s) => Some(s.as_str()),
+        DnValue::Ia5String(s) => Some(s.as_str()),
+        _ => None,
+    }
+}
+
+/// Signs a PEM certificate signing request for `username` and returns the
+/// certificate as PEM.
+///
+/// The request's common name must be the username, as Nextcloud clients
+/// check it when they pick up another user's key.  Extensions asking for
+/// CA rights are dropped.
+pub fn sign_csr(csr_pem: &str, username: &str, server_key: &str) -> Result<String, CsrError> {
+    let mut request =
+        CertificateSigningRequestParams::from_pem(csr_pem).map_err(|_| CsrError::Invalid)?;
+    let common_name = request
+        .params
+        .distinguished_name
+        .get(&DnType::CommonName)
+        .and_then(dn_text);
+    if common_name != Some(username) {
+        return Err(CsrError::WrongCommonName);
+    }
+
+    let today = Utc::now().date_naive();
+    let until = today + Duration::days(365 * i64::from(CERTIFICATE_VALIDITY_YEARS));
+    request.params.is_ca = IsCa::ExplicitNoCa;
+    request.params.not_before =
+        rcgen::date_time_ymd(today.year(), today.month() as u8, today.day() as u8);
+    request.params.not_after =
+        rcgen::date_time_ymd(until.year(), until.month() as u8, until.day() as u8);
+
+    let key = KeyPair::from_pem(server_key).map_err(|e| CsrError::Signing(e.to_string()))?;
+    let mut issuer_params = CertificateParams::default();
+    let mut issuer_name = DistinguishedName::new();
+    issuer_name.push(DnType::CommonName, ISSUER_NAME);
+    issuer_params.distinguished_name = issuer_name;
+    let issuer = Issuer::new(issuer_params, key);
+
+    request
+        .signed_by(&issuer)
+        .map(|certificate| certificate.pem())
+        .map_err(|e| CsrError::Signing(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csr_for(common_name: &str) -> String {
+        let mut params = CertificateParams::default();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+        params
+            .serialize_request(&KeyPair::generate().unwrap())
+            .unwrap()
+            .pem()
+            .unwrap()
+    }
+
+    #[test]
+    fn sign_csr_issues_certificate_for_own_name() {
+        // An EC key keeps the test fast; signing does not care about the type
+        let server_key = KeyPair::generate().unwrap().serialize_pem();
+
+        let certificate = sign_csr(&csr_for("alice"), "alice", &server_key).unwrap();
+        assert!(certificate.starts_with("-----BEGIN CERTIFICATE-----"));
+        assert!(
+            server_public_key(&server_key)
+                .unwrap()
+                .contains("PUBLIC KEY")
+        );
+    }
+
+    #[test]
+    fn sign_csr_rejects_other_names_and_garbage() {
+        let server_key = KeyPair::generate().unwrap().serialize_pem();
+
+        assert_eq!(
+            sign_csr(&csr_for("bob"), "alice", &server_key),
+            Err(CsrError::WrongCommonName)
+        );
+        assert_eq!(
+            sign_csr("not a request", "alice", &server_key),
+            Err(CsrError::Invalid)
+        );
+    }
+}
@@ -0,0 +1,641 @@
+//! LDAP / Active Directory authentication.
+//!
+//! Users are looked up with the service account (or anonymously when no
+//! bind DN is configured), then authenticated by binding as their own DN
+//! on the same connection.  Each operation opens a fresh connection: logins
+//! are infrequent enough that pooling is not worth the reconnect handling.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use ldap3::{Ldap, LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry, ldap_escape};
+
+use crate::application::dtos::settings_dto::{LdapTestResultDto, LdapTestUserDto};
+use crate::application::ports::auth_ports::{LdapServicePort, LdapUserEntry};
+use crate::common::config::LdapConfig;
+use crate::common::errors::{DomainError, ErrorKind};
+
+/// LDAP result code for a failed bind
+const RC_INVALID_CREDENTIALS: u32 = 49;
+
+fn server_error(e: LdapError) -> DomainError {
+    DomainError::new(
+        ErrorKind::InternalError,
+        "LDAP",
+        format!("LDAP server error: {}", e),
+    )
+}
+
+fn invalid_credentials() -> DomainError {
+    DomainError::new(ErrorKind::AccessDenied, "Auth", "Invalid credentials")
+}
+
+/// Values of an attribute; attribute names are case-insensitive.
+fn attr_values<'a>(attrs: &'a HashMap<String, Vec<String>>, name: &str) -> &'a [String] {
+    if name.is_empty() {
+        return &[];
+    }
+    attrs
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, values)| values.as_slice())
+        .unwrap_or(&[])
+}
+
+fn first_value(attrs: &HashMap<String, Vec<String>>, name: &str) -> Option<String> {
+    attr_values(attrs, name)
+        .iter()
+        .map(|v| v.trim())
+        .find(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Group name from a `memberOf` value: the value of the first RDN of a DN
+/// (`cn=staff,ou=groups,dc=example,dc=org` → `staff`).  Values that are
+/// not DNs are kept as they are.
+fn group_name(value: &str) -> String {
+    let mut rdn_value = None;
+    let mut current = String::new();
+    let mut chars = value.trim().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            '=' if rdn_value.is_none() => {
+                rdn_value = Some(String::new());
+                current.clear();
+            }
+            ',' | '+' if rdn_value.is_some() => break,
+            _ => current.push(c),
+        }
+    }
+    match rdn_value {
+        Some(_) => current.trim().to_string(),
+        None => value.trim().to_string(),
+    }
+}
+
+/// Maps a directory entry to the attributes OxiCloud uses.  `login_name`
+/// stands in when the entry lacks the username attribute.
+fn map_entry(
+    config: &LdapConfig,
+    dn: String,
+    attrs: &HashMap<String, Vec<String>>,
+    login_name: &str,
+) -> LdapUserEntry {
+    let quota_bytes = first_value(attrs, &config.quota_attribute).and_then(|v| {
+        let parsed = v.parse::<i64>().ok().filter(|q| *q >= 0);
+        if parsed.is_none() {
+            tracing::warn!("Ignoring invalid LDAP quota {:?} of {}", v, dn);
+        }
+        parsed
+    });
+    LdapUserEntry {
+        username: first_value(attrs, &config.username_attribute)
+            .unwrap_or_else(|| login_name.to_string()),
+        email: first_value(attrs, &config.email_attribute),
+        display_name: first_value(attrs, &config.display_name_attribute),
+        groups: attr_values(attrs, &config.groups_attribute)
+            .iter()
+            .map(|v| group_name(v))
+            .filter(|name| !name.is_empty())
+            .collect(),
+        quota_bytes,
+        dn,
+    }
+}
+
+/// A connection to the directory, bound as the service account (or
+/// anonymous) when opened.
+pub trait DirectoryConnection: Send {
+    /// Runs a search; a non-success result is an error.
+    async fn search(
+        &mut self,
+        base: &str,
+        scope: Scope,
+        filter: &str,
+        attrs: Vec<&str>,
+    ) -> Result<Vec<SearchEntry>, DomainError>;
+
+    /// Binds as `dn` and returns the LDAP result code.
+    async fn bind(&mut self, dn: &str, password: &str) -> Result<u32, DomainError>;
+
+    async fn unbind(&mut self);
+}
+
+/// Opens connections to the configured directory.
+pub trait Directory: Send + Sync + 'static {
+    type Connection: DirectoryConnection;
+
+    async fn connect(&self, config: &LdapConfig) -> Result<Self::Connection, DomainError>;
+}
+
+fn timeout(config: &LdapConfig) -> Duration {
+    Duration::from_secs(config.timeout_secs.max(1))
+}
+
+/// The directory reached over the network with `ldap3`.
+pub struct Ldap3Directory;
+
+pub struct Ldap3Connection {
+    ldap: Ldap,
+    timeout: Duration,
+}
+
+impl DirectoryConnection for Ldap3Connection {
+    async fn search(
+        &mut self,
+        base: &str,
+        scope: Scope,
+        filter: &str,
+        attrs: Vec<&str>,
+    ) -> Result<Vec<SearchEntry>, DomainError> {
+        let (entries, _) = self
+            .ldap
+            .with_timeout(self.timeout)
+            .search(base, scope, filter, attrs)
+            .await
+            .map_err(server_error)?
+            .success()
+            .map_err(server_error)?;
+        Ok(entries.into_iter().map(SearchEntry::construct).collect())
+    }
+
+    async fn bind(&mut self, dn: &str, password: &str) -> Result<u32, DomainError> {
+        let result = self
+            .ldap
+            .with_timeout(self.timeout)
+            .simple_bind(dn, password)
+            .await
+            .map_err(server_error)?;
+        Ok(result.rc)
+    }
+
+    async fn unbind(&mut self) {
+        let _ = self.ldap.unbind().await;
+    }
+}
+
+impl Directory for Ldap3Directory {
+    type Connection = Ldap3Connection;
+
+    async fn connect(&self, config: &LdapConfig) -> Result<Ldap3Connection, DomainError> {
+        let timeout = timeout(config);
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(timeout)
+            .set_starttls(config.starttls)
+            .set_no_tls_verify(config.tls_skip_verify);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &config.url)
+            .await
+            .map_err(server_error)?;
+        ldap3::drive!(conn);
+
+        if !config.bind_dn.is_empty() {
+            ldap.with_timeout(timeout)
+                .simple_bind(&config.bind_dn, &config.bind_password)
+                .await
+                .map_err(server_error)?
+                .success()
+                .map_err(|e| {
+                    DomainError::new(
+                        ErrorKind::InternalError,
+                        "LDAP",
+                        format!("LDAP service account bind failed: {}", e),
+                    )
+                })?;
+        }
+        Ok(Ldap3Connection { ldap, timeout })
+    }
+}
+
+/// LDAP service implementing `LdapServicePort`.
+pub struct LdapService<D: Directory = Ldap3Directory> {
+    config: LdapConfig,
+    directory: D,
+}
+
+impl LdapService {
+    pub fn new(config: LdapConfig) -> Self {
+        Self::with_directory(config, Ldap3Directory)
+    }
+}
+
+impl<D: Directory> LdapService<D> {
+    pub fn with_directory(config: LdapConfig, directory: D) -> Self {
+        Self { config, directory }
+    }
+
+    pub fn config(&self) -> &LdapConfig {
+        &self.config
+    }
+
+    fn requested_attributes(&self) -> Vec<&str> {
+        [
+            self.config.username_attribute.as_str(),
+            self.config.email_attribute.as_str(),
+            self.config.display_name_attribute.as_str(),
+            self.config.groups_attribute.as_str(),
+            self.config.quota_attribute.as_str(),
+        ]
+        .into_iter()
+        .filter(|a| !a.is_empty())
+        .collect()
+    }
+
+    /// Searches the user below the base DN.  More than one match is an
+    /// error: logging in to an arbitrary one of them would be worse.
+    async fn search_user(
+        &self,
+        conn: &mut D::Connection,
+        username: &str,
+    ) -> Result<Option<LdapUserEntry>, DomainError> {
+        let filter = self
+            .config
+            .user_filter
+            .replace("{username}", &ldap_escape(username));
+        let entries = conn
+            .search(
+                &self.config.user_base_dn,
+                Scope::Subtree,
+                &filter,
+                self.requested_attributes(),
+            )
+            .await?;
+
+        let mut entries = entries.into_iter();
+        let Some(entry) = entries.next() else {
+            return Ok(None);
+        };
+        if entries.next().is_some() {
+            return Err(DomainError::new(
+                ErrorKind::InternalError,
+                "LDAP",
+                format!(
+                    "LDAP user filter matches several entries for '{}'",
+                    username
+                ),
+            ));
+        }
+        Ok(Some(map_entry(
+            &self.config,
+            entry.dn,
+            &entry.attrs,
+            username,
+        )))
+    }
+
+    /// Checks the connection, the service account and the base DN, and
+    /// looks up `username` when given.
+    pub async fn test_connection(&self, username: Option<&str>) -> LdapTestResultDto {
+        let failure = |message: String| LdapTestResultDto {
+            success: false,
+            message,
+            user: None,
+        };
+
+        let mut conn = match self.directory.connect(&self.config).await {
+            Ok(conn) => conn,
+            Err(e) => return failure(e.message),
+        };
+
+        let result = match username.map(str::trim).filter(|u| !u.is_empty()) {
+            Some(username) => match self.search_user(&mut conn, username).await {
+                Ok(Some(entry)) => LdapTestResultDto {
+                    success: true,
+                    message: format!("Connected and found '{}'", username),
+                    user: Some(LdapTestUserDto {
+                        dn: entry.dn,
+                        username: entry.username,
+                        email: entry.email,
+                        display_name: entry.display_name,
+                        groups: entry.groups,
+                        quota_bytes: entry.quota_bytes,
+                    }),
+                },
+                Ok(None) => failure(format!(
+                    "Connected, but no entry under '{}' matches the user filter for '{}'",
+                    self.config.user_base_dn, username
+                )),
+                Err(e) => failure(e.message),
+            },
+            None => {
+                let base = conn
+                    .search(
+                        &self.config.user_base_dn,
+                        Scope::Base,
+                        "(objectClass=*)",
+                        vec!["1.1"],
+                    )
+                    .await;
+                match base {
+                    Ok(_) => LdapTestResultDto {
+                        success: true,
+                        message: "Connected, and the user base DN exists".to_string(),
+                        user: None,
+                    },
+                    Err(e) => failure(format!(
+                        "Connected, but the user base DN '{}' cannot be read: {}",
+                        self.config.user_base_dn, e.message
+                    )),
+                }
+            }
+        };
+
+        conn.unbind().await;
+        result
+    }
+}
+
+impl<D: Directory> LdapServicePort for LdapService<D> {
+    async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<LdapUserEntry, DomainError> {
+        // An empty password makes a simple bind "unauthenticated", which
+        // many servers accept for any DN.
+        if username.trim().is_empty() || password.is_empty() {
+            return Err(invalid_credentials());
+        }
+
+        let mut conn = self.directory.connect(&self.config).await?;
+        let entry = self.search_user(&mut conn, username).await;
+        let entry = match entry {
+            Ok(Some(entry)) => entry,
+            Ok(None) => {
+                conn.unbind().await;
+                return Err(invalid_credentials());
+            }
+            Err(e) => {
+                conn.unbind().await;
+                return Err(e);
+            }
+        };
+
+        let bind = conn.bind(&entry.dn, password).await;
+        conn.unbind().await;
+
+        match bind? {
+            0 => Ok(entry),
+            RC_INVALID_CREDENTIALS => Err(invalid_credentials()),
+            rc => Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Auth",
+                format!("LDAP bind refused (result code {})", rc),
+            )),
+        }
+    }
+
+    async fn find_user(&self, username: &str) -> Result<Option<LdapUserEntry>, DomainError> {
+        let mut conn = self.directory.connect(&self.config).await?;
+        let entry = self.search_user(&mut conn, username).await;
+        conn.unbind().await;
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const ALICE_DN: &str = "uid=alice,ou=people,dc=example,dc=org";
+
+    /// In-memory directory: entries are returned for the exact filter they
+    /// are registered under, and every call is logged.
+    #[derive(Default)]
+    struct FakeDirectory {
+        entries: Vec<(String, SearchEntry)>,
+        passwords: HashMap<String, String>,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct FakeConnection {
+        entries: Vec<(String, SearchEntry)>,
+        passwords: HashMap<String, String>,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeDirectory {
+        fn with_user(mut self, filter: &str, dn: &str, uid: &str, password: &str) -> Self {
+            let entry = SearchEntry {
+                dn: dn.to_string(),
+                attrs: HashMap::from([
+                    ("uid".to_string(), vec![uid.to_string()]),
+                    ("mail".to_string(), vec![format!("{}@example.org", uid)]),
+                ]),
+                bin_attrs: HashMap::new(),
+            };
+            self.entries.push((filter.to_string(), entry));
+            self.passwords.insert(dn.to_string(), password.to_string());
+            self
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.log.lock().unwrap().clone()
+        }
+    }
+
+    impl DirectoryConnection for FakeConnection {
+        async fn search(
+            &mut self,
+            base: &str,
+            _scope: Scope,
+            filter: &str,
+            _attrs: Vec<&str>,
+        ) -> Result<Vec<SearchEntry>, DomainError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("search {} {}", base, filter));
+            Ok(self
+                .entries
+                .iter()
+                .filter(|(f, _)| f == filter)
+                .map(|(_, entry)| entry.clone())
+                .collect())
+        }
+
+        async fn bind(&mut self, dn: &str, password: &str) -> Result<u32, DomainError> {
+            self.log.lock().unwrap().push(format!("bind {}", dn));
+            match self.passwords.get(dn) {
+                Some(p) if p == password => Ok(0),
+                _ => Ok(RC_INVALID_CREDENTIALS),
+            }
+        }
+
+        async fn unbind(&mut self) {
+            self.log.lock().unwrap().push("unbind".to_string());
+        }
+    }
+
+    impl Directory for FakeDirectory {
+        type Connection = FakeConnection;
+
+        async fn connect(&self, _config: &LdapConfig) -> Result<FakeConnection, DomainError> {
+            self.log.lock().unwrap().push("connect".to_string());
+            Ok(FakeConnection {
+                entries: self.entries.clone(),
+                passwords: self.passwords.clone(),
+                log: self.log.clone(),
+            })
+        }
+    }
+
+    fn service(directory: FakeDirectory) -> LdapService<FakeDirectory> {
+        let config = LdapConfig {
+            user_base_dn: "ou=people,dc=example,dc=org".to_string(),
+            user_filter: "(uid={username})".to_string(),
+            ..LdapConfig::default()
+        };
+        LdapService::with_directory(config, directory)
+    }
+
+    #[tokio::test]
+    async fn authenticate_searches_then_binds_as_the_entry() {
+        let ldap =
+            service(FakeDirectory::default().with_user("(uid=alice)", ALICE_DN, "alice", "secret"));
+
+        let entry = ldap.authenticate("alice", "secret").await.unwrap();
+        assert_eq!(entry.dn, ALICE_DN);
+        assert_eq!(entry.username, "alice");
+        assert_eq!(entry.email.as_deref(), Some("alice@example.org"));
+        assert_eq!(
+            ldap.directory.calls(),
+            vec![
+                "connect".to_string(),
+                "search ou=people,dc=example,dc=org (uid=alice)".to_string(),
+                format!("bind {}", ALICE_DN),
+                "unbind".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn authenticate_rejects_wrong_passwords_and_unknown_users() {
+        let ldap =
+            service(FakeDirectory::default().with_user("(uid=alice)", ALICE_DN, "alice", "secret"));
+
+        let err = ldap.authenticate("alice", "wrong").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+
+        let err = ldap.authenticate("bob", "secret").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+        // Unknown users are never bound
+        assert_eq!(
+            ldap.directory
+                .calls()
+                .iter()
+                .filter(|c| c.starts_with("bind"))
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn authenticate_rejects_empty_passwords_without_connecting() {
+        let ldap =
+            service(FakeDirectory::default().with_user("(uid=alice)", ALICE_DN, "alice", ""));
+
+        let err = ldap.authenticate("alice", "").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+        let err = ldap.authenticate("  ", "secret").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+        assert!(ldap.directory.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn usernames_are_escaped_in_the_filter() {
+        let ldap = service(FakeDirectory::default());
+
+        assert!(ldap.find_user("*)(uid=admin").await.unwrap().is_none());
+        assert!(ldap.directory.calls().contains(
+            &"search ou=people,dc=example,dc=org (uid=\\2a\\29\\28uid=admin)".to_string()
+        ));
+    }
+
+    #[tokio::test]
+    async fn several_matches_are_an_error() {
+        let ldap = service(
+            FakeDirectory::default()
+                .with_user("(uid=alice)", ALICE_DN, "alice", "secret")
+                .with_user(
+                    "(uid=alice)",
+                    "uid=alice,ou=former,dc=example,dc=org",
+                    "alice",
+                    "secret",
+                ),
+        );
+
+        let err = ldap.authenticate("alice", "secret").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InternalError);
+        assert!(!ldap.directory.calls().iter().any(|c| c.starts_with("bind")));
+    }
+
+    /// Runs against the directory in `tests/common/glauth.cfg`.
+    #[tokio::test]
+    #[ignore = "needs the glauth-test service of tests/common/docker-compose.test.yml"]
+    async fn authenticates_against_glauth() {
+        let ldap = LdapService::new(LdapConfig {
+            url: std::env::var("OXICLOUD_TEST_LDAP_URL")
+                .unwrap_or_else(|_| "ldap://127.0.0.1:3893".to_string()),
+            bind_dn: "cn=search,ou=svcaccts,dc=glauth,dc=com".to_string(),
+            bind_password: "search-secret".to_string(),
+            user_base_dn: "dc=glauth,dc=com".to_string(),
+            user_filter: "(&(objectClass=posixAccount)(uid={username}))".to_string(),
+            ..LdapConfig::default()
+        });
+
+        let entry = ldap.authenticate("alice", "TestPassword1!").await.unwrap();
+        assert_eq!(entry.username, "alice");
+        assert_eq!(entry.email.as_deref(), Some("alice@glauth.com"));
+        assert!(entry.groups.iter().any(|g| g == "staff"));
+
+        let err = ldap.authenticate("alice", "wrong").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+        assert!(ldap.find_user("nobody").await.unwrap().is_none());
+        assert!(ldap.test_connection(Some("alice")).await.success);
+    }
+
+    #[test]
+    fn group_name_takes_the_first_rdn_value() {
+        assert_eq!(group_name("cn=staff,ou=groups,dc=example,dc=org"), "staff");
+        assert_eq!(
+            group_name("CN=Sales\\, EMEA,OU=Groups,DC=corp"),
+            "Sales, EMEA"
+        );
+        assert_eq!(group_name("developers"), "developers");
+    }
+
+    #[test]
+    fn map_entry_reads_attributes_case_insensitively() {
+        let config = LdapConfig {
+            quota_attribute: "oxicloudQuota".to_string(),
+            ..LdapConfig::default()
+        };
+        let attrs = HashMap::from([
+            ("UID".to_string(), vec!["alice".to_string()]),
+            ("mail".to_string(), vec!["alice@example.org".to_string()]),
+            (
+                "memberOf".to_string(),
+                vec!["cn=staff,ou=groups,dc=example,dc=org".to_string()],
+            ),
+            ("oxicloudquota".to_string(), vec!["1073741824".to_string()]),
+        ]);
+
+        let entry = map_entry(
+            &config,
+            "uid=alice,dc=example,dc=org".into(),
+            &attrs,
+            "ALICE",
+        );
+        assert_eq!(entry.username, "alice");
+        assert_eq!(entry.email.as_deref(), Some("alice@example.org"));
+        assert_eq!(entry.display_name, None);
+        assert_eq!(entry.groups, vec!["staff"]);
+        assert_eq!(entry.quota_bytes, Some(1_073_741_824));
+    }
+}
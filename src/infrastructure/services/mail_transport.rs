@@ -0,0 +1,135 @@
+//! Outbound mail delivery (MailPort implementation).
+//!
+//! SMTP is the production transport.  The file transport writes each
+//! message as an .eml file and the log transport prints it, which is enough
+//! to follow reset and verification links on a development machine.  A
+//! local SMTP sink such as MailHog works with the SMTP transport and
+//! `OXICLOUD_SMTP_TLS=none`.
+
+use std::time::Duration;
+
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::application::ports::mail_ports::{MailMessage, MailPort};
+use crate::common::config::{MailConfig, MailTransportKind, SmtpTlsMode};
+use crate::common::errors::{DomainError, ErrorKind, Result};
+
+/// Timeout for a whole SMTP exchange
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+enum Transport {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    File(AsyncFileTransport<Tokio1Executor>),
+    Log,
+}
+
+pub struct MailTransport {
+    from: Mailbox,
+    transport: Transport,
+}
+
+fn mail_error(message: impl Into<String>) -> DomainError {
+    DomainError::new(ErrorKind::InternalError, "Mail", message)
+}
+
+impl MailTransport {
+    /// Builds the configured transport, or `None` when mail is disabled.
+    pub fn from_config(config: &MailConfig) -> Result<Option<Self>> {
+        let from: Mailbox = config
+            .from
+            .parse()
+            .map_err(|e| mail_error(format!("Invalid sender address '{}': {}", config.from, e)))?;
+
+        let transport = match config.transport {
+            MailTransportKind::Disabled => return Ok(None),
+            MailTransportKind::Smtp => {
+                let host = config.smtp_host.as_str();
+                let builder = match config.smtp_tls {
+                    SmtpTlsMode::None => {
+                        AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+                    }
+                    SmtpTlsMode::StartTls => {
+                        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                            .map_err(|e| mail_error(format!("SMTP TLS setup failed: {}", e)))?
+                    }
+                    SmtpTlsMode::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+                        .map_err(|e| mail_error(format!("SMTP TLS setup failed: {}", e)))?,
+                };
+                let mut builder = builder.port(config.smtp_port).timeout(Some(SMTP_TIMEOUT));
+                if !config.smtp_username.is_empty() {
+                    builder = builder.credentials(Credentials::new(
+                        config.smtp_username.clone(),
+                        config.smtp_password.clone(),
+                    ));
+                }
+                Transport::Smtp(builder.build())
+            }
+            MailTransportKind::File => {
+                std::fs::create_dir_all(&config.file_dir).map_err(|e| {
+                    mail_error(format!(
+                        "Cannot create mail directory {:?}: {}",
+                        config.file_dir, e
+                    ))
+                })?;
+                Transport::File(AsyncFileTransport::<Tokio1Executor>::new(&config.file_dir))
+            }
+            MailTransportKind::Log => Transport::Log,
+        };
+        Ok(Some(Self { from, transport }))
+    }
+
+    /// Short description for the startup log
+    pub fn describe(config: &MailConfig) -> String {
+        match config.transport {
+            MailTransportKind::Disabled => "disabled".to_string(),
+            MailTransportKind::Smtp => {
+                format!("smtp ({}:{})", config.smtp_host, config.smtp_port)
+            }
+            MailTransportKind::File => format!("file ({})", config.file_dir.display()),
+            MailTransportKind::Log => "log".to_string(),
+        }
+    }
+}
+
+impl MailPort for MailTransport {
+    async fn send(&self, message: MailMessage) -> Result<()> {
+        let to: Mailbox = message.to.parse().map_err(|e| {
+            DomainError::new(
+                ErrorKind::InvalidInput,
+                "Mail",
+                format!("Invalid recipient '{}': {}", message.to, e),
+            )
+        })?;
+
+        if let Transport::Log = self.transport {
+            tracing::info!("Mail to {} — {}\n{}", to, message.subject, message.body);
+            return Ok(());
+        }
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body)
+            .map_err(|e| mail_error(format!("Cannot build message: {}", e)))?;
+
+        match &self.transport {
+            Transport::Smtp(smtp) => {
+                smtp.send(email)
+                    .await
+                    .map_err(|e| mail_error(format!("SMTP delivery failed: {}", e)))?;
+            }
+            Transport::File(file) => {
+                file.send(email)
+                    .await
+                    .map_err(|e| mail_error(format!("Cannot write message: {}", e)))?;
+            }
+            Transport::Log => {}
+        }
+        Ok(())
+    }
+}
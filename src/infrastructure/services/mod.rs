@@ -1,31 +1,44 @@
+pub mod account_token;
 pub mod audio_metadata_service;
 pub mod azure_blob_backend;
 pub mod cached_blob_backend;
 pub mod chunked_upload_service;
 pub mod compression_service;
 pub mod dedup_service;
+pub mod e2ee_keys;
 pub mod encrypted_blob_backend;
 pub mod exif_service;
 pub mod file_content_cache;
 pub mod file_system_i18n_service;
 pub mod image_transcode_service;
 pub mod jwt_service;
+pub mod ldap_service;
 pub mod local_blob_backend;
 pub mod login_lockout_service;
+pub mod mail_transport;
 pub mod migration_blob_backend;
 pub mod migration_job;
 pub mod nextcloud_chunked_upload_service;
+pub mod notification_check_service;
 pub mod oidc_service;
 pub mod password_hasher;
 pub mod path_resolver_service;
 pub mod path_service;
+pub mod pg_live_event_relay;
+pub mod push_token;
+pub mod retention_cleanup_service;
 pub mod retry_blob_backend;
 pub mod s3_blob_backend;
 pub mod share_unlock_cookie;
+pub mod signed_token;
+pub mod snapshot_scheduler_service;
 pub mod thumbnail_service;
 #[cfg(test)]
 mod thumbnail_service_test;
+pub mod totp;
 pub mod trash_cleanup_service;
+pub mod webauthn;
 pub mod webdav_lock_service;
+pub mod webhook_sender;
 pub mod wopi_discovery_service;
 pub mod zip_service;
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, info, instrument};
+
+use crate::application::services::notification_service::NotificationService;
+
+/// Background job that looks for conditions users should be notified
+/// about (nearly full storage, expiring shared links) and prunes old
+/// notifications.
+pub struct NotificationCheckService {
+    service: Arc<NotificationService>,
+    check_interval_minutes: u64,
+}
+
+impl NotificationCheckService {
+    pub fn new(service: Arc<NotificationService>, check_interval_minutes: u64) -> Self {
+        Self {
+            service,
+            check_interval_minutes: check_interval_minutes.max(1), // Minimum 1 minute
+        }
+    }
+
+    /// Starts the periodic check job
+    #[instrument(skip(self))]
+    pub async fn start_check_job(&self) {
+        let service = self.service.clone();
+        let interval_minutes = self.check_interval_minutes;
+
+        info!(
+            "Starting notification check job with interval of {} minutes",
+            interval_minutes
+        );
+
+        tokio::spawn(async move {
+            let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+            loop {
+                // First tick completes immediately
+                interval.tick().await;
+                debug!("Running scheduled notification checks");
+                service.run_checks().await;
+            }
+        });
+    }
+}
@@ -0,0 +1,128 @@
+//! Relays live changes between instances sharing the database
+//! (LiveEventRelayPort implementation) through PostgreSQL LISTEN/NOTIFY.
+//!
+//! Every instance publishes the changes it makes on one channel and
+//! listens on it for the changes of the others, recognising its own by an
+//! id chosen at startup.  NOTIFY is not durable: changes published while
+//! the listener reconnects are lost, so local subscribers are told to
+//! reload once it is back.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::application::ports::live_event_ports::{LiveEvent, LiveEventRelayPort};
+use crate::application::services::live_event_service::LiveEventService;
+use crate::common::errors::{DomainError, Result};
+
+/// NOTIFY channel the instances talk on
+pub const CHANNEL: &str = "oxicloud_live_events";
+/// NOTIFY payloads must stay below 8000 bytes
+const MAX_PAYLOAD_BYTES: usize = 7_900;
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// A change as it travels between instances.
+#[derive(Debug, Serialize, Deserialize)]
+struct RelayMessage {
+    /// Instance that published the change
+    origin: Uuid,
+    event: LiveEvent,
+}
+
+/// Publishes and receives live changes with PostgreSQL LISTEN/NOTIFY.
+pub struct PgLiveEventRelay {
+    pool: Arc<PgPool>,
+    origin: Uuid,
+}
+
+impl PgLiveEventRelay {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self {
+            pool,
+            origin: Uuid::new_v4(),
+        }
+    }
+
+    /// Starts listening for the changes of other instances and hands them
+    /// to `service`.
+    pub async fn start_listener(&self, service: Arc<LiveEventService>) {
+        let pool = self.pool.clone();
+        let origin = self.origin;
+        info!(
+            "Relaying live events between instances on channel {}",
+            CHANNEL
+        );
+
+        tokio::spawn(async move {
+            let mut delay = Duration::from_secs(1);
+            let mut connected_before = false;
+            loop {
+                match Self::listen(&pool, origin, &service, connected_before).await {
+                    Ok(()) => delay = Duration::from_secs(1),
+                    Err(e) => {
+                        warn!("Live event listener failed, retrying in {:?}: {}", delay, e);
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                    }
+                }
+                connected_before = true;
+            }
+        });
+    }
+
+    /// Listens until the connection is lost.
+    async fn listen(
+        pool: &PgPool,
+        origin: Uuid,
+        service: &LiveEventService,
+        reconnected: bool,
+    ) -> std::result::Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(CHANNEL).await?;
+        if reconnected {
+            info!("Live event listener reconnected");
+            service.resync_all();
+        }
+
+        // `try_recv` returns None when the connection drops, instead of
+        // reconnecting silently like `recv`
+        while let Some(notification) = listener.try_recv().await? {
+            match serde_json::from_str::<RelayMessage>(notification.payload()) {
+                Ok(message) if message.origin == origin => {}
+                Ok(message) => service.deliver(message.event),
+                Err(e) => debug!("Ignoring malformed live event notification: {}", e),
+            }
+        }
+        warn!("Live event listener lost its connection");
+        Ok(())
+    }
+}
+
+impl LiveEventRelayPort for PgLiveEventRelay {
+    async fn publish(&self, event: &LiveEvent) -> Result<()> {
+        let message = RelayMessage {
+            origin: self.origin,
+            event: event.clone(),
+        };
+        let payload = serde_json::to_string(&message)
+            .map_err(|e| DomainError::internal_error("LiveEvents", e.to_string()))?;
+        if payload.len() > MAX_PAYLOAD_BYTES {
+            return Err(DomainError::internal_error(
+                "LiveEvents",
+                format!("Change too large to relay ({} bytes)", payload.len()),
+            ));
+        }
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(CHANNEL)
+            .bind(payload)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| DomainError::internal_error("LiveEvents", e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Pre-authentication tokens for the Nextcloud notify_push websocket.
+//! A client that is already signed in asks for one and presents it instead
+//! of its password when it opens the socket.  A bare [`signed_token`] with
+//! an `exp` a few seconds ahead.
+
+use uuid::Uuid;
+
+use crate::common::errors::DomainError;
+use crate::infrastructure::services::signed_token::{self, TokenScope};
+
+/// Seconds a token stays valid, as in Nextcloud's notify_push
+pub const DEFAULT_TTL_SECS: i64 = 15;
+const SCOPE: TokenScope<'static> = TokenScope {
+    domain: "oxicloud-push-token",
+    audience: "oxicloud:notify_push",
+};
+
+pub fn issue(secret: &str, user_id: Uuid, ttl_secs: i64) -> Result<String, DomainError> {
+    signed_token::issue(secret, SCOPE, user_id, (), ttl_secs)
+}
+
+/// Returns the user the token was issued to iff it is well-formed, signed
+/// by `secret` and unexpired.
+pub fn verify(secret: &str, jwt: &str) -> Option<Uuid> {
+    signed_token::verify::<()>(secret, SCOPE, jwt).map(|(user_id, ())| user_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SECRET: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn issue_then_verify_round_trips() {
+        let user_id = Uuid::new_v4();
+        let jwt = issue(TEST_SECRET, user_id, 60).unwrap();
+        assert_eq!(verify(TEST_SECRET, &jwt), Some(user_id));
+    }
+
+    #[test]
+    fn account_tokens_do_not_pass_as_push_tokens() {
+        use crate::application::ports::account_token_ports::AccountTokenPurpose;
+        use crate::infrastructure::services::account_token::{self, AccountToken};
+
+        let token = AccountToken {
+            user_id: Uuid::new_v4(),
+            token_id: Uuid::new_v4(),
+            email: None,
+        };
+        let jwt = account_token::issue(TEST_SECRET, AccountTokenPurpose::PasswordReset, &token, 60)
+            .unwrap();
+        assert!(verify(TEST_SECRET, &jwt).is_none());
+    }
+}
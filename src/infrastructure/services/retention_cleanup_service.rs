@@ -0,0 +1,71 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, error, info, instrument};
+
+use crate::common::errors::DomainError;
+
+/// Background job that deletes log entries older than the retention period.
+///
+/// `prune` is given the cutoff and returns how many entries it removed;
+/// `label` names the log in tracing output.
+pub struct RetentionCleanupService<F> {
+    label: &'static str,
+    prune: F,
+    retention_days: u32,
+    cleanup_interval_hours: u64,
+}
+
+impl<F, Fut> RetentionCleanupService<F>
+where
+    F: Fn(DateTime<Utc>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<u64, DomainError>> + Send,
+{
+    pub fn new(
+        label: &'static str,
+        retention_days: u32,
+        cleanup_interval_hours: u64,
+        prune: F,
+    ) -> Self {
+        Self {
+            label,
+            prune,
+            retention_days: retention_days.max(1), // Minimum 1 day
+            cleanup_interval_hours: cleanup_interval_hours.max(1), // Minimum 1 hour
+        }
+    }
+
+    /// Starts the periodic cleanup job
+    #[instrument(skip(self), fields(label = self.label))]
+    pub async fn start_cleanup_job(self) {
+        let Self {
+            label,
+            prune,
+            retention_days,
+            cleanup_interval_hours: interval_hours,
+        } = self;
+
+        info!(
+            "Starting {} cleanup job with interval of {} hours ({} days retention)",
+            label, interval_hours, retention_days
+        );
+
+        tokio::spawn(async move {
+            let mut interval = time::interval(Duration::from_secs(interval_hours * 60 * 60));
+
+            loop {
+                // First tick completes immediately
+                interval.tick().await;
+                debug!("Running scheduled {} cleanup", label);
+
+                let cutoff = Utc::now() - ChronoDuration::days(i64::from(retention_days));
+                match prune(cutoff).await {
+                    Ok(0) => debug!("No {} entries to prune", label),
+                    Ok(n) => info!("{} cleanup: {} entries pruned", label, n),
+                    Err(e) => error!("Error pruning the {}: {:?}", label, e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,160 @@
+//! Purpose-scoped signed tokens: short JWTs that one feature hands out and
+//! later takes back (mailed links, websocket pre-authentication).  Each
+//! [`TokenScope`] signs with a key derived from the JWT secret and its own
+//! domain prefix, so a token never passes as an access token, as a token of
+//! another feature, or the other way round.  The `aud` claim further
+//! separates purposes within one feature.
+//!
+//! Being signed rather than stored, these tokens verify on every instance
+//! behind a load balancer; features that need single use track a `jti`
+//! themselves.
+
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::common::errors::DomainError;
+
+/// Where a token may be used: `domain` picks the signing key, `audience`
+/// the purpose within it.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenScope<'a> {
+    pub domain: &'a str,
+    pub audience: &'a str,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims<E> {
+    sub: String,
+    aud: String,
+    exp: i64,
+    iat: i64,
+    #[serde(flatten)]
+    extra: E,
+}
+
+fn signing_key(secret: &str, domain: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(domain.as_bytes());
+    hasher.update(b":");
+    hasher.update(secret.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// Signs a token for `user_id` valid `ttl_secs` from now.  `extra` holds
+/// any feature-specific claims.
+pub fn issue<E: Serialize>(
+    secret: &str,
+    scope: TokenScope<'_>,
+    user_id: Uuid,
+    extra: E,
+    ttl_secs: i64,
+) -> Result<String, DomainError> {
+    if secret.is_empty() {
+        return Err(DomainError::internal_error(
+            "SignedToken",
+            "JWT secret is empty",
+        ));
+    }
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: user_id.to_string(),
+        aud: scope.audience.to_string(),
+        exp: now + ttl_secs,
+        iat: now,
+        extra,
+    };
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(&signing_key(secret, scope.domain)),
+    )
+    .map_err(|e| DomainError::internal_error("SignedToken", format!("sign: {}", e)))
+}
+
+/// Returns the user and extra claims iff the token is well-formed, signed
+/// by `secret` for `scope` and unexpired.
+pub fn verify<E: DeserializeOwned>(
+    secret: &str,
+    scope: TokenScope<'_>,
+    jwt: &str,
+) -> Option<(Uuid, E)> {
+    if secret.is_empty() || jwt.is_empty() {
+        return None;
+    }
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_exp = true;
+    validation.leeway = 0;
+    validation.set_audience(&[scope.audience]);
+    validation.set_required_spec_claims(&["exp", "sub", "aud"]);
+
+    let data = decode::<Claims<E>>(
+        jwt,
+        &DecodingKey::from_secret(&signing_key(secret, scope.domain)),
+        &validation,
+    )
+    .ok()?;
+    Some((Uuid::parse_str(&data.claims.sub).ok()?, data.claims.extra))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SECRET: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+    const SCOPE: TokenScope<'static> = TokenScope {
+        domain: "oxicloud-test-token",
+        audience: "oxicloud:test",
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Extra {
+        note: String,
+    }
+
+    #[test]
+    fn issue_then_verify_round_trips() {
+        let user_id = Uuid::new_v4();
+        let extra = Extra {
+            note: "hello".to_string(),
+        };
+        let jwt = issue(TEST_SECRET, SCOPE, user_id, &extra, 60).unwrap();
+        assert_eq!(
+            verify::<Extra>(TEST_SECRET, SCOPE, &jwt),
+            Some((user_id, extra))
+        );
+
+        let bare = issue(TEST_SECRET, SCOPE, user_id, (), 60).unwrap();
+        assert_eq!(verify::<()>(TEST_SECRET, SCOPE, &bare), Some((user_id, ())));
+    }
+
+    #[test]
+    fn verify_rejects_other_scopes_secrets_expiry_and_plain_jwts() {
+        let jwt = issue(TEST_SECRET, SCOPE, Uuid::new_v4(), (), 60).unwrap();
+        let other_audience = TokenScope {
+            audience: "oxicloud:other",
+            ..SCOPE
+        };
+        let other_domain = TokenScope {
+            domain: "oxicloud-other-token",
+            ..SCOPE
+        };
+        assert!(verify::<()>(TEST_SECRET, other_audience, &jwt).is_none());
+        assert!(verify::<()>(TEST_SECRET, other_domain, &jwt).is_none());
+        assert!(verify::<()>("other-secret", SCOPE, &jwt).is_none());
+        assert!(verify::<()>("", SCOPE, &jwt).is_none());
+
+        let expired = issue(TEST_SECRET, SCOPE, Uuid::new_v4(), (), -10).unwrap();
+        assert!(verify::<()>(TEST_SECRET, SCOPE, &expired).is_none());
+
+        // Signed with the JWT secret itself, as access tokens are
+        let plain =
+            crate::infrastructure::services::share_unlock_cookie::issue_jwt(TEST_SECRET, "x", 60)
+                .unwrap();
+        assert!(verify::<()>(TEST_SECRET, SCOPE, &plain).is_none());
+        assert!(verify::<()>(TEST_SECRET, SCOPE, "garbage").is_none());
+    }
+}
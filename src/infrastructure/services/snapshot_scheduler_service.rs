@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, error, info, instrument};
+
+use crate::application::services::snapshot_service::SnapshotService;
+
+/// Background job for folder snapshots.
+///
+/// On every tick it takes the snapshots whose schedule is due and deletes
+/// snapshots past their expiry (scheduled or manual).
+pub struct SnapshotSchedulerService {
+    snapshot_service: Arc<SnapshotService>,
+    check_interval_minutes: u64,
+}
+
+impl SnapshotSchedulerService {
+    pub fn new(snapshot_service: Arc<SnapshotService>, check_interval_minutes: u64) -> Self {
+        Self {
+            snapshot_service,
+            check_interval_minutes: check_interval_minutes.max(1), // Minimum 1 minute
+        }
+    }
+
+    /// Starts the periodic snapshot job
+    #[instrument(skip(self))]
+    pub async fn start_scheduler_job(&self) {
+        let snapshot_service = self.snapshot_service.clone();
+        let interval_minutes = self.check_interval_minutes;
+
+        info!(
+            "Starting snapshot scheduler job with interval of {} minutes",
+            interval_minutes
+        );
+
+        tokio::spawn(async move {
+            let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+            loop {
+                // First tick completes immediately
+                interval.tick().await;
+                debug!("Running scheduled snapshot task");
+                Self::run_once(&snapshot_service).await;
+            }
+        });
+    }
+
+    async fn run_once(snapshot_service: &SnapshotService) {
+        match snapshot_service.run_due_schedules().await {
+            Ok(0) => debug!("No snapshot schedules due"),
+            Ok(n) => info!("Snapshot scheduler: {} scheduled snapshots taken", n),
+            Err(e) => error!("Error running snapshot schedules: {:?}", e),
+        }
+
+        match snapshot_service.delete_expired().await {
+            Ok(0) => debug!("No expired snapshots"),
+            Ok(n) => info!("Snapshot scheduler: {} expired snapshots deleted", n),
+            Err(e) => error!("Error deleting expired snapshots: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,445 @@
+//! WebAuthn (passkey) ceremony verification.
+//!
+//! Implements the relying-party checks of the WebAuthn Level 2
+//! registration and authentication ceremonies.  Attestation statements are
+//! not verified — credentials are requested with `attestation: "none"` and
+//! the authenticator's make does not matter to us — so registration only
+//! extracts the credential and its COSE public key.  Supported algorithms
+//! are ES256, EdDSA (Ed25519) and RS256, which covers platform
+//! authenticators, password managers and security keys.
+
+use std::io::Cursor;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ciborium::Value;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// COSE algorithm identifiers
+pub const ALG_ES256: i64 = -7;
+pub const ALG_EDDSA: i64 = -8;
+pub const ALG_RS256: i64 = -257;
+/// In order of preference, as offered in `pubKeyCredParams`
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [ALG_ES256, ALG_EDDSA, ALG_RS256];
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum WebAuthnError {
+    #[error("Malformed {0}")]
+    Malformed(&'static str),
+    #[error("Unexpected ceremony type '{0}'")]
+    WrongCeremony(String),
+    #[error("Challenge does not match")]
+    ChallengeMismatch,
+    #[error("Origin '{0}' is not allowed")]
+    OriginMismatch(String),
+    #[error("Credential is scoped to another relying party")]
+    RpIdMismatch,
+    #[error("User presence was not confirmed")]
+    UserNotPresent,
+    #[error("User verification is required")]
+    UserNotVerified,
+    #[error("Unsupported public key algorithm {0}")]
+    UnsupportedAlgorithm(i64),
+    #[error("Invalid signature")]
+    InvalidSignature,
+}
+
+type Result<T> = std::result::Result<T, WebAuthnError>;
+
+/// Which ceremony a client data object belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ceremony {
+    Registration,
+    Authentication,
+}
+
+impl Ceremony {
+    fn client_data_type(self) -> &'static str {
+        match self {
+            Self::Registration => "webauthn.create",
+            Self::Authentication => "webauthn.get",
+        }
+    }
+}
+
+/// Decodes base64url as sent by browsers, tolerating padding.
+pub fn decode_base64url(value: &str) -> Option<Vec<u8>> {
+    URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')).ok()
+}
+
+pub fn encode_base64url(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// The `clientDataJSON` collected by the browser
+#[derive(Debug, Clone)]
+pub struct ClientData {
+    pub ceremony_type: String,
+    pub challenge: Vec<u8>,
+    pub origin: String,
+}
+
+impl ClientData {
+    pub fn parse(client_data_json: &[u8]) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Raw {
+            #[serde(rename = "type")]
+            ceremony_type: String,
+            challenge: String,
+            origin: String,
+        }
+        let raw: Raw = serde_json::from_slice(client_data_json)
+            .map_err(|_| WebAuthnError::Malformed("client data"))?;
+        Ok(Self {
+            ceremony_type: raw.ceremony_type,
+            challenge: decode_base64url(&raw.challenge)
+                .ok_or(WebAuthnError::Malformed("client data challenge"))?,
+            origin: raw.origin,
+        })
+    }
+
+    /// Checks the type, the challenge we issued and the page origin.
+    pub fn check(
+        &self,
+        ceremony: Ceremony,
+        expected_challenge: &[u8],
+        allowed_origins: &[String],
+    ) -> Result<()> {
+        if self.ceremony_type != ceremony.client_data_type() {
+            return Err(WebAuthnError::WrongCeremony(self.ceremony_type.clone()));
+        }
+        if self.challenge != expected_challenge {
+            return Err(WebAuthnError::ChallengeMismatch);
+        }
+        let origin = self.origin.trim_end_matches('/');
+        if !allowed_origins
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+        {
+            return Err(WebAuthnError::OriginMismatch(self.origin.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Credential data included in the authenticator data at registration
+#[derive(Debug, Clone)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE_Key encoding of the public key
+    pub public_key: Vec<u8>,
+    pub algorithm: i64,
+}
+
+/// Parsed `authenticatorData`
+#[derive(Debug, Clone)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        const MALFORMED: WebAuthnError = WebAuthnError::Malformed("authenticator data");
+        if data.len() < 37 {
+            return Err(MALFORMED);
+        }
+        let mut rp_id_hash = [0u8; 32];
+        rp_id_hash.copy_from_slice(&data[..32]);
+        let flags = data[32];
+        let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            // aaguid (16) | credential id length (2) | credential id | COSE key
+            let rest = &data[37..];
+            if rest.len() < 18 {
+                return Err(MALFORMED);
+            }
+            let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+            let rest = &rest[18..];
+            if rest.len() < id_len {
+                return Err(MALFORMED);
+            }
+            let (credential_id, key_and_extensions) = rest.split_at(id_len);
+
+            // The key is followed by optional extensions: read one CBOR item
+            let mut cursor = Cursor::new(key_and_extensions);
+            let key: Value = ciborium::de::from_reader(&mut cursor).map_err(|_| MALFORMED)?;
+            let key_len = cursor.position() as usize;
+            Some(AttestedCredential {
+                credential_id: credential_id.to_vec(),
+                public_key: key_and_extensions[..key_len].to_vec(),
+                algorithm: cose_algorithm(&key)?,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            rp_id_hash,
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+
+    pub fn user_verified(&self) -> bool {
+        self.flags & FLAG_USER_VERIFIED != 0
+    }
+
+    /// Checks the RP ID hash and the user presence and verification flags.
+    pub fn check(&self, rp_id: &str, require_user_verification: bool) -> Result<()> {
+        if self.rp_id_hash[..] != Sha256::digest(rp_id.as_bytes())[..] {
+            return Err(WebAuthnError::RpIdMismatch);
+        }
+        if !self.user_present() {
+            return Err(WebAuthnError::UserNotPresent);
+        }
+        if require_user_verification && !self.user_verified() {
+            return Err(WebAuthnError::UserNotVerified);
+        }
+        Ok(())
+    }
+}
+
+/// Extracts `authData` from a CBOR attestation object.  The attestation
+/// statement is ignored (see the module documentation).
+pub fn attestation_auth_data(attestation_object: &[u8]) -> Result<Vec<u8>> {
+    const MALFORMED: WebAuthnError = WebAuthnError::Malformed("attestation object");
+    let value: Value = ciborium::de::from_reader(attestation_object).map_err(|_| MALFORMED)?;
+    let entries = value.as_map().ok_or(MALFORMED)?;
+    entries
+        .iter()
+        .find(|(key, _)| key.as_text() == Some("authData"))
+        .and_then(|(_, value)| value.as_bytes())
+        .cloned()
+        .ok_or(MALFORMED)
+}
+
+fn cose_field(entries: &[(Value, Value)], label: i64) -> Option<&Value> {
+    entries
+        .iter()
+        .find(|(key, _)| {
+            key.as_integer()
+                .and_then(|k| i64::try_from(k).ok())
+                .is_some_and(|k| k == label)
+        })
+        .map(|(_, value)| value)
+}
+
+fn cose_int(entries: &[(Value, Value)], label: i64) -> Option<i64> {
+    cose_field(entries, label)?
+        .as_integer()
+        .and_then(|v| i64::try_from(v).ok())
+}
+
+fn cose_bytes(entries: &[(Value, Value)], label: i64) -> Result<&[u8]> {
+    cose_field(entries, label)
+        .and_then(Value::as_bytes)
+        .map(Vec::as_slice)
+        .ok_or(WebAuthnError::Malformed("public key"))
+}
+
+fn cose_algorithm(key: &Value) -> Result<i64> {
+    let entries = key.as_map().ok_or(WebAuthnError::Malformed("public key"))?;
+    let alg = cose_int(entries, 3).ok_or(WebAuthnError::Malformed("public key"))?;
+    if !SUPPORTED_ALGORITHMS.contains(&alg) {
+        return Err(WebAuthnError::UnsupportedAlgorithm(alg));
+    }
+    Ok(alg)
+}
+
+/// Verifies an assertion signature over `authenticatorData ||
+/// SHA-256(clientDataJSON)` with a stored COSE public key.
+pub fn verify_assertion_signature(
+    cose_public_key: &[u8],
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature_bytes: &[u8],
+) -> Result<()> {
+    let key: Value = ciborium::de::from_reader(cose_public_key)
+        .map_err(|_| WebAuthnError::Malformed("public key"))?;
+    let alg = cose_algorithm(&key)?;
+    let entries = key.as_map().ok_or(WebAuthnError::Malformed("public key"))?;
+
+    let mut message = authenticator_data.to_vec();
+    message.extend_from_slice(&Sha256::digest(client_data_json));
+
+    let verified = match alg {
+        ALG_ES256 => {
+            // Uncompressed SEC1 point from the x and y coordinates
+            let mut point = vec![0x04];
+            point.extend_from_slice(cose_bytes(entries, -2)?);
+            point.extend_from_slice(cose_bytes(entries, -3)?);
+            UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                .verify(&message, signature_bytes)
+        }
+        ALG_EDDSA => UnparsedPublicKey::new(&signature::ED25519, cose_bytes(entries, -2)?)
+            .verify(&message, signature_bytes),
+        ALG_RS256 => RsaPublicKeyComponents {
+            n: cose_bytes(entries, -1)?,
+            e: cose_bytes(entries, -2)?,
+        }
+        .verify(
+            &signature::RSA_PKCS1_2048_8192_SHA256,
+            &message,
+            signature_bytes,
+        ),
+        other => return Err(WebAuthnError::UnsupportedAlgorithm(other)),
+    };
+    verified.map_err(|_| WebAuthnError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair};
+
+    const RP_ID: &str = "cloud.example.org";
+
+    fn es256_key_pair() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 =
+            EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(
+            &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+            pkcs8.as_ref(),
+            &rng,
+        )
+        .unwrap()
+    }
+
+    fn cose_es256(key_pair: &EcdsaKeyPair) -> Vec<u8> {
+        let point = key_pair.public_key().as_ref();
+        let key = Value::Map(vec![
+            (Value::from(1), Value::from(2)),
+            (Value::from(3), Value::from(ALG_ES256)),
+            (Value::from(-1), Value::from(1)),
+            (Value::from(-2), Value::Bytes(point[1..33].to_vec())),
+            (Value::from(-3), Value::Bytes(point[33..].to_vec())),
+        ]);
+        let mut out = Vec::new();
+        ciborium::ser::into_writer(&key, &mut out).unwrap();
+        out
+    }
+
+    fn auth_data(flags: u8, sign_count: u32, attested: Option<(&[u8], &[u8])>) -> Vec<u8> {
+        let mut data = Sha256::digest(RP_ID.as_bytes()).to_vec();
+        data.push(flags);
+        data.extend_from_slice(&sign_count.to_be_bytes());
+        if let Some((credential_id, cose_key)) = attested {
+            data.extend_from_slice(&[0u8; 16]);
+            data.extend_from_slice(&(credential_id.len() as u16).to_be_bytes());
+            data.extend_from_slice(credential_id);
+            data.extend_from_slice(cose_key);
+        }
+        data
+    }
+
+    #[test]
+    fn registration_and_assertion_round_trip() {
+        let key_pair = es256_key_pair();
+        let cose_key = cose_es256(&key_pair);
+
+        let attestation = Value::Map(vec![
+            (Value::from("fmt"), Value::from("none")),
+            (Value::from("attStmt"), Value::Map(vec![])),
+            (
+                Value::from("authData"),
+                Value::Bytes(auth_data(
+                    FLAG_USER_PRESENT | FLAG_USER_VERIFIED | FLAG_ATTESTED_CREDENTIAL,
+                    0,
+                    Some((b"credential-1", &cose_key)),
+                )),
+            ),
+        ]);
+        let mut attestation_object = Vec::new();
+        ciborium::ser::into_writer(&attestation, &mut attestation_object).unwrap();
+
+        let registered =
+            AuthenticatorData::parse(&attestation_auth_data(&attestation_object).unwrap()).unwrap();
+        registered.check(RP_ID, true).unwrap();
+        assert_eq!(
+            registered.check("evil.example", false),
+            Err(WebAuthnError::RpIdMismatch)
+        );
+        let credential = registered.attested_credential.unwrap();
+        assert_eq!(credential.credential_id, b"credential-1");
+        assert_eq!(credential.public_key, cose_key);
+        assert_eq!(credential.algorithm, ALG_ES256);
+
+        let authenticator_data = auth_data(FLAG_USER_PRESENT, 7, None);
+        let client_data =
+            br#"{"type":"webauthn.get","challenge":"AAEC","origin":"https://cloud.example.org"}"#;
+        let mut message = authenticator_data.clone();
+        message.extend_from_slice(&Sha256::digest(client_data));
+        let sig = key_pair
+            .sign(&SystemRandom::new(), &message)
+            .unwrap()
+            .as_ref()
+            .to_vec();
+
+        let parsed = AuthenticatorData::parse(&authenticator_data).unwrap();
+        assert_eq!(parsed.sign_count, 7);
+        assert_eq!(
+            parsed.check(RP_ID, true),
+            Err(WebAuthnError::UserNotVerified)
+        );
+        verify_assertion_signature(
+            &credential.public_key,
+            &authenticator_data,
+            client_data,
+            &sig,
+        )
+        .unwrap();
+        assert_eq!(
+            verify_assertion_signature(
+                &credential.public_key,
+                &auth_data(FLAG_USER_PRESENT, 8, None),
+                client_data,
+                &sig
+            ),
+            Err(WebAuthnError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn client_data_checks_type_challenge_and_origin() {
+        let json = br#"{"type":"webauthn.get","challenge":"AAEC","origin":"https://cloud.example.org","crossOrigin":false}"#;
+        let client_data = ClientData::parse(json).unwrap();
+        let origins = vec!["https://cloud.example.org/".to_string()];
+
+        client_data
+            .check(Ceremony::Authentication, &[0, 1, 2], &origins)
+            .unwrap();
+        assert_eq!(
+            client_data.check(Ceremony::Registration, &[0, 1, 2], &origins),
+            Err(WebAuthnError::WrongCeremony("webauthn.get".to_string()))
+        );
+        assert_eq!(
+            client_data.check(Ceremony::Authentication, &[9], &origins),
+            Err(WebAuthnError::ChallengeMismatch)
+        );
+        assert!(matches!(
+            client_data.check(
+                Ceremony::Authentication,
+                &[0, 1, 2],
+                &["https://other.example".to_string()]
+            ),
+            Err(WebAuthnError::OriginMismatch(_))
+        ));
+    }
+}
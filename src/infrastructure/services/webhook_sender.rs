@@ -0,0 +1,264 @@
+//! Outbound HTTP for webhook deliveries (WebhookSenderPort implementation).
+//!
+//! Redirects are not followed.  Unless a request may reach private
+//! targets, the receiver's host is resolved first, every address checked,
+//! and the connection pinned to the checked addresses so a second DNS
+//! answer cannot point it elsewhere.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use reqwest::{Client, ClientBuilder, Url, redirect};
+
+use crate::application::ports::webhook_ports::{WebhookRequest, WebhookSenderPort};
+use crate::common::errors::{DomainError, Result};
+
+/// Timeout for a whole delivery attempt
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const USER_AGENT: &str = concat!("OxiCloud-Webhook/", env!("CARGO_PKG_VERSION"));
+
+fn send_error(message: impl Into<String>) -> DomainError {
+    DomainError::internal_error("Webhook", message)
+}
+
+fn client_builder() -> ClientBuilder {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .redirect(redirect::Policy::none())
+        .user_agent(USER_AGENT)
+}
+
+/// Whether an address is reachable from the internet, i.e. not loopback,
+/// private, link-local or otherwise reserved.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b))
+                // IETF protocol assignments, 192.0.0.0/24
+                || (a == 192 && b == 0 && c == 0)
+                // Benchmarking, 198.18.0.0/15
+                || (a == 198 && (b & 0xfe) == 18)
+                // Reserved, 240.0.0.0/4
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(v4));
+            }
+            let segments = v6.segments();
+            let first = segments[0];
+            // NAT64, 64:ff9b::/96, carries the IPv4 address in the last 32 bits
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [.., hi, lo] = segments;
+                return is_public_address(IpAddr::V4(Ipv4Addr::from(
+                    (u32::from(hi) << 16) | u32::from(lo),
+                )));
+            }
+            // 6to4, 2002::/16, carries it right after the prefix
+            if first == 0x2002 {
+                return is_public_address(IpAddr::V4(Ipv4Addr::from(
+                    (u32::from(segments[1]) << 16) | u32::from(segments[2]),
+                )));
+            }
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local, fc00::/7
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10
+                || (first & 0xffc0) == 0xfe80
+                // Documentation, 2001:db8::/32
+                || (first == 0x2001 && segments[1] == 0x0db8))
+        }
+    }
+}
+
+/// Posts signed event payloads to webhook receivers.
+pub struct WebhookSender {
+    /// Shared client for requests that may reach any address
+    client: Client,
+}
+
+impl WebhookSender {
+    pub fn new() -> Result<Self> {
+        let client = client_builder()
+            .build()
+            .map_err(|e| send_error(format!("Cannot build HTTP client: {}", e)))?;
+        Ok(Self { client })
+    }
+
+    /// Resolves the host of `url` and checks that all its addresses are
+    /// public.
+    async fn public_addresses(url: &Url) -> Result<(String, Vec<SocketAddr>)> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| DomainError::access_denied("Webhook", "Webhook URL has no host"))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+            .await
+            .map_err(|e| send_error(format!("Cannot resolve {}: {}", host, e)))?
+            .collect();
+        if addrs.is_empty() {
+            return Err(send_error(format!("{} has no addresses", host)));
+        }
+        if let Some(addr) = addrs.iter().find(|a| !is_public_address(a.ip())) {
+            return Err(DomainError::access_denied(
+                "Webhook",
+                format!("{} resolves to the non-public address {}", host, addr.ip()),
+            ));
+        }
+        Ok((host, addrs))
+    }
+}
+
+impl WebhookSenderPort for WebhookSender {
+    async fn send(&self, request: &WebhookRequest) -> Result<u16> {
+        let url = Url::parse(&request.url)
+            .map_err(|e| DomainError::access_denied("Webhook", format!("Invalid URL: {}", e)))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(DomainError::access_denied(
+                "Webhook",
+                "Webhook URLs must use http or https",
+            ));
+        }
+
+        let client = if request.allow_private_target {
+            self.client.clone()
+        } else {
+            let (host, addrs) = Self::public_addresses(&url).await?;
+            client_builder()
+                .resolve_to_addrs(&host, &addrs)
+                .build()
+                .map_err(|e| send_error(format!("Cannot build HTTP client: {}", e)))?
+        };
+
+        let mut builder = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(request.body.clone());
+        for (name, value) in &request.headers {
+            builder = builder.header(*name, value);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| send_error(format!("Request failed: {}", e)))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use axum::{Router, http::HeaderMap, routing::post};
+
+    #[test]
+    fn private_and_reserved_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "192.0.0.8",
+            "198.18.0.1",
+            "198.19.255.255",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a9fe:a9fe",
+            "2002:c0a8:101::1",
+            "2002:7f00:1::",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "93.184.216.34",
+            "1.1.1.1",
+            "198.20.0.1",
+            "2606:4700:4700::1111",
+            "64:ff9b::101:101",
+            "2002:101:101::1",
+        ] {
+            assert!(is_public_address(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    /// Starts a receiver on a loopback port that answers with `status` and
+    /// remembers the headers and body of each request.
+    async fn local_receiver(status: u16) -> (String, Arc<Mutex<Vec<(HeaderMap, String)>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let log = log.clone();
+                async move {
+                    log.lock().unwrap().push((headers, body));
+                    axum::http::StatusCode::from_u16(status).unwrap()
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}/hook"), received)
+    }
+
+    #[tokio::test]
+    async fn posts_to_a_local_receiver_when_private_targets_are_allowed() {
+        let (url, received) = local_receiver(202).await;
+        let sender = WebhookSender::new().unwrap();
+        let request = WebhookRequest {
+            url,
+            body: r#"{"event":"ping"}"#.to_string(),
+            headers: vec![("X-OxiCloud-Event", "ping".to_string())],
+            allow_private_target: true,
+        };
+
+        assert_eq!(sender.send(&request).await.unwrap(), 202);
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let (headers, body) = &received[0];
+        assert_eq!(body, r#"{"event":"ping"}"#);
+        assert_eq!(headers["x-oxicloud-event"], "ping");
+        assert_eq!(headers["content-type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn refuses_a_local_receiver_otherwise() {
+        let (url, received) = local_receiver(200).await;
+        let sender = WebhookSender::new().unwrap();
+        let request = WebhookRequest {
+            url,
+            body: "{}".to_string(),
+            headers: Vec::new(),
+            allow_private_target: false,
+        };
+
+        let err = sender.send(&request).await.unwrap_err();
+        assert_eq!(err.kind, crate::common::errors::ErrorKind::AccessDenied);
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
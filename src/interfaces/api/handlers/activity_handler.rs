@@ -0,0 +1,35 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::application::dtos::activity_dto::ActivityQueryDto;
+use crate::application::services::activity_service::ActivityService;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::AuthUser;
+
+/// Activity of the current user: what they did and what happened to their
+/// files, folders, shares and account, newest first
+#[utoipa::path(
+    get,
+    path = "/api/activity",
+    params(ActivityQueryDto),
+    responses(
+        (status = 200, description = "Page of activity entries", body = crate::application::dtos::pagination::PaginatedResponseDto<crate::application::dtos::activity_dto::ActivityDto>),
+        (status = 400, description = "Invalid filter"),
+    ),
+    tag = "activity"
+)]
+pub async fn list_activity(
+    State(service): State<Arc<ActivityService>>,
+    auth_user: AuthUser,
+    Query(query): Query<ActivityQueryDto>,
+) -> Response {
+    match service.list_for_user(auth_user.id, &query).await {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
@@ -3,16 +3,28 @@ use axum::{
     extract::{Json, Path, Query, State},
     http::{HeaderMap, StatusCode, header},
     response::IntoResponse,
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
 };
 
+use crate::application::dtos::activity_dto::{ActivityDto, ActivityQueryDto};
+use crate::application::dtos::group_dto::{
+    AddGroupMemberDto, CreateGroupDto, ListGroupsQueryDto, UpdateGroupDto,
+};
 use crate::application::dtos::settings_dto::{
     AdminCreateUserDto, AdminResetPasswordDto, DashboardStatsDto, ListUsersQueryDto,
     MigrationStateDto, SaveOidcSettingsDto, SaveStorageSettingsDto, StartMigrationDto,
-    TestOidcConnectionDto, TestStorageConnectionDto, UpdateUserActiveDto, UpdateUserQuotaDto,
-    UpdateUserRoleDto, VerifyMigrationDto,
+    TestLdapConnectionDto, TestOidcConnectionDto, TestStorageConnectionDto, UpdateUserActiveDto,
+    UpdateUserQuotaDto, UpdateUserRoleDto, VerifyMigrationDto,
+};
+use crate::application::dtos::two_factor_dto::TwoFactorPolicyDto;
+use crate::application::dtos::webhook_dto::{
+    CreateWebhookDto, UpdateWebhookDto, WebhookDeliveryDto, WebhookDeliveryQueryDto, WebhookDto,
 };
 use crate::application::ports::auth_ports::TokenServicePort;
+use crate::application::services::activity_service::{self, ActivityService};
+use crate::application::services::group_service::GroupService;
+use crate::application::services::two_factor_service::TwoFactorService;
+use crate::application::services::webhook_service::WebhookService;
 use crate::common::di::AppState;
 use crate::interfaces::errors::AppError;
 use std::sync::Arc;
@@ -25,6 +37,8 @@ pub fn admin_routes() -> Router<Arc<AppState>> {
         .route("/settings/oidc", get(get_oidc_settings))
         .route("/settings/oidc", put(save_oidc_settings))
         .route("/settings/oidc/test", post(test_oidc_connection))
+        // LDAP
+        .route("/settings/ldap/test", post(test_ldap_connection))
         // Storage settings
         .route("/settings/storage", get(get_storage_settings))
         .route("/settings/storage", put(save_storage_settings))
@@ -53,9 +67,36 @@ pub fn admin_routes() -> Router<Arc<AppState>> {
         .route("/users/{id}/active", put(update_user_active))
         .route("/users/{id}/quota", put(update_user_quota))
         .route("/users/{id}/password", put(reset_user_password))
+        .route("/users/{id}/groups", get(list_user_groups))
+        .route("/users/{id}/2fa", delete(reset_user_two_factor))
+        // Group management
+        .route("/groups", get(list_groups))
+        .route("/groups", post(create_group))
+        .route("/groups/{id}", get(get_group))
+        .route("/groups/{id}", put(update_group))
+        .route("/groups/{id}", delete(delete_group))
+        .route("/groups/{id}/members", get(list_group_members))
+        .route("/groups/{id}/members", post(add_group_member))
+        .route(
+            "/groups/{id}/members/{user_id}",
+            delete(remove_group_member),
+        )
         // Registration control
         .route("/settings/registration", get(get_registration_setting))
         .route("/settings/registration", put(set_registration_setting))
+        // Two-factor authentication
+        .route("/settings/2fa", get(get_two_factor_policy))
+        .route("/settings/2fa", put(set_two_factor_policy))
+        // Activity log
+        .route("/activity", get(list_activity))
+        .route("/activity/export", get(export_activity))
+        // Webhooks receiving the events of all users
+        .route("/webhooks", get(list_webhooks))
+        .route("/webhooks", post(create_webhook))
+        .route("/webhooks/{id}", patch(update_webhook))
+        .route("/webhooks/{id}", delete(delete_webhook))
+        .route("/webhooks/{id}/deliveries", get(list_webhook_deliveries))
+        .route("/webhooks/{id}/ping", post(ping_webhook))
         // Audio metadata
         .route("/audio/metadata/reextract", post(reextract_audio_metadata))
 }
@@ -185,6 +226,28 @@ async fn test_oidc_connection(
     Ok(Json(result))
 }
 
+/// POST /api/admin/settings/ldap/test — test the LDAP connection and
+/// optionally look up a user
+async fn test_ldap_connection(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(dto): Json<TestLdapConnectionDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let auth = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Auth service not configured"))?;
+
+    let ldap = auth
+        .auth_application_service
+        .ldap_service()
+        .ok_or_else(|| AppError::bad_request("LDAP is not enabled (OXICLOUD_LDAP_ENABLED)"))?;
+
+    Ok(Json(ldap.test_connection(dto.username.as_deref()).await))
+}
+
 // ─────────────────────────────────────────────────────
 // Storage settings handlers
 // ─────────────────────────────────────────────────────
@@ -1108,6 +1171,280 @@ pub async fn reset_user_password(
     ))
 }
 
+// ============================================================================
+// Group Management
+// ============================================================================
+
+fn group_service(state: &AppState) -> Result<&Arc<GroupService>, AppError> {
+    state
+        .auth_service
+        .as_ref()
+        .map(|auth| &auth.group_service)
+        .ok_or_else(|| AppError::internal_error("Auth service not configured"))
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(id).map_err(|_| AppError::bad_request("Invalid UUID"))
+}
+
+/// GET /api/admin/groups?limit=50&offset=0 — list groups
+#[utoipa::path(
+    get,
+    path = "/api/admin/groups",
+    params(
+        ("limit" = Option<i64>, Query, description = "Max groups to return (default 100, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Pagination offset")
+    ),
+    responses(
+        (status = 200, description = "List of groups"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required")
+    ),
+    tag = "admin"
+)]
+pub async fn list_groups(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ListGroupsQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let (groups, total) = group_service(&state)?.list_groups(limit, offset).await?;
+
+    Ok(Json(serde_json::json!({
+        "groups": groups,
+        "total": total,
+        "limit": limit,
+        "offset": offset,
+    })))
+}
+
+/// POST /api/admin/groups — create a group
+#[utoipa::path(
+    post,
+    path = "/api/admin/groups",
+    request_body = CreateGroupDto,
+    responses(
+        (status = 201, description = "Group created", body = crate::application::dtos::group_dto::GroupDto),
+        (status = 400, description = "Invalid group data"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 409, description = "A group with this name exists")
+    ),
+    tag = "admin"
+)]
+pub async fn create_group(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(dto): Json<CreateGroupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let group = group_service(&state)?.create_group(dto).await?;
+
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+/// GET /api/admin/groups/:id — get a group
+#[utoipa::path(
+    get,
+    path = "/api/admin/groups/{id}",
+    params(("id" = String, Path, description = "Group UUID")),
+    responses(
+        (status = 200, description = "Group details", body = crate::application::dtos::group_dto::GroupDto),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Group not found")
+    ),
+    tag = "admin"
+)]
+pub async fn get_group(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let group = group_service(&state)?.get_group(parse_uuid(&id)?).await?;
+
+    Ok(Json(group))
+}
+
+/// PUT /api/admin/groups/:id — rename a group or change its description or
+/// default quota
+#[utoipa::path(
+    put,
+    path = "/api/admin/groups/{id}",
+    params(("id" = String, Path, description = "Group UUID")),
+    request_body = UpdateGroupDto,
+    responses(
+        (status = 200, description = "Group updated", body = crate::application::dtos::group_dto::GroupDto),
+        (status = 400, description = "Invalid group data"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Group not found"),
+        (status = 409, description = "A group with this name exists")
+    ),
+    tag = "admin"
+)]
+pub async fn update_group(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateGroupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let group = group_service(&state)?
+        .update_group(parse_uuid(&id)?, dto)
+        .await?;
+
+    Ok(Json(group))
+}
+
+/// DELETE /api/admin/groups/:id — delete a group
+#[utoipa::path(
+    delete,
+    path = "/api/admin/groups/{id}",
+    params(("id" = String, Path, description = "Group UUID")),
+    responses(
+        (status = 204, description = "Group deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Group not found")
+    ),
+    tag = "admin"
+)]
+pub async fn delete_group(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    group_service(&state)?
+        .delete_group(parse_uuid(&id)?)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/admin/groups/:id/members — list the members of a group
+#[utoipa::path(
+    get,
+    path = "/api/admin/groups/{id}/members",
+    params(("id" = String, Path, description = "Group UUID")),
+    responses(
+        (status = 200, description = "Group members", body = Vec<crate::application::dtos::group_dto::GroupMemberDto>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Group not found")
+    ),
+    tag = "admin"
+)]
+pub async fn list_group_members(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let members = group_service(&state)?
+        .list_members(parse_uuid(&id)?)
+        .await?;
+
+    Ok(Json(members))
+}
+
+/// POST /api/admin/groups/:id/members — add a user to a group
+#[utoipa::path(
+    post,
+    path = "/api/admin/groups/{id}/members",
+    params(("id" = String, Path, description = "Group UUID")),
+    request_body = AddGroupMemberDto,
+    responses(
+        (status = 204, description = "User added (or already a member)"),
+        (status = 400, description = "Invalid user ID"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Group or user not found")
+    ),
+    tag = "admin"
+)]
+pub async fn add_group_member(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(dto): Json<AddGroupMemberDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    group_service(&state)?
+        .add_member(parse_uuid(&id)?, parse_uuid(&dto.user_id)?)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/admin/groups/:id/members/:user_id — remove a user from a group
+#[utoipa::path(
+    delete,
+    path = "/api/admin/groups/{id}/members/{user_id}",
+    params(
+        ("id" = String, Path, description = "Group UUID"),
+        ("user_id" = String, Path, description = "User UUID")
+    ),
+    responses(
+        (status = 204, description = "User removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "User is not a member of the group")
+    ),
+    tag = "admin"
+)]
+pub async fn remove_group_member(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((id, user_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    group_service(&state)?
+        .remove_member(parse_uuid(&id)?, parse_uuid(&user_id)?)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/admin/users/:id/groups — list the groups a user belongs to
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}/groups",
+    params(("id" = String, Path, description = "User UUID")),
+    responses(
+        (status = 200, description = "Groups of the user", body = Vec<crate::application::dtos::group_dto::GroupDto>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required")
+    ),
+    tag = "admin"
+)]
+pub async fn list_user_groups(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let groups = group_service(&state)?
+        .groups_of_user(parse_uuid(&id)?)
+        .await?;
+
+    Ok(Json(groups))
+}
+
 // ============================================================================
 // Registration Control
 // ============================================================================
@@ -1189,6 +1526,329 @@ pub async fn set_registration_setting(
     ))
 }
 
+// ============================================================================
+// Two-Factor Authentication
+// ============================================================================
+
+fn two_factor_service(state: &AppState) -> Result<&Arc<TwoFactorService>, AppError> {
+    state
+        .auth_service
+        .as_ref()
+        .map(|auth| &auth.two_factor_service)
+        .ok_or_else(|| AppError::internal_error("Auth service not configured"))
+}
+
+/// GET /api/admin/settings/2fa — roles and groups that must use two-factor authentication
+#[utoipa::path(
+    get,
+    path = "/api/admin/settings/2fa",
+    responses(
+        (status = 200, description = "Two-factor policy", body = TwoFactorPolicyDto),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required")
+    ),
+    tag = "admin"
+)]
+pub async fn get_two_factor_policy(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let policy = two_factor_service(&state)?.policy().await?;
+
+    Ok(Json(policy))
+}
+
+/// PUT /api/admin/settings/2fa — set the roles and groups that must use two-factor authentication
+#[utoipa::path(
+    put,
+    path = "/api/admin/settings/2fa",
+    request_body = TwoFactorPolicyDto,
+    responses(
+        (status = 200, description = "Two-factor policy updated", body = TwoFactorPolicyDto),
+        (status = 400, description = "Unknown role"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required")
+    ),
+    tag = "admin"
+)]
+pub async fn set_two_factor_policy(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(dto): Json<TwoFactorPolicyDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let (admin_id, _) = admin_guard(&state, &headers).await?;
+
+    let policy = two_factor_service(&state)?
+        .set_policy(dto, admin_id)
+        .await?;
+
+    Ok(Json(policy))
+}
+
+/// DELETE /api/admin/users/:id/2fa — remove a user's second factor, e.g. after a lost device
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{id}/2fa",
+    params(("id" = String, Path, description = "User UUID")),
+    responses(
+        (status = 204, description = "Two-factor authentication reset"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "User has no two-factor authentication")
+    ),
+    tag = "admin"
+)]
+pub async fn reset_user_two_factor(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    two_factor_service(&state)?.reset(parse_uuid(&id)?).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn activity_service(state: &AppState) -> Result<&Arc<ActivityService>, AppError> {
+    state
+        .activity_service
+        .as_ref()
+        .ok_or_else(|| AppError::not_found("Activity log is disabled"))
+}
+
+/// GET /api/admin/activity — activity of all users, newest first
+#[utoipa::path(
+    get,
+    path = "/api/admin/activity",
+    params(ActivityQueryDto),
+    responses(
+        (status = 200, description = "Page of activity entries", body = crate::application::dtos::pagination::PaginatedResponseDto<ActivityDto>),
+        (status = 400, description = "Invalid filter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Activity log disabled")
+    ),
+    tag = "admin"
+)]
+pub async fn list_activity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ActivityQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let page = activity_service(&state)?.list_all(&query).await?;
+    Ok(Json(page))
+}
+
+/// GET /api/admin/activity/export?format=csv|json — download the filtered activity log
+#[utoipa::path(
+    get,
+    path = "/api/admin/activity/export",
+    params(ActivityQueryDto),
+    responses(
+        (status = 200, description = "Activity log as CSV or JSON attachment"),
+        (status = 400, description = "Invalid filter or format"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Activity log disabled")
+    ),
+    tag = "admin"
+)]
+pub async fn export_activity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ActivityQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let format = query.format.as_deref().unwrap_or("csv");
+    if format != "csv" && format != "json" {
+        return Err(AppError::bad_request("Export format must be csv or json"));
+    }
+
+    let entries = activity_service(&state)?.export(&query).await?;
+    let (content_type, body) = if format == "csv" {
+        (
+            "text/csv; charset=utf-8",
+            activity_service::to_csv(&entries),
+        )
+    } else {
+        let json = serde_json::to_string(&entries)
+            .map_err(|e| AppError::internal_error(format!("Failed to serialize: {}", e)))?;
+        ("application/json", json)
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"activity.{}\"", format),
+            ),
+        ],
+        body,
+    ))
+}
+
+fn webhook_service(state: &AppState) -> Result<&Arc<WebhookService>, AppError> {
+    state
+        .webhook_service
+        .as_ref()
+        .ok_or_else(|| AppError::not_found("Webhooks are disabled"))
+}
+
+/// GET /api/admin/webhooks — webhooks receiving the events of all users
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhooks",
+    responses(
+        (status = 200, description = "Admin webhooks", body = Vec<WebhookDto>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Webhooks disabled")
+    ),
+    tag = "admin"
+)]
+pub async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WebhookDto>>, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    Ok(Json(webhook_service(&state)?.list(None).await?))
+}
+
+/// POST /api/admin/webhooks — register a webhook for the events of all users
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhooks",
+    request_body = CreateWebhookDto,
+    responses(
+        (status = 201, description = "Webhook created, with its signing key", body = WebhookDto),
+        (status = 400, description = "Invalid URL or filter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Webhooks disabled")
+    ),
+    tag = "admin"
+)]
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(dto): Json<CreateWebhookDto>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    let webhook = webhook_service(&state)?.create(None, dto).await?;
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+/// PATCH /api/admin/webhooks/{id} — change, pause or rotate the key of an admin webhook
+#[utoipa::path(
+    patch,
+    path = "/api/admin/webhooks/{id}",
+    params(("id" = String, Path, description = "Webhook ID")),
+    request_body = UpdateWebhookDto,
+    responses(
+        (status = 200, description = "Webhook updated", body = WebhookDto),
+        (status = 400, description = "Invalid URL or filter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Webhook not found")
+    ),
+    tag = "admin"
+)]
+pub async fn update_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateWebhookDto>,
+) -> Result<Json<WebhookDto>, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    Ok(Json(webhook_service(&state)?.update(None, &id, dto).await?))
+}
+
+/// DELETE /api/admin/webhooks/{id} — delete an admin webhook
+#[utoipa::path(
+    delete,
+    path = "/api/admin/webhooks/{id}",
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Webhook not found")
+    ),
+    tag = "admin"
+)]
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    webhook_service(&state)?.delete(None, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/admin/webhooks/{id}/deliveries — latest deliveries to an admin webhook
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhooks/{id}/deliveries",
+    params(("id" = String, Path, description = "Webhook ID"), WebhookDeliveryQueryDto),
+    responses(
+        (status = 200, description = "Deliveries, newest first", body = Vec<WebhookDeliveryDto>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Webhook not found")
+    ),
+    tag = "admin"
+)]
+pub async fn list_webhook_deliveries(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(query): Query<WebhookDeliveryQueryDto>,
+) -> Result<Json<Vec<WebhookDeliveryDto>>, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    Ok(Json(
+        webhook_service(&state)?
+            .deliveries(None, &id, query.limit)
+            .await?,
+    ))
+}
+
+/// POST /api/admin/webhooks/{id}/ping — send a test event to an admin webhook
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhooks/{id}/ping",
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, description = "Outcome of the test delivery", body = WebhookDeliveryDto),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin required"),
+        (status = 404, description = "Webhook not found")
+    ),
+    tag = "admin"
+)]
+pub async fn ping_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookDeliveryDto>, AppError> {
+    admin_guard(&state, &headers).await?;
+
+    Ok(Json(webhook_service(&state)?.ping(None, &id).await?))
+}
+
 async fn reextract_audio_metadata(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -1,22 +1,32 @@
 use axum::{
     Router,
-    extract::{Json, Query, State},
+    extract::{Json, Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::{IntoResponse, Redirect, Response},
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
 };
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::application::dtos::two_factor_dto::{
+    TwoFactorChallengeSetupDto, TwoFactorCodeDto, TwoFactorLoginDto,
+};
 use crate::application::dtos::user_dto::{
-    ChangePasswordDto, LoginDto, OidcCallbackQueryDto, OidcExchangeDto, OidcProviderInfoDto,
-    RefreshTokenDto, RegisterDto, SetupAdminDto,
+    AccountLinkRequestDto, ChangePasswordDto, LoginDto, OidcCallbackQueryDto, OidcExchangeDto,
+    OidcProviderInfoDto, RefreshTokenDto, RegisterDto, ResetPasswordDto, SetupAdminDto, UserDto,
+    VerifyEmailDto,
+};
+use crate::application::dtos::webauthn_dto::{
+    WebAuthnLoginDto, WebAuthnLoginOptionsDto, WebAuthnRegisterDto,
 };
-use crate::application::services::auth_application_service::OidcCallbackResult;
+use crate::application::ports::activity_ports::{ActivityAction, NewActivity};
+use crate::application::services::account_recovery_service::AccountRecoveryService;
+use crate::application::services::auth_application_service::{LoginResult, OidcCallbackResult};
 use crate::common::di::AppState;
 use crate::interfaces::api::cookie_auth;
 use crate::interfaces::errors::AppError;
 use crate::interfaces::middleware::auth::CurrentUserId;
+use crate::interfaces::middleware::trusted_proxy::ClientInfo;
 
 /// Public auth routes — no authentication required.
 pub fn auth_public_routes() -> Router<Arc<AppState>> {
@@ -36,18 +46,50 @@ pub fn auth_protected_routes() -> Router<Arc<AppState>> {
         .route("/me", get(get_current_user))
         .route("/change-password", put(change_password))
         .route("/logout", post(logout))
+        // Two-factor authentication of the current user
+        .route("/2fa", get(get_two_factor_status))
+        .route("/2fa/setup", post(setup_two_factor))
+        .route("/2fa/confirm", post(confirm_two_factor))
+        .route("/2fa/disable", post(disable_two_factor))
+        .route("/2fa/recovery-codes", post(regenerate_recovery_codes))
+        // Passkeys of the current user
+        .route(
+            "/webauthn/register/options",
+            post(webauthn_register_options),
+        )
+        .route("/webauthn/register", post(webauthn_register))
+        .route("/webauthn/credentials", get(list_webauthn_credentials))
+        .route(
+            "/webauthn/credentials/{id}",
+            delete(delete_webauthn_credential),
+        )
 }
 
 /// Rate-limited auth routes — split out so main.rs can apply per-endpoint
 /// rate limiting middleware independently.
 pub fn login_route() -> Router<Arc<AppState>> {
-    Router::new().route("/login", post(login))
+    Router::new()
+        .route("/login", post(login))
+        .route("/login/2fa", post(login_two_factor))
+        .route("/login/2fa/setup", post(login_two_factor_setup))
+        .route("/webauthn/login/options", post(webauthn_login_options))
+        .route("/webauthn/login", post(webauthn_login))
 }
 
 pub fn register_route() -> Router<Arc<AppState>> {
     Router::new().route("/register", post(register))
 }
 
+/// Mailed-link routes (password reset, address verification) — public,
+/// rate-limited like register since most of them send mail.
+pub fn account_recovery_route() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/password/forgot", post(forgot_password))
+        .route("/password/reset", post(reset_password))
+        .route("/verify-email", post(verify_email))
+        .route("/verify-email/resend", post(resend_verification))
+}
+
 pub fn refresh_route() -> Router<Arc<AppState>> {
     Router::new().route("/refresh", post(refresh_token))
 }
@@ -108,9 +150,9 @@ async fn register(
         .register(dto.clone())
         .await
     {
-        Ok(user) => {
+        Ok(registered) => {
             tracing::info!("Registration successful for user: {}", dto.username);
-            Ok((StatusCode::CREATED, Json(user)))
+            Ok((StatusCode::CREATED, Json(registered)))
         }
         Err(err) => {
             tracing::error!("Registration failed for user {}: {}", dto.username, err);
@@ -121,6 +163,7 @@ async fn register(
 
 async fn login(
     State(state): State<Arc<AppState>>,
+    client: ClientInfo,
     headers: HeaderMap,
     Json(dto): Json<LoginDto>,
 ) -> Result<Response, AppError> {
@@ -176,7 +219,13 @@ async fn login(
         .login(dto.clone())
         .await
     {
-        Ok(auth_response) => {
+        Ok(LoginResult::TwoFactorRequired(challenge)) => {
+            // The lockout is only reset once the second factor succeeds, so
+            // that fresh challenges do not buy more code guesses.
+            tracing::info!("Login for user {} awaits the second factor", dto.username);
+            Ok((StatusCode::OK, Json(challenge)).into_response())
+        }
+        Ok(LoginResult::Authenticated(auth_response)) => {
             // ── Successful login — reset lockout counter ──
             auth_service.login_lockout.record_success(&dto.username);
 
@@ -195,6 +244,9 @@ async fn login(
                 ));
             }
 
+            record_session_client(&state, &auth_response.refresh_token, &client).await;
+            record_login(&state, &auth_response.user, "password", &client);
+
             // ── Set HttpOnly cookies so the browser never stores tokens in JS ──
             let mut response = (StatusCode::OK, Json(&auth_response)).into_response();
             cookie_auth::append_auth_cookies(
@@ -229,17 +281,221 @@ async fn login(
         Err(err) => {
             // ── Record failed attempt for lockout tracking ──
             auth_service.login_lockout.record_failure(&dto.username);
+            record_login_failure(&state, &dto.username, &client).await;
             tracing::error!("Login failed for user {}: {}", dto.username, err);
             Err(err.into())
         }
     }
 }
 
+fn account_recovery(state: &AppState) -> Result<&AccountRecoveryService, AppError> {
+    state
+        .auth_service
+        .as_ref()
+        .and_then(|auth| auth.account_recovery.as_deref())
+        .ok_or_else(|| {
+            AppError::new(
+                StatusCode::NOT_IMPLEMENTED,
+                "Password recovery is not available: no mail transport is configured.",
+                "MailNotConfigured",
+            )
+        })
+}
+
+/// Answer of the mailed-link requests, the same whether or not the
+/// account exists.
+fn link_requested() -> Response {
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "message": "If an account matches, a mail with a link is on its way."
+        })),
+    )
+        .into_response()
+}
+
+/// POST /api/auth/password/forgot — Mails a password reset link.
+/// Request body: { "login": "username or email" }
+async fn forgot_password(
+    State(state): State<Arc<AppState>>,
+    Json(dto): Json<AccountLinkRequestDto>,
+) -> Result<Response, AppError> {
+    account_recovery(&state)?
+        .request_password_reset(&dto.login)
+        .await?;
+    Ok(link_requested())
+}
+
+/// POST /api/auth/password/reset — Sets a new password with a mailed token.
+/// Request body: { "token": "...", "new_password": "..." }
+///
+/// Signs the user out of every session.
+async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    Json(dto): Json<ResetPasswordDto>,
+) -> Result<StatusCode, AppError> {
+    account_recovery(&state)?
+        .reset_password(&dto.token, &dto.new_password)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/verify-email — Confirms an address with a mailed token.
+/// Request body: { "token": "..." }
+async fn verify_email(
+    State(state): State<Arc<AppState>>,
+    Json(dto): Json<VerifyEmailDto>,
+) -> Result<StatusCode, AppError> {
+    account_recovery(&state)?.verify_email(&dto.token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/verify-email/resend — Mails a new verification link.
+/// Request body: { "login": "username or email" }
+async fn resend_verification(
+    State(state): State<Arc<AppState>>,
+    Json(dto): Json<AccountLinkRequestDto>,
+) -> Result<Response, AppError> {
+    account_recovery(&state)?
+        .resend_verification(&dto.login)
+        .await?;
+    Ok(link_requested())
+}
+
+/// Records where a newly issued session is used from, for the session list.
+pub(crate) async fn record_session_client(
+    state: &AppState,
+    refresh_token: &str,
+    client: &ClientInfo,
+) {
+    if let Some(sessions) = &state.user_session_service {
+        sessions
+            .record_client(refresh_token, &client.ip, client.user_agent.as_deref())
+            .await;
+    }
+}
+
+/// Logs a sign-in to the activity log.
+fn record_login(state: &AppState, user: &UserDto, method: &str, client: &ClientInfo) {
+    let (Some(activity), Ok(user_id)) = (&state.activity_service, Uuid::parse_str(&user.id)) else {
+        return;
+    };
+    activity.record(
+        NewActivity::by(ActivityAction::Login, user_id)
+            .with_ip(client.ip.clone())
+            .with_detail("method", method),
+    );
+}
+
+/// Logs a rejected sign-in, against the account when the name is known.
+async fn record_login_failure(state: &AppState, username: &str, client: &ClientInfo) {
+    let Some(activity) = &state.activity_service else {
+        return;
+    };
+    let mut entry = NewActivity::new(ActivityAction::LoginFailed)
+        .with_actor_name(username)
+        .with_ip(client.ip.clone());
+    if let Some(auth) = &state.auth_service
+        && let Ok(user) = auth
+            .auth_application_service
+            .get_user_by_username(username)
+            .await
+        && let Ok(user_id) = Uuid::parse_str(&user.id)
+    {
+        entry = entry.with_owner(user_id);
+    }
+    activity.record(entry);
+}
+
+/// POST /api/auth/login/2fa — Second step of a password login.
+/// Request body: { "challenge_token": "...", "code": "123456" }
+///
+/// Accepts a TOTP code or a recovery code.  Wrong codes count towards the
+/// account lockout like wrong passwords.
+async fn login_two_factor(
+    State(state): State<Arc<AppState>>,
+    client: ClientInfo,
+    Json(dto): Json<TwoFactorLoginDto>,
+) -> Result<Response, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+    let auth_app = &auth_service.auth_application_service;
+
+    let username = auth_app.two_factor_challenge_username(&dto.challenge_token);
+    if let Some(username) = username.as_deref()
+        && let Err(lockout_secs) = auth_service.login_lockout.check(username)
+    {
+        return Err(AppError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!(
+                "Account temporarily locked due to too many failed attempts. Try again in {} seconds.",
+                lockout_secs
+            ),
+            "AccountLocked",
+        ));
+    }
+
+    let result = match auth_app.login_two_factor(dto).await {
+        Ok(result) => result,
+        Err(err) => {
+            if let Some(username) = username.as_deref() {
+                auth_service.login_lockout.record_failure(username);
+                record_login_failure(&state, username, &client).await;
+                tracing::warn!("Second factor rejected for user {}: {}", username, err);
+            }
+            return Err(err.into());
+        }
+    };
+    auth_service
+        .login_lockout
+        .record_success(&result.auth.user.username);
+    tracing::info!(
+        "Two-factor login successful for user: {}",
+        result.auth.user.username
+    );
+
+    record_session_client(&state, &result.auth.refresh_token, &client).await;
+    record_login(&state, &result.auth.user, "two_factor", &client);
+
+    let mut response = (StatusCode::OK, Json(&result)).into_response();
+    cookie_auth::append_auth_cookies(
+        response.headers_mut(),
+        &result.auth.access_token,
+        &result.auth.refresh_token,
+        result.auth.expires_in,
+        state.core.config.auth.refresh_token_expiry_secs,
+    );
+    cookie_auth::append_csrf_cookie(response.headers_mut(), result.auth.expires_in);
+    Ok(response)
+}
+
+/// POST /api/auth/login/2fa/setup — Generates the TOTP secret for a login
+/// challenge whose user must enrol before getting in.
+/// Request body: { "challenge_token": "..." }
+async fn login_two_factor_setup(
+    State(state): State<Arc<AppState>>,
+    Json(dto): Json<TwoFactorChallengeSetupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let setup = auth_service
+        .auth_application_service
+        .two_factor_challenge_setup(&dto.challenge_token)
+        .await?;
+    Ok((StatusCode::OK, Json(setup)))
+}
+
 /// Token refresh — accepts the refresh token from **either**:
 /// 1. JSON body `{ "refresh_token": "..." }` (API clients, backward compat)
 /// 2. HttpOnly cookie `oxicloud_refresh` (browsers)
 async fn refresh_token(
     State(state): State<Arc<AppState>>,
+    client: ClientInfo,
     headers: HeaderMap,
     body: axum::body::Bytes,
 ) -> Result<Response, AppError> {
@@ -267,6 +523,7 @@ async fn refresh_token(
         .await?;
 
     tracing::info!("Token refresh successful, new token issued");
+    record_session_client(&state, &auth_response.refresh_token, &client).await;
 
     let mut response = (StatusCode::OK, Json(&auth_response)).into_response();
     cookie_auth::append_auth_cookies(
@@ -341,6 +598,7 @@ async fn change_password(
 async fn logout(
     State(state): State<Arc<AppState>>,
     CurrentUserId(user_id): CurrentUserId,
+    client: ClientInfo,
     headers: HeaderMap,
     body: axum::body::Bytes,
 ) -> Result<Response, AppError> {
@@ -365,6 +623,10 @@ async fn logout(
         .logout(user_id, &refresh_token)
         .await?;
 
+    if let Some(activity) = &state.activity_service {
+        activity.record(NewActivity::by(ActivityAction::Logout, user_id).with_ip(client.ip));
+    }
+
     // Clear HttpOnly + CSRF cookies so the browser forgets the session
     let mut response = StatusCode::OK.into_response();
     cookie_auth::append_clear_cookies(response.headers_mut());
@@ -372,6 +634,283 @@ async fn logout(
     Ok(response)
 }
 
+// ============================================================================
+// Two-factor authentication
+// ============================================================================
+
+/// GET /api/auth/2fa — Two-factor state of the current user
+async fn get_two_factor_status(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let user = auth_service
+        .auth_application_service
+        .get_user_entity(user_id)
+        .await?;
+    let status = auth_service.two_factor_service.status(&user).await?;
+    Ok((StatusCode::OK, Json(status)))
+}
+
+/// POST /api/auth/2fa/setup — Starts enrolment; returns the secret and
+/// its `otpauth://` URI.  Two-factor authentication stays off until
+/// confirmed.
+async fn setup_two_factor(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let user = auth_service
+        .auth_application_service
+        .get_user_entity(user_id)
+        .await?;
+    let setup = auth_service.two_factor_service.begin_setup(&user).await?;
+    Ok((StatusCode::OK, Json(setup)))
+}
+
+/// POST /api/auth/2fa/confirm — Completes enrolment with a code from the
+/// authenticator; returns the recovery codes.
+async fn confirm_two_factor(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+    Json(dto): Json<TwoFactorCodeDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let codes = auth_service
+        .two_factor_service
+        .confirm_setup(user_id, &dto.code)
+        .await?;
+    Ok((StatusCode::OK, Json(codes)))
+}
+
+/// POST /api/auth/2fa/disable — Turns two-factor authentication off;
+/// requires a current TOTP or recovery code.
+async fn disable_two_factor(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+    Json(dto): Json<TwoFactorCodeDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let user = auth_service
+        .auth_application_service
+        .get_user_entity(user_id)
+        .await?;
+    auth_service
+        .two_factor_service
+        .disable(&user, &dto.code)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/2fa/recovery-codes — Replaces the recovery codes;
+/// requires a current TOTP or recovery code.
+async fn regenerate_recovery_codes(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+    Json(dto): Json<TwoFactorCodeDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let codes = auth_service
+        .two_factor_service
+        .regenerate_recovery_codes(user_id, &dto.code)
+        .await?;
+    Ok((StatusCode::OK, Json(codes)))
+}
+
+// ============================================================================
+// Passkeys (WebAuthn)
+// ============================================================================
+
+/// POST /api/auth/webauthn/register/options — Starts registering a passkey;
+/// returns options for `navigator.credentials.create()`.
+async fn webauthn_register_options(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let user = auth_service
+        .auth_application_service
+        .get_user_entity(user_id)
+        .await?;
+    let options = auth_service
+        .webauthn_service
+        .registration_options(&user)
+        .await?;
+    Ok((StatusCode::OK, Json(options)))
+}
+
+/// POST /api/auth/webauthn/register — Completes the registration.
+/// Request body: { "name": "Laptop", "credential": <PublicKeyCredential JSON> }
+async fn webauthn_register(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+    Json(dto): Json<WebAuthnRegisterDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let credential = auth_service
+        .webauthn_service
+        .finish_registration(user_id, dto)
+        .await?;
+    Ok((StatusCode::CREATED, Json(credential)))
+}
+
+/// GET /api/auth/webauthn/credentials — Passkeys of the current user
+async fn list_webauthn_credentials(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let credentials = auth_service
+        .webauthn_service
+        .list_credentials(user_id)
+        .await?;
+    Ok((StatusCode::OK, Json(credentials)))
+}
+
+/// DELETE /api/auth/webauthn/credentials/{id} — Removes a passkey and
+/// revokes the sessions opened with it.
+async fn delete_webauthn_credential(
+    State(state): State<Arc<AppState>>,
+    CurrentUserId(user_id): CurrentUserId,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let id = Uuid::parse_str(&id).map_err(|_| AppError::bad_request("Invalid UUID"))?;
+    auth_service
+        .webauthn_service
+        .delete_credential(user_id, id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/webauthn/login/options — Starts a passkey login; returns
+/// options for `navigator.credentials.get()`.
+/// Request body: { "challenge_token": "..." } to answer the second factor
+/// of a password login, or empty for a passwordless login.
+async fn webauthn_login_options(
+    State(state): State<Arc<AppState>>,
+    body: axum::body::Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+
+    let dto = if body.is_empty() {
+        WebAuthnLoginOptionsDto::default()
+    } else {
+        serde_json::from_slice::<WebAuthnLoginOptionsDto>(&body)
+            .map_err(|e| AppError::bad_request(format!("Invalid request body: {}", e)))?
+    };
+    let options = auth_service
+        .auth_application_service
+        .webauthn_login_options(dto.challenge_token.as_deref())
+        .await?;
+    Ok((StatusCode::OK, Json(options)))
+}
+
+/// POST /api/auth/webauthn/login — Completes a passkey login.
+/// Request body: { "challenge_token": "..."?, "credential": <PublicKeyCredential JSON> }
+///
+/// As second factor, rejected passkeys count towards the account lockout
+/// like wrong codes.
+async fn webauthn_login(
+    State(state): State<Arc<AppState>>,
+    client: ClientInfo,
+    Json(dto): Json<WebAuthnLoginDto>,
+) -> Result<Response, AppError> {
+    let auth_service = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Authentication service not configured"))?;
+    let auth_app = &auth_service.auth_application_service;
+
+    let username = dto
+        .challenge_token
+        .as_deref()
+        .and_then(|token| auth_app.two_factor_challenge_username(token));
+    if let Some(username) = username.as_deref()
+        && let Err(lockout_secs) = auth_service.login_lockout.check(username)
+    {
+        return Err(AppError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!(
+                "Account temporarily locked due to too many failed attempts. Try again in {} seconds.",
+                lockout_secs
+            ),
+            "AccountLocked",
+        ));
+    }
+
+    let auth_response = match auth_app.login_webauthn(dto).await {
+        Ok(auth_response) => auth_response,
+        Err(err) => {
+            if let Some(username) = username.as_deref() {
+                auth_service.login_lockout.record_failure(username);
+                record_login_failure(&state, username, &client).await;
+            }
+            tracing::warn!("Passkey login rejected: {}", err);
+            return Err(err.into());
+        }
+    };
+    auth_service
+        .login_lockout
+        .record_success(&auth_response.user.username);
+    tracing::info!(
+        "Passkey login successful for user: {}",
+        auth_response.user.username
+    );
+    record_session_client(&state, &auth_response.refresh_token, &client).await;
+    record_login(&state, &auth_response.user, "passkey", &client);
+
+    let mut response = (StatusCode::OK, Json(&auth_response)).into_response();
+    cookie_auth::append_auth_cookies(
+        response.headers_mut(),
+        &auth_response.access_token,
+        &auth_response.refresh_token,
+        auth_response.expires_in,
+        state.core.config.auth.refresh_token_expiry_secs,
+    );
+    cookie_auth::append_csrf_cookie(response.headers_mut(), auth_response.expires_in);
+    Ok(response)
+}
+
 /// POST /api/setup — One-time endpoint to create the first admin user.
 ///
 /// Available only when the system is not yet initialized (no admin exists).
@@ -475,6 +1014,8 @@ struct SystemStatus {
     admin_count: i64,
     /// Whether registration is allowed (only if admin exists)
     registration_allowed: bool,
+    /// Whether users can reset a forgotten password by mail
+    password_reset_available: bool,
 }
 
 async fn get_system_status(
@@ -503,6 +1044,10 @@ async fn get_system_status(
         initialized: db_initialized || admin_count > 0,
         admin_count,
         registration_allowed: db_initialized || admin_count > 0,
+        password_reset_available: auth_service.account_recovery.is_some()
+            && !auth_service
+                .auth_application_service
+                .password_login_disabled(),
     };
 
     tracing::info!(
@@ -665,6 +1210,7 @@ async fn oidc_callback(
 /// Request body: { "code": "<one_time_code>" }
 async fn oidc_exchange(
     State(state): State<Arc<AppState>>,
+    client: ClientInfo,
     Json(body): Json<OidcExchangeDto>,
 ) -> Result<Response, AppError> {
     let auth_service = state
@@ -684,6 +1230,8 @@ async fn oidc_exchange(
         "OIDC token exchange successful for user: {}",
         auth_response.user.username
     );
+    record_session_client(&state, &auth_response.refresh_token, &client).await;
+    record_login(&state, &auth_response.user, "oidc", &client);
 
     // Set HttpOnly cookies for the browser
     let mut response = (StatusCode::OK, Json(&auth_response)).into_response();
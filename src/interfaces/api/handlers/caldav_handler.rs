@@ -8,12 +8,18 @@
  * Supported methods:
  * - OPTIONS: Advertise CalDAV capabilities
  * - PROPFIND: List calendars and their properties
- * - REPORT: Query events (calendar-query, calendar-multiget)
+ * - REPORT: Query events (calendar-query, calendar-multiget, sync-collection,
+ *   free-busy-query)
  * - MKCALENDAR: Create a new calendar
- * - PUT: Create/update calendar events (.ics)
- * - GET: Retrieve calendar event data
+ * - PUT: Create/update calendar events and tasks (.ics)
+ * - GET: Retrieve calendar event and task data
  * - DELETE: Remove calendars or events
  * - PROPPATCH: Modify calendar properties
+ * - POST: Free-busy requests to the scheduling outbox
+ *
+ * Scheduling (RFC 6638) is implicit: storing or deleting an object with an
+ * ORGANIZER delivers iTIP messages to the other OxiCloud users involved,
+ * which their clients read from the scheduling inbox at /caldav/inbox/.
  */
 use axum::{
     Router,
@@ -26,14 +32,21 @@ use percent_encoding::percent_decode_str;
 use std::fmt::Write;
 use std::sync::Arc;
 
-use crate::application::adapters::caldav_adapter::{CalDavAdapter, CalDavReportType};
-use crate::application::adapters::webdav_adapter::{PropFindRequest, PropFindType};
+use crate::application::adapters::caldav_adapter::{
+    CalDavAdapter, CalDavReportType, CalendarDataRecurrence,
+};
+use crate::application::adapters::webdav_adapter::{
+    PropFindRequest, PropFindType, PropPatchStatus, QualifiedName, WebDavAdapter,
+};
 use crate::application::dtos::calendar_dto::{
-    CreateCalendarDto, CreateEventICalDto, UpdateCalendarDto,
+    CalendarEventDto, CreateCalendarDto, CreateEventICalDto, UpdateCalendarDto,
 };
 use crate::application::ports::calendar_ports::CalendarUseCase;
+use crate::application::ports::calendar_scheduling_ports::CalendarSchedulingUseCase;
+use crate::application::services::calendar_scheduling_service::CalendarSchedulingService;
 use crate::application::services::calendar_service::CalendarService;
 use crate::common::di::AppState;
+use crate::domain::services::recurrence_service::IcalComponent;
 use crate::interfaces::errors::AppError;
 use crate::interfaces::middleware::auth::{AuthUser, CurrentUser};
 
@@ -95,6 +108,15 @@ async fn handle_caldav_methods_inner(
 ) -> Result<Response<Body>, AppError> {
     let method = req.method().clone();
 
+    // The scheduling collections belong to the current user and are not
+    // subject to the `{username}/…` path heuristic
+    if path == "inbox" || path.starts_with("inbox/") {
+        return handle_schedule_inbox(state, req, &path).await;
+    }
+    if path == "outbox" {
+        return handle_schedule_outbox(state, req).await;
+    }
+
     match method.as_str() {
         "OPTIONS" => handle_options().await,
         "PROPFIND" => handle_propfind(state, req, &path).await,
@@ -188,15 +210,25 @@ fn get_calendar_service(state: &AppState) -> Result<&Arc<CalendarService>, AppEr
     })
 }
 
+fn get_scheduling_service(state: &AppState) -> Result<&Arc<CalendarSchedulingService>, AppError> {
+    state.calendar_scheduling_service.as_ref().ok_or_else(|| {
+        AppError::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "CalDAV scheduling is not configured",
+            "NotImplemented",
+        )
+    })
+}
+
 // ─── OPTIONS ─────────────────────────────────────────────────────────
 
 async fn handle_options() -> Result<Response<Body>, AppError> {
     Ok(Response::builder()
         .status(StatusCode::OK)
-        .header(HEADER_DAV, "1, 2, calendar-access")
+        .header(HEADER_DAV, "1, 2, calendar-access, calendar-auto-schedule")
         .header(
             header::ALLOW,
-            "OPTIONS, GET, PUT, DELETE, PROPFIND, PROPPATCH, REPORT, MKCALENDAR",
+            "OPTIONS, GET, PUT, DELETE, POST, PROPFIND, PROPPATCH, REPORT, MKCALENDAR",
         )
         .body(Body::empty())
         .unwrap())
@@ -272,11 +304,15 @@ async fn handle_propfind(
             username
         };
 
+        // Scheduling properties are only advertised on the user's own principal
+        let email = (username == user.username).then_some(user.email.as_str());
+
         let mut response_body = Vec::new();
         CalDavAdapter::generate_principal_propfind_response(
             &mut response_body,
             &propfind_request,
             username,
+            email,
         )
         .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
 
@@ -439,6 +475,7 @@ async fn handle_propfind(
             let report_type = CalDavReportType::CalendarMultiget {
                 hrefs: vec![format!("{}{}.ics", base_href, ical_uid)],
                 props: vec![],
+                recurrence: CalendarDataRecurrence::AsStored,
             };
 
             let mut response_body = Vec::new();
@@ -479,13 +516,33 @@ async fn handle_report(
     let effective_path = strip_username_prefix(path);
     let calendar_id = effective_path.split('/').next().unwrap_or(effective_path);
 
+    // On the calendar home, a free-busy-query covers every readable calendar
+    if let CalDavReportType::FreeBusyQuery { time_range } = &report {
+        let scheduling_service = get_scheduling_service(&state)?;
+        let (start, end) = time_range
+            .ok_or_else(|| AppError::bad_request("free-busy-query requires a time-range"))?;
+        let calendar_id = (!calendar_id.is_empty()).then_some(calendar_id);
+        let freebusy = scheduling_service
+            .free_busy_query(user.id, calendar_id, start, end)
+            .await?;
+        return Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .body(Body::from(freebusy))
+            .unwrap());
+    }
+
     if calendar_id.is_empty() {
         return Err(AppError::bad_request("Calendar ID required in path"));
     }
 
     let events = match &report {
-        CalDavReportType::CalendarQuery { time_range, .. } => {
-            if let Some((start, end)) = time_range {
+        CalDavReportType::CalendarQuery {
+            time_range,
+            component,
+            ..
+        } => {
+            let mut events = if let Some((start, end)) = time_range {
                 calendar_service
                     .get_events_in_range(calendar_id, *start, *end, user.id)
                     .await
@@ -499,7 +556,11 @@ async fn handle_report(
                     .map_err(|e| {
                         AppError::internal_error(format!("Failed to list events: {}", e))
                     })?
+            };
+            if let Some(component) = component {
+                events.retain(|event| event.component() == component);
             }
+            events
         }
         CalDavReportType::CalendarMultiget { hrefs, .. } => {
             let all_events = calendar_service
@@ -512,10 +573,17 @@ async fn handle_report(
                 .filter(|evt| hrefs.iter().any(|href| href.contains(&evt.ical_uid)))
                 .collect()
         }
-        CalDavReportType::SyncCollection { .. } => calendar_service
-            .list_events(calendar_id, None, None, user.id)
-            .await
-            .map_err(|e| AppError::internal_error(format!("Failed to list events: {}", e)))?,
+        CalDavReportType::SyncCollection { sync_token, props } => {
+            return handle_sync_collection(
+                calendar_service,
+                calendar_id,
+                sync_token,
+                props,
+                user.id,
+            )
+            .await;
+        }
+        CalDavReportType::FreeBusyQuery { .. } => unreachable!("answered above"),
     };
 
     let base_href = &format!("/caldav/{}/", calendar_id);
@@ -535,6 +603,59 @@ async fn handle_report(
         .unwrap())
 }
 
+/// Answers a sync-collection REPORT (RFC 6578) with the changes since the
+/// client's token, or `403 DAV:valid-sync-token` when it can't.
+async fn handle_sync_collection(
+    calendar_service: &CalendarService,
+    calendar_id: &str,
+    sync_token: &str,
+    props: &[QualifiedName],
+    user_id: uuid::Uuid,
+) -> Result<Response<Body>, AppError> {
+    let since = if sync_token.trim().is_empty() {
+        None
+    } else {
+        match WebDavAdapter::parse_sync_token(sync_token) {
+            Some(token) => Some(token),
+            None => return Ok(invalid_sync_token_response()),
+        }
+    };
+
+    let Some(changes) = calendar_service
+        .get_changes_since(calendar_id, since, user_id)
+        .await?
+    else {
+        return Ok(invalid_sync_token_response());
+    };
+
+    let base_href = &format!("/caldav/{}/", calendar_id);
+    let mut response_body = Vec::new();
+    CalDavAdapter::generate_sync_collection_response(
+        &mut response_body,
+        &changes,
+        props,
+        base_href,
+    )
+    .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(response_body))
+        .unwrap())
+}
+
+/// Expired or unknown sync token; the client falls back to a full sync.
+fn invalid_sync_token_response() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(
+            r#"<?xml version="1.0" encoding="utf-8"?><D:error xmlns:D="DAV:"><D:valid-sync-token/></D:error>"#,
+        ))
+        .unwrap()
+}
+
 // ─── MKCALENDAR ──────────────────────────────────────────────────────
 
 async fn handle_mkcalendar(
@@ -618,7 +739,7 @@ async fn handle_put(
         None
     };
 
-    if let Some(existing_event) = existing {
+    if let Some(existing_event) = &existing {
         // Update existing event — re-create from iCal for full fidelity
         calendar_service
             .delete_event(&existing_event.id, user.id)
@@ -633,6 +754,7 @@ async fn handle_put(
             .create_event_from_ical(create_dto, user.id)
             .await
             .map_err(|e| AppError::internal_error(format!("Failed to recreate event: {}", e)))?;
+        schedule_write(&state, user.id, existing.as_ref(), &event).await;
 
         Ok(Response::builder()
             .status(StatusCode::NO_CONTENT)
@@ -649,6 +771,7 @@ async fn handle_put(
             .create_event_from_ical(create_dto, user.id)
             .await
             .map_err(|e| AppError::internal_error(format!("Failed to create event: {}", e)))?;
+        schedule_write(&state, user.id, None, &event).await;
 
         Ok(Response::builder()
             .status(StatusCode::CREATED)
@@ -658,6 +781,25 @@ async fn handle_put(
     }
 }
 
+/// Delivers the scheduling messages for a stored object.  The store itself
+/// has succeeded, so delivery failures are only logged.
+async fn schedule_write(
+    state: &AppState,
+    user_id: uuid::Uuid,
+    previous: Option<&CalendarEventDto>,
+    current: &CalendarEventDto,
+) {
+    let Some(scheduling_service) = &state.calendar_scheduling_service else {
+        return;
+    };
+    if let Err(e) = scheduling_service
+        .schedule_write(user_id, previous, current)
+        .await
+    {
+        tracing::warn!("Scheduling failed for {}: {}", current.ical_uid, e);
+    }
+}
+
 /// Extract UID from iCalendar data
 fn extract_uid_from_ical(ical_data: &str) -> Option<String> {
     for line in ical_data.lines() {
@@ -741,13 +883,21 @@ fn generate_full_calendar_ical(
         calendar_name
     );
     for event in events {
-        write_vevent(&mut buf, event);
+        if event.is_task() {
+            write_vtodos(&mut buf, event);
+        } else {
+            write_vevent(&mut buf, event);
+        }
     }
     buf.push_str("END:VCALENDAR\r\n");
     buf
 }
 
 fn generate_event_ical(event: &crate::application::dtos::calendar_dto::CalendarEventDto) -> String {
+    // Tasks and scheduling objects keep their complete iCalendar object
+    if event.is_task() || event.is_scheduling_object() {
+        return event.ical_data.clone();
+    }
     let mut buf = String::with_capacity(512);
     buf.push_str("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n");
     write_vevent(&mut buf, event);
@@ -786,6 +936,15 @@ fn write_vevent(
     );
 }
 
+/// Writes the VTODO components of a stored task into `buf`.
+fn write_vtodos(buf: &mut String, task: &crate::application::dtos::calendar_dto::CalendarEventDto) {
+    for calendar in IcalComponent::parse(&task.ical_data) {
+        for component in calendar.components.iter().filter(|c| c.name == "VTODO") {
+            component.write_to(buf);
+        }
+    }
+}
+
 // ─── DELETE ──────────────────────────────────────────────────────────
 
 async fn handle_delete(
@@ -827,6 +986,12 @@ async fn handle_delete(
             .delete_event(&event.id, user.id)
             .await
             .map_err(|e| AppError::internal_error(format!("Failed to delete event: {}", e)))?;
+
+        if let Some(scheduling_service) = &state.calendar_scheduling_service
+            && let Err(e) = scheduling_service.schedule_delete(user.id, event).await
+        {
+            tracing::warn!("Scheduling failed for {}: {}", event.ical_uid, e);
+        }
     }
 
     Ok(Response::builder()
@@ -887,10 +1052,10 @@ async fn handle_proppatch(
 
     let mut results = Vec::new();
     for prop in &props_to_set {
-        results.push((&prop.name, true));
+        results.push((&prop.name, PropPatchStatus::Ok));
     }
     for prop in &props_to_remove {
-        results.push((prop, true));
+        results.push((prop, PropPatchStatus::Ok));
     }
 
     let href = format!("/caldav/{}", path);
@@ -909,6 +1074,200 @@ async fn handle_proppatch(
         .unwrap())
 }
 
+// ─── Scheduling inbox / outbox (RFC 6638) ────────────────────────────
+
+async fn handle_schedule_inbox(
+    state: Arc<AppState>,
+    req: Request<Body>,
+    path: &str,
+) -> Result<Response<Body>, AppError> {
+    let user = extract_user(&req)?;
+    let scheduling_service = get_scheduling_service(&state)?;
+    let message_id = path
+        .strip_prefix("inbox/")
+        .map(|file| file.trim_end_matches(".ics"))
+        .filter(|id| !id.is_empty());
+
+    match (req.method().as_str(), message_id) {
+        ("OPTIONS", _) => handle_options().await,
+        ("PROPFIND", None) => {
+            let depth = req
+                .headers()
+                .get("Depth")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("1")
+                .to_string();
+            let body_bytes = body::to_bytes(req.into_body(), MAX_CALDAV_BODY)
+                .await
+                .map_err(|e| {
+                    AppError::bad_request(format!("Failed to read request body: {}", e))
+                })?;
+            let propfind_request = if body_bytes.is_empty() {
+                PropFindRequest {
+                    prop_find_type: PropFindType::AllProp,
+                }
+            } else {
+                WebDavAdapter::parse_propfind(body_bytes.reader()).map_err(|e| {
+                    AppError::bad_request(format!("Failed to parse PROPFIND: {}", e))
+                })?
+            };
+
+            let messages = if depth == "0" {
+                vec![]
+            } else {
+                scheduling_service.list_inbox(user.id).await?
+            };
+
+            let mut response_body = Vec::new();
+            CalDavAdapter::generate_schedule_inbox_propfind(
+                &mut response_body,
+                &messages,
+                &propfind_request,
+                &depth,
+            )
+            .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+            Ok(Response::builder()
+                .status(StatusCode::MULTI_STATUS)
+                .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+                .body(Body::from(response_body))
+                .unwrap())
+        }
+        ("REPORT", None) => {
+            let body_bytes = body::to_bytes(req.into_body(), MAX_CALDAV_BODY)
+                .await
+                .map_err(|e| {
+                    AppError::bad_request(format!("Failed to read request body: {}", e))
+                })?;
+            let report = CalDavAdapter::parse_report(body_bytes.reader())
+                .map_err(|e| AppError::bad_request(format!("Failed to parse REPORT: {}", e)))?;
+
+            let mut messages = scheduling_service.list_inbox(user.id).await?;
+            let props = match &report {
+                CalDavReportType::CalendarQuery { props, .. } => props.as_slice(),
+                CalDavReportType::CalendarMultiget { hrefs, props, .. } => {
+                    messages.retain(|m| hrefs.iter().any(|href| href.contains(&m.id)));
+                    props.as_slice()
+                }
+                _ => {
+                    return Err(AppError::bad_request(
+                        "Report not supported on the scheduling inbox",
+                    ));
+                }
+            };
+
+            let mut response_body = Vec::new();
+            CalDavAdapter::generate_schedule_messages_response(
+                &mut response_body,
+                &messages,
+                props,
+            )
+            .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+            Ok(Response::builder()
+                .status(StatusCode::MULTI_STATUS)
+                .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+                .body(Body::from(response_body))
+                .unwrap())
+        }
+        ("GET", Some(message_id)) => {
+            let message = scheduling_service
+                .list_inbox(user.id)
+                .await?
+                .into_iter()
+                .find(|m| m.id == message_id)
+                .ok_or_else(|| AppError::not_found(format!("Message not found: {}", message_id)))?;
+
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header(
+                    header::CONTENT_TYPE,
+                    format!("text/calendar; charset=utf-8; method={}", message.method),
+                )
+                .header(header::ETAG, format!("\"{}\"", message.id))
+                .body(Body::from(message.ical_data))
+                .unwrap())
+        }
+        ("DELETE", Some(message_id)) => {
+            scheduling_service
+                .delete_inbox_message(user.id, message_id)
+                .await?;
+            Ok(Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(Body::empty())
+                .unwrap())
+        }
+        (method, _) => Err(AppError::method_not_allowed(format!(
+            "Method not allowed on the scheduling inbox: {}",
+            method
+        ))),
+    }
+}
+
+async fn handle_schedule_outbox(
+    state: Arc<AppState>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    let user = extract_user(&req)?;
+    let scheduling_service = get_scheduling_service(&state)?;
+    let method = req.method().clone();
+
+    if method == "OPTIONS" {
+        return handle_options().await;
+    }
+
+    let body_bytes = body::to_bytes(req.into_body(), MAX_CALDAV_BODY)
+        .await
+        .map_err(|e| AppError::bad_request(format!("Failed to read request body: {}", e)))?;
+
+    match method.as_str() {
+        "PROPFIND" => {
+            let propfind_request = if body_bytes.is_empty() {
+                PropFindRequest {
+                    prop_find_type: PropFindType::AllProp,
+                }
+            } else {
+                WebDavAdapter::parse_propfind(body_bytes.reader()).map_err(|e| {
+                    AppError::bad_request(format!("Failed to parse PROPFIND: {}", e))
+                })?
+            };
+
+            let mut response_body = Vec::new();
+            CalDavAdapter::generate_schedule_outbox_propfind(&mut response_body, &propfind_request)
+                .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+            Ok(Response::builder()
+                .status(StatusCode::MULTI_STATUS)
+                .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+                .body(Body::from(response_body))
+                .unwrap())
+        }
+        "POST" => {
+            // Only free-busy requests are POSTed; invitations are implicit
+            let ical_data = String::from_utf8(body_bytes.to_vec()).map_err(|e| {
+                AppError::bad_request(format!("Invalid UTF-8 in iCalendar data: {}", e))
+            })?;
+            let responses = scheduling_service
+                .free_busy_request(user.id, &ical_data)
+                .await?;
+
+            let mut response_body = Vec::new();
+            CalDavAdapter::generate_schedule_response(&mut response_body, &responses)
+                .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+                .body(Body::from(response_body))
+                .unwrap())
+        }
+        _ => Err(AppError::method_not_allowed(format!(
+            "Method not allowed on the scheduling outbox: {}",
+            method
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::strip_username_prefix;
@@ -9,7 +9,7 @@
  * Supported methods:
  * - OPTIONS: Advertise CardDAV capabilities
  * - PROPFIND: List address books and their properties
- * - REPORT: Query contacts (addressbook-query, addressbook-multiget)
+ * - REPORT: Query contacts (addressbook-query, addressbook-multiget, sync-collection)
  * - MKCOL (ext): Create a new address book
  * - PUT: Create/update contacts (.vcf)
  * - GET: Retrieve contact vCard data
@@ -28,7 +28,9 @@ use std::sync::Arc;
 use crate::application::adapters::carddav_adapter::{
     CardDavAdapter, CardDavReportType, contact_to_vcard,
 };
-use crate::application::adapters::webdav_adapter::{PropFindRequest, PropFindType};
+use crate::application::adapters::webdav_adapter::{
+    PropFindRequest, PropFindType, PropPatchStatus, QualifiedName, WebDavAdapter,
+};
 use crate::application::dtos::address_book_dto::{CreateAddressBookDto, UpdateAddressBookDto};
 use crate::application::dtos::contact_dto::CreateContactVCardDto;
 use crate::application::ports::carddav_ports::{AddressBookUseCase, ContactUseCase};
@@ -380,10 +382,16 @@ async fn handle_report(
                 .filter(|c| hrefs.iter().any(|href| href.contains(&c.uid)))
                 .collect()
         }
-        CardDavReportType::SyncCollection { .. } => contact_svc
-            .list_contacts(address_book_id, user.id)
-            .await
-            .map_err(|e| AppError::internal_error(format!("Failed to list contacts: {}", e)))?,
+        CardDavReportType::SyncCollection { sync_token, props } => {
+            return handle_sync_collection(
+                contact_svc,
+                address_book_id,
+                sync_token,
+                props,
+                user.id,
+            )
+            .await;
+        }
     };
 
     // Generate vCards
@@ -410,6 +418,59 @@ async fn handle_report(
         .unwrap())
 }
 
+/// Answers a sync-collection REPORT (RFC 6578) from the address book's
+/// change log.  An empty token is an initial sync and returns every contact.
+async fn handle_sync_collection(
+    contact_svc: &ContactStorageAdapter,
+    address_book_id: &str,
+    sync_token: &str,
+    props: &[QualifiedName],
+    user_id: uuid::Uuid,
+) -> Result<Response<Body>, AppError> {
+    let since = if sync_token.trim().is_empty() {
+        None
+    } else {
+        match WebDavAdapter::parse_sync_token(sync_token) {
+            Some(token) => Some(token),
+            None => return Ok(invalid_sync_token_response()),
+        }
+    };
+
+    let Some(changes) = contact_svc
+        .get_changes_since(address_book_id, since, user_id)
+        .await?
+    else {
+        return Ok(invalid_sync_token_response());
+    };
+
+    let base_href = &format!("/carddav/{}/", address_book_id);
+    let mut response_body = Vec::new();
+    CardDavAdapter::generate_sync_collection_response(
+        &mut response_body,
+        &changes,
+        props,
+        base_href,
+    )
+    .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(response_body))
+        .unwrap())
+}
+
+/// Expired or unknown sync token; the client falls back to a full sync.
+fn invalid_sync_token_response() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(
+            r#"<?xml version="1.0" encoding="utf-8"?><D:error xmlns:D="DAV:"><D:valid-sync-token/></D:error>"#,
+        ))
+        .unwrap()
+}
+
 // ─── MKCOL (create address book) ─────────────────────────────────────
 
 async fn handle_mkcol(
@@ -715,10 +776,10 @@ async fn handle_proppatch(
 
     let mut results = Vec::new();
     for prop in &props_to_set {
-        results.push((&prop.name, true));
+        results.push((&prop.name, PropPatchStatus::Ok));
     }
     for prop in &props_to_remove {
-        results.push((prop, true));
+        results.push((prop, PropPatchStatus::Ok));
     }
 
     let href = format!("/carddav/{}", path);
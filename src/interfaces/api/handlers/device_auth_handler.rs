@@ -21,8 +21,10 @@ use uuid::Uuid;
 use crate::application::dtos::device_auth_dto::*;
 use crate::application::services::device_auth_service::DeviceAuthService;
 use crate::common::di::AppState;
+use crate::interfaces::api::handlers::auth_handler::record_session_client;
 use crate::interfaces::errors::AppError;
 use crate::interfaces::middleware::auth::AuthUser;
+use crate::interfaces::middleware::trusted_proxy::ClientInfo;
 
 /// Create the device auth router.
 ///
@@ -72,6 +74,7 @@ async fn device_authorize(
 /// Returns tokens on success, or RFC 8628 error codes while pending.
 async fn device_token(
     State(state): State<Arc<AppState>>,
+    client: ClientInfo,
     Json(body): Json<DeviceTokenRequestDto>,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
     let device_service = match get_device_service(&state) {
@@ -91,7 +94,11 @@ async fn device_token(
     }
 
     match device_service.poll(&body.device_code).await {
-        Ok(tokens) => Ok((StatusCode::OK, Json(tokens)).into_response()),
+        Ok(tokens) => {
+            // The device, not the browser that approved it, uses the session
+            record_session_client(&state, &tokens.refresh_token, &client).await;
+            Ok((StatusCode::OK, Json(tokens)).into_response())
+        }
         Err(poll_err) => {
             let status =
                 StatusCode::from_u16(poll_err.http_status()).unwrap_or(StatusCode::BAD_REQUEST);
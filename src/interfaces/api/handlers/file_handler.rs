@@ -116,22 +116,24 @@ impl FileHandler {
                     .to_string();
 
                 // ── SECURITY: Verify folder ownership before upload (IDOR V-03 fix) ──
+                // Uploads into a folder shared with write access are charged
+                // against the folder's owner.
+                let mut owner_id = auth_user.id;
                 if let Some(ref fid) = folder_id {
-                    use crate::application::ports::inbound::FolderUseCase;
                     let folder_service = &state.applications.folder_service;
-                    if folder_service
-                        .get_folder_owned(fid, auth_user.id)
+                    match folder_service
+                        .writable_folder_owner(fid, auth_user.id)
                         .await
-                        .is_err()
                     {
-                        tracing::warn!(
-                            "⛔ UPLOAD REJECTED (IDOR): user='{}' attempted upload to folder '{}' owned by another user",
-                            auth_user.username,
-                            fid,
-                        );
-                        return Err(Self::domain_error_response(
-                            crate::common::errors::DomainError::not_found("Folder", fid),
-                        ));
+                        Ok(id) => owner_id = id,
+                        Err(err) => {
+                            tracing::warn!(
+                                "⛔ UPLOAD REJECTED (IDOR): user='{}' attempted upload to folder '{}' without write access",
+                                auth_user.username,
+                                fid,
+                            );
+                            return Err(Self::domain_error_response(err));
+                        }
                     }
                 }
 
@@ -144,7 +146,7 @@ impl FileHandler {
                         .and_then(|s| s.parse::<u64>().ok())
                         .unwrap_or(0);
                     if let Err(err) = storage_svc
-                        .check_storage_quota(auth_user.id, estimated_size)
+                        .check_storage_quota(owner_id, estimated_size)
                         .await
                     {
                         tracing::warn!(
@@ -248,9 +250,7 @@ impl FileHandler {
 
                 // ── Quota enforcement ────────────────────────────────
                 if let Some(storage_svc) = state.storage_usage_service.as_ref()
-                    && let Err(err) = storage_svc
-                        .check_storage_quota(auth_user.id, total_size)
-                        .await
+                    && let Err(err) = storage_svc.check_storage_quota(owner_id, total_size).await
                 {
                     let _ = tokio::fs::remove_file(&temp_path).await;
                     tracing::warn!(
@@ -394,8 +394,13 @@ impl FileHandler {
             }
         };
 
-        // Non-image (video, etc.) with no cached thumbnail → 204
-        if !thumbnail_service.is_supported_image(&file.mime_type) {
+        // Non-image (video, etc.) with no cached thumbnail → 204, as are
+        // end-to-end encrypted files, which the server cannot read
+        let encrypted = match state.e2ee_service.as_ref() {
+            Some(e2ee) => e2ee.is_encrypted(&file.id).await.unwrap_or(false),
+            None => false,
+        };
+        if encrypted || !thumbnail_service.is_supported_image(&file.mime_type) {
             return Response::builder()
                 .status(StatusCode::NO_CONTENT)
                 .header(header::CACHE_CONTROL, "no-store")
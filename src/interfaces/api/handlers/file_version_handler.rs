@@ -0,0 +1,136 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::application::ports::file_version_ports::FileVersionUseCase;
+use crate::application::services::file_version_service::FileVersionService;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::AuthUser;
+
+use super::file_handler::build_content_disposition;
+
+/// List previous versions of a file, newest first
+#[utoipa::path(
+    get,
+    path = "/api/files/{id}/versions",
+    params(("id" = String, Path, description = "File ID")),
+    responses(
+        (status = 200, description = "List of versions", body = Vec<crate::application::dtos::file_version_dto::FileVersionDto>),
+        (status = 404, description = "File not found"),
+    ),
+    tag = "versions"
+)]
+pub async fn list_versions(
+    State(service): State<Arc<FileVersionService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match service.list_versions(&id, auth_user.id).await {
+        Ok(versions) => (StatusCode::OK, Json(versions)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Download the content of a previous version
+#[utoipa::path(
+    get,
+    path = "/api/files/{id}/versions/{version_id}",
+    params(
+        ("id" = String, Path, description = "File ID"),
+        ("version_id" = String, Path, description = "Version ID"),
+    ),
+    responses(
+        (status = 200, description = "Version content"),
+        (status = 404, description = "File or version not found"),
+    ),
+    tag = "versions"
+)]
+pub async fn download_version(
+    State(service): State<Arc<FileVersionService>>,
+    auth_user: AuthUser,
+    Path((id, version_id)): Path<(String, String)>,
+) -> Response {
+    match service
+        .get_version_content(&id, &version_id, auth_user.id)
+        .await
+    {
+        Ok((version, stream)) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, version.mime_type.as_str())
+            .header(
+                header::CONTENT_DISPOSITION,
+                build_content_disposition(&version.name, &version.mime_type, false),
+            )
+            .header(header::CONTENT_LENGTH, version.size)
+            .header(header::ETAG, format!("\"{}\"", version.etag))
+            .header(header::CACHE_CONTROL, "private, max-age=3600, immutable")
+            .body(Body::from_stream(stream))
+            .unwrap()
+            .into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Restore a previous version as the current content of the file
+///
+/// The content being replaced is kept as a new version.
+#[utoipa::path(
+    post,
+    path = "/api/files/{id}/versions/{version_id}/restore",
+    params(
+        ("id" = String, Path, description = "File ID"),
+        ("version_id" = String, Path, description = "Version ID"),
+    ),
+    responses(
+        (status = 200, description = "Version restored", body = crate::application::dtos::file_dto::FileDto),
+        (status = 404, description = "File or version not found"),
+    ),
+    tag = "versions"
+)]
+pub async fn restore_version(
+    State(service): State<Arc<FileVersionService>>,
+    auth_user: AuthUser,
+    Path((id, version_id)): Path<(String, String)>,
+) -> Response {
+    match service
+        .restore_version(&id, &version_id, auth_user.id)
+        .await
+    {
+        Ok(file) => (StatusCode::OK, Json(file)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Permanently delete a previous version
+#[utoipa::path(
+    delete,
+    path = "/api/files/{id}/versions/{version_id}",
+    params(
+        ("id" = String, Path, description = "File ID"),
+        ("version_id" = String, Path, description = "Version ID"),
+    ),
+    responses(
+        (status = 204, description = "Version deleted"),
+        (status = 404, description = "File or version not found"),
+    ),
+    tag = "versions"
+)]
+pub async fn delete_version(
+    State(service): State<Arc<FileVersionService>>,
+    auth_user: AuthUser,
+    Path((id, version_id)): Path<(String, String)>,
+) -> Response {
+    match service.delete_version(&id, &version_id, auth_user.id).await {
+        Ok(()) => {
+            info!("Deleted version {} of file {}", version_id, id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
@@ -77,21 +77,20 @@ impl FolderHandler {
         }
 
         // ── SECURITY: Verify parent folder ownership (IDOR V-04 fix) ──
-        if let Some(ref parent_id) = dto.parent_id {
-            use crate::application::ports::inbound::FolderUseCase;
-            if service
-                .get_folder_owned(parent_id, auth_user.id)
-                .await
-                .is_err()
-            {
-                tracing::warn!(
-                    "create_folder: user '{}' attempted to create folder in parent '{}' owned by another user",
-                    auth_user.username,
-                    parent_id,
-                );
-                return AppError::not_found(format!("Parent folder not found: {}", parent_id))
-                    .into_response();
+        // Folders shared with write access accept new subfolders too.
+        if let Some(ref parent_id) = dto.parent_id
+            && let Err(err) = service.writable_folder_owner(parent_id, auth_user.id).await
+        {
+            tracing::warn!(
+                "create_folder: user '{}' attempted to create folder in parent '{}' without write access",
+                auth_user.username,
+                parent_id,
+            );
+            if err.kind == crate::common::errors::ErrorKind::AccessDenied {
+                return AppError::from(err).into_response();
             }
+            return AppError::not_found(format!("Parent folder not found: {}", parent_id))
+                .into_response();
         }
 
         match service.create_folder(dto).await {
@@ -101,28 +100,16 @@ impl FolderHandler {
     }
 
     /// Gets a folder by ID.
-    /// Validates that the authenticated user owns the folder.
+    /// Validates that the authenticated user owns the folder or that it was
+    /// shared with them.
     pub(super) async fn get_folder_impl(
         State(service): State<AppState>,
         auth_user: AuthUser,
         Path(id): Path<String>,
     ) -> impl IntoResponse {
-        match service.get_folder(&id).await {
-            Ok(folder) => {
-                // Access check: folder must belong to the requesting user
-                if let Some(ref owner) = folder.owner_id
-                    && owner != &auth_user.id.to_string()
-                {
-                    tracing::warn!(
-                        "get_folder: user '{}' attempted to access folder '{}' owned by '{}'",
-                        auth_user.id,
-                        id,
-                        owner
-                    );
-                    return AppError::not_found("Folder not found").into_response();
-                }
-                (StatusCode::OK, Json(folder)).into_response()
-            }
+        use crate::application::ports::inbound::FolderUseCase;
+        match service.get_folder_owned(&id, auth_user.id).await {
+            Ok(folder) => (StatusCode::OK, Json(folder)).into_response(),
             Err(err) => AppError::from(err).into_response(),
         }
     }
@@ -302,10 +289,20 @@ impl FolderHandler {
         let user_id = auth_user.id;
         // Check if trash service is available
         if let Some(trash_service) = &state.trash_service {
+            // Folders deleted from a shared folder go to the owner's trash
+            let owner_id = match state
+                .applications
+                .folder_service
+                .modifiable_folder_owner(&id, user_id)
+                .await
+            {
+                Ok(owner_id) => owner_id,
+                Err(err) => return AppError::from(err).into_response(),
+            };
             tracing::info!("Moving folder to trash: {}", id);
 
             // Try to move to trash first
-            match trash_service.move_to_trash(&id, "folder", user_id).await {
+            match trash_service.move_to_trash(&id, "folder", owner_id).await {
                 Ok(_) => {
                     tracing::info!("Folder successfully moved to trash: {}", id);
                     return StatusCode::NO_CONTENT.into_response();
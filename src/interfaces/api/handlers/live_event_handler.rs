@@ -0,0 +1,48 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::{Stream, StreamExt, stream};
+
+use crate::application::services::live_event_service::{LiveEventService, LiveMessage};
+use crate::interfaces::middleware::auth::AuthUser;
+
+/// Server-sent event for a message.  Changes are named after their kind;
+/// `resync` asks the client to reload what it shows.
+fn to_event(message: LiveMessage) -> Event {
+    match message {
+        LiveMessage::Change(change) => Event::default()
+            .event(change.kind.as_str())
+            .json_data(&change)
+            .unwrap_or_else(|_| Event::default().event("resync").data("{}")),
+        LiveMessage::Resync => Event::default().event("resync").data("{}"),
+    }
+}
+
+/// Live changes to the current user's files, folders, shares and
+/// notifications, as server-sent events.
+///
+/// The stream opens with a `ready` event; changes made before it may have
+/// been missed, so clients reload once they see it.  Every other event is
+/// named after the change (`file_created`, `folder_moved`, `share_deleted`,
+/// ...) and carries it as JSON.
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    responses(
+        (status = 200, description = "Stream of changes", content_type = "text/event-stream", body = String),
+    ),
+    tag = "events"
+)]
+pub async fn stream_events(
+    State(service): State<Arc<LiveEventService>>,
+    auth_user: AuthUser,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let ready = Event::default().event("ready").data("{}");
+    let changes = service.subscribe(auth_user.id).map(to_event);
+    let events = stream::once(async move { ready }).chain(changes).map(Ok);
+    Sse::new(events).keep_alive(KeepAlive::new().interval(service.keepalive()))
+}
@@ -1,3 +1,4 @@
+pub mod activity_handler;
 pub mod admin_handler;
 pub mod app_password_handler;
 pub mod auth_handler;
@@ -10,15 +11,23 @@ pub mod dedup_handler;
 pub mod device_auth_handler;
 pub mod favorites_handler;
 pub mod file_handler;
+pub mod file_version_handler;
 pub mod folder_handler;
 pub mod i18n_handler;
+pub mod live_event_handler;
 pub mod music_handler;
+pub mod notification_handler;
 pub mod photos_handler;
 pub mod recent_handler;
 pub mod search_handler;
+pub mod session_handler;
 pub mod share_handler;
+pub mod snapshot_handler;
+pub mod tasks_handler;
 pub mod trash_handler;
+pub mod user_share_handler;
 pub mod webdav_handler;
+pub mod webhook_handler;
 pub mod wopi_handler;
 
 /// Tipo de resultado para controladores de API
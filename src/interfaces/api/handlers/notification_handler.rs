@@ -0,0 +1,107 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::application::dtos::notification_dto::NotificationQueryDto;
+use crate::application::services::notification_service::NotificationService;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::AuthUser;
+
+fn no_content(result: crate::common::errors::Result<()>) -> Response {
+    match result {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Latest notifications of the current user, newest first, with the
+/// number of unread ones
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    params(NotificationQueryDto),
+    responses(
+        (status = 200, description = "Notifications", body = crate::application::dtos::notification_dto::NotificationListDto),
+    ),
+    tag = "notifications"
+)]
+pub async fn list_notifications(
+    State(service): State<Arc<NotificationService>>,
+    auth_user: AuthUser,
+    Query(query): Query<NotificationQueryDto>,
+) -> Response {
+    match service.list(auth_user.id, query.limit).await {
+        Ok(list) => (StatusCode::OK, Json(list)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Mark all notifications of the current user as read
+#[utoipa::path(
+    post,
+    path = "/api/notifications/read",
+    responses((status = 204, description = "All notifications marked as read")),
+    tag = "notifications"
+)]
+pub async fn mark_all_read(
+    State(service): State<Arc<NotificationService>>,
+    auth_user: AuthUser,
+) -> Response {
+    no_content(service.mark_read(auth_user.id, None).await)
+}
+
+/// Mark one notification as read
+#[utoipa::path(
+    post,
+    path = "/api/notifications/{id}/read",
+    params(("id" = i64, Path, description = "Notification ID")),
+    responses(
+        (status = 204, description = "Notification marked as read"),
+        (status = 404, description = "Notification not found"),
+    ),
+    tag = "notifications"
+)]
+pub async fn mark_read(
+    State(service): State<Arc<NotificationService>>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    no_content(service.mark_read(auth_user.id, Some(id)).await)
+}
+
+/// Delete one notification
+#[utoipa::path(
+    delete,
+    path = "/api/notifications/{id}",
+    params(("id" = i64, Path, description = "Notification ID")),
+    responses(
+        (status = 204, description = "Notification deleted"),
+        (status = 404, description = "Notification not found"),
+    ),
+    tag = "notifications"
+)]
+pub async fn delete_notification(
+    State(service): State<Arc<NotificationService>>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    no_content(service.delete(auth_user.id, id).await)
+}
+
+/// Delete all notifications of the current user
+#[utoipa::path(
+    delete,
+    path = "/api/notifications",
+    responses((status = 204, description = "All notifications deleted")),
+    tag = "notifications"
+)]
+pub async fn delete_all_notifications(
+    State(service): State<Arc<NotificationService>>,
+    auth_user: AuthUser,
+) -> Response {
+    no_content(service.delete_all(auth_user.id).await)
+}
@@ -0,0 +1,87 @@
+//! HTTP handlers for the signed-in devices of the current user.
+//!
+//!   GET    /api/auth/sessions          — Active sessions (one per login)
+//!   GET    /api/auth/sessions/overview — Sessions, app passwords and devices
+//!   DELETE /api/auth/sessions/{id}     — Sign a session out
+//!
+//! All endpoints require JWT authentication.  The refresh cookie, when the
+//! browser sends it, marks the caller's own session.
+
+use crate::application::dtos::session_dto::{SessionDto, SessionOverviewDto};
+use crate::application::services::user_session_service::UserSessionService;
+use crate::common::di::AppState;
+use crate::interfaces::api::cookie_auth;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::AuthUser;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Protected routes — require JWT auth middleware.
+pub fn session_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/overview", get(session_overview))
+        .route("/sessions/{id}", delete(revoke_session))
+}
+
+fn session_service(state: &AppState) -> Result<&Arc<UserSessionService>, AppError> {
+    state
+        .user_session_service
+        .as_ref()
+        .ok_or_else(|| AppError::internal_error("Session service not configured"))
+}
+
+/// GET /api/auth/sessions — Active sessions of the current user, most
+/// recently used first.
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    headers: HeaderMap,
+) -> Result<Json<Vec<SessionDto>>, AppError> {
+    let current = cookie_auth::extract_cookie_value(&headers, cookie_auth::REFRESH_COOKIE);
+    let sessions = session_service(&state)?
+        .list_sessions(user.id, current.as_deref())
+        .await?;
+    Ok(Json(sessions))
+}
+
+/// GET /api/auth/sessions/overview — Everything with access to the
+/// account: sessions, app passwords and pending device authorizations.
+async fn session_overview(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    headers: HeaderMap,
+) -> Result<Json<SessionOverviewDto>, AppError> {
+    let current = cookie_auth::extract_cookie_value(&headers, cookie_auth::REFRESH_COOKIE);
+    let overview = session_service(&state)?
+        .overview(user.id, current.as_deref())
+        .await?;
+    Ok(Json(overview))
+}
+
+/// DELETE /api/auth/sessions/{id} — Revokes the session family `id`.
+/// Signing out the caller's own session also clears its cookies.
+async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    let family_id = Uuid::parse_str(&id).map_err(|_| AppError::bad_request("Invalid UUID"))?;
+    let current = cookie_auth::extract_cookie_value(&headers, cookie_auth::REFRESH_COOKIE);
+    let was_current = session_service(&state)?
+        .revoke_session(user.id, family_id, current.as_deref())
+        .await?;
+
+    let mut response = StatusCode::NO_CONTENT.into_response();
+    if was_current {
+        cookie_auth::append_clear_cookies(response.headers_mut());
+        cookie_auth::append_clear_csrf_cookie(response.headers_mut());
+    }
+    Ok(response)
+}
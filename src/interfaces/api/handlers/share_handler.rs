@@ -4,28 +4,37 @@ use uuid::Uuid;
 use axum::{
     Json,
     body::Body,
-    extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode, header},
+    extract::{Multipart, Path, Query, State},
+    http::{HeaderMap, Method, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use http_range_header::parse_range_header;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use utoipa::ToSchema;
 
+use crate::application::dtos::file_dto::FileDto;
 use crate::application::services::share_browse_service::ZipTarget;
 use crate::application::services::share_service::ShareService;
+use crate::application::services::share_upload_service::{
+    MAX_CHUNK_SIZE, ShareAccess, ShareChunkedUploadRequest, upload_file_name,
+};
 use crate::infrastructure::services::share_unlock_cookie;
+use crate::interfaces::api::handlers::chunked_upload_handler::ChunkUploadParams;
 use crate::interfaces::api::handlers::file_handler::build_content_disposition;
+use crate::interfaces::middleware::trusted_proxy::ClientInfo;
 use crate::{
     application::{
         dtos::share_dto::{CreateShareDto, UpdateShareDto},
         ports::{
             file_ports::{FileRetrievalUseCase, OptimizedFileContent},
-            share_ports::ShareUseCase,
+            share_ports::{ShareDownload, ShareUseCase},
         },
     },
-    common::{di::AppState, errors::ErrorKind},
+    common::{
+        di::AppState,
+        errors::{DomainError, ErrorKind},
+    },
     domain::entities::share::ShareItemType,
     interfaces::errors::AppError,
     interfaces::middleware::auth::AuthUser,
@@ -210,6 +219,49 @@ pub async fn delete_shared_link(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GetShareAccessesQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+/// List the downloads made through a shared link, newest first
+#[utoipa::path(
+    get,
+    path = "/api/shares/{id}/accesses",
+    params(
+        ("id" = String, Path, description = "Share ID"),
+        ("page" = Option<usize>, Query, description = "Page number (default 1)"),
+        ("per_page" = Option<usize>, Query, description = "Entries per page (default 50)")
+    ),
+    responses(
+        (status = 200, description = "Access log of the share", body = crate::application::dtos::pagination::PaginatedResponseDto<crate::application::dtos::share_dto::ShareAccessDto>),
+        (status = 404, description = "Share not found")
+    ),
+    tag = "shares"
+)]
+pub async fn get_shared_link_accesses(
+    State(share_use_case): State<Arc<ShareService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<GetShareAccessesQuery>,
+) -> impl IntoResponse {
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(_) => return AppError::bad_request("Invalid UUID").into_response(),
+    };
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 500);
+
+    match share_use_case
+        .get_shared_link_accesses(id, auth_user.id, page, per_page)
+        .await
+    {
+        Ok(accesses) => (StatusCode::OK, Json(accesses)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
 /// Access a shared item via its token
 #[utoipa::path(
     get,
@@ -327,6 +379,8 @@ pub async fn verify_shared_item_password(
 pub async fn download_shared_file(
     State(state): State<Arc<AppState>>,
     Path(token): Path<String>,
+    client: ClientInfo,
+    method: Method,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     // 1. Resolve share service
@@ -375,22 +429,79 @@ pub async fn download_shared_file(
     }
 
     // 4. Stream the file with full Range / 304 / 416 / 206 support.
+    let link = LinkDownload {
+        share_service: &share_service,
+        token: &token,
+        client,
+    };
     serve_share_file(
         &state,
         &share_dto.item_id,
         share_dto.item_name.as_deref(),
+        &method,
         &headers,
+        link,
     )
     .await
 }
 
+/// The share link a file is served through, so the download can be counted
+/// against the link's limit and logged for its owner.
+struct LinkDownload<'a> {
+    share_service: &'a ShareService,
+    token: &'a str,
+    client: ClientInfo,
+}
+
+impl LinkDownload<'_> {
+    async fn record(self, file_id: Option<&str>, file_name: &str) -> Result<(), DomainError> {
+        self.share_service
+            .record_shared_link_download(
+                self.token,
+                ShareDownload {
+                    ip_address: Some(self.client.ip),
+                    user_agent: self.client.user_agent,
+                    file_id: file_id.map(str::to_string),
+                    file_name: Some(file_name.to_string()),
+                },
+            )
+            .await
+    }
+}
+
+/// Returns whether a request for a file of `size` bytes starts a new
+/// download.  Only GETs send a body; ranges that skip the first byte resume
+/// or continue one that was already counted; unsatisfiable ranges are
+/// answered with a 416.
+fn starts_download(method: &Method, request_headers: &HeaderMap, size: u64) -> bool {
+    if method != Method::GET {
+        return false;
+    }
+    let Some(range_str) = request_headers
+        .get(header::RANGE)
+        .and_then(|h| h.to_str().ok())
+    else {
+        return true;
+    };
+    // An unparsable Range header is ignored and the whole file is sent
+    let Ok(ranges) = parse_range_header(range_str) else {
+        return true;
+    };
+    match ranges.validate(size) {
+        Ok(valid_ranges) => valid_ranges.first().is_none_or(|r| *r.start() == 0),
+        Err(_) => false,
+    }
+}
+
 /// Stream a file for a public share. Honours `If-None-Match` (304),
 /// `Range` (206 / 416), and falls back to a 200 via `get_file_optimized`.
 async fn serve_share_file(
     state: &Arc<AppState>,
     file_id: &str,
     name_override: Option<&str>,
+    method: &Method,
     request_headers: &HeaderMap,
+    link: LinkDownload<'_>,
 ) -> Response {
     let retrieval = &state.applications.file_retrieval_service;
 
@@ -421,6 +532,12 @@ async fn serve_share_file(
             .into_response();
     }
 
+    if starts_download(method, request_headers, file_dto.size)
+        && let Err(err) = link.record(Some(file_id), display_name).await
+    {
+        return share_browse_error_response(err);
+    }
+
     if let Some(range_hdr) = request_headers.get(header::RANGE)
         && let Ok(range_str) = range_hdr.to_str()
         && let Ok(ranges) = parse_range_header(range_str)
@@ -542,7 +659,7 @@ fn sharing_disabled_response() -> Response {
     .into_response()
 }
 
-fn share_browse_error_response(err: crate::common::errors::DomainError) -> Response {
+fn share_browse_error_response(err: DomainError) -> Response {
     if err.kind == ErrorKind::AccessDenied {
         if err.message.contains("password") {
             return (
@@ -647,9 +764,14 @@ pub async fn list_share_contents_subfolder(
 pub async fn download_share_file_in_folder(
     State(state): State<Arc<AppState>>,
     Path((token, file_id)): Path<(String, String)>,
+    client: ClientInfo,
+    method: Method,
     headers: HeaderMap,
 ) -> impl IntoResponse {
-    let Some(browse) = state.share_browse_service.clone() else {
+    let (Some(browse), Some(share_service)) = (
+        state.share_browse_service.clone(),
+        state.share_service.clone(),
+    ) else {
         return sharing_disabled_response();
     };
     let unlock_jwt = unlock_jwt_from_headers(&headers, &token);
@@ -661,7 +783,12 @@ pub async fn download_share_file_in_folder(
         return share_browse_error_response(err);
     }
 
-    serve_share_file(&state, &file_id, None, &headers).await
+    let link = LinkDownload {
+        share_service: &share_service,
+        token: &token,
+        client,
+    };
+    serve_share_file(&state, &file_id, None, &method, &headers, link).await
 }
 
 #[utoipa::path(
@@ -680,9 +807,11 @@ pub async fn download_share_file_in_folder(
 pub async fn download_share_zip_root(
     State(state): State<Arc<AppState>>,
     Path(token): Path<String>,
+    client: ClientInfo,
+    method: Method,
     headers: HeaderMap,
 ) -> impl IntoResponse {
-    serve_share_zip(state, token, None, headers).await
+    serve_share_zip(state, token, None, client, method, headers).await
 }
 
 #[utoipa::path(
@@ -704,18 +833,25 @@ pub async fn download_share_zip_root(
 pub async fn download_share_zip_subfolder(
     State(state): State<Arc<AppState>>,
     Path((token, folder_id)): Path<(String, String)>,
+    client: ClientInfo,
+    method: Method,
     headers: HeaderMap,
 ) -> impl IntoResponse {
-    serve_share_zip(state, token, Some(folder_id), headers).await
+    serve_share_zip(state, token, Some(folder_id), client, method, headers).await
 }
 
 async fn serve_share_zip(
     state: Arc<AppState>,
     token: String,
     folder_id: Option<String>,
+    client: ClientInfo,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    let Some(browse) = state.share_browse_service.clone() else {
+    let (Some(browse), Some(share_service)) = (
+        state.share_browse_service.clone(),
+        state.share_service.clone(),
+    ) else {
         return sharing_disabled_response();
     };
     let zip_service = match &state.core.zip_service {
@@ -739,6 +875,19 @@ async fn serve_share_zip(
         Err(err) => return share_browse_error_response(err),
     };
 
+    let archive_name = format!("{}.zip", target.display_name);
+    let link = LinkDownload {
+        share_service: &share_service,
+        token: &token,
+        client,
+    };
+    // HEAD probes and link previews receive no archive
+    if method == Method::GET
+        && let Err(err) = link.record(None, &archive_name).await
+    {
+        return share_browse_error_response(err);
+    }
+
     let temp_file = match zip_service
         .create_folder_zip(&target.folder_id, &target.display_name)
         .await
@@ -765,11 +914,7 @@ async fn serve_share_zip(
     let stream = ReaderStream::new(tokio_file);
     let body = Body::from_stream(stream);
 
-    let disposition = build_content_disposition(
-        &format!("{}.zip", target.display_name),
-        "application/zip",
-        false,
-    );
+    let disposition = build_content_disposition(&archive_name, "application/zip", false);
 
     let mut response = Response::builder()
         .status(StatusCode::OK)
@@ -785,3 +930,465 @@ async fn serve_share_zip(
     response.extensions_mut().insert(Arc::new(temp_path));
     response
 }
+
+// ── Public upload ("file drop") endpoints ─────────────────────────────────
+
+/// A file a visitor uploaded through a share link.  Only the final name is
+/// reported: visitors must not learn IDs or paths in the owner's storage.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareUploadResponse {
+    pub name: String,
+    pub size: u64,
+}
+
+impl From<FileDto> for ShareUploadResponse {
+    fn from(file: FileDto) -> Self {
+        Self {
+            name: file.name,
+            size: file.size,
+        }
+    }
+}
+
+/// Request body for starting a chunked upload through a share link
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateShareUploadRequest {
+    pub filename: String,
+    /// Subfolder of the shared folder; the shared folder itself when omitted
+    pub folder_id: Option<String>,
+    pub content_type: Option<String>,
+    pub total_size: u64,
+    pub chunk_size: Option<usize>,
+}
+
+fn share_uploads_disabled_response() -> Response {
+    AppError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Sharing is disabled",
+        "Disabled",
+    )
+    .into_response()
+}
+
+/// Spools a multipart field to `temp_path`, hashing it on the way, and
+/// returns its size and BLAKE3 hash.  Stops as soon as the field grows past
+/// `allowance` bytes, so an anonymous visitor cannot fill the disk beyond
+/// what the link and the owner's quota accept.
+async fn spool_share_upload(
+    mut field: axum::extract::multipart::Field<'_>,
+    temp_path: &std::path::Path,
+    allowance: Option<u64>,
+) -> Result<(u64, String), DomainError> {
+    let spool_error = |msg: String| DomainError::internal_error("ShareUpload", msg);
+    let file = tokio::fs::File::create(temp_path)
+        .await
+        .map_err(|e| spool_error(format!("Failed to create temp file: {}", e)))?;
+    let mut writer = tokio::io::BufWriter::with_capacity(524_288, file);
+    let mut hasher = blake3::Hasher::new();
+    let mut total_size: u64 = 0;
+    loop {
+        match field.chunk().await {
+            Ok(Some(chunk)) => {
+                total_size += chunk.len() as u64;
+                if let Some(limit) = allowance
+                    && total_size > limit
+                {
+                    return Err(DomainError::quota_exceeded(format!(
+                        "File exceeds the {} bytes this link can still accept",
+                        limit
+                    )));
+                }
+                hasher.update(&chunk);
+                tokio::io::AsyncWriteExt::write_all(&mut writer, &chunk)
+                    .await
+                    .map_err(|e| spool_error(format!("Failed to write chunk: {}", e)))?;
+            }
+            Ok(None) => break,
+            Err(e) => {
+                return Err(spool_error(format!(
+                    "Connection lost during upload (received {} bytes): {}",
+                    total_size, e
+                )));
+            }
+        }
+    }
+    tokio::io::AsyncWriteExt::flush(&mut writer)
+        .await
+        .map_err(|e| spool_error(format!("Failed to flush temp file: {}", e)))?;
+    Ok((total_size, hasher.finalize().to_hex().to_string()))
+}
+
+/// Upload files to a folder share with write permission
+///
+/// Multipart body: an optional `folder_id` field (a subfolder of the shared
+/// folder) followed by one or more `file` fields.  Names already taken in
+/// the folder get a numbered suffix.
+#[utoipa::path(
+    post,
+    path = "/api/s/{token}/upload",
+    params(("token" = String, Path, description = "Share token")),
+    request_body(content_type = "multipart/form-data", description = "Files to upload"),
+    responses(
+        (status = 201, description = "Files uploaded", body = Vec<ShareUploadResponse>),
+        (status = 400, description = "No file provided"),
+        (status = 401, description = "Password required"),
+        (status = 403, description = "The link does not accept uploads"),
+        (status = 404, description = "Subfolder not found or not in share scope"),
+        (status = 410, description = "Share expired"),
+        (status = 507, description = "Link upload limit or owner quota exceeded")
+    ),
+    tag = "shares"
+)]
+pub async fn upload_to_share(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let Some(uploads) = state.share_upload_service.clone() else {
+        return share_uploads_disabled_response();
+    };
+    let unlock_jwt = unlock_jwt_from_headers(&headers, &token);
+    let access = ShareAccess {
+        token: &token,
+        unlock_jwt: unlock_jwt.as_deref(),
+    };
+
+    let mut folder_id: Option<String> = None;
+    let mut uploaded: Vec<ShareUploadResponse> = Vec::new();
+
+    while let Some(field) = multipart.next_field().await.unwrap_or(None) {
+        match field.name().unwrap_or("") {
+            "folder_id" => {
+                let v = field.text().await.unwrap_or_default();
+                folder_id = (!v.is_empty()).then_some(v);
+            }
+            "file" => {
+                let filename = upload_file_name(field.file_name().unwrap_or("unnamed"));
+                let content_type = field
+                    .content_type()
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+
+                // Reject early, before spooling, when the size is announced;
+                // the spool enforces the limits on what actually arrives
+                let estimated_size = field
+                    .headers()
+                    .get(header::CONTENT_LENGTH)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .unwrap_or(0);
+                let allowance = match uploads
+                    .upload_allowance(access, folder_id.as_deref(), estimated_size)
+                    .await
+                {
+                    Ok(allowance) => allowance,
+                    Err(err) => return share_browse_error_response(err),
+                };
+
+                let temp_path = state
+                    .core
+                    .path_service
+                    .get_root_path()
+                    .join(".dedup_temp")
+                    .join(format!("share-upload-{}", Uuid::new_v4()));
+                let hash = match spool_share_upload(field, &temp_path, allowance).await {
+                    Ok((_, hash)) => hash,
+                    Err(err) => {
+                        let _ = tokio::fs::remove_file(&temp_path).await;
+                        tracing::warn!("❌ SHARE UPLOAD SPOOL FAILED: {} - {}", filename, err);
+                        return share_browse_error_response(err);
+                    }
+                };
+
+                let content_type = crate::common::mime_detect::refine_content_type_from_file(
+                    &temp_path,
+                    &filename,
+                    &content_type,
+                )
+                .await;
+
+                match uploads
+                    .upload_file(
+                        access,
+                        folder_id.as_deref(),
+                        &filename,
+                        content_type,
+                        &temp_path,
+                        hash,
+                    )
+                    .await
+                {
+                    Ok(file) => {
+                        tracing::info!(
+                            "✅ SHARE UPLOAD: {} ({} bytes) via link {}",
+                            file.name,
+                            file.size,
+                            token
+                        );
+                        uploaded.push(file.into());
+                    }
+                    Err(err) => {
+                        let _ = tokio::fs::remove_file(&temp_path).await;
+                        return share_browse_error_response(err);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if uploaded.is_empty() {
+        return AppError::bad_request("No file provided").into_response();
+    }
+    (StatusCode::CREATED, Json(uploaded)).into_response()
+}
+
+/// Start a chunked upload through a folder share with write permission
+#[utoipa::path(
+    post,
+    path = "/api/s/{token}/uploads",
+    params(("token" = String, Path, description = "Share token")),
+    request_body = CreateShareUploadRequest,
+    responses(
+        (status = 201, description = "Upload session created", body = crate::application::ports::chunked_upload_ports::CreateUploadResponseDto),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "The link does not accept uploads"),
+        (status = 507, description = "Link upload limit or owner quota exceeded")
+    ),
+    tag = "shares"
+)]
+pub async fn create_share_upload(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<CreateShareUploadRequest>,
+) -> impl IntoResponse {
+    let Some(uploads) = state.share_upload_service.clone() else {
+        return share_uploads_disabled_response();
+    };
+    let unlock_jwt = unlock_jwt_from_headers(&headers, &token);
+    let access = ShareAccess {
+        token: &token,
+        unlock_jwt: unlock_jwt.as_deref(),
+    };
+
+    let request = ShareChunkedUploadRequest {
+        filename: request.filename,
+        folder_id: request.folder_id,
+        content_type: request
+            .content_type
+            .unwrap_or_else(|| "application/octet-stream".to_string()),
+        total_size: request.total_size,
+        chunk_size: request.chunk_size,
+    };
+    match uploads.create_chunked_upload(access, request).await {
+        Ok(session) => (StatusCode::CREATED, Json(session)).into_response(),
+        Err(err) => share_browse_error_response(err),
+    }
+}
+
+/// Upload one chunk of a chunked upload started through a share link
+#[utoipa::path(
+    patch,
+    path = "/api/s/{token}/uploads/{upload_id}",
+    params(
+        ("token" = String, Path, description = "Share token"),
+        ("upload_id" = String, Path, description = "Upload session ID"),
+        ("chunk_index" = usize, Query, description = "Zero-based chunk index"),
+        ("checksum" = Option<String>, Query, description = "Optional MD5 checksum")
+    ),
+    request_body(content_type = "application/octet-stream", description = "Raw chunk bytes"),
+    responses(
+        (status = 200, description = "Chunk received", body = crate::application::ports::chunked_upload_ports::ChunkUploadResponseDto),
+        (status = 404, description = "Upload session not found for this link")
+    ),
+    tag = "shares"
+)]
+pub async fn upload_share_chunk(
+    State(state): State<Arc<AppState>>,
+    Path((token, upload_id)): Path<(String, String)>,
+    Query(params): Query<ChunkUploadParams>,
+    headers: HeaderMap,
+    body: Body,
+) -> impl IntoResponse {
+    let Some(uploads) = state.share_upload_service.clone() else {
+        return share_uploads_disabled_response();
+    };
+    let unlock_jwt = unlock_jwt_from_headers(&headers, &token);
+    let access = ShareAccess {
+        token: &token,
+        unlock_jwt: unlock_jwt.as_deref(),
+    };
+
+    let data = match axum::body::to_bytes(body, MAX_CHUNK_SIZE).await {
+        Ok(data) => data,
+        Err(_) => {
+            return AppError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Chunk exceeds the maximum chunk size",
+                "PayloadTooLarge",
+            )
+            .into_response();
+        }
+    };
+    let checksum = params.checksum.or_else(|| {
+        headers
+            .get("Content-MD5")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    });
+
+    match uploads
+        .upload_chunk(access, &upload_id, params.chunk_index, data, checksum)
+        .await
+    {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(err) => share_browse_error_response(err),
+    }
+}
+
+/// Get the status of a chunked upload started through a share link
+#[utoipa::path(
+    head,
+    path = "/api/s/{token}/uploads/{upload_id}",
+    params(
+        ("token" = String, Path, description = "Share token"),
+        ("upload_id" = String, Path, description = "Upload session ID")
+    ),
+    responses(
+        (status = 200, description = "Upload status", body = crate::application::ports::chunked_upload_ports::UploadStatusResponseDto),
+        (status = 404, description = "Upload session not found for this link")
+    ),
+    tag = "shares"
+)]
+pub async fn get_share_upload_status(
+    State(state): State<Arc<AppState>>,
+    Path((token, upload_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(uploads) = state.share_upload_service.clone() else {
+        return share_uploads_disabled_response();
+    };
+    let unlock_jwt = unlock_jwt_from_headers(&headers, &token);
+    let access = ShareAccess {
+        token: &token,
+        unlock_jwt: unlock_jwt.as_deref(),
+    };
+
+    match uploads.upload_status(access, &upload_id).await {
+        Ok(status) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("Upload-Offset", status.bytes_received.to_string())
+            .header("Upload-Length", status.total_size.to_string())
+            .body(Body::from(serde_json::to_string(&status).unwrap()))
+            .unwrap()
+            .into_response(),
+        Err(err) => share_browse_error_response(err),
+    }
+}
+
+/// Finish a chunked upload started through a share link
+#[utoipa::path(
+    post,
+    path = "/api/s/{token}/uploads/{upload_id}/complete",
+    params(
+        ("token" = String, Path, description = "Share token"),
+        ("upload_id" = String, Path, description = "Upload session ID")
+    ),
+    responses(
+        (status = 201, description = "File stored", body = ShareUploadResponse),
+        (status = 404, description = "Upload session not found for this link"),
+        (status = 507, description = "Owner quota exceeded")
+    ),
+    tag = "shares"
+)]
+pub async fn complete_share_upload(
+    State(state): State<Arc<AppState>>,
+    Path((token, upload_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(uploads) = state.share_upload_service.clone() else {
+        return share_uploads_disabled_response();
+    };
+    let unlock_jwt = unlock_jwt_from_headers(&headers, &token);
+    let access = ShareAccess {
+        token: &token,
+        unlock_jwt: unlock_jwt.as_deref(),
+    };
+
+    match uploads.complete_chunked_upload(access, &upload_id).await {
+        Ok(file) => {
+            tracing::info!(
+                "✅ SHARE CHUNKED UPLOAD: {} ({} bytes) via link {}",
+                file.name,
+                file.size,
+                token
+            );
+            (StatusCode::CREATED, Json(ShareUploadResponse::from(file))).into_response()
+        }
+        Err(err) => share_browse_error_response(err),
+    }
+}
+
+/// Cancel a chunked upload started through a share link
+#[utoipa::path(
+    delete,
+    path = "/api/s/{token}/uploads/{upload_id}",
+    params(
+        ("token" = String, Path, description = "Share token"),
+        ("upload_id" = String, Path, description = "Upload session ID")
+    ),
+    responses(
+        (status = 204, description = "Upload cancelled"),
+        (status = 404, description = "Upload session not found for this link")
+    ),
+    tag = "shares"
+)]
+pub async fn cancel_share_upload(
+    State(state): State<Arc<AppState>>,
+    Path((token, upload_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(uploads) = state.share_upload_service.clone() else {
+        return share_uploads_disabled_response();
+    };
+    let unlock_jwt = unlock_jwt_from_headers(&headers, &token);
+    let access = ShareAccess {
+        token: &token,
+        unlock_jwt: unlock_jwt.as_deref(),
+    };
+
+    match uploads.cancel_chunked_upload(access, &upload_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => share_browse_error_response(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn only_requests_from_the_first_byte_start_a_download() {
+        let get = Method::GET;
+        assert!(starts_download(&get, &HeaderMap::new(), 1000));
+        assert!(starts_download(&get, &range("bytes=0-99"), 1000));
+        assert!(starts_download(&get, &range("garbage"), 1000));
+        assert!(!starts_download(&get, &range("bytes=100-"), 1000));
+        assert!(!starts_download(&get, &range("bytes=5000-"), 1000));
+    }
+
+    #[test]
+    fn head_requests_do_not_start_a_download() {
+        assert!(!starts_download(&Method::HEAD, &HeaderMap::new(), 1000));
+        assert!(!starts_download(&Method::HEAD, &range("bytes=0-99"), 1000));
+    }
+}
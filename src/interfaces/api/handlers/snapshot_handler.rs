@@ -0,0 +1,301 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::application::dtos::snapshot_dto::{
+    CreateSnapshotDto, RestoreSnapshotDto, SetSnapshotScheduleDto,
+};
+use crate::application::ports::snapshot_ports::SnapshotUseCase;
+use crate::application::services::snapshot_service::SnapshotService;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::AuthUser;
+
+use super::file_handler::build_content_disposition;
+
+/// Query parameters selecting a path inside a snapshot
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct SnapshotPathQuery {
+    /// Path relative to the snapshot root (empty = root)
+    #[serde(default)]
+    pub path: String,
+}
+
+/// Take a snapshot of a folder tree
+#[utoipa::path(
+    post,
+    path = "/api/folders/{id}/snapshots",
+    params(("id" = String, Path, description = "Folder ID")),
+    request_body = CreateSnapshotDto,
+    responses(
+        (status = 201, description = "Snapshot created", body = crate::application::dtos::snapshot_dto::SnapshotDto),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Folder not found"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn create_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(dto): Json<CreateSnapshotDto>,
+) -> Response {
+    match service.create_snapshot(&id, auth_user.id, dto).await {
+        Ok(snapshot) => (StatusCode::CREATED, Json(snapshot)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// List the snapshots of a folder, newest first
+#[utoipa::path(
+    get,
+    path = "/api/folders/{id}/snapshots",
+    params(("id" = String, Path, description = "Folder ID")),
+    responses(
+        (status = 200, description = "List of snapshots", body = Vec<crate::application::dtos::snapshot_dto::SnapshotDto>),
+    ),
+    tag = "snapshots"
+)]
+pub async fn list_folder_snapshots(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match service.list_snapshots(auth_user.id, Some(&id)).await {
+        Ok(snapshots) => (StatusCode::OK, Json(snapshots)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// List all snapshots of the current user, newest first
+#[utoipa::path(
+    get,
+    path = "/api/snapshots",
+    responses(
+        (status = 200, description = "List of snapshots", body = Vec<crate::application::dtos::snapshot_dto::SnapshotDto>),
+    ),
+    tag = "snapshots"
+)]
+pub async fn list_snapshots(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+) -> Response {
+    match service.list_snapshots(auth_user.id, None).await {
+        Ok(snapshots) => (StatusCode::OK, Json(snapshots)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Get a snapshot
+#[utoipa::path(
+    get,
+    path = "/api/snapshots/{id}",
+    params(("id" = String, Path, description = "Snapshot ID")),
+    responses(
+        (status = 200, description = "Snapshot", body = crate::application::dtos::snapshot_dto::SnapshotDto),
+        (status = 404, description = "Snapshot not found"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn get_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match service.get_snapshot(&id, auth_user.id).await {
+        Ok(snapshot) => (StatusCode::OK, Json(snapshot)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// List the folders and files directly below a path of a snapshot
+#[utoipa::path(
+    get,
+    path = "/api/snapshots/{id}/browse",
+    params(("id" = String, Path, description = "Snapshot ID"), SnapshotPathQuery),
+    responses(
+        (status = 200, description = "Snapshot entries", body = Vec<crate::application::dtos::snapshot_dto::SnapshotEntryDto>),
+        (status = 400, description = "Path is not a folder"),
+        (status = 404, description = "Snapshot or path not found"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn browse_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<SnapshotPathQuery>,
+) -> Response {
+    match service.browse(&id, &query.path, auth_user.id).await {
+        Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Download a file from a snapshot
+#[utoipa::path(
+    get,
+    path = "/api/snapshots/{id}/download",
+    params(("id" = String, Path, description = "Snapshot ID"), SnapshotPathQuery),
+    responses(
+        (status = 200, description = "File content"),
+        (status = 400, description = "Path is not a file"),
+        (status = 404, description = "Snapshot or path not found"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn download_snapshot_file(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<SnapshotPathQuery>,
+) -> Response {
+    match service
+        .get_file_content(&id, &query.path, auth_user.id)
+        .await
+    {
+        Ok((entry, stream)) => {
+            let mime = entry
+                .mime_type
+                .as_deref()
+                .unwrap_or("application/octet-stream");
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, mime)
+                .header(
+                    header::CONTENT_DISPOSITION,
+                    build_content_disposition(&entry.name, mime, false),
+                )
+                .header(header::CONTENT_LENGTH, entry.size)
+                .header(header::CACHE_CONTROL, "private, max-age=3600, immutable")
+                .body(Body::from_stream(stream))
+                .unwrap()
+                .into_response()
+        }
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Restore all or part of a snapshot as a new folder or file
+#[utoipa::path(
+    post,
+    path = "/api/snapshots/{id}/restore",
+    params(("id" = String, Path, description = "Snapshot ID")),
+    request_body = RestoreSnapshotDto,
+    responses(
+        (status = 200, description = "Snapshot restored", body = crate::application::dtos::snapshot_dto::RestoreSnapshotResultDto),
+        (status = 404, description = "Snapshot, path or target folder not found"),
+        (status = 409, description = "An item with this name already exists in the target folder"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn restore_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    body: Option<Json<RestoreSnapshotDto>>,
+) -> Response {
+    let dto = body.map(|Json(d)| d).unwrap_or_default();
+    match service.restore(&id, auth_user.id, dto).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Delete a snapshot
+#[utoipa::path(
+    delete,
+    path = "/api/snapshots/{id}",
+    params(("id" = String, Path, description = "Snapshot ID")),
+    responses(
+        (status = 204, description = "Snapshot deleted"),
+        (status = 404, description = "Snapshot not found"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn delete_snapshot(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match service.delete_snapshot(&id, auth_user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Get the automatic snapshot schedule of a folder
+#[utoipa::path(
+    get,
+    path = "/api/folders/{id}/snapshot-schedule",
+    params(("id" = String, Path, description = "Folder ID")),
+    responses(
+        (status = 200, description = "Snapshot schedule", body = crate::application::dtos::snapshot_dto::SnapshotScheduleDto),
+        (status = 404, description = "No schedule for this folder"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn get_snapshot_schedule(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match service.get_schedule(&id, auth_user.id).await {
+        Ok(Some(schedule)) => (StatusCode::OK, Json(schedule)).into_response(),
+        Ok(None) => AppError::not_found("No snapshot schedule for this folder").into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Create or replace the automatic snapshot schedule of a folder
+#[utoipa::path(
+    put,
+    path = "/api/folders/{id}/snapshot-schedule",
+    params(("id" = String, Path, description = "Folder ID")),
+    request_body = SetSnapshotScheduleDto,
+    responses(
+        (status = 200, description = "Snapshot schedule saved", body = crate::application::dtos::snapshot_dto::SnapshotScheduleDto),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Folder not found"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn set_snapshot_schedule(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(dto): Json<SetSnapshotScheduleDto>,
+) -> Response {
+    match service.set_schedule(&id, auth_user.id, dto).await {
+        Ok(schedule) => (StatusCode::OK, Json(schedule)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Remove the automatic snapshot schedule of a folder
+#[utoipa::path(
+    delete,
+    path = "/api/folders/{id}/snapshot-schedule",
+    params(("id" = String, Path, description = "Folder ID")),
+    responses(
+        (status = 204, description = "Snapshot schedule removed"),
+        (status = 404, description = "No schedule for this folder"),
+    ),
+    tag = "snapshots"
+)]
+pub async fn delete_snapshot_schedule(
+    State(service): State<Arc<SnapshotService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match service.delete_schedule(&id, auth_user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
@@ -0,0 +1,323 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::error;
+use utoipa::ToSchema;
+
+use crate::application::dtos::calendar_dto::{CalendarEventDto, CreateTaskDto, UpdateTaskDto};
+use crate::application::ports::calendar_ports::CalendarUseCase;
+use crate::application::services::calendar_service::CalendarService;
+use crate::domain::errors::ErrorKind;
+use crate::interfaces::middleware::auth::AuthUser;
+
+/// A task (VTODO) in a calendar.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskResponse {
+    pub id: String,
+    pub calendar_id: String,
+    /// iCalendar UID; the CalDAV resource is `/caldav/{calendar_id}/{uid}.ics`
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub due_time: Option<DateTime<Utc>>,
+    /// NEEDS-ACTION, IN-PROCESS, COMPLETED or CANCELLED
+    pub status: Option<String>,
+    pub percent_complete: Option<i16>,
+    /// 1 (highest) to 9 (lowest), 0 when undefined
+    pub priority: Option<i16>,
+    /// UID of the parent task
+    pub related_to: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub rrule: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TaskResponse {
+    fn from_dto(event: CalendarEventDto) -> Self {
+        let task = event.task.unwrap_or_default();
+        Self {
+            id: event.id,
+            calendar_id: event.calendar_id,
+            uid: event.ical_uid,
+            summary: event.summary,
+            description: event.description,
+            start_time: task.start_time,
+            due_time: task.due_time,
+            status: task.status,
+            percent_complete: task.percent_complete,
+            priority: task.priority,
+            related_to: task.related_to,
+            completed_at: task.completed_at,
+            rrule: event.rrule,
+            created_at: event.created_at,
+            updated_at: event.updated_at,
+        }
+    }
+}
+
+/// Request body for creating a task.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTaskRequest {
+    pub summary: String,
+    pub description: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub due_time: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+    pub percent_complete: Option<i16>,
+    pub priority: Option<i16>,
+    pub related_to: Option<String>,
+}
+
+/// Request body for updating a task. Absent fields are left unchanged;
+/// an empty string clears `description` or `related_to`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTaskRequest {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub due_time: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+    pub percent_complete: Option<i16>,
+    pub priority: Option<i16>,
+    pub related_to: Option<String>,
+}
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+fn domain_err_to_response(err: crate::domain::errors::DomainError) -> Response {
+    let status = match err.kind {
+        ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        ErrorKind::AccessDenied => StatusCode::FORBIDDEN,
+        ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+fn task_not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": "Task not found" })),
+    )
+        .into_response()
+}
+
+/// Loads a task and checks that it lives in the calendar named in the path.
+async fn find_task(
+    service: &CalendarService,
+    calendar_id: &str,
+    task_id: &str,
+    auth_user: &AuthUser,
+) -> Result<CalendarEventDto, Response> {
+    match service.get_event(task_id, auth_user.id).await {
+        Ok(event) if event.is_task() && event.calendar_id == calendar_id => Ok(event),
+        Ok(_) => Err(task_not_found()),
+        Err(err) if err.kind == ErrorKind::NotFound => Err(task_not_found()),
+        Err(err) => {
+            error!("Error loading task {}: {}", task_id, err);
+            Err(domain_err_to_response(err))
+        }
+    }
+}
+
+// ── Tasks ────────────────────────────────────────────────────────────────────
+
+/// List the tasks in a calendar, ordered by due time (undated last),
+/// then priority.
+#[utoipa::path(
+    get,
+    path = "/api/calendars/{calendar_id}/tasks",
+    params(("calendar_id" = String, Path, description = "Calendar UUID")),
+    responses(
+        (status = 200, description = "List of tasks", body = Vec<TaskResponse>),
+        (status = 403, description = "Access denied"),
+        (status = 404, description = "Calendar not found"),
+    ),
+    tag = "tasks"
+)]
+pub async fn list_tasks(
+    State(service): State<Arc<CalendarService>>,
+    auth_user: AuthUser,
+    Path(calendar_id): Path<String>,
+) -> impl IntoResponse {
+    match service
+        .list_events(&calendar_id, None, None, auth_user.id)
+        .await
+    {
+        Ok(events) => {
+            let mut tasks: Vec<TaskResponse> = events
+                .into_iter()
+                .filter(CalendarEventDto::is_task)
+                .map(TaskResponse::from_dto)
+                .collect();
+            // Priority 0 means undefined and sorts after 9
+            tasks.sort_by_key(|t| {
+                (
+                    t.due_time.is_none(),
+                    t.due_time,
+                    t.priority.filter(|p| *p > 0).unwrap_or(10),
+                )
+            });
+            (StatusCode::OK, Json(tasks)).into_response()
+        }
+        Err(err) => {
+            error!("Error listing tasks in calendar {}: {}", calendar_id, err);
+            domain_err_to_response(err)
+        }
+    }
+}
+
+/// Create a task in a calendar.
+#[utoipa::path(
+    post,
+    path = "/api/calendars/{calendar_id}/tasks",
+    params(("calendar_id" = String, Path, description = "Calendar UUID")),
+    request_body = CreateTaskRequest,
+    responses(
+        (status = 201, description = "Task created", body = TaskResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 403, description = "Access denied"),
+        (status = 404, description = "Calendar not found"),
+    ),
+    tag = "tasks"
+)]
+pub async fn create_task(
+    State(service): State<Arc<CalendarService>>,
+    auth_user: AuthUser,
+    Path(calendar_id): Path<String>,
+    Json(body): Json<CreateTaskRequest>,
+) -> impl IntoResponse {
+    let dto = CreateTaskDto {
+        calendar_id: calendar_id.clone(),
+        summary: body.summary,
+        description: body.description,
+        start_time: body.start_time,
+        due_time: body.due_time,
+        status: body.status,
+        percent_complete: body.percent_complete,
+        priority: body.priority,
+        related_to: body.related_to,
+    };
+    match service.create_task(dto, auth_user.id).await {
+        Ok(task) => (StatusCode::CREATED, Json(TaskResponse::from_dto(task))).into_response(),
+        Err(err) => {
+            error!("Error creating task in calendar {}: {}", calendar_id, err);
+            domain_err_to_response(err)
+        }
+    }
+}
+
+/// Get a single task.
+#[utoipa::path(
+    get,
+    path = "/api/calendars/{calendar_id}/tasks/{task_id}",
+    params(
+        ("calendar_id" = String, Path, description = "Calendar UUID"),
+        ("task_id"     = String, Path, description = "Task UUID"),
+    ),
+    responses(
+        (status = 200, description = "Task details", body = TaskResponse),
+        (status = 403, description = "Access denied"),
+        (status = 404, description = "Task not found"),
+    ),
+    tag = "tasks"
+)]
+pub async fn get_task(
+    State(service): State<Arc<CalendarService>>,
+    auth_user: AuthUser,
+    Path((calendar_id, task_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match find_task(&service, &calendar_id, &task_id, &auth_user).await {
+        Ok(task) => (StatusCode::OK, Json(TaskResponse::from_dto(task))).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// Update a task. Setting `status` to COMPLETED records the completion time.
+#[utoipa::path(
+    put,
+    path = "/api/calendars/{calendar_id}/tasks/{task_id}",
+    params(
+        ("calendar_id" = String, Path, description = "Calendar UUID"),
+        ("task_id"     = String, Path, description = "Task UUID"),
+    ),
+    request_body = UpdateTaskRequest,
+    responses(
+        (status = 200, description = "Task updated", body = TaskResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 403, description = "Access denied"),
+        (status = 404, description = "Task not found"),
+    ),
+    tag = "tasks"
+)]
+pub async fn update_task(
+    State(service): State<Arc<CalendarService>>,
+    auth_user: AuthUser,
+    Path((calendar_id, task_id)): Path<(String, String)>,
+    Json(body): Json<UpdateTaskRequest>,
+) -> impl IntoResponse {
+    if let Err(response) = find_task(&service, &calendar_id, &task_id, &auth_user).await {
+        return response;
+    }
+    let dto = UpdateTaskDto {
+        summary: body.summary,
+        description: body.description,
+        start_time: body.start_time,
+        due_time: body.due_time,
+        status: body.status,
+        percent_complete: body.percent_complete,
+        priority: body.priority,
+        related_to: body.related_to,
+    };
+    match service.update_task(&task_id, dto, auth_user.id).await {
+        Ok(task) => (StatusCode::OK, Json(TaskResponse::from_dto(task))).into_response(),
+        Err(err) => {
+            error!("Error updating task {}: {}", task_id, err);
+            domain_err_to_response(err)
+        }
+    }
+}
+
+/// Delete a task.
+#[utoipa::path(
+    delete,
+    path = "/api/calendars/{calendar_id}/tasks/{task_id}",
+    params(
+        ("calendar_id" = String, Path, description = "Calendar UUID"),
+        ("task_id"     = String, Path, description = "Task UUID"),
+    ),
+    responses(
+        (status = 204, description = "Task deleted"),
+        (status = 403, description = "Access denied"),
+        (status = 404, description = "Task not found"),
+    ),
+    tag = "tasks"
+)]
+pub async fn delete_task(
+    State(service): State<Arc<CalendarService>>,
+    auth_user: AuthUser,
+    Path((calendar_id, task_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    if let Err(response) = find_task(&service, &calendar_id, &task_id, &auth_user).await {
+        return response;
+    }
+    match service.delete_event(&task_id, auth_user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => {
+            error!("Error deleting task {}: {}", task_id, err);
+            domain_err_to_response(err)
+        }
+    }
+}
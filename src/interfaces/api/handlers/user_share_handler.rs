@@ -0,0 +1,145 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::application::dtos::user_share_dto::{CreateUserShareDto, UpdateUserShareDto};
+use crate::application::ports::user_share_ports::UserShareUseCase;
+use crate::application::services::user_share_service::UserShareService;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::AuthUser;
+
+#[derive(Debug, Deserialize)]
+pub struct GetUserSharesQuery {
+    pub item_id: Option<String>,
+    pub item_type: Option<String>,
+}
+
+/// Share a file or folder with another user
+#[utoipa::path(
+    post,
+    path = "/api/user-shares",
+    request_body = CreateUserShareDto,
+    responses(
+        (status = 201, description = "Share created", body = crate::application::dtos::user_share_dto::UserShareDto),
+        (status = 400, description = "Bad request"),
+        (status = 403, description = "Not allowed to reshare the item"),
+        (status = 404, description = "Item or recipient not found"),
+        (status = 409, description = "Already shared with this user")
+    ),
+    tag = "user-shares"
+)]
+pub async fn create_user_share(
+    State(service): State<Arc<UserShareService>>,
+    auth_user: AuthUser,
+    Json(dto): Json<CreateUserShareDto>,
+) -> impl IntoResponse {
+    match service.share_with_user(auth_user.id, dto).await {
+        Ok(share) => (StatusCode::CREATED, Json(share)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// List the shares the authenticated user created or that cover their items
+#[utoipa::path(
+    get,
+    path = "/api/user-shares",
+    params(
+        ("item_id" = Option<String>, Query, description = "Only shares of this item"),
+        ("item_type" = Option<String>, Query, description = "Item type (file or folder), required with item_id")
+    ),
+    responses(
+        (status = 200, description = "Outgoing shares", body = Vec<crate::application::dtos::user_share_dto::UserShareDto>),
+        (status = 400, description = "Invalid item type")
+    ),
+    tag = "user-shares"
+)]
+pub async fn list_user_shares(
+    State(service): State<Arc<UserShareService>>,
+    auth_user: AuthUser,
+    Query(query): Query<GetUserSharesQuery>,
+) -> impl IntoResponse {
+    let item = match (&query.item_id, &query.item_type) {
+        (Some(item_id), Some(item_type)) => Some((item_id.as_str(), item_type.as_str())),
+        (None, None) => None,
+        _ => {
+            return AppError::bad_request("item_id and item_type must be given together")
+                .into_response();
+        }
+    };
+    match service.list_outgoing(auth_user.id, item).await {
+        Ok(shares) => (StatusCode::OK, Json(shares)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// List the files and folders other users shared with the authenticated user
+#[utoipa::path(
+    get,
+    path = "/api/user-shares/shared-with-me",
+    responses(
+        (status = 200, description = "Incoming shares", body = Vec<crate::application::dtos::user_share_dto::UserShareDto>)
+    ),
+    tag = "user-shares"
+)]
+pub async fn list_shared_with_me(
+    State(service): State<Arc<UserShareService>>,
+    auth_user: AuthUser,
+) -> impl IntoResponse {
+    match service.list_shared_with_me(auth_user.id).await {
+        Ok(shares) => (StatusCode::OK, Json(shares)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Change the permissions (owner) or the mount name (recipient) of a share
+#[utoipa::path(
+    put,
+    path = "/api/user-shares/{id}",
+    params(("id" = String, Path, description = "Share ID")),
+    request_body = UpdateUserShareDto,
+    responses(
+        (status = 200, description = "Share updated", body = crate::application::dtos::user_share_dto::UserShareDto),
+        (status = 400, description = "Invalid name"),
+        (status = 403, description = "Change not allowed"),
+        (status = 404, description = "Share not found")
+    ),
+    tag = "user-shares"
+)]
+pub async fn update_user_share(
+    State(service): State<Arc<UserShareService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateUserShareDto>,
+) -> impl IntoResponse {
+    match service.update_share(&id, auth_user.id, dto).await {
+        Ok(share) => (StatusCode::OK, Json(share)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Remove a share (owner, resharer or recipient)
+#[utoipa::path(
+    delete,
+    path = "/api/user-shares/{id}",
+    params(("id" = String, Path, description = "Share ID")),
+    responses(
+        (status = 204, description = "Share removed"),
+        (status = 404, description = "Share not found")
+    ),
+    tag = "user-shares"
+)]
+pub async fn delete_user_share(
+    State(service): State<Arc<UserShareService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match service.delete_share(&id, auth_user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
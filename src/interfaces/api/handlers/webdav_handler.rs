@@ -9,7 +9,7 @@
 use axum::{
     Router,
     body::{self, Body},
-    http::{HeaderName, Request, StatusCode, header},
+    http::{HeaderMap, HeaderName, Request, StatusCode, header},
     response::Response,
 };
 use bytes::{Buf, Bytes};
@@ -17,13 +17,18 @@ use chrono::Utc;
 use quick_xml::Writer;
 use uuid::Uuid;
 
-use crate::application::adapters::webdav_adapter::{LockInfo, PropFindRequest, WebDavAdapter};
+use crate::application::adapters::webdav_adapter::{
+    LockInfo, PropFindRequest, PropPatchStatus, PropValue, WebDavAdapter,
+};
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::dtos::folder_dto::FolderDto;
+use crate::application::ports::dead_property_ports::DeadPropertyUseCase;
 use crate::application::ports::file_ports::FileRetrievalUseCase;
 use crate::application::ports::file_ports::{FileManagementUseCase, FileUploadUseCase};
 use crate::application::ports::inbound::FolderUseCase;
 use crate::application::ports::storage_ports::StorageUsagePort;
+use crate::application::ports::user_share_ports::UserShareRepositoryPort;
+use crate::application::services::dead_property_service::DeadPropertyService;
 use crate::application::services::file_retrieval_service::FileRetrievalService;
 use crate::application::services::folder_service::FolderService;
 use crate::common::di::AppState;
@@ -32,6 +37,7 @@ use crate::infrastructure::services::path_resolver_service::ResolvedResource;
 use crate::interfaces::errors::AppError;
 use crate::interfaces::middleware::auth::{AuthUser, CurrentUser};
 use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Characters that MUST NOT be percent-encoded inside a URI path segment.
@@ -116,6 +122,224 @@ fn assert_owner(owner_id: Option<&str>, user_id: &str, path: &str) -> Result<(),
     }
 }
 
+/// The client's modification time for an upload (`X-OC-Mtime`, Unix
+/// seconds), as sent by Nextcloud/ownCloud sync clients.
+pub fn parse_oc_mtime(headers: &HeaderMap) -> Option<i64> {
+    headers
+        .get("x-oc-mtime")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|&t| t > 0)
+}
+
+/// Acknowledge a client-set modification time the way Nextcloud does, so
+/// sync clients don't follow up with a PROPPATCH.
+pub fn with_mtime_accepted(
+    builder: axum::http::response::Builder,
+    mtime: Option<i64>,
+) -> axum::http::response::Builder {
+    if mtime.is_some() {
+        builder.header("X-OC-MTime", "accepted")
+    } else {
+        builder
+    }
+}
+
+/// Resolve a path to the caller's folder or file.
+///
+/// User-scoped through the PathResolver when available, otherwise the
+/// legacy lookups with an ownership check.
+async fn resolve_resource(
+    state: &AppState,
+    path: &str,
+    user_id: Uuid,
+) -> Result<ResolvedResource, AppError> {
+    if let Some(resolver) = &state.path_resolver {
+        return resolver
+            .resolve_path_for_user(path, user_id)
+            .await
+            .map_err(|_| AppError::not_found(format!("Resource not found: {}", path)));
+    }
+    if let Ok(folder) = state
+        .applications
+        .folder_service
+        .get_folder_by_path(path)
+        .await
+    {
+        assert_owner(folder.owner_id.as_deref(), &user_id.to_string(), path)?;
+        return Ok(ResolvedResource::Folder(folder));
+    }
+    let file = state
+        .applications
+        .file_retrieval_service
+        .get_file_by_path(path)
+        .await
+        .map_err(|_| AppError::not_found(format!("Resource not found: {}", path)))?;
+    assert_owner(file.owner_id.as_deref(), &user_id.to_string(), path)?;
+    Ok(ResolvedResource::File(file))
+}
+
+/// Resolve a path inside an item another user shared with the caller,
+/// mounted in their home folder as `<home>/<mount name>/...`.
+///
+/// Shares are read-only over `/webdav`; Nextcloud DAV also writes into
+/// shares with write access.  Returns the resource (named after the mount
+/// when it is the mounted item itself) and its owner.
+async fn resolve_mounted(
+    state: &AppState,
+    path: &str,
+    user_id: Uuid,
+) -> Option<(ResolvedResource, Uuid)> {
+    let shares = state.repositories.user_share_repository.as_ref()?;
+    let (home_name, rest) = path.trim_matches('/').split_once('/')?;
+    let home = state
+        .applications
+        .folder_service
+        .list_folders_for_owner(None, user_id)
+        .await
+        .ok()?
+        .into_iter()
+        .find(|f| f.name == home_name)?;
+    let (mount_name, rest) = rest.split_once('/').unwrap_or((rest, ""));
+    let mount = shares.find_mount(user_id, mount_name).await.ok()??;
+    let owner_id = mount.access.owner_id;
+
+    let mut resource = if mount.is_folder {
+        let folder = state
+            .applications
+            .folder_service
+            .get_folder(&mount.item_id)
+            .await
+            .ok()?;
+        if rest.is_empty() {
+            ResolvedResource::Folder(folder)
+        } else {
+            resolve_resource(state, &format!("{}/{}", folder.path, rest), owner_id)
+                .await
+                .ok()?
+        }
+    } else if rest.is_empty() {
+        ResolvedResource::File(
+            state
+                .applications
+                .file_retrieval_service
+                .get_file(&mount.item_id)
+                .await
+                .ok()?,
+        )
+    } else {
+        return None;
+    };
+
+    if rest.is_empty() {
+        match &mut resource {
+            ResolvedResource::Folder(folder) => folder.name = mount.mount_name,
+            ResolvedResource::File(file) => file.name = mount.mount_name,
+        }
+    }
+    tracing::debug!(
+        "WebDAV: '{}' resolved in a share mounted in '{}'",
+        path,
+        home.path
+    );
+    Some((resource, owner_id))
+}
+
+/// Items other users shared with `user_id`, named as mounted in the home
+/// folder.
+async fn mounted_resources(state: &AppState, user_id: Uuid) -> Vec<ResolvedResource> {
+    let Some(shares) = state.repositories.user_share_repository.as_ref() else {
+        return Vec::new();
+    };
+    let mut resources = Vec::new();
+    for mount in shares.list_mounts(user_id).await.unwrap_or_default() {
+        if mount.is_folder {
+            if let Ok(mut folder) = state
+                .applications
+                .folder_service
+                .get_folder(&mount.item_id)
+                .await
+            {
+                folder.name = mount.mount_name;
+                resources.push(ResolvedResource::Folder(folder));
+            }
+        } else if let Ok(mut file) = state
+            .applications
+            .file_retrieval_service
+            .get_file(&mount.item_id)
+            .await
+        {
+            file.name = mount.mount_name;
+            resources.push(ResolvedResource::File(file));
+        }
+    }
+    resources
+}
+
+/// Resolve a path to the caller's own resource or, failing that, to one in
+/// a share mounted in their home folder.  Returns the resource's owner.
+async fn resolve_readable(
+    state: &AppState,
+    path: &str,
+    user_id: Uuid,
+) -> Result<(ResolvedResource, Uuid), AppError> {
+    match resolve_resource(state, path, user_id).await {
+        Ok(resource) => Ok((resource, user_id)),
+        Err(e) => resolve_mounted(state, path, user_id).await.ok_or(e),
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────
+// Dead properties
+// ────────────────────────────────────────────────────────────────────────
+
+/// Load the dead properties of a batch of resources, if the PROPFIND can
+/// return any.  Failures are logged and degrade to "no dead properties".
+async fn load_dead_props(
+    svc: Option<&Arc<DeadPropertyService>>,
+    request: &PropFindRequest,
+    resource_ids: &[String],
+) -> HashMap<String, Vec<PropValue>> {
+    let Some(svc) = svc.filter(|_| request.wants_dead_props() && !resource_ids.is_empty()) else {
+        return HashMap::new();
+    };
+    svc.get_properties_batch(resource_ids)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load WebDAV dead properties: {}", e);
+            HashMap::new()
+        })
+}
+
+/// Copy dead properties along with a COPY.  The copy itself has already
+/// succeeded, so failures are only logged.
+async fn copy_dead_props(state: &AppState, source_id: &str, target_id: &str, kind: DeadPropsCopy) {
+    let Some(svc) = &state.dead_property_service else {
+        return;
+    };
+    let result = match kind {
+        DeadPropsCopy::File => svc.copy_properties(source_id, target_id, false).await,
+        DeadPropsCopy::Folder => svc.copy_properties(source_id, target_id, true).await,
+        DeadPropsCopy::Tree => svc.copy_tree_properties(source_id, target_id).await,
+    };
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to copy WebDAV dead properties from {} to {}: {}",
+            source_id,
+            target_id,
+            e
+        );
+    }
+}
+
+/// What a COPY duplicated, for [`copy_dead_props`].
+#[derive(Clone, Copy)]
+enum DeadPropsCopy {
+    File,
+    Folder,
+    Tree,
+}
+
 /**
  * Creates and returns the WebDAV router with all required endpoints.
  *
@@ -374,58 +598,26 @@ async fn handle_propfind(
             propfind_request,
             folder_service,
             file_retrieval_service,
+            state.dead_property_service.clone(),
             user.id,
+            Vec::new(),
         )
         .await;
     }
 
-    // Single-query path resolution: folder OR file in one DB round-trip
-    if let Some(resolver) = &state.path_resolver {
-        match resolver.resolve_path_for_user(&path, user.id).await {
-            Ok(ResolvedResource::Folder(folder)) => {
-                let folder_id = folder.id.clone();
-                return build_streaming_propfind_response(
-                    folder,
-                    Some(folder_id),
-                    &depth_owned,
-                    &base_href,
-                    propfind_request,
-                    folder_service,
-                    file_retrieval_service,
-                    user.id,
-                )
-                .await;
-            }
-            Ok(ResolvedResource::File(file)) => {
-                let mut buf = Vec::with_capacity(1024);
-                {
-                    let mut xml_writer = Writer::new(&mut buf);
-                    WebDavAdapter::write_multistatus_start(&mut xml_writer)
-                        .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
-                    WebDavAdapter::write_file_entry(
-                        &mut xml_writer,
-                        &file,
-                        &propfind_request,
-                        &base_href,
-                    )
-                    .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
-                    WebDavAdapter::write_multistatus_end(&mut xml_writer)
-                        .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
-                }
-                return Ok(Response::builder()
-                    .status(StatusCode::MULTI_STATUS)
-                    .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-                    .body(Body::from(buf))
-                    .unwrap());
-            }
-            Err(_) => {}
-        }
-    } else {
-        // Fallback: legacy double-query path when PathResolver is unavailable
-        if let Ok(folder) = folder_service.get_folder_by_path(&path).await {
-            assert_owner(folder.owner_id.as_deref(), &user.id.to_string(), &path)?;
+    // Single-query path resolution (folder OR file in one DB round-trip),
+    // then shares mounted in the home folder
+    match resolve_readable(&state, &path, user.id).await? {
+        (ResolvedResource::Folder(folder), owner_id) => {
             let folder_id = folder.id.clone();
-            return build_streaming_propfind_response(
+            // The home folder also lists what other users shared
+            let mounted = if depth_owned == "1" && owner_id == user.id && folder.parent_id.is_none()
+            {
+                mounted_resources(&state, user.id).await
+            } else {
+                Vec::new()
+            };
+            build_streaming_propfind_response(
                 folder,
                 Some(folder_id),
                 &depth_owned,
@@ -433,12 +625,21 @@ async fn handle_propfind(
                 propfind_request,
                 folder_service,
                 file_retrieval_service,
-                user.id,
+                state.dead_property_service.clone(),
+                owner_id,
+                mounted,
             )
-            .await;
+            .await
         }
-        if let Ok(file) = file_retrieval_service.get_file_by_path(&path).await {
-            assert_owner(file.owner_id.as_deref(), &user.id.to_string(), &path)?;
+        (ResolvedResource::File(file), _) => {
+            let dead_props = load_dead_props(
+                state.dead_property_service.as_ref(),
+                &propfind_request,
+                std::slice::from_ref(&file.id),
+            )
+            .await
+            .remove(&file.id)
+            .unwrap_or_default();
             let mut buf = Vec::with_capacity(1024);
             {
                 let mut xml_writer = Writer::new(&mut buf);
@@ -449,20 +650,19 @@ async fn handle_propfind(
                     &file,
                     &propfind_request,
                     &base_href,
+                    &dead_props,
                 )
                 .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
                 WebDavAdapter::write_multistatus_end(&mut xml_writer)
                     .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
             }
-            return Ok(Response::builder()
+            Ok(Response::builder()
                 .status(StatusCode::MULTI_STATUS)
                 .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
                 .body(Body::from(buf))
-                .unwrap());
+                .unwrap())
         }
     }
-
-    Err(AppError::not_found(format!("Resource not found: {}", path)))
 }
 
 /// Builds a streaming 207 Multi-Status PROPFIND response.
@@ -471,6 +671,8 @@ async fn handle_propfind(
 /// (sub-folders and files) are fetched in batches of `PROPFIND_BATCH_SIZE`.
 /// Each batch is serialised to XML and sent as a chunk, so memory stays
 /// constant at O(batch_size) regardless of the total number of children.
+/// `mounted` items (shares in the home folder) follow the folder's own
+/// children.
 #[allow(clippy::too_many_arguments)]
 async fn build_streaming_propfind_response(
     folder: FolderDto,
@@ -480,7 +682,9 @@ async fn build_streaming_propfind_response(
     propfind_request: PropFindRequest,
     folder_service: std::sync::Arc<FolderService>,
     file_retrieval_service: std::sync::Arc<FileRetrievalService>,
+    dead_property_service: Option<Arc<DeadPropertyService>>,
     user_id: Uuid,
+    mounted: Vec<ResolvedResource>,
 ) -> Result<Response<Body>, AppError> {
     let depth = depth.to_string();
     let base_href = base_href.to_string();
@@ -488,12 +692,24 @@ async fn build_streaming_propfind_response(
 
     let stream = async_stream::try_stream! {
         // ── XML header + <D:multistatus> + folder entry ──────────
+        // (the virtual root has no folder_id and no dead properties)
+        let folder_props = match &folder_id {
+            Some(id) => load_dead_props(
+                dead_property_service.as_ref(),
+                &propfind_request,
+                std::slice::from_ref(id),
+            )
+            .await
+            .remove(id)
+            .unwrap_or_default(),
+            None => Vec::new(),
+        };
         let mut buf = Vec::with_capacity(4096);
         {
             let mut w = Writer::new(&mut buf);
             WebDavAdapter::write_multistatus_start(&mut w)
                 .map_err(|e| std::io::Error::other(e.to_string()))?;
-            WebDavAdapter::write_folder_entry(&mut w, &folder, &propfind_request, &base_href)
+            WebDavAdapter::write_folder_entry(&mut w, &folder, &propfind_request, &base_href, &folder_props)
                 .map_err(|e| std::io::Error::other(e.to_string()))?;
         }
         yield Bytes::from(buf);
@@ -522,12 +738,16 @@ async fn build_streaming_propfind_response(
                     break;
                 }
 
+                let ids: Vec<String> = result.items.iter().map(|f| f.id.clone()).collect();
+                let dead_props =
+                    load_dead_props(dead_property_service.as_ref(), &propfind_request, &ids).await;
                 let mut chunk = Vec::with_capacity(result.items.len() * 800);
                 {
                     let mut w = Writer::new(&mut chunk);
                     for subfolder in &result.items {
                         let href = format!("{}{}/", base_href, encode_path_segment(&subfolder.name));
-                        WebDavAdapter::write_folder_entry(&mut w, subfolder, &propfind_request, &href)
+                        let props = dead_props.get(&subfolder.id).map(Vec::as_slice).unwrap_or_default();
+                        WebDavAdapter::write_folder_entry(&mut w, subfolder, &propfind_request, &href, props)
                             .map_err(|e| std::io::Error::other(e.to_string()))?;
                     }
                 }
@@ -553,12 +773,16 @@ async fn build_streaming_propfind_response(
                 }
 
                 let batch_len = batch.len();
+                let ids: Vec<String> = batch.iter().map(|f| f.id.clone()).collect();
+                let dead_props =
+                    load_dead_props(dead_property_service.as_ref(), &propfind_request, &ids).await;
                 let mut chunk = Vec::with_capacity(batch_len * 800);
                 {
                     let mut w = Writer::new(&mut chunk);
                     for file in &batch {
                         let href = format!("{}{}", base_href, encode_path_segment(&file.name));
-                        WebDavAdapter::write_file_entry(&mut w, file, &propfind_request, &href)
+                        let props = dead_props.get(&file.id).map(Vec::as_slice).unwrap_or_default();
+                        WebDavAdapter::write_file_entry(&mut w, file, &propfind_request, &href, props)
                             .map_err(|e| std::io::Error::other(e.to_string()))?;
                     }
                 }
@@ -569,6 +793,29 @@ async fn build_streaming_propfind_response(
                 }
                 offset += batch_len as i64;
             }
+
+            // Shares mounted in the home folder
+            if !mounted.is_empty() {
+                let mut chunk = Vec::with_capacity(mounted.len() * 800);
+                {
+                    let mut w = Writer::new(&mut chunk);
+                    for resource in &mounted {
+                        match resource {
+                            ResolvedResource::Folder(subfolder) => {
+                                let href = format!("{}{}/", base_href, encode_path_segment(&subfolder.name));
+                                WebDavAdapter::write_folder_entry(&mut w, subfolder, &propfind_request, &href, &[])
+                                    .map_err(|e| std::io::Error::other(e.to_string()))?;
+                            }
+                            ResolvedResource::File(file) => {
+                                let href = format!("{}{}", base_href, encode_path_segment(&file.name));
+                                WebDavAdapter::write_file_entry(&mut w, file, &propfind_request, &href, &[])
+                                    .map_err(|e| std::io::Error::other(e.to_string()))?;
+                            }
+                        }
+                    }
+                }
+                yield Bytes::from(chunk);
+            }
         }
 
         // ── Close </D:multistatus> ───────────────────────────────
@@ -605,11 +852,11 @@ async fn build_streaming_propfind_response(
  * @return XML response with property modification results
  */
 async fn handle_proppatch(
-    _state: Arc<AppState>,
+    state: Arc<AppState>,
     req: Request<Body>,
     path: String,
 ) -> Result<Response<Body>, AppError> {
-    let _user = extract_user(&req)?;
+    let user = extract_user(&req)?;
 
     // Read request body (XML — bounded to 1 MB)
     let body_bytes = body::to_bytes(req.into_body(), MAX_XML_BODY)
@@ -617,24 +864,91 @@ async fn handle_proppatch(
         .map_err(|e| {
             AppError::payload_too_large(format!("PROPPATCH body too large or unreadable: {}", e))
         })?;
-    let (props_to_set, props_to_remove) = WebDavAdapter::parse_proppatch(body_bytes.reader())
-        .map_err(|e| AppError::bad_request(format!("Failed to parse PROPPATCH request: {}", e)))?;
+    let (mut props_to_set, props_to_remove) = WebDavAdapter::parse_proppatch(body_bytes.reader())
+        .map_err(|e| {
+        AppError::bad_request(format!("Failed to parse PROPPATCH request: {}", e))
+    })?;
 
-    // For now, we don't actually persist custom properties, but we respond as if we did
-    // In a full implementation, we would store these properties in a database
+    // The virtual root has nowhere to store properties.
+    let resource = if path.is_empty() || path == "/" {
+        None
+    } else {
+        Some(resolve_resource(&state, &path, user.id).await?)
+    };
 
-    // Generate response - we'll pretend all operations succeeded
+    // A client-reported modification time (getlastmodified or
+    // Win32LastModifiedTime) becomes the file's mtime.
+    let mtime = match &resource {
+        Some(ResolvedResource::File(_)) => props_to_set
+            .iter()
+            .rev()
+            .filter_map(PropValue::client_mtime)
+            .next(),
+        _ => None,
+    };
+    // DAV:getlastmodified is live — applied as the mtime, never stored.
     let mut results = Vec::new();
-
-    // For each property to set, indicate success
-    for prop in &props_to_set {
-        results.push((&prop.name, true));
+    props_to_set.retain(|p| {
+        let is_live = p.name.namespace == "DAV:" && p.name.name == "getlastmodified";
+        if is_live {
+            let status = match &resource {
+                Some(ResolvedResource::File(_)) if p.client_mtime().is_some() => {
+                    PropPatchStatus::Ok
+                }
+                Some(ResolvedResource::File(_)) => PropPatchStatus::Conflict,
+                _ => PropPatchStatus::Forbidden,
+            };
+            results.push((p.name.clone(), status));
+        }
+        !is_live
+    });
+
+    let svc = state.dead_property_service.as_ref();
+    match (&resource, svc) {
+        (Some(_), Some(svc)) => results.extend(svc.check_patch(&props_to_set, &props_to_remove)),
+        // Without a property store, acknowledge the patch without persisting
+        // it (clients like Windows Explorer abort copies on a failed PROPPATCH).
+        (resource, _) => {
+            let status = if resource.is_some() {
+                PropPatchStatus::Ok
+            } else {
+                PropPatchStatus::Forbidden
+            };
+            results.extend(
+                props_to_set
+                    .iter()
+                    .map(|p| p.name.clone())
+                    .chain(props_to_remove.iter().cloned())
+                    .map(|name| (name, status)),
+            );
+        }
     }
 
-    // For each property to remove, indicate success
-    for prop in &props_to_remove {
-        results.push((prop, true));
+    // PROPPATCH is atomic (RFC 4918 §9.2): apply nothing unless every
+    // instruction is valid.
+    let mut applied_mtime = None;
+    if PropPatchStatus::settle(&mut results) {
+        if let (Some(resource), Some(svc)) = (&resource, svc) {
+            let (resource_id, is_folder) = match resource {
+                ResolvedResource::Folder(folder) => (folder.id.as_str(), true),
+                ResolvedResource::File(file) => (file.id.as_str(), false),
+            };
+            svc.patch_properties(resource_id, is_folder, props_to_set, props_to_remove)
+                .await?;
+        }
+        if let (Some(ResolvedResource::File(file)), Some(mtime)) = (&resource, mtime) {
+            state
+                .applications
+                .file_management_service
+                .set_modified_time(&file.id, mtime)
+                .await?;
+            applied_mtime = Some(mtime);
+        }
     }
+    let results: Vec<_> = results
+        .iter()
+        .map(|(name, status)| (name, *status))
+        .collect();
 
     // Generate response
     let href = format!("/webdav/{}", encode_uri_path(&path));
@@ -643,9 +957,10 @@ async fn handle_proppatch(
         |e| AppError::internal_error(format!("Failed to generate PROPPATCH response: {}", e)),
     )?;
 
-    Ok(Response::builder()
+    let builder = Response::builder()
         .status(StatusCode::MULTI_STATUS)
-        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8");
+    Ok(with_mtime_accepted(builder, applied_mtime)
         .body(Body::from(response_body))
         .unwrap())
 }
@@ -675,25 +990,15 @@ async fn handle_get(
         return Err(AppError::bad_request("Cannot GET a directory"));
     }
 
-    // Resolve file — user-scoped when PathResolver is available
-    let file = if let Some(resolver) = &state.path_resolver {
-        match resolver.resolve_path_for_user(&path, user.id).await {
-            Ok(ResolvedResource::File(f)) => f,
-            Ok(ResolvedResource::Folder(_)) => {
-                return Err(AppError::bad_request("Cannot GET a directory"));
-            }
-            Err(_) => {
-                return Err(AppError::not_found(format!("File not found: {}", path)));
-            }
+    // Resolve file — user-scoped, or inside a share mounted in the home folder
+    let file = match resolve_readable(&state, &path, user.id).await {
+        Ok((ResolvedResource::File(f), _)) => f,
+        Ok((ResolvedResource::Folder(_), _)) => {
+            return Err(AppError::bad_request("Cannot GET a directory"));
+        }
+        Err(_) => {
+            return Err(AppError::not_found(format!("File not found: {}", path)));
         }
-    } else {
-        // Legacy fallback — fetch + ownership check
-        let f = file_retrieval_service
-            .get_file_by_path(&path)
-            .await
-            .map_err(|_e| AppError::not_found(format!("File not found: {}", path)))?;
-        assert_owner(f.owner_id.as_deref(), &user.id.to_string(), &path)?;
-        f
     };
 
     // Stream file content — constant ~64 KB memory regardless of file size
@@ -727,8 +1032,6 @@ async fn handle_head(
     path: String,
 ) -> Result<Response<Body>, AppError> {
     let user = extract_user(&req)?;
-    let file_retrieval_service = &state.applications.file_retrieval_service;
-    let folder_service = &state.applications.folder_service;
 
     if path.is_empty() || path == "/" {
         // Root folder — return collection headers
@@ -740,69 +1043,31 @@ async fn handle_head(
             .unwrap());
     }
 
-    // Single-query path resolution (user-scoped)
-    if let Some(resolver) = &state.path_resolver {
-        match resolver.resolve_path_for_user(&path, user.id).await {
-            Ok(ResolvedResource::Folder(folder)) => {
-                return Ok(Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, "httpd/unix-directory")
-                    .header(header::CONTENT_LENGTH, 0)
-                    .header(header::ETAG, format!("\"{}\"", folder.id))
-                    .body(Body::empty())
-                    .unwrap());
-            }
-            Ok(ResolvedResource::File(file)) => {
-                return Ok(Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, &*file.mime_type)
-                    .header(header::CONTENT_LENGTH, file.size)
-                    .header(header::ETAG, format!("\"{}\"", file.id))
-                    .header(
-                        header::LAST_MODIFIED,
-                        chrono::DateTime::<Utc>::from_timestamp(file.created_at as i64, 0)
-                            .unwrap_or_else(Utc::now)
-                            .to_rfc2822(),
-                    )
-                    .body(Body::empty())
-                    .unwrap());
-            }
-            Err(_) => return Err(AppError::not_found(format!("Resource not found: {}", path))),
-        }
-    }
-
-    // Fallback: legacy double-query path (with ownership check)
-    if let Ok(folder) = folder_service.get_folder_by_path(&path).await {
-        assert_owner(folder.owner_id.as_deref(), &user.id.to_string(), &path)?;
-        return Ok(Response::builder()
+    // Single-query path resolution (user-scoped), then mounted shares
+    match resolve_readable(&state, &path, user.id).await {
+        Ok((ResolvedResource::Folder(folder), _)) => Ok(Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, "httpd/unix-directory")
             .header(header::CONTENT_LENGTH, 0)
             .header(header::ETAG, format!("\"{}\"", folder.id))
             .body(Body::empty())
-            .unwrap());
+            .unwrap()),
+        // Metadata only — never load content for HEAD
+        Ok((ResolvedResource::File(file), _)) => Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, &*file.mime_type)
+            .header(header::CONTENT_LENGTH, file.size)
+            .header(header::ETAG, format!("\"{}\"", file.id))
+            .header(
+                header::LAST_MODIFIED,
+                chrono::DateTime::<Utc>::from_timestamp(file.created_at as i64, 0)
+                    .unwrap_or_else(Utc::now)
+                    .to_rfc2822(),
+            )
+            .body(Body::empty())
+            .unwrap()),
+        Err(_) => Err(AppError::not_found(format!("Resource not found: {}", path))),
     }
-
-    // Try as file — use metadata only, never load content for HEAD
-    let file = file_retrieval_service
-        .get_file_by_path(&path)
-        .await
-        .map_err(|_e| AppError::not_found(format!("Resource not found: {}", path)))?;
-    assert_owner(file.owner_id.as_deref(), &user.id.to_string(), &path)?;
-
-    Ok(Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, &*file.mime_type)
-        .header(header::CONTENT_LENGTH, file.size)
-        .header(header::ETAG, format!("\"{}\"", file.id))
-        .header(
-            header::LAST_MODIFIED,
-            chrono::DateTime::<Utc>::from_timestamp(file.created_at as i64, 0)
-                .unwrap_or_else(Utc::now)
-                .to_rfc2822(),
-        )
-        .body(Body::empty())
-        .unwrap())
 }
 
 /**
@@ -881,6 +1146,7 @@ async fn handle_put(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("application/octet-stream")
         .to_string();
+    let client_mtime = parse_oc_mtime(req.headers());
 
     // ── Streaming spool: body → temp file + incremental hash ──
     let temp_file = tempfile::NamedTempFile::new()
@@ -950,7 +1216,7 @@ async fn handle_put(
             total_bytes as u64,
             &content_type,
             Some(hash),
-            None,
+            client_mtime,
         )
         .await;
 
@@ -972,10 +1238,12 @@ async fn handle_put(
                 );
             }
 
-            Ok(Response::builder()
-                .status(StatusCode::NO_CONTENT)
-                .body(Body::empty())
-                .unwrap())
+            Ok(with_mtime_accepted(
+                Response::builder().status(StatusCode::NO_CONTENT),
+                client_mtime,
+            )
+            .body(Body::empty())
+            .unwrap())
         }
         Err(e) => Err(AppError::internal_error(format!(
             "Failed to put file: {}",
@@ -1535,7 +1803,7 @@ async fn handle_copy(
 
                 if recursive {
                     let file_management_service = &state.applications.file_management_service;
-                    file_management_service
+                    let result = file_management_service
                         .copy_folder_tree(
                             &folder.id,
                             target_parent_id,
@@ -1545,20 +1813,30 @@ async fn handle_copy(
                         .map_err(|e| {
                             AppError::internal_error(format!("Failed to copy folder tree: {}", e))
                         })?;
+                    copy_dead_props(
+                        &state,
+                        &folder.id,
+                        &result.new_root_folder_id,
+                        DeadPropsCopy::Tree,
+                    )
+                    .await;
                 } else {
                     let create_dto = crate::application::dtos::folder_dto::CreateFolderDto {
                         name: dest_folder_name.to_string(),
                         parent_id: target_parent_id,
                     };
-                    folder_service
-                        .create_folder(create_dto)
-                        .await
-                        .map_err(|e| {
-                            AppError::internal_error(format!(
-                                "Failed to create destination folder: {}",
-                                e
-                            ))
-                        })?;
+                    let new_folder =
+                        folder_service
+                            .create_folder(create_dto)
+                            .await
+                            .map_err(|e| {
+                                AppError::internal_error(format!(
+                                    "Failed to create destination folder: {}",
+                                    e
+                                ))
+                            })?;
+                    copy_dead_props(&state, &folder.id, &new_folder.id, DeadPropsCopy::Folder)
+                        .await;
                 }
             }
             Ok(ResolvedResource::File(file)) => {
@@ -1586,10 +1864,11 @@ async fn handle_copy(
                 };
 
                 let file_management_service = &state.applications.file_management_service;
-                file_management_service
+                let new_file = file_management_service
                     .copy_file(&file.id, target_folder_id)
                     .await
                     .map_err(|e| AppError::internal_error(format!("Failed to copy file: {}", e)))?;
+                copy_dead_props(&state, &file.id, &new_file.id, DeadPropsCopy::File).await;
             }
             Err(_) => {
                 return Err(AppError::not_found(format!(
@@ -1639,7 +1918,7 @@ async fn handle_copy(
 
             if recursive {
                 let file_management_service = &state.applications.file_management_service;
-                file_management_service
+                let result = file_management_service
                     .copy_folder_tree(
                         &folder.id,
                         target_parent_id,
@@ -1649,12 +1928,19 @@ async fn handle_copy(
                     .map_err(|e| {
                         AppError::internal_error(format!("Failed to copy folder tree: {}", e))
                     })?;
+                copy_dead_props(
+                    &state,
+                    &folder.id,
+                    &result.new_root_folder_id,
+                    DeadPropsCopy::Tree,
+                )
+                .await;
             } else {
                 let create_dto = crate::application::dtos::folder_dto::CreateFolderDto {
                     name: dest_folder_name.to_string(),
                     parent_id: target_parent_id,
                 };
-                folder_service
+                let new_folder = folder_service
                     .create_folder(create_dto)
                     .await
                     .map_err(|e| {
@@ -1663,6 +1949,7 @@ async fn handle_copy(
                             e
                         ))
                     })?;
+                copy_dead_props(&state, &folder.id, &new_folder.id, DeadPropsCopy::Folder).await;
             }
         } else {
             let file = file_retrieval_service
@@ -1697,10 +1984,11 @@ async fn handle_copy(
             };
 
             let file_management_service = &state.applications.file_management_service;
-            file_management_service
+            let new_file = file_management_service
                 .copy_file(&file.id, target_folder_id)
                 .await
                 .map_err(|e| AppError::internal_error(format!("Failed to copy file: {}", e)))?;
+            copy_dead_props(&state, &file.id, &new_file.id, DeadPropsCopy::File).await;
         }
     }
 
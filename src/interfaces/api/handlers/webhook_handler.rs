@@ -0,0 +1,154 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::application::dtos::webhook_dto::{
+    CreateWebhookDto, UpdateWebhookDto, WebhookDeliveryQueryDto,
+};
+use crate::application::services::webhook_service::WebhookService;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::AuthUser;
+
+fn json<T: serde::Serialize>(
+    status: StatusCode,
+    result: crate::common::errors::Result<T>,
+) -> Response {
+    match result {
+        Ok(value) => (status, Json(value)).into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Webhooks of the current user
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    responses(
+        (status = 200, description = "Webhooks", body = Vec<crate::application::dtos::webhook_dto::WebhookDto>),
+    ),
+    tag = "webhooks"
+)]
+pub async fn list_webhooks(
+    State(service): State<Arc<WebhookService>>,
+    auth_user: AuthUser,
+) -> Response {
+    json(StatusCode::OK, service.list(Some(auth_user.id)).await)
+}
+
+/// Register a webhook for events about the current user's files and shares.
+/// The response carries the key payloads are signed with; it is not shown again.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    request_body = CreateWebhookDto,
+    responses(
+        (status = 201, description = "Webhook created", body = crate::application::dtos::webhook_dto::WebhookDto),
+        (status = 400, description = "Invalid URL or filter"),
+    ),
+    tag = "webhooks"
+)]
+pub async fn create_webhook(
+    State(service): State<Arc<WebhookService>>,
+    auth_user: AuthUser,
+    Json(dto): Json<CreateWebhookDto>,
+) -> Response {
+    json(
+        StatusCode::CREATED,
+        service.create(Some(auth_user.id), dto).await,
+    )
+}
+
+/// Change, pause or rotate the signing key of a webhook
+#[utoipa::path(
+    patch,
+    path = "/api/webhooks/{id}",
+    params(("id" = String, Path, description = "Webhook ID")),
+    request_body = UpdateWebhookDto,
+    responses(
+        (status = 200, description = "Webhook updated", body = crate::application::dtos::webhook_dto::WebhookDto),
+        (status = 400, description = "Invalid URL or filter"),
+        (status = 404, description = "Webhook not found"),
+    ),
+    tag = "webhooks"
+)]
+pub async fn update_webhook(
+    State(service): State<Arc<WebhookService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateWebhookDto>,
+) -> Response {
+    json(
+        StatusCode::OK,
+        service.update(Some(auth_user.id), &id, dto).await,
+    )
+}
+
+/// Delete a webhook and its delivery log
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 404, description = "Webhook not found"),
+    ),
+    tag = "webhooks"
+)]
+pub async fn delete_webhook(
+    State(service): State<Arc<WebhookService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match service.delete(Some(auth_user.id), &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => AppError::from(err).into_response(),
+    }
+}
+
+/// Latest deliveries to a webhook, newest first
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{id}/deliveries",
+    params(("id" = String, Path, description = "Webhook ID"), WebhookDeliveryQueryDto),
+    responses(
+        (status = 200, description = "Deliveries", body = Vec<crate::application::dtos::webhook_dto::WebhookDeliveryDto>),
+        (status = 404, description = "Webhook not found"),
+    ),
+    tag = "webhooks"
+)]
+pub async fn list_deliveries(
+    State(service): State<Arc<WebhookService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<WebhookDeliveryQueryDto>,
+) -> Response {
+    json(
+        StatusCode::OK,
+        service
+            .deliveries(Some(auth_user.id), &id, query.limit)
+            .await,
+    )
+}
+
+/// Send a test event to a webhook and report how the receiver answered
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{id}/ping",
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, description = "Outcome of the test delivery", body = crate::application::dtos::webhook_dto::WebhookDeliveryDto),
+        (status = 404, description = "Webhook not found"),
+    ),
+    tag = "webhooks"
+)]
+pub async fn ping_webhook(
+    State(service): State<Arc<WebhookService>>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    json(StatusCode::OK, service.ping(Some(auth_user.id), &id).await)
+}
@@ -81,6 +81,10 @@ async fn check_file_info(
         return StatusCode::UNAUTHORIZED.into_response();
     }
 
+    if is_encrypted(&state, &file_id).await {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
     // Fetch file metadata
     let file = match state
         .app_state
@@ -387,6 +391,15 @@ pub struct EditorUrlResponse {
     pub access_token_ttl: i64,
 }
 
+/// Whether a file lies in an end-to-end encrypted folder.  Its contents are
+/// ciphertext to the server, so no editor session is opened for it.
+async fn is_encrypted(state: &WopiState, file_id: &str) -> bool {
+    match state.app_state.e2ee_service.as_ref() {
+        Some(e2ee) => e2ee.is_encrypted(file_id).await.unwrap_or(false),
+        None => false,
+    }
+}
+
 /// Determines if `caller_id` can access `file_id` and with what permissions.
 ///
 /// Uses the SQL-level ownership check (`get_file_owned`) so that files
@@ -432,6 +445,13 @@ pub async fn get_editor_url(
         Ok(result) => result,
         Err(status) => return status.into_response(),
     };
+    if is_encrypted(&state, &params.file_id).await {
+        return (
+            StatusCode::FORBIDDEN,
+            "Files in end-to-end encrypted folders cannot be opened in an editor",
+        )
+            .into_response();
+    }
 
     // Extract extension from filename
     let extension = file.name.rsplit('.').next().unwrap_or("").to_lowercase();
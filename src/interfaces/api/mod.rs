@@ -9,6 +9,7 @@ pub use routes::create_public_api_routes;
 
 use utoipa::OpenApi;
 
+use crate::application::dtos::activity_dto::ActivityDto;
 use crate::application::dtos::contact_dto::{
     AddressDto, ContactDto, ContactGroupDto, EmailDto, PhoneDto,
 };
@@ -16,13 +17,18 @@ use crate::application::dtos::favorites_dto::{
     BatchFavoritesResult, BatchFavoritesStats, FavoriteItemDto,
 };
 use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::file_version_dto::FileVersionDto;
 use crate::application::dtos::folder_dto::{
     CreateFolderDto, FolderDto, MoveFolderDto, RenameFolderDto,
 };
 use crate::application::dtos::folder_listing_dto::FolderListingDto;
+use crate::application::dtos::group_dto::{
+    AddGroupMemberDto, CreateGroupDto, GroupDto, GroupMemberDto, UpdateGroupDto,
+};
 use crate::application::dtos::i18n_dto::{
     LocaleDto, TranslationErrorDto, TranslationRequestDto, TranslationResponseDto,
 };
+use crate::application::dtos::notification_dto::{NotificationDto, NotificationListDto};
 use crate::application::dtos::pagination::{PaginationDto, PaginationRequestDto};
 use crate::application::dtos::recent_dto::RecentItemDto;
 use crate::application::dtos::search_dto::{
@@ -30,15 +36,29 @@ use crate::application::dtos::search_dto::{
     SearchSuggestionItem, SearchSuggestionsDto,
 };
 use crate::application::dtos::share_dto::{
-    CreateShareDto, ShareDto, SharePermissionsDto, UpdateShareDto,
+    CreateShareDto, ShareAccessDto, ShareDto, SharePermissionsDto, UpdateShareDto,
+};
+use crate::application::dtos::snapshot_dto::{
+    CreateSnapshotDto, RestoreSnapshotDto, RestoreSnapshotResultDto, SetSnapshotScheduleDto,
+    SnapshotDto, SnapshotEntryDto, SnapshotScheduleDto,
 };
 use crate::application::dtos::trash_dto::{
     DeletePermanentlyRequest, MoveToTrashRequest, RestoreFromTrashRequest, TrashedItemDto,
 };
+use crate::application::dtos::two_factor_dto::{
+    RecoveryCodesDto, TotpSetupDto, TwoFactorChallengeDto, TwoFactorCodeDto, TwoFactorLoginDto,
+    TwoFactorLoginResponseDto, TwoFactorPolicyDto, TwoFactorStatusDto,
+};
 use crate::application::dtos::user_dto::{
     AuthResponseDto, ChangePasswordDto, LoginDto, RefreshTokenDto, RegisterDto, SetupAdminDto,
     UserDto,
 };
+use crate::application::dtos::user_share_dto::{
+    CreateUserShareDto, UpdateUserShareDto, UserShareDto,
+};
+use crate::application::dtos::webhook_dto::{
+    CreateWebhookDto, UpdateWebhookDto, WebhookDeliveryDto, WebhookDto,
+};
 use crate::application::ports::chunked_upload_ports::{
     ChunkUploadResponseDto, CreateUploadResponseDto, UploadStatusResponseDto,
 };
@@ -53,6 +73,12 @@ use crate::interfaces::api::handlers::dedup_handler::{
     DedupUploadResponse, HashCheckResponse, StatsResponse,
 };
 use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
+use crate::interfaces::api::handlers::share_handler::{
+    CreateShareUploadRequest, ShareUploadResponse,
+};
+use crate::interfaces::api::handlers::tasks_handler::{
+    CreateTaskRequest, TaskResponse, UpdateTaskRequest,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -114,6 +140,7 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
         handlers::share_handler::get_user_shares,
         handlers::share_handler::update_shared_link,
         handlers::share_handler::delete_shared_link,
+        handlers::share_handler::get_shared_link_accesses,
         handlers::share_handler::access_shared_item,
         handlers::share_handler::verify_shared_item_password,
         handlers::share_handler::download_shared_file,
@@ -122,6 +149,52 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
         handlers::share_handler::download_share_file_in_folder,
         handlers::share_handler::download_share_zip_root,
         handlers::share_handler::download_share_zip_subfolder,
+        handlers::share_handler::upload_to_share,
+        handlers::share_handler::create_share_upload,
+        handlers::share_handler::upload_share_chunk,
+        handlers::share_handler::get_share_upload_status,
+        handlers::share_handler::complete_share_upload,
+        handlers::share_handler::cancel_share_upload,
+        // User share handlers (free functions)
+        handlers::user_share_handler::create_user_share,
+        handlers::user_share_handler::list_user_shares,
+        handlers::user_share_handler::list_shared_with_me,
+        handlers::user_share_handler::update_user_share,
+        handlers::user_share_handler::delete_user_share,
+        // File version handlers (free functions)
+        handlers::file_version_handler::list_versions,
+        handlers::file_version_handler::download_version,
+        handlers::file_version_handler::restore_version,
+        handlers::file_version_handler::delete_version,
+        // Folder snapshot handlers (free functions)
+        handlers::snapshot_handler::create_snapshot,
+        handlers::snapshot_handler::list_folder_snapshots,
+        handlers::snapshot_handler::list_snapshots,
+        handlers::snapshot_handler::get_snapshot,
+        handlers::snapshot_handler::browse_snapshot,
+        handlers::snapshot_handler::download_snapshot_file,
+        handlers::snapshot_handler::restore_snapshot,
+        handlers::snapshot_handler::delete_snapshot,
+        handlers::snapshot_handler::get_snapshot_schedule,
+        handlers::snapshot_handler::set_snapshot_schedule,
+        handlers::snapshot_handler::delete_snapshot_schedule,
+        // Activity feed (free function)
+        handlers::activity_handler::list_activity,
+        // Notifications (free functions)
+        handlers::notification_handler::list_notifications,
+        handlers::notification_handler::mark_all_read,
+        handlers::notification_handler::mark_read,
+        handlers::notification_handler::delete_notification,
+        handlers::notification_handler::delete_all_notifications,
+        // Webhooks (free functions)
+        handlers::webhook_handler::list_webhooks,
+        handlers::webhook_handler::create_webhook,
+        handlers::webhook_handler::update_webhook,
+        handlers::webhook_handler::delete_webhook,
+        handlers::webhook_handler::list_deliveries,
+        handlers::webhook_handler::ping_webhook,
+        // Live changes (free functions)
+        handlers::live_event_handler::stream_events,
         // Favorites handlers (free functions)
         handlers::favorites_handler::get_favorites,
         handlers::favorites_handler::add_favorite,
@@ -178,6 +251,12 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
         handlers::contacts_handler::list_contacts_in_group,
         handlers::contacts_handler::add_contact_to_group,
         handlers::contacts_handler::remove_contact_from_group,
+        // Task (VTODO) handlers
+        handlers::tasks_handler::list_tasks,
+        handlers::tasks_handler::create_task,
+        handlers::tasks_handler::get_task,
+        handlers::tasks_handler::update_task,
+        handlers::tasks_handler::delete_task,
         // Admin handlers (pub free functions)
         handlers::admin_handler::get_dashboard_stats,
         handlers::admin_handler::list_users,
@@ -188,8 +267,28 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
         handlers::admin_handler::update_user_active,
         handlers::admin_handler::update_user_quota,
         handlers::admin_handler::reset_user_password,
+        handlers::admin_handler::list_user_groups,
+        handlers::admin_handler::list_groups,
+        handlers::admin_handler::create_group,
+        handlers::admin_handler::get_group,
+        handlers::admin_handler::update_group,
+        handlers::admin_handler::delete_group,
+        handlers::admin_handler::list_group_members,
+        handlers::admin_handler::add_group_member,
+        handlers::admin_handler::remove_group_member,
         handlers::admin_handler::get_registration_setting,
         handlers::admin_handler::set_registration_setting,
+        handlers::admin_handler::get_two_factor_policy,
+        handlers::admin_handler::set_two_factor_policy,
+        handlers::admin_handler::reset_user_two_factor,
+        handlers::admin_handler::list_activity,
+        handlers::admin_handler::export_activity,
+        handlers::admin_handler::list_webhooks,
+        handlers::admin_handler::create_webhook,
+        handlers::admin_handler::update_webhook,
+        handlers::admin_handler::delete_webhook,
+        handlers::admin_handler::list_webhook_deliveries,
+        handlers::admin_handler::ping_webhook,
         handlers::admin_handler::get_general_settings,
         handlers::admin_handler::get_oidc_settings,
         handlers::admin_handler::save_oidc_settings,
@@ -224,11 +323,33 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
             AuthResponseDto,
             ChangePasswordDto,
             RefreshTokenDto,
+            // Two-factor schemas
+            TwoFactorStatusDto,
+            TotpSetupDto,
+            TwoFactorCodeDto,
+            RecoveryCodesDto,
+            TwoFactorChallengeDto,
+            TwoFactorLoginDto,
+            TwoFactorLoginResponseDto,
+            TwoFactorPolicyDto,
+            // Group schemas
+            GroupDto,
+            GroupMemberDto,
+            CreateGroupDto,
+            UpdateGroupDto,
+            AddGroupMemberDto,
             // Share schemas
             ShareDto,
             SharePermissionsDto,
             CreateShareDto,
             UpdateShareDto,
+            ShareAccessDto,
+            ShareUploadResponse,
+            CreateShareUploadRequest,
+            // User share schemas
+            UserShareDto,
+            CreateUserShareDto,
+            UpdateUserShareDto,
             // Trash schemas
             TrashedItemDto,
             MoveToTrashRequest,
@@ -241,6 +362,25 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
             SearchFolderResultDto,
             SearchSuggestionsDto,
             SearchSuggestionItem,
+            // File version schemas
+            FileVersionDto,
+            SnapshotDto,
+            SnapshotEntryDto,
+            CreateSnapshotDto,
+            RestoreSnapshotDto,
+            RestoreSnapshotResultDto,
+            SnapshotScheduleDto,
+            SetSnapshotScheduleDto,
+            // Activity schemas
+            ActivityDto,
+            // Notification schemas
+            NotificationDto,
+            NotificationListDto,
+            // Webhook schemas
+            WebhookDto,
+            CreateWebhookDto,
+            UpdateWebhookDto,
+            WebhookDeliveryDto,
             // Favorites schemas
             FavoriteItemDto,
             BatchFavoritesResult,
@@ -275,14 +415,25 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
             UpdateContactRequest,
             GroupNameRequest,
             AddMemberRequest,
+            // Task schemas
+            TaskResponse,
+            CreateTaskRequest,
+            UpdateTaskRequest,
         )
     ),
     tags(
         (name = "files", description = "File management endpoints"),
+        (name = "versions", description = "File version history endpoints"),
+        (name = "snapshots", description = "Folder snapshot endpoints"),
+        (name = "activity", description = "Activity log endpoints"),
+        (name = "notifications", description = "User notification endpoints"),
+        (name = "webhooks", description = "Outgoing webhook endpoints"),
+        (name = "events", description = "Live change notification stream"),
         (name = "folders", description = "Folder management endpoints"),
         (name = "trash", description = "Trash / recycle bin endpoints"),
         (name = "search", description = "Search endpoints"),
         (name = "shares", description = "Shared links endpoints"),
+        (name = "user-shares", description = "Files and folders shared with other users"),
         (name = "favorites", description = "Favorites management endpoints"),
         (name = "recent", description = "Recent items endpoints"),
         (name = "photos", description = "Photos timeline endpoints"),
@@ -292,6 +443,7 @@ use crate::interfaces::api::handlers::file_handler::MoveFilePayload;
         (name = "batch", description = "Batch operation endpoints"),
         (name = "playlists", description = "Music playlist endpoints"),
         (name = "contacts", description = "Address books, contacts, and groups endpoints"),
+        (name = "tasks", description = "Calendar task (VTODO) endpoints"),
         (name = "admin", description = "Admin management endpoints"),
     ),
     info(
@@ -5,7 +5,7 @@ use axum::{
     extract::{DefaultBodyLimit, State},
     http::StatusCode,
     response::{IntoResponse, Json as AxumJson},
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
 };
 use serde_json::json;
 use std::sync::Arc;
@@ -129,6 +129,34 @@ pub fn create_public_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppStat
             .route(
                 "/s/{token}/zip/{folder_id}",
                 get(share_handler::download_share_zip_subfolder),
+            )
+            // Uploads through writable folder links ("file drop")
+            .route(
+                "/s/{token}/upload",
+                post(share_handler::upload_to_share).layer(DefaultBodyLimit::max(
+                    // The configured upload limit, plus room for the
+                    // multipart framing and the folder_id field
+                    app_state
+                        .core
+                        .config
+                        .storage
+                        .max_upload_size
+                        .saturating_add(64 * 1024),
+                )),
+            )
+            .route(
+                "/s/{token}/uploads",
+                post(share_handler::create_share_upload),
+            )
+            .route(
+                "/s/{token}/uploads/{upload_id}",
+                patch(share_handler::upload_share_chunk)
+                    .head(share_handler::get_share_upload_status)
+                    .delete(share_handler::cancel_share_upload),
+            )
+            .route(
+                "/s/{token}/uploads/{upload_id}/complete",
+                post(share_handler::complete_share_upload),
             );
     }
 
@@ -164,6 +192,7 @@ pub fn create_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
     let search_service = app_state.applications.search_service.clone();
     let share_service = app_state.share_service.clone();
     let favorites_service = app_state.favorites_service.clone();
+    let user_share_service = app_state.user_share_service.clone();
     let recent_service = app_state.recent_service.clone();
 
     // Initialize the batch operations service
@@ -213,11 +242,47 @@ pub fn create_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
     let folders_ops_router = Router::new().route("/{id}", delete(delete_folder_with_trash));
 
     // Merge the routers
-    let folders_router = folders_basic_router
+    let mut folders_router = folders_basic_router
         .merge(folders_ops_router)
         .merge(folder_zip_router)
         .merge(folder_listing_router);
 
+    // Folder snapshot routes if the service is available
+    let mut snapshots_router = None;
+    if let Some(snapshot_service) = app_state.snapshot_service.clone() {
+        use crate::interfaces::api::handlers::snapshot_handler;
+
+        let folder_snapshots_router = Router::new()
+            .route(
+                "/{id}/snapshots",
+                get(snapshot_handler::list_folder_snapshots)
+                    .post(snapshot_handler::create_snapshot),
+            )
+            .route(
+                "/{id}/snapshot-schedule",
+                get(snapshot_handler::get_snapshot_schedule)
+                    .put(snapshot_handler::set_snapshot_schedule)
+                    .delete(snapshot_handler::delete_snapshot_schedule),
+            )
+            .with_state(snapshot_service.clone());
+        folders_router = folders_router.merge(folder_snapshots_router);
+
+        let router = Router::new()
+            .route("/", get(snapshot_handler::list_snapshots))
+            .route(
+                "/{id}",
+                get(snapshot_handler::get_snapshot).delete(snapshot_handler::delete_snapshot),
+            )
+            .route("/{id}/browse", get(snapshot_handler::browse_snapshot))
+            .route(
+                "/{id}/download",
+                get(snapshot_handler::download_snapshot_file),
+            )
+            .route("/{id}/restore", post(snapshot_handler::restore_snapshot))
+            .with_state(snapshot_service);
+        snapshots_router = Some(router);
+    }
+
     // Create file routes for basic operations and trash-enabled delete
     let basic_file_router = Router::new()
         .route("/", get(list_files_query))
@@ -245,7 +310,26 @@ pub fn create_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
         .route("/{id}/rename", put(rename_file));
 
     // Merge the routers
-    let files_router = basic_file_router.merge(file_operations_router);
+    let mut files_router = basic_file_router.merge(file_operations_router);
+
+    // File version history routes if the service is available
+    if let Some(version_service) = app_state.file_version_service.clone() {
+        use crate::interfaces::api::handlers::file_version_handler;
+
+        let versions_router = Router::new()
+            .route("/{id}/versions", get(file_version_handler::list_versions))
+            .route(
+                "/{id}/versions/{version_id}",
+                get(file_version_handler::download_version)
+                    .delete(file_version_handler::delete_version),
+            )
+            .route(
+                "/{id}/versions/{version_id}/restore",
+                post(file_version_handler::restore_version),
+            )
+            .with_state(version_service);
+        files_router = files_router.merge(versions_router);
+    }
 
     // Create routes for batch operations
     let batch_router = Router::new()
@@ -296,11 +380,33 @@ pub fn create_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
             .route("/{id}", get(share_handler::get_shared_link))
             .route("/{id}", put(share_handler::update_shared_link))
             .route("/{id}", delete(share_handler::delete_shared_link))
+            .route(
+                "/{id}/accesses",
+                get(share_handler::get_shared_link_accesses),
+            )
             .with_state(share_service.clone())
     } else {
         Router::new()
     };
 
+    // Create routes for direct shares with other users (requires auth)
+    let user_share_router = if let Some(user_share_service) = user_share_service.clone() {
+        use crate::interfaces::api::handlers::user_share_handler;
+
+        Router::new()
+            .route("/", post(user_share_handler::create_user_share))
+            .route("/", get(user_share_handler::list_user_shares))
+            .route(
+                "/shared-with-me",
+                get(user_share_handler::list_shared_with_me),
+            )
+            .route("/{id}", put(user_share_handler::update_user_share))
+            .route("/{id}", delete(user_share_handler::delete_user_share))
+            .with_state(user_share_service)
+    } else {
+        Router::new()
+    };
+
     // Create a router without the i18n routes
     // Create routes for favorites if the service is available
     let favorites_router = if let Some(favorites_service) = favorites_service.clone() {
@@ -378,6 +484,7 @@ pub fn create_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
         .nest("/batch", batch_router)
         .nest("/search", search_router)
         .nest("/shares", share_router)
+        .nest("/user-shares", user_share_router)
         .nest("/favorites", favorites_router)
         .nest("/recent", recent_router);
 
@@ -392,6 +499,66 @@ pub fn create_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
         router = router.nest("/photos", photos_router);
     }
 
+    if let Some(snapshots_router) = snapshots_router {
+        router = router.nest("/snapshots", snapshots_router);
+    }
+
+    // Activity feed if the activity log is enabled
+    if let Some(activity_service) = app_state.activity_service.clone() {
+        use crate::interfaces::api::handlers::activity_handler;
+
+        let activity_router = Router::new()
+            .route("/", get(activity_handler::list_activity))
+            .with_state(activity_service);
+        router = router.nest("/activity", activity_router);
+    }
+
+    // Notifications if enabled
+    if let Some(notification_service) = app_state.notification_service.clone() {
+        use crate::interfaces::api::handlers::notification_handler;
+
+        let notification_router = Router::new()
+            .route(
+                "/",
+                get(notification_handler::list_notifications)
+                    .delete(notification_handler::delete_all_notifications),
+            )
+            .route("/read", post(notification_handler::mark_all_read))
+            .route("/{id}", delete(notification_handler::delete_notification))
+            .route("/{id}/read", post(notification_handler::mark_read))
+            .with_state(notification_service);
+        router = router.nest("/notifications", notification_router);
+    }
+
+    // Webhooks if enabled
+    if let Some(webhook_service) = app_state.webhook_service.clone() {
+        use crate::interfaces::api::handlers::webhook_handler;
+
+        let webhook_router = Router::new()
+            .route(
+                "/",
+                get(webhook_handler::list_webhooks).post(webhook_handler::create_webhook),
+            )
+            .route(
+                "/{id}",
+                patch(webhook_handler::update_webhook).delete(webhook_handler::delete_webhook),
+            )
+            .route("/{id}/deliveries", get(webhook_handler::list_deliveries))
+            .route("/{id}/ping", post(webhook_handler::ping_webhook))
+            .with_state(webhook_service);
+        router = router.nest("/webhooks", webhook_router);
+    }
+
+    // Live change stream if enabled
+    if let Some(live_event_service) = app_state.live_event_service.clone() {
+        use crate::interfaces::api::handlers::live_event_handler;
+
+        let events_router = Router::new()
+            .route("/", get(live_event_handler::stream_events))
+            .with_state(live_event_service);
+        router = router.nest("/events", events_router);
+    }
+
     // Re-enable trash routes to make the trash view work
     if let Some(_trash_service_ref) = trash_service.clone() {
         tracing::info!("Setting up trash routes for trash view");
@@ -514,6 +681,27 @@ pub fn create_api_routes(app_state: &Arc<AppState>) -> Router<Arc<AppState>> {
         tracing::info!("Contacts REST API routes initialized");
     }
 
+    // REST API for CalDAV tasks (VTODO); clients sync them over /caldav.
+    if let Some(calendar_service) = app_state.calendar_use_case.clone() {
+        use crate::interfaces::api::handlers::tasks_handler;
+
+        let tasks_router = Router::new()
+            .route(
+                "/{calendar_id}/tasks",
+                get(tasks_handler::list_tasks).post(tasks_handler::create_task),
+            )
+            .route(
+                "/{calendar_id}/tasks/{task_id}",
+                get(tasks_handler::get_task)
+                    .put(tasks_handler::update_task)
+                    .delete(tasks_handler::delete_task),
+            )
+            .with_state(calendar_service);
+
+        router = router.nest("/calendars", tasks_router);
+        tracing::info!("Tasks REST API routes initialized");
+    }
+
     // NOTE: WebDAV routes are mounted at top-level (/webdav) in main.rs
     // for client compatibility, NOT under /api.
 
@@ -15,8 +15,9 @@
 //! IPv4-mapped IPv6 addresses (`::ffff:x.x.x.x`) are automatically
 //! normalised to their IPv4 equivalent before CIDR lookup.
 
-use axum::extract::ConnectInfo;
-use axum::http::Request;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::{Extensions, HeaderMap, Request, header, request::Parts};
+use std::convert::Infallible;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::OnceLock;
 
@@ -138,18 +139,16 @@ pub fn is_trusted_proxy(peer: IpAddr) -> bool {
 ///   connections includes the port, e.g. `"127.0.0.1:12345"`.  Pass `false`
 ///   for contexts that only need the bare IP (e.g. rate-limiting keys).
 pub fn client_ip<B>(req: &Request<B>, include_port: bool) -> String {
-    let peer: Option<SocketAddr> = req
-        .extensions()
-        .get::<ConnectInfo<SocketAddr>>()
-        .map(|ci| ci.0);
+    resolve_client_ip(req.extensions(), req.headers(), include_port)
+}
+
+fn resolve_client_ip(extensions: &Extensions, headers: &HeaderMap, include_port: bool) -> String {
+    let peer: Option<SocketAddr> = extensions.get::<ConnectInfo<SocketAddr>>().map(|ci| ci.0);
 
     if let Some(peer_addr) = peer {
         if is_trusted_proxy(peer_addr.ip()) {
             // Try X-Forwarded-For first (leftmost = original client)
-            if let Some(xff) = req
-                .headers()
-                .get("x-forwarded-for")
-                .and_then(|v| v.to_str().ok())
+            if let Some(xff) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok())
                 && let Some(ip) = xff
                     .split(',')
                     .next()
@@ -160,8 +159,7 @@ pub fn client_ip<B>(req: &Request<B>, include_port: bool) -> String {
             }
 
             // Then X-Real-Ip
-            if let Some(xri) = req
-                .headers()
+            if let Some(xri) = headers
                 .get("x-real-ip")
                 .and_then(|v| v.to_str().ok())
                 .map(str::trim)
@@ -181,6 +179,34 @@ pub fn client_ip<B>(req: &Request<B>, include_port: bool) -> String {
     "unknown".to_string()
 }
 
+/// Who is on the other end of a request: the effective client IP (honouring
+/// trusted proxies) and the `User-Agent` header.  Handlers that record
+/// accesses take it as an extractor.
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub ip: String,
+    pub user_agent: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        Ok(Self {
+            ip: resolve_client_ip(&parts.extensions, &parts.headers, false),
+            user_agent,
+        })
+    }
+}
+
 // ─── Tests ───────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -234,6 +260,24 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn client_info_from_untrusted_peer_ignores_forwarding_headers() {
+        let peer: SocketAddr = "203.0.113.7:4321".parse().unwrap();
+        let req = Request::builder()
+            .header("x-forwarded-for", "198.51.100.1")
+            .header(header::USER_AGENT, "curl/8.0")
+            .extension(ConnectInfo(peer))
+            .body(())
+            .unwrap();
+        let (mut parts, _) = req.into_parts();
+
+        let info = ClientInfo::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+        assert_eq!(info.ip, "203.0.113.7");
+        assert_eq!(info.user_agent.as_deref(), Some("curl/8.0"));
+    }
+
     #[test]
     fn parse_cidr_invalid_prefix() {
         assert!(parse_cidr("10.0.0.0/33").is_none());
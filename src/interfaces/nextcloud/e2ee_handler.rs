@@ -0,0 +1,422 @@
+//! Nextcloud end-to-end encryption API
+//! (`/ocs/v2.php/apps/end_to_end_encryption/api/v1`).
+//!
+//! Clients keep their keys and folder metadata here, mark empty folders as
+//! encrypted and lock a folder while they change its contents.  Folders are
+//! addressed by their numeric Nextcloud file id.
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{HeaderMap, Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::application::services::e2ee_service::E2eeService;
+use crate::common::di::AppState;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::interfaces::middleware::auth::AuthUser;
+use crate::interfaces::nextcloud::ocs_handler::ocs_ok;
+use crate::interfaces::nextcloud::shares_handler::{ocs_error, read_params_limited};
+
+/// Header (or parameter) carrying the lock token of an encrypted folder
+pub const E2E_TOKEN: &str = "e2e-token";
+/// Largest request body; metadata grows with the number of files
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+#[allow(clippy::result_large_err)]
+fn service(state: &AppState) -> Result<&Arc<E2eeService>, Response> {
+    state.e2ee_service.as_ref().ok_or_else(|| {
+        ocs_error(
+            StatusCode::NOT_FOUND,
+            "End-to-end encryption is not enabled",
+        )
+    })
+}
+
+fn e2ee_error(err: DomainError) -> Response {
+    let status = match err.kind {
+        ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        ErrorKind::AlreadyExists => StatusCode::CONFLICT,
+        ErrorKind::AccessDenied => StatusCode::FORBIDDEN,
+        ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+        _ => {
+            tracing::error!("OCS end-to-end encryption request failed: {}", err);
+            return ocs_error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error");
+        }
+    };
+    ocs_error(status, &err.message)
+}
+
+fn ok(data: Value) -> Response {
+    Json(ocs_ok(200, data)).into_response()
+}
+
+/// Lock token from the `e2e-token` header, or else from the parameters.
+pub fn lock_token<'a>(
+    headers: &'a HeaderMap,
+    params: Option<&'a HashMap<String, String>>,
+) -> Option<&'a str> {
+    headers
+        .get(E2E_TOKEN)
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| params.and_then(|p| p.get(E2E_TOKEN)).map(String::as_str))
+        .filter(|token| !token.is_empty())
+}
+
+/// OxiCloud id of the folder behind a Nextcloud file id.
+async fn folder_id(state: &AppState, file_id: &str) -> Result<String, Response> {
+    let not_found = || ocs_error(StatusCode::NOT_FOUND, "File not found");
+    let nc_id: i64 = file_id.parse().map_err(|_| not_found())?;
+    let nc = state.nextcloud.as_ref().ok_or_else(not_found)?;
+    nc.file_ids
+        .get_oxicloud_folder_id(nc_id)
+        .await
+        .map_err(|_| not_found())
+}
+
+/// Reads the request parameters and the parameter `name`, which must be
+/// present.
+async fn required_param(
+    req: Request<Body>,
+    name: &str,
+) -> Result<(HashMap<String, String>, String), Response> {
+    let params = read_params_limited(req, MAX_BODY_BYTES).await?;
+    match params.get(name).filter(|v| !v.is_empty()) {
+        Some(value) => {
+            let value = value.clone();
+            Ok((params, value))
+        }
+        None => Err(ocs_error(
+            StatusCode::BAD_REQUEST,
+            &format!("Missing parameter '{name}'"),
+        )),
+    }
+}
+
+// ──────────────────── Keys ────────────────────
+
+/// GET /public-key
+///
+/// With `users` (a JSON array of usernames), the certificates of those
+/// users that have one; otherwise the caller's own.
+pub async fn handle_get_public_keys(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let keys = match params.get("users") {
+        Some(users) => {
+            let Ok(usernames) = serde_json::from_str::<Vec<String>>(users) else {
+                return ocs_error(StatusCode::BAD_REQUEST, "Invalid list of users");
+            };
+            match e2ee.public_keys(&usernames).await {
+                Ok(keys) => keys,
+                Err(e) => return e2ee_error(e),
+            }
+        }
+        None => match e2ee.public_key(user.id).await {
+            Ok(key) => HashMap::from([(user.username.clone(), key)]),
+            Err(e) => return e2ee_error(e),
+        },
+    };
+    ok(json!({ "public-keys": keys }))
+}
+
+/// POST /public-key — sign the caller's CSR (`csr`).
+pub async fn handle_create_public_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    req: Request<Body>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let (_, csr) = match required_param(req, "csr").await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+    match e2ee.sign_public_key(user.id, &user.username, &csr).await {
+        Ok(certificate) => ok(json!({ "public-key": certificate })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// DELETE /public-key
+pub async fn handle_delete_public_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    match e2ee.delete_public_key(user.id).await {
+        Ok(()) => ok(json!([])),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// GET /private-key
+pub async fn handle_get_private_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    match e2ee.private_key(user.id).await {
+        Ok(key) => ok(json!({ "private-key": key })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// POST /private-key — store the caller's encrypted private key
+/// (`privateKey`).
+pub async fn handle_store_private_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    req: Request<Body>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let (_, key) = match required_param(req, "privateKey").await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+    match e2ee.store_private_key(user.id, &key).await {
+        Ok(()) => ok(json!({ "private-key": key })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// DELETE /private-key
+pub async fn handle_delete_private_key(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    match e2ee.delete_private_key(user.id).await {
+        Ok(()) => ok(json!([])),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// GET /server-key
+pub async fn handle_server_key(State(state): State<Arc<AppState>>, _user: AuthUser) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    match e2ee.server_public_key().await {
+        Ok(key) => ok(json!({ "public-key": key })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+// ──────────────────── Folders ────────────────────
+
+/// PUT /encrypted/{fileId} — mark an empty folder as encrypted.
+pub async fn handle_set_encrypted(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    match e2ee.set_encrypted(user.id, &folder_id).await {
+        Ok(()) => ok(json!([])),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// DELETE /encrypted/{fileId} — turn an empty encrypted folder back into a
+/// plain one.
+pub async fn handle_remove_encrypted(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    match e2ee.remove_encrypted(user.id, &folder_id).await {
+        Ok(()) => ok(json!([])),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// POST /lock/{fileId} — lock an encrypted folder, or renew the lock when
+/// the current token is sent.
+pub async fn handle_lock(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+    req: Request<Body>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    let headers = req.headers().clone();
+    let params = match read_params_limited(req, MAX_BODY_BYTES).await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+    let token = lock_token(&headers, Some(&params));
+    match e2ee.lock(user.id, &folder_id, token).await {
+        Ok(token) => ok(json!({ E2E_TOKEN: token })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// DELETE /lock/{fileId} — release the lock held with the `e2e-token`.
+pub async fn handle_unlock(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    let token = lock_token(&headers, Some(&params));
+    match e2ee.unlock(user.id, &folder_id, token).await {
+        Ok(()) => ok(json!([])),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+// ──────────────────── Metadata ────────────────────
+
+/// GET /meta-data/{fileId}
+pub async fn handle_get_metadata(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    match e2ee.metadata(user.id, &folder_id).await {
+        Ok(metadata) => ok(json!({ "meta-data": metadata })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// POST /meta-data/{fileId} — store the first metadata (`metaData`).
+pub async fn handle_create_metadata(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+    req: Request<Body>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    let (_, metadata) = match required_param(req, "metaData").await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+    match e2ee.create_metadata(user.id, &folder_id, &metadata).await {
+        Ok(()) => ok(json!({ "meta-data": metadata })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// PUT /meta-data/{fileId} — replace the metadata (`metaData`) under the
+/// folder's lock.
+pub async fn handle_update_metadata(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+    req: Request<Body>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    let headers = req.headers().clone();
+    let (params, metadata) = match required_param(req, "metaData").await {
+        Ok(p) => p,
+        Err(r) => return r,
+    };
+    let token = lock_token(&headers, Some(&params));
+    match e2ee
+        .update_metadata(user.id, &folder_id, token, &metadata)
+        .await
+    {
+        Ok(()) => ok(json!({ "meta-data": metadata })),
+        Err(e) => e2ee_error(e),
+    }
+}
+
+/// DELETE /meta-data/{fileId}
+pub async fn handle_delete_metadata(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(file_id): Path<String>,
+) -> Response {
+    let e2ee = match service(&state) {
+        Ok(s) => s,
+        Err(r) => return r,
+    };
+    let folder_id = match folder_id(&state, &file_id).await {
+        Ok(id) => id,
+        Err(r) => return r,
+    };
+    match e2ee.delete_metadata(user.id, &folder_id).await {
+        Ok(()) => ok(json!([])),
+        Err(e) => e2ee_error(e),
+    }
+}
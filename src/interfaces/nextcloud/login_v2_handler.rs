@@ -166,14 +166,24 @@ pub async fn handle_login_submit(
         None => return StatusCode::SERVICE_UNAVAILABLE.into_response(),
     };
 
+    if auth.login_lockout.check(username).is_err() {
+        tracing::warn!(user = %username, "Login Flow v2: account temporarily locked");
+        return axum::response::Redirect::to("/nextcloud-error.html?type=account-locked")
+            .into_response();
+    }
+
     let current_user = match auth
         .auth_application_service
-        .verify_credentials(username, password)
+        .verify_credentials(username, password, params.get("totp").map(String::as_str))
         .await
     {
         Ok(user) => user,
-        Err(e) => return login_failed_response(e),
+        Err(e) => {
+            auth.login_lockout.record_failure(username);
+            return login_failed_response(e);
+        }
     };
+    auth.login_lockout.record_success(username);
 
     let app_password = match nextcloud
         .app_passwords
@@ -269,12 +279,17 @@ fn login_failed_response(_err: DomainError) -> Response {
     axum::response::Redirect::to("/nextcloud-error.html?type=invalid-credentials").into_response()
 }
 
-fn parse_form(body: &str) -> HashMap<String, String> {
+/// Parse an `application/x-www-form-urlencoded` body.
+pub(super) fn parse_form(body: &str) -> HashMap<String, String> {
     body.split('&')
         .filter_map(|pair| {
             let (key, value) = pair.split_once('=')?;
-            let key = urlencoding::decode(key).ok()?.to_string();
-            let value = urlencoding::decode(value).ok()?.to_string();
+            let key = urlencoding::decode(&key.replace('+', " "))
+                .ok()?
+                .to_string();
+            let value = urlencoding::decode(&value.replace('+', " "))
+                .ok()?
+                .to_string();
             Some((key, value))
         })
         .collect()
@@ -1,11 +1,16 @@
 pub mod avatar_handler;
 pub mod basic_auth_middleware;
+pub mod e2ee_handler;
 pub mod login_v2_handler;
+pub mod notifications_handler;
+pub mod notify_push_handler;
 pub mod ocs_handler;
 pub mod preview_handler;
 pub mod report_handler;
 pub mod routes;
+pub mod shares_handler;
 pub mod status_handler;
 pub mod trashbin_handler;
 pub mod uploads_handler;
+pub mod versions_handler;
 pub mod webdav_handler;
@@ -0,0 +1,122 @@
+//! Nextcloud notifications API (`/ocs/v2.php/apps/notifications/api/v2`).
+//!
+//! Desktop and mobile clients poll the list and show new entries as system
+//! notifications; dismissing one there deletes it here.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::SecondsFormat;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use crate::application::dtos::notification_dto::NotificationDto;
+use crate::application::ports::notification_ports::NotificationKind;
+use crate::common::di::AppState;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::interfaces::middleware::auth::AuthUser;
+use crate::interfaces::nextcloud::ocs_handler::ocs_ok;
+use crate::interfaces::nextcloud::shares_handler::ocs_error;
+
+/// Most notifications handed to a client at once
+const LIST_LIMIT: usize = 200;
+
+fn notification_error(err: DomainError) -> Response {
+    if err.kind == ErrorKind::NotFound {
+        return ocs_error(StatusCode::NOT_FOUND, "Notification not found");
+    }
+    tracing::error!("OCS notification request failed: {}", err);
+    ocs_error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+}
+
+fn not_found() -> Response {
+    ocs_error(StatusCode::NOT_FOUND, "Notification not found")
+}
+
+/// A notification in the shape Nextcloud clients expect.
+fn to_nc(n: NotificationDto, username: &str, base_url: &str) -> Value {
+    let app = NotificationKind::parse(&n.kind).map_or("notifications", |kind| kind.app());
+    json!({
+        "notification_id": n.id,
+        "app": app,
+        "user": username,
+        "datetime": n.created_at.to_rfc3339_opts(SecondsFormat::Secs, false),
+        "object_type": n.object_type,
+        "object_id": n.object_id,
+        "subject": n.subject,
+        "subjectRich": "",
+        "subjectRichParameters": {},
+        "message": n.message,
+        "messageRich": "",
+        "messageRichParameters": {},
+        "link": n.link.map(|link| format!("{base_url}{link}")).unwrap_or_default(),
+        "icon": "",
+        "shouldNotify": !n.read,
+        "actions": [],
+    })
+}
+
+/// GET /notifications
+pub async fn handle_list(State(state): State<Arc<AppState>>, user: AuthUser) -> Response {
+    let Some(service) = state.notification_service.as_ref() else {
+        return Json(ocs_ok(200, json!([]))).into_response();
+    };
+    let base_url = state.core.config.base_url();
+    match service.list(user.id, Some(LIST_LIMIT)).await {
+        Ok(list) => {
+            let items: Vec<Value> = list
+                .notifications
+                .into_iter()
+                .map(|n| to_nc(n, &user.username, &base_url))
+                .collect();
+            Json(ocs_ok(200, Value::Array(items))).into_response()
+        }
+        Err(e) => notification_error(e),
+    }
+}
+
+/// GET /notifications/{id}
+pub async fn handle_get(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    let Some(service) = state.notification_service.as_ref() else {
+        return not_found();
+    };
+    match service.get(user.id, id).await {
+        Ok(n) => {
+            let data = to_nc(n, &user.username, &state.core.config.base_url());
+            Json(ocs_ok(200, data)).into_response()
+        }
+        Err(e) => notification_error(e),
+    }
+}
+
+/// DELETE /notifications/{id}
+pub async fn handle_delete(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    let Some(service) = state.notification_service.as_ref() else {
+        return not_found();
+    };
+    match service.delete(user.id, id).await {
+        Ok(()) => Json(ocs_ok(200, json!([]))).into_response(),
+        Err(e) => notification_error(e),
+    }
+}
+
+/// DELETE /notifications
+pub async fn handle_delete_all(State(state): State<Arc<AppState>>, user: AuthUser) -> Response {
+    if let Some(service) = state.notification_service.as_ref()
+        && let Err(e) = service.delete_all(user.id).await
+    {
+        return notification_error(e);
+    }
+    Json(ocs_ok(200, json!([]))).into_response()
+}
@@ -0,0 +1,379 @@
+//! Nextcloud `notify_push` protocol, so desktop and mobile clients learn
+//! about changes instead of polling PROPFIND.
+//!
+//! A client opens the websocket at `/push/ws` and sends its username and
+//! app password as two text messages, or an empty username and a token
+//! from `pre_auth`.  After `authenticated` it receives `notify_file`,
+//! `notify_activity` and `notify_notification`; clients that send
+//! `listen notify_file_id` get `notify_file_id [ids]` with the numeric ids
+//! of what changed instead of `notify_file`.  Messages are coalesced and
+//! sent at most once per flush interval.
+
+use axum::{
+    extract::{
+        State,
+        ws::{Message, Utf8Bytes, WebSocket, WebSocketUpgrade},
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::application::ports::live_event_ports::{LiveChange, LiveEventKind};
+use crate::application::services::live_event_service::{LiveEventService, LiveMessage};
+use crate::common::di::AppState;
+use crate::infrastructure::services::push_token;
+use crate::interfaces::middleware::auth::AuthUser;
+
+/// Time a client has to send its credentials
+const AUTH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Changes are coalesced and sent at most this often
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Most ids in one `notify_file_id`; beyond that clients get `notify_file`
+const MAX_FILE_IDS: usize = 100;
+
+/// POST /index.php/apps/notify_push/pre_auth
+///
+/// Short-lived token a signed-in client presents on the websocket instead
+/// of its password.
+pub async fn handle_pre_auth(State(state): State<Arc<AppState>>, user: AuthUser) -> Response {
+    match push_token::issue(
+        &state.core.config.auth.jwt_secret,
+        user.id,
+        push_token::DEFAULT_TTL_SECS,
+    ) {
+        Ok(token) => token.into_response(),
+        Err(e) => {
+            tracing::error!("notify_push pre_auth failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// GET /push/ws
+pub async fn handle_websocket(
+    State(state): State<Arc<AppState>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let Some(service) = state.live_event_service.clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    ws.on_upgrade(move |socket| serve(socket, state, service))
+}
+
+async fn serve(mut socket: WebSocket, state: Arc<AppState>, service: Arc<LiveEventService>) {
+    let user_id = match tokio::time::timeout(AUTH_TIMEOUT, authenticate(&mut socket, &state)).await
+    {
+        Ok(Some(user_id)) => user_id,
+        Ok(None) | Err(_) => {
+            let _ = socket.send(text("err: Invalid credentials")).await;
+            let _ = socket.send(Message::Close(None)).await;
+            return;
+        }
+    };
+    if socket.send(text("authenticated")).await.is_err() {
+        return;
+    }
+
+    let with_activity = state.activity_service.is_some();
+    let mut changes = Box::pin(service.subscribe(user_id));
+    let mut pending = Pending::default();
+    let mut listen_file_id = false;
+    let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+    let mut ping = tokio::time::interval(service.keepalive());
+    ping.reset();
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(message))) => {
+                    if message.trim() == "listen notify_file_id" {
+                        listen_file_id = true;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            message = changes.next() => match message {
+                Some(message) => pending.record(&message),
+                None => break,
+            },
+            _ = flush.tick() => {
+                if pending.is_empty() {
+                    continue;
+                }
+                let file_ids = if listen_file_id {
+                    resolve_file_ids(&state, &pending).await
+                } else {
+                    None
+                };
+                let messages = pending.take(with_activity, file_ids);
+                for message in messages {
+                    if socket.send(text(&message)).await.is_err() {
+                        return;
+                    }
+                }
+            },
+            _ = ping.tick() => {
+                if socket.send(Message::Ping(Default::default())).await.is_err() {
+                    break;
+                }
+            },
+        }
+    }
+}
+
+fn text(message: &str) -> Message {
+    Message::Text(Utf8Bytes::from(message))
+}
+
+/// Reads the two credential lines and returns the user they belong to.
+async fn authenticate(socket: &mut WebSocket, state: &AppState) -> Option<Uuid> {
+    let username = next_text(socket).await?;
+    let password = next_text(socket).await?;
+
+    if username.is_empty() {
+        return push_token::verify(&state.core.config.auth.jwt_secret, &password);
+    }
+
+    let nextcloud = state.nextcloud.as_ref()?;
+    if let Some(auth_svc) = state.auth_service.as_ref()
+        && auth_svc.login_lockout.check(&username).is_err()
+    {
+        tracing::warn!(username = %username, "[NC] notify_push login while locked out");
+        return None;
+    }
+    match nextcloud
+        .app_passwords
+        .verify_basic_auth(&username, &password)
+        .await
+    {
+        Ok((user_id, ..)) => {
+            if let Some(auth_svc) = state.auth_service.as_ref() {
+                auth_svc.login_lockout.record_success(&username);
+            }
+            Some(user_id)
+        }
+        Err(_) => {
+            if let Some(auth_svc) = state.auth_service.as_ref() {
+                auth_svc.login_lockout.record_failure(&username);
+            }
+            None
+        }
+    }
+}
+
+async fn next_text(socket: &mut WebSocket) -> Option<String> {
+    loop {
+        match socket.recv().await? {
+            Ok(Message::Text(message)) => return Some(message.trim().to_string()),
+            Ok(Message::Close(_)) | Err(_) => return None,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Numeric ids of the changed files and folders and of the folders they
+/// are in, or `None` when clients should rescan everything instead.
+async fn resolve_file_ids(state: &AppState, pending: &Pending) -> Option<Vec<i64>> {
+    let nextcloud = state.nextcloud.as_ref()?;
+    if pending.items_overflowed {
+        return None;
+    }
+    let mut ids = Vec::with_capacity(pending.items.len());
+    for item in &pending.items {
+        let id = match item {
+            Item::File(id) => nextcloud.file_ids.get_or_create_file_id(id).await,
+            Item::Folder(id) => nextcloud.file_ids.get_or_create_folder_id(id).await,
+        };
+        match id {
+            Ok(id) => ids.push(id),
+            Err(e) => {
+                tracing::debug!("notify_push: no numeric id for {:?}: {}", item, e);
+                return None;
+            }
+        }
+    }
+    Some(ids)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Item {
+    File(String),
+    Folder(String),
+}
+
+/// Changes waiting for the next flush.
+#[derive(Debug, Default)]
+struct Pending {
+    file: bool,
+    activity: bool,
+    notification: bool,
+    /// Changed files and folders, for `notify_file_id`
+    items: BTreeSet<Item>,
+    /// Too many items, or some could not be named; rescan everything
+    items_overflowed: bool,
+}
+
+impl Pending {
+    fn is_empty(&self) -> bool {
+        !self.file && !self.activity && !self.notification
+    }
+
+    fn record(&mut self, message: &LiveMessage) {
+        match message {
+            LiveMessage::Change(change) => self.record_change(change),
+            LiveMessage::Resync => {
+                self.file = true;
+                self.notification = true;
+                self.items_overflowed = true;
+            }
+        }
+    }
+
+    fn record_change(&mut self, change: &LiveChange) {
+        let is_folder = match change.kind.item_type() {
+            "file" => false,
+            "folder" => true,
+            "share" => {
+                self.activity = true;
+                return;
+            }
+            _ => {
+                self.notification = true;
+                return;
+            }
+        };
+        self.file = true;
+        self.activity = true;
+        if self.items_overflowed {
+            return;
+        }
+
+        // A deleted item is gone from the client's point of view; only the
+        // folders it was in matter
+        let deleted = matches!(
+            change.kind,
+            LiveEventKind::FileDeleted | LiveEventKind::FolderDeleted
+        );
+        if !deleted {
+            self.items.insert(if is_folder {
+                Item::Folder(change.item_id.clone())
+            } else {
+                Item::File(change.item_id.clone())
+            });
+        }
+        match &change.folder_id {
+            Some(folder_id) => {
+                self.items.insert(Item::Folder(folder_id.clone()));
+            }
+            // Nothing to name at the top of the tree
+            None => self.items_overflowed = true,
+        }
+        if let Some(previous) = &change.previous_folder_id {
+            self.items.insert(Item::Folder(previous.clone()));
+        }
+        if self.items.len() > MAX_FILE_IDS {
+            self.items_overflowed = true;
+        }
+        if self.items_overflowed {
+            self.items.clear();
+        }
+    }
+
+    /// The messages to send, leaving nothing pending.  `file_ids` replaces
+    /// `notify_file` for clients listening for ids.
+    fn take(&mut self, with_activity: bool, file_ids: Option<Vec<i64>>) -> Vec<String> {
+        let pending = std::mem::take(self);
+        let mut messages = Vec::new();
+        if pending.file {
+            messages.push(match file_ids {
+                Some(ids) => format!(
+                    "notify_file_id {}",
+                    serde_json::to_string(&ids).unwrap_or_else(|_| "[]".to_string())
+                ),
+                None => "notify_file".to_string(),
+            });
+        }
+        if pending.activity && with_activity {
+            messages.push("notify_activity".to_string());
+        }
+        if pending.notification {
+            messages.push("notify_notification".to_string());
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(kind: LiveEventKind, id: &str, folder: Option<&str>) -> LiveMessage {
+        LiveMessage::Change(LiveChange::new(kind, id).with_folder(folder))
+    }
+
+    #[test]
+    fn changes_are_coalesced_per_flush() {
+        let mut pending = Pending::default();
+        assert!(pending.is_empty());
+        pending.record(&change(LiveEventKind::FileCreated, "f1", Some("d1")));
+        pending.record(&change(LiveEventKind::FileUpdated, "f1", Some("d1")));
+        pending.record(&change(LiveEventKind::NotificationCreated, "7", None));
+
+        assert_eq!(
+            pending.take(true, None),
+            vec!["notify_file", "notify_activity", "notify_notification"]
+        );
+        assert!(pending.is_empty());
+        assert!(pending.take(true, None).is_empty());
+    }
+
+    #[test]
+    fn file_ids_name_items_and_their_folders() {
+        let mut pending = Pending::default();
+        pending.record(&LiveMessage::Change(
+            LiveChange::new(LiveEventKind::FolderMoved, "d3")
+                .with_folder(Some("d2"))
+                .with_previous_folder(Some("d1")),
+        ));
+        pending.record(&change(LiveEventKind::FileDeleted, "f1", Some("d1")));
+        assert!(!pending.items_overflowed);
+        assert_eq!(
+            pending.items.iter().cloned().collect::<Vec<_>>(),
+            vec![
+                Item::Folder("d1".into()),
+                Item::Folder("d2".into()),
+                Item::Folder("d3".into()),
+            ]
+        );
+        assert_eq!(
+            pending.take(false, Some(vec![3, 1, 2])),
+            vec!["notify_file_id [3,1,2]"]
+        );
+    }
+
+    #[test]
+    fn shares_resyncs_and_unnamed_folders_fall_back_to_notify_file() {
+        let mut pending = Pending::default();
+        pending.record(&change(LiveEventKind::ShareCreated, "s1", None));
+        assert!(!pending.file);
+        assert_eq!(pending.take(true, None), vec!["notify_activity"]);
+
+        pending.record(&change(LiveEventKind::FolderCreated, "d1", None));
+        assert!(pending.items_overflowed);
+        assert!(pending.items.is_empty());
+
+        let mut pending = Pending::default();
+        pending.record(&LiveMessage::Resync);
+        assert!(pending.items_overflowed);
+        assert_eq!(
+            pending.take(true, None),
+            vec!["notify_file", "notify_notification"]
+        );
+    }
+}
@@ -14,7 +14,7 @@ use crate::common::di::AppState;
 use crate::interfaces::middleware::auth::AuthUser;
 
 /// Build an OCS success response with the given statuscode and data.
-fn ocs_ok(statuscode: u16, data: serde_json::Value) -> serde_json::Value {
+pub(super) fn ocs_ok(statuscode: u16, data: serde_json::Value) -> serde_json::Value {
     json!({
         "ocs": {
             "meta": { "status": "ok", "statuscode": statuscode, "message": "OK" },
@@ -24,7 +24,7 @@ fn ocs_ok(statuscode: u16, data: serde_json::Value) -> serde_json::Value {
 }
 
 /// Build an OCS error response.
-fn ocs_err(statuscode: u16, message: &str) -> serde_json::Value {
+pub(super) fn ocs_err(statuscode: u16, message: &str) -> serde_json::Value {
     json!({
         "ocs": {
             "meta": { "status": "failure", "statuscode": statuscode, "message": message },
@@ -224,10 +224,6 @@ pub async fn handle_revoke_apppassword(
     Json(ocs_ok(200, json!({}))).into_response()
 }
 
-pub async fn handle_notifications_list() -> Response {
-    Json(ocs_ok(200, json!([]))).into_response()
-}
-
 pub async fn handle_notifications_push() -> Response {
     Json(ocs_ok(200, json!({}))).into_response()
 }
@@ -248,8 +244,8 @@ pub async fn handle_recommendations() -> Response {
 /// GET /ocs/v2.php/apps/files_sharing/api/v1/sharees?search={query}&itemType={type}
 ///
 /// Returns matching users for the sharing autocomplete UI.
-/// Even though sharing is disabled, the Nextcloud mobile app still calls
-/// this endpoint and expects a well-formed OCS response rather than a 404.
+/// The Nextcloud mobile app calls this endpoint even when sharing is
+/// disabled and expects a well-formed OCS response rather than a 404.
 pub async fn handle_sharees_search(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
@@ -469,10 +465,12 @@ fn empty_search_response() -> Json<serde_json::Value> {
 fn capabilities_payload(state: &AppState, ocs_version: u8) -> serde_json::Value {
     let statuscode = if ocs_version == 1 { 100 } else { 200 };
     let base_url = state.core.config.base_url();
+    let links_enabled = state.share_service.is_some();
+    let users_enabled = state.user_share_service.is_some();
     let (nc_major, nc_minor, nc_micro) = state.core.config.nextcloud.emulated_version;
     let nc_version_str = state.core.config.nextcloud.version_string();
 
-    json!({
+    let mut payload = json!({
         "ocs": {
             "meta": {
                 "status": "ok",
@@ -499,7 +497,7 @@ fn capabilities_payload(state: &AppState, ocs_version: u8) -> serde_json::Value
                         "bigfilechunking": true,
                         "favorites": true,
                         "undelete": true,
-                        "versioning": false
+                        "versioning": state.file_version_service.is_some()
                     },
                     "dav": {
                         "chunking": "1.0"
@@ -508,11 +506,25 @@ fn capabilities_payload(state: &AppState, ocs_version: u8) -> serde_json::Value
                         "preferredUploadType": "",
                         "supportedTypes": []
                     },
+                    "end-to-end-encryption": {
+                        "enabled": state.e2ee_service.is_some(),
+                        "api-version": "1.2"
+                    },
                     "files_sharing": {
-                        "api_enabled": false,
-                        "public": { "enabled": false },
-                        "user": { "send_mail": false },
-                        "resharing": false
+                        "api_enabled": links_enabled || users_enabled,
+                        "public": {
+                            "enabled": links_enabled,
+                            "password": { "enforced": false, "askForOptionalPassword": false },
+                            "expire_date": { "enabled": false },
+                            "multiple_links": true,
+                            "upload": links_enabled,
+                            "upload_files_drop": links_enabled,
+                            "send_mail": false
+                        },
+                        "user": { "send_mail": false, "expire_date": { "enabled": false } },
+                        "group_sharing": false,
+                        "resharing": users_enabled,
+                        "default_permissions": 31
                     },
                     "notifications": {
                         "ocs-endpoints": ["list", "get", "delete", "delete-all"]
@@ -535,6 +547,28 @@ fn capabilities_payload(state: &AppState, ocs_version: u8) -> serde_json::Value
                 }
             }
         }
+    });
+    if state.live_event_service.is_some() {
+        payload["ocs"]["data"]["capabilities"]["notify_push"] = notify_push_capability(&base_url);
+    }
+    payload
+}
+
+/// Where clients find the notify_push websocket.
+fn notify_push_capability(base_url: &str) -> serde_json::Value {
+    let ws_base = if let Some(rest) = base_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = base_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        base_url.to_string()
+    };
+    json!({
+        "type": ["files", "activities", "notifications"],
+        "endpoints": {
+            "websocket": format!("{ws_base}/push/ws"),
+            "pre_auth": format!("{base_url}/index.php/apps/notify_push/pre_auth")
+        }
     })
 }
 
@@ -98,6 +98,16 @@ pub async fn handle_preview(
             .unwrap();
     }
 
+    // End-to-end encrypted files are unreadable to the server
+    if let Some(e2ee) = state.e2ee_service.as_ref()
+        && e2ee.is_encrypted(&object_id).await.unwrap_or(false)
+    {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::from("No preview for encrypted files"))
+            .unwrap();
+    }
+
     // Determine thumbnail size based on request params
     let thumb_size = if params.force_icon == Some(1) {
         ThumbnailSize::Icon
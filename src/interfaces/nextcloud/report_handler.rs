@@ -23,7 +23,7 @@ use crate::common::di::AppState;
 use crate::interfaces::errors::AppError;
 use crate::interfaces::middleware::auth::CurrentUser;
 use crate::interfaces::nextcloud::webdav_handler::{
-    format_oc_id, nc_href, resolve_file_id, resolve_folder_id, write_file_response,
+    NcShareInfo, format_oc_id, nc_href, resolve_file_id, resolve_folder_id, write_file_response,
     write_folder_response,
 };
 
@@ -110,6 +110,7 @@ async fn handle_filter_files(
                         oc_id.as_deref(),
                         &user.username,
                         &favorite_ids,
+                        &NcShareInfo::default(),
                     )
                     .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
                 }
@@ -130,6 +131,7 @@ async fn handle_filter_files(
                         oc_id.as_deref(),
                         &user.username,
                         &favorite_ids,
+                        &NcShareInfo::default(),
                     )
                     .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
                 }
@@ -211,6 +213,7 @@ async fn handle_search(
                 oc_id.as_deref(),
                 &user.username,
                 &favorite_ids,
+                &NcShareInfo::default(),
             )
             .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
         }
@@ -230,6 +233,7 @@ async fn handle_search(
                 oc_id.as_deref(),
                 &user.username,
                 &favorite_ids,
+                &NcShareInfo::default(),
             )
             .map_err(|e| AppError::internal_error(format!("XML write error: {}", e)))?;
         }
@@ -5,7 +5,7 @@ use axum::{
     http::{Request, StatusCode},
     middleware,
     response::{IntoResponse, Response},
-    routing::{any, delete, get, post},
+    routing::{any, delete, get, post, put},
 };
 use std::sync::Arc;
 
@@ -14,12 +14,17 @@ use crate::interfaces::middleware::auth::{AuthUser, CurrentUser};
 use crate::interfaces::middleware::rate_limit::{RateLimiter, rate_limit_login};
 use crate::interfaces::nextcloud::avatar_handler;
 use crate::interfaces::nextcloud::basic_auth_middleware::basic_auth_middleware;
+use crate::interfaces::nextcloud::e2ee_handler;
 use crate::interfaces::nextcloud::login_v2_handler;
+use crate::interfaces::nextcloud::notifications_handler;
+use crate::interfaces::nextcloud::notify_push_handler;
 use crate::interfaces::nextcloud::ocs_handler;
 use crate::interfaces::nextcloud::preview_handler;
+use crate::interfaces::nextcloud::shares_handler;
 use crate::interfaces::nextcloud::status_handler;
 use crate::interfaces::nextcloud::trashbin_handler;
 use crate::interfaces::nextcloud::uploads_handler;
+use crate::interfaces::nextcloud::versions_handler;
 use crate::interfaces::nextcloud::webdav_handler;
 
 /// Build Nextcloud routes with a pre-built `Arc<AppState>` for the middleware layer.
@@ -38,7 +43,7 @@ pub fn nextcloud_routes_with_state(state: Arc<AppState>) -> Router<Arc<AppState>
     };
 
     // Public routes — no auth required.
-    let public = Router::new()
+    let mut public = Router::new()
         .route("/status.php", get(status_handler::handle_status))
         // NC connectivity check — app expects 204 to confirm server is reachable.
         .route("/index.php/204", get(handle_connectivity_check))
@@ -78,8 +83,13 @@ pub fn nextcloud_routes_with_state(state: Arc<AppState>) -> Router<Arc<AppState>
             get(ocs_handler::handle_capabilities_v2),
         );
 
+    if state.live_event_service.is_some() {
+        // notify_push authenticates on the socket itself
+        public = public.route("/push/ws", any(notify_push_handler::handle_websocket));
+    }
+
     // Protected routes — require Basic Auth via app passwords.
-    let protected = Router::new()
+    let mut protected = Router::new()
         .route("/ocs/v2.php/cloud/user", get(ocs_handler::handle_user_info))
         .route(
             "/ocs/v1.php/cloud/users/{userid}",
@@ -95,7 +105,12 @@ pub fn nextcloud_routes_with_state(state: Arc<AppState>) -> Router<Arc<AppState>
         )
         .route(
             "/ocs/v2.php/apps/notifications/api/v2/notifications",
-            get(ocs_handler::handle_notifications_list),
+            get(notifications_handler::handle_list)
+                .delete(notifications_handler::handle_delete_all),
+        )
+        .route(
+            "/ocs/v2.php/apps/notifications/api/v2/notifications/{id}",
+            get(notifications_handler::handle_get).delete(notifications_handler::handle_delete),
         )
         .route(
             "/ocs/v2.php/apps/notifications/api/v2/push",
@@ -109,6 +124,48 @@ pub fn nextcloud_routes_with_state(state: Arc<AppState>) -> Router<Arc<AppState>
             "/ocs/v2.php/apps/files_sharing/api/v1/sharees",
             get(ocs_handler::handle_sharees_search),
         )
+        .route(
+            "/ocs/v2.php/apps/files_sharing/api/v1/shares",
+            get(shares_handler::handle_list_shares).post(shares_handler::handle_create_share),
+        )
+        .route(
+            "/ocs/v2.php/apps/files_sharing/api/v1/shares/{id}",
+            get(shares_handler::handle_get_share)
+                .put(shares_handler::handle_update_share)
+                .delete(shares_handler::handle_delete_share),
+        )
+        // End-to-end encryption
+        .route(
+            "/ocs/v2.php/apps/end_to_end_encryption/api/v1/public-key",
+            get(e2ee_handler::handle_get_public_keys)
+                .post(e2ee_handler::handle_create_public_key)
+                .delete(e2ee_handler::handle_delete_public_key),
+        )
+        .route(
+            "/ocs/v2.php/apps/end_to_end_encryption/api/v1/private-key",
+            get(e2ee_handler::handle_get_private_key)
+                .post(e2ee_handler::handle_store_private_key)
+                .delete(e2ee_handler::handle_delete_private_key),
+        )
+        .route(
+            "/ocs/v2.php/apps/end_to_end_encryption/api/v1/server-key",
+            get(e2ee_handler::handle_server_key),
+        )
+        .route(
+            "/ocs/v2.php/apps/end_to_end_encryption/api/v1/encrypted/{file_id}",
+            put(e2ee_handler::handle_set_encrypted).delete(e2ee_handler::handle_remove_encrypted),
+        )
+        .route(
+            "/ocs/v2.php/apps/end_to_end_encryption/api/v1/lock/{file_id}",
+            post(e2ee_handler::handle_lock).delete(e2ee_handler::handle_unlock),
+        )
+        .route(
+            "/ocs/v2.php/apps/end_to_end_encryption/api/v1/meta-data/{file_id}",
+            get(e2ee_handler::handle_get_metadata)
+                .post(e2ee_handler::handle_create_metadata)
+                .put(e2ee_handler::handle_update_metadata)
+                .delete(e2ee_handler::handle_delete_metadata),
+        )
         // Unified Search
         .route(
             "/ocs/v2.php/search/providers",
@@ -153,10 +210,30 @@ pub fn nextcloud_routes_with_state(state: Arc<AppState>) -> Router<Arc<AppState>
             "/remote.php/dav/trashbin/{user}",
             any(handle_dav_trashbin_root),
         )
+        // Versions WebDAV
+        .route(
+            "/remote.php/dav/versions/{user}/{*subpath}",
+            any(handle_dav_versions),
+        )
+        .route(
+            "/remote.php/dav/versions/{user}/",
+            any(handle_dav_versions_root),
+        )
+        .route(
+            "/remote.php/dav/versions/{user}",
+            any(handle_dav_versions_root),
+        )
         .route("/remote.php/webdav/{*subpath}", any(handle_legacy_webdav))
         .route("/remote.php/webdav/", any(handle_legacy_webdav_root))
-        .route("/remote.php/webdav", any(handle_legacy_webdav_root))
-        .layer(middleware::from_fn_with_state(state, basic_auth_middleware));
+        .route("/remote.php/webdav", any(handle_legacy_webdav_root));
+
+    if state.live_event_service.is_some() {
+        protected = protected.route(
+            "/index.php/apps/notify_push/pre_auth",
+            post(notify_push_handler::handle_pre_auth),
+        );
+    }
+    let protected = protected.layer(middleware::from_fn_with_state(state, basic_auth_middleware));
 
     Router::new().merge(public).merge(protected)
 }
@@ -264,6 +341,30 @@ async fn handle_dav_trashbin_root(
         .map_err(|e| e.into_response())
 }
 
+async fn handle_dav_versions(
+    State(state): State<Arc<AppState>>,
+    Path((url_user, subpath)): Path<(String, String)>,
+    user_ext: AuthUser,
+    req: Request<Body>,
+) -> Result<Response, Response> {
+    verify_url_user(&url_user, &user_ext)?;
+    versions_handler::handle_nc_versions(state, req, user_ext, subpath)
+        .await
+        .map_err(|e| e.into_response())
+}
+
+async fn handle_dav_versions_root(
+    State(state): State<Arc<AppState>>,
+    Path(url_user): Path<String>,
+    user_ext: AuthUser,
+    req: Request<Body>,
+) -> Result<Response, Response> {
+    verify_url_user(&url_user, &user_ext)?;
+    versions_handler::handle_nc_versions(state, req, user_ext, String::new())
+        .await
+        .map_err(|e| e.into_response())
+}
+
 /// `GET /index.php/204` — NC app connectivity check. Returns 204 No Content.
 async fn handle_connectivity_check() -> Response {
     Response::builder()
@@ -0,0 +1,984 @@
+//! Nextcloud OCS sharing API (`/ocs/v2.php/apps/files_sharing/api/v1/shares`).
+//!
+//! Public links (share type 3) map onto `ShareService`, shares with other
+//! users (share type 0) onto `UserShareService`.  Both use UUID share ids,
+//! so an id is looked up among the caller's links first.
+
+use axum::{
+    Json,
+    body::{self, Body},
+    extract::{Path, Query, State},
+    http::{Request, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{NaiveDate, Utc};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::folder_dto::FolderDto;
+use crate::application::dtos::share_dto::{
+    CreateShareDto, ShareDto, SharePermissionsDto, UpdateShareDto,
+};
+use crate::application::dtos::user_share_dto::{
+    CreateUserShareDto, UpdateUserShareDto, UserShareDto,
+};
+use crate::application::ports::file_ports::FileRetrievalUseCase;
+use crate::application::ports::inbound::FolderUseCase;
+use crate::application::ports::share_ports::ShareUseCase;
+use crate::application::ports::user_share_ports::UserShareUseCase;
+use crate::common::di::AppState;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::share::ShareItemType;
+use crate::interfaces::middleware::auth::{AuthUser, CurrentUser};
+use crate::interfaces::nextcloud::login_v2_handler::parse_form;
+use crate::interfaces::nextcloud::ocs_handler::{ocs_err, ocs_ok};
+use crate::interfaces::nextcloud::webdav_handler::{
+    nc_to_internal_path, resolve_file_id, resolve_folder_id, resolve_nc_path,
+};
+
+const SHARE_TYPE_USER: u8 = 0;
+const SHARE_TYPE_LINK: u8 = 3;
+
+// Nextcloud share permission bits
+const PERMISSION_READ: u32 = 1;
+const PERMISSION_UPDATE: u32 = 2;
+const PERMISSION_CREATE: u32 = 4;
+const PERMISSION_DELETE: u32 = 8;
+const PERMISSION_SHARE: u32 = 16;
+const PERMISSIONS_WRITE: u32 = PERMISSION_UPDATE | PERMISSION_CREATE | PERMISSION_DELETE;
+
+/// Links listed per page while collecting all of a user's links.
+const LINK_PAGE_SIZE: usize = 100;
+
+// ──────────────────── Handlers ────────────────────
+
+/// GET /ocs/v2.php/apps/files_sharing/api/v1/shares
+///
+/// Without parameters, lists the caller's shares.  `path` restricts the
+/// list to one item (or, with `subfiles=true`, to the items of a folder);
+/// `shared_with_me=true` lists the shares the caller received instead.
+pub async fn handle_list_shares(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let view = CallerView::load(&state, &user).await;
+
+    if flag(&params, "shared_with_me") {
+        let Some(users) = state.user_share_service.as_ref() else {
+            return Json(ocs_ok(200, json!([]))).into_response();
+        };
+        let shares = match users.list_shared_with_me(user.id).await {
+            Ok(shares) => shares,
+            Err(e) => return domain_error(e),
+        };
+        let wanted = params
+            .get("path")
+            .map(|p| format!("/{}", p.trim_matches('/')));
+        let mut data = Vec::new();
+        for share in shares {
+            let share = OcsShare::User(share);
+            if let Some(json) = share.to_json(&state, &user, &view).await
+                && wanted
+                    .as_ref()
+                    .is_none_or(|w| json["file_target"] == w.as_str())
+            {
+                data.push(json);
+            }
+        }
+        return Json(ocs_ok(200, Value::Array(data))).into_response();
+    }
+
+    // With `subfiles`, shares whose item lies directly in this folder
+    let mut parent_path = None;
+    let item = match params.get("path") {
+        Some(path) => {
+            let item = match item_at(&state, &user, path).await {
+                Ok(item) => item,
+                Err(response) => return response,
+            };
+            if flag(&params, "subfiles") {
+                if !item.is_folder {
+                    return ocs_error(StatusCode::BAD_REQUEST, "Not a directory");
+                }
+                parent_path = Some(item.path);
+                None
+            } else {
+                Some(item)
+            }
+        }
+        None => None,
+    };
+
+    let shares = match outgoing_shares(&state, user.id, item.as_ref()).await {
+        Ok(shares) => shares,
+        Err(e) => return domain_error(e),
+    };
+    let mut data = Vec::new();
+    for share in shares {
+        let (item_id, is_folder) = share.item();
+        let Some(item) = load_item(&state, item_id, is_folder).await else {
+            continue;
+        };
+        if let Some(parent) = &parent_path
+            && item.path.rsplit_once('/').map(|(p, _)| p) != Some(parent.as_str())
+        {
+            continue;
+        }
+        data.push(share.item_json(&item, &user, &view));
+    }
+    Json(ocs_ok(200, Value::Array(data))).into_response()
+}
+
+/// GET /ocs/v2.php/apps/files_sharing/api/v1/shares/{id}
+pub async fn handle_get_share(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    let share = match find_share(&state, &user, &id).await {
+        Ok(share) => share,
+        Err(e) => return domain_error(e),
+    };
+    let view = CallerView::load(&state, &user).await;
+    match share.to_json(&state, &user, &view).await {
+        Some(json) => Json(ocs_ok(200, json!([json]))).into_response(),
+        None => ocs_error(
+            StatusCode::NOT_FOUND,
+            "Wrong share ID, share does not exist",
+        ),
+    }
+}
+
+/// POST /ocs/v2.php/apps/files_sharing/api/v1/shares
+///
+/// Takes `path`, `shareType` and, depending on the type, `shareWith`,
+/// `permissions`, `publicUpload`, `password` and `expireDate`.
+pub async fn handle_create_share(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    req: Request<Body>,
+) -> Response {
+    let params = match read_params(req).await {
+        Ok(params) => params,
+        Err(response) => return response,
+    };
+    let Some(path) = params.get("path") else {
+        return ocs_error(
+            StatusCode::NOT_FOUND,
+            "Please specify a file or folder path",
+        );
+    };
+    if path.trim_matches('/').is_empty() {
+        return ocs_error(StatusCode::FORBIDDEN, "You cannot share your root folder");
+    }
+    let item = match item_at(&state, &user, path).await {
+        Ok(item) => item,
+        Err(response) => return response,
+    };
+    let permissions = match params.get("permissions").map(|p| p.parse::<u32>()) {
+        Some(Ok(bits)) => Some(bits),
+        Some(Err(_)) => return ocs_error(StatusCode::BAD_REQUEST, "Invalid permissions"),
+        None => None,
+    };
+
+    let share_type = params.get("shareType").and_then(|t| t.parse::<u8>().ok());
+    let share = match share_type {
+        Some(SHARE_TYPE_LINK) => {
+            let Some(links) = state.share_service.as_ref() else {
+                return ocs_error(
+                    StatusCode::FORBIDDEN,
+                    "Public link sharing is disabled by the administrator",
+                );
+            };
+            let bits = if flag(&params, "publicUpload") {
+                PERMISSION_READ | PERMISSIONS_WRITE
+            } else {
+                permissions.unwrap_or(PERMISSION_READ)
+            };
+            let (link_permissions, hide_contents) = link_permissions_from_ocs(bits, item.is_folder);
+            let (can_write, can_reshare) = match grantable(&state, user.id, &item).await {
+                Ok(grantable) => grantable,
+                Err(e) => return domain_error(e),
+            };
+            if !can_reshare {
+                return ocs_error(
+                    StatusCode::FORBIDDEN,
+                    "You are not allowed to share this item",
+                );
+            }
+            if link_permissions.write && !can_write {
+                return ocs_error(StatusCode::FORBIDDEN, "Cannot increase permissions");
+            }
+            let expires_at = match params.get("expireDate").map(|d| parse_expire_date(d)) {
+                Some(Ok(expires_at)) => expires_at,
+                Some(Err(())) => return invalid_date(),
+                None => None,
+            };
+            let dto = CreateShareDto {
+                item_id: item.id.clone(),
+                item_name: Some(item.name.clone()),
+                item_type: item_type(item.is_folder).to_string(),
+                password: params.get("password").filter(|p| !p.is_empty()).cloned(),
+                expires_at,
+                permissions: Some(link_permissions),
+                hide_contents,
+                max_upload_size: None,
+                max_downloads: None,
+            };
+            links
+                .create_shared_link(user.id, dto)
+                .await
+                .map(OcsShare::Link)
+        }
+        Some(SHARE_TYPE_USER) => {
+            let Some(users) = state.user_share_service.as_ref() else {
+                return ocs_error(StatusCode::FORBIDDEN, "Sharing is disabled");
+            };
+            let Some(recipient) = params.get("shareWith").filter(|r| !r.is_empty()) else {
+                return ocs_error(StatusCode::NOT_FOUND, "Please specify a valid user");
+            };
+            let bits =
+                permissions.unwrap_or(PERMISSION_READ | PERMISSIONS_WRITE | PERMISSION_SHARE);
+            let dto = CreateUserShareDto {
+                item_id: item.id.clone(),
+                item_type: item_type(item.is_folder).to_string(),
+                recipient: recipient.clone(),
+                permissions: Some(user_permissions_from_ocs(bits)),
+            };
+            users
+                .share_with_user(user.id, dto)
+                .await
+                .map(OcsShare::User)
+        }
+        _ => return ocs_error(StatusCode::BAD_REQUEST, "Unknown share type"),
+    };
+
+    match share {
+        Ok(share) => {
+            let view = CallerView::load(&state, &user).await;
+            Json(ocs_ok(200, share.item_json(&item, &user, &view))).into_response()
+        }
+        Err(e) => domain_error(e),
+    }
+}
+
+/// PUT /ocs/v2.php/apps/files_sharing/api/v1/shares/{id}
+///
+/// Links accept `permissions`, `publicUpload`, `password` (empty removes
+/// it) and `expireDate` (empty removes it); user shares `permissions`.
+pub async fn handle_update_share(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<String>,
+    req: Request<Body>,
+) -> Response {
+    let params = match read_params(req).await {
+        Ok(params) => params,
+        Err(response) => return response,
+    };
+    let permissions = match params.get("permissions").map(|p| p.parse::<u32>()) {
+        Some(Ok(bits)) => Some(bits),
+        Some(Err(_)) => return ocs_error(StatusCode::BAD_REQUEST, "Invalid permissions"),
+        None => None,
+    };
+    let share = match find_share(&state, &user, &id).await {
+        Ok(share) => share,
+        Err(e) => return domain_error(e),
+    };
+
+    let updated = match share {
+        OcsShare::Link(link) => {
+            let Some(links) = state.share_service.as_ref() else {
+                return ocs_error(
+                    StatusCode::NOT_FOUND,
+                    "Wrong share ID, share does not exist",
+                );
+            };
+            let is_folder = link.item_type == "folder";
+            let bits = match params.get("publicUpload").map(|v| v == "true" || v == "1") {
+                Some(true) => Some(PERMISSION_READ | PERMISSIONS_WRITE),
+                Some(false) => Some(PERMISSION_READ),
+                None => permissions,
+            };
+            let mut dto = UpdateShareDto {
+                password: params.get("password").cloned(),
+                expires_at: None,
+                permissions: None,
+                hide_contents: None,
+                max_upload_size: None,
+                max_downloads: None,
+            };
+            if let Some(bits) = bits {
+                let (link_permissions, hide_contents) = link_permissions_from_ocs(bits, is_folder);
+                if link_permissions.write {
+                    let Some(item) = load_item(&state, &link.item_id, is_folder).await else {
+                        return ocs_error(StatusCode::NOT_FOUND, "Shared item not found");
+                    };
+                    match grantable(&state, user.id, &item).await {
+                        Ok((true, _)) => {}
+                        Ok((false, _)) => {
+                            return ocs_error(StatusCode::FORBIDDEN, "Cannot increase permissions");
+                        }
+                        Err(e) => return domain_error(e),
+                    }
+                }
+                dto.permissions = Some(link_permissions);
+                dto.hide_contents = Some(hide_contents);
+            }
+            if let Some(date) = params.get("expireDate") {
+                dto.expires_at = match parse_expire_date(date) {
+                    Ok(expires_at) => Some(expires_at.unwrap_or(0)),
+                    Err(()) => return invalid_date(),
+                };
+            }
+            let Ok(link_id) = Uuid::parse_str(&link.id) else {
+                return ocs_error(
+                    StatusCode::NOT_FOUND,
+                    "Wrong share ID, share does not exist",
+                );
+            };
+            links
+                .update_shared_link(link_id, user.id, dto)
+                .await
+                .map(OcsShare::Link)
+        }
+        OcsShare::User(share) => {
+            let Some(users) = state.user_share_service.as_ref() else {
+                return ocs_error(
+                    StatusCode::NOT_FOUND,
+                    "Wrong share ID, share does not exist",
+                );
+            };
+            let dto = UpdateUserShareDto {
+                permissions: permissions.map(user_permissions_from_ocs),
+                mount_name: None,
+            };
+            users
+                .update_share(&share.id, user.id, dto)
+                .await
+                .map(OcsShare::User)
+        }
+    };
+
+    let updated = match updated {
+        Ok(share) => share,
+        Err(e) => return domain_error(e),
+    };
+    let view = CallerView::load(&state, &user).await;
+    match updated.to_json(&state, &user, &view).await {
+        Some(json) => Json(ocs_ok(200, json)).into_response(),
+        None => ocs_error(StatusCode::NOT_FOUND, "Shared item not found"),
+    }
+}
+
+/// DELETE /ocs/v2.php/apps/files_sharing/api/v1/shares/{id}
+///
+/// Recipients may delete shares they received, which unmounts them.
+pub async fn handle_delete_share(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    if let (Some(links), Ok(link_id)) = (state.share_service.as_ref(), Uuid::parse_str(&id)) {
+        match links.delete_shared_link(link_id, user.id).await {
+            Ok(()) => return Json(ocs_ok(200, json!([]))).into_response(),
+            Err(e) if e.kind == ErrorKind::NotFound => {}
+            Err(e) => return domain_error(e),
+        }
+    }
+    let result = match state.user_share_service.as_ref() {
+        Some(users) => users.delete_share(&id, user.id).await,
+        None => Err(DomainError::not_found("Share", &id)),
+    };
+    match result {
+        Ok(()) => Json(ocs_ok(200, json!([]))).into_response(),
+        Err(e) => domain_error(e),
+    }
+}
+
+// ──────────────────── Shares ────────────────────
+
+/// A share of either kind.
+enum OcsShare {
+    Link(ShareDto),
+    User(UserShareDto),
+}
+
+impl OcsShare {
+    /// Id and kind (`true` for folders) of the shared item.
+    fn item(&self) -> (&str, bool) {
+        match self {
+            OcsShare::Link(link) => (link.item_id.as_str(), link.item_type == "folder"),
+            OcsShare::User(share) => (share.item_id.as_str(), share.item_type == "folder"),
+        }
+    }
+
+    /// OCS representation, or `None` when the item is gone.
+    async fn to_json(
+        &self,
+        state: &AppState,
+        user: &CurrentUser,
+        view: &CallerView,
+    ) -> Option<Value> {
+        let (item_id, is_folder) = self.item();
+        let item = load_item(state, item_id, is_folder).await?;
+        Some(self.item_json(&item, user, view))
+    }
+
+    /// OCS representation of the share of `item`.
+    fn item_json(&self, item: &SharedItem, user: &CurrentUser, view: &CallerView) -> Value {
+        let mut json = json!({
+            "item_type": item_type(item.is_folder),
+            "mimetype": item.mime_type,
+            "item_source": item.file_id,
+            "file_source": item.file_id,
+            "file_parent": item.parent_file_id,
+            "storage": 1,
+            "has_preview": false,
+            "parent": null,
+            "note": "",
+            "label": "",
+            "mail_send": 0,
+            "hide_download": 0,
+            "attributes": null,
+            "can_delete": true,
+        });
+        let own_path = view
+            .nc_path(&item.path)
+            .unwrap_or_else(|| format!("/{}", item.name));
+
+        let fields = match self {
+            OcsShare::Link(link) => json!({
+                "id": link.id,
+                "share_type": SHARE_TYPE_LINK,
+                "uid_owner": user.username,
+                "displayname_owner": user.username,
+                "uid_file_owner": user.username,
+                "displayname_file_owner": user.username,
+                "storage_id": format!("home::{}", user.username),
+                "permissions": link_permissions(&link.permissions, link.hide_contents, item.is_folder),
+                "can_edit": true,
+                "stime": link.created_at,
+                "expiration": link.expires_at.map(format_expiration),
+                "token": link.token,
+                "url": link.url,
+                // Nextcloud puts the password hash here; clients only test it
+                "share_with": link.has_password.then_some("***"),
+                "share_with_displayname": "(Shared link)",
+                "password": link.has_password.then_some("***"),
+                "path": own_path,
+                "file_target": format!("/{}", item.name),
+            }),
+            OcsShare::User(share) => {
+                let received = share.recipient_id == user.id.to_string();
+                let target = format!("/{}", share.mount_name);
+                json!({
+                    "id": share.id,
+                    "share_type": SHARE_TYPE_USER,
+                    "uid_owner": share.shared_by_name,
+                    "displayname_owner": share.shared_by_name,
+                    "uid_file_owner": share.owner_name,
+                    "displayname_file_owner": share.owner_name,
+                    "storage_id": format!("home::{}", share.owner_name),
+                    "permissions": user_permissions(&share.permissions, item.is_folder),
+                    "can_edit": !received,
+                    "stime": share.created_at.timestamp(),
+                    "expiration": null,
+                    "token": null,
+                    "share_with": share.recipient_name,
+                    "share_with_displayname": share.recipient_name,
+                    "path": if received { target.clone() } else { own_path },
+                    "file_target": target,
+                })
+            }
+        };
+        if let (Some(json), Value::Object(fields)) = (json.as_object_mut(), fields) {
+            json.extend(fields);
+        }
+        json
+    }
+}
+
+/// The caller's links and user shares, of one item or of all items.
+async fn outgoing_shares(
+    state: &AppState,
+    user_id: Uuid,
+    item: Option<&SharedItem>,
+) -> Result<Vec<OcsShare>, DomainError> {
+    let mut shares = Vec::new();
+
+    if let Some(links) = state.share_service.as_ref() {
+        match item {
+            Some(item) => {
+                let item_type = if item.is_folder {
+                    ShareItemType::Folder
+                } else {
+                    ShareItemType::File
+                };
+                let found = links
+                    .get_shared_links_for_item(&item.id, &item_type, user_id)
+                    .await?;
+                shares.extend(found.into_iter().map(OcsShare::Link));
+            }
+            None => {
+                let now = Utc::now().timestamp().max(0) as u64;
+                let mut page = 1;
+                loop {
+                    let found = links
+                        .get_user_shared_links(user_id, page, LINK_PAGE_SIZE)
+                        .await?;
+                    let done = page >= found.pagination.total_pages;
+                    shares.extend(
+                        found
+                            .items
+                            .into_iter()
+                            .filter(|link| link.expires_at.is_none_or(|at| at > now))
+                            .map(OcsShare::Link),
+                    );
+                    if done {
+                        break;
+                    }
+                    page += 1;
+                }
+            }
+        }
+    }
+
+    if let Some(users) = state.user_share_service.as_ref() {
+        let item = item.map(|item| (item.id.as_str(), item_type(item.is_folder)));
+        shares.extend(
+            users
+                .list_outgoing(user_id, item)
+                .await?
+                .into_iter()
+                .map(OcsShare::User),
+        );
+    }
+    Ok(shares)
+}
+
+/// A share the caller created, covers their items, or received.
+async fn find_share(
+    state: &AppState,
+    user: &CurrentUser,
+    id: &str,
+) -> Result<OcsShare, DomainError> {
+    if let (Some(links), Ok(link_id)) = (state.share_service.as_ref(), Uuid::parse_str(id)) {
+        match links.get_shared_link(link_id, user.id).await {
+            Ok(link) => return Ok(OcsShare::Link(link)),
+            Err(e) if e.kind == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    if let Some(users) = state.user_share_service.as_ref() {
+        let mut shares = users.list_outgoing(user.id, None).await?;
+        shares.extend(users.list_shared_with_me(user.id).await?);
+        if let Some(share) = shares.into_iter().find(|s| s.id == id) {
+            return Ok(OcsShare::User(share));
+        }
+    }
+    Err(DomainError::not_found("Share", id))
+}
+
+/// Whether the caller may grant write access to, and reshare, an item.
+async fn grantable(
+    state: &AppState,
+    user_id: Uuid,
+    item: &SharedItem,
+) -> Result<(bool, bool), DomainError> {
+    match state.user_share_service.as_ref() {
+        Some(users) => {
+            let access = users
+                .caller_access(&item.id, item.is_folder, user_id)
+                .await?;
+            Ok((access.can_write, access.can_reshare))
+        }
+        // Without user shares, paths only lead to the caller's own items
+        None => Ok((true, true)),
+    }
+}
+
+// ──────────────────── Items ────────────────────
+
+/// A shared file or folder.
+struct SharedItem {
+    id: String,
+    is_folder: bool,
+    name: String,
+    /// Internal path, e.g. `My Folder - alice/Docs/a.txt`
+    path: String,
+    mime_type: String,
+    file_id: Option<i64>,
+    parent_file_id: Option<i64>,
+}
+
+impl SharedItem {
+    async fn from_folder(state: &AppState, folder: FolderDto) -> Self {
+        let file_ids = state.nextcloud.as_ref().map(|n| &n.file_ids);
+        let parent_file_id = match &folder.parent_id {
+            Some(parent_id) => resolve_folder_id(file_ids, parent_id).await,
+            None => None,
+        };
+        Self {
+            file_id: resolve_folder_id(file_ids, &folder.id).await,
+            parent_file_id,
+            id: folder.id,
+            is_folder: true,
+            name: folder.name,
+            path: folder.path,
+            mime_type: "httpd/unix-directory".to_string(),
+        }
+    }
+
+    async fn from_file(state: &AppState, file: FileDto) -> Self {
+        let file_ids = state.nextcloud.as_ref().map(|n| &n.file_ids);
+        let parent_file_id = match &file.folder_id {
+            Some(folder_id) => resolve_folder_id(file_ids, folder_id).await,
+            None => None,
+        };
+        Self {
+            file_id: resolve_file_id(file_ids, &file.id).await,
+            parent_file_id,
+            id: file.id,
+            is_folder: false,
+            name: file.name,
+            path: file.path,
+            mime_type: file.mime_type.to_string(),
+        }
+    }
+}
+
+async fn load_item(state: &AppState, item_id: &str, is_folder: bool) -> Option<SharedItem> {
+    if is_folder {
+        let folder = state
+            .applications
+            .folder_service
+            .get_folder(item_id)
+            .await
+            .ok()?;
+        Some(SharedItem::from_folder(state, folder).await)
+    } else {
+        let file = state
+            .applications
+            .file_retrieval_service
+            .get_file(item_id)
+            .await
+            .ok()?;
+        Some(SharedItem::from_file(state, file).await)
+    }
+}
+
+/// The item at an OCS `path`, relative to the caller's home folder.
+async fn item_at(state: &AppState, user: &CurrentUser, path: &str) -> Result<SharedItem, Response> {
+    let not_found = || {
+        ocs_error(
+            StatusCode::NOT_FOUND,
+            "Wrong path, file/folder does not exist",
+        )
+    };
+    let target = resolve_nc_path(state, user, path)
+        .await
+        .map_err(|_| not_found())?;
+    if let Ok(folder) = state
+        .applications
+        .folder_service
+        .get_folder_by_path(&target.internal)
+        .await
+    {
+        return Ok(SharedItem::from_folder(state, folder).await);
+    }
+    match state
+        .applications
+        .file_retrieval_service
+        .get_file_by_path(&target.internal)
+        .await
+    {
+        Ok(file) => Ok(SharedItem::from_file(state, file).await),
+        Err(_) => Err(not_found()),
+    }
+}
+
+/// Where items appear for the caller: in their home folder, or in the
+/// shares mounted there.
+struct CallerView {
+    home: String,
+    /// Internal path of each mounted item, and its mount name
+    mounts: Vec<(String, String)>,
+}
+
+impl CallerView {
+    async fn load(state: &AppState, user: &CurrentUser) -> Self {
+        let mut mounts = Vec::new();
+        if let Some(users) = state.user_share_service.as_ref() {
+            for mount in users.list_mounts(user.id).await.unwrap_or_default() {
+                if let Some(item) = load_item(state, &mount.item_id, mount.is_folder).await {
+                    mounts.push((item.path, mount.mount_name));
+                }
+            }
+        }
+        Self {
+            home: nc_to_internal_path(&user.username, "").unwrap_or_default(),
+            mounts,
+        }
+    }
+
+    /// OCS path (`/Docs/a.txt`) of an internal path the caller can see.
+    fn nc_path(&self, internal: &str) -> Option<String> {
+        let roots = std::iter::once((self.home.as_str(), None)).chain(
+            self.mounts
+                .iter()
+                .map(|(path, name)| (path.as_str(), Some(name.as_str()))),
+        );
+        for (root, mount_name) in roots {
+            let rest = if internal == root {
+                ""
+            } else if let Some(rest) = internal
+                .strip_prefix(root)
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                rest
+            } else {
+                continue;
+            };
+            let path: Vec<&str> = mount_name
+                .into_iter()
+                .chain(Some(rest).filter(|r| !r.is_empty()))
+                .collect();
+            return Some(format!("/{}", path.join("/")));
+        }
+        None
+    }
+}
+
+// ──────────────────── Conversions ────────────────────
+
+fn item_type(is_folder: bool) -> &'static str {
+    if is_folder { "folder" } else { "file" }
+}
+
+/// OCS permissions of a link: read, read-write for folders, or create
+/// only for upload-only links.
+fn link_permissions(
+    permissions: &SharePermissionsDto,
+    hide_contents: bool,
+    is_folder: bool,
+) -> u32 {
+    if hide_contents {
+        PERMISSION_CREATE
+    } else if permissions.write && is_folder {
+        PERMISSION_READ | PERMISSIONS_WRITE
+    } else {
+        PERMISSION_READ
+    }
+}
+
+/// Link permissions and the upload-only flag for OCS permissions.  Only
+/// folder links accept uploads.
+fn link_permissions_from_ocs(bits: u32, is_folder: bool) -> (SharePermissionsDto, bool) {
+    let write = is_folder && bits & PERMISSIONS_WRITE != 0;
+    let permissions = SharePermissionsDto {
+        read: true,
+        write,
+        reshare: false,
+    };
+    (permissions, write && bits & PERMISSION_READ == 0)
+}
+
+/// OCS permissions of a user share.
+fn user_permissions(permissions: &SharePermissionsDto, is_folder: bool) -> u32 {
+    let mut bits = PERMISSION_READ;
+    if permissions.write {
+        bits |= if is_folder {
+            PERMISSIONS_WRITE
+        } else {
+            PERMISSION_UPDATE
+        };
+    }
+    if permissions.reshare {
+        bits |= PERMISSION_SHARE;
+    }
+    bits
+}
+
+fn user_permissions_from_ocs(bits: u32) -> SharePermissionsDto {
+    SharePermissionsDto {
+        read: true,
+        write: bits & PERMISSIONS_WRITE != 0,
+        reshare: bits & PERMISSION_SHARE != 0,
+    }
+}
+
+/// Parses an `expireDate` (`YYYY-MM-DD`, optionally followed by a time).
+/// The share stays valid through that day (UTC); empty means no expiry.
+fn parse_expire_date(value: &str) -> Result<Option<u64>, ()> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let date =
+        NaiveDate::parse_from_str(value.get(..10).unwrap_or(value), "%Y-%m-%d").map_err(|_| ())?;
+    let end = date.succ_opt().ok_or(())?.and_hms_opt(0, 0, 0).ok_or(())?;
+    u64::try_from(end.and_utc().timestamp())
+        .map(Some)
+        .map_err(|_| ())
+}
+
+/// Formats an expiry from [`parse_expire_date`] the way Nextcloud does.
+fn format_expiration(expires_at: u64) -> String {
+    let last_second = i64::try_from(expires_at.saturating_sub(1)).unwrap_or(i64::MAX);
+    chrono::DateTime::from_timestamp(last_second, 0)
+        .map(|at| at.format("%Y-%m-%d 00:00:00").to_string())
+        .unwrap_or_default()
+}
+
+// ──────────────────── Requests and responses ────────────────────
+
+fn flag(params: &HashMap<String, String>, key: &str) -> bool {
+    params
+        .get(key)
+        .is_some_and(|value| value == "true" || value == "1")
+}
+
+/// Request parameters: the query string, overridden by the body, which
+/// clients send form-encoded or as JSON.
+async fn read_params(req: Request<Body>) -> Result<HashMap<String, String>, Response> {
+    read_params_limited(req, 64 * 1024).await
+}
+
+/// [`read_params`] for bodies of up to `limit` bytes.
+pub(super) async fn read_params_limited(
+    req: Request<Body>,
+    limit: usize,
+) -> Result<HashMap<String, String>, Response> {
+    let mut params = req.uri().query().map(parse_form).unwrap_or_default();
+    let is_json = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("json"));
+    let bytes = body::to_bytes(req.into_body(), limit)
+        .await
+        .map_err(|_| ocs_error(StatusCode::BAD_REQUEST, "Invalid request body"))?;
+    let body = String::from_utf8_lossy(&bytes);
+    if body.trim().is_empty() {
+        return Ok(params);
+    }
+
+    if is_json {
+        let fields: serde_json::Map<String, Value> = serde_json::from_str(&body)
+            .map_err(|_| ocs_error(StatusCode::BAD_REQUEST, "Invalid JSON body"))?;
+        for (key, value) in fields {
+            let value = match value {
+                Value::String(s) => s,
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => continue,
+            };
+            params.insert(key, value);
+        }
+    } else {
+        params.extend(parse_form(&body));
+    }
+    Ok(params)
+}
+
+/// OCS v2 failure, with the status code mirrored in the HTTP status.
+pub(super) fn ocs_error(status: StatusCode, message: &str) -> Response {
+    (status, Json(ocs_err(status.as_u16(), message))).into_response()
+}
+
+fn invalid_date() -> Response {
+    ocs_error(
+        StatusCode::BAD_REQUEST,
+        "Invalid date, date format must be YYYY-MM-DD",
+    )
+}
+
+fn domain_error(err: DomainError) -> Response {
+    let status = match err.kind {
+        ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        ErrorKind::AccessDenied | ErrorKind::AlreadyExists => StatusCode::FORBIDDEN,
+        ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+        _ => {
+            tracing::error!("OCS share request failed: {}", err);
+            return ocs_error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error");
+        }
+    };
+    ocs_error(status, &err.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permissions(write: bool, reshare: bool) -> SharePermissionsDto {
+        SharePermissionsDto {
+            read: true,
+            write,
+            reshare,
+        }
+    }
+
+    #[test]
+    fn link_permissions_round_trip() {
+        for (bits, is_folder) in [(1, false), (1, true), (15, true), (4, true)] {
+            let (perms, hide_contents) = link_permissions_from_ocs(bits, is_folder);
+            assert_eq!(link_permissions(&perms, hide_contents, is_folder), bits);
+        }
+        // File links never accept uploads
+        let (perms, hide_contents) = link_permissions_from_ocs(15, false);
+        assert!(!perms.write && !hide_contents);
+    }
+
+    #[test]
+    fn user_permissions_map_write_and_reshare() {
+        assert_eq!(user_permissions(&permissions(false, false), true), 1);
+        assert_eq!(user_permissions(&permissions(true, true), true), 31);
+        assert_eq!(user_permissions(&permissions(true, false), false), 3);
+        let perms = user_permissions_from_ocs(17);
+        assert!(!perms.write && perms.reshare);
+        assert!(user_permissions_from_ocs(3).write);
+    }
+
+    #[test]
+    fn expire_dates_cover_the_whole_day() {
+        let expires_at = parse_expire_date("2030-05-17").unwrap().unwrap();
+        assert_eq!(format_expiration(expires_at), "2030-05-17 00:00:00");
+        assert_eq!(
+            parse_expire_date("2030-05-17 00:00:00").unwrap(),
+            Some(expires_at)
+        );
+        assert_eq!(parse_expire_date("").unwrap(), None);
+        assert!(parse_expire_date("17.05.2030").is_err());
+    }
+
+    #[test]
+    fn nc_paths_follow_home_and_mounts() {
+        let view = CallerView {
+            home: "My Folder - bob".to_string(),
+            mounts: vec![(
+                "My Folder - alice/Team".to_string(),
+                "Team (alice)".to_string(),
+            )],
+        };
+        assert_eq!(view.nc_path("My Folder - bob").as_deref(), Some("/"));
+        assert_eq!(
+            view.nc_path("My Folder - bob/Docs/a.txt").as_deref(),
+            Some("/Docs/a.txt")
+        );
+        assert_eq!(
+            view.nc_path("My Folder - alice/Team/x").as_deref(),
+            Some("/Team (alice)/x")
+        );
+        assert_eq!(
+            view.nc_path("My Folder - alice/Team").as_deref(),
+            Some("/Team (alice)")
+        );
+        assert_eq!(view.nc_path("My Folder - bobby/a.txt"), None);
+        assert_eq!(view.nc_path("My Folder - alice/Private"), None);
+    }
+}
@@ -5,10 +5,13 @@ use axum::{
 };
 use std::sync::Arc;
 
-use crate::application::ports::file_ports::{FileRetrievalUseCase, FileUploadUseCase};
+use crate::application::ports::file_ports::{
+    FileManagementUseCase, FileRetrievalUseCase, FileUploadUseCase,
+};
 use crate::common::di::AppState;
 use crate::common::mime_detect::{filename_from_path, refine_content_type_from_file};
 use crate::infrastructure::services::audio_metadata_service::AudioMetadataService;
+use crate::interfaces::api::handlers::webdav_handler::{parse_oc_mtime, with_mtime_accepted};
 use crate::interfaces::errors::AppError;
 use crate::interfaces::middleware::auth::{AuthUser, CurrentUser};
 
@@ -117,11 +120,7 @@ async fn handle_assemble(
         .ok_or_else(|| AppError::bad_request("Missing Destination header"))?
         .to_string();
 
-    let oc_mtime = req
-        .headers()
-        .get("x-oc-mtime")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.parse::<i64>().ok());
+    let oc_mtime = parse_oc_mtime(req.headers());
 
     let dest_subpath = extract_files_subpath(&destination, &user.username)
         .ok_or_else(|| AppError::bad_request("Invalid Destination URL"))?;
@@ -186,6 +185,13 @@ async fn handle_assemble(
             .create_file(parent_internal, filename, &assembled, &content_type)
             .await
             .map_err(|e| AppError::internal_error(format!("Failed to create file: {}", e)))?;
+        if let Some(mtime) = oc_mtime {
+            state
+                .applications
+                .file_management_service
+                .set_modified_time(&dto.id, mtime)
+                .await?;
+        }
 
         // Extract audio metadata for supported audio files in background.
         if let Some(ref audio_service) = state.applications.audio_metadata_service
@@ -210,18 +216,22 @@ async fn handle_assemble(
     let _ = nc.chunked_uploads.cleanup(&user.username, upload_id).await;
 
     if let Some(tag) = etag {
-        return Ok(Response::builder()
-            .status(StatusCode::CREATED)
-            .header(header::ETAG, format!("\"{}\"", tag))
-            .header("oc-etag", format!("\"{}\"", tag))
-            .body(Body::empty())
-            .unwrap());
+        return Ok(with_mtime_accepted(
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header(header::ETAG, format!("\"{}\"", tag))
+                .header("oc-etag", format!("\"{}\"", tag)),
+            oc_mtime,
+        )
+        .body(Body::empty())
+        .unwrap());
     }
 
-    Ok(Response::builder()
-        .status(StatusCode::CREATED)
-        .body(Body::empty())
-        .unwrap())
+    Ok(
+        with_mtime_accepted(Response::builder().status(StatusCode::CREATED), oc_mtime)
+            .body(Body::empty())
+            .unwrap(),
+    )
 }
 
 /// DELETE — abort an upload session.
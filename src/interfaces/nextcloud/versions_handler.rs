@@ -0,0 +1,441 @@
+use axum::{
+    body::Body,
+    http::{HeaderName, Request, StatusCode, header},
+    response::Response,
+};
+use quick_xml::{
+    Writer,
+    events::{BytesEnd, BytesStart, Event},
+};
+use std::sync::Arc;
+
+use crate::application::dtos::file_version_dto::FileVersionDto;
+use crate::application::ports::file_version_ports::FileVersionUseCase;
+use crate::application::services::file_version_service::FileVersionService;
+use crate::common::di::AppState;
+use crate::interfaces::errors::AppError;
+use crate::interfaces::middleware::auth::{AuthUser, CurrentUser};
+use crate::interfaces::nextcloud::webdav_handler::write_text_element;
+
+const HEADER_DAV: HeaderName = HeaderName::from_static("dav");
+
+/// A parsed subpath below `/remote.php/dav/versions/{user}/`.
+#[derive(Debug, PartialEq, Eq)]
+enum VersionsPath {
+    /// `""` — the per-user versions home.
+    Home,
+    /// `versions` — the collection of all versioned files.
+    Versions,
+    /// `versions/{fileid}` — the versions of one file.
+    File(i64),
+    /// `versions/{fileid}/{version}` — a single version.
+    Version(i64, String),
+}
+
+fn parse_versions_path(subpath: &str) -> Result<VersionsPath, AppError> {
+    let mut segments = subpath
+        .trim_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty());
+    let path = match (segments.next(), segments.next(), segments.next()) {
+        (None, _, _) => VersionsPath::Home,
+        (Some("versions"), None, _) => VersionsPath::Versions,
+        (Some("versions"), Some(file_id), version) => {
+            let file_id = file_id
+                .parse::<i64>()
+                .map_err(|_| AppError::not_found("Unknown file id"))?;
+            match version {
+                None => VersionsPath::File(file_id),
+                Some(version) => VersionsPath::Version(file_id, version.to_string()),
+            }
+        }
+        _ => return Err(AppError::not_found("Resource not found")),
+    };
+    if segments.next().is_some() {
+        return Err(AppError::not_found("Resource not found"));
+    }
+    Ok(path)
+}
+
+/// Dispatch Nextcloud WebDAV versions request to the appropriate handler.
+///
+/// `subpath` is everything after `/remote.php/dav/versions/{user}/`.
+/// Files are addressed by the numeric ids handed out by
+/// `NextcloudFileIdService`, versions by their OxiCloud version id.
+pub async fn handle_nc_versions(
+    state: Arc<AppState>,
+    req: Request<Body>,
+    user: AuthUser,
+    subpath: String,
+) -> Result<Response<Body>, AppError> {
+    let method = req.method().clone();
+    if method.as_str() == "OPTIONS" {
+        return handle_options();
+    }
+
+    let path = parse_versions_path(&subpath)?;
+    match (method.as_str(), path) {
+        ("PROPFIND", VersionsPath::Home) | ("PROPFIND", VersionsPath::Versions) => {
+            handle_propfind_collection(&user, &subpath)
+        }
+        ("PROPFIND", VersionsPath::File(nc_id)) => {
+            let depth = req
+                .headers()
+                .get("depth")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("1")
+                .to_string();
+            handle_propfind_file(state, &user, nc_id, &depth).await
+        }
+        ("PROPFIND", VersionsPath::Version(nc_id, version_id)) => {
+            handle_propfind_version(state, &user, nc_id, &version_id).await
+        }
+        ("GET", VersionsPath::Version(nc_id, version_id)) => {
+            handle_get(state, &user, nc_id, &version_id, false).await
+        }
+        ("HEAD", VersionsPath::Version(nc_id, version_id)) => {
+            handle_get(state, &user, nc_id, &version_id, true).await
+        }
+        ("COPY" | "MOVE", VersionsPath::Version(nc_id, version_id)) => {
+            handle_restore(state, req, &user, nc_id, &version_id).await
+        }
+        _ => Ok(Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .body(Body::empty())
+            .unwrap()),
+    }
+}
+
+// ──────────────────── OPTIONS ────────────────────
+
+fn handle_options() -> Result<Response<Body>, AppError> {
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(HEADER_DAV, "1, 3")
+        .header(header::ALLOW, "OPTIONS, PROPFIND, GET, HEAD, COPY, MOVE")
+        .body(Body::empty())
+        .unwrap())
+}
+
+// ──────────────────── PROPFIND ────────────────────
+
+/// The home and `versions` collections cannot be enumerated — like Nextcloud,
+/// only their own entry is returned.
+fn handle_propfind_collection(
+    user: &CurrentUser,
+    subpath: &str,
+) -> Result<Response<Body>, AppError> {
+    let subpath = subpath.trim_matches('/');
+    let href = if subpath.is_empty() {
+        format!("{}/", versions_home_href(&user.username))
+    } else {
+        format!("{}/{}/", versions_home_href(&user.username), subpath)
+    };
+    multistatus_response(|xml| write_collection_response(xml, &href))
+}
+
+async fn handle_propfind_file(
+    state: Arc<AppState>,
+    user: &CurrentUser,
+    nc_id: i64,
+    depth: &str,
+) -> Result<Response<Body>, AppError> {
+    let (svc, file_id) = resolve(&state, nc_id).await?;
+    let versions = svc.list_versions(&file_id, user.id).await?;
+
+    let href = file_versions_href(&user.username, nc_id);
+    multistatus_response(|xml| {
+        write_collection_response(xml, &format!("{}/", href))?;
+        if depth != "0" {
+            for version in &versions {
+                write_version_response(xml, version, &href, &user.username)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+async fn handle_propfind_version(
+    state: Arc<AppState>,
+    user: &CurrentUser,
+    nc_id: i64,
+    version_id: &str,
+) -> Result<Response<Body>, AppError> {
+    let (svc, file_id) = resolve(&state, nc_id).await?;
+    let version = svc
+        .list_versions(&file_id, user.id)
+        .await?
+        .into_iter()
+        .find(|v| v.id == version_id)
+        .ok_or_else(|| AppError::not_found("Version not found"))?;
+
+    let href = file_versions_href(&user.username, nc_id);
+    multistatus_response(|xml| write_version_response(xml, &version, &href, &user.username))
+}
+
+// ──────────────────── GET / HEAD ────────────────────
+
+async fn handle_get(
+    state: Arc<AppState>,
+    user: &CurrentUser,
+    nc_id: i64,
+    version_id: &str,
+    head_only: bool,
+) -> Result<Response<Body>, AppError> {
+    let (svc, file_id) = resolve(&state, nc_id).await?;
+    let (version, stream) = svc
+        .get_version_content(&file_id, version_id, user.id)
+        .await?;
+
+    let builder = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, &version.mime_type)
+        .header(header::CONTENT_LENGTH, version.size)
+        .header(header::ETAG, format!("\"{}\"", version.etag))
+        .header(header::LAST_MODIFIED, version.modified_at.to_rfc2822());
+    let body = if head_only {
+        Body::empty()
+    } else {
+        Body::from_stream(stream)
+    };
+    Ok(builder.body(body).unwrap())
+}
+
+// ──────────────────── COPY / MOVE (restore) ────────────────────
+
+/// Nextcloud restores a version by moving it to
+/// `/remote.php/dav/versions/{user}/restore/target`.  COPY is accepted too;
+/// either way the version itself is kept (the replaced content becomes a new
+/// version).
+async fn handle_restore(
+    state: Arc<AppState>,
+    req: Request<Body>,
+    user: &CurrentUser,
+    nc_id: i64,
+    version_id: &str,
+) -> Result<Response<Body>, AppError> {
+    let destination = req
+        .headers()
+        .get("destination")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| AppError::bad_request("Missing Destination header"))?;
+    if !is_restore_destination(destination, &user.username) {
+        return Err(AppError::bad_request(
+            "Versions can only be restored to the restore target",
+        ));
+    }
+
+    let (svc, file_id) = resolve(&state, nc_id).await?;
+    svc.restore_version(&file_id, version_id, user.id).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .unwrap())
+}
+
+// ────────────── Helpers ──────────────
+
+/// Look up the versions service and the OxiCloud file id for a numeric
+/// Nextcloud file id.
+async fn resolve(
+    state: &AppState,
+    nc_id: i64,
+) -> Result<(Arc<FileVersionService>, String), AppError> {
+    let svc = state
+        .file_version_service
+        .clone()
+        .ok_or_else(|| AppError::not_found("File versions are disabled"))?;
+    let file_ids = state
+        .nextcloud
+        .as_ref()
+        .map(|nc| &nc.file_ids)
+        .ok_or_else(|| AppError::internal_error("Nextcloud services not available"))?;
+    let file_id = file_ids
+        .get_oxicloud_id(nc_id)
+        .await
+        .map_err(|_| AppError::not_found("File not found"))?;
+    Ok((svc, file_id))
+}
+
+fn versions_home_href(username: &str) -> String {
+    format!("/remote.php/dav/versions/{}", urlencoding::encode(username))
+}
+
+fn file_versions_href(username: &str, nc_id: i64) -> String {
+    format!("{}/versions/{}", versions_home_href(username), nc_id)
+}
+
+/// Whether a Destination header points at `/remote.php/dav/versions/{user}/restore/target`.
+///
+/// For full URLs the host is ignored — only the path is compared.
+fn is_restore_destination(dest: &str, username: &str) -> bool {
+    let path = if dest.starts_with("http://") || dest.starts_with("https://") {
+        match dest.split_once("://") {
+            Some((_, after_scheme)) => {
+                let path_start = after_scheme.find('/').unwrap_or(after_scheme.len());
+                &after_scheme[path_start..]
+            }
+            None => return false,
+        }
+    } else {
+        dest
+    };
+    let Ok(decoded) = urlencoding::decode(path) else {
+        return false;
+    };
+    let expected = format!("/remote.php/dav/versions/{}/restore/target", username);
+    decoded.trim_end_matches('/') == expected
+}
+
+// ────────────── Versions PROPFIND XML Generation ──────────────
+
+/// Build a 207 response around a multistatus body written by `write_body`.
+fn multistatus_response<F>(write_body: F) -> Result<Response<Body>, AppError>
+where
+    F: FnOnce(&mut Writer<&mut Vec<u8>>) -> Result<(), String>,
+{
+    let mut buf = Vec::new();
+    let mut xml = Writer::new(&mut buf);
+
+    let build = |xml: &mut Writer<&mut Vec<u8>>| -> Result<(), String> {
+        let mut ms = BytesStart::new("d:multistatus");
+        ms.push_attribute(("xmlns:d", "DAV:"));
+        ms.push_attribute(("xmlns:oc", "http://owncloud.org/ns"));
+        ms.push_attribute(("xmlns:nc", "http://nextcloud.org/ns"));
+        xml.write_event(Event::Start(ms))
+            .map_err(|e| e.to_string())?;
+        write_body(xml)?;
+        xml.write_event(Event::End(BytesEnd::new("d:multistatus")))
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    };
+    build(&mut xml)
+        .map_err(|e| AppError::internal_error(format!("XML generation failed: {}", e)))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(buf))
+        .unwrap())
+}
+
+/// Write a collection response entry.
+fn write_collection_response<W: std::io::Write>(
+    xml: &mut Writer<W>,
+    href: &str,
+) -> Result<(), String> {
+    xml.write_event(Event::Start(BytesStart::new("d:response")))
+        .map_err(|e| e.to_string())?;
+    write_text_element(xml, "d:href", href)?;
+
+    xml.write_event(Event::Start(BytesStart::new("d:propstat")))
+        .map_err(|e| e.to_string())?;
+    xml.write_event(Event::Start(BytesStart::new("d:prop")))
+        .map_err(|e| e.to_string())?;
+
+    xml.write_event(Event::Start(BytesStart::new("d:resourcetype")))
+        .map_err(|e| e.to_string())?;
+    xml.write_event(Event::Empty(BytesStart::new("d:collection")))
+        .map_err(|e| e.to_string())?;
+    xml.write_event(Event::End(BytesEnd::new("d:resourcetype")))
+        .map_err(|e| e.to_string())?;
+
+    xml.write_event(Event::End(BytesEnd::new("d:prop")))
+        .map_err(|e| e.to_string())?;
+    write_text_element(xml, "d:status", "HTTP/1.1 200 OK")?;
+    xml.write_event(Event::End(BytesEnd::new("d:propstat")))
+        .map_err(|e| e.to_string())?;
+
+    xml.write_event(Event::End(BytesEnd::new("d:response")))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Write a single version as a `<d:response>` element.
+fn write_version_response<W: std::io::Write>(
+    xml: &mut Writer<W>,
+    version: &FileVersionDto,
+    file_href: &str,
+    username: &str,
+) -> Result<(), String> {
+    xml.write_event(Event::Start(BytesStart::new("d:response")))
+        .map_err(|e| e.to_string())?;
+
+    let href = format!("{}/{}", file_href, version.id);
+    write_text_element(xml, "d:href", &href)?;
+
+    xml.write_event(Event::Start(BytesStart::new("d:propstat")))
+        .map_err(|e| e.to_string())?;
+    xml.write_event(Event::Start(BytesStart::new("d:prop")))
+        .map_err(|e| e.to_string())?;
+
+    xml.write_event(Event::Empty(BytesStart::new("d:resourcetype")))
+        .map_err(|e| e.to_string())?;
+    write_text_element(xml, "d:displayname", &version.name)?;
+    write_text_element(xml, "d:getlastmodified", &version.modified_at.to_rfc2822())?;
+    write_text_element(xml, "d:getcontentlength", &version.size.to_string())?;
+    write_text_element(xml, "d:getcontenttype", &version.mime_type)?;
+    write_text_element(xml, "d:getetag", &format!("\"{}\"", version.etag))?;
+    write_text_element(xml, "nc:version-author", username)?;
+    write_text_element(xml, "nc:has-preview", "false")?;
+
+    xml.write_event(Event::End(BytesEnd::new("d:prop")))
+        .map_err(|e| e.to_string())?;
+    write_text_element(xml, "d:status", "HTTP/1.1 200 OK")?;
+    xml.write_event(Event::End(BytesEnd::new("d:propstat")))
+        .map_err(|e| e.to_string())?;
+
+    xml.write_event(Event::End(BytesEnd::new("d:response")))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versions_path() {
+        assert_eq!(parse_versions_path("").unwrap(), VersionsPath::Home);
+        assert_eq!(
+            parse_versions_path("versions/").unwrap(),
+            VersionsPath::Versions
+        );
+        assert_eq!(
+            parse_versions_path("versions/42").unwrap(),
+            VersionsPath::File(42)
+        );
+        assert_eq!(
+            parse_versions_path("/versions/42/abc/").unwrap(),
+            VersionsPath::Version(42, "abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_versions_path_rejects_unknown() {
+        assert!(parse_versions_path("versions/not-a-number").is_err());
+        assert!(parse_versions_path("versions/42/abc/extra").is_err());
+        assert!(parse_versions_path("restore/target").is_err());
+    }
+
+    #[test]
+    fn test_is_restore_destination() {
+        assert!(is_restore_destination(
+            "https://cloud.example.com/remote.php/dav/versions/alice/restore/target",
+            "alice"
+        ));
+        assert!(is_restore_destination(
+            "/remote.php/dav/versions/alice/restore/target/",
+            "alice"
+        ));
+        assert!(!is_restore_destination(
+            "/remote.php/dav/versions/bob/restore/target",
+            "alice"
+        ));
+        assert!(!is_restore_destination(
+            "/remote.php/dav/files/alice/restore/target",
+            "alice"
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 use axum::{
     body::{self, Body},
-    http::{HeaderName, Request, StatusCode, header},
+    http::{HeaderMap, HeaderName, Method, Request, StatusCode, header},
     response::Response,
 };
 use bytes::Buf;
@@ -9,19 +9,21 @@ use quick_xml::{
     Writer,
     events::{BytesEnd, BytesStart, BytesText, Event},
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use crate::application::adapters::webdav_adapter::{PropFindRequest, WebDavAdapter};
+use crate::application::adapters::webdav_adapter::{PropFindRequest, PropValue, WebDavAdapter};
 use crate::application::ports::favorites_ports::FavoritesUseCase;
 use crate::application::ports::file_ports::{
     FileManagementUseCase, FileRetrievalUseCase, FileUploadUseCase,
 };
 use crate::application::ports::inbound::FolderUseCase;
 use crate::application::ports::trash_ports::TrashUseCase;
+use crate::application::ports::user_share_ports::{SharedAccess, SharedMount, UserShareUseCase};
 use crate::common::di::AppState;
 use crate::common::mime_detect::{filename_from_path, refine_content_type};
 use crate::infrastructure::services::audio_metadata_service::AudioMetadataService;
+use crate::interfaces::api::handlers::webdav_handler::{parse_oc_mtime, with_mtime_accepted};
 use crate::interfaces::errors::AppError;
 use crate::interfaces::middleware::auth::{AuthUser, CurrentUser};
 
@@ -62,6 +64,279 @@ pub fn nc_to_internal_path(username: &str, subpath: &str) -> Result<String, AppE
     Ok(format!("{}/{}", home, subpath))
 }
 
+/// A Nextcloud DAV subpath resolved against the user's own tree and the
+/// items other users shared with them.
+///
+/// Shares are mounted in the home folder under their mount name, so
+/// `Shared/report.pdf` may live in another user's tree.
+pub struct NcPath {
+    /// Internal path of the resource
+    pub internal: String,
+    /// Internal path `rest` is relative to: the home folder, or the shared
+    /// item for paths inside a mount
+    base: String,
+    /// Subpath relative to `base`
+    rest: String,
+    /// The share the path lies in, if any
+    pub mount: Option<SharedMount>,
+}
+
+impl NcPath {
+    /// True for the mounted item itself (`Shared`, not `Shared/a.txt`).
+    pub fn is_mount_root(&self) -> bool {
+        self.mount.is_some() && self.rest.is_empty()
+    }
+
+    /// Owner of the resource: the sharer inside a mount, the user otherwise.
+    fn owner_id(&self, user_id: uuid::Uuid) -> uuid::Uuid {
+        self.mount.as_ref().map_or(user_id, |m| m.access.owner_id)
+    }
+
+    /// Fails with 403 inside a read-only share.
+    fn require_write(&self) -> Result<(), AppError> {
+        match &self.mount {
+            Some(mount) if !mount.access.can_write => {
+                Err(AppError::forbidden("This shared item is read-only"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Internal path of the parent and the resource name.
+    fn split_parent(&self) -> (&str, &str) {
+        self.internal
+            .rsplit_once('/')
+            .unwrap_or(("", self.internal.as_str()))
+    }
+}
+
+/// Resolve a Nextcloud DAV subpath, following shares mounted in the home
+/// folder.  Mounts take precedence over the user's own items; mount names
+/// are chosen so they do not collide.
+pub async fn resolve_nc_path(
+    state: &AppState,
+    user: &CurrentUser,
+    subpath: &str,
+) -> Result<NcPath, AppError> {
+    let internal = nc_to_internal_path(&user.username, subpath)?;
+    let rest = subpath.trim_matches('/');
+    let own = NcPath {
+        internal,
+        base: nc_to_internal_path(&user.username, "")?,
+        rest: rest.to_string(),
+        mount: None,
+    };
+
+    let Some(shares) = state.user_share_service.as_ref() else {
+        return Ok(own);
+    };
+    if rest.is_empty() {
+        return Ok(own);
+    }
+    let (first, rest) = rest.split_once('/').unwrap_or((rest, ""));
+    let Some(mount) = shares.find_mount(user.id, first).await? else {
+        return Ok(own);
+    };
+
+    let base = if mount.is_folder {
+        state
+            .applications
+            .folder_service
+            .get_folder(&mount.item_id)
+            .await?
+            .path
+    } else if rest.is_empty() {
+        state
+            .applications
+            .file_retrieval_service
+            .get_file(&mount.item_id)
+            .await?
+            .path
+    } else {
+        return Err(AppError::not_found("Resource not found"));
+    };
+    let internal = if rest.is_empty() {
+        base.clone()
+    } else {
+        format!("{}/{}", base, rest)
+    };
+    Ok(NcPath {
+        internal,
+        base,
+        rest: rest.to_string(),
+        mount: Some(mount),
+    })
+}
+
+/// Items other users shared with `user_id`, named as mounted in the home
+/// folder, and the access granted to each.
+async fn mounted_items(
+    state: &AppState,
+    user_id: uuid::Uuid,
+) -> (Vec<FileDto>, Vec<FolderDto>, HashMap<String, SharedAccess>) {
+    let (mut files, mut folders, mut access) = (Vec::new(), Vec::new(), HashMap::new());
+    let Some(shares) = state.user_share_service.as_ref() else {
+        return (files, folders, access);
+    };
+    for mount in shares.list_mounts(user_id).await.unwrap_or_default() {
+        access.insert(mount.item_id.clone(), mount.access);
+        if mount.is_folder {
+            if let Ok(mut folder) = state
+                .applications
+                .folder_service
+                .get_folder(&mount.item_id)
+                .await
+            {
+                folder.name = mount.mount_name;
+                folders.push(folder);
+            }
+        } else if let Ok(mut file) = state
+            .applications
+            .file_retrieval_service
+            .get_file(&mount.item_id)
+            .await
+        {
+            file.name = mount.mount_name;
+            files.push(file);
+        }
+    }
+    (files, folders, access)
+}
+
+/// Sharing and encryption state of the resources in a PROPFIND response.
+#[derive(Default)]
+pub struct NcShareInfo {
+    /// Share types (0 = user, 3 = public link) of items the user shared
+    share_types: HashMap<String, Vec<u8>>,
+    /// Access to mounted shares listed in the home folder, by item id
+    mount_roots: HashMap<String, SharedAccess>,
+    /// Access to every other resource, when the listing lies in a share
+    inherited: Option<SharedAccess>,
+    /// Listed folders marked as end-to-end encrypted
+    encrypted: HashSet<String>,
+    /// Whether the listing lies in an encrypted folder, which makes every
+    /// resource in it encrypted
+    in_encrypted: bool,
+}
+
+impl NcShareInfo {
+    fn is_encrypted(&self, item_id: &str) -> bool {
+        self.in_encrypted || self.encrypted.contains(item_id)
+    }
+
+    /// Access to an item received through a share, and whether the item is
+    /// the mounted share itself.
+    fn received(&self, item_id: &str) -> Option<(SharedAccess, bool)> {
+        match self.mount_roots.get(item_id) {
+            Some(access) => Some((*access, true)),
+            None => self.inherited.map(|access| (access, false)),
+        }
+    }
+
+    fn write_share_types<W: std::io::Write>(
+        &self,
+        xml: &mut Writer<W>,
+        item_id: &str,
+    ) -> Result<(), String> {
+        let Some(types) = self.share_types.get(item_id) else {
+            return xml
+                .write_event(Event::Empty(BytesStart::new("oc:share-types")))
+                .xml_err();
+        };
+        xml.write_event(Event::Start(BytesStart::new("oc:share-types")))
+            .xml_err()?;
+        for share_type in types {
+            write_text_element(xml, "oc:share-type", &share_type.to_string())?;
+        }
+        xml.write_event(Event::End(BytesEnd::new("oc:share-types")))
+            .xml_err()
+    }
+}
+
+/// Collect the sharing state for `target` and the items listed with it.
+async fn share_info(
+    state: &AppState,
+    user_id: uuid::Uuid,
+    target: &NcPath,
+    target_id: &str,
+    mount_roots: HashMap<String, SharedAccess>,
+    item_ids: Vec<String>,
+) -> NcShareInfo {
+    let mut info = NcShareInfo {
+        mount_roots,
+        inherited: target.mount.as_ref().map(|m| m.access),
+        ..NcShareInfo::default()
+    };
+    if let Some(mount) = target.mount.as_ref().filter(|_| target.is_mount_root()) {
+        info.mount_roots.insert(target_id.to_string(), mount.access);
+    }
+
+    if let Some(users) = state.user_share_service.as_ref() {
+        for id in users
+            .shared_item_ids(user_id, &item_ids)
+            .await
+            .unwrap_or_default()
+        {
+            info.share_types.entry(id).or_default().push(0);
+        }
+    }
+    if let Some(links) = state.share_service.as_ref() {
+        for id in links
+            .shared_item_ids(user_id, &item_ids)
+            .await
+            .unwrap_or_default()
+        {
+            info.share_types.entry(id).or_default().push(3);
+        }
+    }
+    if let Some(e2ee) = state.e2ee_service.as_ref() {
+        info.in_encrypted = e2ee.is_encrypted(target_id).await.unwrap_or(false);
+        if !info.in_encrypted {
+            info.encrypted = e2ee
+                .encrypted_folder_ids(&item_ids)
+                .await
+                .unwrap_or_default();
+        }
+    }
+    info
+}
+
+/// `oc:permissions` and the numeric `ocs:share-permissions` of a resource.
+///
+/// The user's own items allow everything.  Inside a share, writing and
+/// resharing depend on the share; the mounted share itself can always be
+/// renamed, moved or removed from the home folder.
+fn nc_permissions(is_folder: bool, received: Option<(SharedAccess, bool)>) -> (String, u32) {
+    let Some((access, mount_root)) = received else {
+        // Read=1 + Update=2 + Create=4 + Delete=8 + Share=16 = 31 for folders,
+        // Read=1 + Update=2 + Delete=8 + Share=16 = 27 for files
+        return if is_folder {
+            ("RGDNVCK".to_string(), 31)
+        } else {
+            ("RGDNVW".to_string(), 27)
+        };
+    };
+
+    let mut letters = String::from("S");
+    let mut bits = 1;
+    if access.can_reshare {
+        letters.push('R');
+        bits |= 16;
+    }
+    if mount_root {
+        letters.push('M');
+    }
+    letters.push('G');
+    if access.can_write || mount_root {
+        letters.push_str("DNV");
+    }
+    if access.can_write {
+        letters.push_str(if is_folder { "CK" } else { "W" });
+        bits |= if is_folder { 2 | 4 | 8 } else { 2 };
+    }
+    (letters, bits)
+}
+
 /// Build the Nextcloud DAV href for a resource.
 ///
 /// Each path segment is URL-encoded individually so filenames with spaces,
@@ -94,6 +369,9 @@ pub async fn handle_nc_webdav(
     subpath: String,
 ) -> Result<Response<Body>, AppError> {
     let method = req.method().clone();
+    if matches!(method.as_str(), "PUT" | "MKCOL" | "DELETE" | "MOVE") {
+        require_e2ee_lock(&state, req.headers(), &method, &user, &subpath).await?;
+    }
     match method.as_str() {
         "OPTIONS" => handle_options(),
         "PROPFIND" => handle_propfind(state, req, &user, &subpath).await,
@@ -117,6 +395,46 @@ pub async fn handle_nc_webdav(
     }
 }
 
+/// Refuses to change the contents of an end-to-end encrypted folder unless
+/// the request carries the folder's lock token, so clients cannot write
+/// past each other's metadata updates.  Applies to the parent of the
+/// target and, for MOVE, of the destination.
+async fn require_e2ee_lock(
+    state: &AppState,
+    headers: &HeaderMap,
+    method: &Method,
+    user: &CurrentUser,
+    subpath: &str,
+) -> Result<(), AppError> {
+    let Some(e2ee) = state.e2ee_service.as_ref() else {
+        return Ok(());
+    };
+    let token = crate::interfaces::nextcloud::e2ee_handler::lock_token(headers, None);
+    let mut subpaths = vec![subpath.to_string()];
+    if method.as_str() == "MOVE"
+        && let Some(dest) = headers
+            .get("destination")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|d| extract_nc_subpath_from_dest(d, &user.username))
+    {
+        subpaths.push(dest);
+    }
+    for subpath in subpaths {
+        let target = resolve_nc_path(state, user, &subpath).await?;
+        let (parent, _) = target.split_parent();
+        // A parent that does not exist yet is not encrypted either
+        if let Ok(folder) = state
+            .applications
+            .folder_service
+            .get_folder_by_path(parent)
+            .await
+        {
+            e2ee.check_write(&folder.id, token).await?;
+        }
+    }
+    Ok(())
+}
+
 // ──────────────────── OPTIONS ────────────────────
 
 fn handle_options() -> Result<Response<Body>, AppError> {
@@ -160,28 +478,40 @@ async fn handle_propfind(
             .map_err(|e| AppError::bad_request(format!("Invalid PROPFIND XML: {}", e)))?
     };
 
-    let internal_path = nc_to_internal_path(&user.username, subpath)?;
+    let target = resolve_nc_path(&state, user, subpath).await?;
+    let internal_path = &target.internal;
     let folder_service = &state.applications.folder_service;
     let file_service = &state.applications.file_retrieval_service;
 
     // Try to resolve as folder first.
-    let folder_result = folder_service.get_folder_by_path(&internal_path).await;
+    let folder_result = folder_service.get_folder_by_path(internal_path).await;
 
-    if let Ok(folder) = folder_result {
+    if let Ok(mut folder) = folder_result {
         // It's a folder.
+        let mut mount_roots = HashMap::new();
         let (files, subfolders) = if depth != "0" {
-            let files = file_service
+            let mut files = file_service
                 .list_files(Some(&folder.id))
                 .await
                 .unwrap_or_default();
-            let subfolders = folder_service
+            let mut subfolders = folder_service
                 .list_folders(Some(&folder.id))
                 .await
                 .unwrap_or_default();
+            // The home folder also lists what other users shared
+            if target.rest.is_empty() && target.mount.is_none() {
+                let (shared_files, shared_folders, access) = mounted_items(&state, user.id).await;
+                files.extend(shared_files);
+                subfolders.extend(shared_folders);
+                mount_roots = access;
+            }
             (files, subfolders)
         } else {
             (vec![], vec![])
         };
+        if let Some(mount) = target.mount.as_ref().filter(|_| target.is_mount_root()) {
+            folder.name = mount.mount_name.clone();
+        }
 
         // Batch-check favorites for all items in this listing.
         let favorite_ids = if let Some(fav_svc) = state.favorites_service.as_ref() {
@@ -201,6 +531,13 @@ async fn handle_propfind(
             HashSet::new()
         };
 
+        let item_ids = std::iter::once(&folder.id)
+            .chain(files.iter().map(|f| &f.id))
+            .chain(subfolders.iter().map(|sf| &sf.id))
+            .cloned()
+            .collect();
+        let shares = share_info(&state, user.id, &target, &folder.id, mount_roots, item_ids).await;
+
         // Generate Nextcloud-aware XML.
         let nc = state.nextcloud.as_ref();
         let file_id_svc = nc.map(|n| &n.file_ids);
@@ -217,6 +554,7 @@ async fn handle_propfind(
             subpath,
             file_id_svc,
             &favorite_ids,
+            &shares,
         )
         .await
         .map_err(|e| AppError::internal_error(format!("XML generation failed: {}", e)))?;
@@ -229,8 +567,11 @@ async fn handle_propfind(
     }
 
     // Not a folder — try as a file.
-    let file_result = file_service.get_file_by_path(&internal_path).await;
-    if let Ok(file) = file_result {
+    let file_result = file_service.get_file_by_path(internal_path).await;
+    if let Ok(mut file) = file_result {
+        if let Some(mount) = target.mount.as_ref().filter(|_| target.is_mount_root()) {
+            file.name = mount.mount_name.clone();
+        }
         // Batch-check favorites for this single file.
         let favorite_ids = if let Some(fav_svc) = state.favorites_service.as_ref() {
             let items: Vec<(&str, &str)> = vec![(&file.id, "file")];
@@ -242,6 +583,16 @@ async fn handle_propfind(
             HashSet::new()
         };
 
+        let shares = share_info(
+            &state,
+            user.id,
+            &target,
+            &file.id,
+            HashMap::new(),
+            vec![file.id.clone()],
+        )
+        .await;
+
         let nc = state.nextcloud.as_ref();
         let file_id_svc = nc.map(|n| &n.file_ids);
 
@@ -257,6 +608,7 @@ async fn handle_propfind(
             subpath,
             file_id_svc,
             &favorite_ids,
+            &shares,
         )
         .await
         .map_err(|e| AppError::internal_error(format!("XML generation failed: {}", e)))?;
@@ -287,7 +639,7 @@ async fn handle_get(
             .unwrap());
     }
 
-    let internal_path = nc_to_internal_path(&user.username, subpath)?;
+    let internal_path = resolve_nc_path(&state, user, subpath).await?.internal;
     let file_service = &state.applications.file_retrieval_service;
     let folder_service = &state.applications.folder_service;
 
@@ -344,7 +696,7 @@ async fn handle_head(
             .unwrap());
     }
 
-    let internal_path = nc_to_internal_path(&user.username, subpath)?;
+    let internal_path = resolve_nc_path(&state, user, subpath).await?.internal;
     let file_service = &state.applications.file_retrieval_service;
     let folder_service = &state.applications.folder_service;
 
@@ -397,16 +749,38 @@ async fn handle_proppatch(
     // Parse oc:favorite value from PROPPATCH XML.
     let favorite_value = parse_proppatch_favorite(&body_str);
 
+    // A client-reported modification time becomes the file's mtime.
+    let client_mtime = WebDavAdapter::parse_proppatch(body_bytes.reader())
+        .ok()
+        .and_then(|(set, _)| set.iter().rev().filter_map(PropValue::client_mtime).next());
+    let target = resolve_nc_path(&state, user, subpath).await?;
+    let mut applied_mtime = None;
+    if let Some(mtime) = client_mtime
+        && target.require_write().is_ok()
+        && let Ok(file) = state
+            .applications
+            .file_retrieval_service
+            .get_file_by_path(&target.internal)
+            .await
+    {
+        state
+            .applications
+            .file_management_service
+            .set_modified_time(&file.id, mtime)
+            .await?;
+        applied_mtime = Some(mtime);
+    }
+
     if let Some(value) = favorite_value {
-        let internal_path = nc_to_internal_path(&user.username, subpath)?;
+        let internal_path = &target.internal;
         let file_service = &state.applications.file_retrieval_service;
         let folder_service = &state.applications.folder_service;
 
         // Determine item_id and item_type.
         let (item_id, item_type) =
-            if let Ok(file) = file_service.get_file_by_path(&internal_path).await {
+            if let Ok(file) = file_service.get_file_by_path(internal_path).await {
                 (file.id, "file")
-            } else if let Ok(folder) = folder_service.get_folder_by_path(&internal_path).await {
+            } else if let Ok(folder) = folder_service.get_folder_by_path(internal_path).await {
                 (folder.id, "folder")
             } else {
                 return Err(AppError::not_found("Resource not found"));
@@ -457,6 +831,10 @@ async fn handle_proppatch(
             .map_err(|e| AppError::internal_error(format!("XML: {}", e)))?;
         xml.write_event(Event::Empty(BytesStart::new("oc:favorite")))
             .map_err(|e| AppError::internal_error(format!("XML: {}", e)))?;
+        if applied_mtime.is_some() {
+            xml.write_event(Event::Empty(BytesStart::new("d:getlastmodified")))
+                .map_err(|e| AppError::internal_error(format!("XML: {}", e)))?;
+        }
         xml.write_event(Event::End(BytesEnd::new("d:prop")))
             .map_err(|e| AppError::internal_error(format!("XML: {}", e)))?;
         write_text_element(&mut xml, "d:status", "HTTP/1.1 200 OK")
@@ -469,9 +847,10 @@ async fn handle_proppatch(
             .map_err(|e| AppError::internal_error(format!("XML: {}", e)))?;
     }
 
-    Ok(Response::builder()
+    let builder = Response::builder()
         .status(StatusCode::MULTI_STATUS)
-        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8");
+    Ok(with_mtime_accepted(builder, applied_mtime)
         .body(Body::from(buf))
         .unwrap())
 }
@@ -514,7 +893,14 @@ async fn handle_put(
     user: &CurrentUser,
     subpath: &str,
 ) -> Result<Response<Body>, AppError> {
-    let internal_path = nc_to_internal_path(&user.username, subpath)?;
+    let target = resolve_nc_path(&state, user, subpath).await?;
+    target.require_write()?;
+    if target.is_mount_root() && target.mount.as_ref().is_some_and(|m| m.is_folder) {
+        return Err(AppError::conflict(
+            "A shared folder is mounted at this path",
+        ));
+    }
+    let internal_path = target.internal.clone();
     let file_service = &state.applications.file_retrieval_service;
     let upload_service = &state.applications.file_upload_service;
 
@@ -525,11 +911,7 @@ async fn handle_put(
         .unwrap_or("application/octet-stream")
         .to_string();
 
-    let oc_mtime = req
-        .headers()
-        .get("x-oc-mtime")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.parse::<i64>().ok());
+    let oc_mtime = parse_oc_mtime(req.headers());
 
     let max_upload = state.core.config.storage.max_upload_size;
     let body_bytes = body::to_bytes(req.into_body(), max_upload)
@@ -566,26 +948,31 @@ async fn handle_put(
             }
         }
 
-        return Ok(Response::builder()
-            .status(StatusCode::NO_CONTENT)
-            .header(header::ETAG, format!("\"{}\"", updated.etag))
-            .header("oc-etag", format!("\"{}\"", updated.etag))
-            .body(Body::empty())
-            .unwrap());
+        return Ok(with_mtime_accepted(
+            Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .header(header::ETAG, format!("\"{}\"", updated.etag))
+                .header("oc-etag", format!("\"{}\"", updated.etag)),
+            oc_mtime,
+        )
+        .body(Body::empty())
+        .unwrap());
     }
 
-    // Create new file — split subpath into parent dir and filename.
-    let (parent_subpath, filename) = match subpath.rsplit_once('/') {
-        Some((parent, name)) => (parent, name),
-        None => ("", subpath),
-    };
-
-    let parent_internal = nc_to_internal_path(&user.username, parent_subpath)?;
+    // Create new file — split the resolved path into parent dir and filename.
+    let (parent_internal, filename) = target.split_parent();
 
     let file_dto = upload_service
-        .create_file(&parent_internal, filename, &body_bytes, &content_type)
+        .create_file(parent_internal, filename, &body_bytes, &content_type)
         .await
         .map_err(|e| AppError::internal_error(format!("Failed to create file: {}", e)))?;
+    if let Some(mtime) = oc_mtime {
+        state
+            .applications
+            .file_management_service
+            .set_modified_time(&file_dto.id, mtime)
+            .await?;
+    }
 
     // Extract audio metadata for supported audio files in background.
     if let Some(ref audio_service) = state.applications.audio_metadata_service
@@ -605,7 +992,9 @@ async fn handle_put(
         .header(header::ETAG, format!("\"{}\"", file_dto.etag))
         .header("oc-etag", format!("\"{}\"", file_dto.etag));
 
-    Ok(builder.body(Body::empty()).unwrap())
+    Ok(with_mtime_accepted(builder, oc_mtime)
+        .body(Body::empty())
+        .unwrap())
 }
 
 // ──────────────────── MKCOL ────────────────────
@@ -618,11 +1007,12 @@ async fn handle_mkcol(
     use crate::application::dtos::folder_dto::CreateFolderDto;
 
     let folder_service = &state.applications.folder_service;
-    let internal_path = nc_to_internal_path(&user.username, subpath)?;
+    let target = resolve_nc_path(&state, user, subpath).await?;
+    let internal_path = &target.internal;
 
     // If the folder already exists, return 405 per RFC 4918 §9.3.1
     if folder_service
-        .get_folder_by_path(&internal_path)
+        .get_folder_by_path(internal_path)
         .await
         .is_ok()
     {
@@ -632,13 +1022,15 @@ async fn handle_mkcol(
             .unwrap());
     }
 
-    // Collect path segments that need to be created (walk from root to leaf)
-    let segments: Vec<&str> = subpath.split('/').filter(|s| !s.is_empty()).collect();
+    target.require_write()?;
+
+    // Collect path segments that need to be created (walk from the home
+    // folder, or the shared folder, to the leaf)
+    let segments: Vec<&str> = target.rest.split('/').filter(|s| !s.is_empty()).collect();
 
-    let user_root = nc_to_internal_path(&user.username, "")?;
-    let mut current_path = user_root.clone();
+    let mut current_path = target.base.clone();
     let mut parent_id = folder_service
-        .get_folder_by_path(&user_root)
+        .get_folder_by_path(&target.base)
         .await
         .map_err(|_| AppError::not_found("User root folder not found"))?
         .id
@@ -696,16 +1088,34 @@ async fn handle_delete(
     user: &CurrentUser,
     subpath: &str,
 ) -> Result<Response<Body>, AppError> {
-    let internal_path = nc_to_internal_path(&user.username, subpath)?;
+    let target = resolve_nc_path(&state, user, subpath).await?;
+
+    // Deleting a mounted share removes it from the user's tree only
+    if let (true, Some(mount), Some(shares)) = (
+        target.is_mount_root(),
+        target.mount.as_ref(),
+        state.user_share_service.as_ref(),
+    ) {
+        shares.delete_share(&mount.share_id, user.id).await?;
+        return Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .unwrap());
+    }
+    target.require_write()?;
+
+    let internal_path = &target.internal;
+    let owner_id = target.owner_id(user.id);
     let folder_service = &state.applications.folder_service;
     let file_service = &state.applications.file_retrieval_service;
 
     // Prefer soft-delete (move to trash) when trash service is available.
     // This is what Nextcloud clients expect — items appear in the trashbin.
+    // Items in a shared folder go to the trashbin of its owner.
     if let Some(trash_svc) = state.trash_service.as_ref() {
-        if let Ok(folder) = folder_service.get_folder_by_path(&internal_path).await {
+        if let Ok(folder) = folder_service.get_folder_by_path(internal_path).await {
             trash_svc
-                .move_to_trash(&folder.id, "folder", user.id)
+                .move_to_trash(&folder.id, "folder", owner_id)
                 .await
                 .map_err(|e| AppError::internal_error(format!("Failed to trash folder: {}", e)))?;
             return Ok(Response::builder()
@@ -713,9 +1123,9 @@ async fn handle_delete(
                 .body(Body::empty())
                 .unwrap());
         }
-        if let Ok(file) = file_service.get_file_by_path(&internal_path).await {
+        if let Ok(file) = file_service.get_file_by_path(internal_path).await {
             trash_svc
-                .move_to_trash(&file.id, "file", user.id)
+                .move_to_trash(&file.id, "file", owner_id)
                 .await
                 .map_err(|e| AppError::internal_error(format!("Failed to trash file: {}", e)))?;
             return Ok(Response::builder()
@@ -729,7 +1139,7 @@ async fn handle_delete(
     // Fallback: hard delete when trash service is not available.
     let file_mgmt = &state.applications.file_management_service;
 
-    if let Ok(folder) = folder_service.get_folder_by_path(&internal_path).await {
+    if let Ok(folder) = folder_service.get_folder_by_path(internal_path).await {
         folder_service
             .delete_folder(&folder.id, user.id)
             .await
@@ -741,7 +1151,7 @@ async fn handle_delete(
             .unwrap());
     }
 
-    if let Ok(file) = file_service.get_file_by_path(&internal_path).await {
+    if let Ok(file) = file_service.get_file_by_path(internal_path).await {
         file_mgmt
             .delete_file(&file.id)
             .await
@@ -775,26 +1185,56 @@ async fn handle_move(
     let dest_subpath = extract_nc_subpath_from_dest(&destination, &user.username)
         .ok_or_else(|| AppError::bad_request("Invalid Destination URL"))?;
 
-    let src_internal = nc_to_internal_path(&user.username, subpath)?;
+    let src = resolve_nc_path(&state, user, subpath).await?;
+    let dest = resolve_nc_path(&state, user, &dest_subpath).await?;
+
+    // Moving a mounted share within the home folder renames the mount
+    if let (true, Some(mount), Some(shares)) = (
+        src.is_mount_root(),
+        src.mount.as_ref(),
+        state.user_share_service.as_ref(),
+    ) {
+        if dest.mount.is_some() || dest.rest.contains('/') {
+            return Err(AppError::forbidden(
+                "A shared item can only be renamed within the home folder",
+            ));
+        }
+        use crate::application::dtos::user_share_dto::UpdateUserShareDto;
+        shares
+            .update_share(
+                &mount.share_id,
+                user.id,
+                UpdateUserShareDto {
+                    permissions: None,
+                    mount_name: Some(dest.rest.clone()),
+                },
+            )
+            .await?;
+        return Ok(Response::builder()
+            .status(StatusCode::CREATED)
+            .body(Body::empty())
+            .unwrap());
+    }
+
+    // Items never change owner by moving in or out of a share
+    src.require_write()?;
+    dest.require_write()?;
+    if dest.is_mount_root() || src.owner_id(user.id) != dest.owner_id(user.id) {
+        return Err(AppError::forbidden(
+            "Cannot move items between your files and a shared folder",
+        ));
+    }
+
+    let src_internal = &src.internal;
+    let (src_parent_internal, _) = src.split_parent();
+    let (dest_parent_internal, dest_name) = dest.split_parent();
     let folder_service = &state.applications.folder_service;
     let file_service = &state.applications.file_retrieval_service;
     let file_mgmt = &state.applications.file_management_service;
 
     // Try as file first.
-    if let Ok(file) = file_service.get_file_by_path(&src_internal).await {
-        let (dest_parent_sub, dest_name) = match dest_subpath.rsplit_once('/') {
-            Some((parent, name)) => (parent, name),
-            None => ("", dest_subpath.as_str()),
-        };
-        let dest_parent_internal = nc_to_internal_path(&user.username, dest_parent_sub)?;
-
-        // Rename if only the name changes (same parent).
-        let src_parent_sub = match subpath.rsplit_once('/') {
-            Some((parent, _)) => parent,
-            None => "",
-        };
-
-        if src_parent_sub == dest_parent_sub {
+    if let Ok(file) = file_service.get_file_by_path(src_internal).await {
+        if src_parent_internal == dest_parent_internal {
             // Same parent → rename.
             file_mgmt
                 .rename_file(&file.id, dest_name)
@@ -803,7 +1243,7 @@ async fn handle_move(
         } else {
             // Different parent → move.
             let dest_parent = folder_service
-                .get_folder_by_path(&dest_parent_internal)
+                .get_folder_by_path(dest_parent_internal)
                 .await
                 .map_err(|_| AppError::not_found("Destination folder not found"))?;
 
@@ -822,9 +1262,8 @@ async fn handle_move(
         }
 
         // Return ETag and OC-ETag so Nextcloud clients can track the moved file.
-        let dest_internal = nc_to_internal_path(&user.username, &dest_subpath)?;
         let mut builder = Response::builder().status(StatusCode::CREATED);
-        if let Ok(moved) = file_service.get_file_by_path(&dest_internal).await {
+        if let Ok(moved) = file_service.get_file_by_path(&dest.internal).await {
             builder = builder
                 .header(header::ETAG, format!("\"{}\"", moved.id))
                 .header("oc-etag", format!("\"{}\"", moved.id));
@@ -834,19 +1273,8 @@ async fn handle_move(
     }
 
     // Try as folder.
-    if let Ok(folder) = folder_service.get_folder_by_path(&src_internal).await {
-        let (dest_parent_sub, dest_name) = match dest_subpath.rsplit_once('/') {
-            Some((parent, name)) => (parent, name),
-            None => ("", dest_subpath.as_str()),
-        };
-        let dest_parent_internal = nc_to_internal_path(&user.username, dest_parent_sub)?;
-
-        let src_parent_sub = match subpath.rsplit_once('/') {
-            Some((parent, _)) => parent,
-            None => "",
-        };
-
-        if src_parent_sub == dest_parent_sub {
+    if let Ok(folder) = folder_service.get_folder_by_path(src_internal).await {
+        if src_parent_internal == dest_parent_internal {
             // Same parent → rename.
             use crate::application::dtos::folder_dto::RenameFolderDto;
             folder_service
@@ -862,7 +1290,7 @@ async fn handle_move(
         } else {
             // Different parent → move.
             let dest_parent = folder_service
-                .get_folder_by_path(&dest_parent_internal)
+                .get_folder_by_path(dest_parent_internal)
                 .await
                 .map_err(|_| AppError::not_found("Destination parent not found"))?;
 
@@ -945,6 +1373,7 @@ async fn write_nc_multistatus<W: std::io::Write>(
     subpath: &str,
     file_id_svc: Option<&Arc<NextcloudFileIdService>>,
     favorite_ids: &HashSet<String>,
+    shares: &NcShareInfo,
 ) -> Result<(), String> {
     let mut xml = Writer::new(writer);
 
@@ -969,6 +1398,7 @@ async fn write_nc_multistatus<W: std::io::Write>(
             oc_id.as_deref(),
             username,
             favorite_ids,
+            shares,
         )?;
     }
 
@@ -999,6 +1429,7 @@ async fn write_nc_multistatus<W: std::io::Write>(
                 oc_id.as_deref(),
                 username,
                 favorite_ids,
+                shares,
             )?;
         }
 
@@ -1020,6 +1451,7 @@ async fn write_nc_multistatus<W: std::io::Write>(
                 oc_id.as_deref(),
                 username,
                 favorite_ids,
+                shares,
             )?;
         }
     }
@@ -1030,6 +1462,7 @@ async fn write_nc_multistatus<W: std::io::Write>(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn write_folder_response<W: std::io::Write>(
     xml: &mut Writer<W>,
     folder: &FolderDto,
@@ -1038,6 +1471,7 @@ pub fn write_folder_response<W: std::io::Write>(
     oc_id: Option<&str>,
     owner: &str,
     favorite_ids: &HashSet<String>,
+    shares: &NcShareInfo,
 ) -> Result<(), String> {
     xml.write_event(Event::Start(BytesStart::new("d:response")))
         .xml_err()?;
@@ -1080,14 +1514,22 @@ pub fn write_folder_response<W: std::io::Write>(
     if let Some(oid) = oc_id {
         write_text_element(xml, "oc:id", oid)?;
     }
-    write_text_element(xml, "oc:permissions", "RGDNVCK")?;
-    // Numeric share-permissions bitmask: Read=1 + Update=2 + Create=4 + Delete=8 + Share=16 = 31
-    write_text_element(xml, "ocs:share-permissions", "31")?;
+    let (permissions, share_permissions) = nc_permissions(true, shares.received(&folder.id));
+    write_text_element(xml, "oc:permissions", &permissions)?;
+    write_text_element(xml, "ocs:share-permissions", &share_permissions.to_string())?;
     write_text_element(xml, "oc:size", "0")?;
     write_text_element(xml, "oc:owner-id", owner)?;
     write_text_element(xml, "oc:owner-display-name", owner)?;
     write_text_element(xml, "nc:has-preview", "false")?;
-    write_text_element(xml, "nc:is-encrypted", "0")?;
+    write_text_element(
+        xml,
+        "nc:is-encrypted",
+        if shares.is_encrypted(&folder.id) {
+            "1"
+        } else {
+            "0"
+        },
+    )?;
     write_text_element(xml, "nc:mount-type", "")?;
 
     let is_fav = if favorite_ids.contains(&folder.id) {
@@ -1096,9 +1538,7 @@ pub fn write_folder_response<W: std::io::Write>(
         "0"
     };
     write_text_element(xml, "oc:favorite", is_fav)?;
-    // Empty share-types (no sharing API yet)
-    xml.write_event(Event::Empty(BytesStart::new("oc:share-types")))
-        .xml_err()?;
+    shares.write_share_types(xml, &folder.id)?;
 
     xml.write_event(Event::End(BytesEnd::new("d:prop")))
         .xml_err()?;
@@ -1112,6 +1552,7 @@ pub fn write_folder_response<W: std::io::Write>(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn write_file_response<W: std::io::Write>(
     xml: &mut Writer<W>,
     file: &FileDto,
@@ -1120,6 +1561,7 @@ pub fn write_file_response<W: std::io::Write>(
     oc_id: Option<&str>,
     owner: &str,
     favorite_ids: &HashSet<String>,
+    shares: &NcShareInfo,
 ) -> Result<(), String> {
     xml.write_event(Event::Start(BytesStart::new("d:response")))
         .xml_err()?;
@@ -1156,9 +1598,9 @@ pub fn write_file_response<W: std::io::Write>(
     if let Some(oid) = oc_id {
         write_text_element(xml, "oc:id", oid)?;
     }
-    write_text_element(xml, "oc:permissions", "RGDNVW")?;
-    // Numeric share-permissions bitmask: Read=1 + Update=2 + Delete=8 + Share=16 = 27
-    write_text_element(xml, "ocs:share-permissions", "27")?;
+    let (permissions, share_permissions) = nc_permissions(false, shares.received(&file.id));
+    write_text_element(xml, "oc:permissions", &permissions)?;
+    write_text_element(xml, "ocs:share-permissions", &share_permissions.to_string())?;
     write_text_element(xml, "oc:size", &file.size.to_string())?;
     write_text_element(xml, "oc:owner-id", owner)?;
     write_text_element(xml, "oc:owner-display-name", owner)?;
@@ -1169,22 +1611,23 @@ pub fn write_file_response<W: std::io::Write>(
         "0"
     };
     write_text_element(xml, "oc:favorite", is_fav)?;
-    // Empty share-types (no sharing API yet)
-    xml.write_event(Event::Empty(BytesStart::new("oc:share-types")))
-        .xml_err()?;
-
-    // Check if file is an image that can have previews
-    let has_preview = matches!(
-        &*file.mime_type,
-        "image/jpeg" | "image/jpg" | "image/png" | "image/gif" | "image/webp"
-    );
+    shares.write_share_types(xml, &file.id)?;
+
+    // Check if file is an image that can have previews; the server cannot
+    // read encrypted files
+    let encrypted = shares.is_encrypted(&file.id);
+    let has_preview = !encrypted
+        && matches!(
+            &*file.mime_type,
+            "image/jpeg" | "image/jpg" | "image/png" | "image/gif" | "image/webp"
+        );
     write_text_element(
         xml,
         "nc:has-preview",
         if has_preview { "true" } else { "false" },
     )?;
 
-    write_text_element(xml, "nc:is-encrypted", "0")?;
+    write_text_element(xml, "nc:is-encrypted", if encrypted { "1" } else { "0" })?;
     write_text_element(xml, "nc:mount-type", "")?;
     write_text_element(xml, "nc:creation_time", &file.created_at.to_string())?;
     write_text_element(xml, "nc:upload_time", &file.modified_at.to_string())?;
@@ -1344,4 +1787,45 @@ mod tests {
     fn test_timestamp_overflow_returns_zero() {
         assert_eq!(timestamp_to_i64(u64::MAX), 0);
     }
+
+    // ── nc_permissions ──
+
+    fn access(can_write: bool, can_reshare: bool) -> SharedAccess {
+        SharedAccess {
+            owner_id: uuid::Uuid::new_v4(),
+            can_write,
+            can_reshare,
+        }
+    }
+
+    #[test]
+    fn test_own_items_allow_everything() {
+        assert_eq!(nc_permissions(true, None), ("RGDNVCK".to_string(), 31));
+        assert_eq!(nc_permissions(false, None), ("RGDNVW".to_string(), 27));
+    }
+
+    #[test]
+    fn test_read_only_share_permissions() {
+        assert_eq!(
+            nc_permissions(false, Some((access(false, false), false))),
+            ("SG".to_string(), 1)
+        );
+        // The mount itself can still be renamed or removed
+        assert_eq!(
+            nc_permissions(true, Some((access(false, false), true))),
+            ("SMGDNV".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn test_writable_share_permissions() {
+        assert_eq!(
+            nc_permissions(true, Some((access(true, true), false))),
+            ("SRGDNVCK".to_string(), 31)
+        );
+        assert_eq!(
+            nc_permissions(false, Some((access(true, false), false))),
+            ("SGDNVW".to_string(), 3)
+        );
+    }
 }
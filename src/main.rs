@@ -264,11 +264,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     if config.features.enable_auth {
         use interfaces::api::handlers::auth_handler::{
-            auth_protected_routes, auth_public_routes, login_route, refresh_route, register_route,
-            setup_route,
+            account_recovery_route, auth_protected_routes, auth_public_routes, login_route,
+            refresh_route, register_route, setup_route,
         };
         use oxicloud::interfaces::api::handlers::app_password_handler;
         use oxicloud::interfaces::api::handlers::device_auth_handler;
+        use oxicloud::interfaces::api::handlers::session_handler;
         use oxicloud::interfaces::middleware::auth::auth_middleware;
         use oxicloud::interfaces::middleware::csrf::csrf_middleware;
         use oxicloud::interfaces::middleware::rate_limit::{
@@ -315,6 +316,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 rate_limit_register,
             ))
             .with_state(app_state.clone());
+        let auth_recovery = account_recovery_route()
+            .layer(axum::middleware::from_fn_with_state(
+                register_limiter.clone(),
+                rate_limit_register,
+            ))
+            .with_state(app_state.clone());
         let auth_refresh = refresh_route()
             .layer(axum::middleware::from_fn_with_state(
                 refresh_limiter.clone(),
@@ -339,6 +346,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 auth_middleware,
             ))
             .with_state(app_state.clone());
+        // Session list and revocation — require auth + CSRF
+        let sessions_protected = session_handler::session_routes()
+            .layer(axum::middleware::from_fn(csrf_middleware))
+            .layer(axum::middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .with_state(app_state.clone());
         // One-time setup route — public, rate-limited like register
         let setup_router = setup_route()
             .layer(axum::middleware::from_fn_with_state(
@@ -389,12 +404,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .nest("/api/auth", auth_login)
             .nest("/api/auth", auth_register)
             .nest("/api/auth", auth_refresh)
+            // Password reset and address verification links
+            .nest("/api/auth", auth_recovery)
             // Public auth endpoints (status, OIDC)
             .nest("/api/auth", auth_public)
             // Protected auth endpoints (/me, /change-password, /logout)
             .nest("/api/auth", auth_protected)
             // App password management (create, list, revoke)
             .nest("/api/auth", app_pw_protected)
+            // Active sessions (list, overview, revoke)
+            .nest("/api/auth", sessions_protected)
             // One-time setup endpoint — public, rate-limited
             .nest("/api", setup_router)
             // Device Auth Grant public endpoints (authorize + token polling)
@@ -1,3 +1,5 @@
+import { getCsrfHeaders } from './csrf.js';
+import { formatDateTime } from './formatters.js';
 import { i18n } from './i18n.js';
 
 /**
@@ -5,7 +7,8 @@ import { i18n } from './i18n.js';
  *
  * Centralised notification system that renders items inside the bell dropdown
  * in the top-bar. Upload progress, quota errors, and general messages all
- * go through this module.
+ * go through this module. Notifications stored on the server (shares,
+ * storage quota, expiring links) are polled and listed below them.
  *
  * Public API (exported as `notifications`):
  *   addUploadBatch(totalFiles)       → batchId
@@ -30,6 +33,17 @@ const notifications = (() => {
     /* ── state ──────────────────────────────────────────────── */
     let _badgeCount = 0;
     let _batchSeq = 0;
+    let _serverUnread = 0;
+    let _serverEnabled = true;
+
+    const SERVER_POLL_MS = 2 * 60 * 1000;
+
+    /** @type {Record<String,[String,String]>} icon and icon class per notification kind */
+    const SERVER_ICONS = {
+        share_received: ['fa-share-alt', 'upload'],
+        quota_warning: ['fa-exclamation-triangle', 'error'],
+        share_expiring: ['fa-clock', 'upload']
+    };
 
     /** @type {Record<String,BatchNotification>} */
     const _batches = {};
@@ -73,6 +87,12 @@ const notifications = (() => {
                 clear();
             });
         }
+
+        _loadServerNotifications();
+        setInterval(_loadServerNotifications, SERVER_POLL_MS);
+        document.addEventListener('visibilitychange', () => {
+            if (document.visibilityState === 'visible') _loadServerNotifications();
+        });
     }
 
     function close() {
@@ -90,14 +110,16 @@ const notifications = (() => {
     }
     function _clearBadge() {
         _badgeCount = 0;
+        if (_serverUnread > 0) _markServerRead();
         _renderBadge();
     }
     function _renderBadge() {
         const badge = $('notif-badge');
         if (!badge) return;
-        if (_badgeCount > 0) {
+        const count = _badgeCount + _serverUnread;
+        if (count > 0) {
             badge.classList.remove('hidden');
-            badge.textContent = _badgeCount > 99 ? '99+' : String(_badgeCount);
+            badge.textContent = count > 99 ? '99+' : String(count);
         } else {
             badge.classList.add('hidden');
         }
@@ -323,10 +345,108 @@ const notifications = (() => {
         }
     }
 
+    /* ── server notifications ───────────────────────────────── */
+
+    /**
+     * @param {string} path
+     * @param {'POST'|'DELETE'} method
+     */
+    function _serverRequest(path, method) {
+        return fetch(`/api/notifications${path}`, {
+            method,
+            credentials: 'same-origin',
+            headers: { ...getCsrfHeaders() }
+        }).catch((err) => console.error('Notification request failed:', err));
+    }
+
+    async function _loadServerNotifications() {
+        if (!_serverEnabled || document.visibilityState === 'hidden') return;
+        const body = $('notif-panel-body');
+        if (!body) return;
+        try {
+            const res = await fetch('/api/notifications?limit=50', { credentials: 'same-origin' });
+            // Notifications are switched off on this server
+            if (res.status === 404) {
+                _serverEnabled = false;
+                return;
+            }
+            if (!res.ok) return;
+            /** @type {NotificationList} */
+            const list = await res.json();
+
+            const hadUnread = _serverUnread;
+            body.querySelectorAll('.notif-item.server').forEach((el) => {
+                el.remove();
+            });
+            for (const n of list.notifications) {
+                body.appendChild(_serverItem(n));
+            }
+            _serverUnread = list.unread;
+            if (_serverUnread > hadUnread) _ringBell();
+            if ($('notif-wrapper')?.classList.contains('open') && _serverUnread > 0) {
+                _markServerRead();
+            }
+            _renderBadge();
+            _showEmptyIfNeeded();
+        } catch (err) {
+            console.error('Failed to load notifications:', err);
+        }
+    }
+
+    /**
+     * @param {ServerNotification} n
+     * @returns {HTMLElement}
+     */
+    function _serverItem(n) {
+        const [icon, iconClass] = SERVER_ICONS[n.kind] ?? ['fa-bell', 'upload'];
+        const item = document.createElement('div');
+        item.className = `notif-item server${n.read ? '' : ' unread'}${n.link ? ' has-link' : ''}`;
+        item.dataset.id = String(n.id);
+        item.innerHTML = `
+            <div class="notif-item-icon ${iconClass}"><i class="fas ${icon}"></i></div>
+            <div class="notif-item-body">
+                <div class="notif-item-title">${_esc(n.subject)}</div>
+                <div class="notif-item-text">${_esc(n.message)}</div>
+                <div class="notif-item-time">${_esc(formatDateTime(new Date(n.created_at)))}</div>
+            </div>
+            <button class="notif-dismiss-btn"><i class="fas fa-times"></i></button>
+        `;
+        const text = /** @type {HTMLElement} */ (item.querySelector('.notif-item-text'));
+        text.title = n.message;
+        const dismissBtn = /** @type {HTMLElement} */ (item.querySelector('.notif-dismiss-btn'));
+        dismissBtn.title = i18n.t('notifications.dismiss');
+        dismissBtn.setAttribute('aria-label', dismissBtn.title);
+        dismissBtn.addEventListener('click', (e) => {
+            e.stopPropagation();
+            item.remove();
+            _showEmptyIfNeeded();
+            _serverRequest(`/${n.id}`, 'DELETE');
+        });
+        if (n.link) {
+            item.addEventListener('click', () => {
+                close();
+                window.location.href = /** @type {string} */ (n.link);
+            });
+        }
+        return item;
+    }
+
+    function _markServerRead() {
+        _serverUnread = 0;
+        document.querySelectorAll('.notif-item.server.unread').forEach((el) => {
+            el.classList.remove('unread');
+        });
+        _serverRequest('/read', 'POST');
+    }
+
     /* ── clear all ──────────────────────────────────────────── */
     function clear() {
         const body = $('notif-panel-body');
         if (!body) return;
+        if (_serverEnabled && body.querySelector('.notif-item.server')) {
+            _serverUnread = 0;
+            _serverRequest('', 'DELETE');
+        }
         // Remove all notif-items
         body.querySelectorAll('.notif-item').forEach((el) => {
             el.remove();
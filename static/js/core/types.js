@@ -152,6 +152,11 @@
  * @property {string}  auth_provider
  */
 
+/**
+ * Response of a self-registration
+ * @typedef {User & { email_verification_required: boolean }} RegisteredUser
+ */
+
 /**
  * @typedef {Object} AuthResponse
  * @property {User} user
@@ -161,6 +166,15 @@
  * @property {number} expires_in
  */
 
+/**
+ * @typedef {Object} TwoFactorChallenge
+ * @property {true} two_factor_required
+ * @property {string} challenge_token
+ * @property {boolean} enrollment_required
+ * @property {Array<'totp' | 'webauthn'>} methods
+ * @property {number} expires_in
+ */
+
 /**
  * @typedef {'user' | 'admin'} RoleEnum
  */
@@ -253,3 +267,44 @@
  * @property {number|null} width
  * @property {number|null} height
  */
+
+/**
+ * @typedef {Object} ActivityEntry
+ * @property {number} id
+ * @property {string} occurred_at
+ * @property {string} action - e.g. `file_created`, `share_accessed`, `login_failed`
+ * @property {string|null} actor_id
+ * @property {string|null} actor_name
+ * @property {string|null} owner_id
+ * @property {string|null} owner_name
+ * @property {'file'|'folder'|'share'|'account'|null} item_type
+ * @property {string|null} item_id
+ * @property {string|null} item_name
+ * @property {string|null} ip_address
+ * @property {Record<string, any>} details
+ */
+
+/**
+ * @typedef {Object} ActivityPage
+ * @property {ActivityEntry[]} items
+ * @property {{page: number, page_size: number, total_items: number, total_pages: number, has_next: boolean, has_prev: boolean}} pagination
+ */
+
+/**
+ * @typedef {Object} ServerNotification
+ * @property {number} id
+ * @property {'share_received'|'quota_warning'|'share_expiring'} kind
+ * @property {string} subject
+ * @property {string} message
+ * @property {string|null} link - relative to the server root, e.g. `/#/shared`
+ * @property {string} object_type
+ * @property {string} object_id
+ * @property {boolean} read
+ * @property {string} created_at
+ */
+
+/**
+ * @typedef {Object} NotificationList
+ * @property {ServerNotification[]} notifications
+ * @property {number} unread
+ */
@@ -0,0 +1,100 @@
+/**
+ * WebAuthn (passkey) helpers.
+ *
+ * The server sends ceremony options and expects credentials in the JSON
+ * encoding of the WebAuthn API, with binary values as base64url.  These
+ * helpers convert between that encoding and the ArrayBuffers that
+ * `navigator.credentials` works with, so they also run in browsers without
+ * `PublicKeyCredential.parseCreationOptionsFromJSON()`.
+ */
+
+/** @returns {boolean} */
+function passkeysSupported() {
+    return typeof window.PublicKeyCredential === 'function' && !!navigator.credentials;
+}
+
+/**
+ * @param {string} value
+ * @returns {ArrayBuffer}
+ */
+function fromBase64url(value) {
+    const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+    const padded = base64 + '='.repeat((4 - (base64.length % 4)) % 4);
+    const binary = atob(padded);
+    const bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) bytes[i] = binary.charCodeAt(i);
+    return bytes.buffer;
+}
+
+/**
+ * @param {ArrayBuffer | null} buffer
+ * @returns {string | null}
+ */
+function toBase64url(buffer) {
+    if (!buffer) return null;
+    const bytes = new Uint8Array(buffer);
+    let binary = '';
+    for (const b of bytes) binary += String.fromCharCode(b);
+    return btoa(binary).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+
+/**
+ * @param {Array<{type: string, id: string, transports?: string[]}>} descriptors
+ */
+function decodeDescriptors(descriptors) {
+    return (descriptors || []).map((d) => ({ ...d, id: fromBase64url(d.id) }));
+}
+
+/**
+ * Run `navigator.credentials.create()` with options from the server and
+ * return the new credential in its JSON encoding.
+ * @param {{publicKey: any}} options
+ */
+async function createPasskey(options) {
+    const publicKey = {
+        ...options.publicKey,
+        challenge: fromBase64url(options.publicKey.challenge),
+        user: { ...options.publicKey.user, id: fromBase64url(options.publicKey.user.id) },
+        excludeCredentials: decodeDescriptors(options.publicKey.excludeCredentials)
+    };
+    const credential = /** @type {PublicKeyCredential} */ (await navigator.credentials.create({ publicKey }));
+    const response = /** @type {AuthenticatorAttestationResponse} */ (credential.response);
+    return {
+        id: credential.id,
+        rawId: toBase64url(credential.rawId),
+        type: credential.type,
+        response: {
+            clientDataJSON: toBase64url(response.clientDataJSON),
+            attestationObject: toBase64url(response.attestationObject),
+            transports: typeof response.getTransports === 'function' ? response.getTransports() : []
+        }
+    };
+}
+
+/**
+ * Run `navigator.credentials.get()` with options from the server and
+ * return the assertion in its JSON encoding.
+ * @param {{publicKey: any}} options
+ */
+async function getPasskey(options) {
+    const publicKey = {
+        ...options.publicKey,
+        challenge: fromBase64url(options.publicKey.challenge),
+        allowCredentials: decodeDescriptors(options.publicKey.allowCredentials)
+    };
+    const credential = /** @type {PublicKeyCredential} */ (await navigator.credentials.get({ publicKey }));
+    const response = /** @type {AuthenticatorAssertionResponse} */ (credential.response);
+    return {
+        id: credential.id,
+        rawId: toBase64url(credential.rawId),
+        type: credential.type,
+        response: {
+            clientDataJSON: toBase64url(response.clientDataJSON),
+            authenticatorData: toBase64url(response.authenticatorData),
+            signature: toBase64url(response.signature),
+            userHandle: toBase64url(response.userHandle)
+        }
+    };
+}
+
+export { createPasskey, getPasskey, passkeysSupported };
@@ -0,0 +1,53 @@
+/**
+ * OxiCloud - Activity log rendering shared by the profile feed and the
+ * admin audit view
+ */
+
+import { escapeHtml, formatDateTime } from '../core/formatters.js';
+import { i18n } from '../core/i18n.js';
+
+/**
+ * @import {ActivityEntry} from '../core/types.js'
+ */
+
+/**
+ * Translated name of the entry's action, e.g. "Renamed file"
+ * @param {ActivityEntry} entry
+ * @returns {string}
+ */
+function activityLabel(entry) {
+    return i18n.t(`activity.${entry.action}`);
+}
+
+/**
+ * One-line summary of what changed, empty when there is nothing to add
+ * @param {ActivityEntry} entry
+ * @returns {string}
+ */
+function activityDetail(entry) {
+    const details = entry.details || {};
+    if (details.old_name) return `${details.old_name} → ${entry.item_name ?? ''}`;
+    if (details.old_path || details.new_path) return `${details.old_path ?? ''} → ${details.new_path ?? ''}`;
+    if (details.method) return i18n.t(`activity.method_${details.method}`);
+    return '';
+}
+
+/**
+ * HTML of the item cell: the item name and, below it, the change summary
+ * @param {ActivityEntry} entry
+ * @returns {string}
+ */
+function activityItemHtml(entry) {
+    const detail = activityDetail(entry);
+    return escapeHtml(entry.item_name ?? '') + (detail ? `<span class="activity-detail">${escapeHtml(detail)}</span>` : '');
+}
+
+/**
+ * @param {ActivityEntry} entry
+ * @returns {string}
+ */
+function activityTime(entry) {
+    return formatDateTime(new Date(entry.occurred_at));
+}
+
+export { activityDetail, activityItemHtml, activityLabel, activityTime };
@@ -5,14 +5,17 @@
 
 import { getCsrfHeaders } from '../../core/csrf.js';
 import { i18n } from '../../core/i18n.js';
+import { getPasskey, passkeysSupported } from '../../core/webauthn.js';
 
 /**
- * @import {AuthResponse, RoleEnum, User} from '../../core/types.js'
+ * @import {AuthResponse, RoleEnum, TwoFactorChallenge, User} from '../../core/types.js'
  */
 
 // API endpoints
 const API_URL = '/api/auth';
 const LOGIN_ENDPOINT = `${API_URL}/login`;
+const LOGIN_2FA_ENDPOINT = `${API_URL}/login/2fa`;
+const WEBAUTHN_LOGIN_ENDPOINT = `${API_URL}/webauthn/login`;
 const REGISTER_ENDPOINT = `${API_URL}/register`;
 const ME_ENDPOINT = `${API_URL}/me`;
 const REFRESH_ENDPOINT = `${API_URL}/refresh`;
@@ -630,6 +633,7 @@ function initLanguageSelector() {
             showPanel(document.getElementById('login-panel'));
             // Configure OIDC login UI if SSO is enabled
             await configureOidcLoginUI();
+            await setupAccountRecovery(systemStatus);
         }
 
         // setLocale() already calls translatePage() internally
@@ -709,6 +713,7 @@ async function showInitialPanel() {
 
     // Check for OIDC/SSO configuration and update login panel accordingly
     await configureOidcLoginUI();
+    await setupAccountRecovery(systemStatus);
 }
 
 // Fetch OIDC provider info and configure the login UI
@@ -745,6 +750,7 @@ async function configureOidcLoginUI() {
             if (loginForm) loginForm.style.display = 'none';
             if (authDivider) authDivider.style.display = 'none';
             if (showRegisterToggle) showRegisterToggle.parentElement.style.display = 'none';
+            document.getElementById('passkey-login-btn')?.classList.add('hidden');
             showPanel(oidcSection);
         } else {
             // Both password and OIDC enabled: show divider + SSO button
@@ -977,6 +983,116 @@ document.addEventListener('DOMContentLoaded', () => {
     })();
 });
 
+/**
+ * Finish a login once tokens were issued: store display data, check the
+ * cookies were accepted and go to the app.
+ * @param {AuthResponse} data
+ */
+function completeLogin(data) {
+    // Tokens are now set as HttpOnly cookies by the server.
+    // Just store non-sensitive user data for display.
+    console.log('Login succeeded');
+
+    // Reset redirect counter on successful login
+    sessionStorage.removeItem('redirect_count');
+    localStorage.setItem('refresh_attempts', '0');
+
+    if (data.user) {
+        localStorage.setItem(USER_DATA_KEY, JSON.stringify(data.user));
+    }
+
+    // Redirect to main app — but first verify the browser accepted
+    // the auth cookies.  The CSRF cookie (oxicloud_csrf) is non-HttpOnly
+    // so JS can read it.  If it's missing the browser rejected the
+    // Set-Cookie (usually because of Secure flag over plain HTTP).
+    const csrfStored = document.cookie.split('; ').some((c) => c.startsWith('oxicloud_csrf='));
+    if (!csrfStored) {
+        console.error(
+            'Auth cookies were NOT stored by the browser. ' +
+                'This usually means OXICLOUD_COOKIE_SECURE=true (or OXICLOUD_BASE_URL=https://...) ' +
+                'is set but you are accessing via plain HTTP.'
+        );
+        loginError.textContent =
+            'Login succeeded but the browser rejected the session cookie. ' +
+            'If you are accessing via HTTP, set OXICLOUD_COOKIE_SECURE=false in your .env file ' +
+            'or access via HTTPS through a reverse proxy.';
+        loginError.style.display = 'block';
+        return;
+    }
+    redirectToMainApp();
+}
+
+/** Challenge token of a login waiting for its second factor */
+let twoFactorChallenge = '';
+
+/**
+ * Replace the password form with the two-factor step.  When the account
+ * must enrol first, fetch and show the new secret.
+ * @param {TwoFactorChallenge} challenge
+ */
+async function showTwoFactorStep(challenge) {
+    twoFactorChallenge = challenge.challenge_token;
+    loginForm.classList.add('hidden');
+    document.getElementById('two-factor-form').classList.remove('hidden');
+
+    if (challenge.enrollment_required) {
+        const response = await fetch(`${LOGIN_2FA_ENDPOINT}/setup`, {
+            method: 'POST',
+            credentials: 'same-origin',
+            headers: { 'Content-Type': 'application/json', ...getCsrfHeaders() },
+            body: JSON.stringify({ challenge_token: twoFactorChallenge })
+        });
+        const setup = await response.json();
+        if (!response.ok) throw new Error(setup.error || 'Two-factor setup failed');
+
+        document.getElementById('two-factor-secret').textContent = setup.secret;
+        /** @type {HTMLAnchorElement} */ (document.getElementById('two-factor-uri')).href = setup.otpauth_uri;
+        document.getElementById('two-factor-setup').classList.remove('hidden');
+    }
+
+    const methods = challenge.methods || ['totp'];
+    const passkeyButton = document.getElementById('two-factor-passkey');
+    passkeyButton.classList.toggle('hidden', !methods.includes('webauthn') || !passkeysSupported());
+    if (!methods.includes('totp')) {
+        // Passkey-only account: there is no code to type
+        document.getElementById('two-factor-code-group').classList.add('hidden');
+        document.getElementById('two-factor-submit').classList.add('hidden');
+        /** @type {HTMLInputElement} */ (document.getElementById('two-factor-code')).required = false;
+        passkeyButton.focus();
+        return;
+    }
+    document.getElementById('two-factor-code').focus();
+}
+
+/**
+ * Sign in with a passkey.  With a challenge token the passkey is the second
+ * factor of the password login; without one it is a passwordless login.
+ * @param {string} [challengeToken]
+ * @returns {Promise<AuthResponse>}
+ */
+async function loginWithPasskey(challengeToken) {
+    const body = challengeToken ? { challenge_token: challengeToken } : {};
+    const optionsResponse = await fetch(`${WEBAUTHN_LOGIN_ENDPOINT}/options`, {
+        method: 'POST',
+        credentials: 'same-origin',
+        headers: { 'Content-Type': 'application/json', ...getCsrfHeaders() },
+        body: JSON.stringify(body)
+    });
+    const options = await optionsResponse.json();
+    if (!optionsResponse.ok) throw new Error(options.error || 'Passkey login failed');
+
+    const credential = await getPasskey(options);
+    const response = await fetch(WEBAUTHN_LOGIN_ENDPOINT, {
+        method: 'POST',
+        credentials: 'same-origin',
+        headers: { 'Content-Type': 'application/json', ...getCsrfHeaders() },
+        body: JSON.stringify({ ...body, credential })
+    });
+    const data = await response.json();
+    if (!response.ok) throw new Error(data.error || 'Passkey login failed');
+    return data;
+}
+
 // Login form submission
 if (isLoginPage && loginForm) {
     loginForm.addEventListener('submit', async (e) => {
@@ -990,38 +1106,67 @@ if (isLoginPage && loginForm) {
 
         try {
             const data = await login(username, password);
+            if ('two_factor_required' in data) {
+                await showTwoFactorStep(data);
+                return;
+            }
+            completeLogin(data);
+        } catch (error) {
+            loginError.textContent = errMessage(error) || 'Error logging in';
+            loginError.style.display = 'block';
+        }
+    });
 
-            // Tokens are now set as HttpOnly cookies by the server.
-            // Just store non-sensitive user data for display.
-            console.log('Login succeeded');
+    const passkeyLoginButton = document.getElementById('passkey-login-btn');
+    if (passkeysSupported()) passkeyLoginButton.classList.remove('hidden');
+    passkeyLoginButton.addEventListener('click', async () => {
+        loginError.style.display = 'none';
+        try {
+            completeLogin(await loginWithPasskey());
+        } catch (error) {
+            loginError.textContent = errMessage(error) || 'Error logging in';
+            loginError.style.display = 'block';
+        }
+    });
 
-            // Reset redirect counter on successful login
-            sessionStorage.removeItem('redirect_count');
-            localStorage.setItem('refresh_attempts', '0');
+    document.getElementById('two-factor-passkey').addEventListener('click', async () => {
+        loginError.style.display = 'none';
+        try {
+            completeLogin(await loginWithPasskey(twoFactorChallenge));
+        } catch (error) {
+            loginError.textContent = errMessage(error) || 'Error logging in';
+            loginError.style.display = 'block';
+        }
+    });
 
-            if (data.user) {
-                localStorage.setItem(USER_DATA_KEY, JSON.stringify(data.user));
-            }
+    document.getElementById('two-factor-form').addEventListener('submit', async (e) => {
+        e.preventDefault();
+        loginError.style.display = 'none';
 
-            // Redirect to main app — but first verify the browser accepted
-            // the auth cookies.  The CSRF cookie (oxicloud_csrf) is non-HttpOnly
-            // so JS can read it.  If it's missing the browser rejected the
-            // Set-Cookie (usually because of Secure flag over plain HTTP).
-            const csrfStored = document.cookie.split('; ').some((c) => c.startsWith('oxicloud_csrf='));
-            if (!csrfStored) {
-                console.error(
-                    'Auth cookies were NOT stored by the browser. ' +
-                        'This usually means OXICLOUD_COOKIE_SECURE=true (or OXICLOUD_BASE_URL=https://...) ' +
-                        'is set but you are accessing via plain HTTP.'
-                );
-                loginError.textContent =
-                    'Login succeeded but the browser rejected the session cookie. ' +
-                    'If you are accessing via HTTP, set OXICLOUD_COOKIE_SECURE=false in your .env file ' +
-                    'or access via HTTPS through a reverse proxy.';
-                loginError.style.display = 'block';
+        try {
+            const response = await fetch(LOGIN_2FA_ENDPOINT, {
+                method: 'POST',
+                credentials: 'same-origin',
+                headers: { 'Content-Type': 'application/json', ...getCsrfHeaders() },
+                body: JSON.stringify({
+                    challenge_token: twoFactorChallenge,
+                    code: inputVal('two-factor-code').trim()
+                })
+            });
+            const data = await response.json();
+            if (!response.ok) throw new Error(data.error || 'Invalid two-factor code');
+
+            if (data.recovery_codes) {
+                // Enrolment completed: the codes are shown only this once
+                document.getElementById('two-factor-form').classList.add('hidden');
+                document.getElementById('recovery-codes').textContent = data.recovery_codes.join('\n');
+                document.getElementById('recovery-codes-panel').classList.remove('hidden');
+                document
+                    .getElementById('recovery-codes-continue')
+                    .addEventListener('click', () => completeLogin(data), { once: true });
                 return;
             }
-            redirectToMainApp();
+            completeLogin(data);
         } catch (error) {
             loginError.textContent = errMessage(error) || 'Error logging in';
             loginError.style.display = 'block';
@@ -1051,14 +1196,21 @@ if (isLoginPage && registerForm) {
         }
 
         try {
-            await register(username, email, password);
-
-            registerSuccess.textContent = i18n.t('auth.account_success');
-            registerSuccess.style.display = 'block';
+            const registered = await register(username, email, password);
 
             // Clear form
             registerForm.reset();
 
+            if (registered.email_verification_required) {
+                // The account is locked until the mailed link is followed
+                registerSuccess.textContent = i18n.t('auth.verify_email_sent', { email });
+                registerSuccess.style.display = 'block';
+                return;
+            }
+
+            registerSuccess.textContent = i18n.t('auth.account_success');
+            registerSuccess.style.display = 'block';
+
             // Switch to login panel after 2 seconds
             setTimeout(() => {
                 showPanel(loginPanel);
@@ -1071,6 +1223,159 @@ if (isLoginPage && registerForm) {
     });
 }
 
+// ── Password recovery and address verification ──────────────────────
+// Mailed links open the login page with ?reset_token=… or ?verify_token=…
+
+/**
+ * Shows only `panel` among the login page panels
+ * @param {HTMLElement | null} panel
+ */
+function showOnlyPanel(panel) {
+    for (const id of ['language-panel', 'login-panel', 'register-panel', 'admin-setup-panel', 'forgot-panel', 'reset-panel']) {
+        const el = document.getElementById(id);
+        if (el === panel) showPanel(el);
+        else hidePanel(el);
+    }
+}
+
+/**
+ * Shows a success message on the login panel
+ * @param {string} message
+ */
+function showLoginSuccess(message) {
+    const el = document.getElementById('login-success');
+    if (!el) return;
+    el.textContent = message;
+    el.style.display = 'block';
+}
+
+/**
+ * Removes a consumed token from the address bar
+ * @param {string} param
+ */
+function dropUrlParam(param) {
+    const url = new URL(window.location.href);
+    url.searchParams.delete(param);
+    window.history.replaceState({}, document.title, url.pathname + url.search);
+}
+
+/**
+ * POSTs JSON to an account recovery endpoint, throwing the server message
+ * on failure
+ * @param {string} path
+ * @param {Record<string, string>} body
+ */
+async function postAccountRequest(path, body) {
+    const response = await fetch(`${API_URL}${path}`, {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+            ...getCsrfHeaders()
+        },
+        body: JSON.stringify(body)
+    });
+    if (!response.ok) {
+        const data = await response.json().catch(() => ({}));
+        throw new Error(data.message || data.error || `Error ${response.status}`);
+    }
+}
+
+/**
+ * Offers password recovery when mail is configured and handles the
+ * tokens of mailed links
+ * @param {{ password_reset_available?: boolean }} systemStatus
+ */
+async function setupAccountRecovery(systemStatus) {
+    if (systemStatus.password_reset_available) {
+        document.getElementById('forgot-password-toggle')?.classList.remove('hidden');
+    }
+
+    const params = new URLSearchParams(window.location.search);
+    const verifyToken = params.get('verify_token');
+    if (verifyToken) {
+        dropUrlParam('verify_token');
+        try {
+            await postAccountRequest('/verify-email', { token: verifyToken });
+            showLoginSuccess(i18n.t('auth.email_verified'));
+        } catch (error) {
+            loginError.textContent = errMessage(error) || i18n.t('auth.email_verify_error');
+            loginError.style.display = 'block';
+        }
+    }
+
+    if (params.get('reset_token')) {
+        showOnlyPanel(document.getElementById('reset-panel'));
+    }
+}
+
+if (isLoginPage && document.getElementById('forgot-form')) {
+    const forgotPanel = document.getElementById('forgot-panel');
+    const forgotError = document.getElementById('forgot-error');
+    const forgotSuccess = document.getElementById('forgot-success');
+    const resetForm = /** @type {HTMLFormElement} */ (document.getElementById('reset-form'));
+    const resetError = document.getElementById('reset-error');
+
+    document.getElementById('show-forgot-password')?.addEventListener('click', () => {
+        forgotError.style.display = 'none';
+        forgotSuccess.style.display = 'none';
+        showOnlyPanel(forgotPanel);
+    });
+    for (const id of ['forgot-back-to-login', 'reset-back-to-login']) {
+        document.getElementById(id)?.addEventListener('click', () => showOnlyPanel(loginPanel));
+    }
+
+    /**
+     * Requests a mailed link for the entered username or address
+     * @param {string} path
+     */
+    const requestLink = async (path) => {
+        forgotError.style.display = 'none';
+        forgotSuccess.style.display = 'none';
+        const login = inputVal('forgot-login').trim();
+        if (!login) return;
+        try {
+            await postAccountRequest(path, { login });
+            forgotSuccess.textContent = i18n.t('auth.link_sent');
+            forgotSuccess.style.display = 'block';
+        } catch (error) {
+            forgotError.textContent = errMessage(error);
+            forgotError.style.display = 'block';
+        }
+    };
+
+    document.getElementById('forgot-form')?.addEventListener('submit', (e) => {
+        e.preventDefault();
+        requestLink('/password/forgot');
+    });
+    document.getElementById('resend-verification')?.addEventListener('click', () => {
+        requestLink('/verify-email/resend');
+    });
+
+    resetForm.addEventListener('submit', async (e) => {
+        e.preventDefault();
+        resetError.style.display = 'none';
+
+        const password = inputVal('reset-password');
+        if (password !== inputVal('reset-password-confirm')) {
+            resetError.textContent = i18n.t('auth.passwords_mismatch');
+            resetError.style.display = 'block';
+            return;
+        }
+
+        const token = new URLSearchParams(window.location.search).get('reset_token') || '';
+        try {
+            await postAccountRequest('/password/reset', { token, new_password: password });
+            dropUrlParam('reset_token');
+            resetForm.reset();
+            showOnlyPanel(loginPanel);
+            showLoginSuccess(i18n.t('auth.reset_success'));
+        } catch (error) {
+            resetError.textContent = errMessage(error) || i18n.t('auth.reset_error');
+            resetError.style.display = 'block';
+        }
+    });
+}
+
 // Admin setup form submission
 if (isLoginPage && adminSetupForm) {
     adminSetupForm.addEventListener('submit', async (e) => {
@@ -1134,7 +1439,7 @@ if (isLoginPage && adminSetupForm) {
  * Login with username and password
  * @param {string} username
  * @param {string} password
- * @returns {Promise<AuthResponse>}
+ * @returns {Promise<AuthResponse | TwoFactorChallenge>}
  */
 async function login(username, password) {
     try {
@@ -1172,9 +1477,13 @@ async function login(username, password) {
 
         // Parse the JSON response
         try {
-            /** @type {AuthResponse} */
+            /** @type {AuthResponse | TwoFactorChallenge} */
             const data = await response.json();
-            console.log(`Login successful for user id ${data.user.id}, received data`);
+            if ('two_factor_required' in data) {
+                console.log('Password accepted, second factor required');
+            } else {
+                console.log(`Login successful for user id ${data.user.id}, received data`);
+            }
             return data;
         } catch (jsonError) {
             console.error('Error parsing login response:', jsonError);
@@ -1222,7 +1531,7 @@ async function register(username, email, password, role = 'user') {
 
         // Parse the JSON response
         try {
-            /** @type {User} */
+            /** @type {RegisteredUser} */
             const data = await response.json();
             console.log(`Registration successful, user created: ${data.id}, received data`);
             return data;
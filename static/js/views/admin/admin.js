@@ -2,9 +2,10 @@ import { getCsrfHeaders } from '../../core/csrf.js';
 import { escapeHtml } from '../../core/formatters.js';
 import { i18n } from '../../core/i18n.js';
 import { oxiIconsInit } from '../../core/icons.js';
+import { activityItemHtml, activityLabel, activityTime } from '../../features/activity.js';
 
 /**
- * @import {RoleEnum} from '../../core/types.js'
+ * @import {ActivityPage, RoleEnum} from '../../core/types.js'
  */
 
 const API = '/api';
@@ -154,6 +155,7 @@ function switchTab(name, el) {
     if (name === 'users') loadUsers();
     if (name === 'dashboard') loadDashboard();
     if (name === 'storage') loadStorage();
+    if (name === 'activity') loadActivity();
 }
 
 async function loadDashboard() {
@@ -1100,6 +1102,96 @@ async function completeMigration() {
     }
 }
 
+/* ── Activity log ── */
+let activityPage = 0;
+
+/** Query string of the activity filters, without paging */
+function activityFilterQuery() {
+    const params = new URLSearchParams();
+    const type = /** @type {HTMLSelectElement} */ (document.getElementById('activity-type')).value;
+    const from = /** @type {HTMLInputElement} */ (document.getElementById('activity-from')).value;
+    const to = /** @type {HTMLInputElement} */ (document.getElementById('activity-to')).value;
+    if (type) params.set('action', type);
+    if (from) params.set('from', from);
+    if (to) params.set('to', to);
+    return params;
+}
+
+async function loadActivity() {
+    const tbody = document.getElementById('activity-tbody');
+    tbody.innerHTML = `<tr><td colspan="5" class="table-loading-cell"><i class="fas fa-spinner fa-spin"></i> ${escapeHtml(i18n.t('admin.loading'))}</td></tr>`;
+    const params = activityFilterQuery();
+    params.set('page', String(activityPage));
+    params.set('page_size', String(PAGE_SIZE));
+    try {
+        const resp = await fetch(`${API}/admin/activity?${params}`, {
+            headers: headers(),
+            credentials: 'same-origin'
+        });
+        if (!resp.ok) {
+            const e = await resp.json().catch(() => ({}));
+            tbody.innerHTML =
+                '<tr><td colspan="5" class="table-status-error"><i class="fas fa-exclamation-circle"></i> ' +
+                escapeHtml(e.message || i18n.t('admin.activity_failed_load')) +
+                '</td></tr>';
+            return;
+        }
+        /** @type {ActivityPage} */
+        const data = await resp.json();
+        const { pagination } = data;
+        if (data.items.length === 0) {
+            tbody.innerHTML = `<tr><td colspan="5" class="table-status-empty">${escapeHtml(i18n.t('admin.activity_empty'))}</td></tr>`;
+        } else {
+            tbody.innerHTML = data.items
+                .map(
+                    (entry) =>
+                        '<tr><td>' +
+                        escapeHtml(activityTime(entry)) +
+                        '</td><td>' +
+                        escapeHtml(entry.actor_name ?? entry.owner_name ?? '—') +
+                        '</td><td>' +
+                        escapeHtml(activityLabel(entry)) +
+                        '</td><td>' +
+                        activityItemHtml(entry) +
+                        '</td><td>' +
+                        escapeHtml(entry.ip_address ?? '') +
+                        '</td></tr>'
+                )
+                .join('');
+        }
+        const from = pagination.total_items === 0 ? 0 : activityPage * PAGE_SIZE + 1;
+        const to = Math.min((activityPage + 1) * PAGE_SIZE, pagination.total_items);
+        document.getElementById('activity-info').textContent = i18n.t('admin.showing_entries', {
+            from: from,
+            to: to,
+            total: pagination.total_items
+        });
+        /** @type {HTMLButtonElement} */ (document.getElementById('activity-prev-btn')).disabled = !pagination.has_prev;
+        /** @type {HTMLButtonElement} */ (document.getElementById('activity-next-btn')).disabled = !pagination.has_next;
+    } catch (e) {
+        tbody.innerHTML =
+            '<tr><td colspan="5" class="table-status-error"><i class="fas fa-exclamation-circle"></i> ' +
+            escapeHtml(i18n.t('admin.error_network', { message: /** @type {Error} */ (e).message })) +
+            '</td></tr>';
+    }
+}
+
+/** Restarts the listing at the first page after a filter change */
+function reloadActivity() {
+    activityPage = 0;
+    loadActivity();
+}
+
+/**
+ * Downloads the filtered log; the browser sends the session cookie
+ * @param {'csv' | 'json'} format
+ */
+function exportActivity(format) {
+    const params = activityFilterQuery();
+    params.set('format', format);
+    window.location.href = `${API}/admin/activity/export?${params}`;
+}
+
 async function init() {
     try {
         oxiIconsInit();
@@ -1164,11 +1256,13 @@ document.addEventListener('translationsLoaded', () => {
     // Re-render dynamic content that uses i18n.t()
     loadDashboard();
     if (activeTabName === 'users') loadUsers();
+    if (activeTabName === 'activity') loadActivity();
 });
 document.addEventListener('localeChanged', () => {
     i18n.translatePage();
     loadDashboard();
     if (activeTabName === 'users') loadUsers();
+    if (activeTabName === 'activity') loadActivity();
 });
 
 init();
@@ -1186,6 +1280,9 @@ document.getElementById('tab-btn-oidc').addEventListener('click', function () {
 document.getElementById('tab-btn-storage').addEventListener('click', function () {
     switchTab('storage', this);
 });
+document.getElementById('tab-btn-activity').addEventListener('click', function () {
+    switchTab('activity', this);
+});
 
 document.getElementById('ds-registration').addEventListener('change', function () {
     toggleRegistration(/** @type {HTMLInputElement} */ (this).checked);
@@ -1225,3 +1322,20 @@ document.getElementById('btn-pause-migration').addEventListener('click', pauseMi
 document.getElementById('btn-resume-migration').addEventListener('click', resumeMigration);
 document.getElementById('btn-verify-migration').addEventListener('click', verifyMigration);
 document.getElementById('btn-complete-migration').addEventListener('click', completeMigration);
+
+/* ── Activity log event listeners ── */
+document.getElementById('activity-type').addEventListener('change', reloadActivity);
+document.getElementById('activity-from').addEventListener('change', reloadActivity);
+document.getElementById('activity-to').addEventListener('change', reloadActivity);
+document.getElementById('activity-prev-btn').addEventListener('click', () => {
+    if (activityPage > 0) {
+        activityPage--;
+        loadActivity();
+    }
+});
+document.getElementById('activity-next-btn').addEventListener('click', () => {
+    activityPage++;
+    loadActivity();
+});
+document.getElementById('btn-export-activity-csv').addEventListener('click', () => exportActivity('csv'));
+document.getElementById('btn-export-activity-json').addEventListener('click', () => exportActivity('json'));
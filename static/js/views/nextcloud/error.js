@@ -19,6 +19,14 @@ switch (errorType) {
             history.back();
         });
         break;
+    case 'account-locked':
+        errorTitle.textContent = 'Account Locked';
+        errorMessage.textContent = 'Too many failed sign-in attempts. Please wait a few minutes and try again.';
+        errorAction.textContent = 'Try Again';
+        errorAction.addEventListener('click', () => {
+            history.back();
+        });
+        break;
     case 'session-expired':
         errorTitle.textContent = 'Session Expired';
         errorMessage.textContent = 'Your session has expired. Please try again.';
@@ -1,6 +1,12 @@
 import { getCsrfHeaders } from '../../core/csrf.js';
 import { i18n } from '../../core/i18n.js';
 import { oxiIconsInit } from '../../core/icons.js';
+import { createPasskey, passkeysSupported } from '../../core/webauthn.js';
+import { activityItemHtml, activityLabel, activityTime } from '../../features/activity.js';
+
+/**
+ * @import {ActivityEntry, ActivityPage} from '../../core/types.js'
+ */
 
 const API = '/api';
 
@@ -86,6 +92,14 @@ async function init() {
         }
 
         loadAppPasswords();
+        loadSessions();
+        loadActivity();
+
+        // Passkeys can be added by accounts that sign in with a password
+        if (passkeysSupported() && (!user.auth_provider || user.auth_provider === 'local' || user.auth_provider === 'ldap')) {
+            document.getElementById('passkeys-section').classList.remove('hidden');
+            loadPasskeys();
+        }
 
         try {
             const oidcResp = await fetch(`${API}/auth/oidc/providers`, {
@@ -346,6 +360,255 @@ async function revokeAppPassword(id, label) {
     }
 }
 
+/** @param {{id: string, name: string, created_at: string, last_used_at?: string}} passkey */
+function renderPasskeyRow(passkey) {
+    const tr = document.createElement('tr');
+    const name = document.createElement('td');
+    name.textContent = passkey.name;
+    const created = document.createElement('td');
+    created.textContent = new Date(passkey.created_at).toLocaleDateString();
+    const lastUsed = document.createElement('td');
+    lastUsed.textContent = passkey.last_used_at ? timeAgo(passkey.last_used_at) : i18n.t('profile.never');
+    const actions = document.createElement('td');
+    const btn = document.createElement('button');
+    btn.className = 'btn btn-danger-sm';
+    btn.innerHTML = '<i class="fas fa-trash"></i>';
+    btn.title = i18n.t('profile.remove_passkey_title');
+    btn.addEventListener('click', () => {
+        removePasskey(passkey.id, passkey.name);
+    });
+    actions.appendChild(btn);
+    tr.append(name, created, lastUsed, actions);
+    return tr;
+}
+
+async function loadPasskeys() {
+    try {
+        const resp = await fetch(`${API}/auth/webauthn/credentials`, {
+            headers: headers(),
+            credentials: 'same-origin'
+        });
+        if (!resp.ok) {
+            document.getElementById('passkeys-section').classList.add('hidden');
+            return;
+        }
+        const passkeys = /** @type {Array<{id: string, name: string, created_at: string, last_used_at?: string}>} */ (await resp.json());
+
+        const tbody = document.getElementById('passkey-tbody');
+        const table = document.getElementById('passkey-table');
+        const empty = document.getElementById('passkey-empty');
+        tbody.innerHTML = '';
+        table.classList.toggle('hidden', passkeys.length === 0);
+        empty.classList.toggle('hidden', passkeys.length > 0);
+        for (const passkey of passkeys) tbody.appendChild(renderPasskeyRow(passkey));
+    } catch (e) {
+        console.error('Failed to load passkeys', e);
+    }
+}
+
+async function addPasskey() {
+    const nameInput = /** @type {HTMLInputElement} */ (document.getElementById('passkey-name'));
+    const statusEl = document.getElementById('passkey-status');
+    const btn = /** @type {HTMLButtonElement} */ (document.getElementById('passkey-add'));
+
+    btn.disabled = true;
+    statusEl.innerHTML = '';
+
+    try {
+        const optionsResp = await fetch(`${API}/auth/webauthn/register/options`, {
+            method: 'POST',
+            headers: headers(),
+            credentials: 'same-origin'
+        });
+        const options = await optionsResp.json().catch(() => ({}));
+        if (!optionsResp.ok) throw new Error(options.error || i18n.t('profile.error_add_passkey'));
+
+        const credential = await createPasskey(options);
+        const resp = await fetch(`${API}/auth/webauthn/register`, {
+            method: 'POST',
+            headers: headers(),
+            credentials: 'same-origin',
+            body: JSON.stringify({ name: nameInput.value.trim() || null, credential })
+        });
+        if (!resp.ok) {
+            const err = await resp.json().catch(() => ({}));
+            throw new Error(err.error || i18n.t('profile.error_add_passkey'));
+        }
+        nameInput.value = '';
+        statusEl.innerHTML = `<div class="alert alert-success"><i class="fas fa-check-circle"></i> ${escapeHtml(i18n.t('profile.passkey_added'))}</div>`;
+        loadPasskeys();
+    } catch (err) {
+        statusEl.innerHTML = `<div class="alert alert-error"><i class="fas fa-exclamation-circle"></i> ${escapeHtml(/** @type {Error} */ (err).message)}</div>`;
+    } finally {
+        btn.disabled = false;
+    }
+}
+
+/**
+ * @param {string} id
+ * @param {string} name
+ */
+async function removePasskey(id, name) {
+    if (!confirm(i18n.t('profile.confirm_remove_passkey', { name: name }))) return;
+    try {
+        const resp = await fetch(`${API}/auth/webauthn/credentials/${encodeURIComponent(id)}`, {
+            method: 'DELETE',
+            headers: headers(),
+            credentials: 'same-origin'
+        });
+        if (resp.ok || resp.status === 204) {
+            loadPasskeys();
+        } else {
+            const err = await resp.json().catch(() => ({}));
+            alert(err.error || i18n.t('profile.error_remove_passkey'));
+        }
+    } catch (err) {
+        alert(i18n.t('profile.error_network', { message: /** @type {Error} */ (err).message }));
+    }
+}
+
+/**
+ * @typedef {Object} SessionInfo
+ * @property {string} id
+ * @property {string} device
+ * @property {string | null} ip_address
+ * @property {'password' | 'passkey' | 'oidc' | 'device'} login_method
+ * @property {string} last_used_at
+ * @property {boolean} current
+ */
+
+/** @param {SessionInfo} session */
+function renderSessionRow(session) {
+    const tr = document.createElement('tr');
+    const device = document.createElement('td');
+    device.textContent = session.device;
+    if (session.current) {
+        const badge = document.createElement('span');
+        badge.className = 'badge badge-active';
+        badge.textContent = i18n.t('profile.this_device');
+        device.append(' ', badge);
+    }
+    const method = document.createElement('td');
+    method.textContent = i18n.t(`profile.login_method_${session.login_method}`);
+    const ip = document.createElement('td');
+    ip.textContent = session.ip_address || '—';
+    const lastActive = document.createElement('td');
+    lastActive.textContent = timeAgo(session.last_used_at);
+    const actions = document.createElement('td');
+    const btn = document.createElement('button');
+    btn.className = 'btn btn-danger-sm';
+    btn.innerHTML = '<i class="fas fa-sign-out-alt"></i>';
+    btn.title = i18n.t('profile.sign_out_session');
+    btn.addEventListener('click', () => {
+        revokeSession(session);
+    });
+    actions.appendChild(btn);
+    tr.append(device, method, ip, lastActive, actions);
+    return tr;
+}
+
+async function loadSessions() {
+    try {
+        const resp = await fetch(`${API}/auth/sessions`, {
+            headers: headers(),
+            credentials: 'same-origin'
+        });
+        if (!resp.ok) {
+            document.getElementById('sessions-section').classList.add('hidden');
+            return;
+        }
+        const sessions = /** @type {SessionInfo[]} */ (await resp.json());
+        const tbody = document.getElementById('sessions-tbody');
+        tbody.innerHTML = '';
+        for (const session of sessions) tbody.appendChild(renderSessionRow(session));
+    } catch (e) {
+        console.error('Failed to load sessions', e);
+    }
+}
+
+/** @param {SessionInfo} session */
+async function revokeSession(session) {
+    const message = session.current
+        ? i18n.t('profile.confirm_sign_out_current')
+        : i18n.t('profile.confirm_sign_out_session', { device: session.device });
+    if (!confirm(message)) return;
+    try {
+        const resp = await fetch(`${API}/auth/sessions/${encodeURIComponent(session.id)}`, {
+            method: 'DELETE',
+            headers: headers(),
+            credentials: 'same-origin'
+        });
+        if (resp.ok || resp.status === 204) {
+            if (session.current) {
+                window.location.href = '/login';
+                return;
+            }
+            loadSessions();
+        } else {
+            const err = await resp.json().catch(() => ({}));
+            alert(err.error || i18n.t('profile.error_sign_out_session'));
+        }
+    } catch (err) {
+        alert(i18n.t('profile.error_network', { message: /** @type {Error} */ (err).message }));
+    }
+}
+
+const ACTIVITY_PAGE_SIZE = 20;
+let activityPage = 0;
+
+/** @param {ActivityEntry} entry */
+function renderActivityRow(entry) {
+    const tr = document.createElement('tr');
+    const time = document.createElement('td');
+    time.textContent = activityTime(entry);
+    const event = document.createElement('td');
+    event.textContent = activityLabel(entry);
+    // Someone else acting on the user's items, e.g. a visitor of a link
+    if (entry.actor_name && entry.actor_id !== entry.owner_id) {
+        const actor = document.createElement('span');
+        actor.className = 'activity-detail';
+        actor.textContent = entry.actor_name;
+        event.appendChild(actor);
+    }
+    const item = document.createElement('td');
+    item.innerHTML = activityItemHtml(entry);
+    const ip = document.createElement('td');
+    ip.textContent = entry.ip_address || '—';
+    tr.append(time, event, item, ip);
+    return tr;
+}
+
+/**
+ * Loads the first page of the feed, or appends the next one
+ * @param {boolean} [append]
+ */
+async function loadActivity(append = false) {
+    activityPage = append ? activityPage + 1 : 0;
+    const params = new URLSearchParams({ page: String(activityPage), page_size: String(ACTIVITY_PAGE_SIZE) });
+    const type = /** @type {HTMLSelectElement} */ (document.getElementById('activity-type')).value;
+    if (type) params.set('action', type);
+    try {
+        const resp = await fetch(`${API}/activity?${params}`, {
+            headers: headers(),
+            credentials: 'same-origin'
+        });
+        if (!resp.ok) {
+            // 404 when the activity log is disabled on this server
+            document.getElementById('activity-section').classList.add('hidden');
+            return;
+        }
+        const page = /** @type {ActivityPage} */ (await resp.json());
+        const tbody = document.getElementById('activity-tbody');
+        if (!append) tbody.innerHTML = '';
+        for (const entry of page.items) tbody.appendChild(renderActivityRow(entry));
+        document.getElementById('activity-empty').classList.toggle('hidden', tbody.childElementCount > 0);
+        document.getElementById('activity-table').classList.toggle('hidden', tbody.childElementCount === 0);
+        document.getElementById('activity-more').classList.toggle('hidden', !page.pagination.has_next);
+    } catch (e) {
+        console.error('Failed to load activity', e);
+    }
+}
+
 /** @param {string} str */
 function escapeHtml(str) {
     var div = document.createElement('div');
@@ -360,6 +623,9 @@ document.getElementById('password-form').addEventListener('submit', changePasswo
 document.getElementById('app-pw-generate').addEventListener('click', createAppPassword);
 document.getElementById('app-pw-copy-btn').addEventListener('click', copyAppPassword);
 document.getElementById('app-pw-auto-toggle').addEventListener('click', toggleAutoPasswords);
+document.getElementById('passkey-add').addEventListener('click', addPasskey);
+document.getElementById('activity-type').addEventListener('change', () => loadActivity());
+document.getElementById('activity-more').addEventListener('click', () => loadActivity(true));
 
 /* Re-render when language changes */
 window.addEventListener('translationsLoaded', () => {
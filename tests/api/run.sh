@@ -96,6 +96,10 @@ hurl --variables-file "$API_DIR/test.env" --file-root "$REPO_ROOT/tests" --test
   "$API_DIR/recent.hurl" \
   "$API_DIR/batch_folder_copy.hurl" \
   "$API_DIR/dedup_blob_cleanup.hurl" \
+  "$API_DIR/file_versions.hurl" \
+  "$API_DIR/snapshots.hurl" \
+  "$API_DIR/activity.hurl" \
+  "$API_DIR/user_share_copy.hurl" \
   "$API_DIR/contacts.hurl"
 
 #bash "$API_DIR/dedup_bulk_upload.sh"